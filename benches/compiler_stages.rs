@@ -0,0 +1,73 @@
+use cmm::common::target::Target;
+use cmm::compiler::code_emission::emit_assembly;
+use cmm::compiler::code_gen;
+use cmm::compiler::ir_gen::TackyEmitter;
+use cmm::compiler::lexer::tokenize;
+use cmm::compiler::parser::Parser;
+use cmm::compiler::semantic::validate;
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// Number of binary operators in the synthetic benchmark program.
+const OPERATOR_COUNT: usize = 2000;
+
+/// Builds a program returning a single expression with `operator_count` binary operators, cycling
+/// through arithmetic, comparison, and logical operators.
+fn synthetic_program(operator_count: usize) -> String {
+    const OPERATORS: [&str; 10] = ["+", "*", "-", "/", "%", "<", "==", "&&", "||", ">="];
+    let mut expression = String::from("1");
+    for index in 0..operator_count {
+        let operand = match index % 3 {
+            0 => format!("{}", index % 97 + 1),
+            1 => format!("-{}", index % 89 + 1),
+            _ => format!("(~{} + 3)", index % 83),
+        };
+        expression.push_str(&format!(
+            " {} {}",
+            OPERATORS[index % OPERATORS.len()],
+            operand
+        ));
+    }
+    format!("int main(void) {{ return {}; }}", expression)
+}
+
+fn bench_compiler_stages(c: &mut Criterion) {
+    let source_code = synthetic_program(OPERATOR_COUNT);
+    let tokens = tokenize(&source_code).unwrap();
+    let tacky_ast = TackyEmitter::new()
+        .convert_ast(validate(Parser::new(tokens.clone()).parse_ast().unwrap()).unwrap())
+        .unwrap();
+    let assembly_ast = code_gen::convert_ast(tacky_ast.clone()).unwrap();
+    let target = Target::X86_64_LINUX;
+
+    c.bench_function("tokenize", |b| {
+        b.iter(|| tokenize(black_box(&source_code)).unwrap())
+    });
+    c.bench_function("parse", |b| {
+        b.iter_batched(
+            || tokens.clone(),
+            |tokens| Parser::new(tokens).parse_ast().unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("tacky", |b| {
+        b.iter_batched(
+            || validate(Parser::new(tokens.clone()).parse_ast().unwrap()).unwrap(),
+            |validated_ast| TackyEmitter::new().convert_ast(validated_ast).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("codegen", |b| {
+        b.iter_batched(
+            || tacky_ast.clone(),
+            |tacky_ast| code_gen::convert_ast(tacky_ast).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    c.bench_function("emit", |b| {
+        b.iter(|| emit_assembly(black_box(&assembly_ast), &target))
+    });
+}
+
+criterion_group!(benches, bench_compiler_stages);
+criterion_main!(benches);
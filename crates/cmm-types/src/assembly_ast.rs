@@ -0,0 +1,1172 @@
+use crate::printer::print_string_literal;
+use crate::symbol::Symbol;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Represents an abstract syntax tree for assembly code.
+///
+/// With the `serde` feature, the AST serializes with the externally tagged enum representation of
+/// serde, e.g. `{"Mov": {"assembly_type": "Longword", "source": {"Imm": 1}, "destination": {"Register": "AX"}}}`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyAst {
+    /// Represents a complete program, containing its function definitions, the read-only
+    /// constants they refer to, and the variables of its writable data.
+    Program {
+        functions: Vec<AssemblyFunction>,
+        static_constants: Vec<AssemblyStaticConstant>,
+        static_variables: Vec<AssemblyStaticVariable>,
+    },
+}
+
+/// Represents a constant stored in the read-only data of the program.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyStaticConstant {
+    /// A null-terminated string, whose value excludes the null terminator.
+    String { identifier: Symbol, value: Vec<u8> },
+    /// A `double`, aligned to the given number of bytes. Operands of SSE instructions other than
+    /// `movsd` must be aligned to 16 bytes.
+    Double {
+        identifier: Symbol,
+        value: f64,
+        alignment: i32,
+    },
+}
+
+/// Represents a variable stored in the writable data of the program. Only a `global` variable is
+/// visible to the linker outside of its object file.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyStaticVariable {
+    Variable {
+        identifier: Symbol,
+        global: bool,
+        alignment: i32,
+        initial_values: Vec<AssemblyStaticInit>,
+    },
+}
+
+impl AssemblyStaticVariable {
+    /// Returns `true` if every byte of the initial value is zero, so the variable can be placed in
+    /// the zero-filled `.bss` section.
+    pub fn is_zero(&self) -> bool {
+        let AssemblyStaticVariable::Variable { initial_values, .. } = self;
+        initial_values
+            .iter()
+            .all(|initial_value| match initial_value {
+                AssemblyStaticInit::Integer { value, .. } => *value == 0,
+                AssemblyStaticInit::Double(value) => value.to_bits() == 0,
+                AssemblyStaticInit::Zero(_) => true,
+            })
+    }
+}
+
+/// Represents a part of the initial value of a static variable.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyStaticInit {
+    /// An integer with the size of the type, whose lower bits are stored.
+    Integer {
+        assembly_type: AssemblyType,
+        value: i64,
+    },
+    /// A `double`.
+    Double(f64),
+    /// The given number of zero bytes.
+    Zero(usize),
+}
+
+impl AssemblyStaticInit {
+    /// Returns the number of bytes the part of the initial value covers.
+    pub fn size(&self) -> usize {
+        match self {
+            AssemblyStaticInit::Integer { assembly_type, .. } => assembly_type.size() as usize,
+            AssemblyStaticInit::Double(_) => 8,
+            AssemblyStaticInit::Zero(size) => *size,
+        }
+    }
+}
+
+/// Represents the definition of a function.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyFunction {
+    /// A function with a name and a list of instructions. Only a `global` function is visible to
+    /// the linker outside of its object file.
+    Function {
+        identifier: Symbol,
+        global: bool,
+        instructions: Vec<AssemblyInstruction>,
+    },
+}
+
+/// Represents a single instruction in the assembly code.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyInstruction {
+    /// Move instruction: copies a value from a source operand to a destination operand.
+    Mov {
+        assembly_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Move with sign extension instruction: sign extends a source into a larger destination.
+    Movsx {
+        source_type: AssemblyType,
+        destination_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Move with zero extension instruction: zero extends a 1-byte source into a larger destination.
+    /// A 4-byte source needs no instruction of its own, as writing the lower 4 bytes of a register
+    /// clears its upper 4 bytes.
+    MovZeroExtend {
+        source_type: AssemblyType,
+        destination_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Load effective address instruction: stores the address of a memory operand into a register.
+    Lea {
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Unary instruction: applies a unary operator to an operand.
+    Unary {
+        op: AssemblyUnaryOperator,
+        assembly_type: AssemblyType,
+        operand: AssemblyOperand,
+    },
+    /// Binary instruction: applies a binary operator to two operands.
+    Binary {
+        op: AssemblyBinaryOperator,
+        assembly_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Compare instruction: compares two operands. `double`s are compared with `comisd`, which
+    /// sets the flags of an unsigned comparison.
+    Cmp {
+        assembly_type: AssemblyType,
+        left: AssemblyOperand,
+        right: AssemblyOperand,
+    },
+    /// Divide instruction: divides the value stored in %edx and %eax, or %rdx and %rax, by an operand.
+    Idiv {
+        assembly_type: AssemblyType,
+        operand: AssemblyOperand,
+    },
+    /// Unsigned divide instruction: divides the unsigned value stored in %edx and %eax, or %rdx and
+    /// %rax, by an operand.
+    Div {
+        assembly_type: AssemblyType,
+        operand: AssemblyOperand,
+    },
+    /// Sign extension instruction: sign extends %eax into %edx (`cdq`), %rax into %rdx (`cqo`), %ax
+    /// into %dx (`cwtd`), or %al into %ah (`cbtw`).
+    Cdq { assembly_type: AssemblyType },
+    /// Conversion instruction: converts a signed 4-byte or 8-byte integer into a `double`
+    /// (`cvtsi2sdl` or `cvtsi2sdq`). The type is the type of the source.
+    Cvtsi2sd {
+        assembly_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Truncating conversion instruction: converts a `double` into a signed 4-byte or 8-byte
+    /// integer, rounding toward zero (`cvttsd2sil` or `cvttsd2siq`). The type is the type of the
+    /// destination.
+    Cvttsd2si {
+        assembly_type: AssemblyType,
+        source: AssemblyOperand,
+        destination: AssemblyOperand,
+    },
+    /// Unconditional jump instruction: jumps to a specified label.
+    Jmp { label: Symbol },
+    /// Conditional jump instruction: jumps to a specified label if a condition is met.
+    JmpCC {
+        condition: AssemblyConditionCode,
+        label: Symbol,
+    },
+    /// Set instruction: sets the value of a register/memory based on RFLAGS if a condition is met.
+    SetCC {
+        condition: AssemblyConditionCode,
+        operand: AssemblyOperand,
+    },
+    /// Label pseudo-instruction: represents a label in the assembly code.
+    Label(Symbol),
+    /// Stack allocation instruction: allocates the given number of bytes of stack space.
+    AllocateStack { stack_offset: i32 },
+    /// Stack deallocation instruction: releases the given number of bytes of stack space, such as
+    /// the arguments of a call once it returns.
+    DeallocateStack { stack_offset: i32 },
+    /// Push instruction: pushes an 8-byte operand onto the stack, such as an argument of a call.
+    Push(AssemblyOperand),
+    /// Call instruction: calls the function with the given name.
+    Call(Symbol),
+    /// Return instruction: signifies the end of a function execution.
+    Ret,
+}
+
+/// Represents the size of the operands of an instruction.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyType {
+    /// 1-byte operands, used for `char` and `unsigned char` values
+    Byte,
+    /// 2-byte operands, used for `short` values
+    Word,
+    /// 4-byte operands, used for `int` and `unsigned int` values
+    Longword,
+    /// 8-byte operands, used for `long` values
+    Quadword,
+    /// 8-byte floating-point operands in SSE registers, used for `double` values
+    Double,
+}
+
+impl AssemblyType {
+    /// Returns the size of the operands in bytes.
+    ///
+    /// # Returns
+    ///
+    /// 1 for `Byte`, 2 for `Word`, 4 for `Longword`, and 8 for `Quadword` and `Double`.
+    pub fn size(&self) -> i32 {
+        match self {
+            AssemblyType::Byte => 1,
+            AssemblyType::Word => 2,
+            AssemblyType::Longword => 4,
+            AssemblyType::Quadword | AssemblyType::Double => 8,
+        }
+    }
+
+    /// Returns the AT&T syntax suffix of the instructions operating on the operands.
+    ///
+    /// # Returns
+    ///
+    /// `b` for `Byte`, `w` for `Word`, `l` for `Longword`, `q` for `Quadword`, and `sd` for
+    /// `Double`, e.g. `movb`, `movw`, `movl`, `movq`, and `movsd`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            AssemblyType::Byte => "b",
+            AssemblyType::Word => "w",
+            AssemblyType::Longword => "l",
+            AssemblyType::Quadword => "q",
+            AssemblyType::Double => "sd",
+        }
+    }
+
+    /// Returns the part of the registers holding the operands.
+    ///
+    /// # Returns
+    ///
+    /// The `RegisterSize` with the same number of bytes. SSE registers are named the same
+    /// regardless of the size.
+    pub fn register_size(&self) -> RegisterSize {
+        match self {
+            AssemblyType::Byte => RegisterSize::Byte,
+            AssemblyType::Word => RegisterSize::Word,
+            AssemblyType::Longword => RegisterSize::Longword,
+            AssemblyType::Quadword | AssemblyType::Double => RegisterSize::Quadword,
+        }
+    }
+}
+
+/// Represents a condition code
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyConditionCode {
+    /// Equal
+    E,
+    /// Not equal
+    NE,
+    /// Greater than
+    G,
+    /// Less than
+    L,
+    /// Greater than or equal
+    GE,
+    /// Less than or equal
+    LE,
+    /// Above, the unsigned greater than
+    A,
+    /// Above or equal, the unsigned greater than or equal
+    AE,
+    /// Below, the unsigned less than
+    B,
+    /// Below or equal, the unsigned less than or equal
+    BE,
+    /// Parity, set by `comisd` for unordered operands, where one of them is NaN
+    P,
+    /// No parity, the operands of `comisd` are ordered
+    NP,
+}
+
+/// Represents an unary operator.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyUnaryOperator {
+    /// Negation instruction
+    Neg,
+    /// Bitwise NOT instruction
+    Not,
+}
+
+/// Represents a binary operator.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyBinaryOperator {
+    Add,
+    Sub,
+    Mult,
+    And,
+    Or,
+    Xor,
+    /// Arithmetic left shift, by an immediate value or by the lowest byte of CX
+    Sal,
+    /// Arithmetic right shift, by an immediate value or by the lowest byte of CX
+    Sar,
+    /// Logical right shift, by an immediate value or by the lowest byte of CX
+    Shr,
+    /// Floating-point division of `double`s, as integer division is not a binary instruction
+    DivDouble,
+}
+
+impl AssemblyBinaryOperator {
+    /// Returns whether the operator is a shift, whose count is a byte instead of a 4-byte operand.
+    ///
+    /// # Returns
+    ///
+    /// `true` for `Sal`, `Sar`, and `Shr`, `false` otherwise.
+    pub fn is_shift(&self) -> bool {
+        matches!(
+            self,
+            AssemblyBinaryOperator::Sal | AssemblyBinaryOperator::Sar | AssemblyBinaryOperator::Shr
+        )
+    }
+
+    /// Returns the mnemonic of the SSE instruction applying the operator to `double` operands.
+    ///
+    /// # Returns
+    ///
+    /// The mnemonic, e.g. `addsd` for `Add` or `xorpd` for `Xor`, or `None` for the shifts, which
+    /// have no `double` form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::assembly_ast::AssemblyBinaryOperator;
+    /// assert_eq!(AssemblyBinaryOperator::Mult.double_mnemonic(), Some("mulsd"));
+    /// assert_eq!(AssemblyBinaryOperator::Sal.double_mnemonic(), None);
+    /// ```
+    pub fn double_mnemonic(&self) -> Option<&'static str> {
+        match self {
+            AssemblyBinaryOperator::Add => Some("addsd"),
+            AssemblyBinaryOperator::Sub => Some("subsd"),
+            AssemblyBinaryOperator::Mult => Some("mulsd"),
+            AssemblyBinaryOperator::DivDouble => Some("divsd"),
+            AssemblyBinaryOperator::And => Some("andpd"),
+            AssemblyBinaryOperator::Or => Some("orpd"),
+            AssemblyBinaryOperator::Xor => Some("xorpd"),
+            AssemblyBinaryOperator::Sal
+            | AssemblyBinaryOperator::Sar
+            | AssemblyBinaryOperator::Shr => None,
+        }
+    }
+}
+
+/// Represents an operand for an instruction, which can be an immediate value or a register.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyOperand {
+    /// An immediate integer value. Only `Mov` accepts values that do not fit into 32 bits.
+    Imm(i64),
+    /// A CPU register
+    Register(AssemblyRegister),
+    // A pseudo CPU register
+    Pseudo(Symbol),
+    /// A stack location
+    Stack(i32),
+    /// A memory location at an offset from the address held by a register, such as the object a
+    /// pointer points to
+    Memory(AssemblyRegister, i32),
+    /// A memory location at the address held by a base register plus the value of an index
+    /// register times a scale of 1, 2, 4, or 8, such as an element of an array
+    Indexed(AssemblyRegister, AssemblyRegister, i32),
+    /// A read-only constant, addressed relative to the instruction pointer
+    Data(Symbol),
+}
+
+/// Represents a CPU register.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AssemblyRegister {
+    /// AX CPU register
+    AX,
+    /// CX CPU register, whose lowest byte holds variable shift counts
+    CX,
+    /// DX CPU register
+    DX,
+    /// DI CPU register, which passes the first integer argument of a call
+    DI,
+    /// SI CPU register, which passes the second integer argument of a call
+    SI,
+    /// R8 CPU register, which passes the fifth integer argument of a call
+    R8,
+    /// R9 CPU register, which passes the sixth integer argument of a call
+    R9,
+    /// R10 scratch register
+    R10,
+    /// R11 scratch register
+    R11,
+    /// XMM0 SSE register
+    XMM0,
+    /// XMM1 SSE register
+    XMM1,
+    /// XMM2 SSE register
+    XMM2,
+    /// XMM3 SSE register
+    XMM3,
+    /// XMM4 SSE register
+    XMM4,
+    /// XMM5 SSE register
+    XMM5,
+    /// XMM6 SSE register
+    XMM6,
+    /// XMM7 SSE register
+    XMM7,
+    /// XMM14 SSE scratch register, for source operands
+    XMM14,
+    /// XMM15 SSE scratch register, for destination operands
+    XMM15,
+}
+
+/// Represents the part of a register an instruction operates on.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RegisterSize {
+    /// The lowest byte, e.g. `%al`
+    Byte,
+    /// The lowest 2 bytes, e.g. `%ax`
+    Word,
+    /// The lowest 4 bytes, e.g. `%eax`
+    Longword,
+    /// All 8 bytes, e.g. `%rax`
+    Quadword,
+}
+
+impl AssemblyRegister {
+    /// Returns the AT&T syntax name of a part of the register.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The part of the register to name.
+    ///
+    /// # Returns
+    ///
+    /// The register name, e.g. `%al`, `%ax`, `%eax`, or `%rax`. The numbered registers name their
+    /// parts with a suffix, e.g. `%r10b`, `%r10w`, `%r10d`, or `%r10`. SSE registers have a single name, e.g. `%xmm0`.
+    pub fn name(&self, size: RegisterSize) -> &'static str {
+        match (self, size) {
+            (AssemblyRegister::AX, RegisterSize::Byte) => "%al",
+            (AssemblyRegister::AX, RegisterSize::Word) => "%ax",
+            (AssemblyRegister::AX, RegisterSize::Longword) => "%eax",
+            (AssemblyRegister::AX, RegisterSize::Quadword) => "%rax",
+            (AssemblyRegister::CX, RegisterSize::Byte) => "%cl",
+            (AssemblyRegister::CX, RegisterSize::Word) => "%cx",
+            (AssemblyRegister::CX, RegisterSize::Longword) => "%ecx",
+            (AssemblyRegister::CX, RegisterSize::Quadword) => "%rcx",
+            (AssemblyRegister::DX, RegisterSize::Byte) => "%dl",
+            (AssemblyRegister::DX, RegisterSize::Word) => "%dx",
+            (AssemblyRegister::DX, RegisterSize::Longword) => "%edx",
+            (AssemblyRegister::DX, RegisterSize::Quadword) => "%rdx",
+            (AssemblyRegister::DI, RegisterSize::Byte) => "%dil",
+            (AssemblyRegister::DI, RegisterSize::Word) => "%di",
+            (AssemblyRegister::DI, RegisterSize::Longword) => "%edi",
+            (AssemblyRegister::DI, RegisterSize::Quadword) => "%rdi",
+            (AssemblyRegister::SI, RegisterSize::Byte) => "%sil",
+            (AssemblyRegister::SI, RegisterSize::Word) => "%si",
+            (AssemblyRegister::SI, RegisterSize::Longword) => "%esi",
+            (AssemblyRegister::SI, RegisterSize::Quadword) => "%rsi",
+            (AssemblyRegister::R8, RegisterSize::Byte) => "%r8b",
+            (AssemblyRegister::R8, RegisterSize::Word) => "%r8w",
+            (AssemblyRegister::R8, RegisterSize::Longword) => "%r8d",
+            (AssemblyRegister::R8, RegisterSize::Quadword) => "%r8",
+            (AssemblyRegister::R9, RegisterSize::Byte) => "%r9b",
+            (AssemblyRegister::R9, RegisterSize::Word) => "%r9w",
+            (AssemblyRegister::R9, RegisterSize::Longword) => "%r9d",
+            (AssemblyRegister::R9, RegisterSize::Quadword) => "%r9",
+            (AssemblyRegister::R10, RegisterSize::Byte) => "%r10b",
+            (AssemblyRegister::R10, RegisterSize::Word) => "%r10w",
+            (AssemblyRegister::R10, RegisterSize::Longword) => "%r10d",
+            (AssemblyRegister::R10, RegisterSize::Quadword) => "%r10",
+            (AssemblyRegister::R11, RegisterSize::Byte) => "%r11b",
+            (AssemblyRegister::R11, RegisterSize::Word) => "%r11w",
+            (AssemblyRegister::R11, RegisterSize::Longword) => "%r11d",
+            (AssemblyRegister::R11, RegisterSize::Quadword) => "%r11",
+            (AssemblyRegister::XMM0, _) => "%xmm0",
+            (AssemblyRegister::XMM1, _) => "%xmm1",
+            (AssemblyRegister::XMM2, _) => "%xmm2",
+            (AssemblyRegister::XMM3, _) => "%xmm3",
+            (AssemblyRegister::XMM4, _) => "%xmm4",
+            (AssemblyRegister::XMM5, _) => "%xmm5",
+            (AssemblyRegister::XMM6, _) => "%xmm6",
+            (AssemblyRegister::XMM7, _) => "%xmm7",
+            (AssemblyRegister::XMM14, _) => "%xmm14",
+            (AssemblyRegister::XMM15, _) => "%xmm15",
+        }
+    }
+}
+
+impl AssemblyOperand {
+    /// Returns the operand formatted in AT&T syntax for an instruction of the given size.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The part of a register operand the instruction operates on.
+    ///
+    /// # Returns
+    ///
+    /// A value displaying the operand, e.g. `$1`, `%rax`, `-8(%rbp)`, `0(%rax)`, `(%rax,%rdx,4)`, or
+    /// `string.0(%rip)`. Pseudo registers are shown by their name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::assembly_ast::{AssemblyOperand, AssemblyRegister, RegisterSize};
+    /// let register = AssemblyOperand::Register(AssemblyRegister::R10);
+    /// assert_eq!(register.sized(RegisterSize::Quadword).to_string(), "%r10");
+    /// assert_eq!(register.sized(RegisterSize::Byte).to_string(), "%r10b");
+    /// assert_eq!(AssemblyOperand::Stack(-8).sized(RegisterSize::Byte).to_string(), "-8(%rbp)");
+    /// let memory = AssemblyOperand::Memory(AssemblyRegister::AX, 0);
+    /// assert_eq!(memory.sized(RegisterSize::Byte).to_string(), "0(%rax)");
+    /// let indexed = AssemblyOperand::Indexed(AssemblyRegister::AX, AssemblyRegister::DX, 4);
+    /// assert_eq!(indexed.sized(RegisterSize::Byte).to_string(), "(%rax,%rdx,4)");
+    /// ```
+    pub fn sized(&self, size: RegisterSize) -> SizedOperand<'_> {
+        SizedOperand {
+            operand: self,
+            size,
+        }
+    }
+}
+
+/// An operand formatted for an instruction of a given size, see `AssemblyOperand::sized`.
+pub struct SizedOperand<'a> {
+    operand: &'a AssemblyOperand,
+    size: RegisterSize,
+}
+
+impl fmt::Display for AssemblyAst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyAst::Program {
+                functions,
+                static_constants,
+                static_variables,
+            } => {
+                for function in functions {
+                    write!(f, "{}", function)?;
+                }
+                for static_constant in static_constants {
+                    writeln!(f, "{}", static_constant)?;
+                }
+                for static_variable in static_variables {
+                    writeln!(f, "{}", static_variable)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for AssemblyStaticConstant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyStaticConstant::String { identifier, value } => {
+                write!(
+                    f,
+                    "{}:\n    .asciz {}",
+                    identifier,
+                    print_string_literal(value)
+                )
+            }
+            AssemblyStaticConstant::Double {
+                identifier,
+                value,
+                alignment,
+            } => write!(
+                f,
+                "    .balign {}\n{}:\n    .double {:?}",
+                alignment, identifier, value
+            ),
+        }
+    }
+}
+
+impl fmt::Display for AssemblyStaticVariable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyStaticVariable::Variable {
+                identifier,
+                alignment,
+                initial_values,
+                ..
+            } => {
+                write!(f, "    .balign {}\n{}:", alignment, identifier)?;
+                for initial_value in initial_values {
+                    write!(f, "\n    {}", initial_value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats a part of an initial value as an assembler directive, e.g. `.long 3` or `.zero 8`.
+impl fmt::Display for AssemblyStaticInit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyStaticInit::Integer {
+                assembly_type,
+                value,
+            } => {
+                let directive = match assembly_type {
+                    AssemblyType::Byte => ".byte",
+                    AssemblyType::Word => ".short",
+                    AssemblyType::Longword => ".long",
+                    AssemblyType::Quadword | AssemblyType::Double => ".quad",
+                };
+                write!(f, "{} {}", directive, value)
+            }
+            AssemblyStaticInit::Double(value) => write!(f, ".double {:?}", value),
+            AssemblyStaticInit::Zero(size) => write!(f, ".zero {}", size),
+        }
+    }
+}
+
+impl fmt::Display for AssemblyFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyFunction::Function {
+                identifier,
+                global: _,
+                instructions,
+            } => {
+                writeln!(f, "{}:", identifier)?;
+                for instruction in instructions {
+                    match instruction {
+                        AssemblyInstruction::Label(_) => writeln!(f, "{}", instruction)?,
+                        _ => writeln!(f, "    {}", instruction)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats instructions in AT&T syntax, without the symbol prefixes of any target.
+impl fmt::Display for AssemblyInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssemblyInstruction::Mov {
+                assembly_type,
+                source,
+                destination,
+            } => {
+                let size = assembly_type.register_size();
+                write!(
+                    f,
+                    "mov{} {}, {}",
+                    assembly_type.suffix(),
+                    source.sized(size),
+                    destination.sized(size)
+                )
+            }
+            AssemblyInstruction::Movsx {
+                source_type,
+                destination_type,
+                source,
+                destination,
+            } => write!(
+                f,
+                "movs{}{} {}, {}",
+                source_type.suffix(),
+                destination_type.suffix(),
+                source.sized(source_type.register_size()),
+                destination.sized(destination_type.register_size())
+            ),
+            AssemblyInstruction::MovZeroExtend {
+                source_type,
+                destination_type,
+                source,
+                destination,
+            } => write!(
+                f,
+                "movz{}{} {}, {}",
+                source_type.suffix(),
+                destination_type.suffix(),
+                source.sized(source_type.register_size()),
+                destination.sized(destination_type.register_size())
+            ),
+            AssemblyInstruction::Lea {
+                source,
+                destination,
+            } => write!(
+                f,
+                "leaq {}, {}",
+                source.sized(RegisterSize::Quadword),
+                destination.sized(RegisterSize::Quadword)
+            ),
+            AssemblyInstruction::Unary {
+                op,
+                assembly_type,
+                operand,
+            } => write!(
+                f,
+                "{}{} {}",
+                op,
+                assembly_type.suffix(),
+                operand.sized(assembly_type.register_size())
+            ),
+            AssemblyInstruction::Binary {
+                op,
+                assembly_type,
+                source,
+                destination,
+            } => {
+                let size = assembly_type.register_size();
+                if let (AssemblyType::Double, Some(mnemonic)) =
+                    (assembly_type, op.double_mnemonic())
+                {
+                    return write!(
+                        f,
+                        "{} {}, {}",
+                        mnemonic,
+                        source.sized(size),
+                        destination.sized(size)
+                    );
+                }
+                // Variable shift counts are the lowest byte of %cl.
+                let source_size = if op.is_shift() {
+                    RegisterSize::Byte
+                } else {
+                    size
+                };
+                write!(
+                    f,
+                    "{}{} {}, {}",
+                    op,
+                    assembly_type.suffix(),
+                    source.sized(source_size),
+                    destination.sized(size)
+                )
+            }
+            AssemblyInstruction::Cmp {
+                assembly_type,
+                left,
+                right,
+            } => {
+                let size = assembly_type.register_size();
+                let mnemonic = match assembly_type {
+                    AssemblyType::Double => "comi",
+                    _ => "cmp",
+                };
+                write!(
+                    f,
+                    "{}{} {}, {}",
+                    mnemonic,
+                    assembly_type.suffix(),
+                    left.sized(size),
+                    right.sized(size)
+                )
+            }
+            AssemblyInstruction::Idiv {
+                assembly_type,
+                operand,
+            } => write!(
+                f,
+                "idiv{} {}",
+                assembly_type.suffix(),
+                operand.sized(assembly_type.register_size())
+            ),
+            AssemblyInstruction::Div {
+                assembly_type,
+                operand,
+            } => write!(
+                f,
+                "div{} {}",
+                assembly_type.suffix(),
+                operand.sized(assembly_type.register_size())
+            ),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Byte,
+            } => write!(f, "cbtw"),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Word,
+            } => write!(f, "cwtd"),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Longword,
+            } => write!(f, "cdq"),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Quadword | AssemblyType::Double,
+            } => write!(f, "cqo"),
+            AssemblyInstruction::Cvtsi2sd {
+                assembly_type,
+                source,
+                destination,
+            } => write!(
+                f,
+                "cvtsi2sd{} {}, {}",
+                assembly_type.suffix(),
+                source.sized(assembly_type.register_size()),
+                destination.sized(RegisterSize::Quadword)
+            ),
+            AssemblyInstruction::Cvttsd2si {
+                assembly_type,
+                source,
+                destination,
+            } => write!(
+                f,
+                "cvttsd2si{} {}, {}",
+                assembly_type.suffix(),
+                source.sized(RegisterSize::Quadword),
+                destination.sized(assembly_type.register_size())
+            ),
+            AssemblyInstruction::Jmp { label } => write!(f, "jmp {}", label),
+            AssemblyInstruction::JmpCC { condition, label } => {
+                write!(f, "j{} {}", condition, label)
+            }
+            AssemblyInstruction::SetCC { condition, operand } => {
+                write!(f, "set{} {}", condition, operand.sized(RegisterSize::Byte))
+            }
+            AssemblyInstruction::Label(label) => write!(f, "{}:", label),
+            AssemblyInstruction::AllocateStack { stack_offset } => {
+                write!(f, "subq ${}, %rsp", stack_offset)
+            }
+            AssemblyInstruction::DeallocateStack { stack_offset } => {
+                write!(f, "addq ${}, %rsp", stack_offset)
+            }
+            AssemblyInstruction::Push(operand) => {
+                write!(f, "pushq {}", operand.sized(RegisterSize::Quadword))
+            }
+            AssemblyInstruction::Call(identifier) => write!(f, "call {}", identifier),
+            AssemblyInstruction::Ret => write!(f, "ret"),
+        }
+    }
+}
+
+impl fmt::Display for AssemblyConditionCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suffix = match self {
+            AssemblyConditionCode::E => "e",
+            AssemblyConditionCode::NE => "ne",
+            AssemblyConditionCode::G => "g",
+            AssemblyConditionCode::L => "l",
+            AssemblyConditionCode::GE => "ge",
+            AssemblyConditionCode::LE => "le",
+            AssemblyConditionCode::A => "a",
+            AssemblyConditionCode::AE => "ae",
+            AssemblyConditionCode::B => "b",
+            AssemblyConditionCode::BE => "be",
+            AssemblyConditionCode::P => "p",
+            AssemblyConditionCode::NP => "np",
+        };
+        f.write_str(suffix)
+    }
+}
+
+/// Formats the mnemonic without its size suffix, e.g. `neg` for both `negl` and `negq`.
+impl fmt::Display for AssemblyUnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mnemonic = match self {
+            AssemblyUnaryOperator::Neg => "neg",
+            AssemblyUnaryOperator::Not => "not",
+        };
+        f.write_str(mnemonic)
+    }
+}
+
+/// Formats the mnemonic of integer operands without its size suffix, e.g. `add` for both `addl`
+/// and `addq`. See `double_mnemonic` for `double` operands.
+impl fmt::Display for AssemblyBinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mnemonic = match self {
+            AssemblyBinaryOperator::Add => "add",
+            AssemblyBinaryOperator::Sub => "sub",
+            AssemblyBinaryOperator::Mult => "imul",
+            AssemblyBinaryOperator::And => "and",
+            AssemblyBinaryOperator::Or => "or",
+            AssemblyBinaryOperator::Xor => "xor",
+            AssemblyBinaryOperator::Sal => "sal",
+            AssemblyBinaryOperator::Sar => "sar",
+            AssemblyBinaryOperator::Shr => "shr",
+            AssemblyBinaryOperator::DivDouble => "div",
+        };
+        f.write_str(mnemonic)
+    }
+}
+
+/// Formats operands in AT&T syntax, naming the lowest 4 bytes of registers.
+impl fmt::Display for AssemblyOperand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.sized(RegisterSize::Longword).fmt(f)
+    }
+}
+
+impl fmt::Display for SizedOperand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operand {
+            AssemblyOperand::Imm(value) => write!(f, "${}", value),
+            AssemblyOperand::Register(register) => f.write_str(register.name(self.size)),
+            AssemblyOperand::Pseudo(identifier) => write!(f, "{}", identifier),
+            AssemblyOperand::Stack(offset) => write!(f, "{}(%rbp)", offset),
+            AssemblyOperand::Memory(register, offset) => {
+                write!(f, "{}({})", offset, register.name(RegisterSize::Quadword))
+            }
+            AssemblyOperand::Indexed(base, index, scale) => write!(
+                f,
+                "({},{},{})",
+                base.name(RegisterSize::Quadword),
+                index.name(RegisterSize::Quadword),
+                scale
+            ),
+            AssemblyOperand::Data(identifier) => write!(f, "{}(%rip)", identifier),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_function() {
+        let function = AssemblyFunction::Function {
+            identifier: Symbol::from("main"),
+            global: true,
+            instructions: vec![
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Longword,
+                    left: AssemblyOperand::Imm(0),
+                    right: AssemblyOperand::Pseudo(Symbol::from("tmp.0")),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::E,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Label(Symbol::from("end.1")),
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+                AssemblyInstruction::Ret,
+            ],
+        };
+        assert_eq!(
+            function.to_string(),
+            "main:\n    cmpl $0, tmp.0\n    sete %r11b\nend.1:\n    movl -4(%rbp), %eax\n    ret\n"
+        );
+    }
+
+    #[test]
+    fn test_display_quadword_instructions() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let instructions = [
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(4294967296),
+                destination: register_r10.clone(),
+            },
+            AssemblyInstruction::Movsx {
+                source_type: AssemblyType::Longword,
+                destination_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Stack(-4),
+                destination: register_r10.clone(),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Sal,
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Register(AssemblyRegister::CX),
+                destination: register_r10,
+            },
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Quadword,
+            },
+        ];
+        let lines: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "movq $4294967296, %r10",
+                "movslq -4(%rbp), %r10",
+                "salq %cl, %r10",
+                "cqo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_byte_instructions() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let instructions = [
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Byte,
+                source: AssemblyOperand::Imm(97),
+                destination: AssemblyOperand::Stack(-1),
+            },
+            AssemblyInstruction::Movsx {
+                source_type: AssemblyType::Byte,
+                destination_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-1),
+                destination: register_r10.clone(),
+            },
+            AssemblyInstruction::MovZeroExtend {
+                source_type: AssemblyType::Byte,
+                destination_type: AssemblyType::Quadword,
+                source: register_r10.clone(),
+                destination: register_r10,
+            },
+        ];
+        let lines: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "movb $97, -1(%rbp)",
+                "movsbl -1(%rbp), %r10d",
+                "movzbq %r10b, %r10"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_call_instructions() {
+        let instructions = [
+            AssemblyInstruction::AllocateStack { stack_offset: 8 },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Byte,
+                source: AssemblyOperand::Imm(97),
+                destination: AssemblyOperand::Register(AssemblyRegister::DI),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Stack(-8),
+                destination: AssemblyOperand::Register(AssemblyRegister::XMM1),
+            },
+            AssemblyInstruction::Push(AssemblyOperand::Register(AssemblyRegister::R9)),
+            AssemblyInstruction::Call(Symbol::from("f")),
+            AssemblyInstruction::DeallocateStack { stack_offset: 16 },
+        ];
+        let lines: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "subq $8, %rsp",
+                "movb $97, %dil",
+                "movsd -8(%rbp), %xmm1",
+                "pushq %r9",
+                "call f",
+                "addq $16, %rsp"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_program_with_string_constant() {
+        let program = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                instructions: vec![AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Data(Symbol::from("string.0")),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                }],
+            }],
+            static_constants: vec![AssemblyStaticConstant::String {
+                identifier: Symbol::from("string.0"),
+                value: b"hi\n".to_vec(),
+            }],
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            program.to_string(),
+            "main:\n    leaq string.0(%rip), %r11\nstring.0:\n    .asciz \"hi\\n\"\n"
+        );
+    }
+
+    #[test]
+    fn test_display_double_instructions() {
+        let register_xmm15 = AssemblyOperand::Register(AssemblyRegister::XMM15);
+        let instructions = [
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Data(Symbol::from("double.0")),
+                destination: register_xmm15.clone(),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::DivDouble,
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Stack(-8),
+                destination: register_xmm15.clone(),
+            },
+            AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Double,
+                left: AssemblyOperand::Stack(-16),
+                right: register_xmm15.clone(),
+            },
+            AssemblyInstruction::Cvtsi2sd {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Register(AssemblyRegister::R10),
+                destination: register_xmm15.clone(),
+            },
+            AssemblyInstruction::Cvttsd2si {
+                assembly_type: AssemblyType::Quadword,
+                source: register_xmm15,
+                destination: AssemblyOperand::Register(AssemblyRegister::R11),
+            },
+        ];
+        let lines: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "movsd double.0(%rip), %xmm15",
+                "divsd -8(%rbp), %xmm15",
+                "comisd -16(%rbp), %xmm15",
+                "cvtsi2sdl %r10d, %xmm15",
+                "cvttsd2siq %xmm15, %r11"
+            ]
+        );
+        let constant = AssemblyStaticConstant::Double {
+            identifier: Symbol::from("double.0"),
+            value: -0.0,
+            alignment: 16,
+        };
+        assert_eq!(
+            constant.to_string(),
+            "    .balign 16\ndouble.0:\n    .double -0.0"
+        );
+    }
+
+    #[test]
+    fn test_display_static_variable() {
+        let variable = AssemblyStaticVariable::Variable {
+            identifier: Symbol::from("a"),
+            global: true,
+            alignment: 8,
+            initial_values: vec![
+                AssemblyStaticInit::Integer {
+                    assembly_type: AssemblyType::Word,
+                    value: -1,
+                },
+                AssemblyStaticInit::Zero(6),
+                AssemblyStaticInit::Double(2.5),
+            ],
+        };
+        assert_eq!(
+            variable.to_string(),
+            "    .balign 8\na:\n    .short -1\n    .zero 6\n    .double 2.5"
+        );
+        assert!(!variable.is_zero());
+    }
+}
@@ -0,0 +1,1250 @@
+use crate::printer;
+use crate::span::Span;
+use crate::symbol::Symbol;
+use std::fmt;
+use std::ops::Index;
+
+/// Represents the abstract syntax tree of a program.
+///
+/// The declarations, statements, and expressions parsed from source code hold their `Span`, the
+/// location they were parsed from, which is `None` for nodes built in code and for the tokens of
+/// a stream without locations. The `Debug` output resolves every `ExprId` to its expression, so
+/// it prints the program as a nested tree, and leaves the spans out.
+#[derive(PartialEq)]
+pub enum CmmAst {
+    /// A program is composed of structure declarations, enumeration declarations, file-scope
+    /// variable declarations, and function declarations and definitions, each in the order they
+    /// appear.
+    Program {
+        structs: Vec<CmmStructDeclaration>,
+        enums: Vec<CmmEnumDeclaration>,
+        /// The variables declared outside of the functions, which have static storage duration.
+        variables: Vec<CmmDeclaration>,
+        functions: Vec<CmmFunction>,
+        /// The expressions of the program, referenced by `ExprId`.
+        expressions: ExpressionArena,
+    },
+}
+
+/// Represents the declaration of a structure type.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmStructDeclaration {
+    /// A structure type consisting of its tag and its members, in the order they are laid out in
+    /// memory, e.g. `struct point { int x; int y; };`.
+    Struct {
+        tag: Symbol,
+        members: Vec<CmmMember>,
+        span: Option<Span>,
+    },
+}
+
+/// Represents a member of a structure type.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmMember {
+    /// A member, which is stored at an offset from the start of the structure.
+    Member {
+        identifier: Symbol,
+        member_type: CmmType,
+    },
+}
+
+/// Represents the declaration of an enumeration type.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmEnumDeclaration {
+    /// An enumeration consisting of its optional tag and its enumerators, in the order they are
+    /// declared, e.g. `enum color { RED, GREEN = 5, BLUE };`. The enumeration type itself is `int`.
+    Enum {
+        tag: Option<Symbol>,
+        enumerators: Vec<CmmEnumerator>,
+        span: Option<Span>,
+    },
+}
+
+/// Represents an enumerator, a named `int` constant of an enumeration.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmEnumerator {
+    /// An enumerator with an optional constant expression giving its value. Without one, the value
+    /// is one more than the value of the previous enumerator, or zero for the first one.
+    Enumerator {
+        identifier: Symbol,
+        value: Option<ExprId>,
+    },
+}
+
+/// Represents a function declaration or definition.
+#[derive(Debug, PartialEq)]
+pub enum CmmFunction {
+    /// A function consisting of its name, its parameters, and its body. A function without
+    /// parameters is declared with `(void)`, and a prototype such as `int f(int a);` has no body.
+    /// A variadic function, such as `int printf(...);`, takes further arguments after its
+    /// parameters.
+    Function {
+        identifier: Symbol,
+        /// The storage-class specifier of the declaration, which determines the linkage of the
+        /// function.
+        storage_class: Option<CmmStorageClass>,
+        /// The type of the returned value, or `None` for a `void` function, which returns
+        /// nothing.
+        return_type: Option<CmmType>,
+        parameters: Vec<CmmParameter>,
+        variadic: bool,
+        /// The block items of the body in source order, which share the scope of the parameters.
+        body: Option<Vec<CmmBlockItem>>,
+        /// The location of the declarator, from the start of the declaration to the closing
+        /// parenthesis of the parameter list.
+        span: Option<Span>,
+    },
+}
+
+/// Represents an item of a block, which runs after the items before it.
+#[derive(Debug, PartialEq)]
+pub enum CmmBlockItem {
+    /// A variable declaration, visible from its declarator to the end of the block.
+    Declaration(CmmDeclaration),
+    /// A statement.
+    Statement(CmmStatement),
+}
+
+/// Represents a storage-class specifier of a declaration.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CmmStorageClass {
+    /// `static`, which gives a function or a file-scope variable internal linkage, so it is only
+    /// visible in its own file, and gives a local variable static storage duration.
+    Static,
+    /// `extern`, which keeps the linkage of a previous declaration, and is external otherwise.
+    Extern,
+}
+
+impl fmt::Display for CmmStorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmmStorageClass::Static => write!(f, "static"),
+            CmmStorageClass::Extern => write!(f, "extern"),
+        }
+    }
+}
+
+/// Represents a parameter of a function declaration or definition.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmParameter {
+    /// A parameter, which is a variable of the function body initialized with the argument of
+    /// the call converted to its type.
+    Parameter {
+        identifier: Symbol,
+        parameter_type: CmmType,
+    },
+}
+
+/// Represents a statement within a function. Every statement holds its `span`, which ends with
+/// its last token, such as the semicolon or the last token of a nested statement.
+#[derive(Debug, PartialEq)]
+pub enum CmmStatement {
+    /// A return statement, which returns an expression, or nothing from a `void` function.
+    Return {
+        expression: Option<ExprId>,
+        span: Option<Span>,
+    },
+    /// An expression statement, which evaluates an expression for its side effects and discards
+    /// its value.
+    Expression {
+        expression: ExprId,
+        span: Option<Span>,
+    },
+    /// A null statement, a lone `;`, which does nothing.
+    Null { span: Option<Span> },
+    /// An if statement, which executes `then_branch` if the condition is nonzero, and the optional
+    /// `else_branch` otherwise. An `else` belongs to the closest preceding `if` without one.
+    If {
+        condition: ExprId,
+        then_branch: Box<CmmStatement>,
+        else_branch: Option<Box<CmmStatement>>,
+        span: Option<Span>,
+    },
+    /// A while loop, which executes `body` as long as the condition is nonzero, checking the
+    /// condition before every iteration.
+    While {
+        condition: ExprId,
+        body: Box<CmmStatement>,
+        span: Option<Span>,
+    },
+    /// A do-while loop, which executes `body` as long as the condition is nonzero, checking the
+    /// condition after every iteration, so the body runs at least once.
+    DoWhile {
+        body: Box<CmmStatement>,
+        condition: ExprId,
+        span: Option<Span>,
+    },
+    /// A for loop. The `init` clause runs once, then `body` and the `post` expression run as long
+    /// as the condition is nonzero. A missing condition is always true. A variable declared in
+    /// `init` is only visible inside the loop.
+    For {
+        init: CmmForInit,
+        condition: Option<ExprId>,
+        post: Option<ExprId>,
+        body: Box<CmmStatement>,
+        span: Option<Span>,
+    },
+    /// A break statement, which exits the innermost enclosing loop.
+    Break { span: Option<Span> },
+    /// A continue statement, which skips to the next iteration of the innermost enclosing loop. In
+    /// a for loop, the post expression still runs before the condition is checked again.
+    Continue { span: Option<Span> },
+}
+
+impl CmmStatement {
+    /// Returns the location of the statement, or `None` if it was not parsed from source code.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CmmStatement::Return { span, .. }
+            | CmmStatement::Expression { span, .. }
+            | CmmStatement::Null { span }
+            | CmmStatement::If { span, .. }
+            | CmmStatement::While { span, .. }
+            | CmmStatement::DoWhile { span, .. }
+            | CmmStatement::For { span, .. }
+            | CmmStatement::Break { span }
+            | CmmStatement::Continue { span } => *span,
+        }
+    }
+}
+
+/// Represents the initial clause of a for loop.
+#[derive(Debug, PartialEq)]
+pub enum CmmForInit {
+    /// A variable declaration, scoped to the loop.
+    Declaration(CmmDeclaration),
+    /// An optional expression, evaluated for its side effects.
+    Expression(Option<ExprId>),
+}
+
+/// Represents a variable declaration.
+#[derive(Debug, PartialEq)]
+pub enum CmmDeclaration {
+    /// A variable declaration with an optional initializer, which is converted to the type of the
+    /// variable.
+    Declaration {
+        identifier: Symbol,
+        /// The storage-class specifier of the declaration, if any. A `static` variable keeps its
+        /// value for the whole run of the program, and an `extern` declaration refers to a
+        /// variable defined elsewhere.
+        storage_class: Option<CmmStorageClass>,
+        variable_type: CmmType,
+        initializer: Option<CmmInitializer>,
+        /// The location of the declaration without its semicolon, which also ends the initial
+        /// clause of a for loop.
+        span: Option<Span>,
+    },
+}
+
+/// Represents the initializer of a variable declaration.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmInitializer {
+    /// An expression, which initializes a variable that is not an array.
+    Single(ExprId),
+    /// A braced list of initializers for the elements of an array, e.g. `{1, 2, 3}`. The
+    /// elements without an initializer are zero.
+    Compound(Vec<CmmInitializer>),
+}
+
+/// Represents the type of a variable or an expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CmmType {
+    /// A signed 32-bit integer.
+    Int,
+    /// An unsigned 32-bit integer, whose arithmetic wraps around modulo 2^32.
+    UnsignedInt,
+    /// A signed 64-bit integer.
+    Long,
+    /// An unsigned 64-bit integer, whose arithmetic wraps around modulo 2^64.
+    UnsignedLong,
+    /// A signed 16-bit integer.
+    Short,
+    /// A signed 8-bit integer, like the `char` of x86-64 C compilers.
+    Char,
+    /// An unsigned 8-bit integer.
+    UnsignedChar,
+    /// A boolean stored in a byte, which only holds 0 or 1 because every value converted to it
+    /// becomes 1 unless it compares equal to zero.
+    Bool,
+    /// A 64-bit IEEE 754 floating-point number.
+    Double,
+    /// The 64-bit address of an object of the referenced type, such as `int *`.
+    Pointer(Box<CmmType>),
+    /// A fixed number of consecutive elements of the element type, such as `int [3]`.
+    Array(Box<CmmType>, usize),
+    /// A structure type declared with its tag, such as `struct point`, whose members are laid
+    /// out by the type table of the compiler.
+    Struct(Symbol),
+    /// A `const` qualified type, whose objects cannot be modified after their initialization. An
+    /// array type is never qualified itself, its elements are.
+    Const(Box<CmmType>),
+}
+
+impl CmmType {
+    /// Returns the type a pointer of the type refers to, or `None` if the type is not a pointer.
+    pub fn referenced_type(&self) -> Option<&CmmType> {
+        match self {
+            CmmType::Pointer(referenced_type) => Some(referenced_type),
+            CmmType::Const(qualified_type) => qualified_type.referenced_type(),
+            _ => None,
+        }
+    }
+
+    /// Returns the `const` qualified version of the type. Qualifying an array qualifies its
+    /// elements, and qualifying a qualified type keeps it as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// let array = CmmType::Array(Box::new(CmmType::Int), 3);
+    /// let element = CmmType::Const(Box::new(CmmType::Int));
+    /// assert_eq!(array.qualified(), CmmType::Array(Box::new(element.clone()), 3));
+    /// assert_eq!(element.clone().qualified(), element);
+    /// ```
+    pub fn qualified(self) -> CmmType {
+        match self {
+            CmmType::Array(element_type, length) => {
+                CmmType::Array(Box::new(element_type.qualified()), length)
+            }
+            CmmType::Const(_) => self,
+            _ => CmmType::Const(Box::new(self)),
+        }
+    }
+
+    /// Returns the type without its top-level `const` qualifier, which is the type of the value
+    /// an object of the type holds.
+    pub fn unqualified(&self) -> &CmmType {
+        match self {
+            CmmType::Const(qualified_type) => qualified_type,
+            _ => self,
+        }
+    }
+
+    /// Returns whether an object of the type cannot be modified, because it is `const` qualified
+    /// or an array of `const` qualified elements.
+    pub fn is_const(&self) -> bool {
+        matches!(self.scalar_type(), CmmType::Const(_))
+    }
+
+    /// Returns whether values of the type are numbers, which are the integer types and `double`.
+    /// A `const` qualified type is arithmetic if the type it qualifies is.
+    pub fn is_arithmetic(&self) -> bool {
+        matches!(
+            self.unqualified(),
+            CmmType::Int
+                | CmmType::UnsignedInt
+                | CmmType::Long
+                | CmmType::UnsignedLong
+                | CmmType::Short
+                | CmmType::Char
+                | CmmType::UnsignedChar
+                | CmmType::Bool
+                | CmmType::Double
+        )
+    }
+
+    /// Returns the type a value of the type is promoted to before an arithmetic operation.
+    ///
+    /// Following the integer promotions of C, `short`, the character types, and `_Bool` are
+    /// promoted to `int`, which holds all of their values. The other types are kept without their
+    /// `const` qualifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// assert_eq!(CmmType::Short.promoted(), CmmType::Int);
+    /// assert_eq!(CmmType::Const(Box::new(CmmType::Long)).promoted(), CmmType::Long);
+    /// ```
+    pub fn promoted(&self) -> CmmType {
+        match self.unqualified() {
+            CmmType::Short | CmmType::Char | CmmType::UnsignedChar | CmmType::Bool => CmmType::Int,
+            unqualified_type => unqualified_type.clone(),
+        }
+    }
+
+    /// Returns the type both arithmetic operands of a binary operator are converted to before the
+    /// operation.
+    ///
+    /// Following the usual arithmetic conversions of C, an operand is converted to `double` if the
+    /// other operand is a `double`. Otherwise both operands are promoted, then the operand of the
+    /// smaller type is converted to the larger type, and an operand of a signed type is converted
+    /// to the unsigned type of the same size.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The type of the other operand, which must be arithmetic like this one.
+    ///
+    /// # Returns
+    ///
+    /// The common `CmmType` of the operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// assert_eq!(CmmType::Char.common_type(&CmmType::Short), CmmType::Int);
+    /// assert_eq!(CmmType::Int.common_type(&CmmType::UnsignedInt), CmmType::UnsignedInt);
+    /// assert_eq!(CmmType::UnsignedInt.common_type(&CmmType::Long), CmmType::Long);
+    /// assert_eq!(CmmType::Long.common_type(&CmmType::UnsignedLong), CmmType::UnsignedLong);
+    /// assert_eq!(CmmType::Long.common_type(&CmmType::Double), CmmType::Double);
+    /// ```
+    pub fn common_type(&self, other: &CmmType) -> CmmType {
+        let (first, second) = (self.promoted(), other.promoted());
+        if first == CmmType::Double || second == CmmType::Double {
+            CmmType::Double
+        } else if first.size() != second.size() {
+            if first.size() > second.size() {
+                first
+            } else {
+                second
+            }
+        } else if matches!(first, CmmType::UnsignedInt | CmmType::UnsignedLong) {
+            first
+        } else {
+            second
+        }
+    }
+
+    /// Returns the size of an object of the type in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains a structure, whose size depends on its members.
+    pub fn size(&self) -> usize {
+        match self {
+            CmmType::Int | CmmType::UnsignedInt => 4,
+            CmmType::Long | CmmType::UnsignedLong | CmmType::Double | CmmType::Pointer(_) => 8,
+            CmmType::Short => 2,
+            CmmType::Char | CmmType::UnsignedChar | CmmType::Bool => 1,
+            CmmType::Array(element_type, length) => element_type.size() * length,
+            CmmType::Struct(tag) => panic!("The size of struct {} depends on its members", tag),
+            CmmType::Const(qualified_type) => qualified_type.size(),
+        }
+    }
+
+    /// Returns the alignment of an object of the type in bytes. An array is aligned like its
+    /// elements, except that the x86-64 System V ABI aligns arrays of 16 bytes or more to 16 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains a structure, like `size`.
+    pub fn alignment(&self) -> usize {
+        match self {
+            CmmType::Array(_, _) if self.size() >= 16 => 16,
+            CmmType::Array(element_type, _) => element_type.alignment(),
+            CmmType::Const(qualified_type) => qualified_type.alignment(),
+            _ => self.size(),
+        }
+    }
+
+    /// Returns the type of the value of an expression of the type. An array decays to a pointer to
+    /// its first element, other types are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// let array = CmmType::Array(Box::new(CmmType::Int), 3);
+    /// assert_eq!(array.decayed(), CmmType::Pointer(Box::new(CmmType::Int)));
+    /// assert_eq!(CmmType::Long.decayed(), CmmType::Long);
+    /// ```
+    pub fn decayed(&self) -> CmmType {
+        match self {
+            CmmType::Array(element_type, _) => CmmType::Pointer(element_type.clone()),
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the type of the innermost elements of an array, which are not arrays themselves.
+    /// Other types are their own scalar type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// let row = CmmType::Array(Box::new(CmmType::Char), 3);
+    /// assert_eq!(CmmType::Array(Box::new(row), 2).scalar_type(), &CmmType::Char);
+    /// assert_eq!(CmmType::Long.scalar_type(), &CmmType::Long);
+    /// ```
+    pub fn scalar_type(&self) -> &CmmType {
+        match self {
+            CmmType::Array(element_type, _) => element_type.scalar_type(),
+            _ => self,
+        }
+    }
+
+    /// Formats a declaration of a name with the type, e.g. `int **p` or `int a[2][3]`, where the
+    /// asterisks of a pointer type and the lengths of an array type belong to the name, and so
+    /// does the `const` of a qualified pointer, as in `int *const p`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmType;
+    /// let pointer = CmmType::Pointer(Box::new(CmmType::Pointer(Box::new(CmmType::Int))));
+    /// assert_eq!(pointer.declare("p"), "int **p");
+    /// assert_eq!(CmmType::Long.declare("x"), "long x");
+    /// let array = CmmType::Array(Box::new(CmmType::Array(Box::new(CmmType::Char), 3)), 2);
+    /// assert_eq!(array.declare("a"), "char a[2][3]");
+    /// let pointer_to_array = CmmType::Pointer(Box::new(CmmType::Array(Box::new(CmmType::Int), 3)));
+    /// assert_eq!(pointer_to_array.declare("p"), "int (*p)[3]");
+    /// let constant = CmmType::Const(Box::new(CmmType::Char));
+    /// let pointer = CmmType::Const(Box::new(CmmType::Pointer(Box::new(constant))));
+    /// assert_eq!(pointer.declare("s"), "const char *const s");
+    /// ```
+    pub fn declare(&self, name: impl fmt::Display) -> String {
+        match self {
+            CmmType::Pointer(referenced_type) => match referenced_type.as_ref() {
+                CmmType::Array(_, _) => referenced_type.declare(format_args!("(*{})", name)),
+                _ => referenced_type.declare(format_args!("*{}", name)),
+            },
+            CmmType::Array(element_type, length) => {
+                element_type.declare(format_args!("{}[{}]", name, length))
+            }
+            CmmType::Const(qualified_type) => match qualified_type.as_ref() {
+                CmmType::Pointer(_) => qualified_type.declare(format_args!("const {}", name)),
+                _ => format!("const {}", qualified_type.declare(name)),
+            },
+            _ => format!("{} {}", self, name),
+        }
+    }
+}
+
+/// Formats the type as in a cast, e.g. `unsigned int`, `const char *`, or `int [3]`.
+impl fmt::Display for CmmType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmmType::Int => write!(f, "int"),
+            CmmType::UnsignedInt => write!(f, "unsigned int"),
+            CmmType::Long => write!(f, "long"),
+            CmmType::UnsignedLong => write!(f, "unsigned long"),
+            CmmType::Short => write!(f, "short"),
+            CmmType::Char => write!(f, "char"),
+            CmmType::UnsignedChar => write!(f, "unsigned char"),
+            CmmType::Bool => write!(f, "_Bool"),
+            CmmType::Double => write!(f, "double"),
+            CmmType::Struct(tag) => write!(f, "struct {}", tag),
+            CmmType::Pointer(_) | CmmType::Array(_, _) | CmmType::Const(_) => {
+                f.write_str(self.declare("").trim_end())
+            }
+        }
+    }
+}
+
+/// Represents an expression that evaluates to a value.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmExpression {
+    /// Represents an integer literal constant.
+    IntegerConstant { value: i32 },
+    /// Represents an integer literal constant of type `long`, either with an `l` suffix or too
+    /// large for an `int`.
+    LongConstant { value: i64 },
+    /// Represents an integer literal constant of type `unsigned int`, either with a `u` suffix or
+    /// hexadecimal or octal and too large for an `int`.
+    UnsignedIntegerConstant { value: u32 },
+    /// Represents an integer literal constant of type `unsigned long`, either with a `u` and an
+    /// `l` suffix, with a `u` suffix and too large for an `unsigned int`, or hexadecimal or octal
+    /// and too large for a `long`.
+    UnsignedLongConstant { value: u64 },
+    /// Represents a floating-point literal constant of type `double`.
+    DoubleConstant { value: f64 },
+    /// Represents a string literal, evaluating to the address of its first character. The value
+    /// holds the bytes of the string without a terminating null byte, and adjacent string literals
+    /// are joined into one.
+    StringLiteral { value: Vec<u8> },
+    /// Represents the value of a variable.
+    Variable { identifier: Symbol },
+    /// Represents a call of a function, whose arguments are evaluated and converted to the types
+    /// of the parameters before the call.
+    FunctionCall {
+        identifier: Symbol,
+        arguments: Vec<ExprId>,
+    },
+    /// Represents an explicit conversion of the value of an expression to another type, such as
+    /// `(long) x`.
+    Cast {
+        target_type: CmmType,
+        expression: ExprId,
+    },
+    /// Represents `&x`, the address of the object an lvalue designates, whose type is a pointer to
+    /// the type of the lvalue.
+    AddressOf { expression: ExprId },
+    /// Represents `*p`, the object a pointer refers to. It is an lvalue, so it can be read or
+    /// assigned to.
+    Dereference { expression: ExprId },
+    /// Represents `a[i]`, the element at an index of an array or past a pointer, which is the same
+    /// as `*(a + i)`. Like in C, either operand may be the index, so `i[a]` is `a[i]` too.
+    Subscript { array: ExprId, index: ExprId },
+    /// Represents `s.member`, a member of a structure. It is an lvalue like the structure.
+    Member { structure: ExprId, member: Symbol },
+    /// Represents `p->member`, a member of the structure a pointer refers to, which is the same as
+    /// `(*p).member`.
+    PointerMember { pointer: ExprId, member: Symbol },
+    Unary {
+        operator: CmmUnaryOperator,
+        expression: ExprId,
+    },
+    Binary {
+        operator: CmmBinaryOperator,
+        left: ExprId,
+        right: ExprId,
+    },
+    /// Represents an assignment, which stores the value in the target and evaluates to it.
+    Assignment { target: ExprId, value: ExprId },
+    /// Represents a compound assignment such as `x += 2`, which applies the operator to the target
+    /// and the value, stores the result in the target, and evaluates to it.
+    CompoundAssignment {
+        operator: CmmBinaryOperator,
+        target: ExprId,
+        value: ExprId,
+    },
+}
+
+/// Identifies an expression stored in an `ExpressionArena`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ExprId(u32);
+
+impl ExprId {
+    /// Returns the position of the expression in its arena, in allocation order.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Stores the expressions of a program contiguously.
+///
+/// Expressions refer to their operands by `ExprId` instead of owning them, so a deep expression
+/// tree takes a single growing allocation instead of one allocation per node. Operands are always
+/// allocated before the expressions that use them.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ExpressionArena {
+    expressions: Vec<CmmExpression>,
+    /// The location of every expression, indexed by `ExprId`.
+    spans: Vec<Option<Span>>,
+}
+
+impl ExpressionArena {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        ExpressionArena::default()
+    }
+
+    /// Stores an expression in the arena.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The expression to store. Its operands must already be stored in this arena.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId` referring to the stored expression.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::{CmmExpression, CmmUnaryOperator, ExpressionArena};
+    /// let mut expressions = ExpressionArena::new();
+    /// let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+    /// let negation = expressions.alloc(CmmExpression::Unary {
+    ///     operator: CmmUnaryOperator::Negate,
+    ///     expression: constant,
+    /// });
+    /// assert_eq!(expressions[constant], CmmExpression::IntegerConstant { value: 1 });
+    /// assert_eq!(expressions.iter().last().map(|(id, _)| id), Some(negation));
+    /// ```
+    pub fn alloc(&mut self, expression: CmmExpression) -> ExprId {
+        self.alloc_at(expression, None)
+    }
+
+    /// Stores an expression parsed from source code in the arena, see `alloc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The expression to store.
+    /// * `span`: The location of the expression, or `None` if it is unknown.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId` referring to the stored expression.
+    pub fn alloc_at(&mut self, expression: CmmExpression, span: Option<Span>) -> ExprId {
+        let id = ExprId(self.expressions.len() as u32);
+        self.expressions.push(expression);
+        self.spans.push(span);
+        id
+    }
+
+    /// Returns the expression an `ExprId` refers to.
+    pub fn get(&self, id: ExprId) -> &CmmExpression {
+        &self.expressions[id.0 as usize]
+    }
+
+    /// Returns the location of the expression an `ExprId` refers to, or `None` if it was not
+    /// parsed from source code. The span of a parenthesized expression excludes the parentheses.
+    pub fn span(&self, id: ExprId) -> Option<Span> {
+        self.spans[id.0 as usize]
+    }
+
+    /// Returns the number of stored expressions.
+    pub fn len(&self) -> usize {
+        self.expressions.len()
+    }
+
+    /// Returns `true` if the arena stores no expressions.
+    pub fn is_empty(&self) -> bool {
+        self.expressions.is_empty()
+    }
+
+    /// Iterates over the stored expressions in allocation order, operands before their users.
+    pub fn iter(&self) -> impl Iterator<Item = (ExprId, &CmmExpression)> {
+        self.expressions
+            .iter()
+            .enumerate()
+            .map(|(index, expression)| (ExprId(index as u32), expression))
+    }
+}
+
+impl Index<ExprId> for ExpressionArena {
+    type Output = CmmExpression;
+
+    fn index(&self, id: ExprId) -> &CmmExpression {
+        self.get(id)
+    }
+}
+
+/// Represents a unary operator.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmUnaryOperator {
+    /// `+x`, which applies the integer promotions to its arithmetic operand and is otherwise a
+    /// no-op.
+    Plus,
+    Complement,
+    Negate,
+    Not,
+    /// `++x`, which increments the operand variable and evaluates to its new value.
+    PrefixIncrement,
+    /// `--x`, which decrements the operand variable and evaluates to its new value.
+    PrefixDecrement,
+    /// `x++`, which increments the operand variable and evaluates to its old value.
+    PostfixIncrement,
+    /// `x--`, which decrements the operand variable and evaluates to its old value.
+    PostfixDecrement,
+}
+
+/// Represents a binary operator.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CmmBinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    LeftShift,
+    RightShift,
+    And,
+    Or,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LessThan,
+    GreaterThanEqual,
+    LessThanEqual,
+}
+
+impl CmmBinaryOperator {
+    /// Returns the precedence of the operator, a higher precedence binds tighter.
+    ///
+    /// The values match the precedences the parser assigns to the operator tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::cmm_ast::CmmBinaryOperator;
+    /// assert!(CmmBinaryOperator::Multiply.precedence() > CmmBinaryOperator::Add.precedence());
+    /// ```
+    pub fn precedence(&self) -> u32 {
+        match self {
+            CmmBinaryOperator::Multiply
+            | CmmBinaryOperator::Divide
+            | CmmBinaryOperator::Remainder => 50,
+            CmmBinaryOperator::Add | CmmBinaryOperator::Subtract => 45,
+            CmmBinaryOperator::LeftShift | CmmBinaryOperator::RightShift => 40,
+            CmmBinaryOperator::LessThan
+            | CmmBinaryOperator::GreaterThan
+            | CmmBinaryOperator::LessThanEqual
+            | CmmBinaryOperator::GreaterThanEqual => 35,
+            CmmBinaryOperator::Equal | CmmBinaryOperator::NotEqual => 30,
+            CmmBinaryOperator::BitwiseAnd => 25,
+            CmmBinaryOperator::BitwiseXor => 20,
+            CmmBinaryOperator::BitwiseOr => 15,
+            CmmBinaryOperator::And => 10,
+            CmmBinaryOperator::Or => 5,
+        }
+    }
+}
+
+/// Formats the AST as C-- source code, see `printer::print_ast`.
+impl fmt::Display for CmmAst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&printer::print_ast(self))
+    }
+}
+
+impl fmt::Debug for CmmAst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmmAst::Program {
+                structs,
+                enums,
+                variables,
+                functions,
+                expressions,
+            } => f
+                .debug_struct("Program")
+                .field(
+                    "structs",
+                    &structs.iter().map(UnlocatedStruct).collect::<Vec<_>>(),
+                )
+                .field(
+                    "enums",
+                    &enums
+                        .iter()
+                        .map(|declaration| ResolvedEnum {
+                            declaration,
+                            expressions,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .field(
+                    "variables",
+                    &variables
+                        .iter()
+                        .map(|declaration| ResolvedDeclaration {
+                            declaration,
+                            expressions,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .field(
+                    "functions",
+                    &functions
+                        .iter()
+                        .map(|function| ResolvedFunction {
+                            function,
+                            expressions,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .finish(),
+        }
+    }
+}
+
+/// Formats a structure declaration without its span.
+struct UnlocatedStruct<'a>(&'a CmmStructDeclaration);
+
+impl fmt::Debug for UnlocatedStruct<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let CmmStructDeclaration::Struct { tag, members, .. } = self.0;
+        f.debug_struct("Struct")
+            .field("tag", tag)
+            .field("members", members)
+            .finish()
+    }
+}
+
+/// Formats an enumeration with the values of its enumerators resolved from the arena.
+struct ResolvedEnum<'a> {
+    declaration: &'a CmmEnumDeclaration,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedEnum<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let CmmEnumDeclaration::Enum {
+            tag, enumerators, ..
+        } = self.declaration;
+        let enumerators = enumerators
+            .iter()
+            .map(|CmmEnumerator::Enumerator { identifier, value }| {
+                (
+                    identifier,
+                    value.map(|id| ResolvedExpression {
+                        id,
+                        expressions: self.expressions,
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        f.debug_struct("Enum")
+            .field("tag", tag)
+            .field("enumerators", &enumerators)
+            .finish()
+    }
+}
+
+/// Formats a function with its expressions resolved from the arena.
+struct ResolvedFunction<'a> {
+    function: &'a CmmFunction,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedFunction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.function {
+            CmmFunction::Function {
+                identifier,
+                storage_class,
+                return_type,
+                parameters,
+                variadic,
+                body,
+                ..
+            } => f
+                .debug_struct("Function")
+                .field("identifier", identifier)
+                .field("storage_class", storage_class)
+                .field("return_type", return_type)
+                .field("parameters", parameters)
+                .field("variadic", variadic)
+                .field(
+                    "body",
+                    &body.as_ref().map(|items| {
+                        items
+                            .iter()
+                            .map(|item| ResolvedBlockItem {
+                                item,
+                                expressions: self.expressions,
+                            })
+                            .collect::<Vec<_>>()
+                    }),
+                )
+                .finish(),
+        }
+    }
+}
+
+/// Formats a block item with its expressions resolved from the arena.
+struct ResolvedBlockItem<'a> {
+    item: &'a CmmBlockItem,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedBlockItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.item {
+            CmmBlockItem::Declaration(declaration) => f
+                .debug_tuple("Declaration")
+                .field(&ResolvedDeclaration {
+                    declaration,
+                    expressions: self.expressions,
+                })
+                .finish(),
+            CmmBlockItem::Statement(statement) => f
+                .debug_tuple("Statement")
+                .field(&ResolvedStatement {
+                    statement,
+                    expressions: self.expressions,
+                })
+                .finish(),
+        }
+    }
+}
+
+/// Formats a statement with its expressions resolved from the arena.
+struct ResolvedStatement<'a> {
+    statement: &'a CmmStatement,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedStatement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let resolve_expression = |id: &ExprId| ResolvedExpression {
+            id: *id,
+            expressions: self.expressions,
+        };
+        let expressions = self.expressions;
+        let resolve_statement = |statement| ResolvedStatement {
+            statement,
+            expressions,
+        };
+        match self.statement {
+            CmmStatement::Return { expression, .. } => f
+                .debug_struct("Return")
+                .field("expression", &expression.as_ref().map(resolve_expression))
+                .finish(),
+            CmmStatement::Expression { expression, .. } => f
+                .debug_struct("Expression")
+                .field("expression", &resolve_expression(expression))
+                .finish(),
+            CmmStatement::Null { .. } => f.write_str("Null"),
+            CmmStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => f
+                .debug_struct("If")
+                .field("condition", &resolve_expression(condition))
+                .field("then_branch", &resolve_statement(then_branch))
+                .field(
+                    "else_branch",
+                    &else_branch.as_deref().map(resolve_statement),
+                )
+                .finish(),
+            CmmStatement::While {
+                condition, body, ..
+            } => f
+                .debug_struct("While")
+                .field("condition", &resolve_expression(condition))
+                .field("body", &resolve_statement(body))
+                .finish(),
+            CmmStatement::DoWhile {
+                body, condition, ..
+            } => f
+                .debug_struct("DoWhile")
+                .field("body", &resolve_statement(body))
+                .field("condition", &resolve_expression(condition))
+                .finish(),
+            CmmStatement::For {
+                init,
+                condition,
+                post,
+                body,
+                ..
+            } => f
+                .debug_struct("For")
+                .field(
+                    "init",
+                    &ResolvedForInit {
+                        init,
+                        expressions: self.expressions,
+                    },
+                )
+                .field("condition", &condition.as_ref().map(resolve_expression))
+                .field("post", &post.as_ref().map(resolve_expression))
+                .field("body", &resolve_statement(body))
+                .finish(),
+            CmmStatement::Break { .. } => f.write_str("Break"),
+            CmmStatement::Continue { .. } => f.write_str("Continue"),
+        }
+    }
+}
+
+/// Formats the initial clause of a for loop with its expressions resolved from the arena.
+struct ResolvedForInit<'a> {
+    init: &'a CmmForInit,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedForInit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.init {
+            CmmForInit::Declaration(declaration) => f
+                .debug_tuple("Declaration")
+                .field(&ResolvedDeclaration {
+                    declaration,
+                    expressions: self.expressions,
+                })
+                .finish(),
+            CmmForInit::Expression(expression) => f
+                .debug_tuple("Expression")
+                .field(&expression.map(|id| ResolvedExpression {
+                    id,
+                    expressions: self.expressions,
+                }))
+                .finish(),
+        }
+    }
+}
+
+/// Formats a declaration with its initializer resolved from the arena.
+struct ResolvedDeclaration<'a> {
+    declaration: &'a CmmDeclaration,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedDeclaration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.declaration {
+            CmmDeclaration::Declaration {
+                identifier,
+                storage_class,
+                variable_type,
+                initializer,
+                ..
+            } => f
+                .debug_struct("Declaration")
+                .field("identifier", identifier)
+                .field("storage_class", storage_class)
+                .field("variable_type", variable_type)
+                .field(
+                    "initializer",
+                    &initializer.as_ref().map(|initializer| ResolvedInitializer {
+                        initializer,
+                        expressions: self.expressions,
+                    }),
+                )
+                .finish(),
+        }
+    }
+}
+
+/// Formats an initializer with its expressions resolved from the arena.
+struct ResolvedInitializer<'a> {
+    initializer: &'a CmmInitializer,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedInitializer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.initializer {
+            CmmInitializer::Single(id) => f
+                .debug_tuple("Single")
+                .field(&ResolvedExpression {
+                    id: *id,
+                    expressions: self.expressions,
+                })
+                .finish(),
+            CmmInitializer::Compound(initializers) => f
+                .debug_tuple("Compound")
+                .field(
+                    &initializers
+                        .iter()
+                        .map(|initializer| ResolvedInitializer {
+                            initializer,
+                            expressions: self.expressions,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .finish(),
+        }
+    }
+}
+
+/// Formats an expression and its operands resolved from the arena.
+struct ResolvedExpression<'a> {
+    id: ExprId,
+    expressions: &'a ExpressionArena,
+}
+
+impl fmt::Debug for ResolvedExpression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let resolve = |id: &ExprId| ResolvedExpression {
+            id: *id,
+            expressions: self.expressions,
+        };
+        match &self.expressions[self.id] {
+            CmmExpression::IntegerConstant { value } => f
+                .debug_struct("IntegerConstant")
+                .field("value", value)
+                .finish(),
+            CmmExpression::LongConstant { value } => f
+                .debug_struct("LongConstant")
+                .field("value", value)
+                .finish(),
+            CmmExpression::UnsignedIntegerConstant { value } => f
+                .debug_struct("UnsignedIntegerConstant")
+                .field("value", value)
+                .finish(),
+            CmmExpression::UnsignedLongConstant { value } => f
+                .debug_struct("UnsignedLongConstant")
+                .field("value", value)
+                .finish(),
+            CmmExpression::DoubleConstant { value } => f
+                .debug_struct("DoubleConstant")
+                .field("value", value)
+                .finish(),
+            CmmExpression::StringLiteral { value } => f
+                .debug_struct("StringLiteral")
+                .field(
+                    "value",
+                    &format_args!("{}", printer::print_string_literal(value)),
+                )
+                .finish(),
+            CmmExpression::Variable { identifier } => f
+                .debug_struct("Variable")
+                .field("identifier", identifier)
+                .finish(),
+            CmmExpression::FunctionCall {
+                identifier,
+                arguments,
+            } => f
+                .debug_struct("FunctionCall")
+                .field("identifier", identifier)
+                .field(
+                    "arguments",
+                    &arguments.iter().map(resolve).collect::<Vec<_>>(),
+                )
+                .finish(),
+            CmmExpression::Cast {
+                target_type,
+                expression,
+            } => f
+                .debug_struct("Cast")
+                .field("target_type", target_type)
+                .field("expression", &resolve(expression))
+                .finish(),
+            CmmExpression::AddressOf { expression } => f
+                .debug_struct("AddressOf")
+                .field("expression", &resolve(expression))
+                .finish(),
+            CmmExpression::Dereference { expression } => f
+                .debug_struct("Dereference")
+                .field("expression", &resolve(expression))
+                .finish(),
+            CmmExpression::Subscript { array, index } => f
+                .debug_struct("Subscript")
+                .field("array", &resolve(array))
+                .field("index", &resolve(index))
+                .finish(),
+            CmmExpression::Member { structure, member } => f
+                .debug_struct("Member")
+                .field("structure", &resolve(structure))
+                .field("member", member)
+                .finish(),
+            CmmExpression::PointerMember { pointer, member } => f
+                .debug_struct("PointerMember")
+                .field("pointer", &resolve(pointer))
+                .field("member", member)
+                .finish(),
+            CmmExpression::Unary {
+                operator,
+                expression,
+            } => f
+                .debug_struct("Unary")
+                .field("operator", operator)
+                .field("expression", &resolve(expression))
+                .finish(),
+            CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            } => f
+                .debug_struct("Binary")
+                .field("operator", operator)
+                .field("left", &resolve(left))
+                .field("right", &resolve(right))
+                .finish(),
+            CmmExpression::Assignment { target, value } => f
+                .debug_struct("Assignment")
+                .field("target", &resolve(target))
+                .field("value", &resolve(value))
+                .finish(),
+            CmmExpression::CompoundAssignment {
+                operator,
+                target,
+                value,
+            } => f
+                .debug_struct("CompoundAssignment")
+                .field("operator", operator)
+                .field("target", &resolve(target))
+                .field("value", &resolve(value))
+                .finish(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! The data types shared by the stages of the C-- compiler: the C-- AST, the TACKY IR, the
+//! assembly AST, the interned symbols they are built from, and the source locations of the C--
+//! AST.
+//!
+//! The `cmm` crate re-exports these modules at their pipeline locations, e.g.
+//! `cmm::compiler::parser::cmm_ast`.
+
+pub mod assembly_ast;
+pub mod cmm_ast;
+pub mod printer;
+pub mod span;
+pub mod symbol;
+pub mod tacky_ast;
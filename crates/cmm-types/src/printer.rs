@@ -0,0 +1,2478 @@
+use crate::cmm_ast::{
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmEnumDeclaration, CmmEnumerator,
+    CmmExpression, CmmForInit, CmmFunction, CmmInitializer, CmmMember, CmmParameter, CmmStatement,
+    CmmStructDeclaration, CmmUnaryOperator, ExprId, ExpressionArena,
+};
+
+/// The string used to indent statements inside a function body.
+const INDENT: &str = "    ";
+
+/// Pretty-prints a C-- AST back into C-- source code.
+///
+/// Parentheses are only emitted where the operator precedence or associativity requires them, so
+/// parsing the printed source code yields the original AST. The branches of an if statement and the
+/// body of a loop are indented on their own lines, and an `else if` chain stays on one indentation
+/// level.
+///
+/// An `if` without `else` ending the then branch of an `if` with `else` is printed without braces,
+/// so it reads back with the `else` attached to the inner `if`. The parser never produces such an
+/// AST.
+///
+/// Function declarations are separated by a blank line, and prototypes end in a semicolon.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the C-- AST to print.
+///
+/// # Returns
+///
+/// A `String` containing the C-- source code.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm_types::symbol::Symbol;
+/// # use cmm_types::cmm_ast::{CmmAst, CmmBlockItem, CmmFunction, CmmStatement, CmmType, CmmExpression, ExpressionArena};
+/// # use cmm_types::printer::print_ast;
+/// let mut expressions = ExpressionArena::new();
+/// let expression = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+/// let cmm_ast = CmmAst::Program {
+///     structs: vec![],
+///     enums: vec![],
+///     variables: vec![],
+///     functions: vec![CmmFunction::Function {
+///         identifier: Symbol::from("main"),
+///         storage_class: None,
+///         return_type: Some(CmmType::Int),
+///         parameters: vec![],
+///         variadic: false,
+///         body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return { expression: Some(expression), span: None })]),
+///         span: None,
+///     }],
+///     expressions,
+/// };
+/// assert_eq!(print_ast(&cmm_ast), "int main(void) {\n    return 2;\n}\n");
+/// ```
+pub fn print_ast(cmm_ast: &CmmAst) -> String {
+    match cmm_ast {
+        CmmAst::Program {
+            structs,
+            enums,
+            variables,
+            functions,
+            expressions,
+        } => {
+            structs
+                .iter()
+                .map(print_struct)
+                .chain(
+                    enums
+                        .iter()
+                        .map(|declaration| print_enum(declaration, expressions)),
+                )
+                .chain(variables.iter().map(|declaration| {
+                    format!("{};\n", print_declaration(declaration, expressions))
+                }))
+                .chain(
+                    functions
+                        .iter()
+                        .map(|function| print_function(function, expressions)),
+                )
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+/// Pretty-prints a C-- structure declaration with a member on every line.
+fn print_struct(declaration: &CmmStructDeclaration) -> String {
+    let CmmStructDeclaration::Struct { tag, members, .. } = declaration;
+    let printed_members = members
+        .iter()
+        .map(|member| format!("{}{};\n", INDENT, print_member(member)))
+        .collect::<String>();
+    format!("struct {} {{\n{}}};\n", tag, printed_members)
+}
+
+/// Pretty-prints a C-- enumeration declaration on a single line, e.g.
+/// `enum color { RED, GREEN = 5, BLUE };`.
+fn print_enum(declaration: &CmmEnumDeclaration, expressions: &ExpressionArena) -> String {
+    let CmmEnumDeclaration::Enum {
+        tag, enumerators, ..
+    } = declaration;
+    let printed_enumerators = enumerators
+        .iter()
+        .map(
+            |CmmEnumerator::Enumerator { identifier, value }| match value {
+                Some(value) => {
+                    format!("{} = {}", identifier, print_expression(expressions, *value))
+                }
+                None => identifier.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+    match tag {
+        Some(tag) => format!("enum {} {{ {} }};\n", tag, printed_enumerators),
+        None => format!("enum {{ {} }};\n", printed_enumerators),
+    }
+}
+
+/// Pretty-prints the declaration of a structure member without the terminating semicolon.
+fn print_member(member: &CmmMember) -> String {
+    let CmmMember::Member {
+        identifier,
+        member_type,
+    } = member;
+    member_type.declare(identifier)
+}
+
+/// Pretty-prints a C-- function declaration or definition.
+fn print_function(function: &CmmFunction, expressions: &ExpressionArena) -> String {
+    match function {
+        CmmFunction::Function {
+            identifier,
+            storage_class,
+            return_type,
+            parameters,
+            variadic,
+            body,
+            ..
+        } => {
+            let printed_parameters = if parameters.is_empty() && !variadic {
+                "void".to_string()
+            } else {
+                print_parameters(parameters, *variadic)
+            };
+            let printed_return_type = return_type
+                .as_ref()
+                .map_or_else(|| "void".to_string(), ToString::to_string);
+            let specifiers = match storage_class {
+                Some(storage_class) => format!("{} {}", storage_class, printed_return_type),
+                None => printed_return_type,
+            };
+            match body {
+                Some(body) => format!(
+                    "{} {}({}) {{\n{}}}\n",
+                    specifiers,
+                    identifier,
+                    printed_parameters,
+                    body.iter()
+                        .map(|item| format!("{}\n", print_block_item(item, expressions, 1)))
+                        .collect::<String>()
+                ),
+                None => format!("{} {}({});\n", specifiers, identifier, printed_parameters),
+            }
+        }
+    }
+}
+
+/// Pretty-prints a C-- block item, indenting every line by `depth` levels.
+fn print_block_item(item: &CmmBlockItem, expressions: &ExpressionArena, depth: usize) -> String {
+    match item {
+        CmmBlockItem::Declaration(declaration) => format!(
+            "{}{};",
+            INDENT.repeat(depth),
+            print_declaration(declaration, expressions)
+        ),
+        CmmBlockItem::Statement(statement) => print_statement(statement, expressions, depth),
+    }
+}
+
+/// Pretty-prints the parameters of a function declaration, separated by commas, followed by an
+/// ellipsis if the function is variadic.
+fn print_parameters(parameters: &[CmmParameter], variadic: bool) -> String {
+    parameters
+        .iter()
+        .map(
+            |CmmParameter::Parameter {
+                 identifier,
+                 parameter_type,
+             }| parameter_type.declare(identifier),
+        )
+        .chain(variadic.then(|| "...".to_string()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Pretty-prints a C-- statement, indenting every line by `depth` levels.
+fn print_statement(
+    statement: &CmmStatement,
+    expressions: &ExpressionArena,
+    depth: usize,
+) -> String {
+    let indentation = INDENT.repeat(depth);
+    match statement {
+        CmmStatement::Return {
+            expression: Some(expression),
+            ..
+        } => {
+            format!(
+                "{}return {};",
+                indentation,
+                print_expression(expressions, *expression)
+            )
+        }
+        CmmStatement::Return {
+            expression: None, ..
+        } => format!("{}return;", indentation),
+        CmmStatement::Expression { expression, .. } => format!(
+            "{}{};",
+            indentation,
+            print_expression(expressions, *expression)
+        ),
+        CmmStatement::Null { .. } => format!("{};", indentation),
+        CmmStatement::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            let mut source_code = format!(
+                "{}if ({})\n{}",
+                indentation,
+                print_expression(expressions, *condition),
+                print_statement(then_branch, expressions, depth + 1)
+            );
+            match else_branch.as_deref() {
+                Some(else_if @ CmmStatement::If { .. }) => {
+                    let else_if = print_statement(else_if, expressions, depth);
+                    source_code.push_str(&format!(
+                        "\n{}else {}",
+                        indentation,
+                        else_if.trim_start()
+                    ));
+                }
+                Some(else_branch) => source_code.push_str(&format!(
+                    "\n{}else\n{}",
+                    indentation,
+                    print_statement(else_branch, expressions, depth + 1)
+                )),
+                None => {}
+            }
+            source_code
+        }
+        CmmStatement::While {
+            condition, body, ..
+        } => format!(
+            "{}while ({})\n{}",
+            indentation,
+            print_expression(expressions, *condition),
+            print_statement(body, expressions, depth + 1)
+        ),
+        CmmStatement::DoWhile {
+            body, condition, ..
+        } => format!(
+            "{}do\n{}\n{}while ({});",
+            indentation,
+            print_statement(body, expressions, depth + 1),
+            indentation,
+            print_expression(expressions, *condition)
+        ),
+        CmmStatement::For {
+            init,
+            condition,
+            post,
+            body,
+            ..
+        } => {
+            let init = match init {
+                CmmForInit::Declaration(declaration) => print_declaration(declaration, expressions),
+                CmmForInit::Expression(expression) => expression
+                    .map(|expression| print_expression(expressions, expression))
+                    .unwrap_or_default(),
+            };
+            let print_clause = |clause: &Option<ExprId>| {
+                clause
+                    .map(|expression| format!(" {}", print_expression(expressions, expression)))
+                    .unwrap_or_default()
+            };
+            format!(
+                "{}for ({};{};{})\n{}",
+                indentation,
+                init,
+                print_clause(condition),
+                print_clause(post),
+                print_statement(body, expressions, depth + 1)
+            )
+        }
+        CmmStatement::Break { .. } => format!("{}break;", indentation),
+        CmmStatement::Continue { .. } => format!("{}continue;", indentation),
+    }
+}
+
+/// Pretty-prints a C-- declaration without the terminating semicolon.
+fn print_declaration(declaration: &CmmDeclaration, expressions: &ExpressionArena) -> String {
+    let CmmDeclaration::Declaration {
+        identifier,
+        storage_class,
+        variable_type,
+        initializer,
+        ..
+    } = declaration;
+    let declarator = match storage_class {
+        Some(storage_class) => format!("{} {}", storage_class, variable_type.declare(identifier)),
+        None => variable_type.declare(identifier),
+    };
+    match initializer {
+        Some(initializer) => format!(
+            "{} = {}",
+            declarator,
+            print_initializer(initializer, expressions)
+        ),
+        None => declarator,
+    }
+}
+
+/// Pretty-prints the initializer of a C-- declaration, with the elements of a compound
+/// initializer in braces.
+fn print_initializer(initializer: &CmmInitializer, expressions: &ExpressionArena) -> String {
+    match initializer {
+        CmmInitializer::Single(expression) => print_expression(expressions, *expression),
+        CmmInitializer::Compound(initializers) => format!(
+            "{{{}}}",
+            initializers
+                .iter()
+                .map(|initializer| print_initializer(initializer, expressions))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Pretty-prints a C-- expression with minimal parentheses.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the expression and its operands.
+/// * `expression`: The `ExprId` of the C-- expression to print.
+///
+/// # Returns
+///
+/// A `String` containing the expression source code.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm_types::cmm_ast::{CmmBinaryOperator, CmmExpression, ExpressionArena};
+/// # use cmm_types::printer::print_expression;
+/// let mut expressions = ExpressionArena::new();
+/// let one = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+/// let two = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+/// let sum = expressions.alloc(CmmExpression::Binary {
+///     operator: CmmBinaryOperator::Add,
+///     left: one,
+///     right: two,
+/// });
+/// let three = expressions.alloc(CmmExpression::IntegerConstant { value: 3 });
+/// let product = expressions.alloc(CmmExpression::Binary {
+///     operator: CmmBinaryOperator::Multiply,
+///     left: sum,
+///     right: three,
+/// });
+/// assert_eq!(print_expression(&expressions, product), "(1 + 2) * 3");
+/// ```
+pub fn print_expression(expressions: &ExpressionArena, expression: ExprId) -> String {
+    match &expressions[expression] {
+        CmmExpression::IntegerConstant { value } => value.to_string(),
+        CmmExpression::LongConstant { value } => format!("{}L", value),
+        CmmExpression::UnsignedIntegerConstant { value } => format!("{}U", value),
+        CmmExpression::UnsignedLongConstant { value } => format!("{}UL", value),
+        CmmExpression::DoubleConstant { value } => format!("{:?}", value),
+        CmmExpression::StringLiteral { value } => print_string_literal(value),
+        CmmExpression::Variable { identifier } => identifier.to_string(),
+        CmmExpression::FunctionCall {
+            identifier,
+            arguments,
+        } => format!(
+            "{}({})",
+            identifier,
+            arguments
+                .iter()
+                .map(|argument| print_expression(expressions, *argument))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        CmmExpression::Cast {
+            target_type,
+            expression: operand,
+        } => format!(
+            "({}) {}",
+            target_type,
+            print_prefix_operand(expressions, *operand)
+        ),
+        CmmExpression::Unary {
+            operator:
+                operator @ (CmmUnaryOperator::PostfixIncrement | CmmUnaryOperator::PostfixDecrement),
+            expression: operand,
+        } => format!(
+            "{}{}",
+            print_postfix_operand(expressions, *operand),
+            unary_operator_symbol(operator)
+        ),
+        CmmExpression::Unary {
+            operator,
+            expression: operand,
+        } => {
+            let printed_operand = print_prefix_operand(expressions, *operand);
+            // Two consecutive hyphens or plus signs would be tokenized as a decrement or an
+            // increment operator.
+            let symbol = unary_operator_symbol(operator);
+            let separator = match (symbol.chars().last(), printed_operand.chars().next()) {
+                (Some('-'), Some('-')) | (Some('+'), Some('+')) => " ",
+                _ => "",
+            };
+            format!("{}{}{}", symbol, separator, printed_operand)
+        }
+        CmmExpression::AddressOf {
+            expression: operand,
+        } => {
+            // Two consecutive ampersands would be tokenized as a logical and operator.
+            let printed_operand = print_prefix_operand(expressions, *operand);
+            let separator = if printed_operand.starts_with('&') {
+                " "
+            } else {
+                ""
+            };
+            format!("&{}{}", separator, printed_operand)
+        }
+        CmmExpression::Dereference {
+            expression: operand,
+        } => format!("*{}", print_prefix_operand(expressions, *operand)),
+        CmmExpression::Subscript { array, index } => format!(
+            "{}[{}]",
+            print_postfix_operand(expressions, *array),
+            print_expression(expressions, *index)
+        ),
+        // A period after a constant would be lexed as part of a floating-point constant.
+        CmmExpression::Member { structure, member } => match &expressions[*structure] {
+            CmmExpression::IntegerConstant { .. }
+            | CmmExpression::LongConstant { .. }
+            | CmmExpression::UnsignedIntegerConstant { .. }
+            | CmmExpression::UnsignedLongConstant { .. }
+            | CmmExpression::DoubleConstant { .. } => {
+                format!("({}).{}", print_expression(expressions, *structure), member)
+            }
+            _ => format!(
+                "{}.{}",
+                print_postfix_operand(expressions, *structure),
+                member
+            ),
+        },
+        CmmExpression::PointerMember { pointer, member } => format!(
+            "{}->{}",
+            print_postfix_operand(expressions, *pointer),
+            member
+        ),
+        CmmExpression::Binary {
+            operator,
+            left,
+            right,
+        } => {
+            let precedence = operator.precedence();
+            // Binary operators are left-associative, so only the right operand needs parentheses
+            // when it has the same precedence as the parent operator.
+            let printed_left = print_operand(expressions, *left, |operand_precedence| {
+                operand_precedence < precedence
+            });
+            let printed_right = print_operand(expressions, *right, |operand_precedence| {
+                operand_precedence <= precedence
+            });
+            format!(
+                "{} {} {}",
+                printed_left,
+                binary_operator_symbol(operator),
+                printed_right
+            )
+        }
+        // Assignments bind weakest and are right-associative, so only an invalid target, such as a
+        // binary expression, needs parentheses.
+        CmmExpression::Assignment { target, value } => format!(
+            "{} = {}",
+            print_operand(expressions, *target, |_| true),
+            print_expression(expressions, *value)
+        ),
+        CmmExpression::CompoundAssignment {
+            operator,
+            target,
+            value,
+        } => format!(
+            "{} {}= {}",
+            print_operand(expressions, *target, |_| true),
+            binary_operator_symbol(operator),
+            print_expression(expressions, *value)
+        ),
+    }
+}
+
+/// Prints the bytes of a string as a C string literal.
+///
+/// Printable ASCII characters are kept as they are, except for the double quote and the backslash,
+/// which are escaped. Newlines and tabs get their escape sequences, and every other byte is printed
+/// as a three-digit octal escape sequence, so that a following digit cannot extend it. The GNU
+/// assembler and QBE read the same syntax, so the output also serves as their string directives.
+///
+/// # Arguments
+///
+/// * `value`: The bytes of the string.
+///
+/// # Returns
+///
+/// A `String` containing the string literal, including its quotes.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm_types::printer::print_string_literal;
+/// assert_eq!(print_string_literal(b"say \"hi\"\n"), r#""say \"hi\"\n""#);
+/// assert_eq!(print_string_literal(b"\x07\x001"), r#""\007\0001""#);
+/// ```
+pub fn print_string_literal(value: &[u8]) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for &byte in value {
+        match byte {
+            b'"' => literal.push_str("\\\""),
+            b'\\' => literal.push_str("\\\\"),
+            b'\n' => literal.push_str("\\n"),
+            b'\t' => literal.push_str("\\t"),
+            b' '..=b'~' => literal.push(char::from(byte)),
+            _ => literal.push_str(&format!("\\{:03o}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Pretty-prints the operand of a prefix unary operator or a cast, parenthesizing binary operands
+/// and assignments.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the operand.
+/// * `operand`: The `ExprId` of the operand expression.
+///
+/// # Returns
+///
+/// A `String` containing the operand source code.
+fn print_prefix_operand(expressions: &ExpressionArena, operand: ExprId) -> String {
+    match &expressions[operand] {
+        CmmExpression::Binary { .. }
+        | CmmExpression::Assignment { .. }
+        | CmmExpression::CompoundAssignment { .. } => {
+            format!("({})", print_expression(expressions, operand))
+        }
+        _ => print_expression(expressions, operand),
+    }
+}
+
+/// Pretty-prints the operand of a postfix operator or a subscript, parenthesizing every operand
+/// that is not a non-negative constant, a variable, or a postfix expression itself.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the operand.
+/// * `operand`: The `ExprId` of the operand expression.
+///
+/// # Returns
+///
+/// A `String` containing the operand source code.
+fn print_postfix_operand(expressions: &ExpressionArena, operand: ExprId) -> String {
+    match &expressions[operand] {
+        CmmExpression::IntegerConstant { value } if *value >= 0 => {
+            print_expression(expressions, operand)
+        }
+        CmmExpression::LongConstant { value } if *value >= 0 => {
+            print_expression(expressions, operand)
+        }
+        CmmExpression::DoubleConstant { value } if *value >= 0.0 => {
+            print_expression(expressions, operand)
+        }
+        CmmExpression::UnsignedIntegerConstant { .. }
+        | CmmExpression::UnsignedLongConstant { .. }
+        | CmmExpression::Variable { .. }
+        | CmmExpression::Subscript { .. }
+        | CmmExpression::Member { .. }
+        | CmmExpression::PointerMember { .. }
+        | CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixIncrement | CmmUnaryOperator::PostfixDecrement,
+            ..
+        } => print_expression(expressions, operand),
+        _ => format!("({})", print_expression(expressions, operand)),
+    }
+}
+
+/// Pretty-prints the operand of a binary expression, parenthesizing binary operands when needed and
+/// assignments always.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the operand.
+/// * `operand`: The `ExprId` of the operand expression.
+/// * `needs_parentheses`: Decides from the operand's precedence whether parentheses are required.
+///
+/// # Returns
+///
+/// A `String` containing the operand source code.
+fn print_operand(
+    expressions: &ExpressionArena,
+    operand: ExprId,
+    needs_parentheses: impl Fn(u32) -> bool,
+) -> String {
+    match &expressions[operand] {
+        CmmExpression::Binary { operator, .. } if needs_parentheses(operator.precedence()) => {
+            format!("({})", print_expression(expressions, operand))
+        }
+        CmmExpression::Assignment { .. } | CmmExpression::CompoundAssignment { .. } => {
+            format!("({})", print_expression(expressions, operand))
+        }
+        _ => print_expression(expressions, operand),
+    }
+}
+
+/// Prints a C-- AST as an indented tree with one node per line.
+///
+/// Every node shows its kind followed by its operator, constant value, or name, and the lines
+/// connecting a node to its children make deeply nested expressions easy to follow. With `colored`,
+/// node kinds, operators, constants, and names are highlighted with ANSI escape codes.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the C-- AST to print.
+/// * `colored`: Whether to highlight the tree with ANSI escape codes for a terminal.
+///
+/// # Returns
+///
+/// A `String` containing the tree, ending with a newline.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm_types::symbol::Symbol;
+/// # use cmm_types::cmm_ast::{CmmAst, CmmBlockItem, CmmFunction, CmmStatement, CmmType, CmmExpression, CmmUnaryOperator, ExpressionArena};
+/// # use cmm_types::printer::print_ast_tree;
+/// let mut expressions = ExpressionArena::new();
+/// let two = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+/// let expression = expressions.alloc(CmmExpression::Unary { operator: CmmUnaryOperator::Negate, expression: two });
+/// let cmm_ast = CmmAst::Program {
+///     structs: vec![],
+///     enums: vec![],
+///     variables: vec![],
+///     functions: vec![CmmFunction::Function {
+///         identifier: Symbol::from("main"),
+///         storage_class: None,
+///         return_type: Some(CmmType::Int),
+///         parameters: vec![],
+///         variadic: false,
+///         body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return { expression: Some(expression), span: None })]),
+///         span: None,
+///     }],
+///     expressions,
+/// };
+/// assert_eq!(
+///     print_ast_tree(&cmm_ast, false),
+///     "Program\n\
+///      └── Function main\n    \
+///          └── Return\n        \
+///              └── Unary Negate (-)\n            \
+///                  └── Constant 2\n"
+/// );
+/// ```
+pub fn print_ast_tree(cmm_ast: &CmmAst, colored: bool) -> String {
+    let style = TreeStyle { colored };
+    let CmmAst::Program {
+        structs,
+        enums,
+        variables,
+        functions,
+        expressions,
+    } = cmm_ast;
+    let mut output = format!("{}\n", style.paint(NODE_COLOR, "Program"));
+    for (index, declaration) in structs.iter().enumerate() {
+        let is_last = enums.is_empty()
+            && variables.is_empty()
+            && functions.is_empty()
+            && index == structs.len() - 1;
+        push_struct_tree(&mut output, declaration, is_last, &style);
+    }
+    for (index, declaration) in enums.iter().enumerate() {
+        let is_last = variables.is_empty() && functions.is_empty() && index == enums.len() - 1;
+        push_enum_tree(&mut output, expressions, declaration, is_last, &style);
+    }
+    for (index, declaration) in variables.iter().enumerate() {
+        let is_last = functions.is_empty() && index == variables.len() - 1;
+        push_declaration_tree(&mut output, expressions, declaration, "", is_last, &style);
+    }
+    for (index, function) in functions.iter().enumerate() {
+        let is_last = index == functions.len() - 1;
+        push_function_tree(&mut output, expressions, function, is_last, &style);
+    }
+    output
+}
+
+/// Appends the line of a structure declaration and the lines of its members.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `declaration`: The structure declaration to print.
+/// * `is_last`: Whether the declaration is the last one of the program.
+/// * `style`: The highlighting of the tree.
+fn push_struct_tree(
+    output: &mut String,
+    declaration: &CmmStructDeclaration,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let CmmStructDeclaration::Struct { tag, members, .. } = declaration;
+    push_tree_line(
+        output,
+        "",
+        is_last,
+        &format!(
+            "{} {}",
+            style.paint(NODE_COLOR, "Struct"),
+            style.paint(IDENTIFIER_COLOR, tag)
+        ),
+    );
+    let prefix = if is_last { TREE_SPACE } else { TREE_LINE };
+    for (index, member) in members.iter().enumerate() {
+        let CmmMember::Member {
+            identifier,
+            member_type,
+        } = member;
+        push_tree_line(
+            output,
+            prefix,
+            index == members.len() - 1,
+            &format!(
+                "{} {}: {}",
+                style.paint(NODE_COLOR, "Member"),
+                style.paint(IDENTIFIER_COLOR, identifier),
+                member_type
+            ),
+        );
+    }
+}
+
+/// Appends the line of an enumeration declaration and the lines of its enumerators, which have
+/// the expression of their value as a child.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the values of the enumerators.
+/// * `declaration`: The enumeration declaration to print.
+/// * `is_last`: Whether the declaration is the last one of the program.
+/// * `style`: The highlighting of the tree.
+fn push_enum_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    declaration: &CmmEnumDeclaration,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let CmmEnumDeclaration::Enum {
+        tag, enumerators, ..
+    } = declaration;
+    let label = match tag {
+        Some(tag) => format!(
+            "{} {}",
+            style.paint(NODE_COLOR, "Enum"),
+            style.paint(IDENTIFIER_COLOR, tag)
+        ),
+        None => style.paint(NODE_COLOR, "Enum"),
+    };
+    push_tree_line(output, "", is_last, &label);
+    let prefix = if is_last { TREE_SPACE } else { TREE_LINE };
+    for (index, CmmEnumerator::Enumerator { identifier, value }) in enumerators.iter().enumerate() {
+        let is_last_enumerator = index == enumerators.len() - 1;
+        push_tree_line(
+            output,
+            prefix,
+            is_last_enumerator,
+            &format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Enumerator"),
+                style.paint(IDENTIFIER_COLOR, identifier)
+            ),
+        );
+        if let Some(value) = value {
+            let child_prefix = format!(
+                "{}{}",
+                prefix,
+                if is_last_enumerator {
+                    TREE_SPACE
+                } else {
+                    TREE_LINE
+                }
+            );
+            push_expression_tree(output, expressions, *value, &child_prefix, true, style);
+        }
+    }
+}
+
+/// Appends the lines of a function and its body. A prototype is a `Declaration` node without
+/// children.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the expressions of the function body.
+/// * `function`: The function to print.
+/// * `is_last`: Whether the function is the last one of the program.
+/// * `style`: The highlighting of the tree.
+fn push_function_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    function: &CmmFunction,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let CmmFunction::Function {
+        identifier,
+        storage_class,
+        return_type,
+        parameters,
+        variadic,
+        body,
+        ..
+    } = function;
+    let kind = if body.is_some() {
+        "Function"
+    } else {
+        "Declaration"
+    };
+    let mut function_label = format!("{} ", style.paint(NODE_COLOR, kind));
+    if let Some(storage_class) = storage_class {
+        function_label.push_str(&format!("{} ", storage_class));
+    }
+    // Most functions return an `int`, so only a `void` return type is shown.
+    if return_type.is_none() {
+        function_label.push_str("void ");
+    }
+    function_label.push_str(&style.paint(IDENTIFIER_COLOR, identifier));
+    if !parameters.is_empty() || *variadic {
+        function_label.push_str(&format!(" ({})", print_parameters(parameters, *variadic)));
+    }
+    push_tree_line(output, "", is_last, &function_label);
+    let prefix = if is_last { TREE_SPACE } else { TREE_LINE };
+    let items = body.as_deref().unwrap_or_default();
+    for (index, item) in items.iter().enumerate() {
+        let is_last_item = index == items.len() - 1;
+        match item {
+            CmmBlockItem::Declaration(declaration) => push_declaration_tree(
+                output,
+                expressions,
+                declaration,
+                prefix,
+                is_last_item,
+                style,
+            ),
+            CmmBlockItem::Statement(statement) => {
+                push_statement_tree(output, expressions, statement, prefix, is_last_item, style)
+            }
+        }
+    }
+}
+
+/// The branch drawn before a node that has further siblings below it.
+const TREE_BRANCH: &str = "├── ";
+/// The branch drawn before the last child of a node.
+const TREE_LAST_BRANCH: &str = "└── ";
+/// The line continuing past the descendants of a node that has further siblings.
+const TREE_LINE: &str = "│   ";
+/// The indentation below the last child of a node.
+const TREE_SPACE: &str = "    ";
+
+/// The ANSI escape code of the node kinds, bold.
+const NODE_COLOR: &str = "\x1b[1m";
+/// The ANSI escape code of the operators, yellow.
+const OPERATOR_COLOR: &str = "\x1b[33m";
+/// The ANSI escape code of the constant values, green.
+const CONSTANT_COLOR: &str = "\x1b[32m";
+/// The ANSI escape code of the names, cyan.
+const IDENTIFIER_COLOR: &str = "\x1b[36m";
+/// The ANSI escape code resetting the highlighting.
+const RESET_COLOR: &str = "\x1b[0m";
+
+/// Decides whether the parts of the tree lines are highlighted.
+struct TreeStyle {
+    colored: bool,
+}
+
+impl TreeStyle {
+    /// Returns the text highlighted with the given ANSI escape code, or as is without colors.
+    fn paint(&self, color: &str, text: impl std::fmt::Display) -> String {
+        if self.colored {
+            format!("{}{}{}", color, text, RESET_COLOR)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Appends a single node line, connected to its parent with a branch.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `prefix`: The lines of the ancestors of the node.
+/// * `is_last`: Whether the node is the last child of its parent.
+/// * `label`: The text of the node.
+fn push_tree_line(output: &mut String, prefix: &str, is_last: bool, label: &str) {
+    output.push_str(prefix);
+    output.push_str(if is_last {
+        TREE_LAST_BRANCH
+    } else {
+        TREE_BRANCH
+    });
+    output.push_str(label);
+    output.push('\n');
+}
+
+/// Appends the lines of a statement and all of its nested statements and expressions.
+///
+/// The branches of an if statement are grouped under `Then` and `Else` nodes, and the body of a
+/// loop under a `Body` node, next to the condition in source order. The clauses of a for loop are
+/// grouped under `Init`, `Condition`, and `Post` nodes, leaving out the missing clauses.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the expressions of the statement.
+/// * `statement`: The statement to print.
+/// * `prefix`: The lines of the ancestors of the statement.
+/// * `is_last`: Whether the statement is the last child of its parent.
+/// * `style`: The highlighting of the tree.
+fn push_statement_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    statement: &CmmStatement,
+    prefix: &str,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let child_prefix = format!("{}{}", prefix, if is_last { TREE_SPACE } else { TREE_LINE });
+    match statement {
+        CmmStatement::Return { expression, .. } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "Return"));
+            if let Some(expression) = expression {
+                push_expression_tree(output, expressions, *expression, &child_prefix, true, style);
+            }
+        }
+        CmmStatement::Expression { expression, .. } => {
+            push_tree_line(
+                output,
+                prefix,
+                is_last,
+                &style.paint(NODE_COLOR, "Expression"),
+            );
+            push_expression_tree(output, expressions, *expression, &child_prefix, true, style);
+        }
+        CmmStatement::Null { .. } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "Null"))
+        }
+        CmmStatement::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "If"));
+            push_expression_tree(output, expressions, *condition, &child_prefix, false, style);
+            let mut branches = vec![("Then", then_branch)];
+            branches.extend(else_branch.iter().map(|else_branch| ("Else", else_branch)));
+            for (index, (name, branch)) in branches.iter().enumerate() {
+                let is_last_branch = index == branches.len() - 1;
+                push_tree_line(
+                    output,
+                    &child_prefix,
+                    is_last_branch,
+                    &style.paint(NODE_COLOR, name),
+                );
+                let branch_prefix = format!(
+                    "{}{}",
+                    child_prefix,
+                    if is_last_branch {
+                        TREE_SPACE
+                    } else {
+                        TREE_LINE
+                    }
+                );
+                push_statement_tree(output, expressions, branch, &branch_prefix, true, style);
+            }
+        }
+        CmmStatement::While {
+            condition, body, ..
+        } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "While"));
+            push_expression_tree(output, expressions, *condition, &child_prefix, false, style);
+            push_tree_line(
+                output,
+                &child_prefix,
+                true,
+                &style.paint(NODE_COLOR, "Body"),
+            );
+            let body_prefix = format!("{}{}", child_prefix, TREE_SPACE);
+            push_statement_tree(output, expressions, body, &body_prefix, true, style);
+        }
+        CmmStatement::DoWhile {
+            body, condition, ..
+        } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "DoWhile"));
+            push_tree_line(
+                output,
+                &child_prefix,
+                false,
+                &style.paint(NODE_COLOR, "Body"),
+            );
+            let body_prefix = format!("{}{}", child_prefix, TREE_LINE);
+            push_statement_tree(output, expressions, body, &body_prefix, true, style);
+            push_expression_tree(output, expressions, *condition, &child_prefix, true, style);
+        }
+        CmmStatement::For {
+            init,
+            condition,
+            post,
+            body,
+            ..
+        } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "For"));
+            let mut clauses = Vec::with_capacity(4);
+            match init {
+                CmmForInit::Declaration(declaration) => {
+                    clauses.push(("Init", ForClause::Declaration(declaration)))
+                }
+                CmmForInit::Expression(Some(expression)) => {
+                    clauses.push(("Init", ForClause::Expression(*expression)))
+                }
+                CmmForInit::Expression(None) => {}
+            }
+            clauses
+                .extend(condition.map(|condition| ("Condition", ForClause::Expression(condition))));
+            clauses.extend(post.map(|post| ("Post", ForClause::Expression(post))));
+            clauses.push(("Body", ForClause::Statement(body)));
+            for (index, (name, clause)) in clauses.iter().enumerate() {
+                let is_last_clause = index == clauses.len() - 1;
+                push_tree_line(
+                    output,
+                    &child_prefix,
+                    is_last_clause,
+                    &style.paint(NODE_COLOR, name),
+                );
+                let clause_prefix = format!(
+                    "{}{}",
+                    child_prefix,
+                    if is_last_clause {
+                        TREE_SPACE
+                    } else {
+                        TREE_LINE
+                    }
+                );
+                match clause {
+                    ForClause::Declaration(declaration) => push_declaration_tree(
+                        output,
+                        expressions,
+                        declaration,
+                        &clause_prefix,
+                        true,
+                        style,
+                    ),
+                    ForClause::Expression(expression) => push_expression_tree(
+                        output,
+                        expressions,
+                        *expression,
+                        &clause_prefix,
+                        true,
+                        style,
+                    ),
+                    ForClause::Statement(statement) => push_statement_tree(
+                        output,
+                        expressions,
+                        statement,
+                        &clause_prefix,
+                        true,
+                        style,
+                    ),
+                }
+            }
+        }
+        CmmStatement::Break { .. } => {
+            push_tree_line(output, prefix, is_last, &style.paint(NODE_COLOR, "Break"))
+        }
+        CmmStatement::Continue { .. } => push_tree_line(
+            output,
+            prefix,
+            is_last,
+            &style.paint(NODE_COLOR, "Continue"),
+        ),
+    }
+}
+
+/// A clause of a for loop, printed below its name in the tree.
+enum ForClause<'a> {
+    Declaration(&'a CmmDeclaration),
+    Expression(ExprId),
+    Statement(&'a CmmStatement),
+}
+
+/// Appends the line of a declaration and its initializer.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the initializer.
+/// * `declaration`: The declaration to print.
+/// * `prefix`: The lines of the ancestors of the declaration.
+/// * `is_last`: Whether the declaration is the last child of its parent.
+/// * `style`: The highlighting of the tree.
+fn push_declaration_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    declaration: &CmmDeclaration,
+    prefix: &str,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let CmmDeclaration::Declaration {
+        identifier,
+        storage_class,
+        variable_type,
+        initializer,
+        ..
+    } = declaration;
+    let storage_class = match storage_class {
+        Some(storage_class) => format!("{} ", storage_class),
+        None => String::new(),
+    };
+    push_tree_line(
+        output,
+        prefix,
+        is_last,
+        &format!(
+            "{} {}: {}{}",
+            style.paint(NODE_COLOR, "Declaration"),
+            style.paint(IDENTIFIER_COLOR, identifier),
+            storage_class,
+            variable_type
+        ),
+    );
+    if let Some(initializer) = initializer {
+        let initializer_prefix =
+            format!("{}{}", prefix, if is_last { TREE_SPACE } else { TREE_LINE });
+        push_initializer_tree(
+            output,
+            expressions,
+            initializer,
+            &initializer_prefix,
+            true,
+            style,
+        );
+    }
+}
+
+/// Appends the lines of an initializer, grouping the elements of a compound initializer under a
+/// `Compound` node.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the expressions of the initializer.
+/// * `initializer`: The initializer to print.
+/// * `prefix`: The lines of the ancestors of the initializer.
+/// * `is_last`: Whether the initializer is the last child of its parent.
+/// * `style`: The highlighting of the tree.
+fn push_initializer_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    initializer: &CmmInitializer,
+    prefix: &str,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    match initializer {
+        CmmInitializer::Single(expression) => {
+            push_expression_tree(output, expressions, *expression, prefix, is_last, style)
+        }
+        CmmInitializer::Compound(initializers) => {
+            push_tree_line(
+                output,
+                prefix,
+                is_last,
+                &style.paint(NODE_COLOR, "Compound"),
+            );
+            let element_prefix =
+                format!("{}{}", prefix, if is_last { TREE_SPACE } else { TREE_LINE });
+            for (index, element) in initializers.iter().enumerate() {
+                push_initializer_tree(
+                    output,
+                    expressions,
+                    element,
+                    &element_prefix,
+                    index == initializers.len() - 1,
+                    style,
+                );
+            }
+        }
+    }
+}
+
+/// Appends the lines of an expression and all of its operands.
+///
+/// # Arguments
+///
+/// * `output`: The tree printed so far.
+/// * `expressions`: The arena holding the expression and its operands.
+/// * `expression`: The `ExprId` of the expression to print.
+/// * `prefix`: The lines of the ancestors of the expression.
+/// * `is_last`: Whether the expression is the last child of its parent.
+/// * `style`: The highlighting of the tree.
+fn push_expression_tree(
+    output: &mut String,
+    expressions: &ExpressionArena,
+    expression: ExprId,
+    prefix: &str,
+    is_last: bool,
+    style: &TreeStyle,
+) {
+    let (label, operands) = match &expressions[expression] {
+        CmmExpression::IntegerConstant { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Constant"),
+                style.paint(CONSTANT_COLOR, value)
+            ),
+            vec![],
+        ),
+        CmmExpression::LongConstant { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Constant"),
+                style.paint(CONSTANT_COLOR, format_args!("{}L", value))
+            ),
+            vec![],
+        ),
+        CmmExpression::UnsignedIntegerConstant { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Constant"),
+                style.paint(CONSTANT_COLOR, format_args!("{}U", value))
+            ),
+            vec![],
+        ),
+        CmmExpression::UnsignedLongConstant { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Constant"),
+                style.paint(CONSTANT_COLOR, format_args!("{}UL", value))
+            ),
+            vec![],
+        ),
+        CmmExpression::DoubleConstant { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Constant"),
+                style.paint(CONSTANT_COLOR, format_args!("{:?}", value))
+            ),
+            vec![],
+        ),
+        CmmExpression::StringLiteral { value } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "StringLiteral"),
+                style.paint(CONSTANT_COLOR, print_string_literal(value))
+            ),
+            vec![],
+        ),
+        CmmExpression::Variable { identifier } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Variable"),
+                style.paint(IDENTIFIER_COLOR, identifier)
+            ),
+            vec![],
+        ),
+        CmmExpression::FunctionCall {
+            identifier,
+            arguments,
+        } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "FunctionCall"),
+                style.paint(IDENTIFIER_COLOR, identifier)
+            ),
+            arguments.clone(),
+        ),
+        CmmExpression::Cast {
+            target_type,
+            expression: operand,
+        } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Cast"),
+                style.paint(OPERATOR_COLOR, target_type)
+            ),
+            vec![*operand],
+        ),
+        CmmExpression::Unary {
+            operator,
+            expression: operand,
+        } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Unary"),
+                style.paint(
+                    OPERATOR_COLOR,
+                    format!("{:?} ({})", operator, unary_operator_symbol(operator))
+                )
+            ),
+            vec![*operand],
+        ),
+        CmmExpression::AddressOf {
+            expression: operand,
+        } => (style.paint(NODE_COLOR, "AddressOf"), vec![*operand]),
+        CmmExpression::Dereference {
+            expression: operand,
+        } => (style.paint(NODE_COLOR, "Dereference"), vec![*operand]),
+        CmmExpression::Subscript { array, index } => {
+            (style.paint(NODE_COLOR, "Subscript"), vec![*array, *index])
+        }
+        CmmExpression::Member { structure, member } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Member"),
+                style.paint(IDENTIFIER_COLOR, member)
+            ),
+            vec![*structure],
+        ),
+        CmmExpression::PointerMember { pointer, member } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "PointerMember"),
+                style.paint(IDENTIFIER_COLOR, member)
+            ),
+            vec![*pointer],
+        ),
+        CmmExpression::Binary {
+            operator,
+            left,
+            right,
+        } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "Binary"),
+                style.paint(
+                    OPERATOR_COLOR,
+                    format!("{:?} ({})", operator, binary_operator_symbol(operator))
+                )
+            ),
+            vec![*left, *right],
+        ),
+        CmmExpression::Assignment { target, value } => {
+            (style.paint(NODE_COLOR, "Assignment"), vec![*target, *value])
+        }
+        CmmExpression::CompoundAssignment {
+            operator,
+            target,
+            value,
+        } => (
+            format!(
+                "{} {}",
+                style.paint(NODE_COLOR, "CompoundAssignment"),
+                style.paint(
+                    OPERATOR_COLOR,
+                    format!("{:?} ({}=)", operator, binary_operator_symbol(operator))
+                )
+            ),
+            vec![*target, *value],
+        ),
+    };
+    push_tree_line(output, prefix, is_last, &label);
+    let operand_prefix = format!("{}{}", prefix, if is_last { TREE_SPACE } else { TREE_LINE });
+    for (index, operand) in operands.iter().enumerate() {
+        push_expression_tree(
+            output,
+            expressions,
+            *operand,
+            &operand_prefix,
+            index == operands.len() - 1,
+            style,
+        );
+    }
+}
+
+/// Returns the source code symbol of a unary operator.
+fn unary_operator_symbol(operator: &CmmUnaryOperator) -> &'static str {
+    match operator {
+        CmmUnaryOperator::Plus => "+",
+        CmmUnaryOperator::Complement => "~",
+        CmmUnaryOperator::Negate => "-",
+        CmmUnaryOperator::Not => "!",
+        CmmUnaryOperator::PrefixIncrement | CmmUnaryOperator::PostfixIncrement => "++",
+        CmmUnaryOperator::PrefixDecrement | CmmUnaryOperator::PostfixDecrement => "--",
+    }
+}
+
+/// Returns the source code symbol of a binary operator.
+fn binary_operator_symbol(operator: &CmmBinaryOperator) -> &'static str {
+    match operator {
+        CmmBinaryOperator::Add => "+",
+        CmmBinaryOperator::Subtract => "-",
+        CmmBinaryOperator::Multiply => "*",
+        CmmBinaryOperator::Divide => "/",
+        CmmBinaryOperator::Remainder => "%",
+        CmmBinaryOperator::BitwiseAnd => "&",
+        CmmBinaryOperator::BitwiseOr => "|",
+        CmmBinaryOperator::BitwiseXor => "^",
+        CmmBinaryOperator::LeftShift => "<<",
+        CmmBinaryOperator::RightShift => ">>",
+        CmmBinaryOperator::And => "&&",
+        CmmBinaryOperator::Or => "||",
+        CmmBinaryOperator::Equal => "==",
+        CmmBinaryOperator::NotEqual => "!=",
+        CmmBinaryOperator::GreaterThan => ">",
+        CmmBinaryOperator::LessThan => "<",
+        CmmBinaryOperator::GreaterThanEqual => ">=",
+        CmmBinaryOperator::LessThanEqual => "<=",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmm_ast::{CmmStorageClass, CmmType};
+
+    fn constant(expressions: &mut ExpressionArena, value: i32) -> ExprId {
+        expressions.alloc(CmmExpression::IntegerConstant { value })
+    }
+
+    fn subtract(expressions: &mut ExpressionArena, left: ExprId, right: ExprId) -> ExprId {
+        expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Subtract,
+            left,
+            right,
+        })
+    }
+
+    #[test]
+    fn test_print_left_associative_operands() {
+        let mut expressions = ExpressionArena::new();
+        let (one, two, three) = (
+            constant(&mut expressions, 1),
+            constant(&mut expressions, 2),
+            constant(&mut expressions, 3),
+        );
+        let difference = subtract(&mut expressions, one, two);
+        let left_nested = subtract(&mut expressions, difference, three);
+        assert_eq!(print_expression(&expressions, left_nested), "1 - 2 - 3");
+
+        let difference = subtract(&mut expressions, two, three);
+        let right_nested = subtract(&mut expressions, one, difference);
+        assert_eq!(print_expression(&expressions, right_nested), "1 - (2 - 3)");
+    }
+
+    #[test]
+    fn test_print_ast_tree() {
+        let mut expressions = ExpressionArena::new();
+        let (one, two, three) = (
+            constant(&mut expressions, 1),
+            constant(&mut expressions, 2),
+            constant(&mut expressions, 3),
+        );
+        let difference = subtract(&mut expressions, one, two);
+        let expression = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left: difference,
+            right: three,
+        });
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(expression),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── Return\n\
+             \x20       └── Binary Multiply (*)\n\
+             \x20           ├── Binary Subtract (-)\n\
+             \x20           │   ├── Constant 1\n\
+             \x20           │   └── Constant 2\n\
+             \x20           └── Constant 3\n"
+        );
+
+        let colored_tree = print_ast_tree(&cmm_ast, true);
+        assert!(colored_tree.starts_with("\x1b[1mProgram\x1b[0m\n"));
+        assert!(colored_tree.contains("\x1b[1mConstant\x1b[0m \x1b[32m3\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_print_if_statements() {
+        let mut expressions = ExpressionArena::new();
+        let (one, two, three) = (
+            constant(&mut expressions, 1),
+            constant(&mut expressions, 2),
+            constant(&mut expressions, 3),
+        );
+        let return_statement = |expression| {
+            Box::new(CmmStatement::Return {
+                expression: Some(expression),
+                span: None,
+            })
+        };
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::If {
+                    condition: one,
+                    then_branch: return_statement(one),
+                    else_branch: Some(Box::new(CmmStatement::If {
+                        condition: two,
+                        then_branch: Box::new(CmmStatement::If {
+                            condition: three,
+                            then_branch: return_statement(two),
+                            else_branch: Some(return_statement(three)),
+                            span: None,
+                        }),
+                        else_branch: None,
+                        span: None,
+                    })),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   if (1)\n\
+             \x20       return 1;\n\
+             \x20   else if (2)\n\
+             \x20       if (3)\n\
+             \x20           return 2;\n\
+             \x20       else\n\
+             \x20           return 3;\n\
+             }\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── If\n\
+             \x20       ├── Constant 1\n\
+             \x20       ├── Then\n\
+             \x20       │   └── Return\n\
+             \x20       │       └── Constant 1\n\
+             \x20       └── Else\n\
+             \x20           └── If\n\
+             \x20               ├── Constant 2\n\
+             \x20               └── Then\n\
+             \x20                   └── If\n\
+             \x20                       ├── Constant 3\n\
+             \x20                       ├── Then\n\
+             \x20                       │   └── Return\n\
+             \x20                       │       └── Constant 2\n\
+             \x20                       └── Else\n\
+             \x20                           └── Return\n\
+             \x20                               └── Constant 3\n"
+        );
+    }
+
+    #[test]
+    fn test_print_for_loops() {
+        let mut expressions = ExpressionArena::new();
+        let zero = constant(&mut expressions, 0);
+        let i = expressions.alloc(CmmExpression::Variable {
+            identifier: "i".into(),
+        });
+        let three = constant(&mut expressions, 3);
+        let condition = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::LessThan,
+            left: i,
+            right: three,
+        });
+        let one = constant(&mut expressions, 1);
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: i,
+            right: one,
+        });
+        let post = expressions.alloc(CmmExpression::Assignment {
+            target: i,
+            value: sum,
+        });
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::For {
+                    init: CmmForInit::Declaration(CmmDeclaration::Declaration {
+                        identifier: "i".into(),
+                        storage_class: None,
+                        variable_type: CmmType::UnsignedInt,
+                        initializer: Some(CmmInitializer::Single(zero)),
+                        span: None,
+                    }),
+                    condition: Some(condition),
+                    post: Some(post),
+                    body: Box::new(CmmStatement::For {
+                        init: CmmForInit::Expression(None),
+                        condition: None,
+                        post: None,
+                        body: Box::new(CmmStatement::Return {
+                            expression: Some(i),
+                            span: None,
+                        }),
+                        span: None,
+                    }),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   for (unsigned int i = 0; i < 3; i = i + 1)\n\
+             \x20       for (;;)\n\
+             \x20           return i;\n\
+             }\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── For\n\
+             \x20       ├── Init\n\
+             \x20       │   └── Declaration i: unsigned int\n\
+             \x20       │       └── Constant 0\n\
+             \x20       ├── Condition\n\
+             \x20       │   └── Binary LessThan (<)\n\
+             \x20       │       ├── Variable i\n\
+             \x20       │       └── Constant 3\n\
+             \x20       ├── Post\n\
+             \x20       │   └── Assignment\n\
+             \x20       │       ├── Variable i\n\
+             \x20       │       └── Binary Add (+)\n\
+             \x20       │           ├── Variable i\n\
+             \x20       │           └── Constant 1\n\
+             \x20       └── Body\n\
+             \x20           └── For\n\
+             \x20               └── Body\n\
+             \x20                   └── Return\n\
+             \x20                       └── Variable i\n"
+        );
+    }
+
+    #[test]
+    fn test_print_compound_initializers() {
+        let mut expressions = ExpressionArena::new();
+        let (one, two, three) = (
+            constant(&mut expressions, 1),
+            constant(&mut expressions, 2),
+            constant(&mut expressions, 3),
+        );
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::For {
+                    init: CmmForInit::Declaration(CmmDeclaration::Declaration {
+                        identifier: "a".into(),
+                        storage_class: None,
+                        variable_type: CmmType::Array(
+                            Box::new(CmmType::Array(Box::new(CmmType::Int), 2)),
+                            2,
+                        ),
+                        initializer: Some(CmmInitializer::Compound(vec![
+                            CmmInitializer::Compound(vec![CmmInitializer::Single(one)]),
+                            CmmInitializer::Compound(vec![
+                                CmmInitializer::Single(two),
+                                CmmInitializer::Single(three),
+                            ]),
+                        ])),
+                        span: None,
+                    }),
+                    condition: None,
+                    post: None,
+                    body: Box::new(CmmStatement::Return {
+                        expression: Some(one),
+                        span: None,
+                    }),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   for (int a[2][2] = {{1}, {2, 3}};;)\n\
+             \x20       return 1;\n\
+             }\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── For\n\
+             \x20       ├── Init\n\
+             \x20       │   └── Declaration a: int [2][2]\n\
+             \x20       │       └── Compound\n\
+             \x20       │           ├── Compound\n\
+             \x20       │           │   └── Constant 1\n\
+             \x20       │           └── Compound\n\
+             \x20       │               ├── Constant 2\n\
+             \x20       │               └── Constant 3\n\
+             \x20       └── Body\n\
+             \x20           └── Return\n\
+             \x20               └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_break_and_continue() {
+        let mut expressions = ExpressionArena::new();
+        let one = constant(&mut expressions, 1);
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::While {
+                    condition: one,
+                    body: Box::new(CmmStatement::If {
+                        condition: one,
+                        then_branch: Box::new(CmmStatement::Break { span: None }),
+                        else_branch: Some(Box::new(CmmStatement::Continue { span: None })),
+                        span: None,
+                    }),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   while (1)\n\
+             \x20       if (1)\n\
+             \x20           break;\n\
+             \x20       else\n\
+             \x20           continue;\n\
+             }\n"
+        );
+        assert!(print_ast_tree(&cmm_ast, false).ends_with(
+            "│   └── Break\n\
+             \x20               └── Else\n\
+             \x20                   └── Continue\n"
+        ));
+    }
+
+    #[test]
+    fn test_print_null_and_expression_statements() {
+        let mut expressions = ExpressionArena::new();
+        let a = expressions.alloc(CmmExpression::Variable {
+            identifier: "a".into(),
+        });
+        let one = constant(&mut expressions, 1);
+        let assignment = expressions.alloc(CmmExpression::Assignment {
+            target: a,
+            value: one,
+        });
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![
+                    CmmBlockItem::Statement(CmmStatement::While {
+                        condition: one,
+                        body: Box::new(CmmStatement::Null { span: None }),
+                        span: None,
+                    }),
+                    CmmBlockItem::Statement(CmmStatement::Expression {
+                        expression: assignment,
+                        span: None,
+                    }),
+                ]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   while (1)\n\
+             \x20       ;\n\
+             \x20   a = 1;\n\
+             }\n"
+        );
+        assert!(print_ast_tree(&cmm_ast, false).ends_with(
+            "│       └── Null\n\
+             \x20   └── Expression\n\
+             \x20       └── Assignment\n\
+             \x20           ├── Variable a\n\
+             \x20           └── Constant 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_print_void_functions() {
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "reset".into(),
+                storage_class: None,
+                return_type: None,
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: None,
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions: ExpressionArena::new(),
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "void reset(void) {\n\
+             \x20   return;\n\
+             }\n"
+        );
+        assert!(print_ast_tree(&cmm_ast, false).ends_with("    └── Return\n"));
+    }
+
+    #[test]
+    fn test_print_assignments() {
+        let mut expressions = ExpressionArena::new();
+        let (a, b) = (
+            expressions.alloc(CmmExpression::Variable {
+                identifier: "a".into(),
+            }),
+            expressions.alloc(CmmExpression::Variable {
+                identifier: "b".into(),
+            }),
+        );
+        let one = constant(&mut expressions, 1);
+        let inner = expressions.alloc(CmmExpression::Assignment {
+            target: b,
+            value: one,
+        });
+        let chained = expressions.alloc(CmmExpression::Assignment {
+            target: a,
+            value: inner,
+        });
+        assert_eq!(print_expression(&expressions, chained), "a = b = 1");
+
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: inner,
+            right: one,
+        });
+        assert_eq!(print_expression(&expressions, sum), "(b = 1) + 1");
+        let negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: inner,
+        });
+        assert_eq!(print_expression(&expressions, negation), "-(b = 1)");
+    }
+
+    #[test]
+    fn test_print_compound_assignments() {
+        let mut expressions = ExpressionArena::new();
+        let (a, b) = (
+            expressions.alloc(CmmExpression::Variable {
+                identifier: "a".into(),
+            }),
+            expressions.alloc(CmmExpression::Variable {
+                identifier: "b".into(),
+            }),
+        );
+        let (one, two) = (constant(&mut expressions, 1), constant(&mut expressions, 2));
+        let shift = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::LeftShift,
+            left: one,
+            right: two,
+        });
+        let inner = expressions.alloc(CmmExpression::CompoundAssignment {
+            operator: CmmBinaryOperator::BitwiseOr,
+            target: b,
+            value: shift,
+        });
+        let chained = expressions.alloc(CmmExpression::CompoundAssignment {
+            operator: CmmBinaryOperator::RightShift,
+            target: a,
+            value: inner,
+        });
+        assert_eq!(print_expression(&expressions, chained), "a >>= b |= 1 << 2");
+
+        let product = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left: inner,
+            right: two,
+        });
+        assert_eq!(print_expression(&expressions, product), "(b |= 1 << 2) * 2");
+        let bitwise_and = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::BitwiseAnd,
+            left: shift,
+            right: one,
+        });
+        let bitwise_xor = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::BitwiseXor,
+            left: bitwise_and,
+            right: two,
+        });
+        assert_eq!(
+            print_expression(&expressions, bitwise_xor),
+            "1 << 2 & 1 ^ 2"
+        );
+    }
+
+    #[test]
+    fn test_print_while_loops() {
+        let mut expressions = ExpressionArena::new();
+        let (zero, one) = (constant(&mut expressions, 0), constant(&mut expressions, 1));
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::While {
+                    condition: one,
+                    body: Box::new(CmmStatement::While {
+                        condition: zero,
+                        body: Box::new(CmmStatement::Return {
+                            expression: Some(one),
+                            span: None,
+                        }),
+                        span: None,
+                    }),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   while (1)\n\
+             \x20       while (0)\n\
+             \x20           return 1;\n\
+             }\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── While\n\
+             \x20       ├── Constant 1\n\
+             \x20       └── Body\n\
+             \x20           └── While\n\
+             \x20               ├── Constant 0\n\
+             \x20               └── Body\n\
+             \x20                   └── Return\n\
+             \x20                       └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_do_while_loops() {
+        let mut expressions = ExpressionArena::new();
+        let (zero, one) = (constant(&mut expressions, 0), constant(&mut expressions, 1));
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::DoWhile {
+                    body: Box::new(CmmStatement::Return {
+                        expression: Some(one),
+                        span: None,
+                    }),
+                    condition: zero,
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(void) {\n\
+             \x20   do\n\
+             \x20       return 1;\n\
+             \x20   while (0);\n\
+             }\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main\n\
+             \x20   └── DoWhile\n\
+             \x20       ├── Body\n\
+             \x20       │   └── Return\n\
+             \x20       │       └── Constant 1\n\
+             \x20       └── Constant 0\n"
+        );
+    }
+
+    #[test]
+    fn test_print_unary_operands() {
+        let mut expressions = ExpressionArena::new();
+        let one = constant(&mut expressions, 1);
+        let negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: one,
+        });
+        let double_negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: negation,
+        });
+        assert_eq!(print_expression(&expressions, double_negation), "- -1");
+
+        let smallest_int = constant(&mut expressions, i32::MIN);
+        let negated_smallest_int = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: smallest_int,
+        });
+        assert_eq!(
+            print_expression(&expressions, negated_smallest_int),
+            "- -2147483648"
+        );
+
+        let two = constant(&mut expressions, 2);
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: one,
+            right: two,
+        });
+        let negated_sum = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Not,
+            expression: sum,
+        });
+        assert_eq!(print_expression(&expressions, negated_sum), "!(1 + 2)");
+    }
+
+    #[test]
+    fn test_print_casts() {
+        let mut expressions = ExpressionArena::new();
+        let one = constant(&mut expressions, 1);
+        let two = constant(&mut expressions, 2);
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: one,
+            right: two,
+        });
+        let cast = expressions.alloc(CmmExpression::Cast {
+            target_type: CmmType::UnsignedInt,
+            expression: sum,
+        });
+        let product = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left: cast,
+            right: two,
+        });
+        assert_eq!(
+            print_expression(&expressions, product),
+            "(unsigned int) (1 + 2) * 2"
+        );
+
+        let negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: one,
+        });
+        let cast = expressions.alloc(CmmExpression::Cast {
+            target_type: CmmType::Double,
+            expression: negation,
+        });
+        let mut tree = String::new();
+        push_expression_tree(
+            &mut tree,
+            &expressions,
+            cast,
+            "",
+            true,
+            &TreeStyle { colored: false },
+        );
+        assert_eq!(print_expression(&expressions, cast), "(double) -1");
+        let constant_char = CmmType::Const(Box::new(CmmType::Char));
+        let pointer_cast = expressions.alloc(CmmExpression::Cast {
+            target_type: CmmType::Const(Box::new(CmmType::Pointer(Box::new(constant_char)))),
+            expression: one,
+        });
+        assert_eq!(
+            print_expression(&expressions, pointer_cast),
+            "(const char *const) 1"
+        );
+        assert_eq!(
+            tree,
+            "└── Cast double\n    \
+             └── Unary Negate (-)\n        \
+                 └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_function_calls() {
+        let mut expressions = ExpressionArena::new();
+        let one = constant(&mut expressions, 1);
+        let two = constant(&mut expressions, 2);
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: one,
+            right: two,
+        });
+        let call = expressions.alloc(CmmExpression::FunctionCall {
+            identifier: "main".into(),
+            arguments: vec![sum, one],
+        });
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![
+                    CmmParameter::Parameter {
+                        identifier: "argc".into(),
+                        parameter_type: CmmType::Int,
+                    },
+                    CmmParameter::Parameter {
+                        identifier: "depth".into(),
+                        parameter_type: CmmType::Long,
+                    },
+                ],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(call),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "int main(int argc, long depth) {\n    return main(1 + 2, 1);\n}\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             └── Function main (int argc, long depth)\n    \
+                 └── Return\n        \
+                     └── FunctionCall main\n            \
+                         ├── Binary Add (+)\n            \
+                         │   ├── Constant 1\n            \
+                         │   └── Constant 2\n            \
+                         └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_structs() {
+        let mut expressions = ExpressionArena::new();
+        let s = expressions.alloc(CmmExpression::Variable {
+            identifier: "s".into(),
+        });
+        let member = expressions.alloc(CmmExpression::Member {
+            structure: s,
+            member: "next".into(),
+        });
+        let pointer_member = expressions.alloc(CmmExpression::PointerMember {
+            pointer: member,
+            member: "x".into(),
+        });
+        let one = constant(&mut expressions, 1);
+        let constant_member = expressions.alloc(CmmExpression::Member {
+            structure: one,
+            member: "x".into(),
+        });
+        let difference = subtract(&mut expressions, pointer_member, constant_member);
+        let point = CmmType::Struct("point".into());
+        let cmm_ast = CmmAst::Program {
+            structs: vec![CmmStructDeclaration::Struct {
+                tag: "point".into(),
+                members: vec![
+                    CmmMember::Member {
+                        identifier: "x".into(),
+                        member_type: CmmType::Int,
+                    },
+                    CmmMember::Member {
+                        identifier: "next".into(),
+                        member_type: CmmType::Pointer(Box::new(point.clone())),
+                    },
+                ],
+                span: None,
+            }],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: "main".into(),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(difference),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "struct point {\n    int x;\n    struct point *next;\n};\n\n\
+             int main(void) {\n    return s.next->x - (1).x;\n}\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             ├── Struct point\n\
+             │   ├── Member x: int\n\
+             │   └── Member next: struct point *\n\
+             └── Function main\n    \
+                 └── Return\n        \
+                     └── Binary Subtract (-)\n            \
+                         ├── PointerMember x\n            \
+                         │   └── Member next\n            \
+                         │       └── Variable s\n            \
+                         └── Member x\n                \
+                             └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_enums() {
+        let mut expressions = ExpressionArena::new();
+        let one = constant(&mut expressions, 1);
+        let a = expressions.alloc(CmmExpression::Variable {
+            identifier: "A".into(),
+        });
+        let difference = subtract(&mut expressions, a, one);
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![
+                CmmEnumDeclaration::Enum {
+                    tag: Some("color".into()),
+                    enumerators: vec![
+                        CmmEnumerator::Enumerator {
+                            identifier: "A".into(),
+                            value: None,
+                        },
+                        CmmEnumerator::Enumerator {
+                            identifier: "B".into(),
+                            value: Some(difference),
+                        },
+                    ],
+                    span: None,
+                },
+                CmmEnumDeclaration::Enum {
+                    tag: None,
+                    enumerators: vec![CmmEnumerator::Enumerator {
+                        identifier: "C".into(),
+                        value: Some(one),
+                    }],
+                    span: None,
+                },
+            ],
+            variables: vec![],
+            functions: vec![],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "enum color { A, B = A - 1 };\n\nenum { C = 1 };\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             ├── Enum color\n\
+             │   ├── Enumerator A\n\
+             │   └── Enumerator B\n\
+             │       └── Binary Subtract (-)\n\
+             │           ├── Variable A\n\
+             │           └── Constant 1\n\
+             └── Enum\n    \
+                 └── Enumerator C\n        \
+                     └── Constant 1\n"
+        );
+    }
+
+    #[test]
+    fn test_print_function_prototypes() {
+        let mut expressions = ExpressionArena::new();
+        let zero = constant(&mut expressions, 0);
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![
+                CmmFunction::Function {
+                    identifier: "f".into(),
+                    storage_class: Some(CmmStorageClass::Static),
+                    return_type: Some(CmmType::Int),
+                    parameters: vec![CmmParameter::Parameter {
+                        identifier: "a".into(),
+                        parameter_type: CmmType::Char,
+                    }],
+                    variadic: true,
+                    body: None,
+                    span: None,
+                },
+                CmmFunction::Function {
+                    identifier: "main".into(),
+                    storage_class: None,
+                    return_type: Some(CmmType::Int),
+                    parameters: vec![],
+                    variadic: false,
+                    body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                        expression: Some(zero),
+                        span: None,
+                    })]),
+                    span: None,
+                },
+            ],
+            expressions,
+        };
+        assert_eq!(
+            print_ast(&cmm_ast),
+            "static int f(char a, ...);\n\nint main(void) {\n    return 0;\n}\n"
+        );
+        assert_eq!(
+            print_ast_tree(&cmm_ast, false),
+            "Program\n\
+             ├── Declaration static f (char a, ...)\n\
+             └── Function main\n    \
+                 └── Return\n        \
+                     └── Constant 0\n"
+        );
+    }
+
+    #[test]
+    fn test_print_increments_and_decrements() {
+        let mut expressions = ExpressionArena::new();
+        let a = expressions.alloc(CmmExpression::Variable {
+            identifier: "a".into(),
+        });
+        let increment = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PrefixIncrement,
+            expression: a,
+        });
+        let decrement = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PrefixDecrement,
+            expression: a,
+        });
+        let negated_decrement = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: decrement,
+        });
+        assert_eq!(print_expression(&expressions, negated_decrement), "- --a");
+        let decremented_negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PrefixDecrement,
+            expression: negated_decrement,
+        });
+        assert_eq!(
+            print_expression(&expressions, decremented_negation),
+            "-- - --a"
+        );
+        let sum = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left: increment,
+            right: decrement,
+        });
+        assert_eq!(print_expression(&expressions, sum), "++a + --a");
+
+        let postfix_increment = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixIncrement,
+            expression: a,
+        });
+        let postfix_decrement = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixDecrement,
+            expression: postfix_increment,
+        });
+        assert_eq!(print_expression(&expressions, postfix_decrement), "a++--");
+        let incremented_negation = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixIncrement,
+            expression: negated_decrement,
+        });
+        assert_eq!(
+            print_expression(&expressions, incremented_negation),
+            "(- --a)++"
+        );
+        let negative_one = constant(&mut expressions, -1);
+        let incremented_constant = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixIncrement,
+            expression: negative_one,
+        });
+        assert_eq!(
+            print_expression(&expressions, incremented_constant),
+            "(-1)++"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Represents a range of source code, with 1-based lines and columns.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Span {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Span {
+    /// Returns the span covering both this span and a later one.
+    ///
+    /// # Arguments
+    ///
+    /// * `end`: A span that ends after this span.
+    ///
+    /// # Returns
+    ///
+    /// A `Span` from the start of this span to the end of `end`.
+    pub fn to(&self, end: &Span) -> Span {
+        Span {
+            end_line: end.end_line,
+            end_column: end.end_column,
+            ..*self
+        }
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Represents an interned name, such as an identifier, a temporary variable, or a label.
+///
+/// Symbols are reference counted, so the compiler stages can copy names between ASTs without
+/// allocating. Symbols created by the same `Interner` share a single allocation per distinct name.
+/// Symbols compare, hash, and print like the names they hold.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    /// Returns the name held by the symbol.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&self.0)
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol(Arc::from(name))
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol(Arc::from(name))
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Symbol::from)
+    }
+}
+
+/// Deduplicates the names created during a single compilation session.
+#[derive(Debug, Default)]
+pub struct Interner {
+    symbols: HashSet<Symbol>,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Returns the symbol for a name, allocating it only the first time the name is seen.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name to intern.
+    ///
+    /// # Returns
+    ///
+    /// A `Symbol` sharing its allocation with every other symbol of the same name from this interner.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::symbol::Interner;
+    /// let mut interner = Interner::new();
+    /// let first = interner.intern("main");
+    /// let second = interner.intern("main");
+    /// assert_eq!(first, second);
+    /// assert_eq!(first, "main");
+    /// assert_eq!(interner.len(), 1);
+    /// ```
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(name) {
+            return symbol.clone();
+        }
+        let symbol = Symbol::from(name);
+        self.symbols.insert(symbol.clone());
+        symbol
+    }
+
+    /// Returns the number of distinct names interned so far.
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Returns `true` if no name has been interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interned_symbols_share_allocation() {
+        let mut interner = Interner::new();
+        let first = interner.intern("tmp.0");
+        let second = interner.intern("tmp.0");
+        let other = interner.intern("tmp.1");
+        assert!(Arc::ptr_eq(&first.0, &second.0));
+        assert_ne!(first, other);
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn test_symbol_formats_like_string() {
+        let symbol = Symbol::from("main");
+        assert_eq!(format!("{:?}", symbol), format!("{:?}", "main"));
+        assert_eq!(symbol.to_string(), "main");
+        assert_eq!(format!("{:<6}|", symbol), "main  |");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_symbol_serializes_as_string() {
+        let json = serde_json::to_string(&Symbol::from("tmp.0")).unwrap();
+        assert_eq!(json, "\"tmp.0\"");
+        assert_eq!(
+            serde_json::from_str::<Symbol>(&json).unwrap(),
+            Symbol::from("tmp.0")
+        );
+    }
+}
@@ -0,0 +1,982 @@
+use crate::cmm_ast::CmmType;
+use crate::printer::print_string_literal;
+use crate::symbol::Symbol;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Represents the top-level structure of TACKY Intermediate Representation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyAst {
+    /// A complete TACKY program, with its function definitions, the constants of its read-only
+    /// data, and the variables of its writable data. Functions that are only declared have no
+    /// TACKY function.
+    Program {
+        functions: Vec<TackyFunction>,
+        static_constants: Vec<TackyStaticConstant>,
+        static_variables: Vec<TackyStaticVariable>,
+    },
+}
+
+/// Represents a constant stored in the read-only data of a TACKY program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyStaticConstant {
+    /// A null-terminated string, such as the characters of a string literal.
+    String {
+        /// The unique name of the constant, e.g. `string.0`.
+        identifier: Symbol,
+        /// The bytes of the string, without the terminating null byte.
+        value: Vec<u8>,
+    },
+}
+
+/// Represents a variable with static storage duration, stored in the writable data of a TACKY
+/// program, such as a file-scope variable.
+///
+/// The instructions refer to a scalar static variable by its name like to a variable of the
+/// function, and to the other static variables in `GetAddress`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyStaticVariable {
+    Variable {
+        /// The name of the variable.
+        identifier: Symbol,
+        /// Whether the variable has external linkage, so other files can refer to it.
+        global: bool,
+        /// The alignment of the variable in bytes.
+        alignment: usize,
+        /// The initial value of the variable, in order, which covers the whole variable.
+        initial_values: Vec<TackyStaticInit>,
+    },
+    /// A variable only declared `extern`, which is defined in another file, so the program refers
+    /// to it without storing it.
+    Declaration {
+        /// The name of the variable.
+        identifier: Symbol,
+    },
+}
+
+impl TackyStaticVariable {
+    /// Returns the name of the variable.
+    pub fn identifier(&self) -> &Symbol {
+        match self {
+            TackyStaticVariable::Variable { identifier, .. }
+            | TackyStaticVariable::Declaration { identifier } => identifier,
+        }
+    }
+}
+
+/// Represents a part of the initial value of a static variable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TackyStaticInit {
+    /// A scalar, stored with the size of its type.
+    Value(TackyConstant),
+    /// The given number of zero bytes.
+    Zero(usize),
+}
+
+impl TackyStaticInit {
+    /// Returns the number of bytes the part of the initial value covers.
+    pub fn size(self) -> usize {
+        match self {
+            TackyStaticInit::Value(constant) => constant.constant_type().size(),
+            TackyStaticInit::Zero(size) => size,
+        }
+    }
+
+    /// Returns `true` if every byte of the part is zero. A negative zero `double` is not.
+    pub fn is_zero(self) -> bool {
+        match self {
+            TackyStaticInit::Value(TackyConstant::Double(value)) => value.to_bits() == 0,
+            TackyStaticInit::Value(constant) => constant.as_i64() == 0,
+            TackyStaticInit::Zero(_) => true,
+        }
+    }
+}
+
+/// Represents a TACKY function definition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyFunction {
+    /// Defines a function with a unique identifier and a list of instructions.
+    Function {
+        /// The unique name of the function.
+        identifier: Symbol,
+        /// Whether the function has external linkage, so other files can call it.
+        global: bool,
+        /// The unique names of the parameters, in order. Their types are in `variable_types`.
+        parameters: Vec<Symbol>,
+        /// The sequence of instructions that make up the function's body.
+        instructions: Vec<TackyInstruction>,
+        /// The type of every variable and temporary of the function, and of the scalar static
+        /// variables it refers to by name. Variables missing from the table are `Int`s.
+        variable_types: BTreeMap<Symbol, TackyType>,
+    },
+}
+
+/// Represents a single TACKY instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyInstruction {
+    /// Returns from the function, with a value unless the function returns `void`.
+    Return { value: Option<TackyValue> },
+    /// Performs a unary operation on a value.
+    Unary {
+        /// The unary operator to be applied.
+        operator: TackyUnaryOperator,
+        /// The source value for the operation.
+        source: TackyValue,
+        /// The destination where the result of the operation will be stored.
+        destination: TackyValue,
+    },
+    /// Performs a binary operation on two values.
+    Binary {
+        /// The binary operator to be applied.
+        operator: TackyBinaryOperator,
+        /// The first source value for the operation.
+        source1: TackyValue,
+        /// The second source value for the operation.
+        source2: TackyValue,
+        /// The destination where the result of the operation will be stored.
+        destination: TackyValue,
+    },
+    /// Copies a value to a variable.
+    Copy {
+        /// The source variable to be copied.
+        source: TackyValue,
+        /// The destination where the value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts a signed value to a larger type, copying its sign bit into the new upper bits.
+    SignExtend {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts an unsigned value to a larger type, filling the new upper bits with zeros.
+    ZeroExtend {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts a value to a smaller type, keeping only its lower bits.
+    Truncate {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts a signed integer to the nearest `double`.
+    IntToDouble {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts a `double` to a signed integer, rounding toward zero.
+    DoubleToInt {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts an unsigned integer to the nearest `double`.
+    UIntToDouble {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Converts a `double` to an unsigned integer, rounding toward zero.
+    DoubleToUInt {
+        /// The value to be converted.
+        source: TackyValue,
+        /// The destination where the converted value will be stored.
+        destination: TackyValue,
+    },
+    /// Stores the address of a variable or a static constant into a pointer.
+    GetAddress {
+        /// The name of the variable or the static constant.
+        source: Symbol,
+        /// The destination where the address will be stored.
+        destination: TackyValue,
+    },
+    /// Reads the value a pointer points to. The type of the destination determines how many bytes
+    /// are read.
+    Load {
+        /// The pointer to read through.
+        source_pointer: TackyValue,
+        /// The destination where the value will be stored.
+        destination: TackyValue,
+    },
+    /// Writes a value to where a pointer points to. The type of the source determines how many
+    /// bytes are written.
+    Store {
+        /// The value to be written.
+        source: TackyValue,
+        /// The pointer to write through.
+        destination_pointer: TackyValue,
+    },
+    /// Offsets a pointer by a number of elements, storing `pointer + index * scale` into a
+    /// pointer.
+    AddPtr {
+        /// The pointer to offset.
+        pointer: TackyValue,
+        /// The `long` number of elements to offset the pointer by, which may be negative.
+        index: TackyValue,
+        /// The size of an element in bytes.
+        scale: i64,
+        /// The destination where the offset pointer will be stored.
+        destination: TackyValue,
+    },
+    /// Calls a function and stores its return value.
+    FunCall {
+        /// The name of the called function.
+        identifier: Symbol,
+        /// The arguments, already converted to the types of the parameters. The further arguments
+        /// of a variadic function are promoted.
+        arguments: Vec<TackyValue>,
+        /// The number of arguments for the named parameters if the called function is variadic,
+        /// `None` otherwise.
+        named_arguments: Option<usize>,
+        /// The destination where the return value will be stored.
+        destination: TackyValue,
+    },
+    /// Jumps to a label.
+    Jump { target: Symbol },
+    /// Jumps to a label if a condition evaluates to zero.
+    JumpIfZero {
+        /// The condition to be evaluated.
+        condition: TackyValue,
+        /// Target label to jump to.
+        target: Symbol,
+    },
+    /// Jumps to a label if a condition evaluates to a non-zero value.
+    JumpIfNotZero {
+        /// The condition to be evaluated.
+        condition: TackyValue,
+        /// Target label to jump to.
+        target: Symbol,
+    },
+    /// Defines a label.
+    Label(Symbol),
+}
+
+/// Represents a value within the TACKY IR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyValue {
+    /// Represents an integer constant.
+    Constant(TackyConstant),
+    /// Represents a variable, identified by its name.
+    Variable(Symbol),
+}
+
+impl TackyValue {
+    /// Returns the type of the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable_types`: The variable types of the function the value belongs to.
+    ///
+    /// # Returns
+    ///
+    /// The `TackyType` of the constant or the variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::tacky_ast::{TackyConstant, TackyType, TackyValue};
+    /// # use std::collections::BTreeMap;
+    /// let variable_types = BTreeMap::from([("x.0".into(), TackyType::UnsignedInt)]);
+    /// let constant = TackyValue::Constant(TackyConstant::Int(1));
+    /// assert_eq!(constant.value_type(&variable_types), TackyType::Int);
+    /// let variable = TackyValue::Variable("x.0".into());
+    /// assert_eq!(variable.value_type(&variable_types), TackyType::UnsignedInt);
+    /// ```
+    pub fn value_type(&self, variable_types: &BTreeMap<Symbol, TackyType>) -> TackyType {
+        match self {
+            TackyValue::Constant(constant) => constant.constant_type(),
+            TackyValue::Variable(name) => {
+                variable_types.get(name).copied().unwrap_or(TackyType::Int)
+            }
+        }
+    }
+}
+
+/// Represents a typed constant within the TACKY IR.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TackyConstant {
+    /// A signed 32-bit constant.
+    Int(i32),
+    /// An unsigned 32-bit constant.
+    UnsignedInt(u32),
+    /// A signed 64-bit constant.
+    Long(i64),
+    /// An unsigned 64-bit constant.
+    UnsignedLong(u64),
+    /// A signed 16-bit constant.
+    Short(i16),
+    /// A signed 8-bit constant.
+    Char(i8),
+    /// An unsigned 8-bit constant.
+    UnsignedChar(u8),
+    /// A 64-bit floating-point constant.
+    Double(f64),
+}
+
+impl TackyConstant {
+    /// Returns the type of the constant.
+    pub fn constant_type(self) -> TackyType {
+        match self {
+            TackyConstant::Int(_) => TackyType::Int,
+            TackyConstant::UnsignedInt(_) => TackyType::UnsignedInt,
+            TackyConstant::Long(_) => TackyType::Long,
+            TackyConstant::UnsignedLong(_) => TackyType::UnsignedLong,
+            TackyConstant::Short(_) => TackyType::Short,
+            TackyConstant::Char(_) => TackyType::Char,
+            TackyConstant::UnsignedChar(_) => TackyType::UnsignedChar,
+            TackyConstant::Double(_) => TackyType::Double,
+        }
+    }
+
+    /// Returns the value of the constant as a signed 64-bit integer, which holds every value of
+    /// every integer type except the `unsigned long`s beyond the range of a `long`, whose bits are
+    /// kept instead. A `double` is rounded toward zero.
+    pub fn as_i64(self) -> i64 {
+        match self {
+            TackyConstant::Int(value) => i64::from(value),
+            TackyConstant::UnsignedInt(value) => i64::from(value),
+            TackyConstant::Long(value) => value,
+            TackyConstant::UnsignedLong(value) => value as i64,
+            TackyConstant::Short(value) => i64::from(value),
+            TackyConstant::Char(value) => i64::from(value),
+            TackyConstant::UnsignedChar(value) => i64::from(value),
+            TackyConstant::Double(value) => value as i64,
+        }
+    }
+
+    /// Converts the constant to another type like a C cast does.
+    ///
+    /// Converting to a larger type keeps the value, while converting to a type of the same or a
+    /// smaller size keeps the lower bits. Converting between a `double` and an integer type keeps
+    /// the value, rounded to the nearest `double` or toward zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: The type to convert the constant to.
+    ///
+    /// # Returns
+    ///
+    /// The converted `TackyConstant`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` is `TackyType::Pointer` or `TackyType::Aggregate`, as no constant is an
+    /// address or a block of memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::tacky_ast::{TackyConstant, TackyType};
+    /// assert_eq!(TackyConstant::Int(-1).convert_to(TackyType::UnsignedInt), TackyConstant::UnsignedInt(4294967295));
+    /// assert_eq!(TackyConstant::UnsignedInt(4294967295).convert_to(TackyType::Long), TackyConstant::Long(4294967295));
+    /// assert_eq!(TackyConstant::Long(4294967298).convert_to(TackyType::Int), TackyConstant::Int(2));
+    /// assert_eq!(TackyConstant::Int(200).convert_to(TackyType::Char), TackyConstant::Char(-56));
+    /// assert_eq!(TackyConstant::Double(-2.5).convert_to(TackyType::Int), TackyConstant::Int(-2));
+    /// assert_eq!(TackyConstant::UnsignedInt(3).convert_to(TackyType::Double), TackyConstant::Double(3.0));
+    /// assert_eq!(TackyConstant::Long(-1).convert_to(TackyType::UnsignedLong), TackyConstant::UnsignedLong(18446744073709551615));
+    /// assert_eq!(TackyConstant::UnsignedLong(1 << 63).convert_to(TackyType::Double), TackyConstant::Double(9223372036854775808.0));
+    /// assert_eq!(TackyConstant::Double(1e19).convert_to(TackyType::UnsignedLong), TackyConstant::UnsignedLong(10000000000000000000));
+    /// ```
+    pub fn convert_to(self, target: TackyType) -> TackyConstant {
+        let value = self.as_i64();
+        match target {
+            TackyType::Double => match self {
+                TackyConstant::Double(_) => self,
+                TackyConstant::UnsignedLong(value) => TackyConstant::Double(value as f64),
+                _ => TackyConstant::Double(value as f64),
+            },
+            TackyType::Int => TackyConstant::Int(value as i32),
+            TackyType::UnsignedInt => TackyConstant::UnsignedInt(value as u32),
+            TackyType::Long => TackyConstant::Long(value),
+            TackyType::UnsignedLong => match self {
+                TackyConstant::Double(value) => TackyConstant::UnsignedLong(value as u64),
+                _ => TackyConstant::UnsignedLong(value as u64),
+            },
+            TackyType::Short => TackyConstant::Short(value as i16),
+            TackyType::Char => TackyConstant::Char(value as i8),
+            TackyType::UnsignedChar => TackyConstant::UnsignedChar(value as u8),
+            TackyType::Pointer | TackyType::Aggregate { .. } => {
+                panic!("Constants cannot be converted to pointers or aggregates")
+            }
+        }
+    }
+}
+
+/// Represents the type of a TACKY value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TackyType {
+    /// A signed 32-bit integer.
+    Int,
+    /// An unsigned 32-bit integer.
+    UnsignedInt,
+    /// A signed 64-bit integer.
+    Long,
+    /// An unsigned 64-bit integer.
+    UnsignedLong,
+    /// A signed 16-bit integer.
+    Short,
+    /// A signed 8-bit integer.
+    Char,
+    /// An unsigned 8-bit integer.
+    UnsignedChar,
+    /// A 64-bit IEEE 754 floating-point number.
+    Double,
+    /// A 64-bit address, such as the address of a string literal or of a variable.
+    Pointer,
+    /// A block of memory holding an array, which is only accessed through its address.
+    Aggregate { size: usize, alignment: usize },
+}
+
+impl TackyType {
+    /// Returns `true` if the type is a signed integer type or `double`.
+    pub fn is_signed(self) -> bool {
+        match self {
+            TackyType::Int
+            | TackyType::Long
+            | TackyType::Short
+            | TackyType::Char
+            | TackyType::Double => true,
+            TackyType::UnsignedInt
+            | TackyType::UnsignedLong
+            | TackyType::UnsignedChar
+            | TackyType::Pointer
+            | TackyType::Aggregate { .. } => false,
+        }
+    }
+
+    /// Returns the size of a value of the type in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            TackyType::Int | TackyType::UnsignedInt => 4,
+            TackyType::Long | TackyType::UnsignedLong | TackyType::Double | TackyType::Pointer => 8,
+            TackyType::Short => 2,
+            TackyType::Char | TackyType::UnsignedChar => 1,
+            TackyType::Aggregate { size, .. } => size,
+        }
+    }
+
+    /// Returns the type a value of the type is promoted to before an arithmetic operation.
+    ///
+    /// Following the integer promotions of C, `short` and the character types are promoted to
+    /// `int`, which holds all of their values. The other types are not promoted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::tacky_ast::TackyType;
+    /// assert_eq!(TackyType::UnsignedChar.promoted(), TackyType::Int);
+    /// assert_eq!(TackyType::Short.promoted(), TackyType::Int);
+    /// assert_eq!(TackyType::UnsignedInt.promoted(), TackyType::UnsignedInt);
+    /// ```
+    pub fn promoted(self) -> TackyType {
+        match self {
+            TackyType::Short | TackyType::Char | TackyType::UnsignedChar => TackyType::Int,
+            _ => self,
+        }
+    }
+
+    /// Returns the type both operands of a binary operator are converted to before the operation.
+    ///
+    /// Following the usual arithmetic conversions of C, an operand of an integer type is converted
+    /// to `double` if the other operand is a `double`. Otherwise both operands are promoted, then
+    /// the operand of the smaller type is converted to the larger type, and an operand of a signed
+    /// type is converted to the unsigned type of the same size, e.g. an `int` to `unsigned int`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The type of the other operand.
+    ///
+    /// # Returns
+    ///
+    /// The common `TackyType` of the operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm_types::tacky_ast::TackyType;
+    /// assert_eq!(TackyType::Int.common_type(TackyType::Int), TackyType::Int);
+    /// assert_eq!(TackyType::Int.common_type(TackyType::UnsignedInt), TackyType::UnsignedInt);
+    /// assert_eq!(TackyType::UnsignedInt.common_type(TackyType::Long), TackyType::Long);
+    /// assert_eq!(TackyType::Char.common_type(TackyType::UnsignedChar), TackyType::Int);
+    /// assert_eq!(TackyType::Short.common_type(TackyType::UnsignedInt), TackyType::UnsignedInt);
+    /// assert_eq!(TackyType::Long.common_type(TackyType::UnsignedLong), TackyType::UnsignedLong);
+    /// assert_eq!(TackyType::Long.common_type(TackyType::Double), TackyType::Double);
+    /// ```
+    pub fn common_type(self, other: TackyType) -> TackyType {
+        let (first, second) = (self.promoted(), other.promoted());
+        if first == TackyType::Double || second == TackyType::Double {
+            TackyType::Double
+        } else if first.size() != second.size() {
+            if first.size() > second.size() {
+                first
+            } else {
+                second
+            }
+        } else if first.is_signed() {
+            second
+        } else {
+            first
+        }
+    }
+}
+
+impl From<CmmType> for TackyType {
+    /// Converts a C-- type into the TACKY type of its values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains a structure, whose layout only the type table of the
+    /// TACKY generator knows.
+    fn from(cmm_type: CmmType) -> Self {
+        match cmm_type {
+            CmmType::Int => TackyType::Int,
+            CmmType::UnsignedInt => TackyType::UnsignedInt,
+            CmmType::Long => TackyType::Long,
+            CmmType::UnsignedLong => TackyType::UnsignedLong,
+            CmmType::Short => TackyType::Short,
+            CmmType::Char => TackyType::Char,
+            CmmType::UnsignedChar | CmmType::Bool => TackyType::UnsignedChar,
+            CmmType::Double => TackyType::Double,
+            CmmType::Pointer(_) => TackyType::Pointer,
+            CmmType::Const(qualified_type) => TackyType::from(*qualified_type),
+            CmmType::Array(_, _) | CmmType::Struct(_) => TackyType::Aggregate {
+                size: cmm_type.size(),
+                alignment: cmm_type.alignment(),
+            },
+        }
+    }
+}
+
+/// Represents a unary operator within the TACKY IR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyUnaryOperator {
+    /// Bitwise complement
+    Complement,
+    /// Arithmetic negation
+    Negate,
+    /// Logical negation
+    Not,
+}
+
+/// Represents a binary operator within the TACKY IR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TackyBinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    LeftShift,
+    RightShift,
+    Equal,
+    NotEqual,
+    LessThan,
+    GreaterThan,
+    LessThanEqual,
+    GreaterThanEqual,
+}
+
+impl fmt::Display for TackyAst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyAst::Program {
+                functions,
+                static_constants,
+                static_variables,
+            } => {
+                for static_constant in static_constants {
+                    writeln!(f, "{}", static_constant)?;
+                }
+                for static_variable in static_variables {
+                    writeln!(f, "{}", static_variable)?;
+                }
+                for (index, function) in functions.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", function)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats static constants like C declarations, e.g. `constant string.0 = "hi\n"`.
+impl fmt::Display for TackyStaticConstant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyStaticConstant::String { identifier, value } => write!(
+                f,
+                "constant {} = {}",
+                identifier,
+                print_string_literal(value)
+            ),
+        }
+    }
+}
+
+/// Formats static variables like functions, e.g. `static variable a (align 4) = 1, zero 8`, and
+/// declarations like `extern variable e`.
+impl fmt::Display for TackyStaticVariable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyStaticVariable::Variable {
+                identifier,
+                global,
+                alignment,
+                initial_values,
+            } => {
+                if !global {
+                    write!(f, "static ")?;
+                }
+                write!(f, "variable {} (align {}) = ", identifier, alignment)?;
+                write_separated(f, initial_values)
+            }
+            TackyStaticVariable::Declaration { identifier } => {
+                write!(f, "extern variable {}", identifier)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TackyStaticInit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyStaticInit::Value(constant) => write!(f, "{}", constant),
+            TackyStaticInit::Zero(size) => write!(f, "zero {}", size),
+        }
+    }
+}
+
+impl fmt::Display for TackyFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyFunction::Function {
+                identifier,
+                global,
+                parameters,
+                instructions,
+                ..
+            } => {
+                if !global {
+                    write!(f, "static ")?;
+                }
+                write!(f, "{}", identifier)?;
+                if !parameters.is_empty() {
+                    write!(f, "(")?;
+                    write_separated(f, parameters)?;
+                    write!(f, ")")?;
+                }
+                writeln!(f, ":")?;
+                for instruction in instructions {
+                    match instruction {
+                        TackyInstruction::Label(_) => writeln!(f, "{}", instruction)?,
+                        _ => writeln!(f, "    {}", instruction)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Writes values separated by commas, e.g. `a.0, 1`.
+fn write_separated<T: fmt::Display>(f: &mut fmt::Formatter, values: &[T]) -> fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", value)?;
+    }
+    Ok(())
+}
+
+/// Formats instructions as three-address code, e.g. `tmp.2 = tmp.0 + tmp.1`.
+impl fmt::Display for TackyInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyInstruction::Return { value: Some(value) } => write!(f, "return {}", value),
+            TackyInstruction::Return { value: None } => write!(f, "return"),
+            TackyInstruction::Unary {
+                operator,
+                source,
+                destination,
+            } => write!(f, "{} = {}{}", destination, operator, source),
+            TackyInstruction::Binary {
+                operator,
+                source1,
+                source2,
+                destination,
+            } => write!(f, "{} = {} {} {}", destination, source1, operator, source2),
+            TackyInstruction::Copy {
+                source,
+                destination,
+            } => write!(f, "{} = {}", destination, source),
+            TackyInstruction::SignExtend {
+                source,
+                destination,
+            } => write!(f, "{} = sign_extend {}", destination, source),
+            TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            } => write!(f, "{} = zero_extend {}", destination, source),
+            TackyInstruction::Truncate {
+                source,
+                destination,
+            } => write!(f, "{} = truncate {}", destination, source),
+            TackyInstruction::IntToDouble {
+                source,
+                destination,
+            } => write!(f, "{} = int_to_double {}", destination, source),
+            TackyInstruction::DoubleToInt {
+                source,
+                destination,
+            } => write!(f, "{} = double_to_int {}", destination, source),
+            TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            } => write!(f, "{} = uint_to_double {}", destination, source),
+            TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            } => write!(f, "{} = double_to_uint {}", destination, source),
+            TackyInstruction::GetAddress {
+                source,
+                destination,
+            } => write!(f, "{} = &{}", destination, source),
+            TackyInstruction::Load {
+                source_pointer,
+                destination,
+            } => write!(f, "{} = *{}", destination, source_pointer),
+            TackyInstruction::Store {
+                source,
+                destination_pointer,
+            } => write!(f, "*{} = {}", destination_pointer, source),
+            TackyInstruction::AddPtr {
+                pointer,
+                index,
+                scale,
+                destination,
+            } => write!(
+                f,
+                "{} = add_ptr({}, {}, {})",
+                destination, pointer, index, scale
+            ),
+            TackyInstruction::FunCall {
+                identifier,
+                arguments,
+                named_arguments,
+                destination,
+            } => {
+                write!(f, "{} = call {}(", destination, identifier)?;
+                match named_arguments {
+                    // The ellipsis separates the named arguments from the further ones.
+                    Some(count) => {
+                        let (named, further) = arguments.split_at(*count);
+                        write_separated(f, named)?;
+                        write!(f, "{}...", if named.is_empty() { "" } else { ", " })?;
+                        if !further.is_empty() {
+                            write!(f, ", ")?;
+                            write_separated(f, further)?;
+                        }
+                    }
+                    None => write_separated(f, arguments)?,
+                }
+                write!(f, ")")
+            }
+            TackyInstruction::Jump { target } => write!(f, "jump {}", target),
+            TackyInstruction::JumpIfZero { condition, target } => {
+                write!(f, "jump_if_zero {}, {}", condition, target)
+            }
+            TackyInstruction::JumpIfNotZero { condition, target } => {
+                write!(f, "jump_if_not_zero {}, {}", condition, target)
+            }
+            TackyInstruction::Label(label) => write!(f, "{}:", label),
+        }
+    }
+}
+
+impl fmt::Display for TackyValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyValue::Constant(constant) => write!(f, "{}", constant),
+            TackyValue::Variable(identifier) => write!(f, "{}", identifier),
+        }
+    }
+}
+
+/// Formats unsigned constants with a `u` suffix, long constants with an `L` suffix, unsigned long
+/// constants with a `ul` suffix, and double constants with a decimal point or an exponent, like C
+/// literals. C has no literals of `short`
+/// and the character types, so their constants are formatted as casts, e.g. `(char) 97`.
+impl fmt::Display for TackyConstant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyConstant::Int(value) => write!(f, "{}", value),
+            TackyConstant::UnsignedInt(value) => write!(f, "{}u", value),
+            TackyConstant::Long(value) => write!(f, "{}L", value),
+            TackyConstant::UnsignedLong(value) => write!(f, "{}ul", value),
+            TackyConstant::Short(value) => write!(f, "(short) {}", value),
+            TackyConstant::Char(value) => write!(f, "(char) {}", value),
+            TackyConstant::UnsignedChar(value) => write!(f, "(unsigned char) {}", value),
+            TackyConstant::Double(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+impl fmt::Display for TackyUnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            TackyUnaryOperator::Complement => "~",
+            TackyUnaryOperator::Negate => "-",
+            TackyUnaryOperator::Not => "!",
+        };
+        f.write_str(symbol)
+    }
+}
+
+impl fmt::Display for TackyBinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            TackyBinaryOperator::Add => "+",
+            TackyBinaryOperator::Subtract => "-",
+            TackyBinaryOperator::Multiply => "*",
+            TackyBinaryOperator::Divide => "/",
+            TackyBinaryOperator::Remainder => "%",
+            TackyBinaryOperator::BitwiseAnd => "&",
+            TackyBinaryOperator::BitwiseOr => "|",
+            TackyBinaryOperator::BitwiseXor => "^",
+            TackyBinaryOperator::LeftShift => "<<",
+            TackyBinaryOperator::RightShift => ">>",
+            TackyBinaryOperator::Equal => "==",
+            TackyBinaryOperator::NotEqual => "!=",
+            TackyBinaryOperator::LessThan => "<",
+            TackyBinaryOperator::GreaterThan => ">",
+            TackyBinaryOperator::LessThanEqual => "<=",
+            TackyBinaryOperator::GreaterThanEqual => ">=",
+        };
+        f.write_str(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_function() {
+        let function = TackyFunction::Function {
+            identifier: Symbol::from("main"),
+            global: true,
+            parameters: vec![],
+            instructions: vec![
+                TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Negate,
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Variable(Symbol::from("tmp.0")),
+                    target: Symbol::from("end.1"),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::LessThanEqual,
+                    source1: TackyValue::Variable(Symbol::from("tmp.0")),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
+                },
+                TackyInstruction::Label(Symbol::from("end.1")),
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Variable(Symbol::from("tmp.2"))),
+                },
+            ],
+            variable_types: BTreeMap::new(),
+        };
+        assert_eq!(
+            function.to_string(),
+            "main:\n    tmp.0 = -1\n    jump_if_zero tmp.0, end.1\n    tmp.2 = tmp.0 <= 2\nend.1:\n    return tmp.2\n"
+        );
+    }
+
+    #[test]
+    fn test_display_function_with_parameters() {
+        let function = TackyFunction::Function {
+            identifier: Symbol::from("f"),
+            global: true,
+            parameters: vec![Symbol::from("a.0"), Symbol::from("b.1")],
+            instructions: vec![
+                TackyInstruction::FunCall {
+                    identifier: Symbol::from("f"),
+                    arguments: vec![
+                        TackyValue::Variable(Symbol::from("b.1")),
+                        TackyValue::Constant(TackyConstant::Long(2)),
+                    ],
+                    named_arguments: None,
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
+                },
+                TackyInstruction::FunCall {
+                    identifier: Symbol::from("g"),
+                    arguments: vec![TackyValue::Variable(Symbol::from("a.0"))],
+                    named_arguments: Some(0),
+                    destination: TackyValue::Variable(Symbol::from("tmp.3")),
+                },
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Variable(Symbol::from("tmp.2"))),
+                },
+            ],
+            variable_types: BTreeMap::new(),
+        };
+        assert_eq!(
+            function.to_string(),
+            "f(a.0, b.1):\n    tmp.2 = call f(b.1, 2L)\n    tmp.3 = call g(..., a.0)\n    return tmp.2\n"
+        );
+    }
+
+    #[test]
+    fn test_display_static_variables() {
+        let variables = [
+            TackyStaticVariable::Variable {
+                identifier: Symbol::from("a"),
+                global: true,
+                alignment: 4,
+                initial_values: vec![
+                    TackyStaticInit::Value(TackyConstant::Int(1)),
+                    TackyStaticInit::Zero(8),
+                ],
+            },
+            TackyStaticVariable::Variable {
+                identifier: Symbol::from("d"),
+                global: false,
+                alignment: 8,
+                initial_values: vec![TackyStaticInit::Value(TackyConstant::Double(-0.0))],
+            },
+            TackyStaticVariable::Declaration {
+                identifier: Symbol::from("e"),
+            },
+        ];
+        assert_eq!(
+            variables.map(|variable| variable.to_string()),
+            [
+                "variable a (align 4) = 1, zero 8",
+                "static variable d (align 8) = -0.0",
+                "extern variable e"
+            ]
+        );
+        assert!(TackyStaticInit::Value(TackyConstant::Long(0)).is_zero());
+        assert!(!TackyStaticInit::Value(TackyConstant::Double(-0.0)).is_zero());
+        assert_eq!(TackyStaticInit::Value(TackyConstant::Short(3)).size(), 2);
+    }
+}
@@ -0,0 +1,6 @@
+use cmm::lsp::run_server;
+
+fn main() -> anyhow::Result<()> {
+    let exit_code = run_server(std::io::stdin().lock(), std::io::stdout().lock())?;
+    std::process::exit(exit_code);
+}
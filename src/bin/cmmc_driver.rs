@@ -1,15 +1,45 @@
+use cmm::cache::CompilationCache;
+use cmm::common::config::ResolvedConfig;
+use cmm::common::diagnostics::{Diagnostic, Severity, WarningOptions, diagnostics_to_json};
+use cmm::common::target::Target;
 use cmm::common::validation;
-use cmm::compiler::{CompilerResult, Stage, run_cmm_compiler};
-use cmm::compiler_driver::{run_gcc_linker, run_gcc_preprocessor};
+use cmm::compiler::error_codes;
+use cmm::compiler::errors::CompilerError;
+use cmm::compiler::lexer::tokens::Token;
+use cmm::compiler::metrics::collect_metrics;
+use cmm::compiler::parser::printer::print_ast_tree;
+use cmm::compiler::session::CompilerSession;
+use cmm::compiler::source_map::build_source_map;
+use cmm::compiler::{Backend, CompileOptions, CompilerResult, Stage, Standard, run_cmm_compiler};
+use cmm::compiler_driver::{
+    compile_file_with_cache, run_assembler_check, run_gcc_assembler, run_gcc_linker,
+    run_gcc_preprocessor, run_internal_linker, run_internal_preprocessor,
+};
+use cmm::diff::structural_diff;
+use cmm::repl::run_repl;
+use cmm::test_suite::{TestSuiteOptions, discover_chapters, run_chapter};
 
-use clap::Parser;
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::ffi::OsString;
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
-#[command(version, about, long_about = "C-- Compiler Driver")]
+#[command(
+    version,
+    about,
+    long_about = "C-- Compiler Driver",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct CliArgs {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+
     /// Input file to process.
-    c_file_path: PathBuf,
+    #[clap(required_unless_present = "print_config")]
+    c_file_path: Option<PathBuf>,
 
     /// Tokenizes the C-- source code into tokens
     #[clap(long, conflicts_with_all = &["parse", "codegen", "tacky"], group = "operation")]
@@ -19,6 +49,10 @@ struct CliArgs {
     #[clap(long, conflicts_with_all = &["lex", "codegen", "tacky"], group = "operation")]
     parse: bool,
 
+    /// Resolves the variables of the AST, reports its warnings, and prints the validated AST
+    #[clap(long, conflicts_with_all = &["lex", "parse", "codegen", "tacky"], group = "operation")]
+    validate: bool,
+
     /// Emits a TACKY IR from the AST
     #[clap(long, conflicts_with_all = &["lex", "parse", "codegen"], group = "operation")]
     tacky: bool,
@@ -30,12 +64,370 @@ struct CliArgs {
     /// Stops the compiler after assembly code generation.
     #[clap(short = 'S', conflicts_with_all = &["lex", "parse", "codegen", "tacky"], group = "operation")]
     stop_after_cmm_compiler: bool,
+
+    /// Compiles and assembles the source file into an object file without linking.
+    #[clap(short = 'c', conflicts_with_all = &["lex", "parse", "codegen", "tacky", "stop_after_cmm_compiler"], group = "operation")]
+    stop_after_assembler: bool,
+
+    /// Stops the compiler after the given stage and prints its output. --lex, --parse, --validate, --tacky, and --codegen are shorthands for it.
+    #[clap(long, value_enum, value_name = "STAGE", group = "operation")]
+    stop_after: Option<Stage>,
+
+    /// Output file. With -S, `-` writes the assembly to stdout without any status messages.
+    #[clap(short = 'o')]
+    output_path: Option<PathBuf>,
+
+    /// Optimization level, e.g. -O2. Accepted for cc compatibility, the compiler does not optimize yet.
+    #[clap(short = 'O', value_name = "LEVEL", help_heading = "cc compatibility")]
+    optimization_level: Option<String>,
+
+    /// Generates debug information. Accepted for cc compatibility, no debug information is emitted yet.
+    #[clap(short = 'g', help_heading = "cc compatibility")]
+    debug_info: bool,
+
+    /// Configures warnings. -W<name> enables a warning and -Wno-<name> disables it, -Wall enables every warning, and -Werror turns warnings into errors. The warnings are division-by-zero, unreachable-code, and unused-variable, which -Wunused enables as well. Other values such as -Wextra are accepted for cc compatibility.
+    #[clap(short = 'W', value_name = "WARNING", help_heading = "cc compatibility")]
+    warnings: Vec<String>,
+
+    /// Language standard of the source file. `gnu` accepts the GNU extensions, such as binary constants like 0b1010. c99, c11, c17, gnu99, gnu11, and gnu17 are accepted as aliases.
+    #[clap(long = "std", value_enum, value_name = "STANDARD", default_value_t = Standard::C)]
+    standard: Standard,
+
+    /// Target triple to build for, e.g. x86_64-apple-darwin. Defaults to x86-64 on the host OS.
+    #[clap(long)]
+    target: Option<Target>,
+
+    /// Prints the resolved host, toolchain, and build configuration, then exits.
+    #[clap(long)]
+    print_config: bool,
+
+    /// Format of reported errors. `json` writes an array of diagnostics to stderr, even when compilation succeeds.
+    #[clap(long, value_enum, default_value_t = DiagnosticsFormat::Human)]
+    diagnostics: DiagnosticsFormat,
+
+    /// Prints per-function code metrics to stderr: TACKY instruction counts before and after optimization, assembly instruction count, and stack frame size.
+    #[clap(long)]
+    metrics: bool,
+
+    /// Writes a JSON source map mapping the assembly lines to source spans to `<output>.map.json`. Requires -S.
+    #[clap(long)]
+    source_map: bool,
+
+    /// Checks the emitted assembly with the system assembler and reports the rejected instructions.
+    #[clap(long, conflicts_with_all = &["lex", "parse", "validate", "tacky", "codegen", "stop_after"])]
+    check_asm: bool,
+
+    /// Directory to cache emitted assembly in. Unchanged sources compiled with the same options reuse the cached assembly.
+    #[clap(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Format of the stage output. `text` is compact and human-readable, `debug` prints the full data structures, `tree` draws the AST printed by --parse or --validate as an indented tree, colorized on a terminal, and `json` serializes the assembly AST printed by --codegen.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Code generator to use. `qbe` emits QBE IL and runs the `qbe` compiler on it to produce the assembly.
+    #[clap(long, value_enum, default_value_t = BackendOption::Native)]
+    backend: BackendOption,
+
+    /// Tools that preprocess and link the program. `internal` needs no gcc: it skips preprocessing and writes a static x86-64 Linux executable itself.
+    #[clap(long, value_enum, default_value_t = Toolchain::Gcc)]
+    toolchain: Toolchain,
+}
+
+/// The toolchains that can build the executable.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Toolchain {
+    Gcc,
+    Internal,
+}
+
+/// The code generators that can be selected on the command line.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum BackendOption {
+    Native,
+    Qbe,
+}
+
+impl From<BackendOption> for Backend {
+    fn from(backend: BackendOption) -> Self {
+        match backend {
+            BackendOption::Native => Backend::Native,
+            BackendOption::Qbe => Backend::Qbe,
+        }
+    }
+}
+
+/// The formats the output of a compiler stage can be printed in.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Debug,
+    Tree,
+    Json,
+}
+
+/// The formats errors can be reported in.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum DiagnosticsFormat {
+    Human,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Compares the output of a compiler stage for two C-- source files and prints a tree diff.
+    ///
+    /// The source files are compiled without preprocessing. Exits with status 1 if the outputs differ.
+    Diff {
+        /// The source file to compare against.
+        old_file_path: PathBuf,
+        /// The changed source file.
+        new_file_path: PathBuf,
+        /// The compiler stage whose outputs are compared.
+        #[clap(long, value_enum, default_value_t = Stage::Tacky)]
+        stage: Stage,
+    },
+    /// Prints a detailed description of an error code, e.g. `explain E0002`.
+    Explain {
+        /// The error code shown in a diagnostic.
+        code: String,
+    },
+    /// Starts an interactive session that evaluates C-- expressions and statements with the TACKY interpreter.
+    Repl,
+    /// Runs the test programs of a "Writing a C Compiler" style test suite and reports the results per chapter.
+    ///
+    /// Exits with status 1 if any test fails.
+    TestSuite {
+        /// The root directory of the test suite, containing `chapter_<N>` directories.
+        suite_path: PathBuf,
+        /// The chapter to run. Runs every chapter found in the suite if omitted.
+        #[clap(long)]
+        chapter: Option<u32>,
+        /// Also runs the tests of optional language features.
+        #[clap(long)]
+        extra_credit: bool,
+    },
+}
+
+/// Compiles two source files until the given stage and prints the structural diff of the outputs.
+///
+/// # Returns
+///
+/// `true` if the stage outputs differ.
+fn run_diff(old_file_path: &Path, new_file_path: &Path, stage: Stage) -> anyhow::Result<bool> {
+    let options = CompileOptions {
+        process_until: Some(stage),
+        quiet: true,
+        ..Default::default()
+    };
+    let compile = |path: &Path| -> anyhow::Result<CompilerResult> {
+        let source_code = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read '{}'", path.display()))?;
+        run_cmm_compiler(&source_code, &options)
+            .with_context(|| format!("Failed to compile '{}'", path.display()))
+    };
+    let diff = structural_diff(&compile(old_file_path)?, &compile(new_file_path)?);
+    if diff.is_empty() {
+        println!("No differences");
+    } else {
+        println!("--- {}", old_file_path.display());
+        println!("+++ {}", new_file_path.display());
+        print!("{}", diff);
+    }
+    Ok(!diff.is_empty())
+}
+
+/// Environment variable holding the tracing filter, e.g. `CMM_TRACE=debug`.
+#[cfg(feature = "tracing")]
+const TRACE_ENV_VAR: &str = "CMM_TRACE";
+
+/// Prints the traces of the compilation pipeline to stderr when `CMM_TRACE` is set.
+///
+/// Closed spans are reported with their busy time, which gives the timing of every stage, pass, and function.
+#[cfg(feature = "tracing")]
+fn init_tracing() {
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    if std::env::var_os(TRACE_ENV_VAR).is_none() {
+        return;
+    }
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_env(TRACE_ENV_VAR))
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Whether stage output is colorized: only on a terminal, and never when `NO_COLOR` is set.
+fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Rewrites a bare `-O` to `-O1`, as cc does, so that it never takes the following argument as its level.
+fn expand_bare_optimization_flag(argument: OsString) -> OsString {
+    if argument == "-O" {
+        OsString::from("-O1")
+    } else {
+        argument
+    }
 }
 
 fn main() -> anyhow::Result<()> {
-    let args = CliArgs::parse();
-    let c_file_path = args.c_file_path;
+    #[cfg(feature = "tracing")]
+    init_tracing();
+    let args = CliArgs::parse_from(std::env::args_os().map(expand_bare_optimization_flag));
+    match &args.command {
+        Some(CliCommand::Diff {
+            old_file_path,
+            new_file_path,
+            stage,
+        }) => {
+            if run_diff(old_file_path, new_file_path, *stage)? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(CliCommand::Explain { code }) => {
+            let error_code = error_codes::lookup(code)
+                .ok_or_else(|| anyhow::anyhow!("Unknown error code '{}'", code))?;
+            print!(
+                "{}: {}\n\n{}",
+                error_code.code, error_code.title, error_code.explanation
+            );
+            return Ok(());
+        }
+        Some(CliCommand::Repl) => {
+            run_repl(std::io::stdin().lock(), std::io::stdout())?;
+            return Ok(());
+        }
+        Some(CliCommand::TestSuite {
+            suite_path,
+            chapter,
+            extra_credit,
+        }) => {
+            let options = TestSuiteOptions {
+                extra_credit: *extra_credit,
+            };
+            if !run_test_suite(suite_path, *chapter, &options)? {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+    if args.print_config {
+        let options = CompileOptions {
+            process_until: stop_stage(&args),
+            target: args.target.unwrap_or_default(),
+            backend: Backend::from(args.backend),
+            ..Default::default()
+        };
+        print!("{}", ResolvedConfig::resolve(&options));
+        return Ok(());
+    }
+    let c_file_path = args
+        .c_file_path
+        .clone()
+        .expect("clap requires the input file unless --print-config or a subcommand is given");
+
+    let mut warnings = Vec::new();
+    let result = run_compilation(&args, &c_file_path, &mut warnings);
+    if args.diagnostics == DiagnosticsFormat::Human {
+        for warning in &warnings {
+            let flag = match &warning.warning {
+                Some(name) => format!(" [-W{}]", name),
+                None => String::new(),
+            };
+            eprintln!(
+                "warning[{}]: {}{}",
+                warning.code.as_deref().unwrap_or_default(),
+                warning,
+                flag
+            );
+        }
+        if let Err(error) = &result
+            && let Some(compiler_error) = error.downcast_ref::<CompilerError>()
+        {
+            let code = compiler_error.code();
+            eprintln!("error[{}]: {}", code, compiler_error);
+            eprintln!(
+                "For more information about this error, try `cmmc_driver explain {}`.",
+                code
+            );
+            std::process::exit(1);
+        }
+        return result;
+    }
+    let mut diagnostics: Vec<Diagnostic> = warnings
+        .into_iter()
+        .map(|warning| warning.with_file(&c_file_path))
+        .collect();
+    if let Err(error) = &result {
+        let diagnostic = match error.downcast_ref::<CompilerError>() {
+            Some(compiler_error) => Diagnostic::from(compiler_error),
+            None => Diagnostic::error(format!("{:#}", error)),
+        };
+        diagnostics.push(diagnostic.with_file(&c_file_path));
+    }
+    eprintln!("{}", diagnostics_to_json(&diagnostics));
+    if result.is_err() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Runs the chapters of a test suite and prints a report for each of them.
+///
+/// # Returns
+///
+/// `true` if every test passed.
+fn run_test_suite(
+    suite_path: &Path,
+    chapter: Option<u32>,
+    options: &TestSuiteOptions,
+) -> anyhow::Result<bool> {
+    let chapters = match chapter {
+        Some(chapter) => vec![chapter],
+        None => discover_chapters(suite_path)?,
+    };
+    let mut all_passed = true;
+    for chapter in chapters {
+        let report = run_chapter(suite_path, chapter, options)?;
+        print!("{}", report);
+        all_passed &= report.all_passed();
+    }
+    Ok(all_passed)
+}
+
+/// Returns the stage the command line arguments stop the compilation after, if any.
+///
+/// A single stage flag such as `--parse` is a shorthand for `--stop-after`.
+fn stop_stage(args: &CliArgs) -> Option<Stage> {
+    match (
+        args.lex,
+        args.parse,
+        args.validate,
+        args.tacky,
+        args.codegen,
+    ) {
+        (true, false, false, false, false) => Some(Stage::Lex),
+        (false, true, false, false, false) => Some(Stage::Parse),
+        (false, false, true, false, false) => Some(Stage::Validate),
+        (false, false, false, true, false) => Some(Stage::Tacky),
+        (false, false, false, false, true) => Some(Stage::Codegen),
+        _ => args.stop_after,
+    }
+}
 
+/// Preprocesses, compiles, and links a C-- source file according to the command line arguments.
+///
+/// The warnings of the compilation are added to `warnings`, also when a later step fails.
+fn run_compilation(
+    args: &CliArgs,
+    c_file_path: &Path,
+    warnings: &mut Vec<Diagnostic>,
+) -> anyhow::Result<()> {
     if !c_file_path.is_file() {
         return Err(std::io::Error::new(
             std::io::ErrorKind::NotFound,
@@ -47,61 +439,269 @@ fn main() -> anyhow::Result<()> {
         .into());
     }
 
-    let process_until = match (args.lex, args.parse, args.tacky, args.codegen) {
-        (true, false, false, false) => Some(Stage::Lex),
-        (false, true, false, false) => Some(Stage::Parse),
-        (false, false, true, false) => Some(Stage::Tacky),
-        (false, false, false, true) => Some(Stage::Codegen),
-        _ => None,
+    let target = args.target.unwrap_or_default();
+    if validation::is_object_file(c_file_path) {
+        // Object files built with -c only need to be linked, like `cc main.o -o main`.
+        let options = CompileOptions {
+            target,
+            ..Default::default()
+        };
+        if args.toolchain == Toolchain::Internal {
+            return Err(anyhow::anyhow!(
+                "The internal toolchain cannot link object files, use --toolchain gcc"
+            ));
+        }
+        let (linker_input_path, linker_output_path) =
+            validation::validate_object_linker_paths(c_file_path, args.output_path.as_deref())?;
+        return run_gcc_linker(&linker_input_path, &linker_output_path, &options);
+    }
+    let backend = Backend::from(args.backend);
+    if !backend.supports(&target) {
+        return Err(CompilerError::UnsupportedTarget { target }.into());
+    }
+
+    let writes_to_stdout = args
+        .output_path
+        .as_deref()
+        .is_some_and(|path| path == Path::new(validation::STDOUT_PATH));
+    if writes_to_stdout && !args.stop_after_cmm_compiler {
+        return Err(anyhow::anyhow!(
+            "Writing to stdout with '-o -' is only supported together with -S"
+        ));
+    }
+
+    let process_until = stop_stage(args);
+
+    if args.format == OutputFormat::Json && process_until != Some(Stage::Codegen) {
+        return Err(anyhow::anyhow!(
+            "JSON output is only supported together with --codegen"
+        ));
+    }
+
+    if args.format == OutputFormat::Tree
+        && !matches!(process_until, Some(Stage::Parse | Stage::Validate))
+    {
+        return Err(anyhow::anyhow!(
+            "Tree output is only supported together with --parse or --validate"
+        ));
+    }
+
+    if args.source_map && (!args.stop_after_cmm_compiler || writes_to_stdout) {
+        return Err(anyhow::anyhow!(
+            "Source maps are only supported together with -S and an assembly output file"
+        ));
+    }
+
+    if backend == Backend::Qbe && process_until == Some(Stage::Codegen) {
+        return Err(anyhow::anyhow!(
+            "The QBE backend has no codegen output, use -S to inspect the generated assembly"
+        ));
+    }
+
+    if backend == Backend::Qbe && args.source_map {
+        return Err(anyhow::anyhow!(
+            "Source maps are only supported by the native backend"
+        ));
+    }
+
+    let links_executable =
+        process_until.is_none() && !args.stop_after_cmm_compiler && !args.stop_after_assembler;
+    if args.toolchain == Toolchain::Internal {
+        if args.stop_after_assembler || args.check_asm {
+            return Err(anyhow::anyhow!(
+                "The internal toolchain has no assembler, -c and --check-asm require --toolchain gcc"
+            ));
+        }
+        if backend == Backend::Qbe && links_executable {
+            return Err(anyhow::anyhow!(
+                "The internal toolchain only links code of the native backend"
+            ));
+        }
+    }
+
+    // The final output is checked before any stage runs, so that a wrong path leaves no
+    // intermediate files behind.
+    let output_ext = if args.stop_after_cmm_compiler {
+        Some("s")
+    } else if args.stop_after_assembler {
+        Some("o")
+    } else {
+        None
     };
+    if args.stop_after_cmm_compiler || args.stop_after_assembler || links_executable {
+        validation::validate_output_path(args.output_path.as_deref(), output_ext)?;
+    }
 
     let (preprocessor_input_path, preprocessor_output_path) =
-        validation::validate_preprocessor_paths(Path::new(&c_file_path), None)?;
-    let _ = run_gcc_preprocessor(&preprocessor_input_path, &preprocessor_output_path);
+        validation::validate_preprocessor_paths(c_file_path, None)?;
+    let preprocessor_output = IntermediateFile(preprocessor_output_path.clone());
+    let options = CompileOptions {
+        process_until,
+        target,
+        quiet: writes_to_stdout || args.format == OutputFormat::Json,
+        backend,
+        warnings: WarningOptions::from_flags(args.warnings.iter().map(String::as_str)),
+        standard: args.standard,
+    };
+    match args.toolchain {
+        Toolchain::Gcc => run_gcc_preprocessor(
+            &preprocessor_input_path,
+            &preprocessor_output_path,
+            &options,
+        )?,
+        Toolchain::Internal => run_internal_preprocessor(
+            &preprocessor_input_path,
+            &preprocessor_output_path,
+            &options,
+        )?,
+    }
 
-    let (compiler_input_path, compiler_output_path) =
-        validation::validate_compiler_paths(&preprocessor_output_path, None)?;
-    let cmm_source_code = std::fs::read_to_string(compiler_input_path)?;
-    let compilation_result = run_cmm_compiler(&cmm_source_code, &process_until);
-    std::fs::remove_file(&preprocessor_output_path)?;
+    let assembly_output_path = if args.stop_after_cmm_compiler {
+        args.output_path.as_deref()
+    } else {
+        None
+    };
+    let cache = args.cache_dir.as_deref().map(CompilationCache::new);
+    let mut session = CompilerSession::new(options.clone());
+    let compilation_result = compile_file_with_cache(
+        &preprocessor_output_path,
+        assembly_output_path,
+        &mut session,
+        cache.as_ref(),
+    );
+    warnings.extend(
+        session
+            .take_diagnostics()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning),
+    );
+    let metrics_report = if args.metrics && compilation_result.is_ok() {
+        let source_code = std::fs::read_to_string(&preprocessor_output_path)?;
+        Some(collect_metrics(&source_code, &target)?)
+    } else {
+        None
+    };
+    let source_map = if args.source_map && compilation_result.is_ok() {
+        let source_code = std::fs::read_to_string(&preprocessor_output_path)?;
+        Some(build_source_map(&source_code, &target)?)
+    } else {
+        None
+    };
+    let internal_linker_source = if args.toolchain == Toolchain::Internal
+        && links_executable
+        && compilation_result.is_ok()
+    {
+        Some(std::fs::read_to_string(&preprocessor_output_path)?)
+    } else {
+        None
+    };
+    drop(preprocessor_output);
+    let (compiler_output_path, compilation_result) = compilation_result?;
+    // Without -S, the assembly file is an intermediate of the assembler or the linker.
+    let _compiler_output = (args.stop_after_assembler || links_executable)
+        .then(|| IntermediateFile(compiler_output_path.clone()));
+    if let Some(mut source_map) = source_map {
+        let source_map_path = compiler_output_path.with_extension("map.json");
+        source_map.file = Some(compiler_output_path.clone());
+        source_map.source = Some(c_file_path.to_path_buf());
+        std::fs::write(&source_map_path, serde_json::to_string_pretty(&source_map)?)
+            .with_context(|| format!("Failed to write '{}'", source_map_path.display()))?;
+    }
+    if let Some(metrics_report) = metrics_report {
+        eprint!("{}", metrics_report);
+    }
 
     match compilation_result {
-        Ok(ref inner_result) => match inner_result {
-            CompilerResult::Lexer(tokens) => {
-                println!("Lexer output: {:?}", tokens);
-                return Ok(());
+        CompilerResult::Lexer(tokens) => {
+            match args.format {
+                OutputFormat::Debug => println!("Lexer output: {:?}", tokens),
+                _ => {
+                    let tokens: Vec<String> = tokens.iter().map(Token::to_string).collect();
+                    println!("Lexer output: {}", tokens.join(" "));
+                }
             }
-            CompilerResult::Parser(ast) => {
-                println!("Parser output: {:?}", ast);
-                return Ok(());
+            return Ok(());
+        }
+        CompilerResult::Parser(ast) => {
+            match args.format {
+                OutputFormat::Debug => println!("Parser output: {:?}", ast),
+                OutputFormat::Tree => {
+                    print!("Parser output:\n{}", print_ast_tree(&ast, use_color()))
+                }
+                _ => print!("Parser output:\n{}", ast),
             }
-            CompilerResult::Tacky(tacky_ast) => {
-                println!("TACKY IR output: {:?}", tacky_ast);
-                return Ok(());
+            return Ok(());
+        }
+        CompilerResult::Validator(ast) => {
+            match args.format {
+                OutputFormat::Debug => println!("Validator output: {:?}", ast),
+                OutputFormat::Tree => {
+                    print!("Validator output:\n{}", print_ast_tree(&ast, use_color()))
+                }
+                _ => print!("Validator output:\n{}", ast),
             }
-            CompilerResult::Codegen(assembly_ast) => {
-                println!("Codegen output: {:?}", assembly_ast);
-                return Ok(());
+            return Ok(());
+        }
+        CompilerResult::Tacky(tacky_ast) => {
+            match args.format {
+                OutputFormat::Debug => println!("TACKY IR output: {:?}", tacky_ast),
+                _ => print!("TACKY IR output:\n{}", tacky_ast),
+            }
+            return Ok(());
+        }
+        CompilerResult::Optimizer(tacky_ast) => {
+            match args.format {
+                OutputFormat::Debug => println!("Optimizer output: {:?}", tacky_ast),
+                _ => print!("Optimizer output:\n{}", tacky_ast),
+            }
+            return Ok(());
+        }
+        CompilerResult::Codegen(assembly_ast) => {
+            match args.format {
+                OutputFormat::Text | OutputFormat::Tree => {
+                    print!("Codegen output:\n{}", assembly_ast)
+                }
+                OutputFormat::Debug => println!("Codegen output: {:?}", assembly_ast),
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&assembly_ast)?),
             }
-            CompilerResult::Final(assembly_code) => {
-                std::fs::write(&compiler_output_path, assembly_code)?;
-                println!(
-                    "Assembly code created at: {}",
-                    compiler_output_path.display()
-                );
-                if args.stop_after_cmm_compiler {
+            return Ok(());
+        }
+        CompilerResult::Final(assembly_code) => {
+            if args.check_asm {
+                run_assembler_check(&assembly_code, &options)?;
+            }
+            if args.stop_after_cmm_compiler {
+                if !options.quiet {
                     println!("Assembly code output: {:?}", assembly_code);
-                    return Ok(());
                 }
+                return Ok(());
             }
-        },
-        Err(e) => return Err(e),
+        }
+    }
+
+    if args.stop_after_assembler {
+        let (assembler_input_path, assembler_output_path) = validation::validate_assembler_paths(
+            &compiler_output_path,
+            args.output_path.as_deref(),
+        )?;
+        return run_gcc_assembler(&assembler_input_path, &assembler_output_path, &options);
     }
 
     let (linker_input_path, linker_output_path) =
-        validation::validate_linker_paths(&compiler_output_path, None)?;
-    let _ = run_gcc_linker(&linker_input_path, &linker_output_path);
-    std::fs::remove_file(&compiler_output_path)?;
+        validation::validate_linker_paths(&compiler_output_path, args.output_path.as_deref())?;
+    match internal_linker_source {
+        Some(source_code) => run_internal_linker(&source_code, &linker_output_path, &options),
+        None => run_gcc_linker(&linker_input_path, &linker_output_path, &options),
+    }
+}
 
-    Ok(())
+/// An intermediate file of the compilation, which is removed when it goes out of scope, on
+/// success and on every error path alike.
+struct IntermediateFile(PathBuf);
+
+impl Drop for IntermediateFile {
+    fn drop(&mut self) {
+        // The file may not exist if its stage failed before writing it.
+        let _ = std::fs::remove_file(&self.0);
+    }
 }
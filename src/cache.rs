@@ -0,0 +1,197 @@
+use crate::compiler::CompileOptions;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// Extension of the cached assembly files.
+const CACHE_FILE_EXTENSION: &str = "s";
+
+/// Represents the key of a cached compilation.
+///
+/// The key is a hash of the preprocessed source code, the options that affect the emitted assembly,
+/// and the identity of the compiler binary, so an entry is never reused by a different build of the
+/// compiler, even one with the same version number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CacheKey(u64);
+
+impl CacheKey {
+    /// Computes the cache key of a compilation.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmm_source_code`: The preprocessed source code to compile.
+    /// * `options`: The `CompileOptions` used for the compilation.
+    ///
+    /// # Returns
+    ///
+    /// The `CacheKey` identifying the compilation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::cache::CacheKey;
+    /// # use cmm::compiler::CompileOptions;
+    /// let options = CompileOptions::default();
+    /// let key = CacheKey::new("int main(void) { return 1; }", &options);
+    /// assert_eq!(key, CacheKey::new("int main(void) { return 1; }", &options));
+    /// assert_ne!(key, CacheKey::new("int main(void) { return 2; }", &options));
+    /// ```
+    pub fn new(cmm_source_code: &str, options: &CompileOptions) -> Self {
+        Self::for_compiler(compiler_identity(), cmm_source_code, options)
+    }
+
+    /// Computes the cache key of a compilation by the compiler with the given identity.
+    fn for_compiler(
+        identity: &CompilerIdentity,
+        cmm_source_code: &str,
+        options: &CompileOptions,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        identity.hash(&mut hasher);
+        options.target.to_string().hash(&mut hasher);
+        options.backend.hash(&mut hasher);
+        options.standard.hash(&mut hasher);
+        cmm_source_code.hash(&mut hasher);
+        CacheKey(hasher.finish())
+    }
+}
+
+/// Represents the build of the compiler that emitted a cached compilation.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct CompilerIdentity {
+    /// The version of the compiler crate.
+    version: &'static str,
+    /// The size and modification time of the running compiler binary, if they can be read.
+    binary: Option<(u64, SystemTime)>,
+}
+
+/// Returns the identity of the running compiler, which is read once per process.
+///
+/// Like the default `compiler_check` of ccache, a binary is identified by its size and
+/// modification time, so rebuilding the compiler invalidates the cache without hashing the whole
+/// binary on every compilation.
+fn compiler_identity() -> &'static CompilerIdentity {
+    static IDENTITY: OnceLock<CompilerIdentity> = OnceLock::new();
+    IDENTITY.get_or_init(|| CompilerIdentity {
+        version: env!("CARGO_PKG_VERSION"),
+        binary: std::env::current_exe()
+            .and_then(std::fs::metadata)
+            .and_then(|metadata| Ok((metadata.len(), metadata.modified()?)))
+            .ok(),
+    })
+}
+
+/// Represents a directory of cached compilation results.
+///
+/// Only final assembly code is cached, the outputs of the earlier stages are cheap to recompute.
+#[derive(Debug, Clone)]
+pub struct CompilationCache {
+    /// The directory holding one assembly file per cached compilation.
+    directory: PathBuf,
+}
+
+impl CompilationCache {
+    /// Creates a cache stored in the given directory. The directory is created on the first insert.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory`: The directory to store the cached assembly files in.
+    ///
+    /// # Returns
+    ///
+    /// A new `CompilationCache`.
+    pub fn new(directory: &Path) -> Self {
+        CompilationCache {
+            directory: directory.to_path_buf(),
+        }
+    }
+
+    /// Looks up the assembly code of a previous compilation.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The `CacheKey` of the compilation.
+    ///
+    /// # Returns
+    ///
+    /// The cached assembly code, or `None` if the compilation has not been cached or cannot be read.
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        std::fs::read_to_string(self.entry_path(key)).ok()
+    }
+
+    /// Stores the assembly code of a compilation.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The `CacheKey` of the compilation.
+    /// * `assembly_code`: The emitted assembly code.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` on success, or the I/O error of creating the directory or writing the entry.
+    pub fn insert(&self, key: &CacheKey, assembly_code: &str) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        // Writing to a temporary file first keeps concurrent readers from seeing a partial entry.
+        let temporary_path = self.entry_path(key).with_extension("tmp");
+        std::fs::write(&temporary_path, assembly_code)?;
+        std::fs::rename(&temporary_path, self.entry_path(key))
+    }
+
+    /// Returns the path of the file holding a cached compilation.
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}", key.0))
+            .with_extension(CACHE_FILE_EXTENSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::target::Target;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache = CompilationCache::new(&temp_dir.path().join("cache"));
+        let key = CacheKey::new("int main(void) { return 1; }", &CompileOptions::default());
+        assert_eq!(cache.get(&key), None);
+        cache.insert(&key, "\tret\n").unwrap();
+        assert_eq!(cache.get(&key), Some(String::from("\tret\n")));
+    }
+
+    #[test]
+    fn test_key_depends_on_compiler_binary() {
+        let source_code = "int main(void) { return 1; }";
+        let options = CompileOptions::default();
+        let identity = |modified_seconds| CompilerIdentity {
+            version: env!("CARGO_PKG_VERSION"),
+            binary: Some((
+                1024,
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(modified_seconds),
+            )),
+        };
+        assert_ne!(
+            CacheKey::for_compiler(&identity(1), source_code, &options),
+            CacheKey::for_compiler(&identity(2), source_code, &options)
+        );
+    }
+
+    #[test]
+    fn test_key_depends_on_target() {
+        let source_code = "int main(void) { return 1; }";
+        let linux_options = CompileOptions {
+            target: Target::X86_64_LINUX,
+            ..Default::default()
+        };
+        let macos_options = CompileOptions {
+            target: Target::X86_64_MACOS,
+            ..Default::default()
+        };
+        assert_ne!(
+            CacheKey::new(source_code, &linux_options),
+            CacheKey::new(source_code, &macos_options)
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::common::target::Target;
+use crate::compiler::{Backend, CompileOptions, Stage};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Names of the external binaries the compiler driver may invoke.
+const TOOLCHAIN_BINARIES: [&str; 5] = ["gcc", "clang", "as", "ld", "qbe"];
+
+/// Represents an external toolchain binary looked up on the `PATH`.
+#[derive(Debug, PartialEq)]
+pub struct ToolchainBinary {
+    /// The name of the binary, e.g. `gcc`.
+    pub name: &'static str,
+    /// The location of the binary, if it was found on the `PATH`.
+    pub path: Option<PathBuf>,
+    /// The first line printed by `<binary> --version`, if the binary could be executed.
+    pub version: Option<String>,
+}
+
+/// Represents the configuration the compiler driver resolves at startup.
+#[derive(Debug, PartialEq)]
+pub struct ResolvedConfig {
+    /// The platform the compiler is running on.
+    pub host: Target,
+    /// The target used when no `--target` is given.
+    pub default_target: Target,
+    /// The toolchain binaries the driver relies on.
+    pub toolchain: Vec<ToolchainBinary>,
+    /// The cargo features the compiler was built with.
+    pub features: Vec<&'static str>,
+    /// The stages executed by a compilation with the given options, in order.
+    pub pipeline: Vec<&'static str>,
+}
+
+impl ResolvedConfig {
+    /// Resolves the configuration from the build, the current environment, and the compile options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options`: The options of the compilation, which decide the stages it runs.
+    ///
+    /// # Returns
+    ///
+    /// A `ResolvedConfig` describing the host, the toolchain found on the `PATH`, the compiler
+    /// build, and the pipeline of the compilation.
+    pub fn resolve(options: &CompileOptions) -> Self {
+        ResolvedConfig {
+            host: Target::host(),
+            default_target: Target::default(),
+            toolchain: TOOLCHAIN_BINARIES
+                .iter()
+                .map(|name| resolve_toolchain_binary(name))
+                .collect(),
+            features: enabled_features(),
+            pipeline: pipeline(options),
+        }
+    }
+}
+
+impl fmt::Display for ResolvedConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Host target: {}", self.host)?;
+        writeln!(f, "Default target: {}", self.default_target)?;
+        writeln!(
+            f,
+            "Symbol naming: global symbols prefixed with {:?}, local labels prefixed with {:?}",
+            self.default_target.symbol_prefix(),
+            self.default_target.local_label_prefix()
+        )?;
+        writeln!(f, "Toolchain:")?;
+        for binary in self.toolchain.iter() {
+            match (&binary.path, &binary.version) {
+                (Some(path), Some(version)) => {
+                    writeln!(f, "  {}: {} ({})", binary.name, path.display(), version)?
+                }
+                (Some(path), None) => {
+                    writeln!(f, "  {}: {} (version unknown)", binary.name, path.display())?
+                }
+                (None, _) => writeln!(f, "  {}: not found", binary.name)?,
+            }
+        }
+        if self.features.is_empty() {
+            writeln!(f, "Enabled features: none")?;
+        } else {
+            writeln!(f, "Enabled features: {}", self.features.join(", "))?;
+        }
+        writeln!(f, "Pipeline: {}", self.pipeline.join(" -> "))
+    }
+}
+
+/// Returns the names of the stages a compilation with the given options runs, in order.
+///
+/// The stages are those of `Stage::ALL` up to the one the compilation stops after. A compilation
+/// that is not stopped early then emits the final code, either the assembly of the native backend
+/// or the QBE IL that `qbe` compiles.
+///
+/// # Arguments
+///
+/// * `options`: The options of the compilation.
+///
+/// # Returns
+///
+/// The names of the stages, e.g. `lex -> parse` for a compilation stopped after parsing.
+fn pipeline(options: &CompileOptions) -> Vec<&'static str> {
+    let last_stage = options.process_until.unwrap_or(Stage::Codegen);
+    let stage_count = Stage::ALL
+        .iter()
+        .position(|stage| *stage == last_stage)
+        .expect("Every stage is in Stage::ALL")
+        + 1;
+    let mut pipeline: Vec<&'static str> =
+        Stage::ALL[..stage_count].iter().map(Stage::name).collect();
+    if options.process_until.is_none() {
+        pipeline.push(match options.backend {
+            Backend::Native => "emit",
+            #[cfg(feature = "qbe")]
+            Backend::Qbe => "qbe",
+        });
+    }
+    pipeline
+}
+
+/// Returns the cargo features enabled for this build of the compiler.
+fn enabled_features() -> Vec<&'static str> {
+    [
+        ("x86_64", cfg!(feature = "x86_64")),
+        ("aarch64", cfg!(feature = "aarch64")),
+        ("qbe", cfg!(feature = "qbe")),
+        ("serde", cfg!(feature = "serde")),
+        ("repl", cfg!(feature = "repl")),
+        ("lsp", cfg!(feature = "lsp")),
+        ("cli", cfg!(feature = "cli")),
+        ("tracing", cfg!(feature = "tracing")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+/// Looks up a toolchain binary on the `PATH` and queries its version.
+///
+/// # Arguments
+///
+/// * `name`: The name of the binary to look up.
+///
+/// # Returns
+///
+/// A `ToolchainBinary` with the path and version filled in when available.
+fn resolve_toolchain_binary(name: &'static str) -> ToolchainBinary {
+    let path = std::env::var_os("PATH").and_then(|paths| find_binary(name, &paths));
+    let version = path.as_deref().and_then(query_version);
+    ToolchainBinary {
+        name,
+        path,
+        version,
+    }
+}
+
+/// Finds the first file named `name` in a `PATH`-style list of directories.
+///
+/// # Arguments
+///
+/// * `name`: The name of the binary to look up.
+/// * `paths`: The directories to search, in `PATH` format.
+///
+/// # Returns
+///
+/// The path to the binary, or `None` if no directory contains it.
+fn find_binary(name: &str, paths: &std::ffi::OsStr) -> Option<PathBuf> {
+    std::env::split_paths(paths)
+        .map(|directory| directory.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Returns the first line printed by `<binary> --version`.
+fn query_version(binary_path: &Path) -> Option<String> {
+    let output = Command::new(binary_path)
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stdout
+        .lines()
+        .chain(stderr.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_find_binary_missing() {
+        let paths = OsString::from("/nonexistent-directory");
+        assert_eq!(find_binary("gcc", &paths), None);
+    }
+
+    #[test]
+    fn test_display_lists_missing_binaries() {
+        let config = ResolvedConfig {
+            host: Target::X86_64_LINUX,
+            default_target: Target::X86_64_LINUX,
+            toolchain: vec![ToolchainBinary {
+                name: "clang",
+                path: None,
+                version: None,
+            }],
+            features: vec![],
+            pipeline: pipeline(&CompileOptions::default()),
+        };
+        let output = config.to_string();
+        assert!(output.contains("Host target: x86_64-unknown-linux-gnu\n"));
+        assert!(output.contains("  clang: not found\n"));
+        assert!(output.contains("Enabled features: none\n"));
+        assert!(output.contains(
+            "Pipeline: lex -> parse -> validate -> tacky -> optimize -> codegen -> emit\n"
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_follows_the_options() {
+        let stopped_early = CompileOptions {
+            process_until: Some(Stage::Validate),
+            ..Default::default()
+        };
+        assert_eq!(pipeline(&stopped_early), ["lex", "parse", "validate"]);
+        let full_pipeline = pipeline(&CompileOptions::default());
+        let stage_names: Vec<&str> = Stage::ALL.iter().map(Stage::name).collect();
+        assert_eq!(full_pipeline[..Stage::ALL.len()], stage_names);
+        assert_eq!(full_pipeline.last(), Some(&"emit"));
+    }
+}
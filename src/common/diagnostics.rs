@@ -0,0 +1,489 @@
+use crate::compiler::errors::CompilerError;
+use crate::compiler::semantic::warnings::SemanticWarning;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+
+pub use cmm_types::span::Span;
+
+/// Represents how severe a reported diagnostic is.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(rename_all = "lowercase"))]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Represents a kind of warning, which can be enabled or disabled by name, e.g. with
+/// `-Wunused-variable` or `-Wno-unused-variable`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Warning {
+    /// A division or a remainder by the constant zero.
+    DivisionByZero,
+    /// A local variable whose value is never read, which is disabled by default.
+    UnusedVariable,
+    /// Code after a jump that always leaves the function body.
+    UnreachableCode,
+}
+
+impl Warning {
+    /// Every kind of warning.
+    pub const ALL: [Warning; 3] = [
+        Warning::DivisionByZero,
+        Warning::UnusedVariable,
+        Warning::UnreachableCode,
+    ];
+
+    /// Returns the name of the warning, which the `-W` flags refer to it by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Warning::DivisionByZero => "division-by-zero",
+            Warning::UnusedVariable => "unused-variable",
+            Warning::UnreachableCode => "unreachable-code",
+        }
+    }
+
+    /// Looks up a warning by its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the warning, e.g. `unused-variable`.
+    ///
+    /// # Returns
+    ///
+    /// The `Warning` with the name, or `None` if no warning has it.
+    pub fn from_name(name: &str) -> Option<Warning> {
+        Warning::ALL
+            .into_iter()
+            .find(|warning| warning.name() == name)
+    }
+
+    /// Returns `true` if the warning is reported without a `-W` flag enabling it.
+    fn is_enabled_by_default(&self) -> bool {
+        *self != Warning::UnusedVariable
+    }
+}
+
+/// Represents which warnings a compilation reports, and whether they fail it, configured by the
+/// `-W` flags of the driver.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::{Warning, WarningOptions};
+/// let options = WarningOptions::from_flags(["all", "no-unreachable-code", "error"]);
+/// assert!(options.is_enabled(Warning::UnusedVariable));
+/// assert!(!options.is_enabled(Warning::UnreachableCode));
+/// assert!(options.as_errors);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct WarningOptions {
+    /// The warnings that are reported.
+    enabled: BTreeSet<Warning>,
+    /// Fails the compilation at the first reported warning, like `-Werror`.
+    pub as_errors: bool,
+}
+
+impl Default for WarningOptions {
+    fn default() -> Self {
+        WarningOptions {
+            enabled: Warning::ALL
+                .into_iter()
+                .filter(Warning::is_enabled_by_default)
+                .collect(),
+            as_errors: false,
+        }
+    }
+}
+
+impl WarningOptions {
+    /// Creates the options of a sequence of `-W` flags, applied in order to the default options,
+    /// see `apply_flag`.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags`: The values of the flags, without the `-W` prefix.
+    pub fn from_flags<'a>(flags: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut options = WarningOptions::default();
+        for flag in flags {
+            options.apply_flag(flag);
+        }
+        options
+    }
+
+    /// Applies the value of a `-W` flag.
+    ///
+    /// `all` enables every warning, `error` makes warnings fail the compilation, and the name of a
+    /// warning enables it. `unused` is a group, which enables `unused-variable`. A `no-` prefix
+    /// reverses the effect of a flag. Other flags, such as `extra`, are ignored for compatibility
+    /// with cc.
+    ///
+    /// # Arguments
+    ///
+    /// * `flag`: The value of the flag, without the `-W` prefix, e.g. `no-unused-variable`.
+    pub fn apply_flag(&mut self, flag: &str) {
+        let (name, enable) = match flag.strip_prefix("no-") {
+            Some(name) => (name, false),
+            None => (flag, true),
+        };
+        let warnings = match name {
+            "error" => {
+                self.as_errors = enable;
+                return;
+            }
+            "all" => Warning::ALL.to_vec(),
+            "unused" => vec![Warning::UnusedVariable],
+            name => Warning::from_name(name).into_iter().collect(),
+        };
+        for warning in warnings {
+            if enable {
+                self.enabled.insert(warning);
+            } else {
+                self.enabled.remove(&warning);
+            }
+        }
+    }
+
+    /// Returns `true` if the warning is reported.
+    pub fn is_enabled(&self, warning: Warning) -> bool {
+        self.enabled.contains(&warning)
+    }
+}
+
+/// Converts byte offsets of a source code into lines and columns.
+///
+/// Columns count bytes, which equals characters for the ASCII-only C-- tokens.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// The byte offset of the start of every line.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Indexes the lines of a source code.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: The source code the byte offsets refer to.
+    ///
+    /// # Returns
+    ///
+    /// A new `LineIndex`.
+    pub fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        LineIndex { line_starts }
+    }
+
+    /// Returns the span of a byte range of the source code.
+    ///
+    /// # Arguments
+    ///
+    /// * `range`: The byte range, whose end is exclusive.
+    ///
+    /// # Returns
+    ///
+    /// A `Span` whose end column is the column just after the last byte of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::common::diagnostics::{LineIndex, Span};
+    /// let line_index = LineIndex::new("int main(void) {\n    return 2;\n}");
+    /// assert_eq!(
+    ///     line_index.span(21..27),
+    ///     Span { start_line: 2, start_column: 5, end_line: 2, end_column: 11 }
+    /// );
+    /// ```
+    pub fn span(&self, range: Range<usize>) -> Span {
+        let (start_line, start_column) = self.position(range.start);
+        let (end_line, end_column) = self.position(range.end);
+        Span {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    /// Returns the 1-based line and column of a byte offset.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        (line, offset - self.line_starts[line - 1] + 1)
+    }
+}
+
+/// Represents a single message reported to the user about their source code.
+///
+/// Diagnostics serialize to JSON objects of the form
+/// `{"severity", "code", "warning", "message", "file", "span"}`, where `code`, `warning`, `file`,
+/// and `span` are `null` when unknown or not a warning. The human-readable form of a diagnostic,
+/// its `Display`, appends the start of its span to the message.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A stable identifier of the kind of diagnostic.
+    pub code: Option<String>,
+    /// The name of the warning, see `Warning::name`, also when it was turned into an error.
+    pub warning: Option<String>,
+    /// The description of the diagnostic, without its location.
+    pub message: String,
+    /// The source file the diagnostic refers to.
+    pub file: Option<PathBuf>,
+    /// The location in the source file the diagnostic refers to.
+    pub span: Option<Span>,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic without a code, file, or span.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The human-readable description of the error.
+    ///
+    /// # Returns
+    ///
+    /// A new `Diagnostic` with `Severity::Error`.
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: None,
+            warning: None,
+            message: message.into(),
+            file: None,
+            span: None,
+        }
+    }
+
+    /// Creates a warning diagnostic without a code, file, or span.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The human-readable description of the warning.
+    ///
+    /// # Returns
+    ///
+    /// A new `Diagnostic` with `Severity::Warning`.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message)
+        }
+    }
+
+    /// Sets the source file the diagnostic refers to.
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        match self.span {
+            Some(span) => write!(
+                f,
+                " at line {}, column {}",
+                span.start_line, span.start_column
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<&CompilerError> for Diagnostic {
+    fn from(error: &CompilerError) -> Self {
+        let warning = match error {
+            CompilerError::Warning(warning) => Some(warning.warning().name().to_string()),
+            _ => None,
+        };
+        Diagnostic {
+            code: Some(error.code().to_string()),
+            warning,
+            span: error.span(),
+            ..Diagnostic::error(error.message())
+        }
+    }
+}
+
+impl From<&SemanticWarning> for Diagnostic {
+    fn from(warning: &SemanticWarning) -> Self {
+        Diagnostic {
+            code: Some(warning.code().to_string()),
+            warning: Some(warning.warning().name().to_string()),
+            span: warning.span(),
+            ..Diagnostic::warning(warning.message())
+        }
+    }
+}
+
+/// Collects the diagnostics reported by the stages of compilations, in the order they were
+/// reported.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::{Diagnostic, Diagnostics};
+/// let mut diagnostics = Diagnostics::new();
+/// diagnostics.report(Diagnostic::warning("Unused variable"));
+/// assert!(!diagnostics.has_errors());
+/// diagnostics.report(Diagnostic::error("Unexpected token"));
+/// assert!(diagnostics.has_errors());
+/// assert_eq!(diagnostics.warnings().count(), 1);
+/// assert_eq!(diagnostics.take().len(), 2);
+/// assert!(diagnostics.as_slice().is_empty());
+/// ```
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Creates an empty collector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a diagnostic after the ones reported so far.
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Returns the diagnostics reported so far.
+    pub fn as_slice(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Removes and returns the diagnostics reported so far.
+    pub fn take(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// Returns the warnings reported so far.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+    }
+
+    /// Returns `true` if an error was reported.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+/// Serializes diagnostics into a JSON array.
+///
+/// # Arguments
+///
+/// * `diagnostics`: The diagnostics to serialize.
+///
+/// # Returns
+///
+/// A `String` containing the JSON array.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::{Diagnostic, diagnostics_to_json};
+/// let diagnostics = vec![Diagnostic::error("Unexpected token").with_file("main.c")];
+/// assert_eq!(
+///     diagnostics_to_json(&diagnostics),
+///     r#"[{"severity":"error","code":null,"warning":null,"message":"Unexpected token","file":"main.c","span":null}]"#
+/// );
+/// ```
+#[cfg(feature = "serde")]
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    serde_json::to_string(diagnostics).expect("Diagnostics always serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::lexer::errors::LexerError;
+
+    #[test]
+    fn test_diagnostic_from_compiler_error() {
+        let error = CompilerError::Lexer(LexerError::EmptyInputString);
+        let diagnostic = Diagnostic::from(&error);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.message, error.to_string());
+        assert_eq!(diagnostic.code.as_deref(), Some("E0005"));
+        assert_eq!(diagnostic.span, None);
+    }
+
+    #[test]
+    fn test_diagnostic_message_without_location() {
+        let span = Span {
+            start_line: 2,
+            start_column: 5,
+            end_line: 2,
+            end_column: 6,
+        };
+        let error = CompilerError::Lexer(LexerError::Located {
+            error: Box::new(LexerError::EmptyInputString),
+            span,
+        });
+        let diagnostic = Diagnostic::from(&error);
+        assert_eq!(diagnostic.message, "Lexer error: Input string is empty");
+        assert_eq!(diagnostic.span, Some(span));
+        // The human-readable form keeps the location, like the error itself.
+        assert_eq!(diagnostic.to_string(), error.to_string());
+    }
+
+    #[test]
+    fn test_warning_flags() {
+        let options = WarningOptions::default();
+        assert!(options.is_enabled(Warning::DivisionByZero));
+        assert!(!options.is_enabled(Warning::UnusedVariable));
+        assert!(!options.as_errors);
+
+        let options = WarningOptions::from_flags(["unused", "no-division-by-zero", "extra"]);
+        assert!(options.is_enabled(Warning::UnusedVariable));
+        assert!(!options.is_enabled(Warning::DivisionByZero));
+        assert!(options.is_enabled(Warning::UnreachableCode));
+
+        let options =
+            WarningOptions::from_flags(["error", "no-all", "unreachable-code", "no-error"]);
+        assert_eq!(
+            Warning::ALL.map(|warning| options.is_enabled(warning)),
+            [false, false, true]
+        );
+        assert!(!options.as_errors);
+    }
+
+    #[test]
+    fn test_line_index_spans() {
+        let line_index = LineIndex::new("int\n\nmain");
+        assert_eq!(
+            line_index.span(0..3),
+            Span {
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 4
+            }
+        );
+        let main = line_index.span(5..9);
+        assert_eq!((main.start_line, main.start_column), (3, 1));
+        assert_eq!(line_index.span(0..3).to(&main).end_line, 3);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_diagnostics_to_json_escapes_messages() {
+        let diagnostics = vec![Diagnostic::error("Unexpected \"}\"\n")];
+        assert_eq!(
+            diagnostics_to_json(&diagnostics),
+            r#"[{"severity":"error","code":null,"warning":null,"message":"Unexpected \"}\"\n","file":null,"span":null}]"#
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a CPU architecture known to the compiler driver.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Architecture {
+    /// 64-bit x86, also known as x64 or amd64.
+    X86_64,
+    /// 64-bit ARM, called arm64 by Apple toolchains.
+    Aarch64,
+}
+
+/// Represents an operating system known to the compiler driver.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OperatingSystem {
+    MacOs,
+    Linux,
+}
+
+/// Represents the platform that the generated assembly is built for.
+///
+/// The target decides the symbol naming conventions used during code emission, and the extra flags
+/// passed to the system toolchain when the target differs from the host.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Target {
+    pub architecture: Architecture,
+    pub operating_system: OperatingSystem,
+}
+
+impl Target {
+    /// x86-64 macOS, the platform the compiler was originally developed for.
+    pub const X86_64_MACOS: Target = Target {
+        architecture: Architecture::X86_64,
+        operating_system: OperatingSystem::MacOs,
+    };
+
+    /// x86-64 Linux with the GNU toolchain.
+    pub const X86_64_LINUX: Target = Target {
+        architecture: Architecture::X86_64,
+        operating_system: OperatingSystem::Linux,
+    };
+
+    /// Returns the platform the compiler itself is running on.
+    ///
+    /// # Returns
+    ///
+    /// The `Target` describing the host architecture and operating system.
+    pub fn host() -> Self {
+        let architecture = if cfg!(target_arch = "aarch64") {
+            Architecture::Aarch64
+        } else {
+            Architecture::X86_64
+        };
+        let operating_system = if cfg!(target_os = "macos") {
+            OperatingSystem::MacOs
+        } else {
+            OperatingSystem::Linux
+        };
+        Target {
+            architecture,
+            operating_system,
+        }
+    }
+
+    /// Returns the LLVM-style target triple of the target, e.g. `x86_64-apple-darwin`.
+    pub fn triple(&self) -> String {
+        let architecture = match self.architecture {
+            Architecture::X86_64 => "x86_64",
+            Architecture::Aarch64 => "aarch64",
+        };
+        match self.operating_system {
+            OperatingSystem::MacOs => format!("{}-apple-darwin", architecture),
+            OperatingSystem::Linux => format!("{}-unknown-linux-gnu", architecture),
+        }
+    }
+
+    /// Returns the prefix prepended to global symbol names, e.g. `_main` on macOS.
+    pub fn symbol_prefix(&self) -> &'static str {
+        match self.operating_system {
+            OperatingSystem::MacOs => "_",
+            OperatingSystem::Linux => "",
+        }
+    }
+
+    /// Returns the prefix of local labels, which the assembler keeps out of the symbol table.
+    pub fn local_label_prefix(&self) -> &'static str {
+        match self.operating_system {
+            OperatingSystem::MacOs => "L",
+            OperatingSystem::Linux => ".L",
+        }
+    }
+
+    /// Returns whether stack frames larger than a page must touch every page they allocate.
+    ///
+    /// Allocating a large frame with a single `subq` could jump over the guard page below the stack
+    /// into other memory. Apple toolchains therefore probe every page of a large frame, while Linux
+    /// grows the stack on demand and keeps a guard gap of 1 MiB below it.
+    pub fn requires_stack_probes(&self) -> bool {
+        match self.operating_system {
+            OperatingSystem::MacOs => true,
+            OperatingSystem::Linux => false,
+        }
+    }
+
+    /// Returns the gcc-compatible driver that preprocesses, assembles, and links for this target on
+    /// the given host, together with the flags it needs.
+    ///
+    /// On Apple hosts `gcc` is clang, which selects a macOS architecture with `-arch` and another
+    /// platform with `--target=<triple>`. Elsewhere `gcc` is GNU gcc, which only builds for the
+    /// platform it was configured for, so the cross compiler `<triple>-gcc` is used instead, e.g.
+    /// `aarch64-linux-gnu-gcc`.
+    ///
+    /// # Arguments
+    ///
+    /// * `host`: The platform the toolchain is running on.
+    ///
+    /// # Returns
+    ///
+    /// The name of the driver and its flags, which are empty when the target matches the host.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::common::target::{Architecture, Target};
+    /// let host = Target { architecture: Architecture::Aarch64, ..Target::X86_64_MACOS };
+    /// assert_eq!(
+    ///     Target::X86_64_MACOS.compiler_driver(&host),
+    ///     ("gcc".to_string(), vec!["-arch".to_string(), "x86_64".to_string()])
+    /// );
+    /// assert_eq!(
+    ///     Target::X86_64_LINUX.compiler_driver(&Target::X86_64_LINUX),
+    ///     ("gcc".to_string(), vec![])
+    /// );
+    /// ```
+    pub fn compiler_driver(&self, host: &Target) -> (String, Vec<String>) {
+        if self == host {
+            return ("gcc".to_string(), vec![]);
+        }
+        match (host.operating_system, self.operating_system) {
+            (OperatingSystem::MacOs, OperatingSystem::MacOs) => {
+                let architecture = match self.architecture {
+                    Architecture::X86_64 => "x86_64",
+                    Architecture::Aarch64 => "arm64",
+                };
+                (
+                    "gcc".to_string(),
+                    vec!["-arch".to_string(), architecture.to_string()],
+                )
+            }
+            (OperatingSystem::MacOs, OperatingSystem::Linux) => (
+                "gcc".to_string(),
+                vec![format!("--target={}", self.triple())],
+            ),
+            (OperatingSystem::Linux, _) => (format!("{}-gcc", self.gnu_triple()), vec![]),
+        }
+    }
+
+    /// Returns the target triple GNU toolchains name their cross compilers after, e.g.
+    /// `x86_64-linux-gnu`, which leaves out the vendor of a Linux triple.
+    fn gnu_triple(&self) -> String {
+        match self.operating_system {
+            OperatingSystem::MacOs => self.triple(),
+            OperatingSystem::Linux => self.triple().replace("-unknown-", "-"),
+        }
+    }
+}
+
+impl Default for Target {
+    /// The default target is x86-64 on the host operating system, as x86-64 is the only supported backend.
+    fn default() -> Self {
+        Target {
+            architecture: Architecture::X86_64,
+            ..Target::host()
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.triple())
+    }
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    /// Parses a target triple such as `x86_64-apple-darwin` or `x86_64-unknown-linux-gnu`.
+    fn from_str(triple: &str) -> Result<Self, Self::Err> {
+        let mut components = triple.split('-');
+        let architecture = match components.next() {
+            Some("x86_64") | Some("amd64") => Architecture::X86_64,
+            Some("aarch64") | Some("arm64") => Architecture::Aarch64,
+            _ => {
+                return Err(format!(
+                    "Unknown architecture in target triple '{}'",
+                    triple
+                ));
+            }
+        };
+        let operating_system = components
+            .find_map(|component| match component {
+                "darwin" | "macos" => Some(OperatingSystem::MacOs),
+                "linux" => Some(OperatingSystem::Linux),
+                _ => None,
+            })
+            .ok_or_else(|| format!("Unknown operating system in target triple '{}'", triple))?;
+        Ok(Target {
+            architecture,
+            operating_system,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target_triples() {
+        assert_eq!(
+            "x86_64-apple-darwin".parse::<Target>(),
+            Ok(Target::X86_64_MACOS)
+        );
+        assert_eq!(
+            "x86_64-unknown-linux-gnu".parse::<Target>(),
+            Ok(Target::X86_64_LINUX)
+        );
+        assert_eq!(
+            "arm64-apple-macos".parse::<Target>(),
+            Ok(Target {
+                architecture: Architecture::Aarch64,
+                operating_system: OperatingSystem::MacOs,
+            })
+        );
+        assert!("riscv64-unknown-linux-gnu".parse::<Target>().is_err());
+        assert!("x86_64-pc-windows-msvc".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn test_triple_round_trip() {
+        let target = Target::X86_64_LINUX;
+        assert_eq!(target.triple().parse::<Target>(), Ok(target));
+    }
+
+    #[test]
+    fn test_compiler_driver_for_non_apple_host() {
+        // GNU gcc has no `--target` flag, so a cross compiler builds for another platform.
+        let aarch64_linux = Target {
+            architecture: Architecture::Aarch64,
+            ..Target::X86_64_LINUX
+        };
+        assert_eq!(
+            aarch64_linux.compiler_driver(&Target::X86_64_LINUX),
+            ("aarch64-linux-gnu-gcc".to_string(), vec![])
+        );
+        assert_eq!(
+            Target::X86_64_MACOS.compiler_driver(&Target::X86_64_LINUX),
+            ("x86_64-apple-darwin-gcc".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_compiler_driver_for_apple_host() {
+        assert_eq!(
+            Target::X86_64_LINUX.compiler_driver(&Target::X86_64_MACOS),
+            (
+                "gcc".to_string(),
+                vec!["--target=x86_64-unknown-linux-gnu".to_string()]
+            )
+        );
+    }
+}
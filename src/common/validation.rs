@@ -1,6 +1,9 @@
 use anyhow::anyhow;
 use std::path::{Path, PathBuf};
 
+/// Output path that designates the standard output stream instead of a file.
+pub const STDOUT_PATH: &str = "-";
+
 /// Checks if a given path has a specific file extension.
 ///
 /// # Arguments
@@ -12,7 +15,7 @@ use std::path::{Path, PathBuf};
 ///
 /// `true` if the path has the specified extension, `false` otherwise.
 fn is_valid_path_extension(path: &Path, extension: &str) -> bool {
-    path.extension().map_or(false, |ext| ext == extension)
+    path.extension().is_some_and(|ext| ext == extension)
 }
 
 /// Internal helper for path validation across preprocessor, compiler, and linker stages.
@@ -37,38 +40,46 @@ fn validate_paths_internal(
         ));
     }
 
-    let final_output_path: PathBuf = match output_path {
-        Some(path) => {
-            if let Some(ext) = output_ext {
-                if !is_valid_path_extension(path, ext) {
-                    return Err(anyhow!("Output path must end with '.{}' extension", ext));
-                }
-            } else if path.extension().is_some() {
-                return Err(anyhow!(
-                    "Output path for linker should typically not have a file extension"
-                ));
-            }
-            path.to_path_buf()
-        }
-        None => {
-            let output_ext = match output_ext {
-                Some(ext) => ext,
-                None => "",
-            };
-            let path_buf = input_path.with_extension(output_ext);
-            if path_buf.exists() {
-                return Err(anyhow!(
-                    "Output file already exists: {}",
-                    path_buf.display()
-                ));
-            }
-            path_buf
-        }
+    validate_output_path(output_path, output_ext)?;
+    // Like cc, the default output next to the input is overwritten if it exists.
+    let final_output_path = match output_path {
+        Some(path) => path.to_path_buf(),
+        None => input_path.with_extension(output_ext.unwrap_or_default()),
     };
 
     Ok(((*input_path).to_path_buf(), final_output_path))
 }
 
+/// Validates an output path given on the command line, without looking at the file system.
+///
+/// The driver checks the final output before any stage runs, so that a wrong path is reported
+/// before intermediate files are written.
+///
+/// # Arguments
+///
+/// * `output_path`: The output path, or `None` for the default output, which is always valid.
+/// * `output_ext`: The extension the output must have, or `None` for an executable, which must not
+///   have one. `-` designates stdout and is accepted for any output.
+///
+/// # Returns
+///
+/// `Ok(())` if the path is valid, or an `anyhow::Error` describing the problem.
+pub fn validate_output_path(
+    output_path: Option<&Path>,
+    output_ext: Option<&str>,
+) -> anyhow::Result<()> {
+    match (output_path, output_ext) {
+        (Some(path), _) if path == Path::new(STDOUT_PATH) => Ok(()),
+        (Some(path), Some(ext)) if !is_valid_path_extension(path, ext) => {
+            Err(anyhow!("Output path must end with '.{}' extension", ext))
+        }
+        (Some(path), None) if path.extension().is_some() => Err(anyhow!(
+            "Output path for linker should typically not have a file extension"
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Validates preprocessor paths and their respective files.
 ///
 /// **Input Requirement:** Must have a `.c` extension.
@@ -93,7 +104,7 @@ pub fn validate_preprocessor_paths(
 /// Validates compiler paths and their respective files.
 ///
 /// **Input Requirement:** Must have an `.i` extension.
-/// **Output Requirement:** Must have an `.s` extension.
+/// **Output Requirement:** Must have an `.s` extension, or be `-` to designate stdout.
 ///
 /// # Arguments
 ///
@@ -132,6 +143,61 @@ pub fn validate_linker_paths(
     validate_paths_internal(input_path, "s", output_path, None)
 }
 
+/// Validates assembler paths and their respective files.
+///
+/// **Input Requirement:** Must have an `.s` extension.
+/// **Output Requirement:** Must have an `.o` extension.
+///
+/// # Arguments
+///
+/// * `input_path`: The path to the input compiled assembly file.
+/// * `output_path`: An optional path for the object file.
+///
+/// # Returns
+///
+/// Returns `Ok((PathBuf, PathBuf))` containing the validated input and output paths on success,
+/// or an `anyhow::Error` if validation fails.
+pub fn validate_assembler_paths(
+    input_path: &Path,
+    output_path: Option<&Path>,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    validate_paths_internal(input_path, "s", output_path, Some("o"))
+}
+
+/// Validates the paths of linking an object file into an executable.
+///
+/// **Input Requirement:** Must have an `.o` extension.
+/// **Output Requirement:** No file extension (the final executable).
+///
+/// # Arguments
+///
+/// * `input_path`: The path to the input object file.
+/// * `output_path`: An optional path for the final executable file.
+///
+/// # Returns
+///
+/// Returns `Ok((PathBuf, PathBuf))` containing the validated input and output paths on success,
+/// or an `anyhow::Error` if validation fails.
+pub fn validate_object_linker_paths(
+    input_path: &Path,
+    output_path: Option<&Path>,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    validate_paths_internal(input_path, "o", output_path, None)
+}
+
+/// Checks if a path designates an object file, which only needs to be linked.
+///
+/// # Arguments
+///
+/// * `path`: The path to check.
+///
+/// # Returns
+///
+/// `true` if the path has an `.o` extension.
+pub fn is_object_file(path: &Path) -> bool {
+    is_valid_path_extension(path, "o")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +208,22 @@ mod tests {
         assert!(is_valid_path_extension(path, "c"));
     }
 
+    #[test]
+    fn test_object_file() {
+        assert!(is_object_file(Path::new("build/main.o")));
+        assert!(!is_object_file(Path::new("build/main.c")));
+    }
+
+    #[test]
+    fn test_validate_output_path() {
+        assert!(validate_output_path(None, Some("o")).is_ok());
+        assert!(validate_output_path(Some(Path::new("build/main.o")), Some("o")).is_ok());
+        assert!(validate_output_path(Some(Path::new("build/main.s")), Some("o")).is_err());
+        assert!(validate_output_path(Some(Path::new("-")), Some("s")).is_ok());
+        assert!(validate_output_path(Some(Path::new("build/main")), None).is_ok());
+        assert!(validate_output_path(Some(Path::new("build/main.exe")), None).is_err());
+    }
+
     #[test]
     fn test_invalid_path_extension() {
         let path = Path::new("src/compiler_driver.rs");
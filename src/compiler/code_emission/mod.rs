@@ -1,285 +1,865 @@
+use crate::common::symbol::Symbol;
+use crate::common::target::{OperatingSystem, Target};
 use crate::compiler::code_gen::assembly_ast::{
-    AssemblyAst, AssemblyBinaryOperator, AssemblyConditionCode, AssemblyFunction,
-    AssemblyInstruction, AssemblyOperand, AssemblyRegister, AssemblyUnaryOperator,
+    AssemblyAst, AssemblyFunction, AssemblyInstruction, AssemblyOperand, AssemblyStaticConstant,
+    AssemblyStaticInit, AssemblyStaticVariable, AssemblyType, RegisterSize,
 };
+use crate::compiler::ir_gen::is_generated_name;
+use crate::compiler::parser::printer::print_string_literal;
+use std::fmt::{self, Write};
+use std::ops::Range;
+
+/// The typical length of an emitted instruction line in bytes, used to pre-size the output.
+const AVERAGE_INSTRUCTION_LENGTH: usize = 24;
+
+/// The page size, which the stack probes of targets requiring them allocate at a time.
+const STACK_PROBE_INTERVAL: i32 = 4096;
 
 /// Emits assembly code from an abstract syntax tree.
 ///
 /// # Arguments
 ///
 /// * `assembly_ast`: A reference to the `AssemblyAst` to be converted into assembly code.
+/// * `target`: The `Target` whose symbol naming conventions are used.
 ///
 /// # Returns
 ///
 /// A `String` containing the generated assembly code.
-pub fn emit_assembly(assembly_ast: &AssemblyAst) -> String {
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "emit", skip_all))]
+pub fn emit_assembly(assembly_ast: &AssemblyAst, target: &Target) -> String {
+    let AssemblyAst::Program { functions, .. } = assembly_ast;
+    let line_count: usize = functions
+        .iter()
+        .map(|AssemblyFunction::Function { instructions, .. }| instructions.len() + 4)
+        .sum();
+    let mut program_code = String::with_capacity((line_count + 4) * AVERAGE_INSTRUCTION_LENGTH);
+    write_assembly(&mut program_code, assembly_ast, target)
+        .expect("Writing to a String cannot fail");
+    program_code
+}
+
+/// Writes the assembly code of an abstract syntax tree to a writer.
+///
+/// The code is written piece by piece, without building intermediate strings.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the assembly code to.
+/// * `assembly_ast`: A reference to the `AssemblyAst` to be converted into assembly code.
+/// * `target`: The `Target` whose symbol naming conventions are used.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+pub fn write_assembly<W: Write>(
+    output: &mut W,
+    assembly_ast: &AssemblyAst,
+    target: &Target,
+) -> fmt::Result {
     match assembly_ast {
-        AssemblyAst::Program { function } => emit_function(function),
+        AssemblyAst::Program {
+            functions,
+            static_constants,
+            static_variables,
+        } => {
+            for function in functions {
+                write_function(output, function, target)?;
+            }
+            write_static_constants(output, static_constants, target)?;
+            write_static_variables(output, static_variables, target)?;
+            if target.operating_system == OperatingSystem::Linux {
+                // Marks the stack as non-executable, otherwise the GNU linker warns about it.
+                writeln!(output, "\t.section .note.GNU-stack,\"\",@progbits")?;
+            }
+            Ok(())
+        }
     }
 }
 
-/// Emits assembly code for a single function definition.
+/// Writes the assembly code of a single function definition.
 ///
 /// # Arguments
 ///
+/// * `output`: The writer to write the assembly code to.
 /// * `function`: A reference to the `AssemblyFunction` to be emitted.
+/// * `target`: The `Target` whose symbol naming conventions are used.
 ///
 /// # Returns
 ///
-/// A `String` representing the assembly code for the function.
-fn emit_function(function: &AssemblyFunction) -> String {
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_function<W: Write>(
+    output: &mut W,
+    function: &AssemblyFunction,
+    target: &Target,
+) -> fmt::Result {
     match function {
         AssemblyFunction::Function {
             identifier,
+            global,
             instructions,
         } => {
-            let asm_identifier = "_".to_string() + identifier;
-            let mut function_code = wrap_instruction(format!(".globl {}", asm_identifier).as_str());
-            function_code.push_str(&wrap_label(asm_identifier.as_str()));
-            let prologue = wrap_instruction("pushq %rbp") + &wrap_instruction("movq %rsp, %rbp");
-            function_code.push_str(&prologue);
+            write_function_prologue(output, identifier, *global, target)?;
             for instruction in instructions {
-                function_code.push_str(&format_instruction(instruction));
+                write_instruction(output, instruction, target)?;
             }
-            function_code
+            Ok(())
         }
     }
 }
 
-/// Emits assembly code for a single instruction.
+/// Writes the read-only constants of the program after its code.
+///
+/// The constants are named with local labels, so they stay out of the symbol table like the labels
+/// of the functions. Linux keeps them in `.rodata`, while macOS keeps null-terminated strings in
+/// the `__cstring` section of `__TEXT`, and `double`s in its `__literal8` or `__literal16`
+/// section by their alignment.
 ///
 /// # Arguments
 ///
-/// * `instruction`: A reference to the `Instruction` to be emitted.
+/// * `output`: The writer to write the assembly code to.
+/// * `static_constants`: The constants of the program.
+/// * `target`: The `Target` whose sections and local label naming convention are used.
 ///
 /// # Returns
 ///
-/// A `String` representing the assembly code for the instruction.
-fn format_instruction(instruction: &AssemblyInstruction) -> String {
-    match instruction {
-        AssemblyInstruction::Mov {
-            source,
-            destination,
-        } => wrap_instruction(
-            format!(
-                "movl {}, {}",
-                format_operand(source, false),
-                format_operand(destination, false)
-            )
-            .as_str(),
-        ),
-        AssemblyInstruction::Unary { op, operand } => wrap_instruction(
-            format!(
-                "{} {}",
-                format_unary_operator(op),
-                format_operand(operand, false)
-            )
-            .as_str(),
-        ),
-        AssemblyInstruction::Binary {
-            op,
-            source,
-            destination,
-        } => wrap_instruction(
-            format!(
-                "{} {}, {}",
-                format_binary_operator(op),
-                format_operand(source, false),
-                format_operand(destination, false)
-            )
-            .as_str(),
-        ),
-        AssemblyInstruction::Cmp { left, right } => wrap_instruction(
-            format!(
-                "cmpl {}, {}",
-                format_operand(left, false),
-                format_operand(right, false)
-            )
-            .as_str(),
-        ),
-        AssemblyInstruction::Idiv { operand } => {
-            wrap_instruction(format!("idivl {}", format_operand(operand, false)).as_str())
-        }
-        AssemblyInstruction::AllocateStack { stack_offset } => {
-            wrap_instruction(format!("subq ${}, %rsp", stack_offset).as_str())
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_static_constants<W: Write>(
+    output: &mut W,
+    static_constants: &[AssemblyStaticConstant],
+    target: &Target,
+) -> fmt::Result {
+    let label_prefix = target.local_label_prefix();
+    let mut current_section = None;
+    for static_constant in static_constants {
+        let section = match (&target.operating_system, static_constant) {
+            (OperatingSystem::Linux, _) => "\t.section .rodata",
+            (OperatingSystem::MacOs, AssemblyStaticConstant::String { .. }) => "\t.cstring",
+            (OperatingSystem::MacOs, AssemblyStaticConstant::Double { alignment: 16, .. }) => {
+                "\t.literal16"
+            }
+            (OperatingSystem::MacOs, AssemblyStaticConstant::Double { .. }) => "\t.literal8",
+        };
+        if current_section != Some(section) {
+            writeln!(output, "{}", section)?;
+            current_section = Some(section);
         }
-        AssemblyInstruction::Cdq => wrap_instruction("cdq"),
-        AssemblyInstruction::Jmp { label } => wrap_instruction(format!("jmp L{}", label).as_str()),
-        AssemblyInstruction::JmpCC { condition, label } => wrap_instruction(
-            format!("j{} L{}", transform_condition_code(condition), label,).as_str(),
-        ),
-        AssemblyInstruction::SetCC { condition, operand } => wrap_instruction(
-            format!(
-                "set{} {}",
-                transform_condition_code(condition),
-                format_operand(operand, true)
-            )
-            .as_str(),
-        ),
-        AssemblyInstruction::Label(label) => wrap_label(format!("L{}", label).as_str()),
-        AssemblyInstruction::Ret => {
-            let mut epilogue = wrap_instruction("movq %rbp, %rsp").to_string();
-            epilogue.push_str(wrap_instruction("popq %rbp").as_str());
-            epilogue.push_str(wrap_instruction("ret").as_str());
-            epilogue
+        match static_constant {
+            AssemblyStaticConstant::String { identifier, value } => {
+                writeln!(output, "{}{}:", label_prefix, identifier)?;
+                writeln!(output, "\t.asciz {}", print_string_literal(value))?;
+            }
+            AssemblyStaticConstant::Double {
+                identifier,
+                value,
+                alignment,
+            } => {
+                writeln!(output, "\t.balign {}", alignment)?;
+                writeln!(output, "{}{}:", label_prefix, identifier)?;
+                writeln!(output, "\t.double {:?}", value)?;
+            }
         }
     }
+    Ok(())
 }
 
-/// Converts a `UnaryOp` to its corresponding string representation.
+/// Writes the static variables of the program after its constants.
+///
+/// A variable whose initial value is all zero goes to the `.bss` section, which the loader fills
+/// with zeros, and the others to the `.data` section. Like functions, the variables are named by
+/// their symbols, and only the ones with external linkage are declared `.globl`.
 ///
 /// # Arguments
 ///
-/// * `op`: The `UnaryOp` to convert.
+/// * `output`: The writer to write the assembly code to.
+/// * `static_variables`: The static variables of the program.
+/// * `target`: The `Target` whose symbol naming conventions are used.
 ///
 /// # Returns
 ///
-/// A string representing the unary operation.
-fn format_unary_operator(op: &AssemblyUnaryOperator) -> String {
-    match op {
-        AssemblyUnaryOperator::Neg => "negl".to_string(),
-        AssemblyUnaryOperator::Not => "notl".to_string(),
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_static_variables<W: Write>(
+    output: &mut W,
+    static_variables: &[AssemblyStaticVariable],
+    target: &Target,
+) -> fmt::Result {
+    let mut current_section = None;
+    for static_variable in static_variables {
+        let AssemblyStaticVariable::Variable {
+            identifier,
+            global,
+            alignment,
+            initial_values,
+        } = static_variable;
+        let section = if static_variable.is_zero() {
+            "\t.bss"
+        } else {
+            "\t.data"
+        };
+        if current_section != Some(section) {
+            writeln!(output, "{}", section)?;
+            current_section = Some(section);
+        }
+        let prefix = if is_generated_name(identifier) {
+            target.local_label_prefix()
+        } else {
+            target.symbol_prefix()
+        };
+        if *global {
+            writeln!(output, "\t.globl {}{}", prefix, identifier)?;
+        }
+        writeln!(output, "\t.balign {}", alignment)?;
+        writeln!(output, "{}{}:", prefix, identifier)?;
+        if static_variable.is_zero() {
+            let size = initial_values
+                .iter()
+                .map(AssemblyStaticInit::size)
+                .sum::<usize>();
+            writeln!(output, "\t.zero {}", size)?;
+        } else {
+            for initial_value in initial_values {
+                writeln!(output, "\t{}", initial_value)?;
+            }
+        }
     }
+    Ok(())
 }
 
-/// Converts a `BinaryOp` to its corresponding string representation.
+/// Writes the symbol declaration and the stack frame setup of a function.
 ///
 /// # Arguments
 ///
-/// * `op`: The `BinaryOp` to convert.
+/// * `output`: The writer to write the assembly code to.
+/// * `identifier`: The name of the function.
+/// * `global`: Whether the function has external linkage, so its symbol is declared `.globl`.
+/// * `target`: The `Target` whose symbol naming conventions are used.
 ///
 /// # Returns
 ///
-/// A string representing the binary operation.
-fn format_binary_operator(op: &AssemblyBinaryOperator) -> String {
-    match op {
-        AssemblyBinaryOperator::Add => "addl".to_string(),
-        AssemblyBinaryOperator::Sub => "subl".to_string(),
-        AssemblyBinaryOperator::Mult => "imull".to_string(),
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_function_prologue<W: Write>(
+    output: &mut W,
+    identifier: &Symbol,
+    global: bool,
+    target: &Target,
+) -> fmt::Result {
+    let symbol_prefix = target.symbol_prefix();
+    if global {
+        writeln!(output, "\t.globl {}{}", symbol_prefix, identifier)?;
     }
+    writeln!(output, "{}{}:", symbol_prefix, identifier)?;
+    writeln!(output, "\tpushq %rbp")?;
+    writeln!(output, "\tmovq %rsp, %rbp")
 }
 
-/// Emits assembly code for an operand.
+/// Returns the lines of the emitted assembly code that every instruction is written to.
 ///
 /// # Arguments
 ///
-/// * `operand`: A reference to the `Operand` to be emitted.
-/// * `use_1byte_representation`: A boolean flag indicating whether to use the 1-byte register representation. 4-byte register representation is used, if false.
+/// * `assembly_ast`: A reference to the `AssemblyAst` whose assembly code is emitted.
+/// * `target`: The `Target` the assembly code is emitted for.
 ///
 /// # Returns
 ///
-/// A `String` representing the assembly code for the operand.
-fn format_operand(operand: &AssemblyOperand, use_1byte_representation: bool) -> String {
-    match operand {
-        AssemblyOperand::Imm(value) => format_immediate_value(value),
-        AssemblyOperand::Register(register) => format_register(register, use_1byte_representation),
-        AssemblyOperand::Stack(offset) => format_stack_offset(offset),
-        AssemblyOperand::Pseudo(_) => panic!(
-            "Pseudo registers should not be emitted to assembly. Have you converted them correctly to actual register addresses?"
-        ),
+/// The ranges of 1-based line numbers, one per instruction of the functions in program order. Most
+/// instructions are written to a single line, returns also tear down the stack frame.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::common::target::Target;
+/// # use cmm::compiler::code_emission::instruction_lines;
+/// # use cmm::compiler::code_gen::assembly_ast::{AssemblyAst, AssemblyFunction, AssemblyInstruction, AssemblyType};
+/// let assembly_ast = AssemblyAst::Program { functions: vec![AssemblyFunction::Function {
+///     identifier: Symbol::from("main"),
+///     global: true,
+///     instructions: vec![
+///         AssemblyInstruction::Cdq { assembly_type: AssemblyType::Longword },
+///         AssemblyInstruction::Ret,
+///     ],
+/// }], static_constants: Vec::new(), static_variables: Vec::new() };
+/// assert_eq!(instruction_lines(&assembly_ast, &Target::X86_64_LINUX), vec![5..6, 6..9]);
+/// ```
+pub fn instruction_lines(assembly_ast: &AssemblyAst, target: &Target) -> Vec<Range<usize>> {
+    let AssemblyAst::Program { functions, .. } = assembly_ast;
+    let mut scratch = String::new();
+    let mut next_line = 1;
+    let mut lines = Vec::new();
+    for AssemblyFunction::Function {
+        identifier,
+        global,
+        instructions,
+    } in functions
+    {
+        scratch.clear();
+        write_function_prologue(&mut scratch, identifier, *global, target)
+            .expect("Writing to a String cannot fail");
+        next_line += scratch.lines().count();
+        for instruction in instructions {
+            scratch.clear();
+            write_instruction(&mut scratch, instruction, target)
+                .expect("Writing to a String cannot fail");
+            let line_count = scratch.lines().count();
+            lines.push(next_line..next_line + line_count);
+            next_line += line_count;
+        }
     }
+    lines
 }
 
-/// Maps a `Register` enum variant to its assembly syntax representation.
+/// Writes the assembly code of a single instruction.
 ///
 /// # Arguments
 ///
-/// * `register`: The `Register` enum variant to convert.
-/// * `use_1byte_representation`: A boolean flag indicating whether to use the 1-byte register representation. 4-byte register representation is used, if false.
+/// * `output`: The writer to write the assembly code to.
+/// * `instruction`: A reference to the `Instruction` to be emitted.
+/// * `target`: The `Target` whose local label naming convention is used.
 ///
 /// # Returns
 ///
-/// A `String` representing the AT&T assembly syntax for the given register.
-fn format_register(register: &AssemblyRegister, use_1byte_representation: bool) -> String {
-    match register {
-        AssemblyRegister::AX => match use_1byte_representation {
-            true => "%al".to_string(),
-            false => "%eax".to_string(),
-        },
-        AssemblyRegister::DX => match use_1byte_representation {
-            true => "%dl".to_string(),
-            false => "%edx".to_string(),
-        },
-        AssemblyRegister::R10 => match use_1byte_representation {
-            true => "%r10b".to_string(),
-            false => "%r10d".to_string(),
-        },
-        AssemblyRegister::R11 => match use_1byte_representation {
-            true => "%r11b".to_string(),
-            false => "%r11d".to_string(),
-        },
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_instruction<W: Write>(
+    output: &mut W,
+    instruction: &AssemblyInstruction,
+    target: &Target,
+) -> fmt::Result {
+    let label_prefix = target.local_label_prefix();
+    match instruction {
+        AssemblyInstruction::Mov {
+            assembly_type,
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tmov{} {}, {}",
+            assembly_type.suffix(),
+            FormattedOperand::new(source, assembly_type.register_size(), target),
+            FormattedOperand::new(destination, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::Movsx {
+            source_type,
+            destination_type,
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tmovs{}{} {}, {}",
+            source_type.suffix(),
+            destination_type.suffix(),
+            FormattedOperand::new(source, source_type.register_size(), target),
+            FormattedOperand::new(destination, destination_type.register_size(), target)
+        ),
+        AssemblyInstruction::MovZeroExtend {
+            source_type,
+            destination_type,
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tmovz{}{} {}, {}",
+            source_type.suffix(),
+            destination_type.suffix(),
+            FormattedOperand::new(source, source_type.register_size(), target),
+            FormattedOperand::new(destination, destination_type.register_size(), target)
+        ),
+        AssemblyInstruction::Lea {
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tleaq {}, {}",
+            FormattedOperand::new(source, RegisterSize::Quadword, target),
+            FormattedOperand::new(destination, RegisterSize::Quadword, target)
+        ),
+        AssemblyInstruction::Unary {
+            op,
+            assembly_type,
+            operand,
+        } => writeln!(
+            output,
+            "\t{}{} {}",
+            op,
+            assembly_type.suffix(),
+            FormattedOperand::new(operand, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::Binary {
+            op,
+            assembly_type,
+            source,
+            destination,
+        } => {
+            if let (AssemblyType::Double, Some(mnemonic)) = (assembly_type, op.double_mnemonic()) {
+                return writeln!(
+                    output,
+                    "\t{} {}, {}",
+                    mnemonic,
+                    FormattedOperand::new(source, RegisterSize::Quadword, target),
+                    FormattedOperand::new(destination, RegisterSize::Quadword, target)
+                );
+            }
+            let source_size = if op.is_shift() {
+                RegisterSize::Byte
+            } else {
+                assembly_type.register_size()
+            };
+            writeln!(
+                output,
+                "\t{}{} {}, {}",
+                op,
+                assembly_type.suffix(),
+                FormattedOperand::new(source, source_size, target),
+                FormattedOperand::new(destination, assembly_type.register_size(), target)
+            )
+        }
+        AssemblyInstruction::Cmp {
+            assembly_type,
+            left,
+            right,
+        } => writeln!(
+            output,
+            "\t{}{} {}, {}",
+            match assembly_type {
+                AssemblyType::Double => "comi",
+                _ => "cmp",
+            },
+            assembly_type.suffix(),
+            FormattedOperand::new(left, assembly_type.register_size(), target),
+            FormattedOperand::new(right, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::Idiv {
+            assembly_type,
+            operand,
+        } => writeln!(
+            output,
+            "\tidiv{} {}",
+            assembly_type.suffix(),
+            FormattedOperand::new(operand, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::Div {
+            assembly_type,
+            operand,
+        } => writeln!(
+            output,
+            "\tdiv{} {}",
+            assembly_type.suffix(),
+            FormattedOperand::new(operand, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::AllocateStack { stack_offset }
+            if target.requires_stack_probes() && *stack_offset > STACK_PROBE_INTERVAL =>
+        {
+            write_stack_probes(output, *stack_offset)
+        }
+        AssemblyInstruction::AllocateStack { stack_offset } => {
+            writeln!(output, "\tsubq ${}, %rsp", stack_offset)
+        }
+        AssemblyInstruction::DeallocateStack { stack_offset } => {
+            writeln!(output, "\taddq ${}, %rsp", stack_offset)
+        }
+        AssemblyInstruction::Push(operand) => writeln!(
+            output,
+            "\tpushq {}",
+            FormattedOperand::new(operand, RegisterSize::Quadword, target)
+        ),
+        AssemblyInstruction::Call(identifier) => {
+            writeln!(output, "\tcall {}{}", target.symbol_prefix(), identifier)
+        }
+        AssemblyInstruction::Cdq {
+            assembly_type: AssemblyType::Byte,
+        } => writeln!(output, "\tcbtw"),
+        AssemblyInstruction::Cdq {
+            assembly_type: AssemblyType::Word,
+        } => writeln!(output, "\tcwtd"),
+        AssemblyInstruction::Cdq {
+            assembly_type: AssemblyType::Longword,
+        } => writeln!(output, "\tcdq"),
+        AssemblyInstruction::Cdq {
+            assembly_type: AssemblyType::Quadword | AssemblyType::Double,
+        } => writeln!(output, "\tcqo"),
+        AssemblyInstruction::Cvtsi2sd {
+            assembly_type,
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tcvtsi2sd{} {}, {}",
+            assembly_type.suffix(),
+            FormattedOperand::new(source, assembly_type.register_size(), target),
+            FormattedOperand::new(destination, RegisterSize::Quadword, target)
+        ),
+        AssemblyInstruction::Cvttsd2si {
+            assembly_type,
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\tcvttsd2si{} {}, {}",
+            assembly_type.suffix(),
+            FormattedOperand::new(source, RegisterSize::Quadword, target),
+            FormattedOperand::new(destination, assembly_type.register_size(), target)
+        ),
+        AssemblyInstruction::Jmp { label } => writeln!(output, "\tjmp {}{}", label_prefix, label),
+        AssemblyInstruction::JmpCC { condition, label } => {
+            writeln!(output, "\tj{} {}{}", condition, label_prefix, label)
+        }
+        AssemblyInstruction::SetCC { condition, operand } => writeln!(
+            output,
+            "\tset{} {}",
+            condition,
+            FormattedOperand::new(operand, RegisterSize::Byte, target)
+        ),
+        AssemblyInstruction::Label(label) => writeln!(output, "{}{}:", label_prefix, label),
+        AssemblyInstruction::Ret => {
+            writeln!(output, "\tmovq %rbp, %rsp")?;
+            writeln!(output, "\tpopq %rbp")?;
+            writeln!(output, "\tret")
+        }
     }
 }
 
-/// Formats an integer as an immediate value string, prefixed with a dollar sign.
+/// Writes the allocation of a stack frame that is larger than a page, touching every page.
+///
+/// The frame is allocated a page at a time in a loop, and each new page is written to before the
+/// next one is allocated, so that the guard page below the stack is always hit. The remaining size
+/// is counted down in %r11, which is free before the function body runs:
+///
+/// ```text
+///     movq $size, %r11
+/// 1:
+///     subq $4096, %rsp
+///     orq $0, (%rsp)
+///     subq $4096, %r11
+///     cmpq $4096, %r11
+///     ja 1b
+///     subq %r11, %rsp
+/// ```
 ///
 /// # Arguments
 ///
-/// * `value` - A reference to the i32 integer to format.
+/// * `output`: The writer to write the assembly code to.
+/// * `stack_offset`: The size of the stack frame in bytes, larger than `STACK_PROBE_INTERVAL`.
 ///
 /// # Returns
 ///
-/// A String representing the formatted immediate value (e.g., "$123").
-fn format_immediate_value(value: &i32) -> String {
-    format!("${}", value)
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_stack_probes<W: Write>(output: &mut W, stack_offset: i32) -> fmt::Result {
+    writeln!(output, "\tmovq ${}, %r11", stack_offset)?;
+    writeln!(output, "1:")?;
+    writeln!(output, "\tsubq ${}, %rsp", STACK_PROBE_INTERVAL)?;
+    writeln!(output, "\torq $0, (%rsp)")?;
+    writeln!(output, "\tsubq ${}, %r11", STACK_PROBE_INTERVAL)?;
+    writeln!(output, "\tcmpq ${}, %r11", STACK_PROBE_INTERVAL)?;
+    writeln!(output, "\tja 1b")?;
+    writeln!(output, "\tsubq %r11, %rsp")
 }
 
-/// Formats a stack offset as a string.
-///
-/// # Arguments
-///
-/// * `offset` - A reference to the i32 integer to format.
-///
-/// # Returns
-///
-/// A String representing the formatted stack offset (e.g., "-4(%rbp)").
-fn format_stack_offset(offset: &i32) -> String {
-    format!("{}(%rbp)", offset)
+/// Formats an operand in AT&T syntax when displayed, without allocating.
+struct FormattedOperand<'a> {
+    operand: &'a AssemblyOperand,
+    /// The part of a register operand the instruction operates on.
+    size: RegisterSize,
+    /// The target whose naming conventions name the data operands.
+    target: &'a Target,
 }
 
-/// Wraps a label with a colon and newline
-///
-/// # Arguments
-///
-/// * `label`: The string slice to format.
-///
-/// # Returns
-///
-/// A `String` containing the formatted label.
-///
-fn wrap_label(label: &str) -> String {
-    format!("{}:\n", label)
+impl<'a> FormattedOperand<'a> {
+    fn new(operand: &'a AssemblyOperand, size: RegisterSize, target: &'a Target) -> Self {
+        FormattedOperand {
+            operand,
+            size,
+            target,
+        }
+    }
 }
 
-///  Wraps an assembly instruction with a tab and newline
-///
-/// # Arguments
-///
-/// * `instruction` - The instruction string to format.
-///
-/// # Returns
-///
-/// A formatted string with a tab at the beginning and a newline at the end.
-fn wrap_instruction(instruction: &str) -> String {
-    format!("\t{}\n", instruction)
+impl fmt::Display for FormattedOperand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.operand {
+            AssemblyOperand::Imm(value) => write!(f, "${}", value),
+            AssemblyOperand::Register(register) => f.write_str(register.name(self.size)),
+            AssemblyOperand::Stack(offset) => write!(f, "{}(%rbp)", offset),
+            AssemblyOperand::Memory(register, offset) => {
+                write!(f, "{}({})", offset, register.name(RegisterSize::Quadword))
+            }
+            AssemblyOperand::Indexed(base, index, scale) => write!(
+                f,
+                "({},{},{})",
+                base.name(RegisterSize::Quadword),
+                index.name(RegisterSize::Quadword),
+                scale
+            ),
+            AssemblyOperand::Data(identifier) => {
+                // The generated names of the constants are local labels, while the variables named
+                // in the program are symbols.
+                let prefix = if is_generated_name(identifier) {
+                    self.target.local_label_prefix()
+                } else {
+                    self.target.symbol_prefix()
+                };
+                write!(f, "{}{}(%rip)", prefix, identifier)
+            }
+            AssemblyOperand::Pseudo(_) => panic!(
+                "Pseudo registers should not be emitted to assembly. Have you converted them correctly to actual register addresses?"
+            ),
+        }
+    }
 }
 
-/// Converts an `AssemblyConditionCode` enum variant into its corresponding string representation.
-///
-/// # Arguments
-///
-/// * `condition_code` - The `AssemblyConditionCode` enum variant to convert.
-///
-/// # Returns
-///
-/// A `String` representing the condition code (e.g., "NE", "EQ").
-fn transform_condition_code(condition_code: &AssemblyConditionCode) -> String {
-    match condition_code {
-        AssemblyConditionCode::E => "e".to_string(),
-        AssemblyConditionCode::NE => "ne".to_string(),
-        AssemblyConditionCode::G => "g".to_string(),
-        AssemblyConditionCode::L => "l".to_string(),
-        AssemblyConditionCode::GE => "ge".to_string(),
-        AssemblyConditionCode::LE => "le".to_string(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::code_gen::assembly_ast::{AssemblyBinaryOperator, AssemblyRegister};
+
+    fn emit_allocation(stack_offset: i32, target: &Target) -> String {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![AssemblyInstruction::AllocateStack { stack_offset }],
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let assembly_code = emit_assembly(&assembly_ast, target);
+        let allocation_lines: Vec<&str> = assembly_code
+            .lines()
+            .skip_while(|line| *line != "\tmovq %rsp, %rbp")
+            .skip(1)
+            .take_while(|line| !line.starts_with("\t.section"))
+            .collect();
+        allocation_lines.join("\n")
+    }
+
+    #[test]
+    fn test_large_frames_are_probed_on_macos() {
+        assert_eq!(
+            emit_allocation(4096, &Target::X86_64_MACOS),
+            "\tsubq $4096, %rsp"
+        );
+        assert_eq!(
+            emit_allocation(10000, &Target::X86_64_LINUX),
+            "\tsubq $10000, %rsp"
+        );
+        assert_eq!(
+            emit_allocation(10000, &Target::X86_64_MACOS),
+            "\tmovq $10000, %r11\n\
+             1:\n\
+             \tsubq $4096, %rsp\n\
+             \torq $0, (%rsp)\n\
+             \tsubq $4096, %r11\n\
+             \tcmpq $4096, %r11\n\
+             \tja 1b\n\
+             \tsubq %r11, %rsp"
+        );
+    }
+
+    #[test]
+    fn test_only_global_functions_are_declared_globl() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "helper".into(),
+                global: false,
+                instructions: vec![AssemblyInstruction::Ret],
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let assembly_code = emit_assembly(&assembly_ast, &Target::X86_64_MACOS);
+        assert!(assembly_code.starts_with("_helper:\n\tpushq %rbp\n"));
+        assert!(!assembly_code.contains(".globl"));
+    }
+
+    #[test]
+    fn test_string_constants_are_read_only() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Data("string.0".into()),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                }],
+            }],
+            static_constants: vec![AssemblyStaticConstant::String {
+                identifier: "string.0".into(),
+                value: b"a\tb".to_vec(),
+            }],
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            emit_assembly(&assembly_ast, &Target::X86_64_LINUX),
+            "\t.globl main\n\
+             main:\n\
+             \tpushq %rbp\n\
+             \tmovq %rsp, %rbp\n\
+             \tleaq .Lstring.0(%rip), %rax\n\
+             \t.section .rodata\n\
+             .Lstring.0:\n\
+             \t.asciz \"a\\tb\"\n\
+             \t.section .note.GNU-stack,\"\",@progbits\n"
+        );
+        let assembly_code = emit_assembly(&assembly_ast, &Target::X86_64_MACOS);
+        assert!(assembly_code.contains("\tleaq Lstring.0(%rip), %rax\n"));
+        assert!(assembly_code.ends_with("\t.cstring\nLstring.0:\n\t.asciz \"a\\tb\"\n"));
+    }
+
+    #[test]
+    fn test_double_instructions_and_constants() {
+        let register_xmm15 = AssemblyOperand::Register(AssemblyRegister::XMM15);
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Xor,
+                        assembly_type: AssemblyType::Double,
+                        source: AssemblyOperand::Data("double.1".into()),
+                        destination: register_xmm15.clone(),
+                    },
+                    AssemblyInstruction::Cmp {
+                        assembly_type: AssemblyType::Double,
+                        left: AssemblyOperand::Data("double.0".into()),
+                        right: register_xmm15.clone(),
+                    },
+                    AssemblyInstruction::Cvttsd2si {
+                        assembly_type: AssemblyType::Longword,
+                        source: register_xmm15,
+                        destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                    },
+                ],
+            }],
+            static_constants: vec![
+                AssemblyStaticConstant::String {
+                    identifier: "string.0".into(),
+                    value: Vec::new(),
+                },
+                AssemblyStaticConstant::Double {
+                    identifier: "double.0".into(),
+                    value: 2.5,
+                    alignment: 8,
+                },
+                AssemblyStaticConstant::Double {
+                    identifier: "double.1".into(),
+                    value: -0.0,
+                    alignment: 16,
+                },
+            ],
+            static_variables: Vec::new(),
+        };
+        let assembly_code = emit_assembly(&assembly_ast, &Target::X86_64_LINUX);
+        assert!(assembly_code.contains(
+            "\txorpd .Ldouble.1(%rip), %xmm15\n\
+             \tcomisd .Ldouble.0(%rip), %xmm15\n\
+             \tcvttsd2sil %xmm15, %eax\n"
+        ));
+        assert!(assembly_code.contains(
+            "\t.section .rodata\n\
+             .Lstring.0:\n\
+             \t.asciz \"\"\n\
+             \t.balign 8\n\
+             .Ldouble.0:\n\
+             \t.double 2.5\n\
+             \t.balign 16\n\
+             .Ldouble.1:\n\
+             \t.double -0.0\n"
+        ));
+        // macOS keeps the constants in the literal sections of their sizes.
+        let assembly_code = emit_assembly(&assembly_ast, &Target::X86_64_MACOS);
+        assert!(assembly_code.ends_with(
+            "\t.cstring\n\
+             Lstring.0:\n\
+             \t.asciz \"\"\n\
+             \t.literal8\n\
+             \t.balign 8\n\
+             Ldouble.0:\n\
+             \t.double 2.5\n\
+             \t.literal16\n\
+             \t.balign 16\n\
+             Ldouble.1:\n\
+             \t.double -0.0\n"
+        ));
+    }
+
+    #[test]
+    fn test_static_variables_are_writable() {
+        let static_variable =
+            |identifier: &str, global, initial_values| AssemblyStaticVariable::Variable {
+                identifier: identifier.into(),
+                global,
+                alignment: 4,
+                initial_values,
+            };
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![],
+            static_constants: vec![],
+            static_variables: vec![
+                static_variable(
+                    "counter",
+                    true,
+                    vec![AssemblyStaticInit::Integer {
+                        assembly_type: AssemblyType::Longword,
+                        value: 3,
+                    }],
+                ),
+                static_variable(
+                    "zeros",
+                    true,
+                    vec![
+                        AssemblyStaticInit::Integer {
+                            assembly_type: AssemblyType::Longword,
+                            value: 0,
+                        },
+                        AssemblyStaticInit::Zero(8),
+                    ],
+                ),
+                static_variable("hidden.0", false, vec![AssemblyStaticInit::Zero(4)]),
+            ],
+        };
+        // Variables of only zeros are left to the zero-filled section.
+        assert_eq!(
+            emit_assembly(&assembly_ast, &Target::X86_64_LINUX),
+            "\t.data\n\
+             \t.globl counter\n\
+             \t.balign 4\n\
+             counter:\n\
+             \t.long 3\n\
+             \t.bss\n\
+             \t.globl zeros\n\
+             \t.balign 4\n\
+             zeros:\n\
+             \t.zero 12\n\
+             \t.balign 4\n\
+             .Lhidden.0:\n\
+             \t.zero 4\n\
+             \t.section .note.GNU-stack,\"\",@progbits\n"
+        );
+        let assembly_code = emit_assembly(&assembly_ast, &Target::X86_64_MACOS);
+        assert!(assembly_code.contains("\t.globl _counter\n\t.balign 4\n_counter:\n"));
+        assert!(assembly_code.contains("\t.balign 4\nLhidden.0:\n"));
+    }
+
+    #[test]
+    fn test_call_instructions() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![
+                    AssemblyInstruction::Push(AssemblyOperand::Stack(-8)),
+                    AssemblyInstruction::Call("main".into()),
+                    AssemblyInstruction::DeallocateStack { stack_offset: 16 },
+                ],
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert!(
+            emit_assembly(&assembly_ast, &Target::X86_64_LINUX).contains(
+                "\tpushq -8(%rbp)\n\
+                 \tcall main\n\
+                 \taddq $16, %rsp\n"
+            )
+        );
+        assert!(emit_assembly(&assembly_ast, &Target::X86_64_MACOS).contains("\tcall _main\n"));
     }
 }
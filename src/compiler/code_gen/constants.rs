@@ -1 +1,32 @@
-pub const STACK_ADDRESS_OFFSET: i32 = 4;
+use super::assembly_ast::AssemblyRegister;
+
+/// The largest stack frame in bytes, limited by the 32-bit displacements that address the frame.
+pub const MAX_STACK_FRAME_SIZE: i64 = i32::MAX as i64;
+
+/// The typical number of assembly instructions generated per TACKY instruction.
+///
+/// Used to pre-size the instruction buffers, most TACKY instructions become a `Mov` and an operation.
+pub const ASSEMBLY_INSTRUCTIONS_PER_TACKY_INSTRUCTION: usize = 2;
+
+/// The registers passing the first integer arguments of a call, in order, as the System V ABI
+/// specifies. Further arguments are passed on the stack.
+pub const ARGUMENT_REGISTERS: [AssemblyRegister; 6] = [
+    AssemblyRegister::DI,
+    AssemblyRegister::SI,
+    AssemblyRegister::DX,
+    AssemblyRegister::CX,
+    AssemblyRegister::R8,
+    AssemblyRegister::R9,
+];
+
+/// The registers passing the first `double` arguments of a call, in order.
+pub const DOUBLE_ARGUMENT_REGISTERS: [AssemblyRegister; 8] = [
+    AssemblyRegister::XMM0,
+    AssemblyRegister::XMM1,
+    AssemblyRegister::XMM2,
+    AssemblyRegister::XMM3,
+    AssemblyRegister::XMM4,
+    AssemblyRegister::XMM5,
+    AssemblyRegister::XMM6,
+    AssemblyRegister::XMM7,
+];
@@ -1,3 +1,6 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::code_gen::constants;
+use crate::compiler::error_codes::{self, ErrorCode};
 use crate::compiler::ir_gen::tacky_ast::{TackyBinaryOperator, TackyUnaryOperator};
 use crate::compiler::lexer::tokens::TokenType;
 use std::error::Error;
@@ -37,6 +40,49 @@ pub enum CodegenError {
     ///
     /// * `operator`: The TACKY binary operator that could not be converted.
     UnsupportedBinaryOperatorConversion { operator: TackyBinaryOperator },
+    /// Raised when two labels of a function share a name, which the assembler would reject.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function containing the labels.
+    /// * `label`: The name of the label defined more than once.
+    DuplicateLabel { function: Symbol, label: Symbol },
+    /// Raised when a jump targets a label that is not defined in its function.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function containing the jump.
+    /// * `label`: The name of the missing label.
+    UndefinedLabel { function: Symbol, label: Symbol },
+    /// Raised when the stack frame of a function is too large to be addressed, which happens for
+    /// functions with hundreds of millions of temporaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function.
+    /// * `size`: The size of the stack frame in bytes.
+    StackFrameTooLarge { function: Symbol, size: i64 },
+    /// Raised when an instruction other than a move into a register has an immediate operand that
+    /// does not fit into 32 bits, which the assembler would reject or silently truncate.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function containing the instruction.
+    /// * `value`: The value of the immediate operand.
+    ImmediateOutOfRange { function: Symbol, value: i64 },
+}
+
+impl CodegenError {
+    /// Returns the stable `ErrorCode` of the error.
+    ///
+    /// Most code generation errors are bugs of the compiler, only the limits of the target are
+    /// reported with their own codes.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CodegenError::StackFrameTooLarge { .. } => error_codes::STACK_FRAME_TOO_LARGE,
+            _ => error_codes::INTERNAL_COMPILER_ERROR,
+        }
+    }
 }
 
 impl fmt::Display for CodegenError {
@@ -70,6 +116,36 @@ impl fmt::Display for CodegenError {
                     operator
                 )
             }
+            CodegenError::DuplicateLabel { function, label } => {
+                write!(
+                    f,
+                    "Codegen error: Label '{}' is defined more than once in function '{}'",
+                    label, function
+                )
+            }
+            CodegenError::UndefinedLabel { function, label } => {
+                write!(
+                    f,
+                    "Codegen error: Jump to undefined label '{}' in function '{}'",
+                    label, function
+                )
+            }
+            CodegenError::StackFrameTooLarge { function, size } => {
+                write!(
+                    f,
+                    "Codegen error: The stack frame of function '{}' needs {} bytes, but at most {} bytes can be addressed",
+                    function,
+                    size,
+                    constants::MAX_STACK_FRAME_SIZE
+                )
+            }
+            CodegenError::ImmediateOutOfRange { function, value } => {
+                write!(
+                    f,
+                    "Codegen error: Immediate value {} does not fit into 32 bits in function '{}'",
+                    value, function
+                )
+            }
         }
     }
 }
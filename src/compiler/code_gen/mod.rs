@@ -1,16 +1,23 @@
-pub mod assembly_ast;
 pub mod constants;
 pub mod errors;
+pub use cmm_types::assembly_ast;
 
+use crate::common::symbol::Symbol;
 use crate::compiler::ir_gen::tacky_ast::{
-    TackyAst, TackyBinaryOperator, TackyFunction, TackyInstruction, TackyUnaryOperator, TackyValue,
+    TackyAst, TackyBinaryOperator, TackyConstant, TackyFunction, TackyInstruction,
+    TackyStaticConstant, TackyStaticInit, TackyStaticVariable, TackyType, TackyUnaryOperator,
+    TackyValue,
 };
 use assembly_ast::{
     AssemblyAst, AssemblyBinaryOperator, AssemblyConditionCode, AssemblyFunction,
-    AssemblyInstruction, AssemblyOperand, AssemblyRegister, AssemblyUnaryOperator,
+    AssemblyInstruction, AssemblyOperand, AssemblyRegister, AssemblyStaticConstant,
+    AssemblyStaticInit, AssemblyStaticVariable, AssemblyType, AssemblyUnaryOperator,
 };
 use errors::CodegenError;
-use std::collections::HashMap;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::BTreeMap;
 
 /// Converts the entire TACKY IR into an assembly AST.
 ///
@@ -23,19 +30,23 @@ use std::collections::HashMap;
 /// # Examples
 ///
 /// ```
-/// # use cmm::compiler::ir_gen::tacky_ast::{TackyFunction, TackyAst, TackyInstruction, TackyUnaryOperator, TackyValue};
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyFunction, TackyAst, TackyConstant, TackyInstruction, TackyUnaryOperator, TackyValue};
+/// # use std::collections::BTreeMap;
 /// # use cmm::compiler::code_gen::convert_ast;
-/// # use cmm::compiler::code_gen::assembly_ast::{AssemblyAst, AssemblyFunction, AssemblyInstruction, AssemblyOperand, AssemblyUnaryOperator, AssemblyRegister};
+/// # use cmm::compiler::code_gen::assembly_ast::{AssemblyAst, AssemblyFunction, AssemblyInstruction, AssemblyOperand, AssemblyUnaryOperator, AssemblyRegister, AssemblyType};
 /// # use cmm::compiler::code_gen::errors::CodegenError;
-/// let identifier = "main".to_string();
-/// let temp_0_name = "tmp.0".to_string();
-/// let temp_1_name = "tmp.1".to_string();
-/// let tacky_ast = TackyAst::Program{ function: TackyFunction::Function {
+/// let identifier = Symbol::from("main");
+/// let temp_0_name = Symbol::from("tmp.0");
+/// let temp_1_name = Symbol::from("tmp.1");
+/// let tacky_ast = TackyAst::Program{ functions: vec![TackyFunction::Function {
 ///     identifier: identifier.clone(),
+///     global: true,
+///     parameters: vec![],
 ///     instructions: vec![
 ///         TackyInstruction::Unary {
 ///             operator: TackyUnaryOperator::Negate,
-///             source: TackyValue::Constant(1),
+///             source: TackyValue::Constant(TackyConstant::Int(1)),
 ///             destination: TackyValue::Variable(temp_0_name.clone()),
 ///         },
 ///         TackyInstruction::Unary {
@@ -43,69 +54,278 @@ use std::collections::HashMap;
 ///             source: TackyValue::Variable(temp_0_name),
 ///             destination: TackyValue::Variable(temp_1_name.clone()),
 ///         },
-///         TackyInstruction::Return { value: TackyValue::Variable(temp_1_name) },
+///         TackyInstruction::Return { value: Some(TackyValue::Variable(temp_1_name)) },
 ///     ],
-/// } };
+///     variable_types: BTreeMap::new(),
+/// }], static_constants: Vec::new(), static_variables: Vec::new() };
 /// let assembly_ast = convert_ast(tacky_ast)?;
-/// assert_eq!(assembly_ast, AssemblyAst::Program{ function: AssemblyFunction::Function {
+/// assert_eq!(assembly_ast, AssemblyAst::Program{ functions: vec![AssemblyFunction::Function {
 ///     identifier,
+///     global: true,
 ///     instructions: vec![
-///         AssemblyInstruction::AllocateStack { stack_offset: -8 },
+///         AssemblyInstruction::AllocateStack { stack_offset: 16 },
 ///         AssemblyInstruction::Mov {
+///             assembly_type: AssemblyType::Longword,
 ///             source: AssemblyOperand::Imm(1),
 ///             destination: AssemblyOperand::Stack(-4),
 ///         },
 ///         AssemblyInstruction::Unary {
 ///             op: AssemblyUnaryOperator::Neg,
+///             assembly_type: AssemblyType::Longword,
 ///             operand: AssemblyOperand::Stack(-4),
 ///         },
 ///         AssemblyInstruction::Mov {
+///             assembly_type: AssemblyType::Longword,
 ///             source: AssemblyOperand::Stack(-4),
 ///             destination: AssemblyOperand::Register(AssemblyRegister::R10),
 ///         },
 ///         AssemblyInstruction::Mov {
+///             assembly_type: AssemblyType::Longword,
 ///             source: AssemblyOperand::Register(AssemblyRegister::R10),
 ///             destination: AssemblyOperand::Stack(-8),
 ///         },
 ///         AssemblyInstruction::Unary {
 ///             op: AssemblyUnaryOperator::Not,
+///             assembly_type: AssemblyType::Longword,
 ///             operand: AssemblyOperand::Stack(-8),
 ///         },
 ///         AssemblyInstruction::Mov {
+///             assembly_type: AssemblyType::Longword,
 ///             source: AssemblyOperand::Stack(-8),
 ///             destination: AssemblyOperand::Register(AssemblyRegister::AX),
 ///         },
 ///         AssemblyInstruction::Ret,
 ///     ],
-/// } });
+/// }], static_constants: Vec::new(), static_variables: Vec::new() });
 /// # Ok::<(), CodegenError>(())
 /// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "codegen", skip_all))]
 pub fn convert_ast(tacky_ast: TackyAst) -> Result<AssemblyAst, CodegenError> {
     match tacky_ast {
-        TackyAst::Program { function } => Ok(AssemblyAst::Program {
-            function: convert_function(&function)?,
-        }),
+        TackyAst::Program {
+            functions,
+            static_constants,
+            static_variables,
+        } => {
+            #[cfg(feature = "tracing")]
+            let codegen_span = tracing::Span::current();
+            let statics = static_names(&static_variables);
+            // Every function collects the `double` constants it reads on its own, on its own
+            // thread with the `parallel` feature, and the constants are merged in the order of the
+            // functions, so the output does not depend on how the threads are scheduled.
+            let convert = |function| {
+                #[cfg(feature = "tracing")]
+                let _span = codegen_span.enter();
+                let mut function_constants = DoubleConstants::default();
+                convert_function(function, &statics, &mut function_constants)
+                    .map(|function| (function, function_constants))
+            };
+            #[cfg(feature = "parallel")]
+            let converted_functions: Vec<_> = functions.par_iter().map(convert).collect();
+            #[cfg(not(feature = "parallel"))]
+            let converted_functions: Vec<_> = functions.iter().map(convert).collect();
+            let mut double_constants = DoubleConstants::default();
+            let functions = converted_functions
+                .into_iter()
+                .map(|converted_function| {
+                    let (mut function, function_constants) = converted_function?;
+                    double_constants.merge(&mut function, function_constants);
+                    Ok(function)
+                })
+                .collect::<Result<_, CodegenError>>()?;
+            Ok(AssemblyAst::Program {
+                functions,
+                static_constants: static_constants
+                    .into_iter()
+                    .map(convert_static_constant)
+                    .chain(double_constants.constants)
+                    .collect(),
+                static_variables: static_variables
+                    .into_iter()
+                    .filter_map(convert_static_variable)
+                    .collect(),
+            })
+        }
+    }
+}
+
+/// Converts a TACKY static constant into the read-only constant of the assembly program.
+///
+/// # Arguments
+///
+/// * `static_constant` - The TACKY constant to convert.
+///
+/// # Returns
+///
+/// The `AssemblyStaticConstant` with the same name and value.
+fn convert_static_constant(static_constant: TackyStaticConstant) -> AssemblyStaticConstant {
+    match static_constant {
+        TackyStaticConstant::String { identifier, value } => {
+            AssemblyStaticConstant::String { identifier, value }
+        }
+    }
+}
+
+/// Returns the names of the static variables of a program, which the instructions access in the
+/// data of the program rather than in the stack frame.
+fn static_names(static_variables: &[TackyStaticVariable]) -> FxHashSet<Symbol> {
+    static_variables
+        .iter()
+        .map(|static_variable| static_variable.identifier().clone())
+        .collect()
+}
+
+/// Converts a TACKY static variable into a variable of the writable data of the assembly program.
+///
+/// # Arguments
+///
+/// * `static_variable` - The TACKY variable to convert.
+///
+/// # Returns
+///
+/// The `AssemblyStaticVariable` with the same name, linkage, alignment, and initial value, or
+/// `None` for a variable defined in another file.
+fn convert_static_variable(static_variable: TackyStaticVariable) -> Option<AssemblyStaticVariable> {
+    let TackyStaticVariable::Variable {
+        identifier,
+        global,
+        alignment,
+        initial_values,
+    } = static_variable
+    else {
+        return None;
+    };
+    Some(AssemblyStaticVariable::Variable {
+        identifier,
+        global,
+        alignment: alignment as i32,
+        initial_values: initial_values
+            .into_iter()
+            .map(|initial_value| match initial_value {
+                TackyStaticInit::Value(TackyConstant::Double(value)) => {
+                    AssemblyStaticInit::Double(value)
+                }
+                TackyStaticInit::Value(constant) => AssemblyStaticInit::Integer {
+                    assembly_type: convert_type(constant.constant_type()),
+                    value: constant.as_i64(),
+                },
+                TackyStaticInit::Zero(size) => AssemblyStaticInit::Zero(size),
+            })
+            .collect(),
+    })
+}
+
+/// Converts the TACKY IR into an assembly AST, recording where every assembly instruction comes from.
+///
+/// # Returns
+///
+/// A `Result` containing the generated `AssemblyAst` and, for every instruction of its functions, the
+/// index of the TACKY instruction it was generated from, or `None` for the stack allocation and the
+/// copies of the parameters. The instructions of the functions are counted one after another, in
+/// program order.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyAst, TackyConstant, TackyFunction, TackyInstruction, TackyValue};
+/// # use std::collections::BTreeMap;
+/// # use cmm::compiler::code_gen::convert_ast_with_origins;
+/// let tacky_ast = TackyAst::Program { functions: vec![TackyFunction::Function {
+///     identifier: Symbol::from("main"),
+///     global: true,
+///     parameters: vec![],
+///     instructions: vec![TackyInstruction::Return { value: Some(TackyValue::Constant(TackyConstant::Int(2))) }],
+///     variable_types: BTreeMap::new(),
+/// }], static_constants: Vec::new(), static_variables: Vec::new() };
+/// let (_, origins) = convert_ast_with_origins(tacky_ast).unwrap();
+/// assert_eq!(origins, vec![None, Some(0), Some(0)]);
+/// ```
+pub fn convert_ast_with_origins(
+    tacky_ast: TackyAst,
+) -> Result<(AssemblyAst, Vec<Option<usize>>), CodegenError> {
+    let TackyAst::Program {
+        functions,
+        static_variables,
+        ..
+    } = &tacky_ast;
+    let statics = static_names(static_variables);
+    let mut origins = Vec::new();
+    let mut tacky_index = 0;
+    for TackyFunction::Function {
+        identifier,
+        parameters,
+        instructions,
+        variable_types,
+        ..
+    } in functions
+    {
+        let mut parameter_instructions = parameter_copy_pass(parameters, variable_types);
+        pseudoregister_replacement_pass(&mut parameter_instructions, variable_types, &statics);
+        let parameter_instruction_count = instruction_fixup_pass(parameter_instructions).len();
+        origins.extend(std::iter::repeat_n(None, 1 + parameter_instruction_count));
+        for instruction in instructions {
+            // Every pass rewrites an instruction independently of its neighbours, so a TACKY
+            // instruction turns into as many assembly instructions on its own as it does within
+            // the function.
+            let mut asm_instructions = instruction_conversion_pass(
+                identifier,
+                std::slice::from_ref(instruction),
+                variable_types,
+                &mut DoubleConstants::default(),
+            )?;
+            pseudoregister_replacement_pass(&mut asm_instructions, variable_types, &statics);
+            let instruction_count = instruction_fixup_pass(asm_instructions).len();
+            origins.extend(std::iter::repeat_n(Some(tacky_index), instruction_count));
+            tacky_index += 1;
+        }
     }
+    let assembly_ast = convert_ast(tacky_ast)?;
+    Ok((assembly_ast, origins))
 }
 
 ///
 /// # Arguments
 ///
 ///  * `tacky_function` - A reference to the TACKY `TackyFunction` to convert.
+///  * `statics` - The names of the static variables of the program.
+///  * `double_constants` - The `double` constants of the program, which the constants read by the function are added to.
 ///
 /// # Returns
 ///
 /// A `Result` containing the generated `AssemblyFunction` on success,
 /// or a `CodegenError` on failure.
-fn convert_function(tacky_function: &TackyFunction) -> Result<AssemblyFunction, CodegenError> {
+fn convert_function(
+    tacky_function: &TackyFunction,
+    statics: &FxHashSet<Symbol>,
+    double_constants: &mut DoubleConstants,
+) -> Result<AssemblyFunction, CodegenError> {
     let function = match tacky_function {
         TackyFunction::Function {
             identifier,
+            global,
+            parameters,
             instructions: tacky_instructions,
-        } => AssemblyFunction::Function {
-            identifier: identifier.clone(),
-            instructions: convert_instructions(&tacky_instructions)?,
-        },
+            variable_types,
+        } => {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("function", name = %identifier).entered();
+            let instructions = convert_instructions(
+                identifier,
+                parameters,
+                tacky_instructions,
+                variable_types,
+                statics,
+                double_constants,
+            )?;
+            label_validation_pass(identifier, &instructions)?;
+            operand_validation_pass(identifier, &instructions)?;
+            AssemblyFunction::Function {
+                identifier: identifier.clone(),
+                global: *global,
+                instructions,
+            }
+        }
     };
     Ok(function)
 }
@@ -113,79 +333,295 @@ fn convert_function(tacky_function: &TackyFunction) -> Result<AssemblyFunction,
 /// Converts TACKY instructions into assembly instructions.
 ///
 /// Conversion takes four passes:
-/// 1. Convert TACKY instructions into assembly instructions. No physical registers are assigned during this pass.
+/// 1. Copy the parameters into pseudo registers, and convert TACKY instructions into assembly instructions. No physical registers are assigned during this pass.
 /// 2. Replace pseudo registers with physical registers in the assembly instructions.
-/// 3. Allocate stack space for local variables.
+/// 3. Allocate stack space for local variables, rounded up to 16 bytes, so that %rsp stays aligned
+///    to 16 bytes at every call, as the System V ABI requires.
 /// 4. Fixup instructions by allocating stack space and resolving memory-to-memory operations.
 ///
 /// # Arguments
 ///
+/// * `identifier` - The name of the function, reported in the errors.
+/// * `parameters` - The names of the parameters of the function, in order.
 /// * `tacky_instructions` - A reference to the TACKY `TackyInstruction`s to convert.
+/// * `variable_types` - The types of the variables of the function.
+/// * `statics` - The names of the static variables of the program.
+/// * `double_constants` - The `double` constants of the program, which the constants read by the function are added to.
 ///
 /// # Returns
 ///
 /// A `Result` containing a vector of `AssemblyInstruction`s on success,
 /// or a `CodegenError` on failure.
 fn convert_instructions(
-    tacky_instructions: &Vec<TackyInstruction>,
+    identifier: &Symbol,
+    parameters: &[Symbol],
+    tacky_instructions: &[TackyInstruction],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+    statics: &FxHashSet<Symbol>,
+    double_constants: &mut DoubleConstants,
 ) -> Result<Vec<AssemblyInstruction>, CodegenError> {
-    let mut asm_instructions = instruction_conversion_pass(tacky_instructions)?;
-    let stack_offset = pseudoregister_replacement_pass(&mut asm_instructions);
-    let mut final_instructions = vec![stack_allocation_pass(&stack_offset)];
-    let mut fixed_instructions = instruction_fixup_pass(&mut asm_instructions);
-    final_instructions.append(&mut fixed_instructions);
+    let mut asm_instructions = parameter_copy_pass(parameters, variable_types);
+    asm_instructions.extend(instruction_conversion_pass(
+        identifier,
+        tacky_instructions,
+        variable_types,
+        double_constants,
+    )?);
+    let frame_size =
+        pseudoregister_replacement_pass(&mut asm_instructions, variable_types, statics);
+    let frame_size = frame_size.saturating_add(15) & !15;
+    let stack_offset = stack_frame_size_check(identifier, frame_size)?;
+    let mut final_instructions = instruction_fixup_pass(asm_instructions);
+    final_instructions.insert(0, stack_allocation_pass(&stack_offset));
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        instructions = final_instructions.len(),
+        stack_offset,
+        "generated assembly instructions"
+    );
     Ok(final_instructions)
 }
 
+/// Checks that the stack frame of a function can be addressed by its instructions.
+///
+/// Stack locations are addressed with 32-bit displacements from %rbp, so a frame larger than
+/// `constants::MAX_STACK_FRAME_SIZE` would silently wrap the offsets of its last slots.
+///
+/// # Arguments
+///
+/// * `identifier` - The name of the function, reported in the error.
+/// * `frame_size` - The size of the stack frame in bytes.
+///
+/// # Returns
+///
+/// The frame size as the operand of `AssemblyInstruction::AllocateStack`, or
+/// `CodegenError::StackFrameTooLarge` if the frame is too large.
+fn stack_frame_size_check(identifier: &Symbol, frame_size: i64) -> Result<i32, CodegenError> {
+    match i32::try_from(frame_size) {
+        Ok(size) if size >= 0 && i64::from(size) <= constants::MAX_STACK_FRAME_SIZE => Ok(size),
+        _ => Err(CodegenError::StackFrameTooLarge {
+            function: identifier.clone(),
+            size: frame_size,
+        }),
+    }
+}
+
+/// Executes the label validation pass of the code generation pipeline.
+///
+/// Checks that every label of a function is defined once and that every jump targets a label of the
+/// function. Both are guaranteed for TACKY IR generated from C-- source code, but custom passes may
+/// break them, and the assembler would report the error without pointing to its cause.
+///
+/// # Arguments
+///
+/// * `identifier` - The name of the function, reported in the errors.
+/// * `instructions` - The assembly instructions of the function.
+///
+/// # Returns
+///
+/// A `Result` that is `Ok` if the labels are valid, or the `CodegenError` of the first invalid label.
+fn label_validation_pass(
+    identifier: &Symbol,
+    instructions: &[AssemblyInstruction],
+) -> Result<(), CodegenError> {
+    let mut labels = FxHashSet::default();
+    for instruction in instructions {
+        if let AssemblyInstruction::Label(label) = instruction
+            && !labels.insert(label)
+        {
+            return Err(CodegenError::DuplicateLabel {
+                function: identifier.clone(),
+                label: label.clone(),
+            });
+        }
+    }
+    for instruction in instructions {
+        if let AssemblyInstruction::Jmp { label } | AssemblyInstruction::JmpCC { label, .. } =
+            instruction
+            && !labels.contains(label)
+        {
+            return Err(CodegenError::UndefinedLabel {
+                function: identifier.clone(),
+                label: label.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Executes the parameter copy pass of the code generation pipeline.
+///
+/// Copies the parameters from where the caller passed them into their pseudo registers, so that
+/// the body reads them like any other variable. The first integer and `double` parameters arrive in
+/// the registers of the System V ABI, the others on the stack above the return address and the
+/// saved %rbp, at 16(%rbp), 24(%rbp), and so on.
+///
+/// # Arguments
+///
+/// * `parameters` - The names of the parameters, in order.
+/// * `variable_types` - The types of the parameters.
+///
+/// # Returns
+///
+/// The `Mov` instructions copying the parameters.
+fn parameter_copy_pass(
+    parameters: &[Symbol],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> Vec<AssemblyInstruction> {
+    let parameters: Vec<TackyValue> = parameters
+        .iter()
+        .cloned()
+        .map(TackyValue::Variable)
+        .collect();
+    let parameter_operand = |parameter: &TackyValue| match parameter {
+        TackyValue::Variable(name) => AssemblyOperand::Pseudo(name.clone()),
+        TackyValue::Constant(_) => unreachable!("Parameters are variables"),
+    };
+    let (register_parameters, stack_parameters) = classify_arguments(&parameters, variable_types);
+    let register_copies =
+        register_parameters
+            .into_iter()
+            .map(
+                |(assembly_type, parameter, register)| AssemblyInstruction::Mov {
+                    assembly_type,
+                    source: AssemblyOperand::Register(register),
+                    destination: parameter_operand(parameter),
+                },
+            );
+    let stack_copies =
+        (16..)
+            .step_by(8)
+            .zip(stack_parameters)
+            .map(
+                |(offset, (assembly_type, parameter))| AssemblyInstruction::Mov {
+                    assembly_type,
+                    source: AssemblyOperand::Stack(offset),
+                    destination: parameter_operand(parameter),
+                },
+            );
+    register_copies.chain(stack_copies).collect()
+}
+
 /// Executes the instruction conversion pass of the code generation pipeline.
 ///
 /// Replaces TACKY instructions with equivalent assembly instructions. One TACKY instruction may result in multiple assembly instructions.
+/// The size of every instruction follows the type of its operands. Division, comparisons, and right
+/// shifts also depend on whether their left operand is signed, the other instructions are the same
+/// for signed and unsigned integer types. `double`s are operated on with SSE instructions, and
+/// compared with `comisd`, which sets the flags like an unsigned comparison, see
+/// `push_double_comparison` for comparisons with NaN. Calls follow the System V ABI: the first
+/// arguments are passed in registers and the others are pushed onto the stack in reverse order,
+/// padded so that %rsp stays aligned to 16 bytes. Loads and stores move the pointer into %rax and
+/// access the memory at its address.
 ///
 /// # Arguments
 ///
+/// * `identifier` - The name of the function, which the labels of the conversions between
+///   `unsigned long` and `double` start with.
 /// * `tacky_instructions` - A reference to the TACKY `TackyInstruction`s to convert.
+/// * `variable_types` - The types of the variables of the instructions.
+/// * `double_constants` - The `double` constants read by the instructions, as there are no `double` immediates.
 ///
 /// # Returns
 ///
 /// A `Result` containing a vector of `AssemblyInstruction`s on success,
 /// or a `CodegenError` on failure.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn instruction_conversion_pass(
-    tacky_instructions: &Vec<TackyInstruction>,
+    identifier: &Symbol,
+    tacky_instructions: &[TackyInstruction],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+    double_constants: &mut DoubleConstants,
 ) -> Result<Vec<AssemblyInstruction>, CodegenError> {
-    let mut asm_instructions = vec![];
+    let mut asm_instructions = Vec::with_capacity(
+        tacky_instructions.len() * constants::ASSEMBLY_INSTRUCTIONS_PER_TACKY_INSTRUCTION,
+    );
+    let assembly_type = |value: &TackyValue| convert_type(value.value_type(variable_types));
+    // TACKY labels are a name followed by a number, so labels starting with the name of the
+    // function followed by the name of the conversion are distinct from them and from each other.
+    let mut conversion_count = 0;
+    let mut make_labels = |conversion: &str| {
+        let labels = (
+            Symbol::from(format!(
+                "{}.{}_out_of_range.{}",
+                identifier, conversion, conversion_count
+            )),
+            Symbol::from(format!(
+                "{}.{}_end.{}",
+                identifier, conversion, conversion_count
+            )),
+        );
+        conversion_count += 1;
+        labels
+    };
     for tacky_instruction in tacky_instructions.iter() {
         match tacky_instruction {
             TackyInstruction::Return { value } => {
-                let mov_instruction = AssemblyInstruction::Mov {
-                    source: convert_operand(&value),
-                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
-                };
-                let ret_instruction = AssemblyInstruction::Ret;
-                asm_instructions.push(mov_instruction);
-                asm_instructions.push(ret_instruction);
+                // A `void` function returns without setting a return register.
+                if let Some(value) = value {
+                    asm_instructions.push(AssemblyInstruction::Mov {
+                        assembly_type: assembly_type(value),
+                        source: convert_operand(double_constants, value),
+                        destination: AssemblyOperand::Register(return_register(assembly_type(
+                            value,
+                        ))),
+                    });
+                }
+                asm_instructions.push(AssemblyInstruction::Ret);
             }
             TackyInstruction::Unary {
                 operator,
                 source,
                 destination,
             } => match operator {
+                TackyUnaryOperator::Not if assembly_type(source) == AssemblyType::Double => {
+                    push_zero_comparison(
+                        AssemblyType::Double,
+                        convert_operand(double_constants, source),
+                        &mut asm_instructions,
+                    );
+                    push_double_comparison(
+                        &TackyBinaryOperator::Equal,
+                        assembly_type(destination),
+                        convert_operand(double_constants, destination),
+                        AssemblyRegister::R11,
+                        &mut asm_instructions,
+                    );
+                }
                 TackyUnaryOperator::Not => {
-                    let cmp_instruction = AssemblyInstruction::Cmp {
-                        left: AssemblyOperand::Imm(0),
-                        right: convert_operand(&source),
-                    };
+                    push_zero_comparison(
+                        assembly_type(source),
+                        convert_operand(double_constants, source),
+                        &mut asm_instructions,
+                    );
                     let mov_instruction = AssemblyInstruction::Mov {
+                        assembly_type: assembly_type(destination),
                         source: AssemblyOperand::Imm(0),
-                        destination: convert_operand(&destination),
+                        destination: convert_operand(double_constants, destination),
                     };
                     let set_instruction = AssemblyInstruction::SetCC {
                         condition: AssemblyConditionCode::E,
-                        operand: convert_operand(&destination),
+                        operand: convert_operand(double_constants, destination),
                     };
-                    asm_instructions.push(cmp_instruction);
                     asm_instructions.push(mov_instruction);
                     asm_instructions.push(set_instruction);
                 }
+                // There is no negation of `double`s, flipping the sign bit negates them.
+                TackyUnaryOperator::Negate if assembly_type(source) == AssemblyType::Double => {
+                    let mov_instruction = AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Double,
+                        source: convert_operand(double_constants, source),
+                        destination: convert_operand(double_constants, destination),
+                    };
+                    let xor_instruction = AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Xor,
+                        assembly_type: AssemblyType::Double,
+                        source: double_constants.operand(-0.0, 16),
+                        destination: convert_operand(double_constants, destination),
+                    };
+                    asm_instructions.push(mov_instruction);
+                    asm_instructions.push(xor_instruction);
+                }
                 TackyUnaryOperator::Complement | TackyUnaryOperator::Negate => {
                     let unary_op = match operator {
                         TackyUnaryOperator::Complement => AssemblyUnaryOperator::Not,
@@ -195,12 +631,14 @@ fn instruction_conversion_pass(
                         ),
                     };
                     let mov_instruction = AssemblyInstruction::Mov {
-                        source: convert_operand(&source),
-                        destination: convert_operand(&destination),
+                        assembly_type: assembly_type(source),
+                        source: convert_operand(double_constants, source),
+                        destination: convert_operand(double_constants, destination),
                     };
                     let unary_instruction = AssemblyInstruction::Unary {
                         op: unary_op,
-                        operand: convert_operand(&destination),
+                        assembly_type: assembly_type(source),
+                        operand: convert_operand(double_constants, destination),
                     };
                     asm_instructions.push(mov_instruction);
                     asm_instructions.push(unary_instruction);
@@ -212,57 +650,114 @@ fn instruction_conversion_pass(
                 source2,
                 destination,
             } => {
+                let operand_type = assembly_type(source1);
+                // `comisd` sets the flags like an unsigned comparison.
+                let is_signed = source1.value_type(variable_types).is_signed()
+                    && operand_type != AssemblyType::Double;
                 match operator {
                     TackyBinaryOperator::Add
                     | TackyBinaryOperator::Subtract
-                    | TackyBinaryOperator::Multiply => {
+                    | TackyBinaryOperator::Multiply
+                    | TackyBinaryOperator::BitwiseAnd
+                    | TackyBinaryOperator::BitwiseOr
+                    | TackyBinaryOperator::BitwiseXor
+                    | TackyBinaryOperator::LeftShift
+                    | TackyBinaryOperator::RightShift => {
                         let binary_op = match operator {
                             TackyBinaryOperator::Add => AssemblyBinaryOperator::Add,
                             TackyBinaryOperator::Subtract => AssemblyBinaryOperator::Sub,
                             TackyBinaryOperator::Multiply => AssemblyBinaryOperator::Mult,
+                            TackyBinaryOperator::BitwiseAnd => AssemblyBinaryOperator::And,
+                            TackyBinaryOperator::BitwiseOr => AssemblyBinaryOperator::Or,
+                            TackyBinaryOperator::BitwiseXor => AssemblyBinaryOperator::Xor,
+                            TackyBinaryOperator::LeftShift => AssemblyBinaryOperator::Sal,
+                            TackyBinaryOperator::RightShift if is_signed => {
+                                AssemblyBinaryOperator::Sar
+                            }
+                            TackyBinaryOperator::RightShift => AssemblyBinaryOperator::Shr,
                             _ => unreachable!(
                                 "The other binary operators should have been handled by the previous match arm"
                             ),
                         };
                         let mov_instruction = AssemblyInstruction::Mov {
-                            source: convert_operand(&source1),
-                            destination: convert_operand(&destination),
+                            assembly_type: operand_type,
+                            source: convert_operand(double_constants, source1),
+                            destination: convert_operand(double_constants, destination),
                         };
                         let binary_instruction = AssemblyInstruction::Binary {
                             op: binary_op,
-                            source: convert_operand(&source2),
-                            destination: convert_operand(&destination),
+                            assembly_type: operand_type,
+                            source: convert_operand(double_constants, source2),
+                            destination: convert_operand(double_constants, destination),
                         };
                         asm_instructions.push(mov_instruction);
                         asm_instructions.push(binary_instruction);
                     }
+                    TackyBinaryOperator::Divide if operand_type == AssemblyType::Double => {
+                        let mov_instruction = AssemblyInstruction::Mov {
+                            assembly_type: AssemblyType::Double,
+                            source: convert_operand(double_constants, source1),
+                            destination: convert_operand(double_constants, destination),
+                        };
+                        let div_instruction = AssemblyInstruction::Binary {
+                            op: AssemblyBinaryOperator::DivDouble,
+                            assembly_type: AssemblyType::Double,
+                            source: convert_operand(double_constants, source2),
+                            destination: convert_operand(double_constants, destination),
+                        };
+                        asm_instructions.push(mov_instruction);
+                        asm_instructions.push(div_instruction);
+                    }
                     TackyBinaryOperator::Divide | TackyBinaryOperator::Remainder => {
                         let mov_to_reg_instruction = AssemblyInstruction::Mov {
-                            source: convert_operand(&source1),
+                            assembly_type: operand_type,
+                            source: convert_operand(double_constants, source1),
                             destination: AssemblyOperand::Register(AssemblyRegister::AX),
                         };
-                        let cdq_instruction = AssemblyInstruction::Cdq;
-                        let idiv_instruction = AssemblyInstruction::Idiv {
-                            operand: convert_operand(&source2),
+                        // A signed dividend is sign extended into %edx, an unsigned one zero extended.
+                        let (extend_instruction, div_instruction) = if is_signed {
+                            (
+                                AssemblyInstruction::Cdq {
+                                    assembly_type: operand_type,
+                                },
+                                AssemblyInstruction::Idiv {
+                                    assembly_type: operand_type,
+                                    operand: convert_operand(double_constants, source2),
+                                },
+                            )
+                        } else {
+                            (
+                                AssemblyInstruction::Mov {
+                                    assembly_type: operand_type,
+                                    source: AssemblyOperand::Imm(0),
+                                    destination: AssemblyOperand::Register(AssemblyRegister::DX),
+                                },
+                                AssemblyInstruction::Div {
+                                    assembly_type: operand_type,
+                                    operand: convert_operand(double_constants, source2),
+                                },
+                            )
                         };
                         let mov_from_reg_instruction = match operator {
                             // Quotient is stored in %eax
                             TackyBinaryOperator::Divide => AssemblyInstruction::Mov {
+                                assembly_type: operand_type,
                                 source: AssemblyOperand::Register(AssemblyRegister::AX),
-                                destination: convert_operand(&destination),
+                                destination: convert_operand(double_constants, destination),
                             },
                             // Remainder is stored in %edx
                             TackyBinaryOperator::Remainder => AssemblyInstruction::Mov {
+                                assembly_type: operand_type,
                                 source: AssemblyOperand::Register(AssemblyRegister::DX),
-                                destination: convert_operand(&destination),
+                                destination: convert_operand(double_constants, destination),
                             },
                             _ => unreachable!(
                                 "The other binary operators should have been handled by the previous match arm"
                             ),
                         };
                         asm_instructions.push(mov_to_reg_instruction);
-                        asm_instructions.push(cdq_instruction);
-                        asm_instructions.push(idiv_instruction);
+                        asm_instructions.push(extend_instruction);
+                        asm_instructions.push(div_instruction);
                         asm_instructions.push(mov_from_reg_instruction);
                     }
                     TackyBinaryOperator::Equal
@@ -270,18 +765,51 @@ fn instruction_conversion_pass(
                     | TackyBinaryOperator::GreaterThan
                     | TackyBinaryOperator::LessThan
                     | TackyBinaryOperator::GreaterThanEqual
+                    | TackyBinaryOperator::LessThanEqual
+                        if operand_type == AssemblyType::Double =>
+                    {
+                        // `a < b` is compared as `b > a`, which is false for unordered operands.
+                        let (left, right, operator) = match operator {
+                            TackyBinaryOperator::LessThan => {
+                                (source1, source2, &TackyBinaryOperator::GreaterThan)
+                            }
+                            TackyBinaryOperator::LessThanEqual => {
+                                (source1, source2, &TackyBinaryOperator::GreaterThanEqual)
+                            }
+                            operator => (source2, source1, operator),
+                        };
+                        asm_instructions.push(AssemblyInstruction::Cmp {
+                            assembly_type: AssemblyType::Double,
+                            left: convert_operand(double_constants, left),
+                            right: convert_operand(double_constants, right),
+                        });
+                        push_double_comparison(
+                            operator,
+                            assembly_type(destination),
+                            convert_operand(double_constants, destination),
+                            AssemblyRegister::R11,
+                            &mut asm_instructions,
+                        );
+                    }
+                    TackyBinaryOperator::Equal
+                    | TackyBinaryOperator::NotEqual
+                    | TackyBinaryOperator::GreaterThan
+                    | TackyBinaryOperator::LessThan
+                    | TackyBinaryOperator::GreaterThanEqual
                     | TackyBinaryOperator::LessThanEqual => {
                         let cmp_instruction = AssemblyInstruction::Cmp {
-                            left: convert_operand(&source2),
-                            right: convert_operand(&source1),
+                            assembly_type: operand_type,
+                            left: convert_operand(double_constants, source2),
+                            right: convert_operand(double_constants, source1),
                         };
                         let mov_instruction = AssemblyInstruction::Mov {
+                            assembly_type: assembly_type(destination),
                             source: AssemblyOperand::Imm(0),
-                            destination: convert_operand(&destination),
+                            destination: convert_operand(double_constants, destination),
                         };
                         let set_instruction = AssemblyInstruction::SetCC {
-                            condition: convert_condition_code(&operator)?,
-                            operand: convert_operand(&destination),
+                            condition: convert_condition_code(operator, is_signed)?,
+                            operand: convert_operand(double_constants, destination),
                         };
                         asm_instructions.push(cmp_instruction);
                         asm_instructions.push(mov_instruction);
@@ -294,8 +822,73 @@ fn instruction_conversion_pass(
                 destination,
             } => {
                 let mov_instruction = AssemblyInstruction::Mov {
-                    source: convert_operand(&source),
-                    destination: convert_operand(&destination),
+                    assembly_type: assembly_type(destination),
+                    source: convert_operand(double_constants, source),
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(mov_instruction);
+            }
+            TackyInstruction::SignExtend {
+                source,
+                destination,
+            } => {
+                let movsx_instruction = AssemblyInstruction::Movsx {
+                    source_type: assembly_type(source),
+                    destination_type: assembly_type(destination),
+                    source: convert_operand(double_constants, source),
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(movsx_instruction);
+            }
+            TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            } if assembly_type(source) == AssemblyType::Byte => {
+                let movzx_instruction = AssemblyInstruction::MovZeroExtend {
+                    source_type: AssemblyType::Byte,
+                    destination_type: assembly_type(destination),
+                    source: convert_operand(double_constants, source),
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(movzx_instruction);
+            }
+            TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            } => {
+                // Writing the lower 4 bytes of a register clears its upper 4 bytes.
+                let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+                let mov_to_reg_instruction = AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: convert_operand(double_constants, source),
+                    destination: register_r11.clone(),
+                };
+                let mov_from_reg_instruction = AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11,
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(mov_to_reg_instruction);
+                asm_instructions.push(mov_from_reg_instruction);
+            }
+            TackyInstruction::Truncate {
+                source,
+                destination,
+            } => {
+                // The lower bytes of the source are moved, a constant is truncated right away.
+                let source = match source {
+                    TackyValue::Constant(constant) => convert_operand(
+                        double_constants,
+                        &TackyValue::Constant(
+                            constant.convert_to(destination.value_type(variable_types)),
+                        ),
+                    ),
+                    source => convert_operand(double_constants, source),
+                };
+                let mov_instruction = AssemblyInstruction::Mov {
+                    assembly_type: assembly_type(destination),
+                    source,
+                    destination: convert_operand(double_constants, destination),
                 };
                 asm_instructions.push(mov_instruction);
             }
@@ -305,187 +898,952 @@ fn instruction_conversion_pass(
                 };
                 asm_instructions.push(jmp_instruction);
             }
+            // NaN is not zero, so a `double` jumps if it is equal to zero and ordered.
+            TackyInstruction::JumpIfZero { condition, target }
+                if assembly_type(condition) == AssemblyType::Double =>
+            {
+                let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+                push_zero_comparison(
+                    AssemblyType::Double,
+                    convert_operand(double_constants, condition),
+                    &mut asm_instructions,
+                );
+                push_double_comparison(
+                    &TackyBinaryOperator::Equal,
+                    AssemblyType::Longword,
+                    register_r11.clone(),
+                    AssemblyRegister::R10,
+                    &mut asm_instructions,
+                );
+                push_zero_comparison(AssemblyType::Longword, register_r11, &mut asm_instructions);
+                asm_instructions.push(AssemblyInstruction::JmpCC {
+                    condition: AssemblyConditionCode::NE,
+                    label: target.clone(),
+                });
+            }
             TackyInstruction::JumpIfZero { condition, target } => {
-                let cmp_instruction = AssemblyInstruction::Cmp {
-                    left: AssemblyOperand::Imm(0),
-                    right: convert_operand(&condition),
-                };
+                push_zero_comparison(
+                    assembly_type(condition),
+                    convert_operand(double_constants, condition),
+                    &mut asm_instructions,
+                );
                 let jmp_instruction = AssemblyInstruction::JmpCC {
                     condition: AssemblyConditionCode::E,
                     label: target.clone(),
                 };
-                asm_instructions.push(cmp_instruction);
                 asm_instructions.push(jmp_instruction);
             }
             TackyInstruction::JumpIfNotZero { condition, target } => {
-                let cmp_instruction = AssemblyInstruction::Cmp {
-                    left: AssemblyOperand::Imm(0),
-                    right: convert_operand(&condition),
-                };
+                push_zero_comparison(
+                    assembly_type(condition),
+                    convert_operand(double_constants, condition),
+                    &mut asm_instructions,
+                );
                 let jmp_instruction = AssemblyInstruction::JmpCC {
                     condition: AssemblyConditionCode::NE,
                     label: target.clone(),
                 };
-                asm_instructions.push(cmp_instruction);
                 asm_instructions.push(jmp_instruction);
+                // NaN is not zero either.
+                if assembly_type(condition) == AssemblyType::Double {
+                    asm_instructions.push(AssemblyInstruction::JmpCC {
+                        condition: AssemblyConditionCode::P,
+                        label: target.clone(),
+                    });
+                }
             }
             TackyInstruction::Label(label) => {
                 let label_instruction = AssemblyInstruction::Label(label.clone());
                 asm_instructions.push(label_instruction);
             }
+            TackyInstruction::GetAddress {
+                source,
+                destination,
+            } => {
+                // Variables live in the stack frame, every other source is a static constant.
+                let source = if variable_types.contains_key(source) {
+                    AssemblyOperand::Pseudo(source.clone())
+                } else {
+                    AssemblyOperand::Data(source.clone())
+                };
+                let lea_instruction = AssemblyInstruction::Lea {
+                    source,
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(lea_instruction);
+            }
+            TackyInstruction::Load {
+                source_pointer,
+                destination,
+            } => {
+                let register_ax = AssemblyRegister::AX;
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: convert_operand(double_constants, source_pointer),
+                    destination: AssemblyOperand::Register(register_ax.clone()),
+                });
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: assembly_type(destination),
+                    source: AssemblyOperand::Memory(register_ax, 0),
+                    destination: convert_operand(double_constants, destination),
+                });
+            }
+            TackyInstruction::Store {
+                source,
+                destination_pointer,
+            } => {
+                let register_ax = AssemblyRegister::AX;
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: convert_operand(double_constants, destination_pointer),
+                    destination: AssemblyOperand::Register(register_ax.clone()),
+                });
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: assembly_type(source),
+                    source: convert_operand(double_constants, source),
+                    destination: AssemblyOperand::Memory(register_ax, 0),
+                });
+            }
+            // A constant index is folded into the offset of the address, a variable index uses an
+            // indexed operand, which only scales by 1, 2, 4, or 8.
+            TackyInstruction::AddPtr {
+                pointer,
+                index,
+                scale,
+                destination,
+            } => {
+                let register_ax = AssemblyRegister::AX;
+                let register_dx = AssemblyRegister::DX;
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: convert_operand(double_constants, pointer),
+                    destination: AssemblyOperand::Register(register_ax.clone()),
+                });
+                let offset = match index {
+                    TackyValue::Constant(constant) => constant
+                        .as_i64()
+                        .checked_mul(*scale)
+                        .and_then(|offset| i32::try_from(offset).ok()),
+                    TackyValue::Variable(_) => None,
+                };
+                let source = match offset {
+                    Some(offset) => AssemblyOperand::Memory(register_ax, offset),
+                    None => {
+                        asm_instructions.push(AssemblyInstruction::Mov {
+                            assembly_type: AssemblyType::Quadword,
+                            source: convert_operand(double_constants, index),
+                            destination: AssemblyOperand::Register(register_dx.clone()),
+                        });
+                        let scale = match i32::try_from(*scale) {
+                            Ok(scale @ (1 | 2 | 4 | 8)) => scale,
+                            _ => {
+                                asm_instructions.push(AssemblyInstruction::Binary {
+                                    op: AssemblyBinaryOperator::Mult,
+                                    assembly_type: AssemblyType::Quadword,
+                                    source: AssemblyOperand::Imm(*scale),
+                                    destination: AssemblyOperand::Register(register_dx.clone()),
+                                });
+                                1
+                            }
+                        };
+                        AssemblyOperand::Indexed(register_ax, register_dx, scale)
+                    }
+                };
+                asm_instructions.push(AssemblyInstruction::Lea {
+                    source,
+                    destination: convert_operand(double_constants, destination),
+                });
+            }
+            TackyInstruction::IntToDouble {
+                source,
+                destination,
+            } => {
+                let cvtsi2sd_instruction = AssemblyInstruction::Cvtsi2sd {
+                    assembly_type: assembly_type(source),
+                    source: convert_operand(double_constants, source),
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(cvtsi2sd_instruction);
+            }
+            TackyInstruction::DoubleToInt {
+                source,
+                destination,
+            } => {
+                let cvttsd2si_instruction = AssemblyInstruction::Cvttsd2si {
+                    assembly_type: assembly_type(destination),
+                    source: convert_operand(double_constants, source),
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(cvttsd2si_instruction);
+            }
+            // An `unsigned long` within the range of a `long` is converted as a `long`. A larger one
+            // is halved, keeping its lowest bit so that the halved value rounds like the whole one,
+            // and the converted half is doubled.
+            TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            } if assembly_type(source) == AssemblyType::Quadword => {
+                let (out_of_range, end) = make_labels("ulong_to_double");
+                let source = convert_operand(double_constants, source);
+                let destination = convert_operand(double_constants, destination);
+                let register_ax = AssemblyOperand::Register(AssemblyRegister::AX);
+                let register_dx = AssemblyOperand::Register(AssemblyRegister::DX);
+                asm_instructions.extend([
+                    AssemblyInstruction::Cmp {
+                        assembly_type: AssemblyType::Quadword,
+                        left: AssemblyOperand::Imm(0),
+                        right: source.clone(),
+                    },
+                    AssemblyInstruction::JmpCC {
+                        condition: AssemblyConditionCode::L,
+                        label: out_of_range.clone(),
+                    },
+                    AssemblyInstruction::Cvtsi2sd {
+                        assembly_type: AssemblyType::Quadword,
+                        source: source.clone(),
+                        destination: destination.clone(),
+                    },
+                    AssemblyInstruction::Jmp { label: end.clone() },
+                    AssemblyInstruction::Label(out_of_range),
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Quadword,
+                        source,
+                        destination: register_ax.clone(),
+                    },
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Quadword,
+                        source: register_ax.clone(),
+                        destination: register_dx.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Shr,
+                        assembly_type: AssemblyType::Quadword,
+                        source: AssemblyOperand::Imm(1),
+                        destination: register_dx.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::And,
+                        assembly_type: AssemblyType::Quadword,
+                        source: AssemblyOperand::Imm(1),
+                        destination: register_ax.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Or,
+                        assembly_type: AssemblyType::Quadword,
+                        source: register_ax,
+                        destination: register_dx.clone(),
+                    },
+                    AssemblyInstruction::Cvtsi2sd {
+                        assembly_type: AssemblyType::Quadword,
+                        source: register_dx,
+                        destination: destination.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Add,
+                        assembly_type: AssemblyType::Double,
+                        source: destination.clone(),
+                        destination,
+                    },
+                    AssemblyInstruction::Label(end),
+                ]);
+            }
+            // An `unsigned int` zero extended to 8 bytes is converted as a `long`.
+            TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            } => {
+                let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+                let mov_instruction = AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: convert_operand(double_constants, source),
+                    destination: register_r11.clone(),
+                };
+                let cvtsi2sd_instruction = AssemblyInstruction::Cvtsi2sd {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11,
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(mov_instruction);
+                asm_instructions.push(cvtsi2sd_instruction);
+            }
+            // A `double` within the range of a `long` is converted as a `long`. From a larger one 2^63
+            // is subtracted before the conversion and added back after it.
+            TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            } if assembly_type(destination) == AssemblyType::Quadword => {
+                let (out_of_range, end) = make_labels("double_to_ulong");
+                let upper_bound = double_constants.operand(9223372036854775808.0, 8);
+                let source = convert_operand(double_constants, source);
+                let destination = convert_operand(double_constants, destination);
+                let register_xmm0 = AssemblyOperand::Register(AssemblyRegister::XMM0);
+                let register_dx = AssemblyOperand::Register(AssemblyRegister::DX);
+                asm_instructions.extend([
+                    AssemblyInstruction::Cmp {
+                        assembly_type: AssemblyType::Double,
+                        left: upper_bound.clone(),
+                        right: source.clone(),
+                    },
+                    AssemblyInstruction::JmpCC {
+                        condition: AssemblyConditionCode::AE,
+                        label: out_of_range.clone(),
+                    },
+                    AssemblyInstruction::Cvttsd2si {
+                        assembly_type: AssemblyType::Quadword,
+                        source: source.clone(),
+                        destination: destination.clone(),
+                    },
+                    AssemblyInstruction::Jmp { label: end.clone() },
+                    AssemblyInstruction::Label(out_of_range),
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Double,
+                        source,
+                        destination: register_xmm0.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Sub,
+                        assembly_type: AssemblyType::Double,
+                        source: upper_bound,
+                        destination: register_xmm0.clone(),
+                    },
+                    AssemblyInstruction::Cvttsd2si {
+                        assembly_type: AssemblyType::Quadword,
+                        source: register_xmm0,
+                        destination: destination.clone(),
+                    },
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Quadword,
+                        source: AssemblyOperand::Imm(i64::MIN),
+                        destination: register_dx.clone(),
+                    },
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Add,
+                        assembly_type: AssemblyType::Quadword,
+                        source: register_dx,
+                        destination,
+                    },
+                    AssemblyInstruction::Label(end),
+                ]);
+            }
+            // A `double` is converted into a `long`, whose lower 4 bytes are the `unsigned int`.
+            TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            } => {
+                let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+                let cvttsd2si_instruction = AssemblyInstruction::Cvttsd2si {
+                    assembly_type: AssemblyType::Quadword,
+                    source: convert_operand(double_constants, source),
+                    destination: register_r11.clone(),
+                };
+                let mov_instruction = AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: register_r11,
+                    destination: convert_operand(double_constants, destination),
+                };
+                asm_instructions.push(cvttsd2si_instruction);
+                asm_instructions.push(mov_instruction);
+            }
+            TackyInstruction::FunCall {
+                identifier,
+                arguments,
+                named_arguments,
+                destination,
+            } => {
+                let (register_arguments, stack_arguments) =
+                    classify_arguments(arguments, variable_types);
+                let vector_registers = register_arguments
+                    .iter()
+                    .filter(|(assembly_type, _, _)| *assembly_type == AssemblyType::Double)
+                    .count();
+                let stack_padding = if stack_arguments.len() % 2 == 1 { 8 } else { 0 };
+                if stack_padding != 0 {
+                    asm_instructions.push(AssemblyInstruction::AllocateStack {
+                        stack_offset: stack_padding,
+                    });
+                }
+                for (assembly_type, argument, register) in register_arguments {
+                    asm_instructions.push(AssemblyInstruction::Mov {
+                        assembly_type,
+                        source: convert_operand(double_constants, argument),
+                        destination: AssemblyOperand::Register(register),
+                    });
+                }
+                let stack_size = 8 * stack_arguments.len() as i32 + stack_padding;
+                for (assembly_type, argument) in stack_arguments.into_iter().rev() {
+                    let operand = convert_operand(double_constants, argument);
+                    match (assembly_type, operand) {
+                        // `pushq` reads 8 bytes, so a smaller value in memory is pushed from %rax,
+                        // whose upper bytes the callee ignores.
+                        (
+                            AssemblyType::Byte | AssemblyType::Word | AssemblyType::Longword,
+                            operand @ (AssemblyOperand::Pseudo(_)
+                            | AssemblyOperand::Stack(_)
+                            | AssemblyOperand::Data(_)),
+                        ) => {
+                            let register_ax = AssemblyOperand::Register(AssemblyRegister::AX);
+                            asm_instructions.push(AssemblyInstruction::Mov {
+                                assembly_type,
+                                source: operand,
+                                destination: register_ax.clone(),
+                            });
+                            asm_instructions.push(AssemblyInstruction::Push(register_ax));
+                        }
+                        (_, operand) => asm_instructions.push(AssemblyInstruction::Push(operand)),
+                    }
+                }
+                // A variadic function reads the number of vector registers holding arguments from
+                // %al.
+                if named_arguments.is_some() {
+                    asm_instructions.push(AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Byte,
+                        source: AssemblyOperand::Imm(vector_registers as i64),
+                        destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                    });
+                }
+                asm_instructions.push(AssemblyInstruction::Call(identifier.clone()));
+                if stack_size != 0 {
+                    asm_instructions.push(AssemblyInstruction::DeallocateStack {
+                        stack_offset: stack_size,
+                    });
+                }
+                asm_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: assembly_type(destination),
+                    source: AssemblyOperand::Register(return_register(assembly_type(destination))),
+                    destination: convert_operand(double_constants, destination),
+                });
+            }
         }
     }
     Ok(asm_instructions)
 }
 
-fn convert_condition_code(
-    tacky_binary_operator: &TackyBinaryOperator,
-) -> Result<AssemblyConditionCode, CodegenError> {
-    match tacky_binary_operator {
-        TackyBinaryOperator::Equal => Ok(AssemblyConditionCode::E),
-        TackyBinaryOperator::NotEqual => Ok(AssemblyConditionCode::NE),
-        TackyBinaryOperator::LessThan => Ok(AssemblyConditionCode::L),
-        TackyBinaryOperator::GreaterThan => Ok(AssemblyConditionCode::G),
-        TackyBinaryOperator::LessThanEqual => Ok(AssemblyConditionCode::LE),
-        TackyBinaryOperator::GreaterThanEqual => Ok(AssemblyConditionCode::GE),
-        _ => Err(CodegenError::UnsupportedConditionCodeConversion {
-            operator: tacky_binary_operator.clone(),
-        }),
-    }
-}
-
-/// Converts a `TackyValue` to its corresponding `AssemblyUnaryOperand`.
+/// Returns the register a function returns a value of the given size in: %xmm0 for a `double`
+/// and %rax for an integer or a pointer, as in the System V ABI.
 ///
 /// # Arguments
 ///
-/// * `tacky_operand`: The TackyValue to convert.
+/// * `assembly_type` - The size of the returned value.
 ///
 /// # Returns
 ///
-/// An AssemblyUnaryOperand representing the converted value.
-fn convert_operand(tacky_operand: &TackyValue) -> AssemblyOperand {
-    match tacky_operand {
-        TackyValue::Constant(value) => AssemblyOperand::Imm(*value),
-        TackyValue::Variable(name) => AssemblyOperand::Pseudo(name.clone()),
+/// The `AssemblyRegister` holding the returned value.
+fn return_register(assembly_type: AssemblyType) -> AssemblyRegister {
+    match assembly_type {
+        AssemblyType::Double => AssemblyRegister::XMM0,
+        _ => AssemblyRegister::AX,
     }
 }
 
-/// Replaces pseudo registers with physical registers in the assembly instructions.
+/// The arguments of a call passed in registers, with their sizes and registers.
+type RegisterArguments<'a> = Vec<(AssemblyType, &'a TackyValue, AssemblyRegister)>;
+
+/// The arguments of a call passed on the stack, with their sizes, in order.
+type StackArguments<'a> = Vec<(AssemblyType, &'a TackyValue)>;
+
+/// Assigns the arguments of a call, or the parameters of a function, to the places the System V
+/// ABI passes them in.
 ///
-/// The following instructions should replace their pseudo registers with physical registers:
-/// * `AssemblyInstruction::Mov`
-/// * `AssemblyInstruction::Unary`
-/// * `AssemblyInstruction::Binary`
-/// * `AssemblyInstruction::Idiv`
+/// The first six integer values are passed in `constants::ARGUMENT_REGISTERS`, and the first eight
+/// `double`s in `constants::DOUBLE_ARGUMENT_REGISTERS`. The rest are passed on the stack, in 8-byte
+/// slots.
 ///
 /// # Arguments
 ///
-/// * `asm_ast` - The assembly AST to be modified.
+/// * `values` - The arguments or the parameters, in order.
+/// * `variable_types` - The types of the variables among the values.
 ///
 /// # Returns
 ///
-/// The final stack offset after replacing pseudo registers.
-fn pseudoregister_replacement_pass(instructions: &mut Vec<AssemblyInstruction>) -> i32 {
-    let mut identifier_offsets: HashMap<String, i32> = HashMap::new();
-    let mut offset_counter = 0;
-    for instruction in instructions.iter_mut() {
-        match instruction {
-            AssemblyInstruction::Mov {
-                source,
-                destination,
-            } => {
-                convert_pseudo_register(source, &mut identifier_offsets, &mut offset_counter);
-                convert_pseudo_register(destination, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::Unary { op: _, operand } => {
-                convert_pseudo_register(operand, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::Binary {
-                op: _,
-                source,
-                destination,
-            } => {
-                convert_pseudo_register(source, &mut identifier_offsets, &mut offset_counter);
-                convert_pseudo_register(destination, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::Idiv { operand } => {
-                convert_pseudo_register(operand, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::Cmp { left, right } => {
-                convert_pseudo_register(left, &mut identifier_offsets, &mut offset_counter);
-                convert_pseudo_register(right, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::SetCC {
-                condition: _,
-                operand,
-            } => {
-                convert_pseudo_register(operand, &mut identifier_offsets, &mut offset_counter);
-            }
-            AssemblyInstruction::Cdq => {}
-            AssemblyInstruction::AllocateStack { stack_offset: _ } => {}
-            AssemblyInstruction::Ret => {}
-            AssemblyInstruction::Jmp { label: _ } => {}
-            AssemblyInstruction::JmpCC {
-                condition: _,
-                label: _,
-            } => {}
-            AssemblyInstruction::Label(_) => {}
+/// The values passed in registers and the values passed on the stack, each in their order.
+fn classify_arguments<'a>(
+    values: &'a [TackyValue],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> (RegisterArguments<'a>, StackArguments<'a>) {
+    let mut integer_registers = constants::ARGUMENT_REGISTERS.into_iter();
+    let mut double_registers = constants::DOUBLE_ARGUMENT_REGISTERS.into_iter();
+    let mut register_arguments = Vec::new();
+    let mut stack_arguments = Vec::new();
+    for value in values {
+        let assembly_type = convert_type(value.value_type(variable_types));
+        let register = match assembly_type {
+            AssemblyType::Double => double_registers.next(),
+            _ => integer_registers.next(),
+        };
+        match register {
+            Some(register) => register_arguments.push((assembly_type, value, register)),
+            None => stack_arguments.push((assembly_type, value)),
         }
     }
-    offset_counter
+    (register_arguments, stack_arguments)
 }
 
-/// Converts a pseudo-register operand to a stack operand.
-///
-/// This function takes an `Operand` and attempts to convert it from a `Pseudo` variant (representing an identifier) to a `Stack` variant (representing a memory offset).
+/// Pushes the instructions comparing an operand with zero, which set the flags for the `E` and `NE`
+/// condition codes.
 ///
 /// # Arguments
 ///
-/// * `operand`: A mutable reference to the `Operand` to be converted. If it's a `Pseudo` variant, it will be modified in place to become a `Stack` variant.
-/// * `identifier_offsets`: A mutable reference to a `HashMap` that maps identifier strings to their allocated stack offsets (`i32`).
-/// * `offset_counter`: A mutable reference to an `i32` that acts as a counter for allocating new stack offsets. It is decremented for each new identifier.
+/// * `assembly_type` - The size of the operand. A `double` is compared with a zeroed %xmm0, as
+///   there are no `double` immediates.
+/// * `operand` - The operand to compare.
+/// * `asm_instructions` - The vector to push the instructions to.
+fn push_zero_comparison(
+    assembly_type: AssemblyType,
+    operand: AssemblyOperand,
+    asm_instructions: &mut Vec<AssemblyInstruction>,
+) {
+    if assembly_type == AssemblyType::Double {
+        let register_xmm0 = AssemblyOperand::Register(AssemblyRegister::XMM0);
+        asm_instructions.push(AssemblyInstruction::Binary {
+            op: AssemblyBinaryOperator::Xor,
+            assembly_type,
+            source: register_xmm0.clone(),
+            destination: register_xmm0.clone(),
+        });
+        asm_instructions.push(AssemblyInstruction::Cmp {
+            assembly_type,
+            left: operand,
+            right: register_xmm0,
+        });
+    } else {
+        asm_instructions.push(AssemblyInstruction::Cmp {
+            assembly_type,
+            left: AssemblyOperand::Imm(0),
+            right: operand,
+        });
+    }
+}
+
+/// Pushes the instructions setting a destination to the result of a `double` comparison, whose
+/// `comisd` was pushed before.
+///
+/// `comisd` reports unordered operands, where one of them is NaN, like equal operands whose left
+/// operand is below the right one, and sets the parity flag. Every comparison with NaN is false,
+/// except for `!=`, so the parity flag is combined into the results of `==` and `!=`. `>` and `>=`
+/// are false for unordered operands already, and `<` and `<=` must be compared as `>` and `>=`
+/// with their operands swapped.
 ///
-/// # Returns
+/// # Arguments
 ///
-/// This function does not return a value, but it modifies the `operand` argument in place.
-fn convert_pseudo_register(
-    operand: &mut AssemblyOperand,
-    identifier_offsets: &mut HashMap<String, i32>,
-    offset_counter: &mut i32,
-) -> () {
-    match operand {
-        AssemblyOperand::Pseudo(identifier) => {
-            if let Some(offset) = identifier_offsets.get(identifier) {
-                *operand = AssemblyOperand::Stack(*offset);
-                return;
-            }
-            *offset_counter -= constants::STACK_ADDRESS_OFFSET;
-            identifier_offsets.insert(identifier.clone(), *offset_counter);
-            *operand = AssemblyOperand::Stack(*offset_counter);
+/// * `operator` - The comparison, one of `==`, `!=`, `>`, or `>=`.
+/// * `assembly_type` - The type of the destination.
+/// * `destination` - The operand set to 1 if the comparison holds, and to 0 otherwise.
+/// * `scratch` - A register other than the destination, used to combine the parity flag.
+/// * `asm_instructions` - The instructions to push the instructions to.
+fn push_double_comparison(
+    operator: &TackyBinaryOperator,
+    assembly_type: AssemblyType,
+    destination: AssemblyOperand,
+    scratch: AssemblyRegister,
+    asm_instructions: &mut Vec<AssemblyInstruction>,
+) {
+    let (condition, parity) = match operator {
+        TackyBinaryOperator::Equal => (AssemblyConditionCode::E, Some(AssemblyConditionCode::NP)),
+        TackyBinaryOperator::NotEqual => {
+            (AssemblyConditionCode::NE, Some(AssemblyConditionCode::P))
         }
-        _ => {}
+        TackyBinaryOperator::GreaterThan => (AssemblyConditionCode::A, None),
+        TackyBinaryOperator::GreaterThanEqual => (AssemblyConditionCode::AE, None),
+        _ => unreachable!("`<` and `<=` are compared with swapped operands"),
+    };
+    asm_instructions.push(AssemblyInstruction::Mov {
+        assembly_type,
+        source: AssemblyOperand::Imm(0),
+        destination: destination.clone(),
+    });
+    asm_instructions.push(AssemblyInstruction::SetCC {
+        condition,
+        operand: destination.clone(),
+    });
+    if let Some(parity) = parity {
+        // The moves and the `setcc`s leave the flags of the comparison intact.
+        let scratch = AssemblyOperand::Register(scratch);
+        asm_instructions.push(AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Longword,
+            source: AssemblyOperand::Imm(0),
+            destination: scratch.clone(),
+        });
+        asm_instructions.push(AssemblyInstruction::SetCC {
+            condition: parity.clone(),
+            operand: scratch.clone(),
+        });
+        asm_instructions.push(AssemblyInstruction::Binary {
+            op: match parity {
+                AssemblyConditionCode::NP => AssemblyBinaryOperator::And,
+                _ => AssemblyBinaryOperator::Or,
+            },
+            assembly_type,
+            source: scratch,
+            destination,
+        });
     }
 }
 
-/// Fixes up instructions by resolving memory-to-memory operations.
+/// Converts the type of a TACKY value into the size of the assembly instructions operating on it.
 ///
 /// # Arguments
 ///
-/// * `instructions`: The `AssemblyInstruction`s to process.
-/// * `stack_offset`: The total stack space in bytes to allocate for the function.
+/// * `tacky_type` - The type of the value.
 ///
 /// # Returns
 ///
-/// A new `AssemblyAst` with the instructions fixed up.
-fn instruction_fixup_pass(instructions: &Vec<AssemblyInstruction>) -> Vec<AssemblyInstruction> {
-    let mut fixed_instructions = vec![];
-    for instruction in instructions.iter() {
-        fixed_instructions.append(&mut fixup_asm_instruction(instruction));
+/// `AssemblyType::Double` for `double`s, `AssemblyType::Quadword` for other 8-byte types,
+/// `AssemblyType::Word` for 2-byte types, `AssemblyType::Byte` for 1-byte types, and
+/// `AssemblyType::Longword` otherwise.
+fn convert_type(tacky_type: TackyType) -> AssemblyType {
+    match tacky_type {
+        TackyType::Double => AssemblyType::Double,
+        _ => match tacky_type.size() {
+            8 => AssemblyType::Quadword,
+            2 => AssemblyType::Word,
+            1 => AssemblyType::Byte,
+            _ => AssemblyType::Longword,
+        },
     }
-    fixed_instructions
 }
 
-/// Inserts an instruction to allocate stack space at the beginning of the instruction list.
+/// Converts a TACKY comparison operator into the condition code of its result.
+///
+/// # Arguments
+///
+/// * `tacky_binary_operator` - The comparison operator.
+/// * `is_signed` - Whether the compared operands are signed, which selects the less and greater
+///   codes over the below and above codes.
+///
+/// # Returns
+///
+/// The `AssemblyConditionCode` on success, or a `CodegenError` if the operator is no comparison.
+fn convert_condition_code(
+    tacky_binary_operator: &TackyBinaryOperator,
+    is_signed: bool,
+) -> Result<AssemblyConditionCode, CodegenError> {
+    match (tacky_binary_operator, is_signed) {
+        (TackyBinaryOperator::Equal, _) => Ok(AssemblyConditionCode::E),
+        (TackyBinaryOperator::NotEqual, _) => Ok(AssemblyConditionCode::NE),
+        (TackyBinaryOperator::LessThan, true) => Ok(AssemblyConditionCode::L),
+        (TackyBinaryOperator::GreaterThan, true) => Ok(AssemblyConditionCode::G),
+        (TackyBinaryOperator::LessThanEqual, true) => Ok(AssemblyConditionCode::LE),
+        (TackyBinaryOperator::GreaterThanEqual, true) => Ok(AssemblyConditionCode::GE),
+        (TackyBinaryOperator::LessThan, false) => Ok(AssemblyConditionCode::B),
+        (TackyBinaryOperator::GreaterThan, false) => Ok(AssemblyConditionCode::A),
+        (TackyBinaryOperator::LessThanEqual, false) => Ok(AssemblyConditionCode::BE),
+        (TackyBinaryOperator::GreaterThanEqual, false) => Ok(AssemblyConditionCode::AE),
+        _ => Err(CodegenError::UnsupportedConditionCodeConversion {
+            operator: tacky_binary_operator.clone(),
+        }),
+    }
+}
+
+/// Converts a `TackyValue` to its corresponding `AssemblyUnaryOperand`.
+///
+/// # Arguments
+///
+/// * `double_constants`: The `double` constants of the program, a `double` constant is read from them.
+/// * `tacky_operand`: The TackyValue to convert.
+///
+/// # Returns
+///
+/// An AssemblyUnaryOperand representing the converted value.
+fn convert_operand(
+    double_constants: &mut DoubleConstants,
+    tacky_operand: &TackyValue,
+) -> AssemblyOperand {
+    match tacky_operand {
+        TackyValue::Constant(TackyConstant::Double(value)) => double_constants.operand(*value, 8),
+        // A 4-byte constant is given by its bits, so an `unsigned int` fits a 32-bit immediate too.
+        TackyValue::Constant(TackyConstant::Long(value)) => AssemblyOperand::Imm(*value),
+        TackyValue::Constant(TackyConstant::UnsignedLong(value)) => {
+            AssemblyOperand::Imm(*value as i64)
+        }
+        TackyValue::Constant(constant) => AssemblyOperand::Imm(i64::from(constant.as_i64() as i32)),
+        TackyValue::Variable(name) => AssemblyOperand::Pseudo(name.clone()),
+    }
+}
+
+/// The `double` constants of a program, which are stored in its read-only data.
+#[derive(Default)]
+struct DoubleConstants {
+    /// The constants in the order of their first use.
+    constants: Vec<AssemblyStaticConstant>,
+    /// The indices of the constants by the bits of their values, so every value is stored once.
+    indices: FxHashMap<u64, usize>,
+}
+
+impl DoubleConstants {
+    /// Returns the operand reading a `double` constant, adding the constant on its first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The value of the constant.
+    /// * `alignment`: The alignment the instruction reading the constant requires, a constant
+    ///   read by several instructions gets the largest one.
+    fn operand(&mut self, value: f64, alignment: i32) -> AssemblyOperand {
+        AssemblyOperand::Data(self.identifier(value, alignment))
+    }
+
+    /// Returns the name of a `double` constant, adding the constant on its first use.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The value of the constant.
+    /// * `alignment`: The alignment the instruction reading the constant requires.
+    fn identifier(&mut self, value: f64, alignment: i32) -> Symbol {
+        let next_index = self.constants.len();
+        let index = *self.indices.entry(value.to_bits()).or_insert(next_index);
+        if index == next_index {
+            self.constants.push(AssemblyStaticConstant::Double {
+                identifier: Symbol::from(format!("double.{}", index)),
+                value,
+                alignment,
+            });
+        }
+        let AssemblyStaticConstant::Double {
+            identifier,
+            alignment: constant_alignment,
+            ..
+        } = &mut self.constants[index]
+        else {
+            unreachable!("Only doubles are added to the constants")
+        };
+        *constant_alignment = (*constant_alignment).max(alignment);
+        identifier.clone()
+    }
+
+    /// Adds the constants read by a function to the constants of the program.
+    ///
+    /// The function numbered its constants on its own, so the operands reading a constant the
+    /// program stores under another name are renamed.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The assembly function that read the constants.
+    /// * `function_constants`: The constants read by the function.
+    fn merge(&mut self, function: &mut AssemblyFunction, function_constants: DoubleConstants) {
+        let mut renamed_identifiers = FxHashMap::default();
+        for constant in function_constants.constants {
+            let AssemblyStaticConstant::Double {
+                identifier,
+                value,
+                alignment,
+            } = constant
+            else {
+                unreachable!("Only doubles are added to the constants")
+            };
+            let merged_identifier = self.identifier(value, alignment);
+            if merged_identifier != identifier {
+                renamed_identifiers.insert(identifier, merged_identifier);
+            }
+        }
+        if renamed_identifiers.is_empty() {
+            return;
+        }
+        let AssemblyFunction::Function { instructions, .. } = function;
+        for instruction in instructions.iter_mut() {
+            for_each_operand(instruction, |operand| {
+                if let AssemblyOperand::Data(identifier) = operand
+                    && let Some(merged_identifier) = renamed_identifiers.get(identifier)
+                {
+                    *identifier = merged_identifier.clone();
+                }
+            });
+        }
+    }
+}
+
+/// Calls a function with every operand of an assembly instruction.
+///
+/// # Arguments
+///
+/// * `instruction`: The `AssemblyInstruction` whose operands are visited.
+/// * `visit`: The function called with a mutable reference to each operand, in order.
+fn for_each_operand(
+    instruction: &mut AssemblyInstruction,
+    mut visit: impl FnMut(&mut AssemblyOperand),
+) {
+    match instruction {
+        AssemblyInstruction::Mov {
+            source,
+            destination,
+            ..
+        }
+        | AssemblyInstruction::Movsx {
+            source,
+            destination,
+            ..
+        }
+        | AssemblyInstruction::MovZeroExtend {
+            source,
+            destination,
+            ..
+        }
+        | AssemblyInstruction::Lea {
+            source,
+            destination,
+        }
+        | AssemblyInstruction::Binary {
+            source,
+            destination,
+            ..
+        }
+        | AssemblyInstruction::Cvtsi2sd {
+            source,
+            destination,
+            ..
+        }
+        | AssemblyInstruction::Cvttsd2si {
+            source,
+            destination,
+            ..
+        } => {
+            visit(source);
+            visit(destination);
+        }
+        AssemblyInstruction::Unary { operand, .. }
+        | AssemblyInstruction::Idiv { operand, .. }
+        | AssemblyInstruction::Div { operand, .. }
+        | AssemblyInstruction::SetCC { operand, .. }
+        | AssemblyInstruction::Push(operand) => visit(operand),
+        AssemblyInstruction::Cmp { left, right, .. } => {
+            visit(left);
+            visit(right);
+        }
+        AssemblyInstruction::Cdq { .. }
+        | AssemblyInstruction::AllocateStack { .. }
+        | AssemblyInstruction::DeallocateStack { .. }
+        | AssemblyInstruction::Call(_)
+        | AssemblyInstruction::Ret
+        | AssemblyInstruction::Jmp { .. }
+        | AssemblyInstruction::JmpCC { .. }
+        | AssemblyInstruction::Label(_) => {}
+    }
+}
+
+/// Replaces pseudo registers with physical registers in the assembly instructions.
+///
+/// The following instructions should replace their pseudo registers with physical registers:
+/// * `AssemblyInstruction::Mov`, `AssemblyInstruction::Movsx`, and `AssemblyInstruction::MovZeroExtend`
+/// * `AssemblyInstruction::Lea`
+/// * `AssemblyInstruction::Unary`
+/// * `AssemblyInstruction::Binary`
+/// * `AssemblyInstruction::Idiv` and `AssemblyInstruction::Div`
+/// * `AssemblyInstruction::Cvtsi2sd` and `AssemblyInstruction::Cvttsd2si`
+///
+/// Every pseudo register gets a stack slot of the size of its type, 8-byte slots are aligned to 8
+/// bytes. An array gets a slot of its whole size, aligned to the alignment of the array. A static
+/// variable lives in the data of the program and is addressed relative to %rip instead.
+///
+/// # Arguments
+///
+/// * `asm_ast` - The assembly AST to be modified.
+/// * `variable_types` - The types of the pseudo registers, a missing type is an `int`.
+/// * `statics` - The names of the static variables of the program.
+///
+/// # Returns
+///
+/// The size of the stack frame in bytes, which is the distance of the lowest stack location from
+/// %rbp. It is computed as an `i64`, so that a frame too large to address can be diagnosed.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn pseudoregister_replacement_pass(
+    instructions: &mut [AssemblyInstruction],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+    statics: &FxHashSet<Symbol>,
+) -> i64 {
+    // Most pseudo registers are temporaries written by a single instruction, so the instruction
+    // count is a close upper bound of the distinct identifiers.
+    let mut stack_slots = StackSlots {
+        identifier_offsets: FxHashMap::with_capacity_and_hasher(
+            instructions.len(),
+            Default::default(),
+        ),
+        offset_counter: 0,
+        variable_types,
+        statics,
+    };
+    for instruction in instructions.iter_mut() {
+        for_each_operand(instruction, |operand| {
+            stack_slots.convert_pseudo_register(operand)
+        });
+    }
+    stack_slots.offset_counter.saturating_neg()
+}
+
+/// The stack slots allocated for the pseudo registers of a function.
+struct StackSlots<'a> {
+    /// The allocated stack offsets of the identifiers.
+    identifier_offsets: FxHashMap<Symbol, i32>,
+    /// The offset of the lowest allocated slot. Offsets beyond the range of an `i32` are clamped,
+    /// the frame they belong to is rejected by `stack_frame_size_check`.
+    offset_counter: i64,
+    /// The types of the identifiers, which decide the sizes of their slots.
+    variable_types: &'a BTreeMap<Symbol, TackyType>,
+    /// The names of the static variables, which get no slot.
+    statics: &'a FxHashSet<Symbol>,
+}
+
+impl StackSlots<'_> {
+    /// Converts a pseudo-register operand to a stack operand.
+    ///
+    /// An identifier seen for the first time gets a new slot below the previous ones, and a static
+    /// variable becomes a `Data` operand.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand`: A mutable reference to the `Operand` to be converted. If it's a `Pseudo` variant, it will be modified in place to become a `Stack` variant.
+    fn convert_pseudo_register(&mut self, operand: &mut AssemblyOperand) {
+        if let AssemblyOperand::Pseudo(identifier) = operand {
+            if self.statics.contains(identifier) {
+                *operand = AssemblyOperand::Data(identifier.clone());
+                return;
+            }
+            if let Some(offset) = self.identifier_offsets.get(identifier) {
+                *operand = AssemblyOperand::Stack(*offset);
+                return;
+            }
+            // An array takes its whole size and may need a larger alignment than its size.
+            let (slot_size, alignment) =
+                match TackyValue::Variable(identifier.clone()).value_type(self.variable_types) {
+                    // A size beyond an i64 saturates, so the frame is rejected as too large.
+                    TackyType::Aggregate { size, alignment } => {
+                        (i64::try_from(size).unwrap_or(i64::MAX), alignment as i64)
+                    }
+                    value_type => {
+                        let size = i64::from(convert_type(value_type).size());
+                        (size, size)
+                    }
+                };
+            self.offset_counter = self.offset_counter.saturating_sub(slot_size);
+            // Rounds down to a multiple of the alignment, which is a power of two.
+            self.offset_counter &= -alignment;
+            let offset = i32::try_from(self.offset_counter).unwrap_or(i32::MIN);
+            self.identifier_offsets.insert(identifier.clone(), offset);
+            *operand = AssemblyOperand::Stack(offset);
+        }
+    }
+}
+
+/// Fixes up instructions by resolving memory-to-memory operations.
+///
+/// Takes ownership of the instructions, so correct instructions are moved into the result as is.
+///
+/// # Arguments
+///
+/// * `instructions`: The `AssemblyInstruction`s to process.
+///
+/// # Returns
+///
+/// A new vector of `AssemblyInstruction`s with the invalid instructions rewritten.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn instruction_fixup_pass(instructions: Vec<AssemblyInstruction>) -> Vec<AssemblyInstruction> {
+    // Leaves room for the prepended stack allocation and for some rewritten instructions.
+    let mut fixed_instructions =
+        Vec::with_capacity(instructions.len() + instructions.len() / 2 + 1);
+    for instruction in instructions {
+        fixup_asm_instruction(instruction, &mut fixed_instructions);
+    }
+    fixed_instructions
+}
+
+/// Inserts an instruction to allocate stack space at the beginning of the instruction list.
 ///
 /// # Arguments
 ///
@@ -495,263 +1853,2003 @@ fn instruction_fixup_pass(instructions: &Vec<AssemblyInstruction>) -> Vec<Assemb
 /// # Returns
 ///
 /// A new vector of instructions with the `AllocateStack` instruction prepended
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn stack_allocation_pass(stack_offset: &i32) -> AssemblyInstruction {
     AssemblyInstruction::AllocateStack {
         stack_offset: *stack_offset,
     }
 }
 
-/// Fixes up incorrect assembly instructions. Correct instructions are returned as is.
+/// Returns whether an immediate value does not fit the 32-bit immediate of most instructions.
+///
+/// # Arguments
+///
+/// * `operand` - The operand to check.
+///
+/// # Returns
+///
+/// `true` if the operand is an immediate outside the range of an `i32`, `false` otherwise.
+fn is_large_immediate(operand: &AssemblyOperand) -> bool {
+    matches!(operand, AssemblyOperand::Imm(value) if i32::try_from(*value).is_err())
+}
+
+/// Fixes up an incorrect assembly instruction. Correct instructions are pushed as is.
 ///
 /// Performs the following fixes:
-/// * Replaces memory-to-memory `Mov`, `Add`, and `Sub` operations by using an intermediate scratch register.
-/// * Moves constant values to scratch registers before `Idiv` operations.
+/// * Replaces memory-to-memory `Mov`, `Add`, `Sub`, and `Cmp` operations by using an intermediate scratch register.
+/// * Moves constant values to scratch registers before `Idiv` and `Div` operations and as the right operand of `Cmp` operations.
 /// * Moves destination operand from a memory location to scratch register before `Mult` operations, and then moves the result back to the destination memory location.
+/// * Moves immediate values that do not fit into 32 bits to a scratch register, only a `Mov` into
+///   a register accepts them. This includes the immediates pushed as arguments of a call.
+/// * Moves the immediate source of a `Movsx` or a `MovZeroExtend` to a scratch register, and its
+///   result through a scratch register into a memory location.
+/// * Moves the result of a `Lea` through a scratch register into a memory location.
+/// * Replaces memory-to-memory `double` moves by using an intermediate SSE scratch register.
+/// * Moves the memory destination of a `double` operation, and the right operand of a `double`
+///   `Cmp`, to an SSE scratch register, as SSE instructions only write to and compare registers.
+/// * Moves the immediate source of a `Cvtsi2sd` to a scratch register, and the memory results of
+///   `Cvtsi2sd` and `Cvttsd2si` through scratch registers.
 ///
 /// # Arguments
 ///
 /// * `asm_instruction`: The `AssemblyInstruction` to potentially fix up.
-///
-/// # Returns
-///
-/// A `Vec<AssemblyInstruction>` containing either the original instruction or the sequence of fixed instructions.
-fn fixup_asm_instruction(asm_instruction: &AssemblyInstruction) -> Vec<AssemblyInstruction> {
+/// * `fixed_instructions`: The vector to push the original instruction or the sequence of fixed instructions to.
+fn fixup_asm_instruction(
+    asm_instruction: AssemblyInstruction,
+    fixed_instructions: &mut Vec<AssemblyInstruction>,
+) {
     let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
     let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+    let register_xmm14 = AssemblyOperand::Register(AssemblyRegister::XMM14);
+    let register_xmm15 = AssemblyOperand::Register(AssemblyRegister::XMM15);
     match asm_instruction {
         AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Double,
+            source:
+                source @ (AssemblyOperand::Stack(_)
+                | AssemblyOperand::Memory(..)
+                | AssemblyOperand::Data(_)),
+            destination:
+                destination @ (AssemblyOperand::Stack(_)
+                | AssemblyOperand::Memory(..)
+                | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source,
+                destination: register_xmm14.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: register_xmm14,
+                destination,
+            });
+        }
+        AssemblyInstruction::Binary {
+            op,
+            assembly_type: AssemblyType::Double,
             source,
             destination,
-        } => match (source, destination) {
-            (AssemblyOperand::Stack(_), AssemblyOperand::Stack(_)) => {
-                let instr1 = AssemblyInstruction::Mov {
-                    source: source.clone(),
-                    destination: register_r10.clone(),
-                };
-                let instr2 = AssemblyInstruction::Mov {
-                    source: register_r10.clone(),
-                    destination: destination.clone(),
-                };
-                vec![instr1, instr2]
+        } if !matches!(destination, AssemblyOperand::Register(_)) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: destination.clone(),
+                destination: register_xmm15.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Binary {
+                op,
+                assembly_type: AssemblyType::Double,
+                source,
+                destination: register_xmm15.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: register_xmm15,
+                destination,
+            });
+        }
+        AssemblyInstruction::Cmp {
+            assembly_type: AssemblyType::Double,
+            left,
+            right,
+        } if !matches!(right, AssemblyOperand::Register(_)) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: right,
+                destination: register_xmm15.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Double,
+                left,
+                right: register_xmm15,
+            });
+        }
+        // The other fixups of `double` operations are for integer operands.
+        instruction @ (AssemblyInstruction::Binary {
+            assembly_type: AssemblyType::Double,
+            ..
+        }
+        | AssemblyInstruction::Cmp {
+            assembly_type: AssemblyType::Double,
+            ..
+        }) => fixed_instructions.push(instruction),
+        AssemblyInstruction::Cvtsi2sd {
+            assembly_type,
+            source,
+            destination,
+        } => {
+            let source = match source {
+                AssemblyOperand::Imm(_) => {
+                    fixed_instructions.push(AssemblyInstruction::Mov {
+                        assembly_type,
+                        source,
+                        destination: register_r10.clone(),
+                    });
+                    register_r10
+                }
+                source => source,
+            };
+            if let AssemblyOperand::Register(_) = destination {
+                fixed_instructions.push(AssemblyInstruction::Cvtsi2sd {
+                    assembly_type,
+                    source,
+                    destination,
+                });
+            } else {
+                fixed_instructions.push(AssemblyInstruction::Cvtsi2sd {
+                    assembly_type,
+                    source,
+                    destination: register_xmm15.clone(),
+                });
+                fixed_instructions.push(AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Double,
+                    source: register_xmm15,
+                    destination,
+                });
             }
-            _ => vec![asm_instruction.clone()],
-        },
+        }
+        AssemblyInstruction::Cvttsd2si {
+            assembly_type,
+            source,
+            destination: destination @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Cvttsd2si {
+                assembly_type,
+                source,
+                destination: register_r11.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: register_r11,
+                destination,
+            });
+        }
+        AssemblyInstruction::Mov {
+            assembly_type,
+            source:
+                source @ (AssemblyOperand::Stack(_)
+                | AssemblyOperand::Memory(..)
+                | AssemblyOperand::Data(_)),
+            destination:
+                destination @ (AssemblyOperand::Stack(_)
+                | AssemblyOperand::Memory(..)
+                | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: register_r10,
+                destination,
+            });
+        }
+        AssemblyInstruction::Mov {
+            assembly_type,
+            source,
+            destination:
+                destination @ (AssemblyOperand::Stack(_)
+                | AssemblyOperand::Memory(..)
+                | AssemblyOperand::Data(_)),
+        } if is_large_immediate(&source) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: register_r10,
+                destination,
+            });
+        }
+        AssemblyInstruction::Movsx {
+            source_type,
+            destination_type,
+            source,
+            destination,
+        } if matches!(source, AssemblyOperand::Imm(_))
+            || matches!(
+                destination,
+                AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)
+            ) =>
+        {
+            let (source, destination, store_instruction) = fixup_extension_operands(
+                (source_type, destination_type),
+                source,
+                destination,
+                fixed_instructions,
+            );
+            fixed_instructions.push(AssemblyInstruction::Movsx {
+                source_type,
+                destination_type,
+                source,
+                destination,
+            });
+            fixed_instructions.extend(store_instruction);
+        }
+        AssemblyInstruction::MovZeroExtend {
+            source_type,
+            destination_type,
+            source,
+            destination,
+        } if matches!(source, AssemblyOperand::Imm(_))
+            || matches!(
+                destination,
+                AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)
+            ) =>
+        {
+            let (source, destination, store_instruction) = fixup_extension_operands(
+                (source_type, destination_type),
+                source,
+                destination,
+                fixed_instructions,
+            );
+            fixed_instructions.push(AssemblyInstruction::MovZeroExtend {
+                source_type,
+                destination_type,
+                source,
+                destination,
+            });
+            fixed_instructions.extend(store_instruction);
+        }
+        AssemblyInstruction::Lea {
+            source,
+            destination: destination @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Lea {
+                source,
+                destination: register_r11.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Quadword,
+                source: register_r11,
+                destination,
+            });
+        }
         AssemblyInstruction::Binary {
             op,
+            assembly_type,
             source,
             destination,
-        } => match op {
-            AssemblyBinaryOperator::Add | AssemblyBinaryOperator::Sub => {
-                match (source, destination) {
-                    (AssemblyOperand::Stack(_), AssemblyOperand::Stack(_)) => {
-                        let instr1 = AssemblyInstruction::Mov {
-                            source: source.clone(),
-                            destination: register_r10.clone(),
-                        };
-                        let instr2 = AssemblyInstruction::Binary {
-                            op: op.clone(),
-                            source: register_r10.clone(),
-                            destination: destination.clone(),
-                        };
-                        vec![instr1, instr2]
-                    }
-                    _ => vec![asm_instruction.clone()],
-                }
-            }
-            AssemblyBinaryOperator::Mult => {
-                let instr1 = AssemblyInstruction::Mov {
-                    source: destination.clone(),
-                    destination: register_r11.clone(),
-                };
-                let instr2 = AssemblyInstruction::Binary {
-                    op: op.clone(),
-                    source: source.clone(),
-                    destination: register_r11.clone(),
-                };
-                let instr3 = AssemblyInstruction::Mov {
-                    source: register_r11.clone(),
-                    destination: destination.clone(),
-                };
-                vec![instr1, instr2, instr3]
-            }
-        },
-        AssemblyInstruction::Idiv { operand } => {
-            let instr1 = AssemblyInstruction::Mov {
-                source: operand.clone(),
+        } if !op.is_shift() && is_large_immediate(&source) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source,
                 destination: register_r10.clone(),
-            };
-            let instr2 = AssemblyInstruction::Idiv {
+            });
+            fixup_asm_instruction(
+                AssemblyInstruction::Binary {
+                    op,
+                    assembly_type,
+                    source: register_r10,
+                    destination,
+                },
+                fixed_instructions,
+            );
+        }
+        AssemblyInstruction::Binary {
+            op:
+                op @ (AssemblyBinaryOperator::Add
+                | AssemblyBinaryOperator::Sub
+                | AssemblyBinaryOperator::And
+                | AssemblyBinaryOperator::Or
+                | AssemblyBinaryOperator::Xor),
+            assembly_type,
+            source: source @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+            destination: destination @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Binary {
+                op,
+                assembly_type,
+                source: register_r10,
+                destination,
+            });
+        }
+        AssemblyInstruction::Binary {
+            op: AssemblyBinaryOperator::Mult,
+            assembly_type,
+            source,
+            destination,
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: destination.clone(),
+                destination: register_r11.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type,
+                source,
+                destination: register_r11.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: register_r11,
+                destination,
+            });
+        }
+        // The processor masks shift counts to 5 bits, or to 6 bits for 8-byte operands, so masking
+        // an immediate count keeps the result and makes it fit the 1-byte immediate of the instruction.
+        AssemblyInstruction::Binary {
+            op:
+                op @ (AssemblyBinaryOperator::Sal
+                | AssemblyBinaryOperator::Sar
+                | AssemblyBinaryOperator::Shr),
+            assembly_type,
+            source: AssemblyOperand::Imm(count),
+            destination,
+        } => {
+            let count_mask = i64::from(assembly_type.size()) * 8 - 1;
+            fixed_instructions.push(AssemblyInstruction::Binary {
+                op,
+                assembly_type,
+                source: AssemblyOperand::Imm(count & count_mask),
+                destination,
+            });
+        }
+        // Variable shift counts must be in %cl, the count may be of another type than the shifted
+        // operand, but only its lowest byte is used.
+        AssemblyInstruction::Binary {
+            op:
+                op @ (AssemblyBinaryOperator::Sal
+                | AssemblyBinaryOperator::Sar
+                | AssemblyBinaryOperator::Shr),
+            assembly_type,
+            source,
+            destination,
+        } => {
+            let register_cx = AssemblyOperand::Register(AssemblyRegister::CX);
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source,
+                destination: register_cx.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Binary {
+                op,
+                assembly_type,
+                source: register_cx,
+                destination,
+            });
+        }
+        AssemblyInstruction::Idiv {
+            assembly_type,
+            operand,
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: operand,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Idiv {
+                assembly_type,
                 operand: register_r10,
-            };
-            vec![instr1, instr2]
+            });
         }
-        AssemblyInstruction::Cmp { left, right } => match (left, right) {
-            (AssemblyOperand::Stack(_), AssemblyOperand::Stack(_)) => {
-                let instr1 = AssemblyInstruction::Mov {
-                    source: left.clone(),
-                    destination: register_r10.clone(),
-                };
-                let instr2 = AssemblyInstruction::Cmp {
+        AssemblyInstruction::Div {
+            assembly_type,
+            operand,
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: operand,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Div {
+                assembly_type,
+                operand: register_r10,
+            });
+        }
+        AssemblyInstruction::Cmp {
+            assembly_type,
+            left,
+            right,
+        } if is_large_immediate(&left) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: left,
+                destination: register_r10.clone(),
+            });
+            fixup_asm_instruction(
+                AssemblyInstruction::Cmp {
+                    assembly_type,
                     left: register_r10,
-                    right: right.clone(),
-                };
-                vec![instr1, instr2]
-            }
-            (left, AssemblyOperand::Imm(constant)) => {
-                let instr1 = AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Imm(*constant),
-                    destination: register_r11.clone(),
-                };
-                let instr2 = AssemblyInstruction::Cmp {
-                    left: left.clone(),
-                    right: register_r11.clone(),
-                };
-                vec![instr1, instr2]
-            }
-            _ => vec![asm_instruction.clone()],
-        },
-        AssemblyInstruction::Unary { op: _, operand: _ } => vec![asm_instruction.clone()],
-        AssemblyInstruction::Cdq => vec![asm_instruction.clone()],
-        AssemblyInstruction::AllocateStack { stack_offset: _ } => vec![asm_instruction.clone()],
-        AssemblyInstruction::Ret => vec![asm_instruction.clone()],
-        AssemblyInstruction::Jmp { label: _ } => vec![asm_instruction.clone()],
-        AssemblyInstruction::JmpCC {
-            condition: _,
-            label: _,
-        } => vec![asm_instruction.clone()],
-        AssemblyInstruction::Label(_) => vec![asm_instruction.clone()],
-        AssemblyInstruction::SetCC {
-            condition: _,
-            operand: _,
-        } => vec![asm_instruction.clone()],
+                    right,
+                },
+                fixed_instructions,
+            );
+        }
+        AssemblyInstruction::Cmp {
+            assembly_type,
+            left: left @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+            right: right @ (AssemblyOperand::Stack(_) | AssemblyOperand::Data(_)),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: left,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Cmp {
+                assembly_type,
+                left: register_r10,
+                right,
+            });
+        }
+        AssemblyInstruction::Cmp {
+            assembly_type,
+            left,
+            right: right @ AssemblyOperand::Imm(_),
+        } => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type,
+                source: right,
+                destination: register_r11.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Cmp {
+                assembly_type,
+                left,
+                right: register_r11,
+            });
+        }
+        AssemblyInstruction::Push(operand) if is_large_immediate(&operand) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Quadword,
+                source: operand,
+                destination: register_r10.clone(),
+            });
+            fixed_instructions.push(AssemblyInstruction::Push(register_r10));
+        }
+        instruction => fixed_instructions.push(instruction),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_instruction_conversion_pass_success() {
-        let identifier = "tmp.0".to_string();
+/// Fixes up the operands of a sign or zero extension, which cannot read an immediate value or
+/// write to memory.
+///
+/// # Arguments
+///
+/// * `(source_type, destination_type)`: The sizes of the source and the destination.
+/// * `source`: The source of the extension, an immediate is moved to a scratch register.
+/// * `destination`: The destination of the extension, a memory location is replaced with a scratch
+///   register.
+/// * `fixed_instructions`: The vector to push the move of an immediate source to.
+///
+/// # Returns
+///
+/// The fixed source and destination, and the move storing the scratch register into the original
+/// memory destination, if any.
+fn fixup_extension_operands(
+    (source_type, destination_type): (AssemblyType, AssemblyType),
+    source: AssemblyOperand,
+    destination: AssemblyOperand,
+    fixed_instructions: &mut Vec<AssemblyInstruction>,
+) -> (
+    AssemblyOperand,
+    AssemblyOperand,
+    Option<AssemblyInstruction>,
+) {
+    let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+    let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+    let source = match source {
+        AssemblyOperand::Imm(_) => {
+            fixed_instructions.push(AssemblyInstruction::Mov {
+                assembly_type: source_type,
+                source,
+                destination: register_r10.clone(),
+            });
+            register_r10
+        }
+        source => source,
+    };
+    match destination {
+        AssemblyOperand::Stack(_) | AssemblyOperand::Data(_) => (
+            source,
+            register_r11.clone(),
+            Some(AssemblyInstruction::Mov {
+                assembly_type: destination_type,
+                source: register_r11,
+                destination,
+            }),
+        ),
+        destination => (source, destination, None),
+    }
+}
+
+/// Executes the operand validation pass of the code generation pipeline.
+///
+/// Checks that every immediate value fits the instruction using it. Only a `Mov` into a register
+/// accepts an immediate that does not fit into 32 bits, the fixup pass moves the other ones into
+/// scratch registers, but custom passes may leave them behind, and the assembler would silently
+/// truncate them or report an error without pointing to its cause.
+///
+/// # Arguments
+///
+/// * `identifier` - The name of the function, reported in the errors.
+/// * `instructions` - The assembly instructions of the function.
+///
+/// # Returns
+///
+/// A `Result` that is `Ok` if the operands are valid, or the `CodegenError` of the first invalid operand.
+fn operand_validation_pass(
+    identifier: &Symbol,
+    instructions: &[AssemblyInstruction],
+) -> Result<(), CodegenError> {
+    for instruction in instructions {
+        let operands = match instruction {
+            AssemblyInstruction::Mov {
+                destination: AssemblyOperand::Register(_),
+                ..
+            } => vec![],
+            AssemblyInstruction::Mov { source, .. }
+            | AssemblyInstruction::Movsx { source, .. }
+            | AssemblyInstruction::MovZeroExtend { source, .. }
+            | AssemblyInstruction::Binary { source, .. }
+            | AssemblyInstruction::Cvtsi2sd { source, .. } => vec![source],
+            AssemblyInstruction::Cmp { left, right, .. } => vec![left, right],
+            AssemblyInstruction::Unary { operand, .. }
+            | AssemblyInstruction::Idiv { operand, .. }
+            | AssemblyInstruction::Div { operand, .. }
+            | AssemblyInstruction::SetCC { operand, .. } => vec![operand],
+            _ => vec![],
+        };
+        for operand in operands {
+            if let AssemblyOperand::Imm(value) = operand
+                && is_large_immediate(operand)
+            {
+                return Err(CodegenError::ImmediateOutOfRange {
+                    function: identifier.clone(),
+                    value: *value,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ir_gen::tacky_ast::TackyConstant;
+
+    #[test]
+    fn test_label_validation_pass() {
+        let main = Symbol::from("main");
+        let end = Symbol::from("end");
+        let jump_to_end = AssemblyInstruction::JmpCC {
+            condition: AssemblyConditionCode::E,
+            label: end.clone(),
+        };
+        assert_eq!(
+            label_validation_pass(
+                &main,
+                &[jump_to_end.clone(), AssemblyInstruction::Label(end.clone())]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            label_validation_pass(&main, &[jump_to_end]),
+            Err(CodegenError::UndefinedLabel {
+                function: main.clone(),
+                label: end.clone()
+            })
+        );
+        assert_eq!(
+            label_validation_pass(
+                &main,
+                &[
+                    AssemblyInstruction::Label(end.clone()),
+                    AssemblyInstruction::Label(end.clone())
+                ]
+            ),
+            Err(CodegenError::DuplicateLabel {
+                function: main,
+                label: end
+            })
+        );
+    }
+
+    #[test]
+    fn test_convert_ast_rejects_jump_to_undefined_label() {
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![TackyInstruction::Jump {
+                    target: Symbol::from("missing"),
+                }],
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            convert_ast(tacky_ast).unwrap_err().to_string(),
+            "Codegen error: Jump to undefined label 'missing' in function 'main'"
+        );
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_success() {
+        let identifier = Symbol::from("tmp.0");
         let tacky_instructions = vec![
             TackyInstruction::Unary {
                 operator: TackyUnaryOperator::Negate,
-                source: TackyValue::Constant(1),
+                source: TackyValue::Constant(TackyConstant::Int(1)),
                 destination: TackyValue::Variable(identifier.clone()),
             },
             TackyInstruction::Return {
-                value: TackyValue::Variable(identifier.clone()),
+                value: Some(TackyValue::Variable(identifier.clone())),
+            },
+        ];
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &BTreeMap::new(),
+            &mut DoubleConstants::default(),
+        );
+        assert_eq!(
+            result,
+            Ok(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Pseudo(identifier.clone()),
+                },
+                AssemblyInstruction::Unary {
+                    op: AssemblyUnaryOperator::Neg,
+                    assembly_type: AssemblyType::Longword,
+                    operand: AssemblyOperand::Pseudo(identifier.clone()),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Pseudo(identifier.clone()),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+                AssemblyInstruction::Ret,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_void_return() {
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &[TackyInstruction::Return { value: None }],
+            &BTreeMap::new(),
+            &mut DoubleConstants::default(),
+        );
+        assert_eq!(result, Ok(vec![AssemblyInstruction::Ret]));
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_unsigned_operations() {
+        let u = Symbol::from("u.0");
+        let variable_types = BTreeMap::from([(u.clone(), TackyType::UnsignedInt)]);
+        let tacky_instructions = vec![
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Divide,
+                source1: TackyValue::Variable(u.clone()),
+                source2: TackyValue::Constant(TackyConstant::UnsignedInt(3)),
+                destination: TackyValue::Variable(u.clone()),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::LessThan,
+                source1: TackyValue::Variable(u.clone()),
+                source2: TackyValue::Constant(TackyConstant::UnsignedInt(4294967295)),
+                destination: TackyValue::Variable(Symbol::from("tmp.1")),
+            },
+        ];
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        );
+        assert_eq!(
+            result,
+            Ok(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Pseudo(u.clone()),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+                // The dividend is zero extended instead of sign extended.
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(0),
+                    destination: AssemblyOperand::Register(AssemblyRegister::DX),
+                },
+                AssemblyInstruction::Div {
+                    assembly_type: AssemblyType::Longword,
+                    operand: AssemblyOperand::Imm(3),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::AX),
+                    destination: AssemblyOperand::Pseudo(u.clone()),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Longword,
+                    left: AssemblyOperand::Imm(-1),
+                    right: AssemblyOperand::Pseudo(u.clone()),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(0),
+                    destination: AssemblyOperand::Pseudo(Symbol::from("tmp.1")),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::B,
+                    operand: AssemblyOperand::Pseudo(Symbol::from("tmp.1")),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_pseudoregister_replacement_pass_success() {
+        let pseudo_register_name = Symbol::from("tmp.0");
+        let mut instructions = vec![
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Imm(1),
+                destination: AssemblyOperand::Pseudo(pseudo_register_name),
+            },
+            AssemblyInstruction::Ret,
+        ];
+        let frame_size = pseudoregister_replacement_pass(
+            &mut instructions,
+            &BTreeMap::new(),
+            &FxHashSet::default(),
+        );
+        assert_eq!(frame_size, 4);
+        assert_eq!(
+            instructions,
+            vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Ret,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pseudoregister_replacement_pass_aligns_quadwords() {
+        let long = Symbol::from("l.1");
+        let variable_types = BTreeMap::from([(long.clone(), TackyType::Long)]);
+        let mut instructions = vec![
+            AssemblyInstruction::Movsx {
+                source_type: AssemblyType::Longword,
+                destination_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Pseudo(Symbol::from("tmp.0")),
+                destination: AssemblyOperand::Pseudo(long.clone()),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Pseudo(long),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            },
+        ];
+        // The long slot below the 4-byte slot at -4 starts at -16, not at -12.
+        let frame_size = pseudoregister_replacement_pass(
+            &mut instructions,
+            &variable_types,
+            &FxHashSet::default(),
+        );
+        assert_eq!(frame_size, 16);
+        assert_eq!(
+            instructions,
+            vec![
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Longword,
+                    destination_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Stack(-16),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Stack(-16),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stack_frame_size_check() {
+        let identifier = Symbol::from("main");
+        assert_eq!(stack_frame_size_check(&identifier, 8), Ok(8));
+        assert_eq!(
+            stack_frame_size_check(&identifier, constants::MAX_STACK_FRAME_SIZE),
+            Ok(i32::MAX)
+        );
+        // Four billion temporaries would wrap the 32-bit offsets around.
+        let frame_size = 4 * 4_000_000_000;
+        let error = stack_frame_size_check(&identifier, frame_size).unwrap_err();
+        assert_eq!(
+            error,
+            CodegenError::StackFrameTooLarge {
+                function: identifier.clone(),
+                size: frame_size
+            }
+        );
+        assert_eq!(error.code().code, "E0010");
+        // Negative sizes are rejected instead of wrapping around.
+        assert_eq!(
+            stack_frame_size_check(&identifier, -16),
+            Err(CodegenError::StackFrameTooLarge {
+                function: identifier.clone(),
+                size: -16
+            })
+        );
+        assert!(stack_frame_size_check(&identifier, i64::MIN).is_err());
+    }
+
+    #[test]
+    fn test_pseudoregister_replacement_pass_saturates_huge_aggregates() {
+        let array = Symbol::from("array");
+        let variable_types = BTreeMap::from([(
+            array.clone(),
+            TackyType::Aggregate {
+                size: usize::MAX,
+                alignment: 16,
+            },
+        )]);
+        let mut instructions = vec![AssemblyInstruction::Lea {
+            source: AssemblyOperand::Pseudo(array),
+            destination: AssemblyOperand::Register(AssemblyRegister::AX),
+        }];
+        let frame_size = pseudoregister_replacement_pass(
+            &mut instructions,
+            &variable_types,
+            &FxHashSet::default(),
+        );
+        assert!(frame_size > constants::MAX_STACK_FRAME_SIZE);
+        let frame_size = frame_size.saturating_add(15) & !15;
+        assert!(stack_frame_size_check(&Symbol::from("main"), frame_size).is_err());
+    }
+
+    #[test]
+    fn test_instruction_fixup_pass_success() {
+        let instructions = vec![
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Imm(1),
+                destination: AssemblyOperand::Stack(-4),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-4),
+                destination: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Add,
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-8),
+                destination: AssemblyOperand::Stack(-12),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Imm(2),
+                destination: AssemblyOperand::Stack(-12),
+            },
+            AssemblyInstruction::Ret,
+        ];
+        let fixed_instructions = instruction_fixup_pass(instructions);
+        assert_eq!(
+            fixed_instructions,
+            vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Add,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    destination: AssemblyOperand::Stack(-12),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-12),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Mult,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(2),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R11),
+                    destination: AssemblyOperand::Stack(-12),
+                },
+                AssemblyInstruction::Ret,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_fixup_pass_comparisons_and_division() {
+        let instructions = vec![
+            AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Longword,
+                left: AssemblyOperand::Stack(-4),
+                right: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Longword,
+                left: AssemblyOperand::Stack(-4),
+                right: AssemblyOperand::Imm(0),
+            },
+            AssemblyInstruction::Idiv {
+                assembly_type: AssemblyType::Longword,
+                operand: AssemblyOperand::Imm(3),
+            },
+            AssemblyInstruction::Label(Symbol::from("end.0")),
+        ];
+        let fixed_instructions = instruction_fixup_pass(instructions);
+        assert_eq!(
+            fixed_instructions,
+            vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Longword,
+                    left: AssemblyOperand::Register(AssemblyRegister::R10),
+                    right: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(0),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Longword,
+                    left: AssemblyOperand::Stack(-4),
+                    right: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Idiv {
+                    assembly_type: AssemblyType::Longword,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Label(Symbol::from("end.0")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_fixup_pass_bitwise_operations_and_shifts() {
+        let instructions = vec![
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Xor,
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-4),
+                destination: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Sal,
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Imm(33),
+                destination: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Sar,
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-4),
+                destination: AssemblyOperand::Stack(-8),
+            },
+        ];
+        let fixed_instructions = instruction_fixup_pass(instructions);
+        assert_eq!(
+            fixed_instructions,
+            vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Xor,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sal,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::CX),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sar,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::CX),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_long_operations() {
+        let l = Symbol::from("l.0");
+        let i = Symbol::from("i.1");
+        let variable_types = BTreeMap::from([(l.clone(), TackyType::Long)]);
+        let tacky_instructions = vec![
+            TackyInstruction::SignExtend {
+                source: TackyValue::Variable(i.clone()),
+                destination: TackyValue::Variable(l.clone()),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Divide,
+                source1: TackyValue::Variable(l.clone()),
+                source2: TackyValue::Constant(TackyConstant::Long(4294967296)),
+                destination: TackyValue::Variable(l.clone()),
+            },
+            TackyInstruction::ZeroExtend {
+                source: TackyValue::Constant(TackyConstant::UnsignedInt(4294967295)),
+                destination: TackyValue::Variable(l.clone()),
+            },
+            TackyInstruction::Truncate {
+                source: TackyValue::Constant(TackyConstant::Long(4294967297)),
+                destination: TackyValue::Variable(i.clone()),
             },
         ];
-        let result = instruction_conversion_pass(&tacky_instructions);
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        );
         assert_eq!(
             result,
             Ok(vec![
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Longword,
+                    destination_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Pseudo(i.clone()),
+                    destination: AssemblyOperand::Pseudo(l.clone()),
+                },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Imm(1),
-                    destination: AssemblyOperand::Pseudo(identifier.clone()),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Pseudo(l.clone()),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
                 },
-                AssemblyInstruction::Unary {
-                    op: AssemblyUnaryOperator::Neg,
-                    operand: AssemblyOperand::Pseudo(identifier.clone()),
+                AssemblyInstruction::Cdq {
+                    assembly_type: AssemblyType::Quadword,
+                },
+                AssemblyInstruction::Idiv {
+                    assembly_type: AssemblyType::Quadword,
+                    operand: AssemblyOperand::Imm(4294967296),
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Pseudo(identifier.clone()),
-                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Register(AssemblyRegister::AX),
+                    destination: AssemblyOperand::Pseudo(l.clone()),
+                },
+                // The 4-byte move clears the upper half of %r11.
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(-1),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R11),
+                    destination: AssemblyOperand::Pseudo(l),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Pseudo(i),
                 },
-                AssemblyInstruction::Ret,
             ])
         );
     }
 
     #[test]
-    fn test_pseudoregister_replacement_pass_success() {
-        let pseudo_register_name = "tmp.0".to_string();
-        let mut instructions = vec![
+    fn test_instruction_conversion_pass_function_calls() {
+        let c = Symbol::from("c.0");
+        let d = Symbol::from("d.1");
+        let tmp = Symbol::from("tmp.2");
+        let variable_types =
+            BTreeMap::from([(c.clone(), TackyType::Char), (d.clone(), TackyType::Double)]);
+        let mut arguments: Vec<TackyValue> = (1..=6)
+            .map(|value| TackyValue::Constant(TackyConstant::Int(value)))
+            .collect();
+        arguments.push(TackyValue::Variable(c.clone()));
+        arguments.push(TackyValue::Variable(d.clone()));
+        let tacky_instructions = vec![TackyInstruction::FunCall {
+            identifier: Symbol::from("f"),
+            arguments,
+            named_arguments: None,
+            destination: TackyValue::Variable(tmp.clone()),
+        }];
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        )
+        .unwrap();
+        // The seventh integer argument is passed on the stack, padded to keep %rsp aligned, and
+        // the `double` argument in %xmm0.
+        let mut expected = vec![AssemblyInstruction::AllocateStack { stack_offset: 8 }];
+        expected.extend(
+            (1..=6)
+                .zip(constants::ARGUMENT_REGISTERS)
+                .map(|(value, register)| AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(value),
+                    destination: AssemblyOperand::Register(register),
+                }),
+        );
+        expected.extend([
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Pseudo(d),
+                destination: AssemblyOperand::Register(AssemblyRegister::XMM0),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Byte,
+                source: AssemblyOperand::Pseudo(c),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            },
+            AssemblyInstruction::Push(AssemblyOperand::Register(AssemblyRegister::AX)),
+            AssemblyInstruction::Call(Symbol::from("f")),
+            AssemblyInstruction::DeallocateStack { stack_offset: 16 },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Register(AssemblyRegister::AX),
+                destination: AssemblyOperand::Pseudo(tmp),
+            },
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_variadic_function_calls() {
+        let d = Symbol::from("d.0");
+        let tmp = Symbol::from("tmp.1");
+        let variable_types = BTreeMap::from([(d.clone(), TackyType::Double)]);
+        let tacky_instructions = vec![TackyInstruction::FunCall {
+            identifier: Symbol::from("printf"),
+            arguments: vec![
+                TackyValue::Constant(TackyConstant::Int(1)),
+                TackyValue::Variable(d.clone()),
+            ],
+            named_arguments: Some(1),
+            destination: TackyValue::Variable(tmp.clone()),
+        }];
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        )
+        .unwrap();
+        // The number of vector registers used, one for the `double`, is passed in %al.
+        let expected = vec![
             AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
                 source: AssemblyOperand::Imm(1),
-                destination: AssemblyOperand::Pseudo(pseudo_register_name),
+                destination: AssemblyOperand::Register(AssemblyRegister::DI),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Pseudo(d),
+                destination: AssemblyOperand::Register(AssemblyRegister::XMM0),
+            },
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Byte,
+                source: AssemblyOperand::Imm(1),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            },
+            AssemblyInstruction::Call(Symbol::from("printf")),
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Register(AssemblyRegister::AX),
+                destination: AssemblyOperand::Pseudo(tmp),
             },
-            AssemblyInstruction::Ret,
         ];
-        let offset = pseudoregister_replacement_pass(&mut instructions);
-        assert_eq!(offset, -4);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_parameter_copy_pass() {
+        let parameters: Vec<Symbol> = ["a.0", "b.1", "c.2", "d.3", "e.4", "f.5", "g.6", "h.7"]
+            .into_iter()
+            .map(Symbol::from)
+            .collect();
+        let variable_types = BTreeMap::from([
+            (parameters[1].clone(), TackyType::Double),
+            (parameters[7].clone(), TackyType::Long),
+        ]);
+        let instructions = parameter_copy_pass(&parameters, &variable_types);
+        assert_eq!(instructions.len(), 8);
         assert_eq!(
-            instructions,
-            vec![
-                AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Imm(1),
-                    destination: AssemblyOperand::Stack(-4),
-                },
-                AssemblyInstruction::Ret,
-            ]
+            instructions[1],
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Register(AssemblyRegister::XMM0),
+                destination: AssemblyOperand::Pseudo(parameters[1].clone()),
+            }
+        );
+        // The seventh integer parameter is the first one passed on the stack.
+        assert_eq!(
+            instructions[7],
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Stack(16),
+                destination: AssemblyOperand::Pseudo(parameters[7].clone()),
+            }
         );
     }
 
     #[test]
-    fn test_instruction_fixup_pass_success() {
-        let mut instructions = vec![
-            AssemblyInstruction::Mov {
-                source: AssemblyOperand::Imm(1),
-                destination: AssemblyOperand::Stack(-4),
-            },
+    fn test_instruction_fixup_pass_large_immediates() {
+        let instructions = vec![
             AssemblyInstruction::Mov {
-                source: AssemblyOperand::Stack(-4),
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(4294967296),
                 destination: AssemblyOperand::Stack(-8),
             },
             AssemblyInstruction::Binary {
                 op: AssemblyBinaryOperator::Add,
-                source: AssemblyOperand::Stack(-8),
-                destination: AssemblyOperand::Stack(-12),
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(4294967296),
+                destination: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Quadword,
+                left: AssemblyOperand::Imm(-4294967296),
+                right: AssemblyOperand::Stack(-8),
             },
             AssemblyInstruction::Binary {
-                op: AssemblyBinaryOperator::Mult,
-                source: AssemblyOperand::Imm(2),
-                destination: AssemblyOperand::Stack(-12),
+                op: AssemblyBinaryOperator::Sar,
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(65),
+                destination: AssemblyOperand::Stack(-8),
+            },
+            AssemblyInstruction::Movsx {
+                source_type: AssemblyType::Longword,
+                destination_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(-1),
+                destination: AssemblyOperand::Stack(-16),
             },
-            AssemblyInstruction::Ret,
         ];
-        let fixed_instructions = instruction_fixup_pass(&mut instructions);
+        let fixed_instructions = instruction_fixup_pass(instructions);
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
         assert_eq!(
             fixed_instructions,
             vec![
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Imm(1),
-                    destination: AssemblyOperand::Stack(-4),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(4294967296),
+                    destination: register_r10.clone(),
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Stack(-4),
-                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r10.clone(),
+                    destination: AssemblyOperand::Stack(-8),
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(4294967296),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Add,
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r10.clone(),
                     destination: AssemblyOperand::Stack(-8),
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Stack(-8),
-                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(-4294967296),
+                    destination: register_r10.clone(),
                 },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Quadword,
+                    left: register_r10.clone(),
+                    right: AssemblyOperand::Stack(-8),
+                },
+                // 8-byte shift counts are masked to 6 bits.
                 AssemblyInstruction::Binary {
-                    op: AssemblyBinaryOperator::Add,
-                    source: AssemblyOperand::Register(AssemblyRegister::R10),
-                    destination: AssemblyOperand::Stack(-12),
+                    op: AssemblyBinaryOperator::Sar,
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(1),
+                    destination: AssemblyOperand::Stack(-8),
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Stack(-12),
-                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(-1),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Longword,
+                    destination_type: AssemblyType::Quadword,
+                    source: register_r10,
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11,
+                    destination: AssemblyOperand::Stack(-16),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_char_operations() {
+        let c = Symbol::from("c");
+        let u = Symbol::from("u");
+        let i = Symbol::from("i");
+        let l = Symbol::from("l");
+        let variable_types = BTreeMap::from([
+            (c.clone(), TackyType::Char),
+            (u.clone(), TackyType::UnsignedChar),
+            (i.clone(), TackyType::Int),
+            (l.clone(), TackyType::Long),
+        ]);
+        let tacky_instructions = vec![
+            TackyInstruction::SignExtend {
+                source: TackyValue::Variable(c.clone()),
+                destination: TackyValue::Variable(l.clone()),
+            },
+            TackyInstruction::ZeroExtend {
+                source: TackyValue::Variable(u.clone()),
+                destination: TackyValue::Variable(i.clone()),
+            },
+            TackyInstruction::Truncate {
+                source: TackyValue::Variable(i.clone()),
+                destination: TackyValue::Variable(c.clone()),
+            },
+            TackyInstruction::Truncate {
+                source: TackyValue::Constant(TackyConstant::Int(300)),
+                destination: TackyValue::Variable(u.clone()),
+            },
+        ];
+        let result = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        );
+        assert_eq!(
+            result,
+            Ok(vec![
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Pseudo(c.clone()),
+                    destination: AssemblyOperand::Pseudo(l),
+                },
+                AssemblyInstruction::MovZeroExtend {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Pseudo(u.clone()),
+                    destination: AssemblyOperand::Pseudo(i.clone()),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Pseudo(i),
+                    destination: AssemblyOperand::Pseudo(c),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Imm(44),
+                    destination: AssemblyOperand::Pseudo(u),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_convert_ast_string_literal() {
+        let string = Symbol::from("string.0");
+        let address = Symbol::from("tmp.1");
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::GetAddress {
+                        source: string.clone(),
+                        destination: TackyValue::Variable(address.clone()),
+                    },
+                    TackyInstruction::JumpIfZero {
+                        condition: TackyValue::Variable(address.clone()),
+                        target: Symbol::from("end"),
+                    },
+                    TackyInstruction::Label(Symbol::from("end")),
+                ],
+                variable_types: BTreeMap::from([(address, TackyType::Pointer)]),
+            }],
+            static_constants: vec![TackyStaticConstant::String {
+                identifier: string.clone(),
+                value: b"hi".to_vec(),
+            }],
+            static_variables: Vec::new(),
+        };
+        let AssemblyAst::Program {
+            functions,
+            static_constants,
+            ..
+        } = convert_ast(tacky_ast).unwrap();
+        let [AssemblyFunction::Function { instructions, .. }]: [AssemblyFunction; 1] =
+            functions.try_into().unwrap();
+        assert_eq!(
+            static_constants,
+            vec![AssemblyStaticConstant::String {
+                identifier: string.clone(),
+                value: b"hi".to_vec(),
+            }]
+        );
+        // The address is computed into a scratch register, as `lea` cannot store to memory, and the
+        // 8-byte pointer is compared as a quadword.
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            instructions[..4],
+            [
+                AssemblyInstruction::AllocateStack { stack_offset: 16 },
+                AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Data(string),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11,
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Quadword,
+                    left: AssemblyOperand::Imm(0),
+                    right: AssemblyOperand::Stack(-8),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_ast_pointers() {
+        let variable = Symbol::from("x.0");
+        let pointer = TackyValue::Variable(Symbol::from("p.1"));
+        let loaded = TackyValue::Variable(Symbol::from("tmp.2"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::GetAddress {
+                        source: variable.clone(),
+                        destination: pointer.clone(),
+                    },
+                    TackyInstruction::Store {
+                        source: TackyValue::Constant(TackyConstant::Int(3)),
+                        destination_pointer: pointer.clone(),
+                    },
+                    TackyInstruction::Load {
+                        source_pointer: pointer,
+                        destination: loaded.clone(),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(loaded),
+                    },
+                ],
+                variable_types: BTreeMap::from([
+                    (variable, TackyType::Int),
+                    (Symbol::from("p.1"), TackyType::Pointer),
+                    (Symbol::from("tmp.2"), TackyType::Int),
+                ]),
+            }],
+            static_constants: vec![],
+            static_variables: Vec::new(),
+        };
+        let AssemblyAst::Program { functions, .. } = convert_ast(tacky_ast).unwrap();
+        let [AssemblyFunction::Function { instructions, .. }]: [AssemblyFunction; 1] =
+            functions.try_into().unwrap();
+        // The pointer is moved into a register, which addresses the memory of the store and load.
+        let register_ax = AssemblyOperand::Register(AssemblyRegister::AX);
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        let load_pointer = AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Quadword,
+            source: AssemblyOperand::Stack(-16),
+            destination: register_ax.clone(),
+        };
+        assert_eq!(
+            instructions[1..8],
+            [
+                AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Stack(-4),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11,
+                    destination: AssemblyOperand::Stack(-16),
                 },
+                load_pointer.clone(),
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: AssemblyOperand::Memory(AssemblyRegister::AX, 0),
+                },
+                load_pointer,
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Memory(AssemblyRegister::AX, 0),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: register_r10,
+                    destination: AssemblyOperand::Stack(-20),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_conversion_pass_pointer_additions() {
+        let pointer = TackyValue::Variable(Symbol::from("p.1"));
+        let index = TackyValue::Variable(Symbol::from("i.2"));
+        let destination = TackyValue::Variable(Symbol::from("tmp.3"));
+        let add_ptr = |index: &TackyValue, scale| TackyInstruction::AddPtr {
+            pointer: pointer.clone(),
+            index: index.clone(),
+            scale,
+            destination: destination.clone(),
+        };
+        let tacky_instructions = [
+            add_ptr(&TackyValue::Constant(TackyConstant::Long(-2)), 4),
+            add_ptr(&index, 8),
+            add_ptr(&index, 12),
+        ];
+        let variable_types = BTreeMap::from([
+            (Symbol::from("p.1"), TackyType::Pointer),
+            (Symbol::from("i.2"), TackyType::Long),
+            (Symbol::from("tmp.3"), TackyType::Pointer),
+        ]);
+        let instructions = instruction_conversion_pass(
+            &Symbol::from("main"),
+            &tacky_instructions,
+            &variable_types,
+            &mut DoubleConstants::default(),
+        )
+        .unwrap();
+        let register_ax = AssemblyOperand::Register(AssemblyRegister::AX);
+        let register_dx = AssemblyOperand::Register(AssemblyRegister::DX);
+        let load_pointer = AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Quadword,
+            source: AssemblyOperand::Pseudo(Symbol::from("p.1")),
+            destination: register_ax,
+        };
+        let load_index = AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Quadword,
+            source: AssemblyOperand::Pseudo(Symbol::from("i.2")),
+            destination: register_dx.clone(),
+        };
+        let lea = |source| AssemblyInstruction::Lea {
+            source,
+            destination: AssemblyOperand::Pseudo(Symbol::from("tmp.3")),
+        };
+        // A constant index becomes an offset, and a scale beyond 8 is multiplied into the index.
+        assert_eq!(
+            instructions,
+            [
+                load_pointer.clone(),
+                lea(AssemblyOperand::Memory(AssemblyRegister::AX, -8)),
+                load_pointer.clone(),
+                load_index.clone(),
+                lea(AssemblyOperand::Indexed(
+                    AssemblyRegister::AX,
+                    AssemblyRegister::DX,
+                    8
+                )),
+                load_pointer,
+                load_index,
                 AssemblyInstruction::Binary {
                     op: AssemblyBinaryOperator::Mult,
-                    source: AssemblyOperand::Imm(2),
-                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(12),
+                    destination: register_dx,
+                },
+                lea(AssemblyOperand::Indexed(
+                    AssemblyRegister::AX,
+                    AssemblyRegister::DX,
+                    1
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pseudoregister_replacement_pass_allocates_arrays() {
+        let mut instructions = vec![
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Imm(1),
+                destination: AssemblyOperand::Pseudo(Symbol::from("x.0")),
+            },
+            AssemblyInstruction::Lea {
+                source: AssemblyOperand::Pseudo(Symbol::from("a.1")),
+                destination: AssemblyOperand::Pseudo(Symbol::from("b.2")),
+            },
+            AssemblyInstruction::Lea {
+                source: AssemblyOperand::Pseudo(Symbol::from("b.2")),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            },
+        ];
+        let variable_types = BTreeMap::from([
+            (
+                Symbol::from("a.1"),
+                TackyType::Aggregate {
+                    size: 40,
+                    alignment: 16,
+                },
+            ),
+            (
+                Symbol::from("b.2"),
+                TackyType::Aggregate {
+                    size: 3,
+                    alignment: 1,
+                },
+            ),
+        ]);
+        let frame_size = pseudoregister_replacement_pass(
+            &mut instructions,
+            &variable_types,
+            &FxHashSet::default(),
+        );
+        // The array of 40 bytes is aligned to 16 bytes below the `int`, the array of 3 bytes is
+        // placed right below it.
+        assert_eq!(frame_size, 51);
+        assert_eq!(
+            instructions[1],
+            AssemblyInstruction::Lea {
+                source: AssemblyOperand::Stack(-48),
+                destination: AssemblyOperand::Stack(-51),
+            }
+        );
+    }
+
+    #[test]
+    fn test_convert_ast_double_operations() {
+        let negated = TackyValue::Variable(Symbol::from("tmp.0"));
+        let is_less = TackyValue::Variable(Symbol::from("tmp.1"));
+        let truncated = TackyValue::Variable(Symbol::from("tmp.2"));
+        let one_and_half = TackyValue::Constant(TackyConstant::Double(1.5));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Unary {
+                        operator: TackyUnaryOperator::Negate,
+                        source: one_and_half.clone(),
+                        destination: negated.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::LessThan,
+                        source1: negated.clone(),
+                        source2: one_and_half,
+                        destination: is_less.clone(),
+                    },
+                    TackyInstruction::DoubleToUInt {
+                        source: negated,
+                        destination: truncated,
+                    },
+                    TackyInstruction::Return {
+                        value: Some(is_less),
+                    },
+                ],
+                variable_types: BTreeMap::from([
+                    (Symbol::from("tmp.0"), TackyType::Double),
+                    (Symbol::from("tmp.2"), TackyType::UnsignedInt),
+                ]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let AssemblyAst::Program {
+            functions,
+            static_constants,
+            ..
+        } = convert_ast(tacky_ast).unwrap();
+        let [AssemblyFunction::Function { instructions, .. }]: [AssemblyFunction; 1] =
+            functions.try_into().unwrap();
+        // Both reads of 1.5 share a constant, the sign mask of the negation is aligned for `xorpd`.
+        let one_and_half = Symbol::from("double.0");
+        let sign_mask = Symbol::from("double.1");
+        assert_eq!(
+            static_constants,
+            vec![
+                AssemblyStaticConstant::Double {
+                    identifier: one_and_half.clone(),
+                    value: 1.5,
+                    alignment: 8,
+                },
+                AssemblyStaticConstant::Double {
+                    identifier: sign_mask.clone(),
+                    value: -0.0,
+                    alignment: 16,
+                },
+            ]
+        );
+        let register_xmm14 = AssemblyOperand::Register(AssemblyRegister::XMM14);
+        let register_xmm15 = AssemblyOperand::Register(AssemblyRegister::XMM15);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        let movsd =
+            |source: &AssemblyOperand, destination: &AssemblyOperand| AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source: source.clone(),
+                destination: destination.clone(),
+            };
+        assert_eq!(
+            instructions,
+            vec![
+                AssemblyInstruction::AllocateStack { stack_offset: 16 },
+                movsd(
+                    &AssemblyOperand::Data(one_and_half.clone()),
+                    &register_xmm14
+                ),
+                movsd(&register_xmm14, &AssemblyOperand::Stack(-8)),
+                movsd(&AssemblyOperand::Stack(-8), &register_xmm15),
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Xor,
+                    assembly_type: AssemblyType::Double,
+                    source: AssemblyOperand::Data(sign_mask),
+                    destination: register_xmm15.clone(),
+                },
+                movsd(&register_xmm15, &AssemblyOperand::Stack(-8)),
+                // `comisd` compares a register, and sets the flags of an unsigned comparison. `<`
+                // is compared as `>` with swapped operands, which is false for NaN.
+                movsd(&AssemblyOperand::Data(one_and_half), &register_xmm15),
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Double,
+                    left: AssemblyOperand::Stack(-8),
+                    right: register_xmm15,
                 },
                 AssemblyInstruction::Mov {
-                    source: AssemblyOperand::Register(AssemblyRegister::R11),
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(0),
                     destination: AssemblyOperand::Stack(-12),
                 },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::A,
+                    operand: AssemblyOperand::Stack(-12),
+                },
+                // An `unsigned int` is the lower half of the `long` the `double` converts into.
+                AssemblyInstruction::Cvttsd2si {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: register_r11,
+                    destination: AssemblyOperand::Stack(-16),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-12),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
                 AssemblyInstruction::Ret,
             ]
         );
     }
+
+    #[test]
+    fn test_convert_ast_merges_double_constants_in_function_order() {
+        let copy_doubles = |identifier: &str, values: &[f64]| TackyFunction::Function {
+            identifier: Symbol::from(identifier),
+            global: true,
+            parameters: vec![],
+            instructions: values
+                .iter()
+                .map(|value| TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Double(*value)),
+                    destination: TackyValue::Variable(Symbol::from("x")),
+                })
+                .collect(),
+            variable_types: BTreeMap::from([(Symbol::from("x"), TackyType::Double)]),
+        };
+        let tacky_ast = TackyAst::Program {
+            functions: vec![
+                copy_doubles("first", &[1.5]),
+                copy_doubles("second", &[2.5, 1.5]),
+                copy_doubles("third", &[0.5, 2.5]),
+            ],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let AssemblyAst::Program {
+            functions,
+            static_constants,
+            ..
+        } = convert_ast(tacky_ast).unwrap();
+        // The functions are converted in parallel, the constants keep the numbering of a
+        // conversion of the functions in order.
+        let constant = |index: usize, value: f64| AssemblyStaticConstant::Double {
+            identifier: Symbol::from(format!("double.{}", index)),
+            value,
+            alignment: 8,
+        };
+        assert_eq!(
+            static_constants,
+            vec![constant(0, 1.5), constant(1, 2.5), constant(2, 0.5)]
+        );
+        let read_constants = functions
+            .into_iter()
+            .map(
+                |AssemblyFunction::Function {
+                     mut instructions, ..
+                 }| {
+                    let mut identifiers = Vec::new();
+                    for instruction in instructions.iter_mut() {
+                        for_each_operand(instruction, |operand| {
+                            if let AssemblyOperand::Data(identifier) = operand {
+                                identifiers.push(identifier.to_string());
+                            }
+                        });
+                    }
+                    identifiers
+                },
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(
+            read_constants,
+            vec![
+                vec!["double.0"],
+                vec!["double.1", "double.0"],
+                vec!["double.2", "double.1"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_instruction_fixup_pass_char_extensions() {
+        let instructions = vec![
+            AssemblyInstruction::Movsx {
+                source_type: AssemblyType::Byte,
+                destination_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(-56),
+                destination: AssemblyOperand::Stack(-16),
+            },
+            AssemblyInstruction::MovZeroExtend {
+                source_type: AssemblyType::Byte,
+                destination_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-1),
+                destination: AssemblyOperand::Stack(-8),
+            },
+        ];
+        let fixed_instructions = instruction_fixup_pass(instructions);
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            fixed_instructions,
+            vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Imm(-56),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Quadword,
+                    source: register_r10,
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11.clone(),
+                    destination: AssemblyOperand::Stack(-16),
+                },
+                AssemblyInstruction::MovZeroExtend {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-1),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: register_r11,
+                    destination: AssemblyOperand::Stack(-8),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operand_validation_pass() {
+        let main = Symbol::from("main");
+        let move_to_register = AssemblyInstruction::Mov {
+            assembly_type: AssemblyType::Quadword,
+            source: AssemblyOperand::Imm(4294967296),
+            destination: AssemblyOperand::Register(AssemblyRegister::R10),
+        };
+        assert_eq!(operand_validation_pass(&main, &[move_to_register]), Ok(()));
+        let compare = AssemblyInstruction::Cmp {
+            assembly_type: AssemblyType::Quadword,
+            left: AssemblyOperand::Imm(4294967296),
+            right: AssemblyOperand::Stack(-8),
+        };
+        let error = operand_validation_pass(&main, &[compare]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Codegen error: Immediate value 4294967296 does not fit into 32 bits in function 'main'"
+        );
+        assert_eq!(error.code().code, "E0008");
+    }
 }
@@ -0,0 +1,811 @@
+use std::fmt;
+
+/// Represents a stable identifier of a kind of diagnostic, together with its documentation.
+///
+/// Errors and warnings share the same codes.
+///
+/// The codes never change meaning once assigned, so that tools and users can rely on them, e.g. to
+/// look up the explanation with `cmmc_driver explain E0002`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ErrorCode {
+    /// The identifier, e.g. `E0002`.
+    pub code: &'static str,
+    /// A one-line summary of the diagnostic.
+    pub title: &'static str,
+    /// A longer description of the diagnostic with examples.
+    pub explanation: &'static str,
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+pub const UNDECLARED_VARIABLE: ErrorCode = ErrorCode {
+    code: "E0001",
+    title: "Undeclared variable",
+    explanation: "\
+A variable was used before it was declared.
+
+Erroneous code example:
+
+    int main(void) {
+        return x;
+    }
+
+Every variable must be declared before its first use:
+
+    int main(void) {
+        int x = 2;
+        return x;
+    }
+",
+};
+
+pub const EXPECTED_TOKEN: ErrorCode = ErrorCode {
+    code: "E0002",
+    title: "Expected token",
+    explanation: "\
+The parser found a token that cannot appear at this point of the program. The message lists the
+token, or the tokens, that would have been valid instead.
+
+Erroneous code example:
+
+    int main(void) {
+        return;
+    }
+
+A return statement needs an expression to return:
+
+    int main(void) {
+        return 0;
+    }
+",
+};
+
+pub const UNEXPECTED_END_OF_INPUT: ErrorCode = ErrorCode {
+    code: "E0003",
+    title: "Unexpected end of input",
+    explanation: "\
+The source file ended in the middle of a construct, usually because of a missing closing brace or
+semicolon.
+
+Erroneous code example:
+
+    int main(void) {
+        return 2;
+
+Close every opened brace:
+
+    int main(void) {
+        return 2;
+    }
+",
+};
+
+pub const TRAILING_TOKENS: ErrorCode = ErrorCode {
+    code: "E0004",
+    title: "Trailing tokens after a statement",
+    explanation: "\
+The input continues after a complete statement or expression. The REPL evaluates a single
+statement or expression at a time. In a source file, a token that cannot start a declaration is
+reported as an unexpected token instead.
+
+Erroneous code example, entered in the REPL:
+
+    1 + 2 3
+
+Enter one expression at a time:
+
+    1 + 2
+",
+};
+
+pub const INVALID_TOKEN: ErrorCode = ErrorCode {
+    code: "E0005",
+    title: "Invalid token",
+    explanation: "\
+The lexer found characters that do not form a valid C-- token, such as a character that is not
+part of the language or an identifier starting with a digit, or a block comment `/*` that is never
+closed with `*/`.
+
+Erroneous code example:
+
+    int main(void) {
+        return 1abc @ 2;
+    }
+
+Use only the operators of C-- and start identifiers with a letter or an underscore:
+
+    int main(void) {
+        return 1 + 2;
+    }
+",
+};
+
+pub const INVALID_CONSTANT: ErrorCode = ErrorCode {
+    code: "E0006",
+    title: "Invalid constant",
+    explanation: "\
+An integer constant does not fit into its type, or is malformed. Constants too large for an `int`
+are `long`s, so a decimal constant is invalid beyond the range of a `long`, whose values range from
+-9223372036854775808 to 9223372036854775807. A constant with a `u` suffix and a hexadecimal or
+octal constant may be an `unsigned long`, up to 18446744073709551615. A hexadecimal constant needs
+at least one digit after its `0x` prefix, and an octal constant, which starts with `0`, only has
+the digits 0 to 7, so `0x` and `09` are invalid.
+
+Erroneous code example:
+
+    int main(void) {
+        return 99999999999999999999 > 0;
+    }
+
+Use a constant in the range of a `long`, or an `unsigned long` constant:
+
+    int main(void) {
+        return 9223372036854775807 > 0 && 18446744073709551615UL > 0;
+    }
+
+A character constant is invalid if it is empty, holds more than one character or a character outside
+of ASCII, or uses an escape sequence other than `\\'`, `\\\"`, `\\?`, `\\\\`, `\\a`, `\\b`, `\\f`, `\\n`, `\\r`,
+`\\t`, `\\v`, and `\\0`, e.g. `'ab'` or `'\\x'`. A string literal is invalid if it is unterminated,
+spans several lines, or uses any other escape sequence, e.g. `\"\\x\"`.
+",
+};
+
+pub const UNSUPPORTED_TARGET: ErrorCode = ErrorCode {
+    code: "E0007",
+    title: "Unsupported target",
+    explanation: "\
+No enabled backend generates code for the requested target. The native backend only supports
+x86-64, and the compiler may have been built without it.
+
+Build for an x86-64 target, e.g. with `--target x86_64-unknown-linux-gnu`, or use the QBE backend
+with `--backend qbe`, which also supports ARM64.
+",
+};
+
+pub const INTERNAL_COMPILER_ERROR: ErrorCode = ErrorCode {
+    code: "E0008",
+    title: "Internal compiler error",
+    explanation: "\
+A later stage of the compiler received a program that an earlier stage should have rejected or
+transformed. This is a bug in the compiler, not in the compiled program.
+
+Please report the source file that triggered the error.
+",
+};
+
+pub const DIVISION_BY_ZERO: ErrorCode = ErrorCode {
+    code: "E0009",
+    title: "Division by zero",
+    explanation: "\
+The divisor of a division or a remainder is the constant zero. The program compiles, as dividing by
+zero is only undefined when it is executed, but it fails at runtime when the division is reached.
+This is a warning named `division-by-zero`, which is turned into an error by `-Werror`.
+
+Erroneous code example:
+
+    int main(void) {
+        return 10 / 0;
+    }
+
+Divide by a nonzero value:
+
+    int main(void) {
+        return 10 / 2;
+    }
+",
+};
+
+pub const STACK_FRAME_TOO_LARGE: ErrorCode = ErrorCode {
+    code: "E0010",
+    title: "Stack frame too large",
+    explanation: "\
+A function needs more stack space than x86-64 instructions can address. Every temporary value of a
+function gets its own 4-byte stack slot, and the slots are addressed with 32-bit offsets, which
+limits a stack frame to 2147483647 bytes, or about 500 million temporaries.
+
+Reduce the size of the function, or compile with `--backend qbe`, which keeps temporaries in
+registers where possible.
+",
+};
+
+pub const INVALID_ASSIGNMENT_TARGET: ErrorCode = ErrorCode {
+    code: "E0011",
+    title: "Invalid assignment target",
+    explanation: "\
+The left side of an assignment, the operand of `++` or `--`, or the operand of `&` is neither a
+variable, a dereferenced pointer such as `*p`, nor an array element such as `a[i]`, so there is no
+storage to assign the value to or to take the address of. A whole array cannot be assigned to either,
+only its elements can.
+
+Erroneous code example:
+
+    int main(void) {
+        for (int x = 0; x < 3; x + 1 = 2)
+            return x;
+    }
+
+Assign to a variable:
+
+    int main(void) {
+        for (int x = 0; x < 3; x = x + 1)
+            return x;
+    }
+",
+};
+
+pub const JUMP_OUTSIDE_LOOP: ErrorCode = ErrorCode {
+    code: "E0012",
+    title: "Break or continue outside of a loop",
+    explanation: "\
+A `break` or `continue` statement is not inside a loop, so there is no loop to exit or to continue.
+
+Erroneous code example:
+
+    int main(void) {
+        if (1)
+            break;
+    }
+
+Only use `break` and `continue` in the body of a `while`, `do`-`while`, or `for` loop:
+
+    int main(void) {
+        for (;;)
+            if (1)
+                break;
+    }
+",
+};
+
+pub const INVALID_TYPE_SPECIFIERS: ErrorCode = ErrorCode {
+    code: "E0013",
+    title: "Invalid type specifiers",
+    explanation: "\
+The type specifiers of a declaration do not form a valid type. A declaration names its type with
+`int`, `unsigned`, or both, with `long` and an optional `int` or `unsigned`, with `short` and an
+optional `int`, or with `char` and an optional `unsigned`. `signed` may be added to the signed
+integer types. Each specifier may only appear once.
+
+Erroneous code example:
+
+    int main(void) {
+        for (unsigned unsigned x = 0; x < 3; x = x + 1)
+            return x;
+    }
+
+Use each specifier at most once:
+
+    int main(void) {
+        for (unsigned int x = 0; x < 3; x = x + 1)
+            return x;
+    }
+",
+};
+
+pub const INVALID_OPERAND_TYPE: ErrorCode = ErrorCode {
+    code: "E0014",
+    title: "Invalid operand type",
+    explanation: "\
+A value is used where its type is not allowed. A pointer, such as a string literal, which evaluates
+to the address of its first character, can be tested as a condition, compared, dereferenced, and
+moved by adding or subtracting an integer, but not used as an integer in other arithmetic,
+assignments, or return values. Only a pointer can be dereferenced with `*` or subscripted with
+`[]`. A double cannot be an operand of the remainder, complement, bitwise, or shift operators,
+which only accept integers. A whole structure cannot be used as a value, only its members can. A
+call to a `void` function has no value, so it can only stand alone as an expression statement.
+
+Erroneous code example:
+
+    int main(void) {
+        return \"hello\" * 2;
+    }
+
+Only test string literals as conditions:
+
+    int main(void) {
+        if (\"hello\")
+            return 1;
+    }
+",
+};
+
+pub const INVALID_FUNCTION_CALL: ErrorCode = ErrorCode {
+    code: "E0015",
+    title: "Invalid function call",
+    explanation: "\
+A function call does not match a function of the program. The called function must be declared
+before the call, it must not be hidden by a variable of the same name, and it must be called with exactly one
+argument per parameter, or at least one for a variadic function. The arguments are converted to the types of the
+parameters.
+
+Erroneous code example:
+
+    int count(int n, int depth) {
+        if (n == 1)
+            return count(2);
+        else
+            return depth;
+    }
+
+Pass an argument for every parameter:
+
+    int count(int n, int depth) {
+        if (n == 1)
+            return count(2, 10);
+        else
+            return depth;
+    }
+",
+};
+
+pub const DUPLICATE_DECLARATION: ErrorCode = ErrorCode {
+    code: "E0016",
+    title: "Duplicate declaration",
+    explanation: "\
+A name is declared twice in the same scope, such as two parameters of a function with the same
+name or a local variable with the name of a parameter, or a function, a file-scope variable, a
+structure, or an enumeration is defined twice, so uses of the name would be ambiguous.
+
+Erroneous code example:
+
+    int add(int x, int x) {
+        return x;
+    }
+
+Give every parameter its own name:
+
+    int add(int x, int y) {
+        return x + y;
+    }
+",
+};
+
+pub const CONFLICTING_LINKAGE: ErrorCode = ErrorCode {
+    code: "E0017",
+    title: "Conflicting linkage",
+    explanation: "\
+A function or a file-scope variable is declared `static` after a declaration that gave it external
+linkage, or a file-scope variable is declared without a storage-class specifier after a `static`
+declaration. The first declaration of a name determines whether other files can refer to it, and
+later declarations cannot change it.
+
+Erroneous code example:
+
+    int helper(void);
+    static int helper(void) {
+        return 1;
+    }
+
+Declare the function `static` the first time:
+
+    static int helper(void);
+    static int helper(void) {
+        return 1;
+    }
+",
+};
+
+pub const INCOMPATIBLE_POINTER_TYPES: ErrorCode = ErrorCode {
+    code: "E0018",
+    title: "Incompatible pointer types",
+    explanation: "\
+A pointer is assigned, passed, or compared where a pointer to another type is expected, or a value
+that is not a pointer is converted to a pointer. Only the null pointer constant `0` converts to a
+pointer implicitly, and only a cast converts a pointer to a pointer to another type. A pointer can
+gain a `const` qualifier of the type it points to, but not lose it, since writing through the
+assigned pointer would modify a `const` object.
+
+Erroneous code example:
+
+    int main(void) {
+        for (long x = 1; x; x = 0)
+            for (int *p = &x; p; p = 0)
+                return *p;
+    }
+
+Point to a variable of the referenced type:
+
+    int main(void) {
+        for (int x = 1; x; x = 0)
+            for (int *p = &x; p; p = 0)
+                return *p;
+    }
+",
+};
+
+pub const INVALID_ARRAY_SIZE: ErrorCode = ErrorCode {
+    code: "E0019",
+    title: "Invalid array size",
+    explanation: "\
+The size of an array declaration is not a positive integer constant. The number of elements of an
+array must be known when the program is compiled, so it is written as an integer constant such as
+`10`, and an array needs at least one element.
+
+Erroneous code example:
+
+    int main(int argc, char **argv) {
+        for (int a[0]; argc; argc = 0)
+            return 1;
+    }
+
+Give the array at least one element:
+
+    int main(int argc, char **argv) {
+        for (int a[1]; argc; argc = 0)
+            return 1;
+    }
+",
+};
+
+pub const INVALID_INITIALIZER: ErrorCode = ErrorCode {
+    code: "E0020",
+    title: "Invalid initializer",
+    explanation: "\
+The initializer of a declaration does not match the type of the variable. An array is initialized
+with a braced list holding an initializer for each of its elements, such as `{1, 2, 3}`, and the
+elements left out at the end are zero. A list cannot hold more initializers than the array has
+elements, an array cannot be initialized with an expression, and a variable that is not an array
+cannot be initialized with a list. A structure is not initialized, its members are assigned
+instead. A variable declared outside of the functions or declared `static` is initialized before
+the program starts, so its initializer must be a constant expression, which cannot refer to
+variables or call functions. An `extern` declaration inside a function cannot be initialized.
+
+Erroneous code example:
+
+    int main(void) {
+        for (int a[2] = {1, 2, 3};;)
+            return a[0];
+    }
+
+Give every element at most one initializer:
+
+    int main(void) {
+        for (int a[3] = {1, 2, 3};;)
+            return a[0];
+    }
+",
+};
+
+pub const UNKNOWN_MEMBER: ErrorCode = ErrorCode {
+    code: "E0021",
+    title: "Unknown structure member",
+    explanation: "\
+A member is accessed that the structure does not declare, or a member is accessed on a value that
+is not a structure. The operand of `.` must be a structure, and the operand of `->` a pointer to a
+structure, which has a member of the accessed name.
+
+Erroneous code example:
+
+    struct point { int x; int y; };
+    int main(void) {
+        for (struct point p; ; )
+            return p.z = 1;
+    }
+
+Access a member the structure declares:
+
+    struct point { int x; int y; };
+    int main(void) {
+        for (struct point p; ; )
+            return p.x = 1;
+    }
+",
+};
+
+pub const INCOMPLETE_TYPE: ErrorCode = ErrorCode {
+    code: "E0022",
+    title: "Incomplete structure type",
+    explanation: "\
+A structure type is used before its declaration, where its size or members must be known. A
+variable, a member, or an accessed structure needs a declared structure, while a pointer can point
+to a structure declared later. A structure cannot contain itself, only a pointer to itself.
+
+Erroneous code example:
+
+    struct node { int value; struct node next; };
+
+Point to the next structure instead:
+
+    struct node { int value; struct node *next; };
+",
+};
+
+pub const INVALID_ENUMERATOR: ErrorCode = ErrorCode {
+    code: "E0023",
+    title: "Invalid enumerator value",
+    explanation: "\
+The value of an enumerator is not an integer constant expression, or does not fit an `int`. The
+values of enumerators are computed when the program is compiled, so they can only consist of
+integer constants, the enumerators declared before, casts to integer types, and operators without
+side effects, and must not divide by zero. The operations are computed in their types like at run
+time, so the product below is a `long` too large for an `int`.
+
+Erroneous code example:
+
+    enum sizes { SMALL = 1, LARGE = SMALL * 2147483648 };
+
+Keep the values within the range of `int`:
+
+    enum sizes { SMALL = 1, LARGE = SMALL * 1024 };
+",
+};
+
+pub const ASSIGNMENT_TO_CONST: ErrorCode = ErrorCode {
+    code: "E0024",
+    title: "Assignment to a const object",
+    explanation: "\
+An object of a `const` qualified type is assigned, incremented, or decremented. A `const` object
+keeps the value it is initialized with, so it can only be given a value in its declaration. The
+elements of a `const` array and the members of a `const` structure are `const` as well, and so is
+the object a pointer to a `const` type points to.
+
+Erroneous code example:
+
+    int main(void) {
+        for (const int limit = 10; ; )
+            return limit = 20;
+    }
+
+Initialize the object with its value, or remove the qualifier if it must change:
+
+    int main(void) {
+        for (int limit = 10; ; )
+            return limit = 20;
+    }
+",
+};
+
+pub const EXTENSION_NOT_ENABLED: ErrorCode = ErrorCode {
+    code: "E0025",
+    title: "GNU extension in standard C",
+    explanation: "\
+The source code uses a GNU extension of C while the compiler accepts standard C only. Binary
+constants with a `0b` or `0B` prefix, such as `0b1010`, are a GNU extension. Pass `--std=gnu` to
+accept the extensions, or write the constant in hexadecimal, octal, or decimal.
+
+Erroneous code example:
+
+    int main(void) {
+        return 0b1010;
+    }
+
+Write the constant in hexadecimal:
+
+    int main(void) {
+        return 0xA;
+    }
+",
+};
+
+pub const INVALID_RETURN: ErrorCode = ErrorCode {
+    code: "E0026",
+    title: "Invalid return statement",
+    explanation: "\
+A `return` statement does not match the return type of its function. A `void` function returns
+nothing, so its `return` statements cannot have a value, while every `return` statement of a
+function returning an `int` needs one.
+
+Erroneous code example:
+
+    void reset(void) {
+        return 0;
+    }
+
+Leave out the value:
+
+    void reset(void) {
+        return;
+    }
+",
+};
+
+pub const UNUSED_VARIABLE: ErrorCode = ErrorCode {
+    code: "E0027",
+    title: "Unused variable",
+    explanation: "\
+A local variable is declared, but its value is never read, so the variable can be removed or the
+code meant to use it is missing. Assigning to the variable does not count as reading it. This is a
+warning named `unused-variable`, which is only reported with `-Wunused-variable`, `-Wunused`, or
+`-Wall`, and turned into an error by `-Werror`.
+
+Erroneous code example:
+
+    int main(void) {
+        int unused = 2;
+        return 0;
+    }
+
+Remove the variable, or use its value:
+
+    int main(void) {
+        int used = 2;
+        return used;
+    }
+",
+};
+
+pub const UNREACHABLE_CODE: ErrorCode = ErrorCode {
+    code: "E0028",
+    title: "Unreachable code",
+    explanation: "\
+A function body continues after a `return`, `break`, or `continue` statement, which always jumps
+away, so the code after it can never run. This is a warning named `unreachable-code`, which is
+turned into an error by `-Werror`.
+
+Erroneous code example:
+
+    int main(void) {
+        return 0;
+        return 1;
+    }
+
+Remove the code, or make the jump conditional:
+
+    int main(void) {
+        return 0;
+    }
+",
+};
+
+pub const CONFLICTING_FUNCTION_TYPES: ErrorCode = ErrorCode {
+    code: "E0029",
+    title: "Conflicting function types",
+    explanation: "\
+Two declarations of a function, such as a prototype and the definition, do not agree on its type:
+the return types, the numbers of parameters, the types of a parameter, or whether the function is
+variadic differ. Every declaration of a function must have the same type, so that calls checked
+against the prototype match the definition. A parameter declared as an array is a pointer, and a
+`const` qualifier of a parameter itself does not matter.
+
+Erroneous code example:
+
+    int add(int a, int b);
+    int add(int a, long b) {
+        return a + b;
+    }
+
+Declare the parameters with the same types:
+
+    int add(int a, long b);
+    int add(int a, long b) {
+        return a + b;
+    }
+",
+};
+
+pub const INVALID_MAIN_SIGNATURE: ErrorCode = ErrorCode {
+    code: "E0030",
+    title: "Invalid signature of main",
+    explanation: "\
+The function `main` is declared with a signature that C does not allow. The program starts by
+calling `main`, so it must return an `int` exit status and must have external linkage, which
+rules out `static`. It takes either no parameters, or the argument count as an `int` and the
+arguments as a `char **`.
+
+Erroneous code example:
+
+    void main(void) {
+        return;
+    }
+
+Return an `int`:
+
+    int main(void) {
+        return 0;
+    }
+",
+};
+
+pub const CONFLICTING_DECLARATIONS: ErrorCode = ErrorCode {
+    code: "E0031",
+    title: "Conflicting declarations",
+    explanation: "\
+Two file-scope declarations of a name do not agree: a variable is declared with different types,
+or the name is declared both as a variable and as a function. All declarations of a name in the
+file scope refer to the same entity, so they must declare it the same way.
+
+Erroneous code example:
+
+    int counter;
+    long counter;
+
+Declare the variable with the same type every time:
+
+    long counter;
+    long counter;
+",
+};
+
+pub const TYPE_TOO_LARGE: ErrorCode = ErrorCode {
+    code: "E0032",
+    title: "Type too large",
+    explanation: "\
+An array or structure type is larger than the largest object. The size of an object must fit a
+signed 64-bit integer, so that the distance between any two pointers into it can be computed, which
+limits an object to 9223372036854775807 bytes. The size of a structure includes the padding that
+aligns its members, so members that fit on their own may not fit together.
+
+Erroneous code example:
+
+    long table[2305843009213693952];
+
+Declare fewer elements:
+
+    long table[1024];
+",
+};
+
+/// Every assigned error code, in increasing order.
+pub const ERROR_CODES: &[ErrorCode] = &[
+    UNDECLARED_VARIABLE,
+    EXPECTED_TOKEN,
+    UNEXPECTED_END_OF_INPUT,
+    TRAILING_TOKENS,
+    INVALID_TOKEN,
+    INVALID_CONSTANT,
+    UNSUPPORTED_TARGET,
+    INTERNAL_COMPILER_ERROR,
+    DIVISION_BY_ZERO,
+    STACK_FRAME_TOO_LARGE,
+    INVALID_ASSIGNMENT_TARGET,
+    JUMP_OUTSIDE_LOOP,
+    INVALID_TYPE_SPECIFIERS,
+    INVALID_OPERAND_TYPE,
+    INVALID_FUNCTION_CALL,
+    DUPLICATE_DECLARATION,
+    CONFLICTING_LINKAGE,
+    INCOMPATIBLE_POINTER_TYPES,
+    INVALID_ARRAY_SIZE,
+    INVALID_INITIALIZER,
+    UNKNOWN_MEMBER,
+    INCOMPLETE_TYPE,
+    INVALID_ENUMERATOR,
+    ASSIGNMENT_TO_CONST,
+    EXTENSION_NOT_ENABLED,
+    INVALID_RETURN,
+    UNUSED_VARIABLE,
+    UNREACHABLE_CODE,
+    CONFLICTING_FUNCTION_TYPES,
+    INVALID_MAIN_SIGNATURE,
+    CONFLICTING_DECLARATIONS,
+    TYPE_TOO_LARGE,
+];
+
+/// Looks up an error code by its identifier.
+///
+/// # Arguments
+///
+/// * `code`: The identifier, e.g. `E0002`. The letter may be lowercase.
+///
+/// # Returns
+///
+/// The `ErrorCode` with the identifier, or `None` if no such code is assigned.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::error_codes::{EXPECTED_TOKEN, lookup};
+/// assert_eq!(lookup("e0002"), Some(&EXPECTED_TOKEN));
+/// assert_eq!(lookup("E9999"), None);
+/// ```
+pub fn lookup(code: &str) -> Option<&'static ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .find(|error_code| error_code.code.eq_ignore_ascii_case(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_sequential() {
+        for (index, error_code) in ERROR_CODES.iter().enumerate() {
+            assert_eq!(error_code.code, format!("E{:04}", index + 1));
+            assert!(error_code.explanation.ends_with('\n'));
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::common::diagnostics::Span;
+use crate::common::target::Target;
+#[cfg(feature = "x86_64")]
+use crate::compiler::code_gen::errors::CodegenError;
+use crate::compiler::error_codes::{self, ErrorCode};
+use crate::compiler::ir_gen::errors::{IRConversionError, TackyVerificationError};
+use crate::compiler::lexer::errors::LexerError;
+use crate::compiler::parser::errors::ParserError;
+use crate::compiler::semantic::errors::SemanticError;
+use crate::compiler::semantic::warnings::SemanticWarning;
+use std::error::Error;
+use std::fmt;
+
+/// Represents any error that can occur while compiling C-- source code.
+///
+/// Each variant wraps the error type of a single compiler stage, so embedders can match on the
+/// category of the failure without inspecting error messages.
+#[derive(Debug, PartialEq)]
+pub enum CompilerError {
+    /// Raised when the lexer fails to tokenize the source code.
+    Lexer(LexerError),
+    /// Raised when the parser fails to build an AST from the tokens.
+    Parser(ParserError),
+    /// Raised when the semantic analysis rejects the AST, such as for an undeclared variable.
+    Semantic(SemanticError),
+    /// Raised for a warning when warnings are treated as errors.
+    Warning(SemanticWarning),
+    /// Raised when the C-- AST cannot be converted into TACKY IR.
+    IRConversion(IRConversionError),
+    /// Raised when the TACKY IR breaks an invariant the later stages rely on.
+    TackyVerification(TackyVerificationError),
+    /// Raised when the TACKY IR cannot be converted into an assembly AST.
+    #[cfg(feature = "x86_64")]
+    Codegen(CodegenError),
+    /// Raised when code generation is requested for a target without an enabled backend.
+    UnsupportedTarget { target: Target },
+}
+
+impl CompilerError {
+    /// Returns the stable `ErrorCode` of the error.
+    ///
+    /// Errors of the TACKY verification and most errors of the code generator are bugs of the
+    /// compiler, so they share a single code.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CompilerError::Lexer(error) => error.code(),
+            CompilerError::Parser(error) => error.code(),
+            CompilerError::Semantic(error) => error.code(),
+            CompilerError::Warning(warning) => warning.code(),
+            CompilerError::IRConversion(error) => error.code(),
+            CompilerError::TackyVerification(_) => error_codes::INTERNAL_COMPILER_ERROR,
+            #[cfg(feature = "x86_64")]
+            CompilerError::Codegen(error) => error.code(),
+            CompilerError::UnsupportedTarget { .. } => error_codes::UNSUPPORTED_TARGET,
+        }
+    }
+
+    /// Returns the span of the source code the error points to, if the stage that raised it knows
+    /// one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            CompilerError::Lexer(error) => error.span(),
+            CompilerError::Parser(error) => error.span(),
+            CompilerError::Semantic(error) => error.span(),
+            CompilerError::Warning(warning) => warning.span(),
+            _ => None,
+        }
+    }
+
+    /// Returns the description of the error without the location that its `Display` appends,
+    /// for formats that report the `span` separately.
+    pub fn message(&self) -> String {
+        match self {
+            CompilerError::Lexer(LexerError::Located { error, .. }) => error.to_string(),
+            CompilerError::Parser(ParserError::Located { error, .. }) => error.to_string(),
+            CompilerError::Semantic(SemanticError::Located { error, .. }) => error.to_string(),
+            CompilerError::Warning(warning) => format!("{} [-Werror]", warning.message()),
+            error => error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CompilerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompilerError::Lexer(error) => write!(f, "{}", error),
+            CompilerError::Parser(error) => write!(f, "{}", error),
+            CompilerError::Semantic(error) => write!(f, "{}", error),
+            CompilerError::Warning(warning) => write!(f, "{} [-Werror]", warning),
+            CompilerError::IRConversion(error) => write!(f, "{}", error),
+            CompilerError::TackyVerification(error) => write!(f, "{}", error),
+            #[cfg(feature = "x86_64")]
+            CompilerError::Codegen(error) => write!(f, "{}", error),
+            CompilerError::UnsupportedTarget { target } => write!(
+                f,
+                "Unsupported target '{}': no enabled backend generates code for it",
+                target
+            ),
+        }
+    }
+}
+
+impl Error for CompilerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CompilerError::Lexer(error) => Some(error),
+            CompilerError::Parser(error) => Some(error),
+            CompilerError::Semantic(error) => Some(error),
+            CompilerError::Warning(warning) => Some(warning),
+            CompilerError::IRConversion(error) => Some(error),
+            CompilerError::TackyVerification(error) => Some(error),
+            #[cfg(feature = "x86_64")]
+            CompilerError::Codegen(error) => Some(error),
+            CompilerError::UnsupportedTarget { .. } => None,
+        }
+    }
+}
+
+impl From<LexerError> for CompilerError {
+    fn from(error: LexerError) -> Self {
+        CompilerError::Lexer(error)
+    }
+}
+
+impl From<ParserError> for CompilerError {
+    fn from(error: ParserError) -> Self {
+        CompilerError::Parser(error)
+    }
+}
+
+impl From<SemanticError> for CompilerError {
+    fn from(error: SemanticError) -> Self {
+        CompilerError::Semantic(error)
+    }
+}
+
+impl From<SemanticWarning> for CompilerError {
+    fn from(warning: SemanticWarning) -> Self {
+        CompilerError::Warning(warning)
+    }
+}
+
+impl From<IRConversionError> for CompilerError {
+    fn from(error: IRConversionError) -> Self {
+        CompilerError::IRConversion(error)
+    }
+}
+
+impl From<TackyVerificationError> for CompilerError {
+    fn from(error: TackyVerificationError) -> Self {
+        CompilerError::TackyVerification(error)
+    }
+}
+
+#[cfg(feature = "x86_64")]
+impl From<CodegenError> for CompilerError {
+    fn from(error: CodegenError) -> Self {
+        CompilerError::Codegen(error)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::tacky_ast::TackyValue;
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors that can occur while interpreting TACKY IR.
+#[derive(Debug, PartialEq)]
+pub enum InterpreterError {
+    /// Raised when a variable is read before any value has been stored in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the undefined variable.
+    UndefinedVariable { name: Symbol },
+    /// Raised when a jump targets a label that is not defined in the function.
+    ///
+    /// # Arguments
+    ///
+    /// * `label`: The name of the missing label.
+    UndefinedLabel { label: String },
+    /// Raised when an instruction takes the address of a constant that is not defined in the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the missing constant.
+    UndefinedConstant { name: Symbol },
+    /// Raised when a pointer that points to neither a variable nor a constant is dereferenced, or
+    /// a constant is written through a pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `address`: The address the pointer holds.
+    InvalidAddress { address: i64 },
+    /// Raised when a call targets a function that is not defined in the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the missing function.
+    UndefinedFunction { name: Symbol },
+    /// Raised when a function is called with a different number of arguments than it has parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the called function.
+    /// * `expected`: The number of parameters of the function.
+    /// * `actual`: The number of arguments of the call.
+    ArgumentCountMismatch {
+        name: Symbol,
+        expected: usize,
+        actual: usize,
+    },
+    /// Raised when calls are nested deeper than the interpreter follows, such as in runaway
+    /// recursion.
+    CallDepthExceeded,
+    /// Raised when an instruction tries to store its result into a constant.
+    InvalidDestination { destination: TackyValue },
+    /// Raised when an operator that only accepts integers, such as `%`, is applied to `double`s.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator`: The symbol of the operator.
+    InvalidDoubleOperator { operator: String },
+    /// Raised when the divisor of a division or remainder operation is zero.
+    DivisionByZero,
+    /// Raised when a division or remainder operation overflows, i.e. `INT_MIN / -1`.
+    DivisionOverflow,
+    /// Raised when execution runs past the last instruction without returning.
+    MissingReturn,
+}
+
+impl fmt::Display for InterpreterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterpreterError::UndefinedVariable { name } => {
+                write!(f, "Interpreter error: Undefined variable '{}'", name)
+            }
+            InterpreterError::UndefinedLabel { label } => {
+                write!(f, "Interpreter error: Undefined label '{}'", label)
+            }
+            InterpreterError::UndefinedConstant { name } => {
+                write!(f, "Interpreter error: Undefined constant '{}'", name)
+            }
+            InterpreterError::InvalidAddress { address } => {
+                write!(
+                    f,
+                    "Interpreter error: Invalid memory access at {:#x}",
+                    address
+                )
+            }
+            InterpreterError::UndefinedFunction { name } => {
+                write!(f, "Interpreter error: Undefined function '{}'", name)
+            }
+            InterpreterError::ArgumentCountMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Interpreter error: Function '{}' expects {} arguments, but was called with {}",
+                name, expected, actual
+            ),
+            InterpreterError::CallDepthExceeded => {
+                write!(f, "Interpreter error: Call depth exceeded")
+            }
+            InterpreterError::InvalidDestination { destination } => write!(
+                f,
+                "Interpreter error: Invalid destination {:?}, expected a variable",
+                destination
+            ),
+            InterpreterError::InvalidDoubleOperator { operator } => write!(
+                f,
+                "Interpreter error: The operator '{}' does not accept doubles",
+                operator
+            ),
+            InterpreterError::DivisionByZero => write!(f, "Interpreter error: Division by zero"),
+            InterpreterError::DivisionOverflow => {
+                write!(f, "Interpreter error: Division overflow")
+            }
+            InterpreterError::MissingReturn => {
+                write!(f, "Interpreter error: Function finished without returning")
+            }
+        }
+    }
+}
+
+impl Error for InterpreterError {}
@@ -0,0 +1,1505 @@
+pub mod errors;
+
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::static_accesses::lower_static_accesses;
+use crate::compiler::ir_gen::tacky_ast::{
+    TackyAst, TackyBinaryOperator, TackyConstant, TackyFunction, TackyInstruction,
+    TackyStaticConstant, TackyStaticInit, TackyStaticVariable, TackyType, TackyUnaryOperator,
+    TackyValue,
+};
+use errors::InterpreterError;
+use std::collections::{BTreeMap, HashMap};
+
+/// The address of the first static constant, which the static variables follow. Addresses are
+/// never zero, so a string literal is always true as a condition.
+const STATIC_CONSTANTS_ADDRESS: i64 = 0x1000;
+
+/// The address of the first variable whose address is taken, far above the static constants.
+const VARIABLES_ADDRESS: i64 = 0x1_0000_0000;
+
+/// Executes a TACKY program and returns the value returned by its `main` function.
+///
+/// The interpreter follows the semantics of the generated x86-64 code: arithmetic wraps around on
+/// overflow, while the cases where `idiv` or `div` would trap are reported as errors. Values are
+/// stored as 64-bit integers holding the value of their type, and every result is converted to the
+/// type of its destination, which wraps it around like the registers of the generated code do. A
+/// `double` is stored as the bits of its IEEE 754 representation.
+/// The static constants are laid out one after another from `STATIC_CONSTANTS_ADDRESS`, so that
+/// every constant has an address of its own, followed by the static variables, which start with
+/// their initial values and are the only static data that can be stored to. A variable gets an address from `VARIABLES_ADDRESS` on
+/// when its address is first taken, and loads and stores through the address access the variable in
+/// its frame.
+/// `main` is started like a program run without command-line arguments: its first parameter, the
+/// argument count, is 1, and its other parameters hold no value until they are assigned.
+///
+/// # Arguments
+///
+/// * `tacky_ast`: A reference to the TACKY program to execute.
+///
+/// # Returns
+///
+/// A `Result` containing the returned value on success, or an `InterpreterError` on failure.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::interpreter::interpret;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyAst, TackyConstant, TackyFunction, TackyInstruction, TackyValue};
+/// # use std::collections::BTreeMap;
+/// let tacky_ast = TackyAst::Program {
+///     functions: vec![TackyFunction::Function {
+///         identifier: Symbol::from("main"),
+///         global: true,
+///         parameters: vec![],
+///         instructions: vec![TackyInstruction::Return { value: Some(TackyValue::Constant(TackyConstant::Int(2))) }],
+///         variable_types: BTreeMap::new(),
+///     }],
+///     static_constants: Vec::new(),
+///     static_variables: Vec::new(),
+/// };
+/// assert_eq!(interpret(&tacky_ast), Ok(2));
+/// ```
+pub fn interpret(tacky_ast: &TackyAst) -> Result<i32, InterpreterError> {
+    // The returned value is an `int`, so only its lower 32 bits are kept.
+    interpret_function(tacky_ast, "main").map(|value| value as i32)
+}
+
+/// Executes a TACKY program from the function `entry` and returns the value the function returns.
+///
+/// Behaves like `interpret`, but starts from any function and returns the value in the
+/// representation of the interpreter: the value of an integer type or a pointer as a 64-bit
+/// integer, or the bits of a `double`. The caller knows the return type of `entry` and converts the
+/// value to it.
+///
+/// # Arguments
+///
+/// * `tacky_ast`: A reference to the TACKY program to execute.
+/// * `entry`: The name of the function to start from.
+///
+/// # Returns
+///
+/// A `Result` containing the returned value on success, or an `InterpreterError` on failure. A
+/// function returning no value returns 0.
+pub fn interpret_function(tacky_ast: &TackyAst, entry: &str) -> Result<i64, InterpreterError> {
+    // The static variables live in the static memory, which is only accessed through addresses.
+    match &lower_static_accesses(tacky_ast) {
+        TackyAst::Program {
+            functions,
+            static_constants,
+            static_variables,
+        } => {
+            let functions: HashMap<&str, LoadedFunction> = functions
+                .iter()
+                .map(LoadedFunction::new)
+                .map(|function| (function.identifier(), function))
+                .collect();
+            let mut static_memory = StaticMemory::new(static_constants, static_variables);
+            execute(&functions, &mut static_memory, entry)
+        }
+    }
+}
+
+/// The deepest nesting of calls the interpreter follows before it reports runaway recursion.
+const MAX_CALL_DEPTH: usize = 100_000;
+
+/// A TACKY function together with the positions of its labels.
+struct LoadedFunction<'a> {
+    function: &'a TackyFunction,
+    labels: HashMap<&'a str, usize>,
+}
+
+impl<'a> LoadedFunction<'a> {
+    fn new(function: &'a TackyFunction) -> Self {
+        let TackyFunction::Function { instructions, .. } = function;
+        let labels = instructions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, instruction)| match instruction {
+                TackyInstruction::Label(label) => Some((label.as_str(), index)),
+                _ => None,
+            })
+            .collect();
+        LoadedFunction { function, labels }
+    }
+
+    fn identifier(&self) -> &'a str {
+        let TackyFunction::Function { identifier, .. } = self.function;
+        identifier.as_str()
+    }
+
+    fn parameters(&self) -> &'a [Symbol] {
+        let TackyFunction::Function { parameters, .. } = self.function;
+        parameters
+    }
+
+    fn instructions(&self) -> &'a [TackyInstruction] {
+        let TackyFunction::Function { instructions, .. } = self.function;
+        instructions
+    }
+
+    fn variable_types(&self) -> &'a BTreeMap<Symbol, TackyType> {
+        let TackyFunction::Function { variable_types, .. } = self.function;
+        variable_types
+    }
+}
+
+/// A call of a function that has not returned yet.
+struct Frame<'a> {
+    function: &'a LoadedFunction<'a>,
+    variables: HashMap<Symbol, i64>,
+    /// The bytes of the arrays whose address has been taken.
+    arrays: HashMap<Symbol, Vec<u8>>,
+    instruction_pointer: usize,
+    /// The variable of the caller that receives the returned value, or `None` for `main`.
+    return_destination: Option<&'a TackyValue>,
+}
+
+impl<'a> Frame<'a> {
+    /// Starts `main`, passing an argument count of 1 to its first parameter if it has one.
+    fn main(function: &'a LoadedFunction<'a>) -> Result<Self, InterpreterError> {
+        let mut frame = Frame {
+            function,
+            variables: HashMap::new(),
+            arrays: HashMap::new(),
+            instruction_pointer: 0,
+            return_destination: None,
+        };
+        if let Some(argument_count) = function.parameters().first() {
+            write_value(
+                &TackyValue::Variable(argument_count.clone()),
+                1,
+                &mut frame.variables,
+                function.variable_types(),
+            )?;
+        }
+        Ok(frame)
+    }
+
+    /// Starts a call of a function, storing each argument into its parameter.
+    fn call(
+        function: &'a LoadedFunction<'a>,
+        arguments: &[i64],
+        return_destination: &'a TackyValue,
+    ) -> Result<Self, InterpreterError> {
+        let parameters = function.parameters();
+        if parameters.len() != arguments.len() {
+            return Err(InterpreterError::ArgumentCountMismatch {
+                name: Symbol::from(function.identifier()),
+                expected: parameters.len(),
+                actual: arguments.len(),
+            });
+        }
+        let mut variables = HashMap::new();
+        for (parameter, argument) in parameters.iter().zip(arguments) {
+            write_value(
+                &TackyValue::Variable(parameter.clone()),
+                *argument,
+                &mut variables,
+                function.variable_types(),
+            )?;
+        }
+        Ok(Frame {
+            function,
+            variables,
+            arrays: HashMap::new(),
+            instruction_pointer: 0,
+            return_destination: Some(return_destination),
+        })
+    }
+}
+
+/// The static constants and variables of a program, laid out one after another from
+/// `STATIC_CONSTANTS_ADDRESS`.
+struct StaticMemory {
+    /// The address of every constant and variable.
+    addresses: HashMap<Symbol, i64>,
+    /// The bytes of the constants, each followed by its null terminator, and then the bytes of
+    /// the variables, each aligned to its alignment.
+    bytes: Vec<u8>,
+    /// The offset of the first byte of the variables, before which the bytes are read-only.
+    variables_offset: usize,
+}
+
+impl StaticMemory {
+    fn new(
+        static_constants: &[TackyStaticConstant],
+        static_variables: &[TackyStaticVariable],
+    ) -> Self {
+        let mut addresses = HashMap::new();
+        let mut bytes = Vec::new();
+        for TackyStaticConstant::String { identifier, value } in static_constants {
+            addresses.insert(
+                identifier.clone(),
+                STATIC_CONSTANTS_ADDRESS + bytes.len() as i64,
+            );
+            bytes.extend_from_slice(value);
+            bytes.push(0);
+        }
+        let variables_offset = bytes.len();
+        for static_variable in static_variables {
+            // A variable defined in another file has no storage, so its address stays unknown.
+            let TackyStaticVariable::Variable {
+                identifier,
+                alignment,
+                initial_values,
+                ..
+            } = static_variable
+            else {
+                continue;
+            };
+            bytes.resize(bytes.len().next_multiple_of(*alignment), 0);
+            addresses.insert(
+                identifier.clone(),
+                STATIC_CONSTANTS_ADDRESS + bytes.len() as i64,
+            );
+            for initial_value in initial_values {
+                match initial_value {
+                    TackyStaticInit::Value(constant) => {
+                        let value = read_value(&TackyValue::Constant(*constant), &HashMap::new())
+                            .expect("A constant has a value");
+                        let size = constant.constant_type().size();
+                        bytes.extend_from_slice(&value.to_le_bytes()[..size]);
+                    }
+                    TackyStaticInit::Zero(size) => bytes.resize(bytes.len() + size, 0),
+                }
+            }
+        }
+        StaticMemory {
+            addresses,
+            bytes,
+            variables_offset,
+        }
+    }
+
+    /// Writes the lower bytes of a little-endian value at an address, or returns `None` if the
+    /// bytes are not all inside the variables.
+    fn write(&mut self, address: i64, value: &[u8]) -> Option<()> {
+        let start = usize::try_from(address.checked_sub(STATIC_CONSTANTS_ADDRESS)?).ok()?;
+        if start < self.variables_offset {
+            return None;
+        }
+        self.bytes
+            .get_mut(start..start.checked_add(value.len())?)?
+            .copy_from_slice(value);
+        Some(())
+    }
+
+    /// Reads the little-endian value of the given size at an address, or `None` if the bytes are
+    /// not all inside the constants.
+    fn read(&self, address: i64, size: usize) -> Option<i64> {
+        let start = usize::try_from(address.checked_sub(STATIC_CONSTANTS_ADDRESS)?).ok()?;
+        let bytes = self.bytes.get(start..start.checked_add(size)?)?;
+        let mut value = [0; 8];
+        value[..size].copy_from_slice(bytes);
+        Some(i64::from_le_bytes(value))
+    }
+}
+
+/// The addresses of the variables whose address is taken, laid out one after another from
+/// `VARIABLES_ADDRESS`. Every variable takes a multiple of 8 bytes, so that each one starts at an
+/// address of its own.
+#[derive(Default)]
+struct VariableAddresses {
+    /// The address of every variable, by the depth of its frame and its name.
+    addresses: HashMap<(usize, Symbol), i64>,
+    /// The address, the size, the depth of the frame and the name of every variable, in the order
+    /// of their addresses.
+    variables: Vec<(i64, usize, usize, Symbol)>,
+}
+
+impl VariableAddresses {
+    /// Returns the address of a variable of the given size, assigning the next free address to a
+    /// new variable. A later call of a function at the same depth reuses the addresses of the
+    /// earlier call.
+    fn address_of(&mut self, depth: usize, name: &Symbol, size: usize) -> i64 {
+        let next_address = match self.variables.last() {
+            Some((address, size, _, _)) => address + size.next_multiple_of(8) as i64,
+            None => VARIABLES_ADDRESS,
+        };
+        *self
+            .addresses
+            .entry((depth, name.clone()))
+            .or_insert_with(|| {
+                self.variables
+                    .push((next_address, size, depth, name.clone()));
+                next_address
+            })
+    }
+
+    /// Returns the depth of the frame and the name of the variable holding an address, together
+    /// with the offset of the address from the start of the variable, if any.
+    fn variable_at(&self, address: i64) -> Option<(usize, &Symbol, usize)> {
+        let index = self
+            .variables
+            .partition_point(|(start, _, _, _)| *start <= address)
+            .checked_sub(1)?;
+        let (start, size, depth, name) = &self.variables[index];
+        let offset = (address - start) as usize;
+        (offset < *size).then_some((*depth, name, offset))
+    }
+}
+
+/// Reads the little-endian value of the given size at an offset of the bytes of an array, or
+/// `None` if the bytes are not all inside the array.
+fn read_bytes(bytes: &[u8], offset: usize, size: usize) -> Option<i64> {
+    let bytes = bytes.get(offset..offset.checked_add(size)?)?;
+    let mut value = [0; 8];
+    value[..size].copy_from_slice(bytes);
+    Some(i64::from_le_bytes(value))
+}
+
+/// Executes the program from the entry function until it returns.
+///
+/// The calls that have not returned yet are kept on a stack of frames instead of the native stack,
+/// so that deep recursion in the interpreted program is reported as an error instead of crashing.
+///
+/// # Arguments
+///
+/// * `functions`: The functions of the program by name.
+/// * `static_memory`: The static constants and variables of the program.
+/// * `entry`: The name of the function to start from, `main` for a program.
+///
+/// # Returns
+///
+/// A `Result` containing the value returned by the entry function on success, or an
+/// `InterpreterError` on failure.
+fn execute(
+    functions: &HashMap<&str, LoadedFunction>,
+    static_memory: &mut StaticMemory,
+    entry: &str,
+) -> Result<i64, InterpreterError> {
+    let main = functions
+        .get(entry)
+        .ok_or_else(|| InterpreterError::UndefinedFunction {
+            name: Symbol::from(entry),
+        })?;
+    let mut frames = vec![Frame::main(main)?];
+    let mut variable_addresses = VariableAddresses::default();
+    loop {
+        let depth = frames.len() - 1;
+        let Some(Frame {
+            function,
+            variables,
+            arrays,
+            instruction_pointer,
+            return_destination,
+        }) = frames.last_mut()
+        else {
+            break;
+        };
+        let (labels, variable_types) = (&function.labels, function.variable_types());
+        let Some(instruction) = function.instructions().get(*instruction_pointer) else {
+            return Err(InterpreterError::MissingReturn);
+        };
+        *instruction_pointer += 1;
+        match instruction {
+            TackyInstruction::Return { value } => {
+                // A `void` function returns no value, which leaves the destination of its call
+                // unwritten.
+                let result = value
+                    .as_ref()
+                    .map(|value| read_value(value, variables))
+                    .transpose()?;
+                let return_destination = *return_destination;
+                frames.pop();
+                let Some(caller) = frames.last_mut() else {
+                    return Ok(result.unwrap_or_default());
+                };
+                if let (Some(destination), Some(result)) = (return_destination, result) {
+                    write_value(
+                        destination,
+                        result,
+                        &mut caller.variables,
+                        caller.function.variable_types(),
+                    )?;
+                }
+            }
+            TackyInstruction::FunCall {
+                identifier,
+                arguments,
+                named_arguments: _,
+                destination,
+            } => {
+                let callee = functions.get(identifier.as_str()).ok_or_else(|| {
+                    InterpreterError::UndefinedFunction {
+                        name: identifier.clone(),
+                    }
+                })?;
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| read_value(argument, variables))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if frames.len() == MAX_CALL_DEPTH {
+                    return Err(InterpreterError::CallDepthExceeded);
+                }
+                frames.push(Frame::call(callee, &arguments, destination)?);
+            }
+            TackyInstruction::Unary {
+                operator,
+                source,
+                destination,
+            } => {
+                let result = evaluate_unary(
+                    operator,
+                    read_value(source, variables)?,
+                    source.value_type(variable_types),
+                );
+                write_value(destination, result, variables, variable_types)?;
+            }
+            TackyInstruction::Binary {
+                operator,
+                source1,
+                source2,
+                destination,
+            } => {
+                let result = evaluate_binary(
+                    operator,
+                    read_value(source1, variables)?,
+                    read_value(source2, variables)?,
+                    source1.value_type(variable_types),
+                )?;
+                write_value(destination, result, variables, variable_types)?;
+            }
+            TackyInstruction::Copy {
+                source,
+                destination,
+            }
+            | TackyInstruction::Truncate {
+                source,
+                destination,
+            } => {
+                let result = read_value(source, variables)?;
+                write_value(destination, result, variables, variable_types)?;
+            }
+            // The bits above the source type are replaced with copies of its sign bit or with zeros.
+            TackyInstruction::SignExtend {
+                source,
+                destination,
+            } => {
+                let unused_bits = 64 - source.value_type(variable_types).size() as u32 * 8;
+                let result = read_value(source, variables)? << unused_bits >> unused_bits;
+                write_value(destination, result, variables, variable_types)?;
+            }
+            TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            } => {
+                let unused_bits = 64 - source.value_type(variable_types).size() as u32 * 8;
+                let result = (read_value(source, variables)? as u64) << unused_bits >> unused_bits;
+                write_value(destination, result as i64, variables, variable_types)?;
+            }
+            // The source of an unsigned conversion holds a non-negative value, so both integer
+            // conversions to `double` convert the value the same way, except for the bits of an
+            // `unsigned long` beyond the range of a `long`.
+            TackyInstruction::IntToDouble {
+                source,
+                destination,
+            }
+            | TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            } => {
+                let value = read_value(source, variables)?;
+                let result = match source.value_type(variable_types) {
+                    TackyType::UnsignedLong => value as u64 as f64,
+                    _ => value as f64,
+                };
+                write_value(
+                    destination,
+                    result.to_bits() as i64,
+                    variables,
+                    variable_types,
+                )?;
+            }
+            TackyInstruction::DoubleToInt {
+                source,
+                destination,
+            }
+            | TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            } => {
+                let value = f64::from_bits(read_value(source, variables)? as u64);
+                let result = match destination.value_type(variable_types) {
+                    TackyType::UnsignedLong => value as u64 as i64,
+                    _ => value as i64,
+                };
+                write_value(destination, result, variables, variable_types)?;
+            }
+            TackyInstruction::Jump { target } => {
+                *instruction_pointer = jump_to(labels, target.as_str())?
+            }
+            TackyInstruction::JumpIfZero { condition, target } => {
+                let value = read_value(condition, variables)?;
+                if is_zero(value, condition.value_type(variable_types)) {
+                    *instruction_pointer = jump_to(labels, target.as_str())?;
+                }
+            }
+            TackyInstruction::JumpIfNotZero { condition, target } => {
+                let value = read_value(condition, variables)?;
+                if !is_zero(value, condition.value_type(variable_types)) {
+                    *instruction_pointer = jump_to(labels, target.as_str())?;
+                }
+            }
+            TackyInstruction::Label(_) => {}
+            TackyInstruction::GetAddress {
+                source,
+                destination,
+            } => {
+                let address = match static_memory.addresses.get(source) {
+                    Some(address) => *address,
+                    None => match variable_types.get(source) {
+                        Some(TackyType::Aggregate { size, .. }) => {
+                            arrays
+                                .entry(source.clone())
+                                .or_insert_with(|| vec![0; *size]);
+                            variable_addresses.address_of(depth, source, *size)
+                        }
+                        Some(_) => variable_addresses.address_of(depth, source, 8),
+                        None => {
+                            return Err(InterpreterError::UndefinedConstant {
+                                name: source.clone(),
+                            });
+                        }
+                    },
+                };
+                write_value(destination, address, variables, variable_types)?;
+            }
+            TackyInstruction::Load {
+                source_pointer,
+                destination,
+            } => {
+                let address = read_value(source_pointer, variables)?;
+                let destination_type = destination.value_type(variable_types);
+                let value = match variable_addresses.variable_at(address) {
+                    Some((depth, name, offset)) => {
+                        let frame = frames
+                            .get(depth)
+                            .ok_or(InterpreterError::InvalidAddress { address })?;
+                        match frame.arrays.get(name) {
+                            Some(bytes) => read_bytes(bytes, offset, destination_type.size())
+                                .ok_or(InterpreterError::InvalidAddress { address })?,
+                            None if offset == 0 => {
+                                read_value(&TackyValue::Variable(name.clone()), &frame.variables)?
+                            }
+                            None => return Err(InterpreterError::InvalidAddress { address }),
+                        }
+                    }
+                    None => static_memory
+                        .read(address, destination_type.size())
+                        .ok_or(InterpreterError::InvalidAddress { address })?,
+                };
+                let frame = frames.last_mut().expect("The loading frame is running");
+                write_value(destination, value, &mut frame.variables, variable_types)?;
+            }
+            // The static constants are read-only, so only variables can be stored to.
+            TackyInstruction::Store {
+                source,
+                destination_pointer,
+            } => {
+                let address = read_value(destination_pointer, variables)?;
+                let value = read_value(source, variables)?;
+                let size = source.value_type(variable_types).size();
+                let Some((depth, name, offset)) = variable_addresses.variable_at(address) else {
+                    static_memory
+                        .write(address, &value.to_le_bytes()[..size])
+                        .ok_or(InterpreterError::InvalidAddress { address })?;
+                    continue;
+                };
+                let frame = frames
+                    .get_mut(depth)
+                    .ok_or(InterpreterError::InvalidAddress { address })?;
+                match frame.arrays.get_mut(name) {
+                    Some(bytes) => bytes
+                        .get_mut(offset..offset + size)
+                        .ok_or(InterpreterError::InvalidAddress { address })?
+                        .copy_from_slice(&value.to_le_bytes()[..size]),
+                    None if offset == 0 => write_value(
+                        &TackyValue::Variable(name.clone()),
+                        value,
+                        &mut frame.variables,
+                        frame.function.variable_types(),
+                    )?,
+                    None => return Err(InterpreterError::InvalidAddress { address }),
+                }
+            }
+            TackyInstruction::AddPtr {
+                pointer,
+                index,
+                scale,
+                destination,
+            } => {
+                let address = read_value(pointer, variables)?
+                    .wrapping_add(read_value(index, variables)?.wrapping_mul(*scale));
+                write_value(destination, address, variables, variable_types)?;
+            }
+        }
+    }
+    Err(InterpreterError::MissingReturn)
+}
+
+/// Returns the position of a label in its function.
+fn jump_to(labels: &HashMap<&str, usize>, label: &str) -> Result<usize, InterpreterError> {
+    labels
+        .get(label)
+        .copied()
+        .ok_or_else(|| InterpreterError::UndefinedLabel {
+            label: label.to_string(),
+        })
+}
+
+/// Reads the current value of a TACKY value.
+fn read_value(
+    value: &TackyValue,
+    variables: &HashMap<Symbol, i64>,
+) -> Result<i64, InterpreterError> {
+    match value {
+        TackyValue::Constant(TackyConstant::Double(value)) => Ok(value.to_bits() as i64),
+        TackyValue::Constant(constant) => Ok(constant.as_i64()),
+        TackyValue::Variable(name) => variables
+            .get(name)
+            .copied()
+            .ok_or_else(|| InterpreterError::UndefinedVariable { name: name.clone() }),
+    }
+}
+
+/// Stores a result into a TACKY variable, converted to the type of the variable. Addresses and the
+/// bits of `double`s are stored as is.
+fn write_value(
+    destination: &TackyValue,
+    result: i64,
+    variables: &mut HashMap<Symbol, i64>,
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> Result<(), InterpreterError> {
+    match destination {
+        TackyValue::Variable(name) => {
+            let result = match destination.value_type(variable_types) {
+                TackyType::Pointer | TackyType::Double => result,
+                destination_type => TackyConstant::Long(result)
+                    .convert_to(destination_type)
+                    .as_i64(),
+            };
+            variables.insert(name.clone(), result);
+            Ok(())
+        }
+        TackyValue::Constant(_) => Err(InterpreterError::InvalidDestination {
+            destination: destination.clone(),
+        }),
+    }
+}
+
+/// Returns `true` if a value of the given type is zero. Both zeros of a `double` are zero.
+fn is_zero(value: i64, value_type: TackyType) -> bool {
+    match value_type {
+        TackyType::Double => f64::from_bits(value as u64) == 0.0,
+        _ => value == 0,
+    }
+}
+
+/// Applies a TACKY unary operator to a value of the given type. The result wraps around once it is
+/// converted to the type of its destination.
+fn evaluate_unary(operator: &TackyUnaryOperator, value: i64, operand_type: TackyType) -> i64 {
+    match (operator, operand_type) {
+        // Negating a `double` flips its sign bit.
+        (TackyUnaryOperator::Negate, TackyType::Double) => value ^ i64::MIN,
+        (TackyUnaryOperator::Complement, _) => !value,
+        (TackyUnaryOperator::Negate, _) => value.wrapping_neg(),
+        (TackyUnaryOperator::Not, _) => is_zero(value, operand_type) as i64,
+    }
+}
+
+/// Applies a TACKY binary operator to two values of the given operand type.
+///
+/// The values hold the value of their type, so an unsigned value is never negative, and the exact
+/// result of every operation is computed before it is converted to the type of its destination.
+/// Only an `unsigned long` beyond the range of a `long` is held by its bits instead.
+fn evaluate_binary(
+    operator: &TackyBinaryOperator,
+    left: i64,
+    right: i64,
+    operand_type: TackyType,
+) -> Result<i64, InterpreterError> {
+    if operand_type == TackyType::Double {
+        return evaluate_double_binary(
+            operator,
+            f64::from_bits(left as u64),
+            f64::from_bits(right as u64),
+        );
+    }
+    if operand_type == TackyType::UnsignedLong {
+        return evaluate_unsigned_long_binary(operator, left as u64, right as u64);
+    }
+    let bits = operand_type.size() as u32 * 8;
+    let result = match operator {
+        TackyBinaryOperator::Add => left.wrapping_add(right),
+        TackyBinaryOperator::Subtract => left.wrapping_sub(right),
+        TackyBinaryOperator::Multiply => left.wrapping_mul(right),
+        TackyBinaryOperator::BitwiseAnd => left & right,
+        TackyBinaryOperator::BitwiseOr => left | right,
+        TackyBinaryOperator::BitwiseXor => left ^ right,
+        // Shift counts are masked to the bit width of the operand, like x86-64 does.
+        TackyBinaryOperator::LeftShift => left.wrapping_shl(right as u32 & (bits - 1)),
+        TackyBinaryOperator::RightShift => left.wrapping_shr(right as u32 & (bits - 1)),
+        TackyBinaryOperator::Divide | TackyBinaryOperator::Remainder => {
+            if right == 0 {
+                return Err(InterpreterError::DivisionByZero);
+            }
+            // Only the smallest value of a signed type divided by -1 has a quotient out of range.
+            let smallest_value = i64::MIN >> (64 - bits);
+            if operand_type.is_signed() && left == smallest_value && right == -1 {
+                return Err(InterpreterError::DivisionOverflow);
+            }
+            match operator {
+                TackyBinaryOperator::Divide => left / right,
+                _ => left % right,
+            }
+        }
+        TackyBinaryOperator::Equal => (left == right) as i64,
+        TackyBinaryOperator::NotEqual => (left != right) as i64,
+        TackyBinaryOperator::LessThan => (left < right) as i64,
+        TackyBinaryOperator::GreaterThan => (left > right) as i64,
+        TackyBinaryOperator::LessThanEqual => (left <= right) as i64,
+        TackyBinaryOperator::GreaterThanEqual => (left >= right) as i64,
+    };
+    Ok(result)
+}
+
+/// Applies a TACKY binary operator to two `unsigned long`s.
+///
+/// The operations whose result depends on the sign bit are computed on the unsigned values, and
+/// the others like on `long`s, whose bits they share.
+fn evaluate_unsigned_long_binary(
+    operator: &TackyBinaryOperator,
+    left: u64,
+    right: u64,
+) -> Result<i64, InterpreterError> {
+    let result = match operator {
+        TackyBinaryOperator::RightShift => left.wrapping_shr(right as u32 & 63),
+        TackyBinaryOperator::Divide => left
+            .checked_div(right)
+            .ok_or(InterpreterError::DivisionByZero)?,
+        TackyBinaryOperator::Remainder => left
+            .checked_rem(right)
+            .ok_or(InterpreterError::DivisionByZero)?,
+        TackyBinaryOperator::LessThan => return Ok((left < right) as i64),
+        TackyBinaryOperator::GreaterThan => return Ok((left > right) as i64),
+        TackyBinaryOperator::LessThanEqual => return Ok((left <= right) as i64),
+        TackyBinaryOperator::GreaterThanEqual => return Ok((left >= right) as i64),
+        _ => return evaluate_binary(operator, left as i64, right as i64, TackyType::Long),
+    };
+    Ok(result as i64)
+}
+
+/// Applies a TACKY binary operator to two `double`s.
+///
+/// Division by zero yields an infinity or NaN instead of trapping. Arithmetic results are returned
+/// as the bits of the `double`, comparisons as an `int`.
+fn evaluate_double_binary(
+    operator: &TackyBinaryOperator,
+    left: f64,
+    right: f64,
+) -> Result<i64, InterpreterError> {
+    let result = match operator {
+        TackyBinaryOperator::Add => left + right,
+        TackyBinaryOperator::Subtract => left - right,
+        TackyBinaryOperator::Multiply => left * right,
+        TackyBinaryOperator::Divide => left / right,
+        TackyBinaryOperator::Equal => return Ok((left == right) as i64),
+        TackyBinaryOperator::NotEqual => return Ok((left != right) as i64),
+        TackyBinaryOperator::LessThan => return Ok((left < right) as i64),
+        TackyBinaryOperator::GreaterThan => return Ok((left > right) as i64),
+        TackyBinaryOperator::LessThanEqual => return Ok((left <= right) as i64),
+        TackyBinaryOperator::GreaterThanEqual => return Ok((left >= right) as i64),
+        _ => {
+            return Err(InterpreterError::InvalidDoubleOperator {
+                operator: operator.to_string(),
+            });
+        }
+    };
+    Ok(result.to_bits() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(instructions: Vec<TackyInstruction>) -> TackyAst {
+        TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions,
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_interpret_binary_and_jumps() {
+        let tmp = TackyValue::Variable(Symbol::from("tmp.0"));
+        let tacky_ast = program(vec![
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Multiply,
+                source1: TackyValue::Constant(TackyConstant::Int(3)),
+                source2: TackyValue::Constant(TackyConstant::Int(4)),
+                destination: tmp.clone(),
+            },
+            TackyInstruction::JumpIfNotZero {
+                condition: tmp.clone(),
+                target: Symbol::from("end"),
+            },
+            TackyInstruction::Return {
+                value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+            },
+            TackyInstruction::Label(Symbol::from("end")),
+            TackyInstruction::Return { value: Some(tmp) },
+        ]);
+        assert_eq!(interpret(&tacky_ast), Ok(12));
+    }
+
+    #[test]
+    fn test_interpret_wrapping_and_division_errors() {
+        assert_eq!(
+            evaluate_unary(&TackyUnaryOperator::Negate, i64::MIN, TackyType::Long),
+            i64::MIN
+        );
+        assert_eq!(
+            evaluate_binary(&TackyBinaryOperator::Remainder, -7, 2, TackyType::Int),
+            Ok(-1)
+        );
+        assert_eq!(
+            evaluate_binary(&TackyBinaryOperator::Divide, 1, 0, TackyType::Int),
+            Err(InterpreterError::DivisionByZero)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Divide,
+                i32::MIN.into(),
+                -1,
+                TackyType::Int
+            ),
+            Err(InterpreterError::DivisionOverflow)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Remainder,
+                i64::MIN,
+                -1,
+                TackyType::Long
+            ),
+            Err(InterpreterError::DivisionOverflow)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Divide,
+                i32::MIN.into(),
+                -1,
+                TackyType::Long
+            ),
+            Ok(2147483648)
+        );
+    }
+
+    #[test]
+    fn test_interpret_unsigned_operations() {
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Divide,
+                4294967294,
+                2,
+                TackyType::UnsignedInt
+            ),
+            Ok(i32::MAX.into())
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Divide,
+                2147483648,
+                4294967295,
+                TackyType::UnsignedInt
+            ),
+            Ok(0)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Remainder,
+                1,
+                0,
+                TackyType::UnsignedInt
+            ),
+            Err(InterpreterError::DivisionByZero)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::LessThan,
+                1,
+                4294967295,
+                TackyType::UnsignedInt
+            ),
+            Ok(1)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::RightShift,
+                4294967295,
+                28,
+                TackyType::UnsignedInt
+            ),
+            Ok(15)
+        );
+
+        // The type of the compared variable decides whether all bits set is the largest value.
+        let tmp = TackyValue::Variable(Symbol::from("tmp.0"));
+        let compare_all_bits_set = |tmp_type| TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::UnsignedInt(u32::MAX)),
+                        destination: tmp.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::GreaterThan,
+                        source1: tmp.clone(),
+                        source2: TackyValue::Constant(TackyConstant::Int(1)),
+                        destination: tmp.clone(),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(tmp.clone()),
+                    },
+                ],
+                variable_types: BTreeMap::from([(Symbol::from("tmp.0"), tmp_type)]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            interpret(&compare_all_bits_set(TackyType::UnsignedInt)),
+            Ok(1)
+        );
+        assert_eq!(interpret(&compare_all_bits_set(TackyType::Int)), Ok(0));
+    }
+
+    #[test]
+    fn test_interpret_long_conversions() {
+        // (long)(int)-1 + 4294967296L keeps the sign, while truncating 4294967297L leaves 1.
+        let int = TackyValue::Variable(Symbol::from("tmp.0"));
+        let long = TackyValue::Variable(Symbol::from("tmp.1"));
+        let unsigned = TackyValue::Variable(Symbol::from("tmp.2"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::SignExtend {
+                        source: TackyValue::Constant(TackyConstant::Int(-1)),
+                        destination: long.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: long.clone(),
+                        source2: TackyValue::Constant(TackyConstant::Long(4294967297)),
+                        destination: long.clone(),
+                    },
+                    TackyInstruction::Truncate {
+                        source: long.clone(),
+                        destination: int.clone(),
+                    },
+                    TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(-1)),
+                        destination: unsigned.clone(),
+                    },
+                    TackyInstruction::ZeroExtend {
+                        source: unsigned,
+                        destination: long.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::GreaterThan,
+                        source1: long,
+                        source2: TackyValue::Constant(TackyConstant::Long(0)),
+                        destination: int.clone(),
+                    },
+                    TackyInstruction::Return { value: Some(int) },
+                ],
+                variable_types: BTreeMap::from([
+                    (Symbol::from("tmp.0"), TackyType::Int),
+                    (Symbol::from("tmp.1"), TackyType::Long),
+                    (Symbol::from("tmp.2"), TackyType::UnsignedInt),
+                ]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(interpret(&tacky_ast), Ok(1));
+    }
+
+    #[test]
+    fn test_interpret_char_conversions() {
+        // (char)456 is -56, which is 200 as an unsigned char, so their sum as ints is 144.
+        let char_value = TackyValue::Variable(Symbol::from("tmp.0"));
+        let unsigned_char = TackyValue::Variable(Symbol::from("tmp.1"));
+        let int = TackyValue::Variable(Symbol::from("tmp.2"));
+        let other_int = TackyValue::Variable(Symbol::from("tmp.3"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Truncate {
+                        source: TackyValue::Constant(TackyConstant::Int(456)),
+                        destination: char_value.clone(),
+                    },
+                    TackyInstruction::Copy {
+                        source: char_value.clone(),
+                        destination: unsigned_char.clone(),
+                    },
+                    TackyInstruction::SignExtend {
+                        source: char_value,
+                        destination: int.clone(),
+                    },
+                    TackyInstruction::ZeroExtend {
+                        source: unsigned_char,
+                        destination: other_int.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: int.clone(),
+                        source2: other_int,
+                        destination: int.clone(),
+                    },
+                    TackyInstruction::Return { value: Some(int) },
+                ],
+                variable_types: BTreeMap::from([
+                    (Symbol::from("tmp.0"), TackyType::Char),
+                    (Symbol::from("tmp.1"), TackyType::UnsignedChar),
+                ]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(interpret(&tacky_ast), Ok(144));
+    }
+
+    #[test]
+    fn test_interpret_errors() {
+        let undefined = program(vec![TackyInstruction::Return {
+            value: Some(TackyValue::Variable(Symbol::from("tmp.0"))),
+        }]);
+        assert_eq!(
+            interpret(&undefined),
+            Err(InterpreterError::UndefinedVariable {
+                name: Symbol::from("tmp.0")
+            })
+        );
+        let missing_label = program(vec![TackyInstruction::Jump {
+            target: Symbol::from("nowhere"),
+        }]);
+        assert_eq!(
+            interpret(&missing_label),
+            Err(InterpreterError::UndefinedLabel {
+                label: String::from("nowhere")
+            })
+        );
+        assert_eq!(
+            interpret(&program(vec![])),
+            Err(InterpreterError::MissingReturn)
+        );
+        let missing_constant = program(vec![TackyInstruction::GetAddress {
+            source: Symbol::from("string.0"),
+            destination: TackyValue::Variable(Symbol::from("tmp.1")),
+        }]);
+        assert_eq!(
+            interpret(&missing_constant),
+            Err(InterpreterError::UndefinedConstant {
+                name: Symbol::from("string.0")
+            })
+        );
+    }
+
+    #[test]
+    fn test_interpret_function_calls() {
+        let argc = TackyValue::Variable(Symbol::from("argc.0"));
+        let tmp = TackyValue::Variable(Symbol::from("tmp.1"));
+        let recursive_main = |argument_count: i32| TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![Symbol::from("argc.0")],
+                instructions: vec![
+                    TackyInstruction::JumpIfZero {
+                        condition: argc.clone(),
+                        target: Symbol::from("end.0"),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Subtract,
+                        source1: argc.clone(),
+                        source2: TackyValue::Constant(TackyConstant::Int(1)),
+                        destination: tmp.clone(),
+                    },
+                    TackyInstruction::FunCall {
+                        identifier: Symbol::from("main"),
+                        arguments: vec![tmp.clone()],
+                        named_arguments: None,
+                        destination: tmp.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: tmp.clone(),
+                        source2: TackyValue::Constant(TackyConstant::Int(argument_count)),
+                        destination: tmp.clone(),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(tmp.clone()),
+                    },
+                    TackyInstruction::Label(Symbol::from("end.0")),
+                    TackyInstruction::Return {
+                        value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+                    },
+                ],
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        // `main` starts with an argument count of 1, and calls itself once with 0.
+        assert_eq!(interpret(&recursive_main(5)), Ok(5));
+        let call_without_arguments = program(vec![
+            TackyInstruction::FunCall {
+                identifier: Symbol::from("f"),
+                arguments: vec![],
+                named_arguments: None,
+                destination: tmp.clone(),
+            },
+            TackyInstruction::Return { value: Some(tmp) },
+        ]);
+        assert_eq!(
+            interpret(&call_without_arguments),
+            Err(InterpreterError::UndefinedFunction {
+                name: Symbol::from("f")
+            })
+        );
+    }
+
+    #[test]
+    fn test_interpret_void_function_calls() {
+        let tmp = TackyValue::Variable(Symbol::from("tmp.0"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![
+                TackyFunction::Function {
+                    identifier: Symbol::from("reset"),
+                    global: true,
+                    parameters: vec![],
+                    instructions: vec![TackyInstruction::Return { value: None }],
+                    variable_types: BTreeMap::new(),
+                },
+                TackyFunction::Function {
+                    identifier: Symbol::from("main"),
+                    global: true,
+                    parameters: vec![],
+                    instructions: vec![
+                        TackyInstruction::Copy {
+                            source: TackyValue::Constant(TackyConstant::Int(7)),
+                            destination: tmp.clone(),
+                        },
+                        TackyInstruction::FunCall {
+                            identifier: Symbol::from("reset"),
+                            arguments: vec![],
+                            named_arguments: None,
+                            destination: tmp.clone(),
+                        },
+                        TackyInstruction::Return { value: Some(tmp) },
+                    ],
+                    variable_types: BTreeMap::new(),
+                },
+            ],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        // The call to a `void` function leaves its destination untouched.
+        assert_eq!(interpret(&tacky_ast), Ok(7));
+    }
+
+    #[test]
+    fn test_interpret_call_depth_is_limited() {
+        let tmp = TackyValue::Variable(Symbol::from("tmp.0"));
+        let runaway_recursion = program(vec![
+            TackyInstruction::FunCall {
+                identifier: Symbol::from("main"),
+                arguments: vec![],
+                named_arguments: None,
+                destination: tmp.clone(),
+            },
+            TackyInstruction::Return { value: Some(tmp) },
+        ]);
+        assert_eq!(
+            interpret(&runaway_recursion),
+            Err(InterpreterError::CallDepthExceeded)
+        );
+    }
+
+    #[test]
+    fn test_interpret_string_addresses() {
+        let static_constants = vec![
+            TackyStaticConstant::String {
+                identifier: Symbol::from("string.0"),
+                value: b"ab".to_vec(),
+            },
+            TackyStaticConstant::String {
+                identifier: Symbol::from("string.1"),
+                value: Vec::new(),
+            },
+        ];
+        assert_eq!(
+            StaticMemory::new(&static_constants, &[]).addresses,
+            HashMap::from([
+                (Symbol::from("string.0"), 0x1000),
+                (Symbol::from("string.1"), 0x1003)
+            ])
+        );
+        let address = TackyValue::Variable(Symbol::from("tmp.0"));
+        let not_address = TackyValue::Variable(Symbol::from("tmp.1"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::GetAddress {
+                        source: Symbol::from("string.1"),
+                        destination: address.clone(),
+                    },
+                    TackyInstruction::Unary {
+                        operator: TackyUnaryOperator::Not,
+                        source: address,
+                        destination: not_address.clone(),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(not_address),
+                    },
+                ],
+                variable_types: BTreeMap::from([(Symbol::from("tmp.0"), TackyType::Pointer)]),
+            }],
+            static_constants,
+            static_variables: Vec::new(),
+        };
+        // Even the empty string has a non-zero address.
+        assert_eq!(interpret(&tacky_ast), Ok(0));
+    }
+
+    #[test]
+    fn test_interpret_loads_and_stores() {
+        let pointer = TackyValue::Variable(Symbol::from("p.1"));
+        let loaded = TackyValue::Variable(Symbol::from("tmp.2"));
+        let character = TackyValue::Variable(Symbol::from("tmp.4"));
+        let sum = TackyValue::Variable(Symbol::from("tmp.5"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::GetAddress {
+                        source: Symbol::from("x.0"),
+                        destination: pointer.clone(),
+                    },
+                    TackyInstruction::Store {
+                        source: TackyValue::Constant(TackyConstant::Int(7)),
+                        destination_pointer: pointer.clone(),
+                    },
+                    TackyInstruction::Load {
+                        source_pointer: pointer.clone(),
+                        destination: loaded.clone(),
+                    },
+                    TackyInstruction::GetAddress {
+                        source: Symbol::from("string.0"),
+                        destination: pointer.clone(),
+                    },
+                    TackyInstruction::Load {
+                        source_pointer: pointer,
+                        destination: character.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: TackyValue::Variable(Symbol::from("x.0")),
+                        source2: loaded,
+                        destination: sum.clone(),
+                    },
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: sum.clone(),
+                        source2: character,
+                        destination: sum.clone(),
+                    },
+                    TackyInstruction::Return { value: Some(sum) },
+                ],
+                variable_types: BTreeMap::from([
+                    (Symbol::from("x.0"), TackyType::Int),
+                    (Symbol::from("p.1"), TackyType::Pointer),
+                    (Symbol::from("tmp.2"), TackyType::Int),
+                    (Symbol::from("tmp.4"), TackyType::Char),
+                    (Symbol::from("tmp.5"), TackyType::Int),
+                ]),
+            }],
+            static_constants: vec![TackyStaticConstant::String {
+                identifier: Symbol::from("string.0"),
+                value: b"a".to_vec(),
+            }],
+            static_variables: Vec::new(),
+        };
+        // The store through the pointer changes the variable itself.
+        assert_eq!(interpret(&tacky_ast), Ok(7 + 7 + 97));
+
+        let null_load = program(vec![TackyInstruction::Load {
+            source_pointer: TackyValue::Constant(TackyConstant::Long(0)),
+            destination: TackyValue::Variable(Symbol::from("tmp.0")),
+        }]);
+        assert_eq!(
+            interpret(&null_load),
+            Err(InterpreterError::InvalidAddress { address: 0 })
+        );
+    }
+
+    #[test]
+    fn test_interpret_arrays() {
+        let array = Symbol::from("a.0");
+        let first = TackyValue::Variable(Symbol::from("tmp.1"));
+        let element = TackyValue::Variable(Symbol::from("tmp.2"));
+        let loaded = TackyValue::Variable(Symbol::from("tmp.3"));
+        let variable_types = BTreeMap::from([
+            (
+                array.clone(),
+                TackyType::Aggregate {
+                    size: 12,
+                    alignment: 4,
+                },
+            ),
+            (Symbol::from("tmp.1"), TackyType::Pointer),
+            (Symbol::from("tmp.2"), TackyType::Pointer),
+            (Symbol::from("tmp.3"), TackyType::Char),
+        ]);
+        let add_ptr = |index| TackyInstruction::AddPtr {
+            pointer: first.clone(),
+            index: TackyValue::Constant(TackyConstant::Long(index)),
+            scale: 4,
+            destination: element.clone(),
+        };
+        let program_with = |instructions| TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions,
+                variable_types: variable_types.clone(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        // The elements are stored as bytes, so a `char` reads the lowest byte of an `int`.
+        let tacky_ast = program_with(vec![
+            TackyInstruction::GetAddress {
+                source: array.clone(),
+                destination: first.clone(),
+            },
+            add_ptr(2),
+            TackyInstruction::Store {
+                source: TackyValue::Constant(TackyConstant::Int(0x1234)),
+                destination_pointer: element.clone(),
+            },
+            TackyInstruction::Load {
+                source_pointer: element.clone(),
+                destination: loaded.clone(),
+            },
+            TackyInstruction::Return {
+                value: Some(loaded),
+            },
+        ]);
+        assert_eq!(interpret(&tacky_ast), Ok(0x34));
+
+        let out_of_bounds = program_with(vec![
+            TackyInstruction::GetAddress {
+                source: array,
+                destination: first.clone(),
+            },
+            add_ptr(3),
+            TackyInstruction::Store {
+                source: TackyValue::Constant(TackyConstant::Int(1)),
+                destination_pointer: element,
+            },
+        ]);
+        assert_eq!(
+            interpret(&out_of_bounds),
+            Err(InterpreterError::InvalidAddress {
+                address: VARIABLES_ADDRESS + 12
+            })
+        );
+    }
+
+    #[test]
+    fn test_interpret_doubles() {
+        let double = |value| TackyValue::Constant(TackyConstant::Double(value));
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Divide,
+                7.0f64.to_bits() as i64,
+                2.0f64.to_bits() as i64,
+                TackyType::Double
+            ),
+            Ok(3.5f64.to_bits() as i64)
+        );
+        assert_eq!(
+            evaluate_binary(
+                &TackyBinaryOperator::Remainder,
+                7.0f64.to_bits() as i64,
+                2.0f64.to_bits() as i64,
+                TackyType::Double
+            ),
+            Err(InterpreterError::InvalidDoubleOperator {
+                operator: String::from("%")
+            })
+        );
+        // -0.0 is zero, so its negation is false.
+        let negative_zero = TackyValue::Variable(Symbol::from("tmp.0"));
+        let truncated = TackyValue::Variable(Symbol::from("tmp.1"));
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Unary {
+                        operator: TackyUnaryOperator::Negate,
+                        source: double(0.0),
+                        destination: negative_zero.clone(),
+                    },
+                    TackyInstruction::JumpIfNotZero {
+                        condition: negative_zero,
+                        target: Symbol::from("end"),
+                    },
+                    TackyInstruction::DoubleToInt {
+                        source: double(-2.9),
+                        destination: truncated.clone(),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(truncated),
+                    },
+                    TackyInstruction::Label(Symbol::from("end")),
+                    TackyInstruction::Return {
+                        value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+                    },
+                ],
+                variable_types: BTreeMap::from([(Symbol::from("tmp.0"), TackyType::Double)]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(interpret(&tacky_ast), Ok(-2));
+    }
+}
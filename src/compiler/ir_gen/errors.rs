@@ -1,3 +1,5 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::error_codes::{self, ErrorCode};
 use crate::compiler::lexer::tokens::TokenType;
 use crate::compiler::parser::cmm_ast::CmmBinaryOperator;
 use std::error::Error;
@@ -15,6 +17,21 @@ pub enum IRConversionError {
     UnsupportedBinaryOperatorConversion { operator: CmmBinaryOperator },
 }
 
+impl IRConversionError {
+    /// Returns the stable `ErrorCode` of the error.
+    ///
+    /// The semantic analysis rejects the errors of the program, so the remaining errors are bugs of
+    /// the compiler.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            IRConversionError::UnexpectedToken { .. }
+            | IRConversionError::UnsupportedBinaryOperatorConversion { .. } => {
+                error_codes::INTERNAL_COMPILER_ERROR
+            }
+        }
+    }
+}
+
 impl fmt::Display for IRConversionError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -33,3 +50,41 @@ impl fmt::Display for IRConversionError {
 }
 
 impl Error for IRConversionError {}
+
+/// Represents violations of the invariants of a TACKY IR, found by `verify_tacky`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TackyVerificationError {
+    /// Raised when a function is named like a compiler-generated name.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function.
+    GeneratedFunctionName { function: Symbol },
+    /// Raised when a temporary or a label does not follow the mangling scheme of generated names,
+    /// so that it could clash with a name of the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The name of the function containing the temporary or label.
+    /// * `name`: The name of the temporary or label.
+    UnmangledName { function: Symbol, name: Symbol },
+}
+
+impl fmt::Display for TackyVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TackyVerificationError::GeneratedFunctionName { function } => write!(
+                f,
+                "TACKY verification error: Function '{}' is named like a compiler-generated name",
+                function
+            ),
+            TackyVerificationError::UnmangledName { function, name } => write!(
+                f,
+                "TACKY verification error: '{}' in function '{}' is not a generated name and may clash with a name of the program",
+                name, function
+            ),
+        }
+    }
+}
+
+impl Error for TackyVerificationError {}
@@ -1,22 +1,135 @@
 pub mod errors;
-pub mod tacky_ast;
+pub mod static_accesses;
+pub mod type_table;
+pub mod verifier;
+pub use cmm_types::tacky_ast;
 
+use crate::common::symbol::{Interner, Symbol};
 use crate::compiler::parser::cmm_ast::{
-    CmmAst, CmmBinaryOperator, CmmExpression, CmmFunction, CmmStatement, CmmUnaryOperator,
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmExpression, CmmForInit,
+    CmmFunction, CmmInitializer, CmmParameter, CmmStatement, CmmStorageClass, CmmStructDeclaration,
+    CmmType, CmmUnaryOperator, ExprId, ExpressionArena,
 };
+use crate::compiler::semantic::ValidatedAst;
+use crate::compiler::semantic::constant_expression::{
+    ArithmeticConstant, evaluate_arithmetic_constant,
+};
+use crate::compiler::semantic::symbol_table::{FunctionType, Linkage};
+use crate::compiler::semantic::type_checker::TypeAnnotations;
 use errors::IRConversionError;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::borrow::BorrowMut;
+use std::collections::BTreeMap;
 use tacky_ast::{
-    TackyAst, TackyBinaryOperator, TackyFunction, TackyInstruction, TackyUnaryOperator, TackyValue,
+    TackyAst, TackyBinaryOperator, TackyConstant, TackyFunction, TackyInstruction,
+    TackyStaticConstant, TackyStaticInit, TackyStaticVariable, TackyType, TackyUnaryOperator,
+    TackyValue,
 };
+use type_table::TypeTable;
+
+/// Separates the stem of a generated name from its counter, e.g. `tmp.0` or `and_false.1`.
+///
+/// This is the mangling scheme of the names the compiler generates for temporaries, variables, and
+/// labels. C-- identifiers cannot contain the separator, so a generated name never clashes with a
+/// name written in the program, such as a function called `and_false0`. Variables are renamed to
+/// `<name>.<counter>`, so that variables of different scopes get different names. Function names
+/// are kept as they are, since the linker resolves functions by their source names.
+pub const GENERATED_NAME_SEPARATOR: char = '.';
+
+/// Checks whether a name was generated by the compiler rather than written in the program.
+///
+/// # Arguments
+///
+/// * `name`: The name of a TACKY variable, label, or function.
+///
+/// # Returns
+///
+/// `true` if the name follows the mangling scheme of generated names.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::ir_gen::is_generated_name;
+/// assert!(is_generated_name("and_false.0"));
+/// assert!(!is_generated_name("and_false0"));
+/// ```
+pub fn is_generated_name(name: &str) -> bool {
+    name.contains(GENERATED_NAME_SEPARATOR)
+}
 
 /// Represents an emitter for Tacky, a language or system.
 ///
-/// It holds the C-- AST and a temporary variable counter.
-pub struct TackyEmitter {
-    /// A counter for temporary variables.
+/// It holds the C-- AST and a temporary variable counter. The emitter owns its `Interner` unless it
+/// is created with `with_interner` to share the interner of a `CompilerSession`. It converts the
+/// programs the semantic analysis validated, and gives every variable declaration the semantic
+/// analysis resolved a unique TACKY name. It records the type of every variable and temporary it
+/// emits.
+pub struct TackyEmitter<N: BorrowMut<Interner> = Interner> {
+    /// A counter for temporary variables and renamed program variables.
     temp_counter: usize,
     /// A counter for labels.
     label_counter: usize,
+    /// The interner of the generated temporary variable and label names.
+    interner: N,
+    /// The expression every emitted instruction originates from, if origins are recorded.
+    instruction_origins: Option<Vec<Option<ExprId>>>,
+    /// The number of instructions of the functions converted before the current one.
+    origins_offset: usize,
+    /// The expression whose instructions are currently emitted.
+    current_expression: Option<ExprId>,
+    /// The resolved variables, the declared functions, and the expression types of the program
+    /// being converted.
+    annotations: TypeAnnotations,
+    /// The TACKY names of the variables declared so far, by their unique names in `annotations`.
+    variable_names: FxHashMap<Symbol, Symbol>,
+    /// The number of local variables of `annotations` declared so far, in declaration order.
+    declared_locals: usize,
+    /// The types of the variables and temporaries of the function being converted.
+    variable_types: BTreeMap<Symbol, TackyType>,
+    /// The C-- types of the variables and of the pointer temporaries of the function being
+    /// converted, which keep the types pointers point to.
+    cmm_types: FxHashMap<Symbol, CmmType>,
+    /// The labels of the enclosing loops, innermost last.
+    loops: Vec<LoopLabels>,
+    /// The name of the function being converted, whose return type `return` statements follow.
+    current_function: Option<Symbol>,
+    /// The constants of the read-only data of the program being converted.
+    static_constants: Vec<TackyStaticConstant>,
+    /// The static variables of the program being converted, which the `static` local variables
+    /// are appended to as their functions are converted.
+    static_variables: Vec<TackyStaticVariable>,
+    /// The C-- types of the static variables of the program being converted, by TACKY name,
+    /// including the variables only declared `extern`.
+    static_types: FxHashMap<Symbol, CmmType>,
+    /// The layouts of the structure types of the program being converted.
+    types: TypeTable,
+}
+
+/// Represents the labels `break` and `continue` statements jump to inside a loop.
+struct LoopLabels {
+    /// The label after the loop.
+    break_label: Symbol,
+    /// The label where the next iteration starts, before the condition or the post expression.
+    continue_label: Symbol,
+}
+
+/// Represents an expression designating storage, which can be assigned, incremented, or decremented.
+enum Lvalue {
+    /// A variable of the function, by its unique name.
+    Variable(Symbol),
+    /// The object a pointer points to, as in `*p`.
+    Dereferenced {
+        /// The pointer to the object.
+        pointer: TackyValue,
+        /// The type of the object.
+        referenced_type: CmmType,
+    },
+}
+
+impl Default for TackyEmitter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TackyEmitter {
@@ -29,566 +142,4176 @@ impl TackyEmitter {
         Self {
             temp_counter: 0,
             label_counter: 0,
+            interner: Interner::new(),
+            instruction_origins: None,
+            origins_offset: 0,
+            current_expression: None,
+            annotations: TypeAnnotations::default(),
+            variable_names: FxHashMap::default(),
+            declared_locals: 0,
+            variable_types: BTreeMap::new(),
+            cmm_types: FxHashMap::default(),
+            loops: Vec::new(),
+            current_function: None,
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+            static_types: FxHashMap::default(),
+            types: TypeTable::default(),
+        }
+    }
+
+    /// Creates a new `TackyEmitter` that records the expression every instruction originates from.
+    ///
+    /// # Returns
+    ///
+    /// A new `TackyEmitter` instance, whose origins can be read with `instruction_origins`.
+    pub fn with_instruction_origins() -> Self {
+        Self {
+            instruction_origins: Some(Vec::new()),
+            ..Self::new()
         }
     }
+}
 
-    /// Converts the C-- AST into an intermediate TACKY representation.
+impl<'i> TackyEmitter<&'i mut Interner> {
+    /// Creates a new `TackyEmitter` that interns the generated names in an existing interner.
     ///
     /// # Arguments
     ///
-    /// * `cmm_ast`: A reference to the C-- `CmmAst` to be converted.
+    /// * `interner`: The `Interner` shared with the other stages of the compilation.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the generated `TackyFunction` on success,
-    /// or a `CodegenError` on failure.
-    pub fn convert_ast(&mut self, cmm_ast: CmmAst) -> Result<TackyAst, IRConversionError> {
-        let function = match cmm_ast {
-            CmmAst::Program { function } => self.convert_function(&function)?,
-        };
-        Ok(TackyAst::Program { function })
+    /// A new `TackyEmitter` instance.
+    pub fn with_interner(interner: &'i mut Interner) -> Self {
+        Self {
+            temp_counter: 0,
+            label_counter: 0,
+            interner,
+            instruction_origins: None,
+            origins_offset: 0,
+            current_expression: None,
+            annotations: TypeAnnotations::default(),
+            variable_names: FxHashMap::default(),
+            declared_locals: 0,
+            variable_types: BTreeMap::new(),
+            cmm_types: FxHashMap::default(),
+            loops: Vec::new(),
+            current_function: None,
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+            static_types: FxHashMap::default(),
+            types: TypeTable::default(),
+        }
     }
+}
 
-    /// Converts a C-- function definition into a TACKY function definition.
+impl<N: BorrowMut<Interner>> TackyEmitter<N> {
+    /// Returns the expression each instruction of the last converted program originates from.
+    ///
+    /// The instructions of the functions are counted one after another, in program order. Return
+    /// instructions originate from the returned expression, and the jumps and labels of if
+    /// statements and loops from their conditions. The jumps and labels of a for loop without a
+    /// condition have no origin. The implicit return at the end of a function has no origin either.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId`s in instruction order, or `None` if the emitter does not record origins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::ir_gen::TackyEmitter;
+    /// # use cmm::compiler::validate_source;
+    /// let validated_ast = validate_source("int main(void) { return -~2; }").unwrap();
+    /// let mut emitter = TackyEmitter::with_instruction_origins();
+    /// emitter.convert_ast(validated_ast).unwrap();
+    /// let origins = emitter.instruction_origins().unwrap();
+    /// let indices: Vec<usize> = origins.iter().map(|origin| origin.unwrap().index()).collect();
+    /// assert_eq!(indices, vec![1, 2, 2]);
+    /// ```
+    pub fn instruction_origins(&self) -> Option<&[Option<ExprId>]> {
+        self.instruction_origins.as_deref()
+    }
+
+    /// Converts a validated C-- AST into an intermediate TACKY representation.
+    ///
+    /// The structures are declared first, in program order, so that a structure can contain the
+    /// structures declared before it. The file-scope variables become static variables, see
+    /// `convert_static_variables`, which the functions access through their addresses. The
+    /// variables, the called functions, the values of the enumerators, and the linkage of the
+    /// defined functions are taken from the annotations of the semantic analysis, which already
+    /// rejected the invalid programs, such as undeclared names, redefinitions, invalid operand
+    /// types and conversions, and jumps outside of loops.
     ///
     /// # Arguments
     ///
-    /// * `cmm_function` - A reference to the C-- `CmmFunction` to convert.
+    /// * `validated_ast`: The `ValidatedAst` to be converted, see `validate`.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the generated `TackyFunction` on success,
+    /// A `Result` containing the generated `TackyAst` on success,
     /// or a `CodegenError` on failure.
-    fn convert_function(
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "tacky", skip_all))]
+    pub fn convert_ast(
         &mut self,
-        cmm_function: &CmmFunction,
-    ) -> Result<TackyFunction, IRConversionError> {
-        match cmm_function {
-            CmmFunction::Function { identifier, body } => {
-                let statements = self.convert_statement(body)?;
-                Ok(TackyFunction::Function {
+        validated_ast: ValidatedAst,
+    ) -> Result<TackyAst, IRConversionError> {
+        let ValidatedAst {
+            cmm_ast,
+            annotations,
+        } = validated_ast;
+        self.static_constants.clear();
+        self.static_variables.clear();
+        self.static_types.clear();
+        self.annotations = annotations;
+        self.variable_names.clear();
+        self.declared_locals = 0;
+        self.types.clear();
+        if let Some(origins) = &mut self.instruction_origins {
+            origins.clear();
+        }
+        self.origins_offset = 0;
+        let CmmAst::Program {
+            structs,
+            variables,
+            functions: cmm_functions,
+            expressions,
+            ..
+        } = cmm_ast;
+        for CmmStructDeclaration::Struct { tag, members, .. } in &structs {
+            self.types
+                .declare_struct(tag, members)
+                .expect("The semantic analysis rejects structures larger than the largest object");
+        }
+        self.convert_static_variables(&variables, &expressions);
+        let mut functions = Vec::with_capacity(cmm_functions.len());
+        for CmmFunction::Function {
+            identifier,
+            parameters,
+            body,
+            ..
+        } in &cmm_functions
+        {
+            let Some(body) = body else {
+                continue;
+            };
+            functions.push(self.convert_function(identifier, parameters, body, &expressions)?);
+        }
+        Ok(TackyAst::Program {
+            functions,
+            static_constants: std::mem::take(&mut self.static_constants),
+            static_variables: std::mem::take(&mut self.static_variables),
+        })
+    }
+
+    /// Converts the file-scope variables of a program into static variables, in the order of their
+    /// first declarations.
+    ///
+    /// A variable declared several times is defined once, with the initializer of the declaration
+    /// that has one, see `convert_static_initializer`, and it is zero without one. A variable only
+    /// declared `extern` without an initializer is defined in another file, so it is only
+    /// declared. The variables keep their names, and have external linkage unless they are
+    /// `static`.
+    ///
+    /// # Arguments
+    ///
+    /// * `declarations` - The file-scope declarations of the program.
+    /// * `expressions` - The arena holding the initializers.
+    fn convert_static_variables(
+        &mut self,
+        declarations: &[CmmDeclaration],
+        expressions: &ExpressionArena,
+    ) {
+        let defined: FxHashSet<&Symbol> = declarations
+            .iter()
+            .filter_map(
+                |CmmDeclaration::Declaration {
+                     identifier,
+                     storage_class,
+                     initializer,
+                     ..
+                 }| {
+                    (initializer.is_some() || *storage_class != Some(CmmStorageClass::Extern))
+                        .then_some(identifier)
+                },
+            )
+            .collect();
+        let mut indices = FxHashMap::default();
+        for CmmDeclaration::Declaration {
+            identifier,
+            variable_type,
+            initializer,
+            ..
+        } in declarations
+        {
+            self.static_types
+                .insert(identifier.clone(), variable_type.clone());
+            self.variable_names
+                .insert(identifier.clone(), identifier.clone());
+            if !defined.contains(identifier) {
+                indices.entry(identifier.clone()).or_insert_with(|| {
+                    self.static_variables
+                        .push(TackyStaticVariable::Declaration {
+                            identifier: identifier.clone(),
+                        });
+                    self.static_variables.len() - 1
+                });
+                continue;
+            }
+            let index = *indices.entry(identifier.clone()).or_insert_with(|| {
+                let declaration = self
+                    .annotations
+                    .symbols()
+                    .lookup_in_file_scope(identifier)
+                    .expect("The semantic analysis declares every file-scope variable");
+                self.static_variables.push(TackyStaticVariable::Variable {
                     identifier: identifier.clone(),
-                    instructions: statements,
-                })
+                    global: declaration.linkage == Linkage::External,
+                    alignment: self.types.alignment(variable_type),
+                    initial_values: vec![TackyStaticInit::Zero(self.types.size(variable_type))],
+                });
+                self.static_variables.len() - 1
+            });
+            if let Some(initializer) = initializer {
+                let mut initial_values = Vec::new();
+                self.convert_static_initializer(
+                    expressions,
+                    initializer,
+                    variable_type,
+                    &mut initial_values,
+                );
+                if let TackyStaticVariable::Variable {
+                    initial_values: variable_values,
+                    ..
+                } = &mut self.static_variables[index]
+                {
+                    *variable_values = initial_values;
+                }
             }
         }
     }
 
-    /// Converts a C-- statement into a sequence of TACKY instructions.
+    /// Evaluates the initializer of a static variable, or of one of its elements, into its initial
+    /// value.
+    ///
+    /// The semantic analysis checked that every scalar is a constant expression and converted it
+    /// to the type of its object, except for the pointers, which are converted here. The elements
+    /// without an initializer are zero.
     ///
     /// # Arguments
     ///
-    /// * `cmm_statement` - A reference to the C-- `CmmStatement` to convert.
+    /// * `expressions` - The arena holding the expressions of the initializer.
+    /// * `initializer` - The initializer of the variable or the element.
+    /// * `target_type` - The type of the variable or the element.
+    /// * `initial_values` - The initial value built so far, which the initializer is appended to.
+    fn convert_static_initializer(
+        &self,
+        expressions: &ExpressionArena,
+        initializer: &CmmInitializer,
+        target_type: &CmmType,
+        initial_values: &mut Vec<TackyStaticInit>,
+    ) {
+        match (initializer, target_type.unqualified()) {
+            (CmmInitializer::Compound(initializers), CmmType::Array(element_type, size)) => {
+                for initializer in initializers {
+                    self.convert_static_initializer(
+                        expressions,
+                        initializer,
+                        element_type,
+                        initial_values,
+                    );
+                }
+                let zero_size = (size - initializers.len()) * self.types.size(element_type);
+                match initial_values.last_mut() {
+                    _ if zero_size == 0 => {}
+                    Some(TackyStaticInit::Zero(size)) => *size += zero_size,
+                    _ => initial_values.push(TackyStaticInit::Zero(zero_size)),
+                }
+            }
+            (CmmInitializer::Single(expression), scalar_type) => {
+                let enumerator_value = |variable: ExprId, identifier: &Symbol| match self
+                    .annotations
+                    .variables()
+                    .unique_name(variable)
+                {
+                    Some(_) => None,
+                    None => self.annotations.enumerator_value(identifier),
+                };
+                // The value is converted to the type of the variable like an assignment does,
+                // before it is narrowed to the TACKY type holding it.
+                let value =
+                    evaluate_arithmetic_constant(expressions, *expression, &enumerator_value)
+                        .and_then(|value| value.convert_to(scalar_type))
+                        .expect("The semantic analysis only accepts constant static initializers");
+                let constant = match value {
+                    ArithmeticConstant::Int(value) => TackyConstant::Int(value),
+                    ArithmeticConstant::UnsignedInt(value) => TackyConstant::UnsignedInt(value),
+                    ArithmeticConstant::Long(value) => TackyConstant::Long(value),
+                    ArithmeticConstant::UnsignedLong(value) => TackyConstant::UnsignedLong(value),
+                    ArithmeticConstant::Double(value) => TackyConstant::Double(value),
+                };
+                let constant = match scalar_type {
+                    // A pointer is a 64-bit address.
+                    CmmType::Pointer(_) => constant,
+                    _ => constant.convert_to(TackyType::from(scalar_type.clone())),
+                };
+                initial_values.push(TackyStaticInit::Value(constant));
+            }
+            _ => unreachable!("The semantic analysis rejects initializers not matching the type"),
+        }
+    }
+
+    /// Converts a C-- function definition into a TACKY function definition.
+    ///
+    /// The parameters get their TACKY names first, then the declarations of the body in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The name of the function.
+    /// * `parameters` - The parameters of the function.
+    /// * `body` - The body of the function.
+    /// * `expressions` - The arena holding the expressions of the function.
     ///
     /// # Returns
     ///
-    /// A `Result` containing a vector of `TackyInstruction`s on success,
+    /// A `Result` containing the generated `TackyFunction` on success,
     /// or a `CodegenError` on failure.
-    fn convert_statement(
+    fn convert_function(
         &mut self,
-        cmm_statement: &CmmStatement,
-    ) -> Result<Vec<TackyInstruction>, IRConversionError> {
-        match cmm_statement {
-            CmmStatement::Return { expression } => {
-                let mut tacky_instructions = Vec::new();
-                let tacky_value = self.emit_tacky(expression, &mut tacky_instructions)?;
-                tacky_instructions.push(TackyInstruction::Return { value: tacky_value });
-                Ok(tacky_instructions)
+        identifier: &Symbol,
+        parameters: &[CmmParameter],
+        body: &[CmmBlockItem],
+        expressions: &ExpressionArena,
+    ) -> Result<TackyFunction, IRConversionError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!("function", name = %identifier).entered();
+        self.variable_types.clear();
+        self.cmm_types.clear();
+        self.loops.clear();
+        self.current_function = Some(identifier.clone());
+        let unique_names = self
+            .annotations
+            .variables()
+            .parameters(identifier)
+            .expect("The semantic analysis resolves the parameters of every definition")
+            .to_vec();
+        let parameter_names = parameters
+            .iter()
+            .zip(unique_names)
+            .map(
+                |(
+                    CmmParameter::Parameter {
+                        identifier,
+                        parameter_type,
+                    },
+                    unique_name,
+                )| {
+                    self.make_variable(identifier, unique_name, &parameter_type.decayed())
+                },
+            )
+            .collect();
+        // Most expressions emit a single instruction, logical operators emit a few more.
+        let mut statements = Vec::with_capacity(2 * expressions.len() + 1);
+        for item in body {
+            match item {
+                CmmBlockItem::Declaration(declaration) => {
+                    self.convert_declaration(declaration, expressions, &mut statements)?
+                }
+                CmmBlockItem::Statement(statement) => {
+                    self.convert_statement(statement, expressions, &mut statements)?
+                }
             }
         }
+        let declaration = self
+            .annotations
+            .symbols()
+            .lookup_in_file_scope(identifier)
+            .expect("The semantic analysis declares every defined function");
+        let returns_value = declaration
+            .function_type()
+            .is_some_and(|function_type| function_type.return_type.is_some());
+        let global = declaration.linkage == Linkage::External;
+        append_implicit_return(&mut statements, returns_value);
+        self.record_statement_origins(None, statements.len());
+        self.origins_offset += statements.len();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            instructions = statements.len(),
+            "emitted TACKY instructions"
+        );
+        Ok(TackyFunction::Function {
+            identifier: identifier.clone(),
+            global,
+            parameters: parameter_names,
+            instructions: statements,
+            variable_types: std::mem::take(&mut self.variable_types),
+        })
     }
 
-    /// Converts a C-- expression into a TACKY value.
+    /// Converts a C-- statement into a sequence of TACKY instructions.
     ///
-    /// Recursively calls itself to convert nested expressions.
+    /// An if statement jumps over its then branch when the condition is zero:
+    ///
+    /// ```text
+    /// JumpIfZero(condition, if_else.0)
+    /// <then branch>
+    /// Jump(if_end.1)
+    /// Label(if_else.0)
+    /// <else branch>
+    /// Label(if_end.1)
+    /// ```
+    ///
+    /// Without an else branch, the jump targets the end label, and the `Jump` and else label are
+    /// left out. A while loop checks its condition before every iteration:
+    ///
+    /// ```text
+    /// Label(while_start.0)
+    /// JumpIfZero(condition, while_end.1)
+    /// <body>
+    /// Jump(while_start.0)
+    /// Label(while_end.1)
+    /// ```
+    ///
+    /// A do-while loop runs its body first and jumps back while the condition is nonzero:
+    ///
+    /// ```text
+    /// Label(do_start.0)
+    /// <body>
+    /// Label(do_continue.1)
+    /// JumpIfNotZero(condition, do_start.0)
+    /// Label(do_end.2)
+    /// ```
+    ///
+    /// A for loop runs its initial clause once, and checks its condition before every iteration. Without a condition, the `JumpIfZero` is left out:
+    ///
+    /// ```text
+    /// <initial clause>
+    /// Label(for_start.0)
+    /// JumpIfZero(condition, for_end.2)
+    /// <body>
+    /// Label(for_continue.1)
+    /// <post clause>
+    /// Jump(for_start.0)
+    /// Label(for_end.2)
+    /// ```
+    ///
+    /// Every loop labels its body with its own labels, so that a `break` jumps to the end label of
+    /// the innermost enclosing loop, and a `continue` to its `while_start`, `do_continue`, or
+    /// `for_continue` label. An expression statement emits the instructions of its expression and
+    /// discards the resulting value, and a null statement emits nothing.
     ///
     /// # Arguments
     ///
-    /// * `cmm_expression` - A reference to the C-- `Expression` to convert.
+    /// * `cmm_statement` - A reference to the C-- `CmmStatement` to convert.
+    /// * `expressions` - The arena holding the expressions of the statement.
     /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the generated instructions to.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the generated `TackyValue` on success,
-    /// or a `CodegenError` on failure.
-    fn emit_tacky(
+    /// A `Result` containing `()` on success, or a `CodegenError` on failure.
+    fn convert_statement(
         &mut self,
-        cmm_expression: &CmmExpression,
+        cmm_statement: &CmmStatement,
+        expressions: &ExpressionArena,
         tacky_instructions: &mut Vec<TackyInstruction>,
-    ) -> Result<TackyValue, IRConversionError> {
-        match cmm_expression {
-            CmmExpression::IntegerConstant { value } => Ok(TackyValue::Constant(*value)),
-            CmmExpression::Unary {
-                operator,
-                expression,
-            } => {
-                let source = self.emit_tacky(expression, tacky_instructions)?;
-                let destination_name = self.make_temporary();
-                let destination = TackyValue::Variable(destination_name);
-                let operator = self.convert_unary_operator(operator);
-                tacky_instructions.push(TackyInstruction::Unary {
-                    operator,
-                    source,
-                    destination: destination.clone(),
-                });
-                Ok(destination)
+    ) -> Result<(), IRConversionError> {
+        match cmm_statement {
+            CmmStatement::Return { expression, .. } => {
+                let identifier = self
+                    .current_function
+                    .clone()
+                    .expect("Statements are converted inside a function");
+                let return_type = self
+                    .annotations
+                    .function_type(&identifier)
+                    .and_then(|function_type| function_type.return_type.clone());
+                let value = match (expression, return_type) {
+                    (Some(expression), Some(return_type)) => {
+                        let tacky_value =
+                            self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                        Some(self.convert_assigned_value(
+                            tacky_value,
+                            &return_type,
+                            tacky_instructions,
+                        ))
+                    }
+                    (None, None) => None,
+                    _ => unreachable!(
+                        "The semantic analysis matches the return statements with the return type"
+                    ),
+                };
+                tacky_instructions.push(TackyInstruction::Return { value });
+                self.record_statement_origins(*expression, tacky_instructions.len());
             }
-            CmmExpression::Binary {
-                operator,
-                left,
-                right,
-            } => match operator {
-                CmmBinaryOperator::And => {
-                    let label_false_name = self.make_label("and_false");
-                    let label_end_name = self.make_label("and_end");
-
-                    // First condition
-                    let source1 = self.emit_tacky(left, tacky_instructions)?;
-                    let jump_false1 = TackyInstruction::JumpIfZero {
-                        condition: source1,
-                        target: label_false_name.clone(),
-                    };
-                    tacky_instructions.push(jump_false1);
-
-                    // Second condition, unless first condition is zero
-                    let source2 = self.emit_tacky(right, tacky_instructions)?;
-                    let jump_false2 = TackyInstruction::JumpIfZero {
-                        condition: source2,
-                        target: label_false_name.clone(),
-                    };
-                    tacky_instructions.push(jump_false2);
+            CmmStatement::Expression { expression, .. } => {
+                self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                self.record_statement_origins(Some(*expression), tacky_instructions.len());
+            }
+            CmmStatement::Null { .. } => {}
+            CmmStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let label_else_name = else_branch.as_ref().map(|_| self.make_label("if_else"));
+                let label_end_name = self.make_label("if_end");
 
-                    let destination_name = self.make_temporary();
+                let condition_value =
+                    self.emit_tacky(expressions, *condition, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::JumpIfZero {
+                    condition: condition_value,
+                    target: label_else_name
+                        .clone()
+                        .unwrap_or_else(|| label_end_name.clone()),
+                });
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
 
-                    // Return value if both conditions are non-zero
-                    let copy_true = TackyInstruction::Copy {
-                        source: TackyValue::Constant(1),
-                        destination: TackyValue::Variable(destination_name.clone()),
-                    };
-                    let jump_end = TackyInstruction::Jump {
+                self.convert_statement(then_branch, expressions, tacky_instructions)?;
+                if let (Some(else_branch), Some(label_else_name)) = (else_branch, label_else_name) {
+                    tacky_instructions.push(TackyInstruction::Jump {
                         target: label_end_name.clone(),
-                    };
-
-                    // Return value if any condition is zero
-                    let label_false = TackyInstruction::Label(label_false_name);
-                    let copy_false = TackyInstruction::Copy {
-                        source: TackyValue::Constant(0),
-                        destination: TackyValue::Variable(destination_name.clone()),
-                    };
-                    let label_end = TackyInstruction::Label(label_end_name);
-                    tacky_instructions.push(copy_true);
-                    tacky_instructions.push(jump_end);
-                    tacky_instructions.push(label_false);
-                    tacky_instructions.push(copy_false);
-                    tacky_instructions.push(label_end);
-
-                    Ok(TackyValue::Variable(destination_name))
+                    });
+                    tacky_instructions.push(TackyInstruction::Label(label_else_name));
+                    self.record_statement_origins(Some(*condition), tacky_instructions.len());
+                    self.convert_statement(else_branch, expressions, tacky_instructions)?;
                 }
-                CmmBinaryOperator::Or => {
-                    let label_true_name = self.make_label("or_true");
-                    let label_end_name = self.make_label("or_end");
-
-                    // First condition
-                    let source1 = self.emit_tacky(left, tacky_instructions)?;
-                    let jump_true1 = TackyInstruction::JumpIfNotZero {
-                        condition: source1,
-                        target: label_true_name.clone(),
-                    };
-                    tacky_instructions.push(jump_true1);
-
-                    // Second condition, unless first condition is not zero
-                    let source2 = self.emit_tacky(right, tacky_instructions)?;
-                    let jump_true2 = TackyInstruction::JumpIfNotZero {
-                        condition: source2,
-                        target: label_true_name.clone(),
-                    };
-                    tacky_instructions.push(jump_true2);
+                tacky_instructions.push(TackyInstruction::Label(label_end_name));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+            }
+            CmmStatement::While {
+                condition, body, ..
+            } => {
+                let label_start_name = self.make_label("while_start");
+                let label_end_name = self.make_label("while_end");
 
-                    let destination_name = self.make_temporary();
+                tacky_instructions.push(TackyInstruction::Label(label_start_name.clone()));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+                let condition_value =
+                    self.emit_tacky(expressions, *condition, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::JumpIfZero {
+                    condition: condition_value,
+                    target: label_end_name.clone(),
+                });
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
 
-                    // Return value if both conditions are zero
-                    let copy_false = TackyInstruction::Copy {
-                        source: TackyValue::Constant(0),
-                        destination: TackyValue::Variable(destination_name.clone()),
-                    };
-                    let jump_end = TackyInstruction::Jump {
-                        target: label_end_name.clone(),
-                    };
+                let loop_labels = LoopLabels {
+                    break_label: label_end_name.clone(),
+                    continue_label: label_start_name.clone(),
+                };
+                self.convert_loop_body(body, loop_labels, expressions, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::Jump {
+                    target: label_start_name,
+                });
+                tacky_instructions.push(TackyInstruction::Label(label_end_name));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+            }
+            CmmStatement::DoWhile {
+                body, condition, ..
+            } => {
+                let label_start_name = self.make_label("do_start");
+                let label_continue_name = self.make_label("do_continue");
+                let label_end_name = self.make_label("do_end");
 
-                    // Return value if any condition is non-zero
-                    let label_true = TackyInstruction::Label(label_true_name);
-                    let copy_true = TackyInstruction::Copy {
-                        source: TackyValue::Constant(1),
-                        destination: TackyValue::Variable(destination_name.clone()),
-                    };
-                    let label_end = TackyInstruction::Label(label_end_name);
-                    tacky_instructions.push(copy_false);
-                    tacky_instructions.push(jump_end);
-                    tacky_instructions.push(label_true);
-                    tacky_instructions.push(copy_true);
-                    tacky_instructions.push(label_end);
+                tacky_instructions.push(TackyInstruction::Label(label_start_name.clone()));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+                let loop_labels = LoopLabels {
+                    break_label: label_end_name.clone(),
+                    continue_label: label_continue_name.clone(),
+                };
+                self.convert_loop_body(body, loop_labels, expressions, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::Label(label_continue_name));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+                let condition_value =
+                    self.emit_tacky(expressions, *condition, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::JumpIfNotZero {
+                    condition: condition_value,
+                    target: label_start_name,
+                });
+                tacky_instructions.push(TackyInstruction::Label(label_end_name));
+                self.record_statement_origins(Some(*condition), tacky_instructions.len());
+            }
+            CmmStatement::For {
+                init,
+                condition,
+                post,
+                body,
+                ..
+            } => {
+                let label_start_name = self.make_label("for_start");
+                let label_continue_name = self.make_label("for_continue");
+                let label_end_name = self.make_label("for_end");
 
-                    Ok(TackyValue::Variable(destination_name))
+                match init {
+                    CmmForInit::Declaration(declaration) => {
+                        self.convert_declaration(declaration, expressions, tacky_instructions)?
+                    }
+                    CmmForInit::Expression(Some(expression)) => {
+                        self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                    }
+                    CmmForInit::Expression(None) => {}
                 }
-                CmmBinaryOperator::Equal
-                | CmmBinaryOperator::NotEqual
-                | CmmBinaryOperator::GreaterThan
-                | CmmBinaryOperator::LessThan
-                | CmmBinaryOperator::GreaterThanEqual
-                | CmmBinaryOperator::LessThanEqual
-                | CmmBinaryOperator::Add
-                | CmmBinaryOperator::Subtract
-                | CmmBinaryOperator::Multiply
-                | CmmBinaryOperator::Divide
-                | CmmBinaryOperator::Remainder => {
-                    let source1 = self.emit_tacky(left, tacky_instructions)?;
-                    let source2 = self.emit_tacky(right, tacky_instructions)?;
-                    let destination_name = self.make_temporary();
-                    let destination = TackyValue::Variable(destination_name);
-                    let operator = self.convert_binary_operator(operator)?;
-                    tacky_instructions.push(TackyInstruction::Binary {
-                        operator,
-                        source1,
-                        source2,
-                        destination: destination.clone(),
+
+                tacky_instructions.push(TackyInstruction::Label(label_start_name.clone()));
+                self.record_statement_origins(*condition, tacky_instructions.len());
+                if let Some(condition) = condition {
+                    let condition_value =
+                        self.emit_tacky(expressions, *condition, tacky_instructions)?;
+                    tacky_instructions.push(TackyInstruction::JumpIfZero {
+                        condition: condition_value,
+                        target: label_end_name.clone(),
                     });
-                    Ok(destination)
+                    self.record_statement_origins(Some(*condition), tacky_instructions.len());
                 }
-            },
+
+                let loop_labels = LoopLabels {
+                    break_label: label_end_name.clone(),
+                    continue_label: label_continue_name.clone(),
+                };
+                self.convert_loop_body(body, loop_labels, expressions, tacky_instructions)?;
+                tacky_instructions.push(TackyInstruction::Label(label_continue_name));
+                self.record_statement_origins(*condition, tacky_instructions.len());
+                if let Some(post) = post {
+                    self.emit_tacky(expressions, *post, tacky_instructions)?;
+                }
+                tacky_instructions.push(TackyInstruction::Jump {
+                    target: label_start_name,
+                });
+                tacky_instructions.push(TackyInstruction::Label(label_end_name));
+                self.record_statement_origins(*condition, tacky_instructions.len());
+            }
+            CmmStatement::Break { .. } => {
+                let loop_labels = self
+                    .loops
+                    .last()
+                    .expect("The semantic analysis rejects a break outside of a loop");
+                tacky_instructions.push(TackyInstruction::Jump {
+                    target: loop_labels.break_label.clone(),
+                });
+                self.record_statement_origins(None, tacky_instructions.len());
+            }
+            CmmStatement::Continue { .. } => {
+                let loop_labels = self
+                    .loops
+                    .last()
+                    .expect("The semantic analysis rejects a continue outside of a loop");
+                tacky_instructions.push(TackyInstruction::Jump {
+                    target: loop_labels.continue_label.clone(),
+                });
+                self.record_statement_origins(None, tacky_instructions.len());
+            }
         }
+        Ok(())
     }
 
-    /// Converts a C-- unary operator into a TACKY unary operator.
+    /// Converts the body of a loop, with `break` and `continue` statements jumping to its labels.
     ///
     /// # Arguments
     ///
-    /// * `cmm_operator` - A reference to the C-- `CmmUnaryOperator` to convert.
+    /// * `body` - A reference to the C-- `CmmStatement` of the loop body.
+    /// * `loop_labels` - The labels of the loop.
+    /// * `expressions` - The arena holding the expressions of the body.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the generated instructions to.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the generated `TackyUnaryOperator` on success,
-    /// or a `CodegenError` on failure.
-    fn convert_unary_operator(&self, cmm_operator: &CmmUnaryOperator) -> TackyUnaryOperator {
-        match cmm_operator {
-            CmmUnaryOperator::Complement => TackyUnaryOperator::Complement,
-            CmmUnaryOperator::Negate => TackyUnaryOperator::Negate,
-            CmmUnaryOperator::Not => TackyUnaryOperator::Not,
-        }
+    /// A `Result` containing `()` on success, or an `IRConversionError` on failure.
+    fn convert_loop_body(
+        &mut self,
+        body: &CmmStatement,
+        loop_labels: LoopLabels,
+        expressions: &ExpressionArena,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<(), IRConversionError> {
+        self.loops.push(loop_labels);
+        let result = self.convert_statement(body, expressions, tacky_instructions);
+        self.loops.pop();
+        result
     }
 
-    /// Converts a C-- binary operator into a TACKY binary operator.
+    /// Converts a C-- variable declaration into the TACKY instructions of its initializer.
+    ///
+    /// The variable gets its TACKY name before the initializer is converted, so the initializer
+    /// already refers to the new variable, as in C. A `static` variable becomes a static variable
+    /// that only its function refers to, initialized before the program starts, and an `extern`
+    /// variable refers to the static variable of its name, so neither emits instructions.
     ///
     /// # Arguments
     ///
-    /// * `cmm_operator` - A reference to the C-- `CmmBinaryOperator` to convert.
+    /// * `cmm_declaration` - A reference to the C-- `CmmDeclaration` to convert.
+    /// * `expressions` - The arena holding the expressions of the declaration.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the generated instructions to.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the generated `TackyBinaryOperator` on success,
-    /// or a `CodegenError` on failure.
-    fn convert_binary_operator(
-        &self,
-        cmm_operator: &CmmBinaryOperator,
-    ) -> Result<TackyBinaryOperator, IRConversionError> {
-        match cmm_operator {
-            CmmBinaryOperator::Add => Ok(TackyBinaryOperator::Add),
-            CmmBinaryOperator::Subtract => Ok(TackyBinaryOperator::Subtract),
-            CmmBinaryOperator::Multiply => Ok(TackyBinaryOperator::Multiply),
-            CmmBinaryOperator::Divide => Ok(TackyBinaryOperator::Divide),
-            CmmBinaryOperator::Remainder => Ok(TackyBinaryOperator::Remainder),
-            CmmBinaryOperator::Equal => Ok(TackyBinaryOperator::Equal),
-            CmmBinaryOperator::NotEqual => Ok(TackyBinaryOperator::NotEqual),
-            CmmBinaryOperator::GreaterThan => Ok(TackyBinaryOperator::GreaterThan),
-            CmmBinaryOperator::LessThan => Ok(TackyBinaryOperator::LessThan),
-            CmmBinaryOperator::GreaterThanEqual => Ok(TackyBinaryOperator::GreaterThanEqual),
-            CmmBinaryOperator::LessThanEqual => Ok(TackyBinaryOperator::LessThanEqual),
-            CmmBinaryOperator::And | CmmBinaryOperator::Or => {
-                Err(IRConversionError::UnsupportedBinaryOperatorConversion {
-                    operator: cmm_operator.clone(),
-                })
+    /// A `Result` containing `()` on success, or an `IRConversionError` on failure.
+    fn convert_declaration(
+        &mut self,
+        cmm_declaration: &CmmDeclaration,
+        expressions: &ExpressionArena,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<(), IRConversionError> {
+        let CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            ..
+        } = cmm_declaration;
+        let unique_name = self.annotations.variables().locals()[self.declared_locals]
+            .unique_name
+            .clone();
+        self.declared_locals += 1;
+        match storage_class {
+            Some(CmmStorageClass::Static) => {
+                self.convert_static_local(
+                    identifier,
+                    unique_name,
+                    variable_type,
+                    initializer.as_ref(),
+                    expressions,
+                );
+                return Ok(());
+            }
+            Some(CmmStorageClass::Extern) => {
+                // A variable without a file-scope declaration is defined in another file.
+                if !self.static_types.contains_key(&unique_name) {
+                    self.static_variables
+                        .push(TackyStaticVariable::Declaration {
+                            identifier: unique_name.clone(),
+                        });
+                }
+                self.static_types
+                    .insert(unique_name.clone(), variable_type.clone());
+                self.variable_names.insert(unique_name.clone(), unique_name);
+                return Ok(());
+            }
+            None => {}
+        }
+        let variable_name = self.make_variable(identifier, unique_name, variable_type);
+        match (initializer, variable_type) {
+            (None, _) => {}
+            (Some(CmmInitializer::Single(initializer)), _)
+                if !matches!(
+                    variable_type.unqualified(),
+                    CmmType::Array(_, _) | CmmType::Struct(_)
+                ) =>
+            {
+                let source = self.emit_tacky(expressions, *initializer, tacky_instructions)?;
+                let source = self.convert_assigned_value(source, variable_type, tacky_instructions);
+                tacky_instructions.push(TackyInstruction::Copy {
+                    source,
+                    destination: TackyValue::Variable(variable_name),
+                });
+                self.record_statement_origins(Some(*initializer), tacky_instructions.len());
+            }
+            (Some(initializer @ CmmInitializer::Compound(_)), CmmType::Array(_, _))
+                if !matches!(
+                    variable_type.scalar_type().unqualified(),
+                    CmmType::Struct(_)
+                ) =>
+            {
+                let scalar_type = variable_type.scalar_type().clone();
+                let first_scalar = TackyValue::Variable(
+                    self.make_typed_temporary(&CmmType::Pointer(Box::new(scalar_type))),
+                );
+                tacky_instructions.push(TackyInstruction::GetAddress {
+                    source: variable_name,
+                    destination: first_scalar.clone(),
+                });
+                self.emit_initializer(
+                    expressions,
+                    initializer,
+                    variable_type,
+                    &first_scalar,
+                    0,
+                    tacky_instructions,
+                )?;
+                self.record_statement_origins(None, tacky_instructions.len());
+            }
+            (Some(_), _) => {
+                unreachable!("The semantic analysis rejects initializers not matching the type")
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts a `static` local variable into a static variable without linkage, whose TACKY name
+    /// is generated like the name of a local variable, so it cannot clash with other variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The name of the variable in the program.
+    /// * `unique_name` - The unique name the semantic analysis gave the declaration.
+    /// * `variable_type` - The declared type of the variable.
+    /// * `initializer` - The constant initializer of the variable, if any.
+    /// * `expressions` - The arena holding the expressions of the initializer.
+    fn convert_static_local(
+        &mut self,
+        identifier: &Symbol,
+        unique_name: Symbol,
+        variable_type: &CmmType,
+        initializer: Option<&CmmInitializer>,
+        expressions: &ExpressionArena,
+    ) {
+        let variable_name = self.interner.borrow_mut().intern(&format!(
+            "{}{}{}",
+            identifier, GENERATED_NAME_SEPARATOR, self.temp_counter
+        ));
+        self.temp_counter += 1;
+        let mut initial_values = Vec::new();
+        match initializer {
+            Some(initializer) => self.convert_static_initializer(
+                expressions,
+                initializer,
+                variable_type,
+                &mut initial_values,
+            ),
+            None => initial_values.push(TackyStaticInit::Zero(self.types.size(variable_type))),
+        }
+        self.static_variables.push(TackyStaticVariable::Variable {
+            identifier: variable_name.clone(),
+            global: false,
+            alignment: self.types.alignment(variable_type),
+            initial_values,
+        });
+        self.static_types
+            .insert(variable_name.clone(), variable_type.clone());
+        self.variable_names.insert(unique_name, variable_name);
+    }
+
+    /// Emits the stores of an initializer of an array, or of one of its elements.
+    ///
+    /// The elements of nested arrays are laid out one after the other, so every scalar of the
+    /// array has an index counting from the first one. The scalars without an initializer are set
+    /// to zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the expressions of the initializer.
+    /// * `initializer` - The initializer of the array or the element.
+    /// * `target_type` - The type of the array or the element.
+    /// * `first_scalar` - The pointer to the first scalar of the whole array.
+    /// * `index` - The index of the first scalar of the array or the element.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the generated instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `()` on success, or an `IRConversionError` on failure.
+    fn emit_initializer(
+        &mut self,
+        expressions: &ExpressionArena,
+        initializer: &CmmInitializer,
+        target_type: &CmmType,
+        first_scalar: &TackyValue,
+        index: usize,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<(), IRConversionError> {
+        match (initializer, target_type) {
+            (CmmInitializer::Compound(initializers), CmmType::Array(element_type, size)) => {
+                let element_scalars = element_type.size() / target_type.scalar_type().size();
+                for (element, initializer) in initializers.iter().enumerate() {
+                    self.emit_initializer(
+                        expressions,
+                        initializer,
+                        element_type,
+                        first_scalar,
+                        index + element * element_scalars,
+                        tacky_instructions,
+                    )?;
+                }
+                let zero = TackyValue::Constant(TackyConstant::Int(0));
+                let zero = self.convert_assigned_value(
+                    zero,
+                    target_type.scalar_type(),
+                    tacky_instructions,
+                );
+                let first_zero = index + initializers.len() * element_scalars;
+                for zero_index in first_zero..index + size * element_scalars {
+                    self.emit_scalar_store(
+                        first_scalar,
+                        zero_index,
+                        zero.clone(),
+                        tacky_instructions,
+                    );
+                }
+                Ok(())
             }
+            (CmmInitializer::Single(expression), scalar_type)
+                if !matches!(scalar_type, CmmType::Array(_, _)) =>
+            {
+                let value = self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                let value = self.convert_assigned_value(value, scalar_type, tacky_instructions);
+                self.emit_scalar_store(first_scalar, index, value, tacky_instructions);
+                Ok(())
+            }
+            _ => unreachable!("The semantic analysis rejects initializers not matching the type"),
         }
     }
 
-    /// Generates a unique name for a temporary TACKY variable.
-    ///
-    /// Side effect: increments the temporary variable counter.
-    ///
-    /// # Returns
-    ///
-    /// A `String` containing the generated temporary variable name.
-    fn make_temporary(&mut self) -> String {
-        let temp_name = format!("tmp.{}", self.temp_counter);
-        self.temp_counter += 1;
-        temp_name
+    /// Emits the store of a value into a scalar of an array, see `emit_initializer`.
+    fn emit_scalar_store(
+        &mut self,
+        first_scalar: &TackyValue,
+        index: usize,
+        value: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) {
+        let scalar = if index == 0 {
+            first_scalar.clone()
+        } else {
+            let scalar_type = self
+                .referenced_type(first_scalar)
+                .cloned()
+                .expect("The first scalar of an array is addressed by a pointer");
+            let scale = scalar_type.size() as i64;
+            let scalar = TackyValue::Variable(
+                self.make_typed_temporary(&CmmType::Pointer(Box::new(scalar_type))),
+            );
+            tacky_instructions.push(TackyInstruction::AddPtr {
+                pointer: first_scalar.clone(),
+                index: TackyValue::Constant(TackyConstant::Long(index as i64)),
+                scale,
+                destination: scalar.clone(),
+            });
+            scalar
+        };
+        tacky_instructions.push(TackyInstruction::Store {
+            source: value,
+            destination_pointer: scalar,
+        });
+    }
+
+    /// Converts a C-- expression into a TACKY value.
+    ///
+    /// Recursively calls itself to convert nested expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the expression and its operands.
+    /// * `cmm_expression` - The `ExprId` of the C-- expression to convert.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the generated instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated `TackyValue` on success,
+    /// or a `CodegenError` on failure.
+    fn emit_tacky(
+        &mut self,
+        expressions: &ExpressionArena,
+        cmm_expression: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<TackyValue, IRConversionError> {
+        // Instructions the enclosing expression emitted before this operand belong to it.
+        self.record_origins(tacky_instructions.len());
+        let enclosing_expression = self.current_expression.replace(cmm_expression);
+        let tacky_value = self.emit_expression(expressions, cmm_expression, tacky_instructions);
+        self.record_origins(tacky_instructions.len());
+        self.current_expression = enclosing_expression;
+        tacky_value
+    }
+
+    /// Emits the instructions of a single C-- expression, see `emit_tacky`.
+    fn emit_expression(
+        &mut self,
+        expressions: &ExpressionArena,
+        cmm_expression: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<TackyValue, IRConversionError> {
+        match &expressions[cmm_expression] {
+            CmmExpression::IntegerConstant { value } => {
+                Ok(TackyValue::Constant(TackyConstant::Int(*value)))
+            }
+            CmmExpression::LongConstant { value } => {
+                Ok(TackyValue::Constant(TackyConstant::Long(*value)))
+            }
+            CmmExpression::UnsignedIntegerConstant { value } => {
+                Ok(TackyValue::Constant(TackyConstant::UnsignedInt(*value)))
+            }
+            CmmExpression::UnsignedLongConstant { value } => {
+                Ok(TackyValue::Constant(TackyConstant::UnsignedLong(*value)))
+            }
+            CmmExpression::DoubleConstant { value } => {
+                Ok(TackyValue::Constant(TackyConstant::Double(*value)))
+            }
+            CmmExpression::StringLiteral { value } => {
+                let source = self.make_static_string(value.clone());
+                let destination = TackyValue::Variable(
+                    self.make_typed_temporary(&CmmType::Pointer(Box::new(CmmType::Char))),
+                );
+                tacky_instructions.push(TackyInstruction::GetAddress {
+                    source,
+                    destination: destination.clone(),
+                });
+                Ok(destination)
+            }
+            CmmExpression::Variable { identifier } => match self.resolve_variable(cmm_expression) {
+                Some(variable) => {
+                    let lvalue = self.variable_lvalue(variable, tacky_instructions);
+                    Ok(self.emit_lvalue_load(&lvalue, tacky_instructions))
+                }
+                // An enumerator is an `int` constant, unless a variable of the same name hides it.
+                None => Ok(TackyValue::Constant(TackyConstant::Int(
+                    self.annotations
+                        .enumerator_value(identifier)
+                        .expect("The semantic analysis resolves every identifier"),
+                ))),
+            },
+            CmmExpression::FunctionCall {
+                identifier,
+                arguments,
+            } => {
+                let FunctionType {
+                    return_type,
+                    parameter_types,
+                    variadic,
+                } = self
+                    .annotations
+                    .function_type(identifier)
+                    .cloned()
+                    .expect("The semantic analysis rejects calls of undeclared functions");
+                let mut tacky_arguments = Vec::with_capacity(arguments.len());
+                for (index, argument) in arguments.iter().enumerate() {
+                    let value = self.emit_tacky(expressions, *argument, tacky_instructions)?;
+                    // The further arguments of a variadic function are only promoted, and may be
+                    // pointers, such as the format string of `printf`.
+                    let value = match parameter_types.get(index) {
+                        Some(parameter_type) => {
+                            self.convert_assigned_value(value, parameter_type, tacky_instructions)
+                        }
+                        None => {
+                            let promoted_type = self.value_type(&value).promoted();
+                            self.convert_value(value, promoted_type, tacky_instructions)
+                        }
+                    };
+                    tacky_arguments.push(value);
+                }
+                // The value of a discarded call to a `void` function is never read.
+                let result_type = return_type.unwrap_or(CmmType::Int);
+                let destination = TackyValue::Variable(self.make_typed_temporary(&result_type));
+                tacky_instructions.push(TackyInstruction::FunCall {
+                    identifier: identifier.clone(),
+                    arguments: tacky_arguments,
+                    named_arguments: variadic.then_some(parameter_types.len()),
+                    destination: destination.clone(),
+                });
+                Ok(destination)
+            }
+            CmmExpression::Cast {
+                target_type,
+                expression,
+            } => {
+                let source = self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                Ok(self.convert_cast_value(source, target_type, tacky_instructions))
+            }
+            // The address of a dereferenced object, an element, or a member is the pointer the
+            // operand evaluates.
+            CmmExpression::AddressOf { expression } => {
+                let lvalue = self
+                    .emit_object(expressions, *expression, tacky_instructions)?
+                    .expect("The semantic analysis rejects addresses of values");
+                Ok(self.emit_object_address(lvalue, tacky_instructions))
+            }
+            CmmExpression::Dereference { expression } => {
+                let lvalue = self.emit_dereference(expressions, *expression, tacky_instructions)?;
+                Ok(self.emit_lvalue_load(&lvalue, tacky_instructions))
+            }
+            CmmExpression::Subscript { array, index } => {
+                let lvalue =
+                    self.emit_subscript(expressions, *array, *index, tacky_instructions)?;
+                Ok(self.emit_lvalue_load(&lvalue, tacky_instructions))
+            }
+            CmmExpression::Member { .. } | CmmExpression::PointerMember { .. } => {
+                let lvalue = self
+                    .emit_object(expressions, cmm_expression, tacky_instructions)?
+                    .expect("A member access designates a member");
+                Ok(self.emit_lvalue_load(&lvalue, tacky_instructions))
+            }
+            CmmExpression::Assignment { target, value } => {
+                let lvalue = self.emit_lvalue(expressions, *target, tacky_instructions)?;
+                let source = self.emit_tacky(expressions, *value, tacky_instructions)?;
+                let target_type = self.lvalue_type(&lvalue);
+                let source = self.convert_assigned_value(source, &target_type, tacky_instructions);
+                Ok(self.emit_lvalue_store(&lvalue, source, tacky_instructions))
+            }
+            CmmExpression::CompoundAssignment {
+                operator,
+                target,
+                value,
+            } => {
+                let lvalue = self.emit_lvalue(expressions, *target, tacky_instructions)?;
+                let destination = self.emit_lvalue_load(&lvalue, tacky_instructions);
+                let source2 = self.emit_tacky(expressions, *value, tacky_instructions)?;
+                let operator = self.convert_binary_operator(operator)?;
+                if let Some(result) = self.emit_pointer_arithmetic(
+                    &operator,
+                    destination.clone(),
+                    source2.clone(),
+                    tacky_instructions,
+                ) {
+                    let target_type = self.lvalue_type(&lvalue);
+                    let result =
+                        self.convert_assigned_value(result, &target_type, tacky_instructions);
+                    return Ok(self.emit_lvalue_store(&lvalue, result, tacky_instructions));
+                }
+                let (source1, source2, result_type) = self.convert_operands(
+                    &operator,
+                    destination.clone(),
+                    source2,
+                    tacky_instructions,
+                );
+                self.emit_binary_assignment(
+                    operator,
+                    (source1, source2, result_type),
+                    destination.clone(),
+                    tacky_instructions,
+                );
+                Ok(self.emit_lvalue_store(&lvalue, destination, tacky_instructions))
+            }
+            CmmExpression::Unary {
+                operator:
+                    operator @ (CmmUnaryOperator::PostfixIncrement | CmmUnaryOperator::PostfixDecrement),
+                expression,
+            } => {
+                let lvalue = self.emit_lvalue(expressions, *expression, tacky_instructions)?;
+                let operator = match operator {
+                    CmmUnaryOperator::PostfixIncrement => TackyBinaryOperator::Add,
+                    _ => TackyBinaryOperator::Subtract,
+                };
+                let variable = self.emit_lvalue_load(&lvalue, tacky_instructions);
+                if self.referenced_type(&variable).is_some() {
+                    let old_value =
+                        TackyValue::Variable(self.make_typed_temporary(&self.lvalue_type(&lvalue)));
+                    tacky_instructions.push(TackyInstruction::Copy {
+                        source: variable,
+                        destination: old_value.clone(),
+                    });
+                    let new_value = self.emit_pointer_addition(
+                        old_value.clone(),
+                        TackyValue::Constant(TackyConstant::Long(1)),
+                        operator == TackyBinaryOperator::Subtract,
+                        tacky_instructions,
+                    );
+                    self.emit_lvalue_store(&lvalue, new_value, tacky_instructions);
+                    return Ok(old_value);
+                }
+                let variable_type = self.value_type(&variable);
+                // The old value is saved before the variable changes, and is the value of the expression.
+                let old_value = TackyValue::Variable(self.make_temporary(variable_type));
+                tacky_instructions.push(TackyInstruction::Copy {
+                    source: variable.clone(),
+                    destination: old_value.clone(),
+                });
+                let operand_type = variable_type.promoted();
+                let source1 =
+                    self.convert_value(old_value.clone(), operand_type, tacky_instructions);
+                let source2 = TackyValue::Constant(TackyConstant::Int(1).convert_to(operand_type));
+                self.emit_binary_assignment(
+                    operator,
+                    (source1, source2, operand_type),
+                    variable.clone(),
+                    tacky_instructions,
+                );
+                self.emit_lvalue_store(&lvalue, variable, tacky_instructions);
+                Ok(old_value)
+            }
+            CmmExpression::Unary {
+                operator:
+                    operator @ (CmmUnaryOperator::PrefixIncrement | CmmUnaryOperator::PrefixDecrement),
+                expression,
+            } => {
+                let lvalue = self.emit_lvalue(expressions, *expression, tacky_instructions)?;
+                let operator = match operator {
+                    CmmUnaryOperator::PrefixIncrement => TackyBinaryOperator::Add,
+                    _ => TackyBinaryOperator::Subtract,
+                };
+                let variable = self.emit_lvalue_load(&lvalue, tacky_instructions);
+                if self.referenced_type(&variable).is_some() {
+                    let new_value = self.emit_pointer_addition(
+                        variable,
+                        TackyValue::Constant(TackyConstant::Long(1)),
+                        operator == TackyBinaryOperator::Subtract,
+                        tacky_instructions,
+                    );
+                    return Ok(self.emit_lvalue_store(&lvalue, new_value, tacky_instructions));
+                }
+                let operand_type = self.value_type(&variable).promoted();
+                let source1 =
+                    self.convert_value(variable.clone(), operand_type, tacky_instructions);
+                let destination = TackyValue::Variable(self.make_temporary(operand_type));
+                tacky_instructions.push(TackyInstruction::Binary {
+                    operator,
+                    source1,
+                    source2: TackyValue::Constant(TackyConstant::Int(1).convert_to(operand_type)),
+                    destination: destination.clone(),
+                });
+                self.emit_conversion(destination, variable.clone(), tacky_instructions);
+                Ok(self.emit_lvalue_store(&lvalue, variable, tacky_instructions))
+            }
+            CmmExpression::Unary {
+                operator: CmmUnaryOperator::Plus,
+                expression,
+            } => {
+                // Unary plus only promotes its operand.
+                let source = self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                let source_type = self.value_type(&source).promoted();
+                Ok(self.convert_value(source, source_type, tacky_instructions))
+            }
+            CmmExpression::Unary {
+                operator,
+                expression,
+            } => {
+                let source = self.emit_tacky(expressions, *expression, tacky_instructions)?;
+                let operator = self.convert_unary_operator(operator);
+                // Logical negation tests its operand like a condition, so it also accepts pointers.
+                let source_type = match operator {
+                    TackyUnaryOperator::Not => self.value_type(&source),
+                    TackyUnaryOperator::Complement | TackyUnaryOperator::Negate => {
+                        self.value_type(&source).promoted()
+                    }
+                };
+                let source = self.convert_value(source, source_type, tacky_instructions);
+                // Logical negation yields an int, the other operators keep the promoted type of the
+                // operand.
+                let destination_type = match operator {
+                    TackyUnaryOperator::Not => TackyType::Int,
+                    _ => source_type,
+                };
+                let destination_name = self.make_temporary(destination_type);
+                let destination = TackyValue::Variable(destination_name);
+                tacky_instructions.push(TackyInstruction::Unary {
+                    operator,
+                    source,
+                    destination: destination.clone(),
+                });
+                Ok(destination)
+            }
+            CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            } => match operator {
+                CmmBinaryOperator::And => {
+                    let label_false_name = self.make_label("and_false");
+                    let label_end_name = self.make_label("and_end");
+
+                    // First condition
+                    let source1 = self.emit_tacky(expressions, *left, tacky_instructions)?;
+                    let jump_false1 = TackyInstruction::JumpIfZero {
+                        condition: source1,
+                        target: label_false_name.clone(),
+                    };
+                    tacky_instructions.push(jump_false1);
+
+                    // Second condition, unless first condition is zero
+                    let source2 = self.emit_tacky(expressions, *right, tacky_instructions)?;
+                    let jump_false2 = TackyInstruction::JumpIfZero {
+                        condition: source2,
+                        target: label_false_name.clone(),
+                    };
+                    tacky_instructions.push(jump_false2);
+
+                    let destination_name = self.make_temporary(TackyType::Int);
+
+                    // Return value if both conditions are non-zero
+                    let copy_true = TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(1)),
+                        destination: TackyValue::Variable(destination_name.clone()),
+                    };
+                    let jump_end = TackyInstruction::Jump {
+                        target: label_end_name.clone(),
+                    };
+
+                    // Return value if any condition is zero
+                    let label_false = TackyInstruction::Label(label_false_name);
+                    let copy_false = TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(0)),
+                        destination: TackyValue::Variable(destination_name.clone()),
+                    };
+                    let label_end = TackyInstruction::Label(label_end_name);
+                    tacky_instructions.push(copy_true);
+                    tacky_instructions.push(jump_end);
+                    tacky_instructions.push(label_false);
+                    tacky_instructions.push(copy_false);
+                    tacky_instructions.push(label_end);
+
+                    Ok(TackyValue::Variable(destination_name))
+                }
+                CmmBinaryOperator::Or => {
+                    let label_true_name = self.make_label("or_true");
+                    let label_end_name = self.make_label("or_end");
+
+                    // First condition
+                    let source1 = self.emit_tacky(expressions, *left, tacky_instructions)?;
+                    let jump_true1 = TackyInstruction::JumpIfNotZero {
+                        condition: source1,
+                        target: label_true_name.clone(),
+                    };
+                    tacky_instructions.push(jump_true1);
+
+                    // Second condition, unless first condition is not zero
+                    let source2 = self.emit_tacky(expressions, *right, tacky_instructions)?;
+                    let jump_true2 = TackyInstruction::JumpIfNotZero {
+                        condition: source2,
+                        target: label_true_name.clone(),
+                    };
+                    tacky_instructions.push(jump_true2);
+
+                    let destination_name = self.make_temporary(TackyType::Int);
+
+                    // Return value if both conditions are zero
+                    let copy_false = TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(0)),
+                        destination: TackyValue::Variable(destination_name.clone()),
+                    };
+                    let jump_end = TackyInstruction::Jump {
+                        target: label_end_name.clone(),
+                    };
+
+                    // Return value if any condition is non-zero
+                    let label_true = TackyInstruction::Label(label_true_name);
+                    let copy_true = TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(1)),
+                        destination: TackyValue::Variable(destination_name.clone()),
+                    };
+                    let label_end = TackyInstruction::Label(label_end_name);
+                    tacky_instructions.push(copy_false);
+                    tacky_instructions.push(jump_end);
+                    tacky_instructions.push(label_true);
+                    tacky_instructions.push(copy_true);
+                    tacky_instructions.push(label_end);
+
+                    Ok(TackyValue::Variable(destination_name))
+                }
+                CmmBinaryOperator::Equal
+                | CmmBinaryOperator::NotEqual
+                | CmmBinaryOperator::GreaterThan
+                | CmmBinaryOperator::LessThan
+                | CmmBinaryOperator::GreaterThanEqual
+                | CmmBinaryOperator::LessThanEqual
+                | CmmBinaryOperator::Add
+                | CmmBinaryOperator::Subtract
+                | CmmBinaryOperator::Multiply
+                | CmmBinaryOperator::Divide
+                | CmmBinaryOperator::Remainder
+                | CmmBinaryOperator::BitwiseAnd
+                | CmmBinaryOperator::BitwiseOr
+                | CmmBinaryOperator::BitwiseXor
+                | CmmBinaryOperator::LeftShift
+                | CmmBinaryOperator::RightShift => {
+                    let source1 = self.emit_tacky(expressions, *left, tacky_instructions)?;
+                    let source2 = self.emit_tacky(expressions, *right, tacky_instructions)?;
+                    let operator = self.convert_binary_operator(operator)?;
+                    if let Some(result) = self.emit_pointer_arithmetic(
+                        &operator,
+                        source1.clone(),
+                        source2.clone(),
+                        tacky_instructions,
+                    ) {
+                        return Ok(result);
+                    }
+                    let (source1, source2, destination_type) =
+                        self.convert_operands(&operator, source1, source2, tacky_instructions);
+                    let destination_name = self.make_temporary(destination_type);
+                    let destination = TackyValue::Variable(destination_name);
+                    tacky_instructions.push(TackyInstruction::Binary {
+                        operator,
+                        source1,
+                        source2,
+                        destination: destination.clone(),
+                    });
+                    Ok(destination)
+                }
+            },
+        }
+    }
+
+    /// Converts a C-- unary operator into a TACKY unary operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmm_operator` - A reference to the C-- `CmmUnaryOperator` to convert.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated `TackyUnaryOperator` on success,
+    /// or a `CodegenError` on failure.
+    fn convert_unary_operator(&self, cmm_operator: &CmmUnaryOperator) -> TackyUnaryOperator {
+        match cmm_operator {
+            CmmUnaryOperator::Complement => TackyUnaryOperator::Complement,
+            CmmUnaryOperator::Negate => TackyUnaryOperator::Negate,
+            CmmUnaryOperator::Not => TackyUnaryOperator::Not,
+            CmmUnaryOperator::Plus => {
+                unreachable!("Unary plus is lowered into a conversion by emit_expression")
+            }
+            CmmUnaryOperator::PrefixIncrement
+            | CmmUnaryOperator::PrefixDecrement
+            | CmmUnaryOperator::PostfixIncrement
+            | CmmUnaryOperator::PostfixDecrement => unreachable!(
+                "Increments and decrements are lowered into binary operations by emit_expression"
+            ),
+        }
+    }
+
+    /// Converts a C-- binary operator into a TACKY binary operator.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmm_operator` - A reference to the C-- `CmmBinaryOperator` to convert.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the generated `TackyBinaryOperator` on success,
+    /// or a `CodegenError` on failure.
+    fn convert_binary_operator(
+        &self,
+        cmm_operator: &CmmBinaryOperator,
+    ) -> Result<TackyBinaryOperator, IRConversionError> {
+        match cmm_operator {
+            CmmBinaryOperator::Add => Ok(TackyBinaryOperator::Add),
+            CmmBinaryOperator::Subtract => Ok(TackyBinaryOperator::Subtract),
+            CmmBinaryOperator::Multiply => Ok(TackyBinaryOperator::Multiply),
+            CmmBinaryOperator::Divide => Ok(TackyBinaryOperator::Divide),
+            CmmBinaryOperator::Remainder => Ok(TackyBinaryOperator::Remainder),
+            CmmBinaryOperator::BitwiseAnd => Ok(TackyBinaryOperator::BitwiseAnd),
+            CmmBinaryOperator::BitwiseOr => Ok(TackyBinaryOperator::BitwiseOr),
+            CmmBinaryOperator::BitwiseXor => Ok(TackyBinaryOperator::BitwiseXor),
+            CmmBinaryOperator::LeftShift => Ok(TackyBinaryOperator::LeftShift),
+            CmmBinaryOperator::RightShift => Ok(TackyBinaryOperator::RightShift),
+            CmmBinaryOperator::Equal => Ok(TackyBinaryOperator::Equal),
+            CmmBinaryOperator::NotEqual => Ok(TackyBinaryOperator::NotEqual),
+            CmmBinaryOperator::GreaterThan => Ok(TackyBinaryOperator::GreaterThan),
+            CmmBinaryOperator::LessThan => Ok(TackyBinaryOperator::LessThan),
+            CmmBinaryOperator::GreaterThanEqual => Ok(TackyBinaryOperator::GreaterThanEqual),
+            CmmBinaryOperator::LessThanEqual => Ok(TackyBinaryOperator::LessThanEqual),
+            CmmBinaryOperator::And | CmmBinaryOperator::Or => {
+                Err(IRConversionError::UnsupportedBinaryOperatorConversion {
+                    operator: cmm_operator.clone(),
+                })
+            }
+        }
+    }
+
+    /// Attributes the instructions a statement emitted since the last call to one of its expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The expression of the statement the instructions originate from, if any.
+    /// * `instruction_count`: The number of instructions emitted so far.
+    fn record_statement_origins(&mut self, expression: Option<ExprId>, instruction_count: usize) {
+        self.current_expression = expression;
+        self.record_origins(instruction_count);
+        self.current_expression = None;
+    }
+
+    /// Attributes the instructions emitted since the last call to the current expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `instruction_count`: The number of instructions of the current function emitted so far.
+    fn record_origins(&mut self, instruction_count: usize) {
+        if let Some(origins) = &mut self.instruction_origins {
+            origins.resize(
+                self.origins_offset + instruction_count,
+                self.current_expression,
+            );
+        }
+    }
+
+    /// Converts the operands of a binary operator to the type the operation happens in.
+    ///
+    /// The operands of arithmetic, bitwise, and comparison operators are converted to their common
+    /// type. The operands of a shift are only promoted, since its result has the promoted type of
+    /// the left operand.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator`: The TACKY binary operator applied to the operands.
+    /// * `source1`: The left operand.
+    /// * `source2`: The right operand.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the conversions to.
+    ///
+    /// # Returns
+    ///
+    /// The converted operands and the type of the result, which is `Int` for comparisons.
+    fn convert_operands(
+        &mut self,
+        operator: &TackyBinaryOperator,
+        source1: TackyValue,
+        source2: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> (TackyValue, TackyValue, TackyType) {
+        let is_comparison = matches!(
+            operator,
+            TackyBinaryOperator::Equal
+                | TackyBinaryOperator::NotEqual
+                | TackyBinaryOperator::LessThan
+                | TackyBinaryOperator::GreaterThan
+                | TackyBinaryOperator::LessThanEqual
+                | TackyBinaryOperator::GreaterThanEqual
+        );
+        if is_comparison
+            && (self.value_type(&source1) == TackyType::Pointer
+                || self.value_type(&source2) == TackyType::Pointer)
+        {
+            return self.convert_pointer_operands(source1, source2);
+        }
+        let (source1_type, source2_type) = (self.value_type(&source1), self.value_type(&source2));
+        match operator {
+            TackyBinaryOperator::LeftShift | TackyBinaryOperator::RightShift => {
+                let result_type = source1_type.promoted();
+                let source1 = self.convert_value(source1, result_type, tacky_instructions);
+                let source2 =
+                    self.convert_value(source2, source2_type.promoted(), tacky_instructions);
+                (source1, source2, result_type)
+            }
+            _ => {
+                let common_type = source1_type.common_type(source2_type);
+                let source1 = self.convert_value(source1, common_type, tacky_instructions);
+                let source2 = self.convert_value(source2, common_type, tacky_instructions);
+                let result_type = match operator {
+                    TackyBinaryOperator::Equal
+                    | TackyBinaryOperator::NotEqual
+                    | TackyBinaryOperator::LessThan
+                    | TackyBinaryOperator::GreaterThan
+                    | TackyBinaryOperator::LessThanEqual
+                    | TackyBinaryOperator::GreaterThanEqual => TackyType::Int,
+                    _ => common_type,
+                };
+                (source1, source2, result_type)
+            }
+        }
+    }
+
+    /// Converts the operands of a comparison of pointers.
+    ///
+    /// Pointers to the same type are compared as they are, and a pointer compared with the null
+    /// pointer constant is compared with a `long` zero, the size of a pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `source1`: The left operand.
+    /// * `source2`: The right operand.
+    ///
+    /// # Returns
+    ///
+    /// The operands and the `Int` type of the result.
+    fn convert_pointer_operands(
+        &self,
+        source1: TackyValue,
+        source2: TackyValue,
+    ) -> (TackyValue, TackyValue, TackyType) {
+        let null_pointer = TackyValue::Constant(TackyConstant::Long(0));
+        match (
+            self.referenced_type(&source1),
+            self.referenced_type(&source2),
+        ) {
+            (Some(_), None) => (source1, null_pointer, TackyType::Int),
+            (None, Some(_)) => (null_pointer, source2, TackyType::Int),
+            _ => (source1, source2, TackyType::Int),
+        }
+    }
+
+    /// Emits the pointer arithmetic of an addition or a subtraction with a pointer operand.
+    ///
+    /// Adding an integer to a pointer, in either order, or subtracting an integer from a pointer
+    /// moves the pointer by that many objects of the type it points to. Subtracting two pointers to
+    /// the same type yields the number of objects between them as a `long`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator`: The TACKY binary operator applied to the operands.
+    /// * `source1`: The left operand.
+    /// * `source2`: The right operand.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the arithmetic to.
+    ///
+    /// # Returns
+    ///
+    /// The result, or `None` if the operation is no pointer arithmetic.
+    fn emit_pointer_arithmetic(
+        &mut self,
+        operator: &TackyBinaryOperator,
+        source1: TackyValue,
+        source2: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Option<TackyValue> {
+        let result = match (
+            operator,
+            self.referenced_type(&source1).cloned(),
+            self.referenced_type(&source2).cloned(),
+        ) {
+            (TackyBinaryOperator::Add, Some(_), None) => {
+                self.emit_pointer_addition(source1, source2, false, tacky_instructions)
+            }
+            (TackyBinaryOperator::Add, None, Some(_)) => {
+                self.emit_pointer_addition(source2, source1, false, tacky_instructions)
+            }
+            (TackyBinaryOperator::Subtract, Some(_), None) => {
+                self.emit_pointer_addition(source1, source2, true, tacky_instructions)
+            }
+            (TackyBinaryOperator::Subtract, Some(referenced_type), Some(_)) => {
+                // The distance in bytes is a multiple of the size, so the division is exact.
+                let difference = TackyValue::Variable(self.make_temporary(TackyType::Long));
+                tacky_instructions.push(TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Subtract,
+                    source1,
+                    source2,
+                    destination: difference.clone(),
+                });
+                let destination = TackyValue::Variable(self.make_temporary(TackyType::Long));
+                tacky_instructions.push(TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Divide,
+                    source1: difference,
+                    source2: TackyValue::Constant(TackyConstant::Long(
+                        self.types.size(&referenced_type) as i64,
+                    )),
+                    destination: destination.clone(),
+                });
+                destination
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+
+    /// Emits the addition of an integer index to a pointer, which is scaled by the size of the
+    /// type the pointer points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `pointer`: The pointer to move.
+    /// * `index`: The number of objects to move the pointer by, converted to a `long`.
+    /// * `negate`: Whether the pointer moves backwards, as in `p - 1`.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the addition to.
+    ///
+    /// # Returns
+    ///
+    /// A pointer of the same type as `pointer`.
+    fn emit_pointer_addition(
+        &mut self,
+        pointer: TackyValue,
+        index: TackyValue,
+        negate: bool,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        let referenced_type = self
+            .referenced_type(&pointer)
+            .cloned()
+            .expect("Only a pointer is moved by an index");
+        let index = self.convert_value(index, TackyType::Long, tacky_instructions);
+        let index = match index {
+            TackyValue::Constant(constant) if negate => {
+                TackyValue::Constant(TackyConstant::Long(constant.as_i64().wrapping_neg()))
+            }
+            TackyValue::Variable(_) if negate => {
+                let negated_index = TackyValue::Variable(self.make_temporary(TackyType::Long));
+                tacky_instructions.push(TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Negate,
+                    source: index,
+                    destination: negated_index.clone(),
+                });
+                negated_index
+            }
+            index => index,
+        };
+        let scale = self.types.size(&referenced_type) as i64;
+        let destination = TackyValue::Variable(
+            self.make_typed_temporary(&CmmType::Pointer(Box::new(referenced_type))),
+        );
+        tacky_instructions.push(TackyInstruction::AddPtr {
+            pointer,
+            index,
+            scale,
+            destination: destination.clone(),
+        });
+        destination
+    }
+
+    /// Emits a binary operation storing its result into a variable.
+    ///
+    /// The operation happens in the type of its converted operands, and if the variable has another
+    /// type, the result is converted to the type of the variable through a temporary.
+    ///
+    /// # Arguments
+    ///
+    /// * `operator`: The TACKY binary operator to apply.
+    /// * `operands`: The converted operands and the type of the result, see `convert_operands`.
+    /// * `destination`: The variable the result is stored in.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the operation to.
+    fn emit_binary_assignment(
+        &mut self,
+        operator: TackyBinaryOperator,
+        (source1, source2, result_type): (TackyValue, TackyValue, TackyType),
+        destination: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) {
+        if result_type == self.value_type(&destination) {
+            tacky_instructions.push(TackyInstruction::Binary {
+                operator,
+                source1,
+                source2,
+                destination,
+            });
+        } else {
+            let result = TackyValue::Variable(self.make_temporary(result_type));
+            tacky_instructions.push(TackyInstruction::Binary {
+                operator,
+                source1,
+                source2,
+                destination: result.clone(),
+            });
+            self.emit_conversion(result, destination, tacky_instructions);
+        }
+    }
+
+    /// Converts a value to the type of the variable, parameter, or object it is assigned to.
+    ///
+    /// Arithmetic values are converted like with `convert_value`, and a `_Bool` is assigned
+    /// whether an arithmetic value or a pointer is nonzero. A pointer is assigned a pointer to the
+    /// same type as it is, or the null pointer constant, which is converted to a `long` zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The assigned value.
+    /// * `target_type`: The C-- type of the assignment target.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the conversion to.
+    ///
+    /// # Returns
+    ///
+    /// A `TackyValue` of the target type.
+    fn convert_assigned_value(
+        &mut self,
+        value: TackyValue,
+        target_type: &CmmType,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        match target_type.unqualified() {
+            CmmType::Pointer(_) if self.referenced_type(&value).is_some() => value,
+            // The semantic analysis only converts the null pointer constant to a pointer.
+            CmmType::Pointer(_) => TackyValue::Constant(TackyConstant::Long(0)),
+            CmmType::Array(_, _) => {
+                unreachable!("The semantic analysis rejects assignments to arrays")
+            }
+            CmmType::Bool => self.convert_to_bool(value, tacky_instructions),
+            _ => self.convert_value(
+                value,
+                TackyType::from(target_type.clone()),
+                tacky_instructions,
+            ),
+        }
+    }
+
+    /// Converts a value to the target type of a cast.
+    ///
+    /// Unlike an assignment, a cast converts a pointer to a pointer to another type, which keeps
+    /// the address. The null pointer constant is the only integer the semantic analysis casts to a
+    /// pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The value to convert.
+    /// * `target_type`: The C-- type of the cast.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the conversion to.
+    ///
+    /// # Returns
+    ///
+    /// A `TackyValue` of the target type.
+    fn convert_cast_value(
+        &mut self,
+        value: TackyValue,
+        target_type: &CmmType,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        // The value of a cast is no object, so a qualifier of the target type has no effect.
+        let target_type = target_type.unqualified();
+        if target_type == &CmmType::Bool {
+            return self.convert_to_bool(value, tacky_instructions);
+        }
+        let CmmType::Pointer(referenced_type) = target_type else {
+            return self.convert_value(
+                value,
+                TackyType::from(target_type.clone()),
+                tacky_instructions,
+            );
+        };
+        let source = match self.referenced_type(&value) {
+            Some(value_referenced_type) if value_referenced_type == referenced_type.as_ref() => {
+                return value;
+            }
+            Some(_) => value,
+            None => TackyValue::Constant(TackyConstant::Long(0)),
+        };
+        let destination = TackyValue::Variable(self.make_typed_temporary(target_type));
+        tacky_instructions.push(TackyInstruction::Copy {
+            source,
+            destination: destination.clone(),
+        });
+        destination
+    }
+
+    /// Converts an arithmetic value or a pointer to `_Bool`, which is 0 if the value compares
+    /// equal to zero and 1 otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The value to convert.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the comparison to.
+    ///
+    /// # Returns
+    ///
+    /// A `TackyValue` of type `_Bool`, which is the value itself if it already is one.
+    fn convert_to_bool(
+        &mut self,
+        value: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        if self.is_bool(&value) {
+            return value;
+        }
+        match value {
+            TackyValue::Constant(constant) => {
+                let is_nonzero = match constant {
+                    TackyConstant::Double(value) => value != 0.0,
+                    _ => constant.as_i64() != 0,
+                };
+                TackyValue::Constant(TackyConstant::UnsignedChar(u8::from(is_nonzero)))
+            }
+            TackyValue::Variable(_) => {
+                let destination = TackyValue::Variable(self.make_typed_temporary(&CmmType::Bool));
+                self.emit_conversion(value, destination.clone(), tacky_instructions);
+                destination
+            }
+        }
+    }
+
+    /// Returns whether a value is a variable or a temporary of type `_Bool`, whose TACKY type is
+    /// `unsigned char`.
+    fn is_bool(&self, value: &TackyValue) -> bool {
+        matches!(value, TackyValue::Variable(name)
+            if self.cmm_types.get(name).map(CmmType::unqualified) == Some(&CmmType::Bool))
+    }
+
+    /// Converts a value to another type.
+    ///
+    /// Constants are converted in place, a variable is converted into a temporary of the target
+    /// type.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The value to convert.
+    /// * `target_type`: The type to convert the value to.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the conversion to.
+    ///
+    /// # Returns
+    ///
+    /// A `TackyValue` of the target type, which is the value itself if it already has the type.
+    fn convert_value(
+        &mut self,
+        value: TackyValue,
+        target_type: TackyType,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        if self.value_type(&value) == target_type {
+            return value;
+        }
+        match value {
+            TackyValue::Constant(constant) => {
+                TackyValue::Constant(constant.convert_to(target_type))
+            }
+            TackyValue::Variable(_) => {
+                let destination = TackyValue::Variable(self.make_temporary(target_type));
+                self.emit_conversion(value, destination.clone(), tacky_instructions);
+                destination
+            }
+        }
+    }
+
+    /// Emits the instructions storing a value converted to the type of the destination.
+    ///
+    /// Between integer types of the same size the bits are copied, a larger type is sign or zero
+    /// extended depending on the signedness of the source, and a smaller type is truncated. Between
+    /// an integer type and `double` the value is converted, rounding toward zero, and `short` or a
+    /// character type is converted through an `int`. A `_Bool` destination is set to whether the source
+    /// compares unequal to zero, so that it only holds 0 or 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: The value to convert.
+    /// * `destination`: The variable the converted value is stored in.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the conversion to.
+    fn emit_conversion(
+        &mut self,
+        source: TackyValue,
+        destination: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) {
+        let source_type = self.value_type(&source);
+        let destination_type = self.value_type(&destination);
+        if self.is_bool(&destination) && !self.is_bool(&source) {
+            let zero = match source_type {
+                TackyType::Pointer => TackyConstant::Long(0),
+                _ => TackyConstant::Int(0).convert_to(source_type),
+            };
+            let is_nonzero = TackyValue::Variable(self.make_temporary(TackyType::Int));
+            tacky_instructions.push(TackyInstruction::Binary {
+                operator: TackyBinaryOperator::NotEqual,
+                source1: source,
+                source2: TackyValue::Constant(zero),
+                destination: is_nonzero.clone(),
+            });
+            tacky_instructions.push(TackyInstruction::Truncate {
+                source: is_nonzero,
+                destination,
+            });
+            return;
+        }
+        let is_double_conversion =
+            (source_type == TackyType::Double) != (destination_type == TackyType::Double);
+        if is_double_conversion && (source_type.size() < 4 || destination_type.size() < 4) {
+            let int_value = TackyValue::Variable(self.make_temporary(TackyType::Int));
+            self.emit_conversion(source, int_value.clone(), tacky_instructions);
+            self.emit_conversion(int_value, destination, tacky_instructions);
+            return;
+        }
+        let instruction = match (source_type, destination_type) {
+            (TackyType::Double, TackyType::Double) => TackyInstruction::Copy {
+                source,
+                destination,
+            },
+            (TackyType::Double, integer_type) if integer_type.is_signed() => {
+                TackyInstruction::DoubleToInt {
+                    source,
+                    destination,
+                }
+            }
+            (TackyType::Double, _) => TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            },
+            (integer_type, TackyType::Double) if integer_type.is_signed() => {
+                TackyInstruction::IntToDouble {
+                    source,
+                    destination,
+                }
+            }
+            (_, TackyType::Double) => TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            },
+            _ if destination_type.size() == source_type.size() => TackyInstruction::Copy {
+                source,
+                destination,
+            },
+            _ if destination_type.size() < source_type.size() => TackyInstruction::Truncate {
+                source,
+                destination,
+            },
+            _ if source_type.is_signed() => TackyInstruction::SignExtend {
+                source,
+                destination,
+            },
+            _ => TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            },
+        };
+        tacky_instructions.push(instruction);
+    }
+
+    /// Returns the type of a constant or of a variable of the function being converted.
+    fn value_type(&self, value: &TackyValue) -> TackyType {
+        value.value_type(&self.variable_types)
+    }
+
+    /// Returns the type a pointer of the function being converted points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: A constant, or a variable or temporary of the function.
+    ///
+    /// # Returns
+    ///
+    /// The referenced `CmmType`, or `None` if the value is not a pointer.
+    fn referenced_type(&self, value: &TackyValue) -> Option<&CmmType> {
+        match value {
+            TackyValue::Variable(name) => {
+                self.cmm_types.get(name).and_then(CmmType::referenced_type)
+            }
+            TackyValue::Constant(_) => None,
+        }
+    }
+
+    /// Converts an expression designating storage into an `Lvalue` that can be assigned,
+    /// evaluating the pointer of a dereference.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the expression and its operands.
+    /// * `expression` - The `ExprId` of the expression, which must designate storage, see
+    ///   `emit_object`, and must not designate an array or a structure.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the pointer instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Lvalue` of the expression.
+    fn emit_lvalue(
+        &mut self,
+        expressions: &ExpressionArena,
+        expression: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<Lvalue, IRConversionError> {
+        let lvalue = self
+            .emit_object(expressions, expression, tacky_instructions)?
+            .expect("The semantic analysis rejects assignments to values");
+        Ok(lvalue)
+    }
+
+    /// Converts an expression designating storage into an `Lvalue`, evaluating the pointer of a
+    /// dereference, a subscript, or a member access.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the expression and its operands.
+    /// * `expression` - The `ExprId` of the expression.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the pointer instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Lvalue` of the expression, or `None` if the expression is not a
+    /// variable, a dereference, a subscript, or a member access.
+    fn emit_object(
+        &mut self,
+        expressions: &ExpressionArena,
+        expression: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<Option<Lvalue>, IRConversionError> {
+        let lvalue = match &expressions[expression] {
+            CmmExpression::Variable { .. } => match self.resolve_variable(expression) {
+                Some(variable) => self.variable_lvalue(variable, tacky_instructions),
+                // An enumerator is a constant, which designates no storage.
+                None => return Ok(None),
+            },
+            CmmExpression::Dereference { expression } => {
+                self.emit_dereference(expressions, *expression, tacky_instructions)?
+            }
+            CmmExpression::Subscript { array, index } => {
+                self.emit_subscript(expressions, *array, *index, tacky_instructions)?
+            }
+            CmmExpression::Member { structure, member } => {
+                let structure = self
+                    .emit_object(expressions, *structure, tacky_instructions)?
+                    .expect("The semantic analysis only accesses members of structures");
+                let structure = self.emit_object_address(structure, tacky_instructions);
+                self.emit_member(structure, member, tacky_instructions)
+            }
+            CmmExpression::PointerMember { pointer, member } => {
+                let pointer = self.emit_tacky(expressions, *pointer, tacky_instructions)?;
+                self.emit_member(pointer, member, tacky_instructions)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(lvalue))
+    }
+
+    /// Returns the `Lvalue` of a variable. A static variable is stored in the data of the program
+    /// rather than in the stack frame. A scalar one is accessed by its name like a variable of the
+    /// function, which the code generator addresses relative to %rip, and an array or a structure
+    /// through its address.
+    ///
+    /// # Arguments
+    ///
+    /// * `variable` - The TACKY name of the variable.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the address instruction to.
+    ///
+    /// # Returns
+    ///
+    /// The `Lvalue::Variable` of a variable of the function or of a scalar static variable, or the
+    /// `Lvalue::Dereferenced` of an aggregate static variable.
+    fn variable_lvalue(
+        &mut self,
+        variable: Symbol,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Lvalue {
+        let Some(referenced_type) = self.static_types.get(&variable).cloned() else {
+            return Lvalue::Variable(variable);
+        };
+        if !matches!(
+            referenced_type.unqualified(),
+            CmmType::Array(_, _) | CmmType::Struct(_)
+        ) {
+            self.variable_types
+                .insert(variable.clone(), self.types.tacky_type(&referenced_type));
+            self.cmm_types.insert(variable.clone(), referenced_type);
+            return Lvalue::Variable(variable);
+        }
+        let pointer = TackyValue::Variable(
+            self.make_typed_temporary(&CmmType::Pointer(Box::new(referenced_type.clone()))),
+        );
+        tacky_instructions.push(TackyInstruction::GetAddress {
+            source: variable,
+            destination: pointer.clone(),
+        });
+        Lvalue::Dereferenced {
+            pointer,
+            referenced_type,
+        }
+    }
+
+    /// Returns a pointer to the storage an `Lvalue` designates.
+    ///
+    /// # Arguments
+    ///
+    /// * `lvalue` - The `Lvalue` whose address is taken.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the address instruction to.
+    ///
+    /// # Returns
+    ///
+    /// A pointer to the type of the `Lvalue`, which is the pointer of a dereferenced object itself.
+    fn emit_object_address(
+        &mut self,
+        lvalue: Lvalue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        match lvalue {
+            Lvalue::Variable(source) => {
+                let referenced_type = self.cmm_types[&source].clone();
+                let destination = TackyValue::Variable(
+                    self.make_typed_temporary(&CmmType::Pointer(Box::new(referenced_type))),
+                );
+                tacky_instructions.push(TackyInstruction::GetAddress {
+                    source,
+                    destination: destination.clone(),
+                });
+                destination
+            }
+            Lvalue::Dereferenced { pointer, .. } => pointer,
+        }
+    }
+
+    /// Moves a pointer to a structure to one of its members, which `s.x` finds like `(&s)->x`. The
+    /// members of a `const` qualified structure are `const` qualified as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `structure` - The pointer to the structure.
+    /// * `member` - The name of the member.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the pointer instructions to.
+    ///
+    /// # Returns
+    ///
+    /// The `Lvalue::Dereferenced` of the member.
+    fn emit_member(
+        &mut self,
+        structure: TackyValue,
+        member: &Symbol,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Lvalue {
+        let structure_type = self
+            .referenced_type(&structure)
+            .expect("The semantic analysis only accesses members of structures");
+        let is_const = matches!(structure_type, CmmType::Const(_));
+        let CmmType::Struct(tag) = structure_type.unqualified().clone() else {
+            unreachable!("The semantic analysis only accesses members of structures");
+        };
+        let member = self.types.member(&tag, member);
+        let (offset, mut member_type) = (member.offset, member.member_type.clone());
+        if is_const {
+            member_type = member_type.qualified();
+        }
+        let pointer = TackyValue::Variable(
+            self.make_typed_temporary(&CmmType::Pointer(Box::new(member_type.clone()))),
+        );
+        tacky_instructions.push(TackyInstruction::AddPtr {
+            pointer: structure,
+            index: TackyValue::Constant(TackyConstant::Long(offset as i64)),
+            scale: 1,
+            destination: pointer.clone(),
+        });
+        Lvalue::Dereferenced {
+            pointer,
+            referenced_type: member_type,
+        }
+    }
+
+    /// Evaluates the operand of a dereference into the `Lvalue` of the object it points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the operand.
+    /// * `pointer` - The `ExprId` of the dereferenced expression.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the pointer instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Lvalue::Dereferenced` of the object.
+    fn emit_dereference(
+        &mut self,
+        expressions: &ExpressionArena,
+        pointer: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<Lvalue, IRConversionError> {
+        let pointer = self.emit_tacky(expressions, pointer, tacky_instructions)?;
+        let referenced_type = self
+            .referenced_type(&pointer)
+            .cloned()
+            .expect("The semantic analysis only dereferences pointers");
+        Ok(Lvalue::Dereferenced {
+            pointer,
+            referenced_type,
+        })
+    }
+
+    /// Evaluates the operands of a subscript into the `Lvalue` of the element it designates, which
+    /// `a[i]` finds like `*(a + i)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - The arena holding the operands.
+    /// * `array` - The `ExprId` of the expression before the brackets.
+    /// * `index` - The `ExprId` of the expression inside the brackets.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the pointer instructions to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Lvalue::Dereferenced` of the element.
+    fn emit_subscript(
+        &mut self,
+        expressions: &ExpressionArena,
+        array: ExprId,
+        index: ExprId,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> Result<Lvalue, IRConversionError> {
+        let array = self.emit_tacky(expressions, array, tacky_instructions)?;
+        let index = self.emit_tacky(expressions, index, tacky_instructions)?;
+        let (pointer, index) = match (
+            self.referenced_type(&array).is_some(),
+            self.referenced_type(&index).is_some(),
+        ) {
+            (true, false) => (array, index),
+            (false, true) => (index, array),
+            _ => unreachable!("The semantic analysis subscripts a pointer with an integer"),
+        };
+        let pointer = self.emit_pointer_addition(pointer, index, false, tacky_instructions);
+        let referenced_type = self
+            .referenced_type(&pointer)
+            .cloned()
+            .expect("Adding to a pointer yields a pointer");
+        Ok(Lvalue::Dereferenced {
+            pointer,
+            referenced_type,
+        })
+    }
+
+    /// Returns the C-- type of the storage an `Lvalue` designates.
+    fn lvalue_type(&self, lvalue: &Lvalue) -> CmmType {
+        match lvalue {
+            Lvalue::Variable(name) => self.cmm_types[name].clone(),
+            Lvalue::Dereferenced {
+                referenced_type, ..
+            } => referenced_type.clone(),
+        }
+    }
+
+    /// Reads the current value of an `Lvalue`.
+    ///
+    /// A variable is its own value, while a dereferenced object is loaded into a temporary, which
+    /// `emit_lvalue_store` writes back after it changes. An array decays into a pointer to its
+    /// first element instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `lvalue` - The `Lvalue` to read.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the load to.
+    ///
+    /// # Returns
+    ///
+    /// The `TackyValue` holding the current value.
+    fn emit_lvalue_load(
+        &mut self,
+        lvalue: &Lvalue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        match lvalue {
+            Lvalue::Variable(name) => match &self.cmm_types[name] {
+                CmmType::Array(element_type, _) => {
+                    let pointer_type = CmmType::Pointer(element_type.clone());
+                    let destination =
+                        TackyValue::Variable(self.make_typed_temporary(&pointer_type));
+                    tacky_instructions.push(TackyInstruction::GetAddress {
+                        source: name.clone(),
+                        destination: destination.clone(),
+                    });
+                    destination
+                }
+                _ => TackyValue::Variable(name.clone()),
+            },
+            // The pointer to an array already holds the address of its first element.
+            Lvalue::Dereferenced {
+                pointer,
+                referenced_type: CmmType::Array(element_type, _),
+            } => {
+                let pointer_type = CmmType::Pointer(element_type.clone());
+                let destination = TackyValue::Variable(self.make_typed_temporary(&pointer_type));
+                tacky_instructions.push(TackyInstruction::Copy {
+                    source: pointer.clone(),
+                    destination: destination.clone(),
+                });
+                destination
+            }
+            Lvalue::Dereferenced {
+                pointer,
+                referenced_type,
+            } => {
+                let destination = TackyValue::Variable(self.make_typed_temporary(referenced_type));
+                tacky_instructions.push(TackyInstruction::Load {
+                    source_pointer: pointer.clone(),
+                    destination: destination.clone(),
+                });
+                destination
+            }
+        }
+    }
+
+    /// Writes a value of the type of an `Lvalue` to its storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `lvalue` - The `Lvalue` to write.
+    /// * `value` - The value to write, which is already converted to the type of the `Lvalue`.
+    /// * `tacky_instructions` - A mutable reference to the vector of `TackyInstruction`s to append the store to.
+    ///
+    /// # Returns
+    ///
+    /// The written value, which is the value of an assignment expression.
+    fn emit_lvalue_store(
+        &mut self,
+        lvalue: &Lvalue,
+        value: TackyValue,
+        tacky_instructions: &mut Vec<TackyInstruction>,
+    ) -> TackyValue {
+        match lvalue {
+            Lvalue::Variable(name) => {
+                let destination = TackyValue::Variable(name.clone());
+                if value != destination {
+                    tacky_instructions.push(TackyInstruction::Copy {
+                        source: value,
+                        destination: destination.clone(),
+                    });
+                }
+                destination
+            }
+            Lvalue::Dereferenced { pointer, .. } => {
+                tacky_instructions.push(TackyInstruction::Store {
+                    source: value.clone(),
+                    destination_pointer: pointer.clone(),
+                });
+                value
+            }
+        }
+    }
+
+    /// Generates the TACKY name of a variable declaration.
+    ///
+    /// Side effect: increments the temporary variable counter, which keeps a variable named `tmp`
+    /// apart from the temporaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable in the program.
+    /// * `unique_name`: The unique name the semantic analysis gave the declaration.
+    /// * `variable_type`: The declared type of the variable.
+    ///
+    /// # Returns
+    ///
+    /// The TACKY name of the variable, e.g. "x.0".
+    fn make_variable(
+        &mut self,
+        identifier: &Symbol,
+        unique_name: Symbol,
+        variable_type: &CmmType,
+    ) -> Symbol {
+        let tacky_type = self.types.tacky_type(variable_type);
+        let variable_name = self.interner.borrow_mut().intern(&format!(
+            "{}{}{}",
+            identifier, GENERATED_NAME_SEPARATOR, self.temp_counter
+        ));
+        self.temp_counter += 1;
+        self.variable_types
+            .insert(variable_name.clone(), tacky_type);
+        self.cmm_types
+            .insert(variable_name.clone(), variable_type.clone());
+        self.variable_names
+            .insert(unique_name, variable_name.clone());
+        variable_name
+    }
+
+    /// Looks up the TACKY name of the variable a `CmmExpression::Variable` refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The `ExprId` of the variable expression.
+    ///
+    /// # Returns
+    ///
+    /// The TACKY name of the variable, or `None` if the expression refers to an enumerator.
+    fn resolve_variable(&self, expression: ExprId) -> Option<Symbol> {
+        let unique_name = self.annotations.variables().unique_name(expression)?;
+        Some(self.variable_names[unique_name].clone())
+    }
+
+    /// Generates a unique name for a temporary TACKY variable.
+    ///
+    /// Side effect: increments the temporary variable counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `temporary_type`: The type of the value the temporary holds.
+    ///
+    /// # Returns
+    ///
+    /// A `Symbol` containing the generated temporary variable name.
+    fn make_temporary(&mut self, temporary_type: TackyType) -> Symbol {
+        let temp_name = self.interner.borrow_mut().intern(&format!(
+            "tmp{}{}",
+            GENERATED_NAME_SEPARATOR, self.temp_counter
+        ));
+        self.temp_counter += 1;
+        self.variable_types
+            .insert(temp_name.clone(), temporary_type);
+        temp_name
+    }
+
+    /// Generates a unique name for a temporary holding a value of a C-- type, and records the type,
+    /// so that a temporary holding a pointer keeps the type it points to.
+    ///
+    /// # Arguments
+    ///
+    /// * `temporary_type`: The C-- type of the value the temporary holds.
+    ///
+    /// # Returns
+    ///
+    /// A `Symbol` containing the generated temporary variable name.
+    fn make_typed_temporary(&mut self, temporary_type: &CmmType) -> Symbol {
+        // A temporary holds a value, which is never qualified.
+        let temporary_type = temporary_type.unqualified();
+        let temp_name = self.make_temporary(TackyType::from(temporary_type.clone()));
+        self.cmm_types
+            .insert(temp_name.clone(), temporary_type.clone());
+        temp_name
+    }
+
+    /// Adds a string to the read-only data of the program and generates its unique name.
+    ///
+    /// Side effect: increments the temporary variable counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The bytes of the string, without the terminating null byte.
+    ///
+    /// # Returns
+    ///
+    /// A `Symbol` containing the name of the static constant, e.g. "string.0".
+    fn make_static_string(&mut self, value: Vec<u8>) -> Symbol {
+        let identifier = self.interner.borrow_mut().intern(&format!(
+            "string{}{}",
+            GENERATED_NAME_SEPARATOR, self.temp_counter
+        ));
+        self.temp_counter += 1;
+        self.static_constants.push(TackyStaticConstant::String {
+            identifier: identifier.clone(),
+            value,
+        });
+        identifier
+    }
+
+    /// Generates a unique label string by appending the separator and a counter to a base name.
+    ///
+    /// Side effect: increments the label counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `label_name`: The base name for the label.
+    ///
+    /// # Returns
+    ///
+    /// A unique label symbol (e.g., "myLabel.0", "myLabel.1").
+    fn make_label(&mut self, label_name: &str) -> Symbol {
+        let label = self.interner.borrow_mut().intern(&format!(
+            "{}{}{}",
+            label_name, GENERATED_NAME_SEPARATOR, self.label_counter
+        ));
+        self.label_counter += 1;
+        label
+    }
+}
+
+/// Appends a `return 0` to the instructions of a function when control can reach their end.
+///
+/// Reaching the closing brace of `main` returns 0 in C, which programs rely on to exit successfully.
+/// Other functions return 0 as well, so that execution never runs past the end of their code. A
+/// `void` function returns without a value instead.
+///
+/// # Arguments
+///
+/// * `instructions`: The instructions of the body of the function.
+/// * `returns_value`: Whether the function returns a value, rather than being a `void` function.
+fn append_implicit_return(instructions: &mut Vec<TackyInstruction>, returns_value: bool) {
+    if !matches!(instructions.last(), Some(TackyInstruction::Return { .. })) {
+        instructions.push(TackyInstruction::Return {
+            value: returns_value.then_some(TackyValue::Constant(TackyConstant::Int(0))),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::semantic::validate;
+    use crate::compiler::{parse_source, validate_source};
+
+    #[test]
+    fn test_make_temporary() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let temp_name = tacky_emitter.make_temporary(TackyType::Int);
+        assert_eq!(temp_name, "tmp.0");
+        let temp_name = tacky_emitter.make_temporary(TackyType::UnsignedInt);
+        assert_eq!(temp_name, "tmp.1");
+        assert_eq!(
+            tacky_emitter.variable_types,
+            BTreeMap::from([
+                (Symbol::from("tmp.0"), TackyType::Int),
+                (Symbol::from("tmp.1"), TackyType::UnsignedInt),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_append_implicit_return() {
+        let mut instructions = vec![TackyInstruction::Label(Symbol::from("end"))];
+        append_implicit_return(&mut instructions, true);
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::Label(Symbol::from("end")),
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(0)))
+                },
+            ]
+        );
+
+        let mut instructions = vec![TackyInstruction::Return {
+            value: Some(TackyValue::Constant(TackyConstant::Int(2))),
+        }];
+        append_implicit_return(&mut instructions, true);
+        assert_eq!(instructions.len(), 1);
+
+        let mut instructions = vec![];
+        append_implicit_return(&mut instructions, false);
+        assert_eq!(instructions, vec![TackyInstruction::Return { value: None }]);
+    }
+
+    #[test]
+    fn test_emit_tacky_constant_only() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let cmm_expression = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Constant(TackyConstant::Int(1))));
+        assert_eq!(tacky_instructions, vec![]);
+    }
+
+    #[test]
+    fn test_emit_tacky_single_negate_expression() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let cmm_expression = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: constant,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.0"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![TackyInstruction::Unary {
+                operator: TackyUnaryOperator::Negate,
+                source: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: TackyValue::Variable(Symbol::from("tmp.0")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_emit_tacky_single_complement_expression() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let cmm_expression = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Complement,
+            expression: constant,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.0"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![TackyInstruction::Unary {
+                operator: TackyUnaryOperator::Complement,
+                source: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: TackyValue::Variable(Symbol::from("tmp.0")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_emit_tacky_double_unary_expression() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let complement = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Complement,
+            expression: constant,
+        });
+        let cmm_expression = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: complement,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.1"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![
+                TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Complement,
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Negate,
+                    source: TackyValue::Variable(Symbol::from("tmp.0")),
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emit_tacky_binary_operation() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let left = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let right = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+        let cmm_expression = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left,
+            right,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.0"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: TackyValue::Constant(TackyConstant::Int(1)),
+                source2: TackyValue::Constant(TackyConstant::Int(2)),
+                destination: TackyValue::Variable(Symbol::from("tmp.0")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_emit_tacky_and_operation() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let left = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let right = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+        let cmm_expression = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::And,
+            left,
+            right,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.0"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(1)),
+                    target: Symbol::from("and_false.0"),
+                },
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(2)),
+                    target: Symbol::from("and_false.0"),
+                },
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::Jump {
+                    target: Symbol::from("and_end.1"),
+                },
+                TackyInstruction::Label(Symbol::from("and_false.0")),
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(0)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::Label(Symbol::from("and_end.1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_emit_tacky_or_operation() {
+        let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let left = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: constant,
+        });
+        let right = expressions.alloc(CmmExpression::IntegerConstant { value: 2 });
+        let cmm_expression = expressions.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Or,
+            left,
+            right,
+        });
+        let mut tacky_instructions = vec![];
+        let tacky_value =
+            tacky_emitter.emit_tacky(&expressions, cmm_expression, &mut tacky_instructions);
+
+        assert_eq!(tacky_value, Ok(TackyValue::Variable(Symbol::from("tmp.1"))));
+        assert_eq!(
+            tacky_instructions,
+            vec![
+                TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Negate,
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::JumpIfNotZero {
+                    condition: TackyValue::Variable(Symbol::from("tmp.0")),
+                    target: Symbol::from("or_true.0"),
+                },
+                TackyInstruction::JumpIfNotZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(2)),
+                    target: Symbol::from("or_true.0"),
+                },
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(0)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                },
+                TackyInstruction::Jump {
+                    target: Symbol::from("or_end.1"),
+                },
+                TackyInstruction::Label(Symbol::from("or_true.0")),
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                },
+                TackyInstruction::Label(Symbol::from("or_end.1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_if_statements() {
+        let cmm_ast =
+            parse_source("int main(void) { if (1) if (2) return 3; else return 4; }").unwrap();
+        let mut tacky_emitter = TackyEmitter::with_instruction_origins();
+        let TackyAst::Program { functions, .. } = tacky_emitter
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let return_constant = |value| TackyInstruction::Return {
+            value: Some(TackyValue::Constant(TackyConstant::Int(value))),
+        };
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(1)),
+                    target: Symbol::from("if_end.0"),
+                },
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(2)),
+                    target: Symbol::from("if_else.1"),
+                },
+                return_constant(3),
+                TackyInstruction::Jump {
+                    target: Symbol::from("if_end.2"),
+                },
+                TackyInstruction::Label(Symbol::from("if_else.1")),
+                return_constant(4),
+                TackyInstruction::Label(Symbol::from("if_end.2")),
+                TackyInstruction::Label(Symbol::from("if_end.0")),
+                return_constant(0),
+            ]
+        );
+        // The branching originates from the conditions, the implicit return has no origin.
+        let origins: Vec<Option<usize>> = tacky_emitter
+            .instruction_origins()
+            .unwrap()
+            .iter()
+            .map(|origin| origin.map(|expression| expression.index()))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                Some(0),
+                Some(1),
+                Some(2),
+                Some(1),
+                Some(1),
+                Some(3),
+                Some(1),
+                Some(0),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_while_loop() {
+        let cmm_ast = parse_source("int main(void) { while (1 - 1) return 2; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::Label(Symbol::from("while_start.0")),
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Subtract,
+                    source1: TackyValue::Constant(TackyConstant::Int(1)),
+                    source2: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.0")),
+                },
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Variable(Symbol::from("tmp.0")),
+                    target: Symbol::from("while_end.1"),
+                },
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(2))),
+                },
+                TackyInstruction::Jump {
+                    target: Symbol::from("while_start.0"),
+                },
+                TackyInstruction::Label(Symbol::from("while_end.1")),
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_do_while_loop() {
+        let cmm_ast = parse_source("int main(void) { do if (0) return 1; while (0); }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::Label(Symbol::from("do_start.0")),
+                TackyInstruction::JumpIfZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(0)),
+                    target: Symbol::from("if_end.3"),
+                },
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(1))),
+                },
+                TackyInstruction::Label(Symbol::from("if_end.3")),
+                TackyInstruction::Label(Symbol::from("do_continue.1")),
+                TackyInstruction::JumpIfNotZero {
+                    condition: TackyValue::Constant(TackyConstant::Int(0)),
+                    target: Symbol::from("do_start.0"),
+                },
+                TackyInstruction::Label(Symbol::from("do_end.2")),
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_for_loop() {
+        let cmm_ast =
+            parse_source("int main(void) { for (int i = 0; i < 2; i = i + 1) return i; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let variable = |name| TackyValue::Variable(Symbol::from(name));
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(0)),
+                    destination: variable("i.0"),
+                },
+                TackyInstruction::Label(Symbol::from("for_start.0")),
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::LessThan,
+                    source1: variable("i.0"),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: variable("tmp.1"),
+                },
+                TackyInstruction::JumpIfZero {
+                    condition: variable("tmp.1"),
+                    target: Symbol::from("for_end.2"),
+                },
+                TackyInstruction::Return {
+                    value: Some(variable("i.0")),
+                },
+                TackyInstruction::Label(Symbol::from("for_continue.1")),
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Add,
+                    source1: variable("i.0"),
+                    source2: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: variable("tmp.2"),
+                },
+                TackyInstruction::Copy {
+                    source: variable("tmp.2"),
+                    destination: variable("i.0"),
+                },
+                TackyInstruction::Jump {
+                    target: Symbol::from("for_start.0"),
+                },
+                TackyInstruction::Label(Symbol::from("for_end.2")),
+                TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(0))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_for_loop_without_clauses() {
+        let cmm_ast = parse_source("int main(void) { for (;;) return 1; }").unwrap();
+        let mut tacky_emitter = TackyEmitter::with_instruction_origins();
+        let TackyAst::Program { functions, .. } = tacky_emitter
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        assert_eq!(instructions.len(), 6);
+        // Only the return has an origin, as the loop has no condition.
+        let origins: Vec<Option<usize>> = tacky_emitter
+            .instruction_origins()
+            .unwrap()
+            .iter()
+            .map(|origin| origin.map(|expression| expression.index()))
+            .collect();
+        assert_eq!(origins, vec![None, Some(0), None, None, None, None]);
+    }
+
+    #[test]
+    fn test_convert_break_and_continue() {
+        let cmm_ast = parse_source(
+            "int main(void) { while (1) for (;;) if (2) break; else do continue; while (3); }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let jump = |target| TackyInstruction::Jump {
+            target: Symbol::from(target),
+        };
+        // Each jump targets the innermost enclosing loop.
+        assert_eq!(
+            instructions[4..9],
+            [
+                jump("for_end.4"),
+                jump("if_end.6"),
+                TackyInstruction::Label(Symbol::from("if_else.5")),
+                TackyInstruction::Label(Symbol::from("do_start.7")),
+                jump("do_continue.8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_variables_resolve_to_innermost_declaration() {
+        let cmm_ast = parse_source(
+            "int main(void) { for (int tmp = 1; ; ) for (int tmp = tmp + 1; ; ) return tmp; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        // The inner initializer already refers to the inner variable, and no name is reused.
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Add,
+            source1: TackyValue::Variable(Symbol::from("tmp.1")),
+            source2: TackyValue::Constant(TackyConstant::Int(1)),
+            destination: TackyValue::Variable(Symbol::from("tmp.2")),
+        }));
+        assert!(instructions.contains(&TackyInstruction::Return {
+            value: Some(TackyValue::Variable(Symbol::from("tmp.1"))),
+        }));
+    }
+
+    #[test]
+    fn test_convert_block_items() {
+        let cmm_ast =
+            parse_source("int f(int n) { int x = n + 1; long y; for (; ; ) return x; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        // Body declarations see the parameters and are visible to the later statements.
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Add,
+            source1: TackyValue::Variable(Symbol::from("n.0")),
+            source2: TackyValue::Constant(TackyConstant::Int(1)),
+            destination: TackyValue::Variable(Symbol::from("tmp.2")),
+        }));
+        assert!(instructions.contains(&TackyInstruction::Return {
+            value: Some(TackyValue::Variable(Symbol::from("x.1"))),
+        }));
+    }
+
+    #[test]
+    fn test_convert_null_and_expression_statements() {
+        let cmm_ast =
+            parse_source("int main(void) { int x = 1; x + 2; ; x *= 5; return x; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let x = TackyValue::Variable(Symbol::from("x.0"));
+        // The value of an expression statement is computed and then left unused.
+        assert_eq!(
+            instructions,
+            vec![
+                TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: x.clone(),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Add,
+                    source1: x.clone(),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Multiply,
+                    source1: x.clone(),
+                    source2: TackyValue::Constant(TackyConstant::Int(5)),
+                    destination: x.clone(),
+                },
+                TackyInstruction::Return { value: Some(x) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_prefix_increment_and_decrement() {
+        let cmm_ast =
+            parse_source("int main(void) { for (int x = 1; ; --x) return ++x; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let x = TackyValue::Variable(Symbol::from("x.0"));
+        let increment = [
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: x.clone(),
+                source2: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: TackyValue::Variable(Symbol::from("tmp.1")),
+            },
+            TackyInstruction::Copy {
+                source: TackyValue::Variable(Symbol::from("tmp.1")),
+                destination: x.clone(),
+            },
+            // The incremented variable is the value of the expression.
+            TackyInstruction::Return {
+                value: Some(x.clone()),
+            },
+        ];
+        assert!(instructions.windows(3).any(|window| window == increment));
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Subtract,
+            source1: x.clone(),
+            source2: TackyValue::Constant(TackyConstant::Int(1)),
+            destination: TackyValue::Variable(Symbol::from("tmp.2")),
+        }));
+    }
+
+    #[test]
+    fn test_convert_postfix_increment_and_decrement() {
+        let cmm_ast =
+            parse_source("int main(void) { for (int x = 1; ; x--) return x++; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let x = TackyValue::Variable(Symbol::from("x.0"));
+        let old_value = TackyValue::Variable(Symbol::from("tmp.1"));
+        let increment = [
+            TackyInstruction::Copy {
+                source: x.clone(),
+                destination: old_value.clone(),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: old_value.clone(),
+                source2: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: x.clone(),
+            },
+            // The value from before the increment is the value of the expression.
+            TackyInstruction::Return {
+                value: Some(old_value),
+            },
+        ];
+        assert!(instructions.windows(3).any(|window| window == increment));
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Subtract,
+            source1: TackyValue::Variable(Symbol::from("tmp.2")),
+            source2: TackyValue::Constant(TackyConstant::Int(1)),
+            destination: x,
+        }));
+    }
+
+    #[test]
+    fn test_convert_compound_assignments() {
+        let cmm_ast =
+            parse_source("int main(void) { for (int x = 6; ; x <<= x & 3) return x ^= 5; }")
+                .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let x = TackyValue::Variable(Symbol::from("x.0"));
+        // The variable is both the left operand and the destination, and is the value of the expression.
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::BitwiseXor,
+            source1: x.clone(),
+            source2: TackyValue::Constant(TackyConstant::Int(5)),
+            destination: x.clone(),
+        }));
+        assert!(instructions.contains(&TackyInstruction::Return {
+            value: Some(x.clone())
+        }));
+        let shift_position = instructions
+            .iter()
+            .position(|instruction| {
+                *instruction
+                    == TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::LeftShift,
+                        source1: x.clone(),
+                        source2: TackyValue::Variable(Symbol::from("tmp.1")),
+                        destination: x.clone(),
+                    }
+            })
+            .unwrap();
+        assert_eq!(
+            instructions[shift_position - 1],
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::BitwiseAnd,
+                source1: x.clone(),
+                source2: TackyValue::Constant(TackyConstant::Int(3)),
+                destination: TackyValue::Variable(Symbol::from("tmp.1")),
+            }
+        );
     }
 
-    /// Generates a unique label string by appending a counter to a base name.
-    ///
-    /// Side effect: increments the label counter.
-    ///
-    /// # Arguments
-    ///
-    /// * `label_name`: The base name for the label.
-    ///
-    /// # Returns
-    ///
-    /// A unique label string (e.g., "myLabel0", "myLabel1").
-    fn make_label(&mut self, label_name: &str) -> String {
-        let label = format!("{}{}", label_name, self.label_counter);
-        self.label_counter += 1;
-        label
+    #[test]
+    fn test_convert_unsigned_arithmetic_conversions() {
+        let cmm_ast =
+            parse_source("int main(void) { for (unsigned u = 7; ; u += 2) return u / -2 < 3; }")
+                .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let u = TackyValue::Variable(Symbol::from("u.0"));
+        let conversion = [
+            TackyInstruction::Copy {
+                source: TackyValue::Variable(Symbol::from("tmp.1")),
+                destination: TackyValue::Variable(Symbol::from("tmp.2")),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Divide,
+                source1: u.clone(),
+                source2: TackyValue::Variable(Symbol::from("tmp.2")),
+                destination: TackyValue::Variable(Symbol::from("tmp.3")),
+            },
+            // Constants are converted without a copy.
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::LessThan,
+                source1: TackyValue::Variable(Symbol::from("tmp.3")),
+                source2: TackyValue::Constant(TackyConstant::UnsignedInt(3)),
+                destination: TackyValue::Variable(Symbol::from("tmp.4")),
+            },
+        ];
+        assert!(instructions.windows(3).any(|window| window == conversion));
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Add,
+            source1: u.clone(),
+            source2: TackyValue::Constant(TackyConstant::UnsignedInt(2)),
+            destination: u,
+        }));
+        // The negated `int` is copied into an `unsigned int` before the division.
+        assert_eq!(variable_types[&Symbol::from("tmp.1")], TackyType::Int);
+        assert_eq!(
+            variable_types[&Symbol::from("tmp.2")],
+            TackyType::UnsignedInt
+        );
+        assert_eq!(
+            variable_types[&Symbol::from("tmp.3")],
+            TackyType::UnsignedInt
+        );
+        // Comparisons evaluate to an `int` regardless of the operand types.
+        assert_eq!(variable_types[&Symbol::from("tmp.4")], TackyType::Int);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_convert_long_conversions() {
+        let cmm_ast =
+            parse_source("int main(void) { for (int i = 3; ; i += 5L) return i < 4294967296; }")
+                .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let i = TackyValue::Variable(Symbol::from("i.0"));
+        // The `int` is sign extended to compare it with a constant that only fits into a `long`.
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::LessThan,
+            source1: TackyValue::Variable(Symbol::from("tmp.1")),
+            source2: TackyValue::Constant(TackyConstant::Long(4294967296)),
+            destination: TackyValue::Variable(Symbol::from("tmp.2")),
+        }));
+        // The compound assignment computes a `long` and truncates it back into the `int`.
+        let compound_assignment = [
+            TackyInstruction::SignExtend {
+                source: i.clone(),
+                destination: TackyValue::Variable(Symbol::from("tmp.3")),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: TackyValue::Variable(Symbol::from("tmp.3")),
+                source2: TackyValue::Constant(TackyConstant::Long(5)),
+                destination: TackyValue::Variable(Symbol::from("tmp.4")),
+            },
+            TackyInstruction::Truncate {
+                source: TackyValue::Variable(Symbol::from("tmp.4")),
+                destination: i,
+            },
+        ];
+        assert!(
+            instructions
+                .windows(3)
+                .any(|window| window == compound_assignment)
+        );
+        assert_eq!(variable_types[&Symbol::from("tmp.1")], TackyType::Long);
+        assert_eq!(variable_types[&Symbol::from("tmp.4")], TackyType::Long);
+    }
 
     #[test]
-    fn test_make_temporary() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let temp_name = tacky_emitter.make_temporary();
-        assert_eq!(temp_name, "tmp.0");
-        let temp_name = tacky_emitter.make_temporary();
-        assert_eq!(temp_name, "tmp.1");
+    fn test_convert_casts() {
+        let cmm_ast = parse_source(
+            "int main(void) { for (long l = 4294967298; ; ) return (int) l + (char) 300 + (long) (unsigned int) -1; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        // A constant is converted while generating the IR, so `(char) 300` wraps around to 44.
+        let truncation = [
+            TackyInstruction::Truncate {
+                source: TackyValue::Variable(Symbol::from("l.0")),
+                destination: TackyValue::Variable(Symbol::from("tmp.1")),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: TackyValue::Variable(Symbol::from("tmp.1")),
+                source2: TackyValue::Constant(TackyConstant::Int(44)),
+                destination: TackyValue::Variable(Symbol::from("tmp.2")),
+            },
+        ];
+        assert!(instructions.windows(2).any(|window| window == truncation));
+        // The `unsigned int` is zero extended, so the cast of -1 to a `long` is 4294967295.
+        assert!(instructions.contains(&TackyInstruction::ZeroExtend {
+            source: TackyValue::Variable(Symbol::from("tmp.5")),
+            destination: TackyValue::Variable(Symbol::from("tmp.6")),
+        }));
+        assert_eq!(variable_types[&Symbol::from("tmp.1")], TackyType::Int);
+        assert_eq!(
+            variable_types[&Symbol::from("tmp.5")],
+            TackyType::UnsignedInt
+        );
+        assert_eq!(variable_types[&Symbol::from("tmp.6")], TackyType::Long);
     }
 
     #[test]
-    fn test_emit_tacky_constant_only() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::IntegerConstant { value: 1 };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
+    fn test_convert_pointers() {
+        let cmm_ast =
+            parse_source("int f(int x) { for (int *p = &x; ; *p += 1) return *p; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let pointer = TackyValue::Variable(Symbol::from("p.1"));
+        assert_eq!(
+            instructions[..2],
+            [
+                TackyInstruction::GetAddress {
+                    source: Symbol::from("x.0"),
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
+                },
+                TackyInstruction::Copy {
+                    source: TackyValue::Variable(Symbol::from("tmp.2")),
+                    destination: pointer.clone(),
+                },
+            ]
+        );
+        // A compound assignment through a pointer loads the value once and stores the result.
+        let value = TackyValue::Variable(Symbol::from("tmp.4"));
+        let compound_assignment = [
+            TackyInstruction::Load {
+                source_pointer: pointer.clone(),
+                destination: value.clone(),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: value.clone(),
+                source2: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: value.clone(),
+            },
+            TackyInstruction::Store {
+                source: value,
+                destination_pointer: pointer,
+            },
+        ];
+        assert!(
+            instructions
+                .windows(3)
+                .any(|window| window == compound_assignment)
+        );
+        assert_eq!(variable_types[&Symbol::from("p.1")], TackyType::Pointer);
+        assert_eq!(variable_types[&Symbol::from("tmp.4")], TackyType::Int);
+    }
 
-        assert_eq!(tacky_value, Ok(TackyValue::Constant(1)));
-        assert_eq!(tacky_instructions, vec![]);
+    #[test]
+    fn test_convert_arrays() {
+        let cmm_ast =
+            parse_source("int f(int i) { for (int a[3]; ; ) return a[i] + (&a[2] - a); }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let array = Symbol::from("a.1");
+        let temporary = |name: &str| TackyValue::Variable(Symbol::from(name));
+        // The array decays into the address of its first element, which the index is added to.
+        assert_eq!(
+            instructions[1..5],
+            [
+                TackyInstruction::GetAddress {
+                    source: array.clone(),
+                    destination: temporary("tmp.2"),
+                },
+                TackyInstruction::SignExtend {
+                    source: temporary("i.0"),
+                    destination: temporary("tmp.3"),
+                },
+                TackyInstruction::AddPtr {
+                    pointer: temporary("tmp.2"),
+                    index: temporary("tmp.3"),
+                    scale: 4,
+                    destination: temporary("tmp.4"),
+                },
+                TackyInstruction::Load {
+                    source_pointer: temporary("tmp.4"),
+                    destination: temporary("tmp.5"),
+                },
+            ]
+        );
+        // The difference of two pointers counts the elements between them.
+        assert_eq!(
+            instructions[9..11],
+            [
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Subtract,
+                    source1: temporary("tmp.8"),
+                    source2: temporary("tmp.9"),
+                    destination: temporary("tmp.10"),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Divide,
+                    source1: temporary("tmp.10"),
+                    source2: TackyValue::Constant(TackyConstant::Long(4)),
+                    destination: temporary("tmp.11"),
+                },
+            ]
+        );
+        assert_eq!(
+            variable_types[&array],
+            TackyType::Aggregate {
+                size: 12,
+                alignment: 4
+            }
+        );
+        assert_eq!(variable_types[&Symbol::from("tmp.11")], TackyType::Long);
     }
 
     #[test]
-    fn test_emit_tacky_single_negate_expression() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Unary {
-            operator: CmmUnaryOperator::Negate,
-            expression: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-        };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
+    fn test_convert_structs() {
+        let cmm_ast = parse_source(
+            "struct pair { char c; long y; };\n\
+             int f(struct pair *p) { for (struct pair s; ; ) return s.y + p->c; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let structure = Symbol::from("s.1");
+        let temporary = |name: &str| TackyValue::Variable(Symbol::from(name));
+        // A member is loaded through the address of the structure, moved by the member offset.
+        assert_eq!(
+            instructions[1..6],
+            [
+                TackyInstruction::GetAddress {
+                    source: structure.clone(),
+                    destination: temporary("tmp.2"),
+                },
+                TackyInstruction::AddPtr {
+                    pointer: temporary("tmp.2"),
+                    index: TackyValue::Constant(TackyConstant::Long(8)),
+                    scale: 1,
+                    destination: temporary("tmp.3"),
+                },
+                TackyInstruction::Load {
+                    source_pointer: temporary("tmp.3"),
+                    destination: temporary("tmp.4"),
+                },
+                TackyInstruction::AddPtr {
+                    pointer: temporary("p.0"),
+                    index: TackyValue::Constant(TackyConstant::Long(0)),
+                    scale: 1,
+                    destination: temporary("tmp.5"),
+                },
+                TackyInstruction::Load {
+                    source_pointer: temporary("tmp.5"),
+                    destination: temporary("tmp.6"),
+                },
+            ]
+        );
+        assert_eq!(
+            variable_types[&structure],
+            TackyType::Aggregate {
+                size: 16,
+                alignment: 8
+            }
+        );
+        assert_eq!(variable_types[&Symbol::from("tmp.4")], TackyType::Long);
+        assert_eq!(variable_types[&Symbol::from("tmp.6")], TackyType::Char);
+    }
 
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.0"))));
+    #[test]
+    fn test_convert_enums() {
+        let cmm_ast = parse_source(
+            "enum { A = 2, B, C = B * 3 };\n\
+             int f(int x) { for (int B = C; ; ) return x + A - B; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let variable = |name: &str| TackyValue::Variable(Symbol::from(name));
+        // Enumerators are `int` constants, which a variable of the same name hides.
         assert_eq!(
-            tacky_instructions,
-            vec![TackyInstruction::Unary {
-                operator: TackyUnaryOperator::Negate,
-                source: TackyValue::Constant(1),
-                destination: TackyValue::Variable(String::from("tmp.0")),
+            instructions[..1],
+            [TackyInstruction::Copy {
+                source: TackyValue::Constant(TackyConstant::Int(9)),
+                destination: variable("B.1"),
             }]
         );
+        assert_eq!(
+            instructions[2..4],
+            [
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Add,
+                    source1: variable("x.0"),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: variable("tmp.2"),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Subtract,
+                    source1: variable("tmp.2"),
+                    source2: variable("B.1"),
+                    destination: variable("tmp.3"),
+                },
+            ]
+        );
     }
 
     #[test]
-    fn test_emit_tacky_single_complement_expression() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Unary {
-            operator: CmmUnaryOperator::Complement,
-            expression: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-        };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
-
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.0"))));
+    fn test_convert_bool() {
+        let cmm_ast =
+            parse_source("int f(int x) { for (_Bool b = x; ; ) return (_Bool) 2 + b; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let variable = |name: &str| TackyValue::Variable(Symbol::from(name));
+        // Any nonzero value becomes 1, so the value is compared to zero rather than truncated.
         assert_eq!(
-            tacky_instructions,
-            vec![TackyInstruction::Unary {
-                operator: TackyUnaryOperator::Complement,
-                source: TackyValue::Constant(1),
-                destination: TackyValue::Variable(String::from("tmp.0")),
-            }]
+            instructions[..3],
+            [
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::NotEqual,
+                    source1: variable("x.0"),
+                    source2: TackyValue::Constant(TackyConstant::Int(0)),
+                    destination: variable("tmp.3"),
+                },
+                TackyInstruction::Truncate {
+                    source: variable("tmp.3"),
+                    destination: variable("tmp.2"),
+                },
+                TackyInstruction::Copy {
+                    source: variable("tmp.2"),
+                    destination: variable("b.1"),
+                },
+            ]
         );
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Add,
+            source1: TackyValue::Constant(TackyConstant::Int(1)),
+            source2: variable("tmp.4"),
+            destination: variable("tmp.5"),
+        }));
     }
 
     #[test]
-    fn test_emit_tacky_double_unary_expression() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Unary {
-            operator: CmmUnaryOperator::Negate,
-            expression: Box::new(CmmExpression::Unary {
-                operator: CmmUnaryOperator::Complement,
-                expression: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-            }),
+    fn test_convert_const() {
+        for source_code in [
+            "int f(const int x) { for (const int y = x; ; ) return x + y; }",
+            "int f(int x) { for (const int *p = &x; p; p = 0) return *p; }",
+            "int f(int x) { for (int *const p = &x; ; ) return *p = 2; }",
+            "int f(int x) { for (const int a[2] = {x, 1}; ; ) return *(int *) a = a[1]; }",
+            "int f(int x) { for (const int *p = &x; ; ) return p == &x; }",
+            "struct s { int x; }; int main(void) { for (struct s v; ; ) for (const struct s *p = &v; ; ) return p->x; }",
+        ] {
+            let cmm_ast = parse_source(source_code).unwrap();
+            assert!(
+                TackyEmitter::new()
+                    .convert_ast(validate(cmm_ast).unwrap())
+                    .is_ok(),
+                "{source_code}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_convert_initializers() {
+        let cmm_ast =
+            parse_source("int f(int i) { for (char a[2][2] = {{i}, {2}}; ; ) return a[1][1]; }")
+                .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        let temporary = |name: &str| TackyValue::Variable(Symbol::from(name));
+        let scalar = |index, destination| TackyInstruction::AddPtr {
+            pointer: temporary("tmp.2"),
+            index: TackyValue::Constant(TackyConstant::Long(index)),
+            scale: 1,
+            destination: temporary(destination),
         };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
+        // Every scalar is stored through its offset from the first one, and the missing ones are
+        // zero.
+        assert_eq!(
+            instructions[..9],
+            [
+                TackyInstruction::GetAddress {
+                    source: Symbol::from("a.1"),
+                    destination: temporary("tmp.2"),
+                },
+                TackyInstruction::Truncate {
+                    source: temporary("i.0"),
+                    destination: temporary("tmp.3"),
+                },
+                TackyInstruction::Store {
+                    source: temporary("tmp.3"),
+                    destination_pointer: temporary("tmp.2"),
+                },
+                scalar(1, "tmp.4"),
+                TackyInstruction::Store {
+                    source: TackyValue::Constant(TackyConstant::Char(0)),
+                    destination_pointer: temporary("tmp.4"),
+                },
+                scalar(2, "tmp.5"),
+                TackyInstruction::Store {
+                    source: TackyValue::Constant(TackyConstant::Char(2)),
+                    destination_pointer: temporary("tmp.5"),
+                },
+                scalar(3, "tmp.6"),
+                TackyInstruction::Store {
+                    source: TackyValue::Constant(TackyConstant::Char(0)),
+                    destination_pointer: temporary("tmp.6"),
+                },
+            ]
+        );
+    }
 
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.1"))));
+    #[test]
+    fn test_convert_char_promotions() {
+        let cmm_ast =
+            parse_source("int main(void) { for (char c = 300; ; c += 1) return -c; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let c = TackyValue::Variable(Symbol::from("c.0"));
+        // The initializer is converted to a `char` at compile time.
         assert_eq!(
-            tacky_instructions,
-            vec![
-                TackyInstruction::Unary {
-                    operator: TackyUnaryOperator::Complement,
-                    source: TackyValue::Constant(1),
-                    destination: TackyValue::Variable(String::from("tmp.0")),
+            instructions[0],
+            TackyInstruction::Copy {
+                source: TackyValue::Constant(TackyConstant::Char(44)),
+                destination: c.clone(),
+            }
+        );
+        // The negation operates on the operand promoted to an `int`.
+        assert!(instructions.contains(&TackyInstruction::Unary {
+            operator: TackyUnaryOperator::Negate,
+            source: TackyValue::Variable(Symbol::from("tmp.1")),
+            destination: TackyValue::Variable(Symbol::from("tmp.2")),
+        }));
+        // The compound assignment computes an `int` and truncates it back into the `char`.
+        let compound_assignment = [
+            TackyInstruction::SignExtend {
+                source: c.clone(),
+                destination: TackyValue::Variable(Symbol::from("tmp.3")),
+            },
+            TackyInstruction::Binary {
+                operator: TackyBinaryOperator::Add,
+                source1: TackyValue::Variable(Symbol::from("tmp.3")),
+                source2: TackyValue::Constant(TackyConstant::Int(1)),
+                destination: TackyValue::Variable(Symbol::from("tmp.4")),
+            },
+            TackyInstruction::Truncate {
+                source: TackyValue::Variable(Symbol::from("tmp.4")),
+                destination: c,
+            },
+        ];
+        assert!(
+            instructions
+                .windows(3)
+                .any(|window| window == compound_assignment)
+        );
+        assert_eq!(variable_types[&Symbol::from("c.0")], TackyType::Char);
+        assert_eq!(variable_types[&Symbol::from("tmp.2")], TackyType::Int);
+    }
+
+    #[test]
+    fn test_convert_short_promotions() {
+        let cmm_ast = parse_source(
+            "int main(void) { for (short s = 40000; ; ) return s * (unsigned char) 2 + s; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let s = TackyValue::Variable(Symbol::from("s.0"));
+        // The initializer is converted to a `short` at compile time.
+        assert_eq!(
+            instructions[0],
+            TackyInstruction::Copy {
+                source: TackyValue::Constant(TackyConstant::Short(-25536)),
+                destination: s.clone(),
+            }
+        );
+        // Both operands of the multiplication are promoted to `int`s.
+        assert_eq!(
+            instructions[2..4],
+            [
+                TackyInstruction::SignExtend {
+                    source: s,
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Multiply,
+                    source1: TackyValue::Variable(Symbol::from("tmp.1")),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
                 },
-                TackyInstruction::Unary {
-                    operator: TackyUnaryOperator::Negate,
-                    source: TackyValue::Variable(String::from("tmp.0")),
-                    destination: TackyValue::Variable(String::from("tmp.1")),
-                }
             ]
         );
+        assert_eq!(variable_types[&Symbol::from("s.0")], TackyType::Short);
+        assert_eq!(variable_types[&Symbol::from("tmp.2")], TackyType::Int);
     }
 
     #[test]
-    fn test_emit_tacky_binary_operation() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Binary {
-            operator: CmmBinaryOperator::Add,
-            left: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-            right: Box::new(CmmExpression::IntegerConstant { value: 2 }),
-        };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
+    fn test_convert_unary_plus() {
+        let cmm_ast =
+            parse_source("int main(void) { for (char c = 3; ; ) return +c + +2; }").unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        // Unary plus only promotes its operand, so the sum adds the `char` sign-extended to an `int`.
+        assert_eq!(
+            instructions[2..4],
+            [
+                TackyInstruction::SignExtend {
+                    source: TackyValue::Variable(Symbol::from("c.0")),
+                    destination: TackyValue::Variable(Symbol::from("tmp.1")),
+                },
+                TackyInstruction::Binary {
+                    operator: TackyBinaryOperator::Add,
+                    source1: TackyValue::Variable(Symbol::from("tmp.1")),
+                    source2: TackyValue::Constant(TackyConstant::Int(2)),
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
+                },
+            ]
+        );
+    }
 
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.0"))));
+    #[test]
+    fn test_convert_string_literals() {
+        let cmm_ast = parse_source("int main(void) { if (!\"a\\n\" \"b\") return 1; }").unwrap();
+        let TackyAst::Program {
+            functions,
+            static_constants,
+            ..
+        } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        // Adjacent literals are joined into a single constant.
         assert_eq!(
-            tacky_instructions,
-            vec![TackyInstruction::Binary {
-                operator: TackyBinaryOperator::Add,
-                source1: TackyValue::Constant(1),
-                source2: TackyValue::Constant(2),
-                destination: TackyValue::Variable(String::from("tmp.0")),
+            static_constants,
+            vec![TackyStaticConstant::String {
+                identifier: Symbol::from("string.0"),
+                value: b"a\nb".to_vec(),
             }]
         );
+        let address = TackyValue::Variable(Symbol::from("tmp.1"));
+        assert_eq!(
+            instructions[..2],
+            [
+                TackyInstruction::GetAddress {
+                    source: Symbol::from("string.0"),
+                    destination: address.clone(),
+                },
+                TackyInstruction::Unary {
+                    operator: TackyUnaryOperator::Not,
+                    source: address,
+                    destination: TackyValue::Variable(Symbol::from("tmp.2")),
+                },
+            ]
+        );
+        assert_eq!(variable_types[&Symbol::from("tmp.1")], TackyType::Pointer);
+        assert_eq!(variable_types[&Symbol::from("tmp.2")], TackyType::Int);
     }
 
     #[test]
-    fn test_emit_tacky_and_operation() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Binary {
-            operator: CmmBinaryOperator::And,
-            left: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-            right: Box::new(CmmExpression::IntegerConstant { value: 2 }),
-        };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
-
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.0"))));
+    fn test_convert_double_conversions() {
+        let cmm_ast = parse_source(
+            "int main(void) { for (char c = 'a'; ; c = 2.5) for (double d = c; ; d *= 2) return d; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let tmp = |name| TackyValue::Variable(Symbol::from(name));
+        // A `char` is converted through an `int`, and the result of `main` back into one.
         assert_eq!(
-            tacky_instructions,
-            vec![
-                TackyInstruction::JumpIfZero {
-                    condition: TackyValue::Constant(1),
-                    target: String::from("and_false0"),
+            instructions[2..8],
+            [
+                TackyInstruction::SignExtend {
+                    source: tmp("c.0"),
+                    destination: tmp("tmp.3"),
                 },
-                TackyInstruction::JumpIfZero {
-                    condition: TackyValue::Constant(2),
-                    target: String::from("and_false0"),
+                TackyInstruction::IntToDouble {
+                    source: tmp("tmp.3"),
+                    destination: tmp("tmp.2"),
                 },
                 TackyInstruction::Copy {
-                    source: TackyValue::Constant(1),
-                    destination: TackyValue::Variable(String::from("tmp.0")),
+                    source: tmp("tmp.2"),
+                    destination: tmp("d.1"),
                 },
-                TackyInstruction::Jump {
-                    target: String::from("and_end1"),
+                TackyInstruction::Label(Symbol::from("for_start.3")),
+                TackyInstruction::DoubleToInt {
+                    source: tmp("d.1"),
+                    destination: tmp("tmp.4"),
                 },
-                TackyInstruction::Label(String::from("and_false0")),
-                TackyInstruction::Copy {
-                    source: TackyValue::Constant(0),
-                    destination: TackyValue::Variable(String::from("tmp.0")),
+                TackyInstruction::Return {
+                    value: Some(tmp("tmp.4"))
                 },
-                TackyInstruction::Label(String::from("and_end1")),
             ]
         );
+        // Constants are converted at compile time, truncating toward zero.
+        assert!(instructions.contains(&TackyInstruction::Binary {
+            operator: TackyBinaryOperator::Multiply,
+            source1: tmp("d.1"),
+            source2: TackyValue::Constant(TackyConstant::Double(2.0)),
+            destination: tmp("d.1"),
+        }));
+        assert!(instructions.contains(&TackyInstruction::Copy {
+            source: TackyValue::Constant(TackyConstant::Char(2)),
+            destination: tmp("c.0"),
+        }));
+        assert_eq!(variable_types[&Symbol::from("d.1")], TackyType::Double);
     }
 
     #[test]
-    fn test_emit_tacky_or_operation() {
-        let mut tacky_emitter = TackyEmitter::new();
-        let cmm_expression = CmmExpression::Binary {
-            operator: CmmBinaryOperator::Or,
-            left: Box::new(CmmExpression::Unary {
-                operator: CmmUnaryOperator::Negate,
-                expression: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-            }),
-            right: Box::new(CmmExpression::IntegerConstant { value: 2 }),
-        };
-        let mut tacky_instructions = vec![];
-        let tacky_value = tacky_emitter.emit_tacky(&cmm_expression, &mut tacky_instructions);
+    fn test_convert_function_calls() {
+        let cmm_ast = parse_source(
+            "int f(int argc, long l) { if (argc == 1) return f(2, argc); else return 0; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [
+            TackyFunction::Function {
+                parameters,
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        assert_eq!(parameters, [Symbol::from("argc.0"), Symbol::from("l.1")]);
+        assert_eq!(variable_types[&Symbol::from("l.1")], TackyType::Long);
+        // The `int` argument is sign extended to the `long` parameter before the call.
+        let call = [
+            TackyInstruction::SignExtend {
+                source: TackyValue::Variable(Symbol::from("argc.0")),
+                destination: TackyValue::Variable(Symbol::from("tmp.3")),
+            },
+            TackyInstruction::FunCall {
+                identifier: Symbol::from("f"),
+                arguments: vec![
+                    TackyValue::Constant(TackyConstant::Int(2)),
+                    TackyValue::Variable(Symbol::from("tmp.3")),
+                ],
+                named_arguments: None,
+                destination: TackyValue::Variable(Symbol::from("tmp.4")),
+            },
+        ];
+        assert!(instructions.windows(2).any(|window| window == call));
+    }
+
+    #[test]
+    fn test_convert_function_prototypes() {
+        let cmm_ast = parse_source(
+            "int twice(long x); int main(void) { return twice(3); } int twice(long x) { return x + x; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        // The prototype only declares `twice`, so the program defines two functions.
+        let identifiers: Vec<&str> = functions
+            .iter()
+            .map(|TackyFunction::Function { identifier, .. }| identifier.as_str())
+            .collect();
+        assert_eq!(identifiers, ["main", "twice"]);
+        let TackyFunction::Function { instructions, .. } = &functions[0];
+        assert!(instructions.contains(&TackyInstruction::FunCall {
+            identifier: Symbol::from("twice"),
+            arguments: vec![TackyValue::Constant(TackyConstant::Long(3))],
+            named_arguments: None,
+            destination: TackyValue::Variable(Symbol::from("tmp.0")),
+        }));
+    }
 
-        assert_eq!(tacky_value, Ok(TackyValue::Variable(String::from("tmp.1"))));
+    #[test]
+    fn test_convert_void_functions() {
+        let cmm_ast = parse_source(
+            "void skip(int n) { if (n) return; } void nothing(void) {} \
+             int main(void) { skip(1); nothing(); return 0; }",
+        )
+        .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let [skip, nothing, _]: [TackyFunction; 3] = functions.try_into().unwrap();
+        let TackyFunction::Function { instructions, .. } = skip;
+        // Both the explicit and the implicit return leave out the value.
         assert_eq!(
-            tacky_instructions,
-            vec![
-                TackyInstruction::Unary {
-                    operator: TackyUnaryOperator::Negate,
-                    source: TackyValue::Constant(1),
-                    destination: TackyValue::Variable(String::from("tmp.0")),
-                },
-                TackyInstruction::JumpIfNotZero {
-                    condition: TackyValue::Variable(String::from("tmp.0")),
-                    target: String::from("or_true0"),
-                },
-                TackyInstruction::JumpIfNotZero {
-                    condition: TackyValue::Constant(2),
-                    target: String::from("or_true0"),
+            instructions
+                .iter()
+                .filter(|instruction| **instruction == TackyInstruction::Return { value: None })
+                .count(),
+            2
+        );
+        let TackyFunction::Function { instructions, .. } = nothing;
+        assert_eq!(instructions, [TackyInstruction::Return { value: None }]);
+    }
+
+    #[test]
+    fn test_convert_function_linkage() {
+        let convert =
+            |source_code| TackyEmitter::new().convert_ast(validate_source(source_code).unwrap());
+        let TackyAst::Program { functions, .. } = convert(
+            "static int f(void); extern int f(void) { return 1; } int main(void) { return f(); }",
+        )
+        .unwrap();
+        // The first declaration of `f` gives it internal linkage, which later ones keep.
+        let linkage: Vec<(&str, bool)> = functions
+            .iter()
+            .map(
+                |TackyFunction::Function {
+                     identifier, global, ..
+                 }| (identifier.as_str(), *global),
+            )
+            .collect();
+        assert_eq!(linkage, [("f", false), ("main", true)]);
+    }
+
+    #[test]
+    fn test_convert_static_variables() {
+        let TackyAst::Program {
+            functions,
+            static_variables,
+            ..
+        } = TackyEmitter::new()
+            .convert_ast(
+                validate_source(
+                    "extern int e; static long s[2] = {3}; int g;
+                     int main(void) { static int count = 1; extern int g; return count + e + g; }",
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        // `e` is only declared, and `count` only belongs to `main`.
+        assert_eq!(
+            static_variables,
+            [
+                TackyStaticVariable::Declaration {
+                    identifier: "e".into()
                 },
-                TackyInstruction::Copy {
-                    source: TackyValue::Constant(0),
-                    destination: TackyValue::Variable(String::from("tmp.1")),
+                TackyStaticVariable::Variable {
+                    identifier: "s".into(),
+                    global: false,
+                    alignment: 16,
+                    initial_values: vec![
+                        TackyStaticInit::Value(TackyConstant::Long(3)),
+                        TackyStaticInit::Zero(8)
+                    ],
                 },
-                TackyInstruction::Jump {
-                    target: String::from("or_end1"),
+                TackyStaticVariable::Variable {
+                    identifier: "g".into(),
+                    global: true,
+                    alignment: 4,
+                    initial_values: vec![TackyStaticInit::Zero(4)],
                 },
-                TackyInstruction::Label(String::from("or_true0")),
-                TackyInstruction::Copy {
-                    source: TackyValue::Constant(1),
-                    destination: TackyValue::Variable(String::from("tmp.1")),
+                TackyStaticVariable::Variable {
+                    identifier: "count.0".into(),
+                    global: false,
+                    alignment: 4,
+                    initial_values: vec![TackyStaticInit::Value(TackyConstant::Int(1))],
                 },
-                TackyInstruction::Label(String::from("or_end1")),
             ]
         );
+        let [function]: [TackyFunction; 1] = functions.try_into().unwrap();
+        // The scalar static variables are read by their names, without taking their addresses.
+        assert_eq!(
+            function.to_string(),
+            "main:\n    tmp.1 = count.0 + e\n    tmp.2 = tmp.1 + g\n    return tmp.2\n"
+        );
+        let TackyFunction::Function { variable_types, .. } = function;
+        assert_eq!(variable_types[&Symbol::from("e")], TackyType::Int);
+    }
+
+    #[test]
+    fn test_convert_variadic_function_calls() {
+        let cmm_ast =
+            parse_source("int f(long a, ...); int main(void) { return f(1, (char)2, \"s\"); }")
+                .unwrap();
+        let TackyAst::Program { functions, .. } = TackyEmitter::new()
+            .convert_ast(validate(cmm_ast).unwrap())
+            .unwrap();
+        let TackyFunction::Function { instructions, .. } = &functions[0];
+        // The named argument is converted to the type of its parameter, while the further ones are
+        // only promoted.
+        let Some(TackyInstruction::FunCall {
+            arguments,
+            named_arguments,
+            ..
+        }) = instructions
+            .iter()
+            .find(|instruction| matches!(instruction, TackyInstruction::FunCall { .. }))
+        else {
+            panic!("Expected a call");
+        };
+        assert_eq!(*named_arguments, Some(1));
+        assert_eq!(arguments[0], TackyValue::Constant(TackyConstant::Long(1)));
+        assert_eq!(arguments[1], TackyValue::Constant(TackyConstant::Int(2)));
+        assert!(matches!(arguments[2], TackyValue::Variable(_)));
     }
 
     #[test]
     fn test_emit_ast() {
-        let identifier = "main".to_string();
+        let identifier = Symbol::from("main");
         let mut tacky_emitter = TackyEmitter::new();
+        let mut expressions = ExpressionArena::new();
+        let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let complement = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Complement,
+            expression: constant,
+        });
+        let expression = expressions.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: complement,
+        });
         let cmm_ast = CmmAst::Program {
-            function: CmmFunction::Function {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
                 identifier: identifier.clone(),
-                body: CmmStatement::Return {
-                    expression: CmmExpression::Unary {
-                        operator: CmmUnaryOperator::Negate,
-                        expression: Box::new(CmmExpression::Unary {
-                            operator: CmmUnaryOperator::Complement,
-                            expression: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-                        }),
-                    },
-                },
-            },
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(expression),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
         };
-        let tacky_ast = tacky_emitter.convert_ast(cmm_ast);
+        let tacky_ast = tacky_emitter.convert_ast(validate(cmm_ast).unwrap());
         assert_eq!(
             tacky_ast,
             Ok(TackyAst::Program {
-                function: TackyFunction::Function {
+                functions: vec![TackyFunction::Function {
                     identifier,
+                    global: true,
+                    parameters: vec![],
                     instructions: vec![
                         TackyInstruction::Unary {
                             operator: TackyUnaryOperator::Complement,
-                            source: TackyValue::Constant(1),
-                            destination: TackyValue::Variable(String::from("tmp.0")),
+                            source: TackyValue::Constant(TackyConstant::Int(1)),
+                            destination: TackyValue::Variable(Symbol::from("tmp.0")),
                         },
                         TackyInstruction::Unary {
                             operator: TackyUnaryOperator::Negate,
-                            source: TackyValue::Variable(String::from("tmp.0")),
-                            destination: TackyValue::Variable(String::from("tmp.1")),
+                            source: TackyValue::Variable(Symbol::from("tmp.0")),
+                            destination: TackyValue::Variable(Symbol::from("tmp.1")),
                         },
                         TackyInstruction::Return {
-                            value: TackyValue::Variable(String::from("tmp.1"))
+                            value: Some(TackyValue::Variable(Symbol::from("tmp.1")))
                         },
-                    ]
-                }
+                    ],
+                    variable_types: BTreeMap::from([
+                        (Symbol::from("tmp.0"), TackyType::Int),
+                        (Symbol::from("tmp.1"), TackyType::Int),
+                    ]),
+                }],
+                static_constants: Vec::new(),
+                static_variables: Vec::new()
             })
         );
     }
@@ -0,0 +1,316 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::GENERATED_NAME_SEPARATOR;
+use crate::compiler::ir_gen::tacky_ast::{
+    TackyAst, TackyFunction, TackyInstruction, TackyStaticVariable, TackyType, TackyValue,
+};
+use rustc_hash::FxHashSet;
+use std::collections::BTreeMap;
+
+/// Rewrites the accesses of static variables by name into loads and stores through their
+/// addresses.
+///
+/// The x86-64 code generator addresses a static variable relative to %rip, so the TACKY IR refers
+/// to a scalar static variable by its name like to a variable of the function. The TACKY
+/// interpreter and the QBE backend only keep the variables of a function apart from the data of
+/// the program, so they run on the IR this function returns: every instruction reading a static
+/// variable is preceded by a `GetAddress` and a `Load` into a new temporary, and every instruction
+/// writing one is followed by a `GetAddress` and a `Store`. The static variables are removed from
+/// the variable types of the functions, so that a `GetAddress` of a name missing from them takes
+/// the address of static data again.
+///
+/// # Arguments
+///
+/// * `tacky_ast`: A reference to the `TackyAst` to rewrite.
+///
+/// # Returns
+///
+/// The rewritten `TackyAst`.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::ir_gen::static_accesses::lower_static_accesses;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyAst, TackyFunction, TackyInstruction, TackyStaticVariable, TackyType, TackyValue};
+/// # use std::collections::BTreeMap;
+/// let counter = Symbol::from("counter");
+/// let tacky_ast = TackyAst::Program {
+///     functions: vec![TackyFunction::Function {
+///         identifier: Symbol::from("main"),
+///         global: true,
+///         parameters: vec![],
+///         instructions: vec![TackyInstruction::Return { value: Some(TackyValue::Variable(counter.clone())) }],
+///         variable_types: BTreeMap::from([(counter.clone(), TackyType::Int)]),
+///     }],
+///     static_constants: Vec::new(),
+///     static_variables: vec![TackyStaticVariable::Declaration { identifier: counter }],
+/// };
+/// let TackyAst::Program { functions, .. } = lower_static_accesses(&tacky_ast);
+/// assert_eq!(
+///     functions[0].to_string(),
+///     "main:\n    static.0 = &counter\n    static.1 = *static.0\n    return static.1\n"
+/// );
+/// ```
+pub fn lower_static_accesses(tacky_ast: &TackyAst) -> TackyAst {
+    let TackyAst::Program {
+        functions,
+        static_constants,
+        static_variables,
+    } = tacky_ast;
+    let statics: FxHashSet<&Symbol> = static_variables
+        .iter()
+        .map(TackyStaticVariable::identifier)
+        .collect();
+    TackyAst::Program {
+        functions: functions
+            .iter()
+            .map(|function| lower_function(function, &statics))
+            .collect(),
+        static_constants: static_constants.clone(),
+        static_variables: static_variables.clone(),
+    }
+}
+
+/// Rewrites the accesses of static variables of a function, see `lower_static_accesses`.
+fn lower_function(function: &TackyFunction, statics: &FxHashSet<&Symbol>) -> TackyFunction {
+    let TackyFunction::Function {
+        identifier,
+        global,
+        parameters,
+        instructions,
+        variable_types,
+    } = function;
+    let mut lowering = StaticLowering {
+        statics,
+        variable_types: variable_types.clone(),
+        temporary_counter: 0,
+        instructions: Vec::with_capacity(instructions.len()),
+    };
+    for instruction in instructions {
+        lowering.lower_instruction(instruction.clone());
+    }
+    let StaticLowering {
+        mut variable_types,
+        instructions,
+        ..
+    } = lowering;
+    variable_types.retain(|name, _| !statics.contains(name));
+    TackyFunction::Function {
+        identifier: identifier.clone(),
+        global: *global,
+        parameters: parameters.clone(),
+        instructions,
+        variable_types,
+    }
+}
+
+/// The state of rewriting the static variable accesses of a function.
+struct StaticLowering<'a> {
+    /// The names of the static variables of the program.
+    statics: &'a FxHashSet<&'a Symbol>,
+    /// The types of the variables of the function, extended by the new temporaries.
+    variable_types: BTreeMap<Symbol, TackyType>,
+    /// The number of temporaries created so far, which makes their names unique.
+    temporary_counter: usize,
+    /// The rewritten instructions.
+    instructions: Vec<TackyInstruction>,
+}
+
+impl StaticLowering<'_> {
+    /// Appends an instruction, with the loads of the static variables it reads before it and the
+    /// store of the static variable it writes after it.
+    fn lower_instruction(&mut self, mut instruction: TackyInstruction) {
+        let (sources, destination) = instruction_values(&mut instruction);
+        for source in sources {
+            if let Some(variable) = self.static_variable(source) {
+                let pointer = self.emit_address(&variable);
+                let value = self.make_temporary(self.variable_types[&variable]);
+                self.instructions.push(TackyInstruction::Load {
+                    source_pointer: pointer,
+                    destination: value.clone(),
+                });
+                *source = value;
+            }
+        }
+        let store = destination.and_then(|destination| {
+            let variable = self.static_variable(destination)?;
+            let value = self.make_temporary(self.variable_types[&variable]);
+            *destination = value.clone();
+            Some((variable, value))
+        });
+        self.instructions.push(instruction);
+        if let Some((variable, value)) = store {
+            let pointer = self.emit_address(&variable);
+            self.instructions.push(TackyInstruction::Store {
+                source: value,
+                destination_pointer: pointer,
+            });
+        }
+    }
+
+    /// Returns the name of the static variable a value refers to, if any.
+    fn static_variable(&self, value: &TackyValue) -> Option<Symbol> {
+        match value {
+            TackyValue::Variable(name) if self.statics.contains(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Appends the `GetAddress` of a static variable and returns the pointer it writes.
+    fn emit_address(&mut self, variable: &Symbol) -> TackyValue {
+        let pointer = self.make_temporary(TackyType::Pointer);
+        self.instructions.push(TackyInstruction::GetAddress {
+            source: variable.clone(),
+            destination: pointer.clone(),
+        });
+        pointer
+    }
+
+    /// Creates a temporary of the given type. The names start with the keyword `static`, which
+    /// no variable of the program can be named.
+    fn make_temporary(&mut self, temporary_type: TackyType) -> TackyValue {
+        let name = Symbol::from(format!(
+            "static{}{}",
+            GENERATED_NAME_SEPARATOR, self.temporary_counter
+        ));
+        self.temporary_counter += 1;
+        self.variable_types.insert(name.clone(), temporary_type);
+        TackyValue::Variable(name)
+    }
+}
+
+/// Returns the values an instruction reads, and the value it writes, if any. The pointer of a
+/// `Store` is read.
+fn instruction_values(
+    instruction: &mut TackyInstruction,
+) -> (Vec<&mut TackyValue>, Option<&mut TackyValue>) {
+    match instruction {
+        TackyInstruction::Return { value } => (value.iter_mut().collect(), None),
+        TackyInstruction::Unary {
+            source,
+            destination,
+            ..
+        }
+        | TackyInstruction::Copy {
+            source,
+            destination,
+        }
+        | TackyInstruction::SignExtend {
+            source,
+            destination,
+        }
+        | TackyInstruction::ZeroExtend {
+            source,
+            destination,
+        }
+        | TackyInstruction::Truncate {
+            source,
+            destination,
+        }
+        | TackyInstruction::IntToDouble {
+            source,
+            destination,
+        }
+        | TackyInstruction::DoubleToInt {
+            source,
+            destination,
+        }
+        | TackyInstruction::UIntToDouble {
+            source,
+            destination,
+        }
+        | TackyInstruction::DoubleToUInt {
+            source,
+            destination,
+        }
+        | TackyInstruction::Load {
+            source_pointer: source,
+            destination,
+        } => (vec![source], Some(destination)),
+        TackyInstruction::Binary {
+            source1,
+            source2,
+            destination,
+            ..
+        }
+        | TackyInstruction::AddPtr {
+            pointer: source1,
+            index: source2,
+            destination,
+            ..
+        } => (vec![source1, source2], Some(destination)),
+        TackyInstruction::Store {
+            source,
+            destination_pointer,
+        } => (vec![source, destination_pointer], None),
+        TackyInstruction::GetAddress { destination, .. } => (vec![], Some(destination)),
+        TackyInstruction::FunCall {
+            arguments,
+            destination,
+            ..
+        } => (arguments.iter_mut().collect(), Some(destination)),
+        TackyInstruction::JumpIfZero { condition, .. }
+        | TackyInstruction::JumpIfNotZero { condition, .. } => (vec![condition], None),
+        TackyInstruction::Jump { .. } | TackyInstruction::Label(_) => (vec![], None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ir_gen::tacky_ast::{TackyBinaryOperator, TackyConstant};
+
+    #[test]
+    fn test_lower_static_accesses() {
+        let count = Symbol::from("count");
+        let total = Symbol::from("total.0");
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Binary {
+                        operator: TackyBinaryOperator::Add,
+                        source1: TackyValue::Variable(count.clone()),
+                        source2: TackyValue::Constant(TackyConstant::Long(1)),
+                        destination: TackyValue::Variable(count.clone()),
+                    },
+                    TackyInstruction::Return {
+                        value: Some(TackyValue::Variable(total.clone())),
+                    },
+                ],
+                variable_types: BTreeMap::from([
+                    (count.clone(), TackyType::Long),
+                    (total.clone(), TackyType::Int),
+                ]),
+            }],
+            static_constants: Vec::new(),
+            static_variables: vec![TackyStaticVariable::Declaration {
+                identifier: count.clone(),
+            }],
+        };
+        let TackyAst::Program { functions, .. } = lower_static_accesses(&tacky_ast);
+        let TackyFunction::Function {
+            instructions,
+            variable_types,
+            ..
+        } = &functions[0];
+        assert_eq!(
+            functions[0].to_string(),
+            "main:\n    static.0 = &count\n    static.1 = *static.0\n    \
+             static.2 = static.1 + 1L\n    static.3 = &count\n    *static.3 = static.2\n    \
+             return total.0\n"
+        );
+        assert_eq!(instructions.len(), 6);
+        // The static variable is no variable of the function anymore, its temporaries are.
+        assert_eq!(
+            variable_types
+                .keys()
+                .map(Symbol::as_str)
+                .collect::<Vec<_>>(),
+            ["static.0", "static.1", "static.2", "static.3", "total.0"]
+        );
+        assert_eq!(variable_types[&Symbol::from("static.2")], TackyType::Long);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::tacky_ast::TackyType;
+use crate::compiler::parser::cmm_ast::{CmmMember, CmmType};
+use rustc_hash::FxHashMap;
+
+/// The size of the largest object in bytes, which is the largest distance between two pointers.
+pub const MAX_OBJECT_SIZE: usize = isize::MAX as usize;
+
+/// Holds the layouts of the structure types declared by a program.
+///
+/// The size and alignment of a structure depend on its members, so every size, alignment, and
+/// TACKY type of a C-- type that may contain a structure is computed here rather than by
+/// `CmmType::size`. The table holds the structures of a program the semantic analysis validated,
+/// which declares every structure once, with distinct members of complete types, before its size
+/// or members are needed.
+#[derive(Debug, Default)]
+pub struct TypeTable {
+    /// The layouts of the declared structures, by tag.
+    structs: FxHashMap<Symbol, StructLayout>,
+}
+
+/// Represents the memory layout of a structure type.
+#[derive(Debug, PartialEq)]
+pub struct StructLayout {
+    /// The members, in the order they are laid out in memory.
+    pub members: Vec<StructMember>,
+    /// The size of the structure in bytes, including the padding after its last member.
+    pub size: usize,
+    /// The alignment of the structure in bytes, which is the largest alignment of its members.
+    pub alignment: usize,
+}
+
+/// Represents a member of a structure type and where it is stored.
+#[derive(Debug, PartialEq)]
+pub struct StructMember {
+    /// The name of the member.
+    pub identifier: Symbol,
+    /// The type of the member.
+    pub member_type: CmmType,
+    /// The offset of the member from the start of the structure in bytes.
+    pub offset: usize,
+}
+
+impl TypeTable {
+    /// Removes the structures of the previously converted program.
+    pub fn clear(&mut self) {
+        self.structs.clear();
+    }
+
+    /// Declares a structure type and lays out its members.
+    ///
+    /// Every member is placed at the next offset that is a multiple of its alignment, and the size
+    /// of the structure is rounded up to a multiple of its alignment, so that the members of the
+    /// elements of an array stay aligned.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag of the structure.
+    /// * `members` - The members of the structure, in declaration order.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the structure is larger than `MAX_OBJECT_SIZE`, which leaves it undeclared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a member has an undeclared structure type.
+    pub fn declare_struct(&mut self, tag: &Symbol, members: &[CmmMember]) -> Option<()> {
+        let mut layout = StructLayout {
+            members: Vec::with_capacity(members.len()),
+            size: 0,
+            alignment: 1,
+        };
+        for CmmMember::Member {
+            identifier,
+            member_type,
+        } in members
+        {
+            let alignment = self.member_alignment(member_type);
+            let offset = layout.size.checked_next_multiple_of(alignment)?;
+            layout.size = offset.checked_add(self.checked_size(member_type)?)?;
+            layout.alignment = layout.alignment.max(alignment);
+            layout.members.push(StructMember {
+                identifier: identifier.clone(),
+                member_type: member_type.clone(),
+                offset,
+            });
+        }
+        layout.size = layout
+            .size
+            .checked_next_multiple_of(layout.alignment)
+            .filter(|size| *size <= MAX_OBJECT_SIZE)?;
+        self.structs.insert(tag.clone(), layout);
+        Some(())
+    }
+
+    /// Returns the layout of a declared structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag of the structure.
+    ///
+    /// # Returns
+    ///
+    /// The `StructLayout` of the structure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the structure is not declared.
+    pub fn layout(&self, tag: &Symbol) -> &StructLayout {
+        self.structs
+            .get(tag)
+            .expect("The semantic analysis rejects uses of undeclared structures")
+    }
+
+    /// Looks up a member of a declared structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - The tag of the structure.
+    /// * `member` - The name of the member.
+    ///
+    /// # Returns
+    ///
+    /// The `StructMember`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the structure is not declared or has no member of the name.
+    pub fn member(&self, tag: &Symbol, member: &Symbol) -> &StructMember {
+        self.layout(tag)
+            .members
+            .iter()
+            .find(|struct_member| &struct_member.identifier == member)
+            .expect("The semantic analysis rejects accesses of unknown members")
+    }
+
+    /// Returns the size of an object of a type in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains an undeclared structure, or is larger than
+    /// `MAX_OBJECT_SIZE`.
+    pub fn size(&self, cmm_type: &CmmType) -> usize {
+        self.checked_size(cmm_type)
+            .expect("The semantic analysis rejects types larger than the largest object")
+    }
+
+    /// Returns the size of an object of a type in bytes, or `None` if an array of the type is
+    /// larger than `MAX_OBJECT_SIZE`. A declared structure is never larger.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains an undeclared structure.
+    pub fn checked_size(&self, cmm_type: &CmmType) -> Option<usize> {
+        match cmm_type {
+            CmmType::Array(element_type, length) => self
+                .checked_size(element_type)?
+                .checked_mul(*length)
+                .filter(|size| *size <= MAX_OBJECT_SIZE),
+            CmmType::Struct(tag) => Some(self.layout(tag).size),
+            CmmType::Const(qualified_type) => self.checked_size(qualified_type),
+            _ => Some(cmm_type.size()),
+        }
+    }
+
+    /// Returns the alignment of a variable of a type in bytes, which follows
+    /// `CmmType::alignment`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains an undeclared structure.
+    pub fn alignment(&self, cmm_type: &CmmType) -> usize {
+        match cmm_type {
+            CmmType::Array(_, _) if self.size(cmm_type) >= 16 => 16,
+            _ => self.member_alignment(cmm_type),
+        }
+    }
+
+    /// Returns the alignment of a member of a type in bytes. Unlike a variable, an array member is
+    /// always aligned like its elements.
+    fn member_alignment(&self, cmm_type: &CmmType) -> usize {
+        match cmm_type {
+            CmmType::Array(element_type, _) => self.member_alignment(element_type),
+            CmmType::Struct(tag) => self.layout(tag).alignment,
+            CmmType::Const(qualified_type) => self.member_alignment(qualified_type),
+            _ => cmm_type.alignment(),
+        }
+    }
+
+    /// Returns the TACKY type of a variable of a type. Arrays and structures are aggregates of
+    /// their size and alignment.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the type is or contains an undeclared structure.
+    pub fn tacky_type(&self, cmm_type: &CmmType) -> TackyType {
+        match cmm_type {
+            CmmType::Array(_, _) | CmmType::Struct(_) => TackyType::Aggregate {
+                size: self.size(cmm_type),
+                alignment: self.alignment(cmm_type),
+            },
+            CmmType::Const(qualified_type) => self.tacky_type(qualified_type),
+            _ => TackyType::from(cmm_type.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(identifier: &str, member_type: CmmType) -> CmmMember {
+        CmmMember::Member {
+            identifier: Symbol::from(identifier),
+            member_type,
+        }
+    }
+
+    fn offsets(types: &TypeTable, tag: &str) -> Vec<usize> {
+        let layout = types.layout(&Symbol::from(tag));
+        layout.members.iter().map(|member| member.offset).collect()
+    }
+
+    #[test]
+    fn test_declare_struct_aligns_members() {
+        let mut types = TypeTable::default();
+        let members = [
+            member("c", CmmType::Char),
+            member("x", CmmType::Int),
+            member("d", CmmType::Char),
+            member("p", CmmType::Pointer(Box::new(CmmType::Char))),
+            member("e", CmmType::Char),
+        ];
+        assert_eq!(types.declare_struct(&Symbol::from("s"), &members), Some(()));
+        assert_eq!(offsets(&types, "s"), vec![0, 4, 8, 16, 24]);
+        let s = CmmType::Struct(Symbol::from("s"));
+        assert_eq!(types.size(&s), 32);
+        assert_eq!(types.alignment(&s), 8);
+        assert_eq!(
+            types.tacky_type(&CmmType::Array(Box::new(s), 2)),
+            TackyType::Aggregate {
+                size: 64,
+                alignment: 16
+            }
+        );
+    }
+
+    #[test]
+    fn test_declare_struct_with_nested_types() {
+        let mut types = TypeTable::default();
+        let bytes = CmmType::Array(Box::new(CmmType::Char), 17);
+        assert_eq!(
+            types.declare_struct(&Symbol::from("inner"), &[member("bytes", bytes)]),
+            Some(())
+        );
+        let inner = CmmType::Struct(Symbol::from("inner"));
+        assert_eq!(types.size(&inner), 17);
+        assert_eq!(types.alignment(&inner), 1);
+        let members = [
+            member("c", CmmType::Char),
+            member("inner", inner),
+            member("x", CmmType::Int),
+        ];
+        assert_eq!(
+            types.declare_struct(&Symbol::from("outer"), &members),
+            Some(())
+        );
+        assert_eq!(offsets(&types, "outer"), vec![0, 1, 20]);
+        assert_eq!(types.size(&CmmType::Struct(Symbol::from("outer"))), 24);
+    }
+
+    #[test]
+    fn test_declare_struct_rejects_structures_too_large() {
+        let mut types = TypeTable::default();
+        let half = CmmType::Array(Box::new(CmmType::Char), MAX_OBJECT_SIZE / 2 + 1);
+        // Each member fits an object, but together they do not.
+        let members = [member("a", half.clone()), member("b", half)];
+        assert_eq!(types.declare_struct(&Symbol::from("s"), &members), None);
+        // The padding after the last member may not fit either.
+        let members = [
+            member("x", CmmType::Long),
+            member(
+                "bytes",
+                CmmType::Array(Box::new(CmmType::Char), MAX_OBJECT_SIZE - 8),
+            ),
+        ];
+        assert_eq!(types.declare_struct(&Symbol::from("t"), &members), None);
+        let members = [
+            member("x", CmmType::Long),
+            member(
+                "bytes",
+                CmmType::Array(Box::new(CmmType::Char), MAX_OBJECT_SIZE - 15),
+            ),
+        ];
+        assert_eq!(types.declare_struct(&Symbol::from("u"), &members), Some(()));
+    }
+}
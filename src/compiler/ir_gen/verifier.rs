@@ -0,0 +1,341 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::errors::TackyVerificationError;
+use crate::compiler::ir_gen::is_generated_name;
+use crate::compiler::ir_gen::tacky_ast::{
+    TackyAst, TackyFunction, TackyInstruction, TackyStaticVariable, TackyValue,
+};
+use rustc_hash::FxHashSet;
+
+/// Checks the invariants of a TACKY IR that the later stages rely on.
+///
+/// The names of the IR must follow the mangling scheme of `GENERATED_NAME_SEPARATOR`: functions
+/// keep the names of the program, while every parameter, temporary, and label is a generated name.
+/// Only static variables, which are no variables of the function, may be referred to by the name
+/// of the program. The TACKY generator guarantees this, but custom passes may break it, and a clash between a label
+/// and a function would only surface as a confusing assembler error.
+///
+/// # Arguments
+///
+/// * `tacky_ast`: A reference to the `TackyAst` to verify.
+///
+/// # Returns
+///
+/// `Ok(())` if the IR is valid, or the `TackyVerificationError` of the first violation.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyAst, TackyFunction, TackyInstruction, TackyValue};
+/// # use std::collections::BTreeMap;
+/// # use cmm::compiler::ir_gen::verifier::verify_tacky;
+/// let tacky_ast = |temporary: &str| TackyAst::Program {
+///     functions: vec![TackyFunction::Function {
+///         identifier: Symbol::from("main"),
+///         global: true,
+///         parameters: vec![],
+///         instructions: vec![TackyInstruction::Return { value: Some(TackyValue::Variable(Symbol::from(temporary))) }],
+///         variable_types: BTreeMap::new(),
+///     }],
+///     static_constants: Vec::new(),
+///     static_variables: Vec::new(),
+/// };
+/// assert!(verify_tacky(&tacky_ast("tmp.0")).is_ok());
+/// assert!(verify_tacky(&tacky_ast("tmp0")).is_err());
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn verify_tacky(tacky_ast: &TackyAst) -> Result<(), TackyVerificationError> {
+    let TackyAst::Program {
+        functions,
+        static_variables,
+        ..
+    } = tacky_ast;
+    let statics: FxHashSet<&Symbol> = static_variables
+        .iter()
+        .map(TackyStaticVariable::identifier)
+        .collect();
+    functions
+        .iter()
+        .try_for_each(|function| verify_function(function, &statics))
+}
+
+/// Checks the names of a single TACKY function, see `verify_tacky`.
+fn verify_function(
+    function: &TackyFunction,
+    statics: &FxHashSet<&Symbol>,
+) -> Result<(), TackyVerificationError> {
+    let TackyFunction::Function {
+        identifier,
+        parameters,
+        instructions,
+        variable_types,
+        ..
+    } = function;
+    if is_generated_name(identifier) {
+        return Err(TackyVerificationError::GeneratedFunctionName {
+            function: identifier.clone(),
+        });
+    }
+    let check_name = |name: &Symbol| {
+        if is_generated_name(name) || statics.contains(name) {
+            Ok(())
+        } else {
+            Err(TackyVerificationError::UnmangledName {
+                function: identifier.clone(),
+                name: name.clone(),
+            })
+        }
+    };
+    let check_value = |value: &TackyValue| match value {
+        TackyValue::Constant(_) => Ok(()),
+        TackyValue::Variable(name) => check_name(name),
+    };
+    for parameter in parameters {
+        check_name(parameter)?;
+    }
+    for instruction in instructions {
+        match instruction {
+            TackyInstruction::Return { value } => {
+                if let Some(value) = value {
+                    check_value(value)?
+                }
+            }
+            TackyInstruction::Unary {
+                source,
+                destination,
+                ..
+            }
+            | TackyInstruction::Copy {
+                source,
+                destination,
+            }
+            | TackyInstruction::SignExtend {
+                source,
+                destination,
+            }
+            | TackyInstruction::ZeroExtend {
+                source,
+                destination,
+            }
+            | TackyInstruction::Truncate {
+                source,
+                destination,
+            }
+            | TackyInstruction::IntToDouble {
+                source,
+                destination,
+            }
+            | TackyInstruction::DoubleToInt {
+                source,
+                destination,
+            }
+            | TackyInstruction::UIntToDouble {
+                source,
+                destination,
+            }
+            | TackyInstruction::DoubleToUInt {
+                source,
+                destination,
+            }
+            | TackyInstruction::Load {
+                source_pointer: source,
+                destination,
+            }
+            | TackyInstruction::Store {
+                source,
+                destination_pointer: destination,
+            } => {
+                check_value(source)?;
+                check_value(destination)?;
+            }
+            TackyInstruction::Binary {
+                source1,
+                source2,
+                destination,
+                ..
+            }
+            | TackyInstruction::AddPtr {
+                pointer: source1,
+                index: source2,
+                destination,
+                ..
+            } => {
+                check_value(source1)?;
+                check_value(source2)?;
+                check_value(destination)?;
+            }
+            TackyInstruction::GetAddress {
+                source,
+                destination,
+            } => {
+                if variable_types.contains_key(source) {
+                    check_name(source)?;
+                }
+                check_value(destination)?;
+            }
+            TackyInstruction::FunCall {
+                identifier,
+                arguments,
+                named_arguments: _,
+                destination,
+            } => {
+                if is_generated_name(identifier) {
+                    return Err(TackyVerificationError::GeneratedFunctionName {
+                        function: identifier.clone(),
+                    });
+                }
+                for argument in arguments {
+                    check_value(argument)?;
+                }
+                check_value(destination)?;
+            }
+            TackyInstruction::Jump { target } => check_name(target)?,
+            TackyInstruction::JumpIfZero { condition, target }
+            | TackyInstruction::JumpIfNotZero { condition, target } => {
+                check_value(condition)?;
+                check_name(target)?;
+            }
+            TackyInstruction::Label(label) => check_name(label)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ir_gen::TackyEmitter;
+    use crate::compiler::ir_gen::tacky_ast::TackyConstant;
+    use crate::compiler::{parse_source, validate_source};
+    use std::collections::BTreeMap;
+
+    fn emit_tacky(source_code: &str) -> TackyAst {
+        TackyEmitter::new()
+            .convert_ast(validate_source(source_code).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_functions_named_like_generated_names_do_not_clash() {
+        for function in ["and_false0", "and_end1", "or_true0", "tmp"] {
+            let tacky_ast = emit_tacky(&format!(
+                "int {}(void) {{ return (1 && 2) + (3 || 4); }}",
+                function
+            ));
+            assert_eq!(verify_tacky(&tacky_ast), Ok(()));
+            let TackyAst::Program { functions, .. } = &tacky_ast;
+            let [TackyFunction::Function { instructions, .. }] = functions.as_slice() else {
+                panic!("Expected a single function");
+            };
+            assert!(instructions.iter().all(|instruction| !matches!(
+                instruction,
+                TackyInstruction::Label(label) if *label == function
+            )));
+        }
+    }
+
+    #[test]
+    fn test_generated_names_cannot_be_written_in_the_program() {
+        assert!(parse_source("int tmp.0(void) { return 0; }").is_err());
+        assert!(parse_source("int and_false.0(void) { return 0; }").is_err());
+    }
+
+    #[test]
+    fn test_reject_unmangled_names() {
+        let program = |identifier: &str, instructions| TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: identifier.into(),
+                global: true,
+                parameters: vec![],
+                instructions,
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            verify_tacky(&program(
+                "main",
+                vec![
+                    TackyInstruction::Jump {
+                        target: "main".into()
+                    },
+                    TackyInstruction::Label("main".into()),
+                ]
+            )),
+            Err(TackyVerificationError::UnmangledName {
+                function: "main".into(),
+                name: "main".into()
+            })
+        );
+        assert_eq!(
+            verify_tacky(&program(
+                "main",
+                vec![TackyInstruction::Copy {
+                    source: TackyValue::Constant(TackyConstant::Int(1)),
+                    destination: TackyValue::Variable("x".into()),
+                }]
+            )),
+            Err(TackyVerificationError::UnmangledName {
+                function: "main".into(),
+                name: "x".into()
+            })
+        );
+        assert_eq!(
+            verify_tacky(&program("tmp.0", vec![])),
+            Err(TackyVerificationError::GeneratedFunctionName {
+                function: "tmp.0".into()
+            })
+        );
+        assert_eq!(
+            verify_tacky(&program(
+                "main",
+                vec![TackyInstruction::FunCall {
+                    identifier: "tmp.1".into(),
+                    arguments: vec![TackyValue::Variable("tmp.0".into())],
+                    named_arguments: None,
+                    destination: TackyValue::Variable("tmp.2".into()),
+                }]
+            )),
+            Err(TackyVerificationError::GeneratedFunctionName {
+                function: "tmp.1".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_reject_unmangled_parameters() {
+        let tacky_ast = emit_tacky("int f(int a, long b) { return f(a, b); }");
+        assert_eq!(verify_tacky(&tacky_ast), Ok(()));
+        let TackyAst::Program {
+            functions,
+            static_constants,
+            static_variables,
+        } = tacky_ast;
+        let [
+            TackyFunction::Function {
+                instructions,
+                variable_types,
+                ..
+            },
+        ]: [TackyFunction; 1] = functions.try_into().unwrap();
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                parameters: vec!["a".into()],
+                instructions,
+                variable_types,
+            }],
+            static_constants,
+            static_variables,
+        };
+        assert_eq!(
+            verify_tacky(&tacky_ast),
+            Err(TackyVerificationError::UnmangledName {
+                function: "main".into(),
+                name: "a".into()
+            })
+        );
+    }
+}
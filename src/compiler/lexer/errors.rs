@@ -1,3 +1,5 @@
+use crate::common::diagnostics::Span;
+use crate::compiler::error_codes::{self, ErrorCode};
 use std::error::Error;
 use std::fmt;
 
@@ -17,27 +19,97 @@ pub enum LexerError {
 
     /// Represents a non-matching pattern error during lexing.
     ///
-    /// This error occurs when the lexer finds a string that does not match the expected pattern.
+    /// This error occurs when the lexer finds a string that does not start a token, such as a
+    /// character outside of the grammar like `@`, or a constant running into other characters like
+    /// `123abc`.
     ///
     /// # Arguments
     ///
-    /// * `found`: The string that did not match the pattern.
+    /// * `found`: The invalid character, or the word or constant it belongs to.
     NonmatchingPattern { found: String },
 
     /// Represents an invalid constant error during lexing.
     ///
-    /// This error occurs when the lexer cannot parse a constant integer from the input string.
+    /// This error occurs when the lexer cannot parse a constant integer from the input string,
+    /// because it does not fit into 64 bits, or because it is decimal without a `u` suffix and does
+    /// not fit into a `long`.
     ///
     /// # Arguments
     ///
     /// * `found`: The integer string that could not be parsed.
     InvalidConstant { found: String },
 
-    /// Represents an error where no parser was able to match the input string.
-    NoParserMatched,
+    /// Represents an invalid character constant error during lexing.
+    ///
+    /// This error occurs when a character constant is empty, unterminated, holds more than one
+    /// character or a character outside of ASCII, or uses an unknown escape sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The text of the character constant, up to its closing quote or the end of its line.
+    InvalidCharConstant { found: String },
+
+    /// Represents an error where a string literal could not be parsed.
+    ///
+    /// This error occurs when a string literal is unterminated, spans several lines, or uses an
+    /// unknown escape sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The text of the string literal, up to its closing quote or the end of its line.
+    InvalidStringLiteral { found: String },
+
+    /// Represents a GNU extension used without enabling the extensions.
+    ///
+    /// This error occurs when a binary constant such as `0b1010` is lexed in standard C mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The text using the extension.
+    ExtensionNotEnabled { found: String },
+
+    /// Represents a block comment without a closing `*/`.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The text of the comment, up to the end of its first line.
+    UnterminatedComment { found: String },
 
     /// Represents an error where the input string is empty.
     EmptyInputString,
+
+    /// Wraps an error with the location of the text it was raised for.
+    ///
+    /// # Arguments
+    ///
+    /// * `error`: The error raised while lexing the text.
+    /// * `span`: The location of the text, which starts where the failed token starts.
+    Located { error: Box<LexerError>, span: Span },
+}
+
+impl LexerError {
+    /// Returns the stable `ErrorCode` of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            LexerError::InvalidConstant { .. }
+            | LexerError::InvalidCharConstant { .. }
+            | LexerError::InvalidStringLiteral { .. } => error_codes::INVALID_CONSTANT,
+            LexerError::ExtensionNotEnabled { .. } => error_codes::EXTENSION_NOT_ENABLED,
+            LexerError::UnexpectedCharacter { .. }
+            | LexerError::NonmatchingPattern { .. }
+            | LexerError::UnterminatedComment { .. }
+            | LexerError::EmptyInputString => error_codes::INVALID_TOKEN,
+            LexerError::Located { error, .. } => error.code(),
+        }
+    }
+
+    /// Returns the location of the error, or `None` if it was raised without one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            LexerError::Located { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for LexerError {
@@ -51,11 +123,7 @@ impl fmt::Display for LexerError {
                 )
             }
             LexerError::NonmatchingPattern { found } => {
-                write!(
-                    f,
-                    "Lexer error: The string did not match the expected pattern: '{}'",
-                    found
-                )
+                write!(f, "Lexer error: Invalid token '{}'", found)
             }
             LexerError::InvalidConstant { found } => {
                 write!(
@@ -64,8 +132,26 @@ impl fmt::Display for LexerError {
                     found
                 )
             }
-            LexerError::NoParserMatched => write!(f, "Lexer error: No parser matched"),
+            LexerError::InvalidCharConstant { found } => {
+                write!(f, "Lexer error: Invalid character constant: {}", found)
+            }
+            LexerError::InvalidStringLiteral { found } => {
+                write!(f, "Lexer error: Invalid string literal: {}", found)
+            }
+            LexerError::ExtensionNotEnabled { found } => write!(
+                f,
+                "Lexer error: '{}' uses a GNU extension, which is only accepted with --std=gnu",
+                found
+            ),
+            LexerError::UnterminatedComment { found } => {
+                write!(f, "Lexer error: Unterminated block comment: {}", found)
+            }
             LexerError::EmptyInputString => write!(f, "Lexer error: Input string is empty"),
+            LexerError::Located { error, span } => write!(
+                f,
+                "{} at line {}, column {}",
+                error, span.start_line, span.start_column
+            ),
         }
     }
 }
@@ -1,9 +1,11 @@
 pub mod errors;
 pub mod tokens;
 
+use crate::common::diagnostics::{LineIndex, Span};
+use crate::common::symbol::Interner;
 use errors::LexerError;
-use regex::Regex;
-use std::sync::LazyLock;
+use std::borrow::BorrowMut;
+use std::ops::Range;
 use tokens::Token;
 
 /// Represents the result of a parsing operation, which can either be a success
@@ -13,18 +15,215 @@ use tokens::Token;
 /// # Type Parameters
 ///
 /// * `T`: The type of the successfully parsed value.
-type LexerParseResult<T> = Result<(String, T), LexerError>;
+type LexerParseResult<'a, T> = Result<(&'a str, T), LexerError>;
 
-/// A type alias for a function that parses a string slice into a `LexerParseResult<Token>`.
+/// Represents a lazy stream of the tokens of an input string.
 ///
-/// This is commonly used for defining lexer functions that consume input and produce tokens.
-type LexerParser = Box<dyn Fn(&str) -> LexerParseResult<Token>>;
+/// Each call to `next` lexes a single token, so a consumer such as the `Parser` can process the
+/// tokens while they are produced. The stream ends after the first `LexerError`.
+///
+/// The lexer owns its `Interner` unless it is created with `with_interner` to share the interner
+/// of a `CompilerSession`.
+pub struct Lexer<'a, N: BorrowMut<Interner> = Interner> {
+    /// The whole input, used to compute the byte ranges of the tokens.
+    input: &'a str,
+    /// The input that has not been tokenized yet.
+    remaining: &'a str,
+    /// Deduplicates the identifiers of the input.
+    interner: N,
+    /// Set after an error, so that the stream ends.
+    failed: bool,
+    /// Accepts the GNU extensions of the lexical grammar, such as binary constants.
+    gnu_extensions: bool,
+}
+
+impl<'a> Lexer<'a> {
+    /// Creates a new `Lexer` over the given input string.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_str`: A string slice that represents the code to be tokenized.
+    ///
+    /// # Returns
+    ///
+    /// A new `Lexer` positioned at the start of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::lexer::Lexer;
+    /// # use cmm::compiler::lexer::tokens::Token;
+    /// # use cmm::compiler::lexer::errors::LexerError;
+    /// let mut lexer = Lexer::new("return @;");
+    /// assert_eq!(lexer.next(), Some(Ok(Token::ReturnKeyword)));
+    /// let error = lexer.next().unwrap().unwrap_err();
+    /// assert_eq!(error.to_string(), "Lexer error: Invalid token '@' at line 1, column 8");
+    /// assert_eq!(lexer.next(), None);
+    /// ```
+    pub fn new(input_str: &'a str) -> Self {
+        Lexer {
+            input: input_str,
+            remaining: input_str,
+            interner: Interner::new(),
+            failed: false,
+            gnu_extensions: false,
+        }
+    }
+}
+
+impl<'a, 'i> Lexer<'a, &'i mut Interner> {
+    /// Creates a new `Lexer` that interns the identifiers of the input in an existing interner.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_str`: A string slice that represents the code to be tokenized.
+    /// * `interner`: The `Interner` shared with the other stages of the compilation.
+    ///
+    /// # Returns
+    ///
+    /// A new `Lexer` positioned at the start of the input.
+    pub fn with_interner(input_str: &'a str, interner: &'i mut Interner) -> Self {
+        Lexer {
+            input: input_str,
+            remaining: input_str,
+            interner,
+            failed: false,
+            gnu_extensions: false,
+        }
+    }
+}
+
+impl<'a, N: BorrowMut<Interner>> Lexer<'a, N> {
+    /// Turns the lexer into a lazy stream of the tokens paired with their locations in the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::lexer::Lexer;
+    /// # use cmm::compiler::lexer::tokens::Token;
+    /// let (token, span) = Lexer::new("return\n  12;").with_spans().nth(1).unwrap().unwrap();
+    /// assert_eq!(token, Token::Constant(12));
+    /// assert_eq!((span.start_line, span.start_column), (2, 3));
+    /// ```
+    pub fn with_spans(self) -> SpannedLexer<'a, N> {
+        SpannedLexer {
+            line_index: LineIndex::new(self.input),
+            lexer: self,
+        }
+    }
+
+    /// Sets whether the lexer accepts the GNU extensions of the lexical grammar, which are binary
+    /// constants such as `0b1010`. Without them, such a constant is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::lexer::Lexer;
+    /// # use cmm::compiler::lexer::tokens::Token;
+    /// let mut lexer = Lexer::new("0b101").with_gnu_extensions(true);
+    /// assert_eq!(lexer.next(), Some(Ok(Token::Constant(5))));
+    /// assert!(Lexer::new("0b101").next().unwrap().is_err());
+    /// ```
+    pub fn with_gnu_extensions(mut self, gnu_extensions: bool) -> Self {
+        self.gnu_extensions = gnu_extensions;
+        self
+    }
+
+    /// Lexes the next token of the input, skipping the whitespace and the comments before it, see
+    /// `skip_whitespace_and_comments`.
+    ///
+    /// Errors are located at the text they report, which starts where the failed token starts.
+    /// The lines of the input are only indexed to locate an error, so lexing valid input does not
+    /// pay for it.
+    ///
+    /// The first character of the token selects the parser to run: identifiers and keywords start
+    /// with a letter or an underscore, constants with a digit or a period followed by a digit, see
+    /// `is_double_constant` for telling integer and floating-point constants apart, character
+    /// constants with a single quote, string literals with a double quote, and anything
+    /// else, including the ellipsis `...` and the member access `.`, is a punctuator.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the next `Token` and the byte range it covers in the input, or `None`
+    /// at the end of the input.
+    fn lex_token(&mut self) -> Option<Result<(Token, Range<usize>), LexerError>> {
+        let input_str = skip_whitespace_and_comments(self.remaining);
+        let first_byte = *input_str.as_bytes().first()?;
+        let result = match first_byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                parse_identifier_or_keyword(input_str, self.interner.borrow_mut())
+            }
+            b'.' if !input_str.as_bytes().get(1).is_some_and(u8::is_ascii_digit) => {
+                parse_punctuator(input_str)
+            }
+            b'0'..=b'9' | b'.' if is_double_constant(input_str) => parse_double_constant(input_str),
+            b'0'..=b'9' => parse_constant(input_str, self.gnu_extensions),
+            b'\'' => parse_char_constant(input_str),
+            b'"' => parse_string_literal(input_str),
+            // Terminated block comments were skipped.
+            b'/' if input_str[1..].starts_with('*') => Err(unterminated_comment(input_str)),
+            _ => parse_punctuator(input_str),
+        };
+        let start = self.input.len() - input_str.len();
+        match result {
+            Ok((remaining_str, token)) => {
+                let end = self.input.len() - remaining_str.len();
+                self.remaining = remaining_str;
+                Some(Ok((token, start..end)))
+            }
+            Err(error) => {
+                let end = start + reported_text(&error).len();
+                Some(Err(LexerError::Located {
+                    error: Box::new(error),
+                    span: LineIndex::new(self.input).span(start..end),
+                }))
+            }
+        }
+    }
+
+    /// Lexes the next token of the input like `lex_token`, but ends the stream after an error.
+    fn next_token(&mut self) -> Option<Result<(Token, Range<usize>), LexerError>> {
+        if self.failed {
+            return None;
+        }
+        let token = self.lex_token();
+        self.failed = matches!(token, Some(Err(_)));
+        token
+    }
+}
+
+impl<N: BorrowMut<Interner>> Iterator for Lexer<'_, N> {
+    type Item = Result<Token, LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+            .map(|result| result.map(|(token, _)| token))
+    }
+}
+
+/// Represents a lazy stream of the tokens of an input string together with their locations,
+/// created by `Lexer::with_spans`. The stream ends after the first `LexerError`.
+pub struct SpannedLexer<'a, N: BorrowMut<Interner> = Interner> {
+    /// The lexer producing the tokens.
+    lexer: Lexer<'a, N>,
+    /// Converts the byte ranges of the tokens into lines and columns.
+    line_index: LineIndex,
+}
+
+impl<N: BorrowMut<Interner>> Iterator for SpannedLexer<'_, N> {
+    type Item = Result<(Token, Span), LexerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lexer
+            .next_token()
+            .map(|result| result.map(|(token, range)| (token, self.line_index.span(range))))
+    }
+}
 
 /// Tokenizes an input string into a vector of `Token`s.
 ///
-/// This function iterates through the input string, attempting to parse it
-/// using a predefined set of parsers. It trims whitespace before each parsing
-/// attempt and continues until the string is empty.
+/// This function drains a `Lexer` over the input string, which trims whitespace before each
+/// token and continues until the string is empty.
 ///
 /// # Arguments
 ///
@@ -32,18 +231,21 @@ type LexerParser = Box<dyn Fn(&str) -> LexerParseResult<Token>>;
 ///
 /// # Returns
 ///
-/// A `Vec<Token>` containing the recognized tokens from the input string.
+/// A `Result` containing a `Vec<Token>` of the recognized tokens on success, or a `LexerError` if
+/// no parser matched the remaining input or a constant could not be parsed.
 ///
 /// # Examples
 ///
 /// ```
+/// # use cmm::common::symbol::Symbol;
 /// # use cmm::compiler::lexer::tokenize;
 /// # use cmm::compiler::lexer::tokens::Token;
+/// # use cmm::compiler::lexer::errors::LexerError;
 ///
-/// let tokens = tokenize("int main(void) { return 1; }");
+/// let tokens = tokenize("int main(void) { return 1; }")?;
 /// assert_eq!(tokens, vec![
 ///     Token::IntKeyword,
-///     Token::Identifier("main".to_string()),
+///     Token::Identifier(Symbol::from("main")),
 ///     Token::OpenParen,
 ///     Token::VoidKeyword,
 ///     Token::CloseParen,
@@ -53,81 +255,183 @@ type LexerParser = Box<dyn Fn(&str) -> LexerParseResult<Token>>;
 ///     Token::Semicolon,
 ///     Token::CloseBrace,
 /// ]);
+/// # Ok::<(), LexerError>(())
 /// ```
-pub fn tokenize(input_str: &str) -> Vec<Token> {
-    let mut string_stream = input_str.to_string();
-    let mut token_vec = Vec::new();
-    let parsers: Vec<LexerParser> = vec![
-        // Custom parsers
-        Box::new(parse_identifier_or_keyword),
-        Box::new(parse_constant),
-        // Two character tokens
-        create_regex_parser(Regex::new(r"^--").unwrap(), Token::DoubleHyphen),
-        create_regex_parser(Regex::new(r"^&&").unwrap(), Token::DoubleAmpersand),
-        create_regex_parser(Regex::new(r"^\|\|").unwrap(), Token::DoublePipe),
-        create_regex_parser(Regex::new(r"^==").unwrap(), Token::DoubleEqual),
-        create_regex_parser(Regex::new(r"^!=").unwrap(), Token::ExclamationEqual),
-        create_regex_parser(Regex::new(r"^<=").unwrap(), Token::LessThanEqual),
-        create_regex_parser(Regex::new(r"^>=").unwrap(), Token::GreaterThanEqual),
-        // Single character tokens
-        create_regex_parser(Regex::new(r"^\-").unwrap(), Token::Hyphen),
-        create_regex_parser(Regex::new(r"^\~").unwrap(), Token::Tilde),
-        create_regex_parser(Regex::new(r"^\(").unwrap(), Token::OpenParen),
-        create_regex_parser(Regex::new(r"^\)").unwrap(), Token::CloseParen),
-        create_regex_parser(Regex::new(r"^\{").unwrap(), Token::OpenBrace),
-        create_regex_parser(Regex::new(r"^\}").unwrap(), Token::CloseBrace),
-        create_regex_parser(Regex::new(r"^\;").unwrap(), Token::Semicolon),
-        create_regex_parser(Regex::new(r"^\+").unwrap(), Token::Plus),
-        create_regex_parser(Regex::new(r"^\*").unwrap(), Token::Asterisk),
-        create_regex_parser(Regex::new(r"^\/").unwrap(), Token::ForwardSlash),
-        create_regex_parser(Regex::new(r"^\%").unwrap(), Token::Percent),
-        create_regex_parser(Regex::new(r"^\!").unwrap(), Token::ExclamationMark),
-        create_regex_parser(Regex::new(r"^<").unwrap(), Token::LessThan),
-        create_regex_parser(Regex::new(r"^>").unwrap(), Token::GreaterThan),
-    ];
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "lex", skip_all))]
+pub fn tokenize(input_str: &str) -> Result<Vec<Token>, LexerError> {
+    Lexer::new(input_str).collect()
+}
+
+/// Tokenizes an input string into `Token`s together with their locations in the input.
+///
+/// # Arguments
+///
+/// * `input_str`: A string slice that represents the code to be tokenized.
+///
+/// # Returns
+///
+/// A `Result` containing the recognized tokens paired with their `Span`s on success, or the
+/// `LexerError` of the first invalid token.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::lexer::tokenize_with_spans;
+/// # use cmm::compiler::lexer::tokens::Token;
+/// # use cmm::compiler::lexer::errors::LexerError;
+/// let tokens = tokenize_with_spans("return\n  12;")?;
+/// assert_eq!(tokens[1].0, Token::Constant(12));
+/// assert_eq!((tokens[1].1.start_line, tokens[1].1.start_column), (2, 3));
+/// assert_eq!((tokens[1].1.end_line, tokens[1].1.end_column), (2, 5));
+/// # Ok::<(), LexerError>(())
+/// ```
+pub fn tokenize_with_spans(input_str: &str) -> Result<Vec<(Token, Span)>, LexerError> {
+    Lexer::new(input_str).with_spans().collect()
+}
+
+/// Skips the whitespace and the comments at the start of the input string.
+///
+/// A line comment starts with `//` and runs to the end of its line. A block comment starts with
+/// `/*` and runs to the first `*/`, across any number of lines, so block comments do not nest.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string to skip the whitespace and comments of.
+///
+/// # Returns
+///
+/// The input string from its first token on, or from a block comment without a closing `*/`,
+/// which `lex_token` reports.
+fn skip_whitespace_and_comments(input_str: &str) -> &str {
+    let mut remaining_str = input_str.trim_start();
     loop {
-        string_stream = string_stream.trim_start().to_string();
-        if string_stream.is_empty() {
-            break;
-        }
-        for parser in parsers.iter() {
-            if let Ok((remaining_str, token)) = parser(&string_stream) {
-                token_vec.push(token);
-                string_stream = remaining_str;
-                continue;
-            }
+        if let Some(comment) = remaining_str.strip_prefix("//") {
+            remaining_str = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(end) = remaining_str
+            .strip_prefix("/*")
+            .and_then(|comment| comment.find("*/"))
+        {
+            remaining_str = &remaining_str[end + 4..];
+        } else {
+            return remaining_str;
         }
+        remaining_str = remaining_str.trim_start();
+    }
+}
+
+/// Creates the error of a block comment without a closing `*/`, which reports the comment up to
+/// the end of its first line.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string, starting with the `/*` of the comment.
+fn unterminated_comment(input_str: &str) -> LexerError {
+    let line_end = input_str.find('\n').unwrap_or(input_str.len());
+    LexerError::UnterminatedComment {
+        found: input_str[..line_end].to_string(),
+    }
+}
+
+/// Returns the text of the input an error reports, which starts where the failed token starts,
+/// or the empty string for an error without one.
+fn reported_text(error: &LexerError) -> &str {
+    match error {
+        LexerError::NonmatchingPattern { found }
+        | LexerError::InvalidConstant { found }
+        | LexerError::InvalidCharConstant { found }
+        | LexerError::InvalidStringLiteral { found }
+        | LexerError::ExtensionNotEnabled { found }
+        | LexerError::UnterminatedComment { found } => found,
+        LexerError::UnexpectedCharacter { .. }
+        | LexerError::EmptyInputString
+        | LexerError::Located { .. } => "",
     }
-    token_vec
 }
 
-/// Creates a new lexer parser based on a regex pattern.
+/// Returns the first character of the input string, or the empty string for an empty input.
+fn first_character(input_str: &str) -> &str {
+    let length = input_str.chars().next().map_or(0, char::len_utf8);
+    &input_str[..length]
+}
+
+/// Returns the length of the leading run of word characters (ASCII letters, digits, and underscores).
+fn word_length(input_str: &str) -> usize {
+    input_str
+        .bytes()
+        .take_while(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+        .count()
+}
+
+/// Attempts to parse a punctuator from the input string.
+///
+/// Longer punctuators are matched before the shorter punctuators they start with, so the longest
+/// punctuator always wins.
 ///
 /// # Arguments
 ///
-/// * `pattern`: The regex pattern to match.
-/// * `token`: The token to return if the pattern matches.
+/// * `input_str`: The input string to parse.
 ///
 /// # Returns
 ///
-/// A new lexer parser that matches the pattern and returns the given token.
-fn create_regex_parser(pattern: Regex, token: Token) -> LexerParser {
-    Box::new(move |input_str: &str| {
-        match pattern.captures(input_str) {
-            Some(matched) => {
-                let matched_str = &matched[0];
-                // Strip the matched prefix to get the remaining string
-                let remaining_str = input_str.strip_prefix(matched_str).unwrap().to_string();
-
-                // Clone the token because the closure captures it by value
-                // but needs to return it multiple times across different calls.
-                Ok((remaining_str, token.clone()))
-            }
-            None => Err(LexerError::NonmatchingPattern {
-                found: input_str.to_string(),
-            }),
+/// On successful parsing, return a tuple of remaining input string and the parsed punctuator.
+/// On failure, returns a non-matching pattern error.
+fn parse_punctuator(input_str: &str) -> LexerParseResult<'_, Token> {
+    let bytes = input_str.as_bytes();
+    let (token, length) = match (bytes.first(), bytes.get(1), bytes.get(2)) {
+        // Three character tokens
+        (Some(b'<'), Some(b'<'), Some(b'=')) => (Token::DoubleLessThanEqual, 3),
+        (Some(b'>'), Some(b'>'), Some(b'=')) => (Token::DoubleGreaterThanEqual, 3),
+        (Some(b'.'), Some(b'.'), Some(b'.')) => (Token::Ellipsis, 3),
+        // Two character tokens
+        (Some(b'-'), Some(b'-'), _) => (Token::DoubleHyphen, 2),
+        (Some(b'-'), Some(b'>'), _) => (Token::Arrow, 2),
+        (Some(b'+'), Some(b'+'), _) => (Token::DoublePlus, 2),
+        (Some(b'&'), Some(b'&'), _) => (Token::DoubleAmpersand, 2),
+        (Some(b'|'), Some(b'|'), _) => (Token::DoublePipe, 2),
+        (Some(b'='), Some(b'='), _) => (Token::DoubleEqual, 2),
+        (Some(b'!'), Some(b'='), _) => (Token::ExclamationEqual, 2),
+        (Some(b'<'), Some(b'='), _) => (Token::LessThanEqual, 2),
+        (Some(b'>'), Some(b'='), _) => (Token::GreaterThanEqual, 2),
+        (Some(b'<'), Some(b'<'), _) => (Token::DoubleLessThan, 2),
+        (Some(b'>'), Some(b'>'), _) => (Token::DoubleGreaterThan, 2),
+        (Some(b'+'), Some(b'='), _) => (Token::PlusEqual, 2),
+        (Some(b'-'), Some(b'='), _) => (Token::HyphenEqual, 2),
+        (Some(b'*'), Some(b'='), _) => (Token::AsteriskEqual, 2),
+        (Some(b'/'), Some(b'='), _) => (Token::ForwardSlashEqual, 2),
+        (Some(b'%'), Some(b'='), _) => (Token::PercentEqual, 2),
+        (Some(b'&'), Some(b'='), _) => (Token::AmpersandEqual, 2),
+        (Some(b'|'), Some(b'='), _) => (Token::PipeEqual, 2),
+        (Some(b'^'), Some(b'='), _) => (Token::CaretEqual, 2),
+        // Single character tokens
+        (Some(b'-'), _, _) => (Token::Hyphen, 1),
+        (Some(b'~'), _, _) => (Token::Tilde, 1),
+        (Some(b'('), _, _) => (Token::OpenParen, 1),
+        (Some(b')'), _, _) => (Token::CloseParen, 1),
+        (Some(b'{'), _, _) => (Token::OpenBrace, 1),
+        (Some(b'}'), _, _) => (Token::CloseBrace, 1),
+        (Some(b'['), _, _) => (Token::OpenBracket, 1),
+        (Some(b']'), _, _) => (Token::CloseBracket, 1),
+        (Some(b';'), _, _) => (Token::Semicolon, 1),
+        (Some(b','), _, _) => (Token::Comma, 1),
+        (Some(b'.'), _, _) => (Token::Period, 1),
+        (Some(b'+'), _, _) => (Token::Plus, 1),
+        (Some(b'*'), _, _) => (Token::Asterisk, 1),
+        (Some(b'/'), _, _) => (Token::ForwardSlash, 1),
+        (Some(b'%'), _, _) => (Token::Percent, 1),
+        (Some(b'!'), _, _) => (Token::ExclamationMark, 1),
+        (Some(b'<'), _, _) => (Token::LessThan, 1),
+        (Some(b'>'), _, _) => (Token::GreaterThan, 1),
+        (Some(b'&'), _, _) => (Token::Ampersand, 1),
+        (Some(b'|'), _, _) => (Token::Pipe, 1),
+        (Some(b'^'), _, _) => (Token::Caret, 1),
+        (Some(b'='), _, _) => (Token::Equal, 1),
+        _ => {
+            return Err(LexerError::NonmatchingPattern {
+                found: first_character(input_str).to_string(),
+            });
         }
-    })
+    };
+    Ok((&input_str[length..], token))
 }
 
 /// Attempts to parse an identifier or keyword from the input string.
@@ -135,89 +439,719 @@ fn create_regex_parser(pattern: Regex, token: Token) -> LexerParser {
 /// # Arguments
 ///
 /// * `input_str`: The input string to parse.
+/// * `interner`: The `Interner` the identifier is interned in.
 ///
 /// # Returns
 ///
 /// On successful parsing, return a tuple of remaining input string and the parsed identifier or keyword.
 /// On failure, returns a non-matching pattern error.
-fn parse_identifier_or_keyword(input_str: &str) -> LexerParseResult<Token> {
-    static PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-zA-Z_]\w*\b").unwrap());
-    match PATTERN.captures(input_str) {
-        Some(matched) => {
-            let matched_str = &matched[0];
-            let remaining_str = input_str.strip_prefix(matched_str).unwrap().to_string();
-            let token = match matched_str {
-                "int" => Token::IntKeyword,
-                "void" => Token::VoidKeyword,
-                "return" => Token::ReturnKeyword,
-                _ => Token::Identifier(matched_str.to_string()),
-            };
-            Ok((remaining_str, token))
-        }
-        None => Err(LexerError::NonmatchingPattern {
-            found: input_str.to_string(),
-        }),
+fn parse_identifier_or_keyword<'a>(
+    input_str: &'a str,
+    interner: &mut Interner,
+) -> LexerParseResult<'a, Token> {
+    let starts_word = input_str
+        .bytes()
+        .next()
+        .is_some_and(|byte| byte.is_ascii_alphabetic() || byte == b'_');
+    if !starts_word {
+        return Err(LexerError::NonmatchingPattern {
+            found: first_character(input_str).to_string(),
+        });
     }
+    let (matched_str, remaining_str) = input_str.split_at(word_length(input_str));
+    let token = match matched_str {
+        "int" => Token::IntKeyword,
+        "unsigned" => Token::UnsignedKeyword,
+        "signed" => Token::SignedKeyword,
+        "long" => Token::LongKeyword,
+        "short" => Token::ShortKeyword,
+        "char" => Token::CharKeyword,
+        "_Bool" => Token::BoolKeyword,
+        "double" => Token::DoubleKeyword,
+        "void" => Token::VoidKeyword,
+        "return" => Token::ReturnKeyword,
+        "if" => Token::IfKeyword,
+        "else" => Token::ElseKeyword,
+        "while" => Token::WhileKeyword,
+        "do" => Token::DoKeyword,
+        "for" => Token::ForKeyword,
+        "break" => Token::BreakKeyword,
+        "continue" => Token::ContinueKeyword,
+        "static" => Token::StaticKeyword,
+        "extern" => Token::ExternKeyword,
+        "struct" => Token::StructKeyword,
+        "enum" => Token::EnumKeyword,
+        "const" => Token::ConstKeyword,
+        // Repeated identifiers share the allocation of their first occurrence.
+        _ => Token::Identifier(interner.intern(matched_str)),
+    };
+    Ok((remaining_str, token))
 }
 
 /// Attempts to parse a constant integer from the input string.
 ///
+/// The constant is decimal, hexadecimal with a `0x` or `0X` prefix, such as `0x1F`, or octal with
+/// a leading `0`, such as `0755`. With the GNU extensions, it may also be binary with a `0b` or
+/// `0B` prefix, such as `0b1010`. A `u` suffix makes it an `unsigned int` and an `l` suffix a
+/// `long`, in either case, and both suffixes an `unsigned long`. Like in C, a constant with a `u`
+/// suffix that is too large for an `unsigned int` is an `unsigned long`, and so is a hexadecimal,
+/// octal, or binary constant that is too large for a `long`. Such a constant without a suffix that
+/// is too large for an `int` but fits into an `unsigned int` is an `unsigned int`.
+///
 /// # Arguments
 ///
-/// * `input_str`: The input string to parse, must be in decimal format.
+/// * `input_str`: The input string to parse, must start with a digit.
+/// * `gnu_extensions`: Whether binary constants are accepted.
 ///
 /// # Returns
 ///
 /// On successful parsing, return a tuple of remaining input string and the parsed constant integer.
+/// On failure, returns a non-matching pattern error for a decimal constant followed by other
+/// characters, such as `123abc`, an invalid constant error for a constant that does not fit into
+/// 64 bits, a decimal constant without a `u` suffix that does not fit into a `long`, or a malformed
+/// prefixed constant, such as `0x` or `09`, or an extension error for a binary constant without
+/// the GNU extensions.
+fn parse_constant(input_str: &str, gnu_extensions: bool) -> LexerParseResult<'_, Token> {
+    let bytes = input_str.as_bytes();
+    let (radix, prefix_length) = match bytes {
+        [b'0', b'x' | b'X', ..] => (16, 2),
+        [b'0', b'b' | b'B', ..] => (2, 2),
+        [b'0', b'0'..=b'9', ..] => (8, 1),
+        _ => (10, 0),
+    };
+    // Octal and binary constants take all decimal digits, so that `09` is reported as a whole.
+    let digit_count = bytes[prefix_length..]
+        .iter()
+        .take_while(|byte| match radix {
+            16 => byte.is_ascii_hexdigit(),
+            _ => byte.is_ascii_digit(),
+        })
+        .count();
+    let length = prefix_length + digit_count;
+    let suffix_length = integer_suffix_length(&bytes[length..]);
+    // A constant must end at a word boundary, e.g. `123abc` is not a constant.
+    let word_length = word_length(input_str);
+    if radix == 2 && !gnu_extensions {
+        return Err(LexerError::ExtensionNotEnabled {
+            found: input_str[..word_length].to_string(),
+        });
+    }
+    if digit_count == 0 || word_length != length + suffix_length {
+        if radix == 10 {
+            return Err(LexerError::NonmatchingPattern {
+                found: input_str[..word_length].to_string(),
+            });
+        }
+        return Err(LexerError::InvalidConstant {
+            found: input_str[..word_length].to_string(),
+        });
+    }
+    let (matched_str, remaining_str) = input_str.split_at(length);
+    let invalid_constant = || LexerError::InvalidConstant {
+        found: matched_str.to_string(),
+    };
+    let parsed_int = u64::from_str_radix(&matched_str[prefix_length..], radix)
+        .map_err(|_| invalid_constant())?;
+    let suffix = remaining_str[..suffix_length].to_ascii_lowercase();
+    let token = match (suffix.contains('u'), suffix.contains('l')) {
+        (true, false) if parsed_int <= u64::from(u32::MAX) => {
+            Token::UnsignedConstant(parsed_int as u32)
+        }
+        (true, _) => Token::UnsignedLongConstant(parsed_int),
+        (false, false) if radix != 10 && (1 << 31..1 << 32).contains(&parsed_int) => {
+            Token::UnsignedConstant(parsed_int as u32)
+        }
+        (false, is_long) => match i64::try_from(parsed_int) {
+            Ok(value) if is_long => Token::LongConstant(value),
+            Ok(value) => Token::Constant(value),
+            Err(_) if radix != 10 => Token::UnsignedLongConstant(parsed_int),
+            Err(_) => return Err(invalid_constant()),
+        },
+    };
+    Ok((&remaining_str[suffix_length..], token))
+}
+
+/// Returns the length of the integer suffix at the start of the bytes, which is `u`, `l`, `ul`, or
+/// `lu` in either case, or zero without a suffix.
+fn integer_suffix_length(bytes: &[u8]) -> usize {
+    match bytes {
+        [b'u' | b'U', b'l' | b'L', ..] | [b'l' | b'L', b'u' | b'U', ..] => 2,
+        [b'u' | b'U' | b'l' | b'L', ..] => 1,
+        _ => 0,
+    }
+}
+
+/// Returns `true` if the constant at the start of the input string is a floating-point constant.
+///
+/// The character after the leading decimal digits decides: a decimal point or an exponent starts
+/// a floating-point constant, and anything else ends an integer constant. Hexadecimal and binary
+/// constants are always integers, so the `e` of `0x1e5` is a digit rather than an exponent.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string, must start with a digit or a period.
+fn is_double_constant(input_str: &str) -> bool {
+    let bytes = input_str.as_bytes();
+    if matches!(bytes, [b'0', b'x' | b'X' | b'b' | b'B', ..]) {
+        return false;
+    }
+    let digit_count = bytes
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    matches!(bytes.get(digit_count), Some(b'.' | b'e' | b'E'))
+}
+
+/// Attempts to parse a floating-point constant from the input string.
+///
+/// A floating-point constant has digits with a decimal point, an exponent, or both, such as `1.5`,
+/// `1.`, `.5`, `1e3`, or `2.5E-3`. Digits without either are an integer constant.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string to parse.
+///
+/// # Returns
+///
+/// On successful parsing, return a tuple of remaining input string and the parsed constant.
 /// On failure, returns a non-matching pattern error.
-fn parse_constant(input_str: &str) -> LexerParseResult<Token> {
-    static PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9]+\b").unwrap());
-    match PATTERN.captures(input_str) {
-        Some(matched) => {
-            let matched_str = &matched[0];
-            let remaining_str = input_str.strip_prefix(matched_str).unwrap().to_string();
-            let parsed_int =
-                matched_str
-                    .parse::<i32>()
-                    .map_err(|_| LexerError::InvalidConstant {
-                        found: matched_str.to_string(),
-                    })?;
-            let token = Token::Constant(parsed_int);
-            Ok((remaining_str, token))
+fn parse_double_constant(input_str: &str) -> LexerParseResult<'_, Token> {
+    let bytes = input_str.as_bytes();
+    let count_digits = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+    let integer_digits = count_digits(0);
+    let mut length = integer_digits;
+    let mut mantissa_digits = integer_digits;
+    let has_point = bytes.get(length) == Some(&b'.');
+    if has_point {
+        let fraction_digits = count_digits(length + 1);
+        length += 1 + fraction_digits;
+        mantissa_digits += fraction_digits;
+    }
+    let mut has_exponent = false;
+    if mantissa_digits > 0 && matches!(bytes.get(length), Some(b'e' | b'E')) {
+        let sign_length = usize::from(matches!(bytes.get(length + 1), Some(b'+' | b'-')));
+        let exponent_digits = count_digits(length + 1 + sign_length);
+        if exponent_digits > 0 {
+            length += 1 + sign_length + exponent_digits;
+            has_exponent = true;
+        }
+    }
+    // A constant must end at a word boundary, e.g. `1.5x` and `1.2.3` are not constants.
+    let ends_word = bytes
+        .get(length)
+        .is_none_or(|byte| !byte.is_ascii_alphanumeric() && *byte != b'_' && *byte != b'.');
+    if mantissa_digits == 0 || !(has_point || has_exponent) || !ends_word {
+        // Reports the constant up to the end of its word, e.g. all of `1.2.3`.
+        let end = length
+            + bytes[length..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'.'))
+                .count();
+        return Err(LexerError::NonmatchingPattern {
+            found: input_str[..end].to_string(),
+        });
+    }
+    let (matched_str, remaining_str) = input_str.split_at(length);
+    let parsed_double = matched_str
+        .parse::<f64>()
+        .map_err(|_| LexerError::InvalidConstant {
+            found: matched_str.to_string(),
+        })?;
+    Ok((remaining_str, Token::DoubleConstant(parsed_double)))
+}
+
+/// Attempts to parse a character constant from the input string.
+///
+/// A character constant is a single printable ASCII character other than a quote or a backslash,
+/// or one of the simple escape sequences of C, such as `\n` or `\'`, between single quotes.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string to parse, must start with a single quote.
+///
+/// # Returns
+///
+/// On successful parsing, return a tuple of remaining input string and the parsed character constant.
+/// On failure, returns an invalid character constant error.
+fn parse_char_constant(input_str: &str) -> LexerParseResult<'_, Token> {
+    let bytes = input_str.as_bytes();
+    let (character, length) = match (bytes.get(1), bytes.get(2)) {
+        (Some(b'\\'), Some(escaped)) => (escape_sequence_value(*escaped), 3),
+        (Some(character), _) if (b' '..=b'~').contains(character) && *character != b'\'' => {
+            (Some(*character), 2)
+        }
+        _ => (None, 1),
+    };
+    match (character, bytes.get(length)) {
+        (Some(character), Some(b'\'')) => {
+            Ok((&input_str[length + 1..], Token::CharConstant(character)))
+        }
+        _ => {
+            // Reports the constant up to its closing quote, or up to the end of its line if the
+            // quote is missing.
+            let end = input_str[1..]
+                .find(['\'', '\n'])
+                .map_or(input_str.len(), |position| position + 1);
+            let end = if input_str[end..].starts_with('\'') {
+                end + 1
+            } else {
+                end
+            };
+            Err(LexerError::InvalidCharConstant {
+                found: input_str[..end].to_string(),
+            })
+        }
+    }
+}
+
+/// Attempts to parse a string literal from the input string.
+///
+/// A string literal holds any characters other than a double quote, a backslash, or a newline,
+/// and the same escape sequences as a character constant, between double quotes. Characters
+/// outside of ASCII are kept as their UTF-8 bytes.
+///
+/// # Arguments
+///
+/// * `input_str`: The input string to parse, must start with a double quote.
+///
+/// # Returns
+///
+/// On successful parsing, return a tuple of remaining input string and the parsed string literal,
+/// holding the bytes of the string without a terminating null byte.
+/// On failure, returns an invalid string literal error.
+fn parse_string_literal(input_str: &str) -> LexerParseResult<'_, Token> {
+    let bytes = input_str.as_bytes();
+    let mut value = Vec::new();
+    let mut position = 1;
+    loop {
+        let byte = match (bytes.get(position), bytes.get(position + 1)) {
+            (Some(b'"'), _) => {
+                return Ok((&input_str[position + 1..], Token::StringLiteral(value)));
+            }
+            (Some(b'\\'), Some(escaped)) => {
+                position += 2;
+                escape_sequence_value(*escaped)
+            }
+            (Some(byte), _) if *byte != b'\\' && *byte != b'\n' => {
+                position += 1;
+                Some(*byte)
+            }
+            _ => None,
+        };
+        match byte {
+            Some(byte) => value.push(byte),
+            None => {
+                // Reports the literal up to its closing quote, or up to the end of its line if the
+                // quote is missing.
+                let end = bytes[position..]
+                    .iter()
+                    .position(|byte| matches!(byte, b'"' | b'\n'))
+                    .map_or(input_str.len(), |offset| position + offset);
+                let end = if input_str[end..].starts_with('"') {
+                    end + 1
+                } else {
+                    end
+                };
+                return Err(LexerError::InvalidStringLiteral {
+                    found: input_str[..end].to_string(),
+                });
+            }
         }
-        None => Err(LexerError::NonmatchingPattern {
-            found: input_str.to_string(),
-        }),
+    }
+}
+
+/// Returns the value of a simple escape sequence of C.
+///
+/// # Arguments
+///
+/// * `escaped`: The character following the backslash, e.g. `n` for `\n`.
+///
+/// # Returns
+///
+/// The byte the escape sequence stands for, or `None` for an unknown escape sequence.
+fn escape_sequence_value(escaped: u8) -> Option<u8> {
+    match escaped {
+        b'\'' | b'"' | b'?' | b'\\' => Some(escaped),
+        b'a' => Some(0x07),
+        b'b' => Some(0x08),
+        b'f' => Some(0x0c),
+        b'n' => Some(b'\n'),
+        b'r' => Some(b'\r'),
+        b't' => Some(b'\t'),
+        b'v' => Some(0x0b),
+        b'0' => Some(0),
+        _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::symbol::Symbol;
+
+    /// Tokenizes an input string like `tokenize`, but drops the location of an error.
+    fn tokenize_unlocated(input_str: &str) -> Result<Vec<Token>, LexerError> {
+        tokenize(input_str).map_err(|error| match error {
+            LexerError::Located { error, .. } => *error,
+            error => error,
+        })
+    }
+
+    /// Wraps an error with the location between two 1-based line and column pairs.
+    fn located(error: LexerError, start: (usize, usize), end: (usize, usize)) -> LexerError {
+        LexerError::Located {
+            error: Box::new(error),
+            span: Span {
+                start_line: start.0,
+                start_column: start.1,
+                end_line: end.0,
+                end_column: end.1,
+            },
+        }
+    }
 
     #[test]
     fn test_parse_valid_constant_only() {
         let input = "123";
-        let result = parse_constant(input);
+        let result = parse_constant(input, false);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from(""), Token::Constant(123)));
+        assert_eq!(result.unwrap(), ("", Token::Constant(123)));
+    }
+
+    #[test]
+    fn test_parse_long_constant() {
+        assert_eq!(
+            parse_constant("5l;", false),
+            Ok((";", Token::LongConstant(5)))
+        );
+        assert_eq!(
+            parse_constant("4294967296L)", false),
+            Ok((")", Token::LongConstant(4294967296)))
+        );
+        assert!(parse_constant("5ll", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_unsigned_constants() {
+        assert_eq!(
+            parse_constant("5u;", false),
+            Ok((";", Token::UnsignedConstant(5)))
+        );
+        assert_eq!(
+            parse_constant("4294967295U)", false),
+            Ok((")", Token::UnsignedConstant(4294967295)))
+        );
+        assert_eq!(
+            parse_constant("0xFFFFFFFF", false),
+            Ok(("", Token::UnsignedConstant(4294967295)))
+        );
+        assert_eq!(
+            parse_constant("020000000000", false),
+            Ok(("", Token::UnsignedConstant(2147483648)))
+        );
+        assert_eq!(
+            parse_constant("0x7FFFFFFF", false),
+            Ok(("", Token::Constant(2147483647)))
+        );
+        assert_eq!(
+            parse_constant("0x100000000", false),
+            Ok(("", Token::Constant(4294967296)))
+        );
+        assert_eq!(
+            parse_constant("2147483648", false),
+            Ok(("", Token::Constant(2147483648)))
+        );
+        for (input, value) in [
+            ("5ul", 5),
+            ("5LU", 5),
+            ("10UL", 10),
+            ("1lu", 1),
+            ("4294967296u", 4294967296),
+            ("18446744073709551615UL", 18446744073709551615),
+            ("0xFFFFFFFFFFFFFFFF", 18446744073709551615),
+            ("01000000000000000000000", 9223372036854775808),
+        ] {
+            assert_eq!(
+                parse_constant(input, false),
+                Ok(("", Token::UnsignedLongConstant(value))),
+                "{input}"
+            );
+        }
+        for input in ["9223372036854775808", "9223372036854775808l"] {
+            assert_eq!(
+                parse_constant(input, false),
+                Err(LexerError::InvalidConstant {
+                    found: "9223372036854775808".to_string()
+                }),
+                "{input}"
+            );
+        }
+        assert!(parse_constant("18446744073709551616u", false).is_err());
+        assert!(parse_constant("5uu", false).is_err());
+        assert!(parse_constant("5ulx", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_hexadecimal_and_octal_constants() {
+        assert_eq!(
+            parse_constant("0x1F;", false),
+            Ok((";", Token::Constant(31)))
+        );
+        assert_eq!(
+            parse_constant("0XabC", false),
+            Ok(("", Token::Constant(2748)))
+        );
+        assert_eq!(
+            parse_constant("0xFFFFFFFFl)", false),
+            Ok((")", Token::LongConstant(4294967295)))
+        );
+        assert_eq!(
+            parse_constant("0755 ", false),
+            Ok((" ", Token::Constant(493)))
+        );
+        assert_eq!(parse_constant("00", false), Ok(("", Token::Constant(0))));
+        assert_eq!(parse_constant("0", false), Ok(("", Token::Constant(0))));
+        assert_eq!(
+            parse_constant("017L", false),
+            Ok(("", Token::LongConstant(15)))
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_hexadecimal_and_octal_constants() {
+        for (input, found) in [
+            ("0x;", "0x"),
+            ("0X", "0X"),
+            ("0x1g + 1", "0x1g"),
+            ("09", "09"),
+            ("0758)", "0758"),
+            ("0x10000000000000000", "0x10000000000000000"),
+        ] {
+            assert_eq!(
+                parse_constant(input, false),
+                Err(LexerError::InvalidConstant {
+                    found: found.to_string()
+                }),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            tokenize_unlocated("return 0x;"),
+            Err(LexerError::InvalidConstant {
+                found: "0x".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_binary_constants() {
+        assert_eq!(
+            parse_constant("0b1010;", true),
+            Ok((";", Token::Constant(10)))
+        );
+        assert_eq!(
+            parse_constant("0B11L", true),
+            Ok(("", Token::LongConstant(3)))
+        );
+        for (input, found) in [("0b;", "0b"), ("0b102", "0b102")] {
+            assert_eq!(
+                parse_constant(input, true),
+                Err(LexerError::InvalidConstant {
+                    found: found.to_string()
+                }),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            tokenize_unlocated("return 0b1010;"),
+            Err(LexerError::ExtensionNotEnabled {
+                found: "0b1010".to_string()
+            })
+        );
+        assert_eq!(
+            Lexer::new("return 0b101;")
+                .with_gnu_extensions(true)
+                .collect::<Result<Vec<_>, _>>(),
+            Ok(vec![
+                Token::ReturnKeyword,
+                Token::Constant(5),
+                Token::Semicolon
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_double_constant() {
+        assert_eq!(
+            parse_double_constant("1.5;"),
+            Ok((";", Token::DoubleConstant(1.5)))
+        );
+        assert_eq!(
+            parse_double_constant("1. "),
+            Ok((" ", Token::DoubleConstant(1.0)))
+        );
+        assert_eq!(
+            parse_double_constant(".25)"),
+            Ok((")", Token::DoubleConstant(0.25)))
+        );
+        assert_eq!(
+            parse_double_constant("1e3"),
+            Ok(("", Token::DoubleConstant(1000.0)))
+        );
+        assert_eq!(
+            parse_double_constant("2.5E-1+x"),
+            Ok(("+x", Token::DoubleConstant(0.25)))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_double_constant() {
+        for (input, found) in [
+            ("123", "123"),
+            (".", "."),
+            ("1e", "1e"),
+            ("1.5x", "1.5x"),
+            ("1.2.3;", "1.2.3"),
+            ("1e+", "1e"),
+        ] {
+            assert_eq!(
+                parse_double_constant(input),
+                Err(LexerError::NonmatchingPattern {
+                    found: found.to_string()
+                })
+            );
+        }
+        assert_eq!(
+            tokenize("1.5 + 2"),
+            Ok(vec![
+                Token::DoubleConstant(1.5),
+                Token::Plus,
+                Token::Constant(2)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_double_constants_are_told_apart_by_their_digits() {
+        for input in ["1.5", "1.", ".5", "1e3", "0755.5", "12E-1"] {
+            assert!(is_double_constant(input), "{input}");
+        }
+        for input in ["1", "123;", "0x1e5", "0b1", "10l", "1 . 5"] {
+            assert!(!is_double_constant(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_parse_char_constant() {
+        assert_eq!(
+            parse_char_constant("'a';"),
+            Ok((";", Token::CharConstant(b'a')))
+        );
+        assert_eq!(
+            parse_char_constant("'\\n')"),
+            Ok((")", Token::CharConstant(b'\n')))
+        );
+        assert_eq!(
+            parse_char_constant("'\\''"),
+            Ok(("", Token::CharConstant(b'\'')))
+        );
+        assert_eq!(
+            parse_char_constant("'\\0'"),
+            Ok(("", Token::CharConstant(0)))
+        );
+        assert_eq!(
+            parse_char_constant("'\"'"),
+            Ok(("", Token::CharConstant(b'"')))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_char_constant() {
+        for (input, found) in [
+            ("'';", "''"),
+            ("'ab';", "'ab'"),
+            ("'\\q';", "'\\q'"),
+            ("'a\nb';", "'a"),
+            ("'", "'"),
+            ("'é';", "'é'"),
+        ] {
+            assert_eq!(
+                parse_char_constant(input),
+                Err(LexerError::InvalidCharConstant {
+                    found: found.to_string()
+                }),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            tokenize_unlocated("return 'ab';"),
+            Err(LexerError::InvalidCharConstant {
+                found: "'ab'".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_string_literal() {
+        assert_eq!(
+            parse_string_literal("\"hello\";"),
+            Ok((";", Token::StringLiteral(b"hello".to_vec())))
+        );
+        assert_eq!(
+            parse_string_literal("\"\")"),
+            Ok((")", Token::StringLiteral(Vec::new())))
+        );
+        assert_eq!(
+            parse_string_literal("\"a\\\"b\\n'\\0\" \"c\""),
+            Ok((" \"c\"", Token::StringLiteral(b"a\"b\n'\0".to_vec())))
+        );
+        assert_eq!(
+            parse_string_literal("\"é\""),
+            Ok(("", Token::StringLiteral("é".as_bytes().to_vec())))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_string_literal() {
+        for (input, found) in [
+            ("\"abc", "\"abc"),
+            ("\"ab\ncd\";", "\"ab"),
+            ("\"\\q\";", "\"\\q\""),
+            ("\"\\", "\"\\"),
+        ] {
+            assert_eq!(
+                parse_string_literal(input),
+                Err(LexerError::InvalidStringLiteral {
+                    found: found.to_string()
+                }),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            tokenize_unlocated("return \"ab"),
+            Err(LexerError::InvalidStringLiteral {
+                found: "\"ab".to_string()
+            })
+        );
     }
 
     #[test]
     fn test_parse_invalid_constant() {
         let input = "123;abc";
-        let result = parse_constant(input);
+        let result = parse_constant(input, false);
         assert!(result.is_ok());
-        assert_eq!(
-            result.unwrap(),
-            (String::from(";abc"), Token::Constant(123))
-        );
+        assert_eq!(result.unwrap(), (";abc", Token::Constant(123)));
     }
 
     #[test]
     fn test_parse_valid_constant_with_trailing_characters() {
-        let input = "123abc";
-        let result = parse_constant(input);
+        let input = "123abc + 1;";
+        let result = parse_constant(input, false);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -230,72 +1164,218 @@ mod tests {
     #[test]
     fn test_parse_valid_single_hyphen() {
         let input = "-a";
-        let parser = create_regex_parser(Regex::new(r"^-").unwrap(), Token::Hyphen);
-        let result = parser(input);
+        let result = parse_punctuator(input);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from("a"), Token::Hyphen));
+        assert_eq!(result.unwrap(), ("a", Token::Hyphen));
     }
 
     #[test]
     fn test_parse_valid_double_hyphen() {
         let input = "--a";
-        let parser = create_regex_parser(Regex::new(r"^--").unwrap(), Token::DoubleHyphen);
-        let result = parser(input);
+        let result = parse_punctuator(input);
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from("a"), Token::DoubleHyphen));
+        assert_eq!(result.unwrap(), ("a", Token::DoubleHyphen));
+    }
+
+    #[test]
+    fn test_parse_valid_double_plus() {
+        assert_eq!(parse_punctuator("++a"), Ok(("a", Token::DoublePlus)));
+        assert_eq!(parse_punctuator("+++a"), Ok(("+a", Token::DoublePlus)));
+        assert_eq!(parse_punctuator("+=a"), Ok(("a", Token::PlusEqual)));
     }
 
     #[test]
     fn test_parse_valid_return_keyword() {
         let input = "return 2;";
-        let result = parse_identifier_or_keyword(input);
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from(" 2;"), Token::ReturnKeyword));
+        assert_eq!(result.unwrap(), (" 2;", Token::ReturnKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_control_flow_keywords() {
+        let mut interner = Interner::new();
+        let result = parse_identifier_or_keyword("if(1)", &mut interner);
+        assert_eq!(result.unwrap(), ("(1)", Token::IfKeyword));
+        let result = parse_identifier_or_keyword("else return", &mut interner);
+        assert_eq!(result.unwrap(), (" return", Token::ElseKeyword));
+        let result = parse_identifier_or_keyword("while(1)", &mut interner);
+        assert_eq!(result.unwrap(), ("(1)", Token::WhileKeyword));
+        let result = parse_identifier_or_keyword("do return", &mut interner);
+        assert_eq!(result.unwrap(), (" return", Token::DoKeyword));
+        let result = parse_identifier_or_keyword("for (;;)", &mut interner);
+        assert_eq!(result.unwrap(), (" (;;)", Token::ForKeyword));
+        let result = parse_identifier_or_keyword("break;", &mut interner);
+        assert_eq!(result.unwrap(), (";", Token::BreakKeyword));
+        let result = parse_identifier_or_keyword("continue;", &mut interner);
+        assert_eq!(result.unwrap(), (";", Token::ContinueKeyword));
+        let result = parse_identifier_or_keyword("elsewhere", &mut interner);
+        assert_eq!(
+            result.unwrap(),
+            ("", Token::Identifier(Symbol::from("elsewhere")))
+        );
     }
 
     #[test]
     fn test_parse_valid_void_keyword() {
         let input = "void";
-        let result = parse_identifier_or_keyword(input);
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from(""), Token::VoidKeyword));
+        assert_eq!(result.unwrap(), ("", Token::VoidKeyword));
     }
 
     #[test]
     fn test_parse_valid_int_keyword() {
         let input = "int";
-        let result = parse_identifier_or_keyword(input);
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), (String::from(""), Token::IntKeyword));
+        assert_eq!(result.unwrap(), ("", Token::IntKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_unsigned_keyword() {
+        let input = "unsigned int";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" int", Token::UnsignedKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_signed_keyword() {
+        let input = "signed char";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" char", Token::SignedKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_long_keyword() {
+        let input = "long x";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" x", Token::LongKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_short_keyword() {
+        let input = "short x";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" x", Token::ShortKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_double_keyword() {
+        let input = "double x";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" x", Token::DoubleKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_char_keyword() {
+        let input = "char c";
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
+        assert_eq!(result.unwrap(), (" c", Token::CharKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_bool_keyword() {
+        let mut interner = Interner::new();
+        let result = parse_identifier_or_keyword("_Bool b", &mut interner);
+        assert_eq!(result.unwrap(), (" b", Token::BoolKeyword));
+        let result = parse_identifier_or_keyword("_bool", &mut interner);
+        assert_eq!(
+            result.unwrap(),
+            ("", Token::Identifier(Symbol::from("_bool")))
+        );
+    }
+
+    #[test]
+    fn test_parse_valid_storage_class_keywords() {
+        let mut interner = Interner::new();
+        let result = parse_identifier_or_keyword("static int", &mut interner);
+        assert_eq!(result.unwrap(), (" int", Token::StaticKeyword));
+        let result = parse_identifier_or_keyword("extern int", &mut interner);
+        assert_eq!(result.unwrap(), (" int", Token::ExternKeyword));
+    }
+
+    #[test]
+    fn test_parse_valid_qualifier_keywords() {
+        let mut interner = Interner::new();
+        let result = parse_identifier_or_keyword("const int", &mut interner);
+        assert_eq!(result.unwrap(), (" int", Token::ConstKeyword));
+        let result = parse_identifier_or_keyword("constant", &mut interner);
+        assert_eq!(
+            result.unwrap(),
+            ("", Token::Identifier(Symbol::from("constant")))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_enum_declaration() {
+        assert_eq!(
+            tokenize("enum e { A, B = 5 }; enumerator").unwrap(),
+            vec![
+                Token::EnumKeyword,
+                Token::Identifier(Symbol::from("e")),
+                Token::OpenBrace,
+                Token::Identifier(Symbol::from("A")),
+                Token::Comma,
+                Token::Identifier(Symbol::from("B")),
+                Token::Equal,
+                Token::Constant(5),
+                Token::CloseBrace,
+                Token::Semicolon,
+                Token::Identifier(Symbol::from("enumerator")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_member_access() {
+        assert_eq!(
+            tokenize("struct s a; a.b->c .5").unwrap(),
+            vec![
+                Token::StructKeyword,
+                Token::Identifier(Symbol::from("s")),
+                Token::Identifier(Symbol::from("a")),
+                Token::Semicolon,
+                Token::Identifier(Symbol::from("a")),
+                Token::Period,
+                Token::Identifier(Symbol::from("b")),
+                Token::Arrow,
+                Token::Identifier(Symbol::from("c")),
+                Token::DoubleConstant(0.5),
+            ]
+        );
     }
 
     #[test]
     fn test_parse_valid_identifier() {
         let input = "main";
-        let result = parse_identifier_or_keyword(input);
+        let result = parse_identifier_or_keyword(input, &mut Interner::new());
         assert!(result.is_ok());
         assert_eq!(
             result.unwrap(),
-            (String::from(""), Token::Identifier(input.to_string()))
+            ("", Token::Identifier(Symbol::from(input)))
         );
     }
 
     #[test]
     fn test_parse_invalid_identifier() {
-        assert!(parse_identifier_or_keyword("1_number_first_not_allowed").is_err());
+        assert!(
+            parse_identifier_or_keyword("1_number_first_not_allowed", &mut Interner::new())
+                .is_err()
+        );
     }
 
     #[test]
     fn test_parse_valid_logical_expression() {
         let input = "(a && b)";
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         assert_eq!(
             tokens,
             vec![
                 Token::OpenParen,
-                Token::Identifier(String::from("a")),
+                Token::Identifier(Symbol::from("a")),
                 Token::DoubleAmpersand,
-                Token::Identifier(String::from("b")),
+                Token::Identifier(Symbol::from("b")),
                 Token::CloseParen,
             ]
         );
@@ -304,14 +1384,123 @@ mod tests {
     #[test]
     fn test_parse_valid_comparison_expression() {
         let input = "a >= b";
-        let tokens = tokenize(input);
+        let tokens = tokenize(input).unwrap();
         assert_eq!(
             tokens,
             vec![
-                Token::Identifier(String::from("a")),
+                Token::Identifier(Symbol::from("a")),
                 Token::GreaterThanEqual,
-                Token::Identifier(String::from("b")),
+                Token::Identifier(Symbol::from("b")),
             ]
         );
     }
+
+    #[test]
+    fn test_tokenize_unknown_character() {
+        assert_eq!(
+            tokenize("return @;"),
+            Err(located(
+                LexerError::NonmatchingPattern {
+                    found: "@".to_string()
+                },
+                (1, 8),
+                (1, 9)
+            ))
+        );
+        assert_eq!(
+            tokenize("int x;\n  x = 12ab;"),
+            Err(located(
+                LexerError::NonmatchingPattern {
+                    found: "12ab".to_string()
+                },
+                (2, 7),
+                (2, 11)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_constant_out_of_range() {
+        assert_eq!(
+            tokenize("return 99999999999999999999;"),
+            Err(located(
+                LexerError::InvalidConstant {
+                    found: "99999999999999999999".to_string()
+                },
+                (1, 8),
+                (1, 28)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_skips_comments() {
+        let input = "int/* a\n * block */x // line comment\n= 1 /**/ / 2; // /* no block\n/*/ */";
+        assert_eq!(
+            tokenize(input).unwrap(),
+            vec![
+                Token::IntKeyword,
+                Token::Identifier(Symbol::from("x")),
+                Token::Equal,
+                Token::Constant(1),
+                Token::ForwardSlash,
+                Token::Constant(2),
+                Token::Semicolon,
+            ]
+        );
+        assert_eq!(tokenize("1 // no newline"), Ok(vec![Token::Constant(1)]));
+        assert_eq!(
+            tokenize("\"/* not a comment */\"").unwrap(),
+            vec![Token::StringLiteral(b"/* not a comment */".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_block_comment() {
+        assert_eq!(
+            tokenize("return 1; /* unterminated\n return 2; */ /* again"),
+            Err(located(
+                LexerError::UnterminatedComment {
+                    found: "/* again".to_string()
+                },
+                (2, 15),
+                (2, 23)
+            ))
+        );
+        assert_eq!(
+            tokenize("int x; /* first line\n second line"),
+            Err(located(
+                LexerError::UnterminatedComment {
+                    found: "/* first line".to_string()
+                },
+                (1, 8),
+                (1, 21)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_punctuator_prefers_longest_match() {
+        assert_eq!(parse_punctuator("<=1"), Ok(("1", Token::LessThanEqual)));
+        assert_eq!(parse_punctuator("<1"), Ok(("1", Token::LessThan)));
+        assert_eq!(parse_punctuator("!!"), Ok(("!", Token::ExclamationMark)));
+        assert_eq!(parse_punctuator("==="), Ok(("=", Token::DoubleEqual)));
+        assert_eq!(parse_punctuator("=1"), Ok(("1", Token::Equal)));
+        assert_eq!(
+            parse_punctuator("<<=1"),
+            Ok(("1", Token::DoubleLessThanEqual))
+        );
+        assert_eq!(parse_punctuator("<<1"), Ok(("1", Token::DoubleLessThan)));
+        assert_eq!(parse_punctuator(">>>"), Ok((">", Token::DoubleGreaterThan)));
+        assert_eq!(parse_punctuator("-=1"), Ok(("1", Token::HyphenEqual)));
+        assert_eq!(parse_punctuator("&=&"), Ok(("&", Token::AmpersandEqual)));
+        assert_eq!(parse_punctuator("^1"), Ok(("1", Token::Caret)));
+        assert_eq!(parse_punctuator(",b"), Ok(("b", Token::Comma)));
+        assert_eq!(parse_punctuator("...)"), Ok((")", Token::Ellipsis)));
+        assert_eq!(parse_punctuator("[1]"), Ok(("1]", Token::OpenBracket)));
+        assert_eq!(parse_punctuator("];"), Ok((";", Token::CloseBracket)));
+        assert_eq!(parse_punctuator("->x"), Ok(("x", Token::Arrow)));
+        assert_eq!(parse_punctuator(".x"), Ok(("x", Token::Period)));
+        assert!(parse_punctuator("@").is_err());
+    }
 }
@@ -1,34 +1,95 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::parser::printer::print_string_literal;
 use std::fmt;
 
 /// Represents a token in the C-- language.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
-    Identifier(String),
-    Constant(i32),
+    Identifier(Symbol),
+    /// An integer constant, without its sign. Constants that do not fit into an `int` are `long`s.
+    Constant(i64),
+    /// An integer constant with an `l` or `L` suffix, which is a `long` whatever its value.
+    LongConstant(i64),
+    /// An integer constant of type `unsigned int`, either with a `u` or `U` suffix, or hexadecimal
+    /// or octal and too large for an `int`.
+    UnsignedConstant(u32),
+    /// An integer constant of type `unsigned long`, either with a `u` and an `l` suffix, with a
+    /// `u` suffix and too large for an `unsigned int`, or hexadecimal or octal and too large for a
+    /// `long`.
+    UnsignedLongConstant(u64),
+    /// A character constant such as `'a'` or `'\n'`, holding the ASCII code of the character.
+    CharConstant(u8),
+    /// A floating-point constant such as `1.5`, `.5`, or `1e3`, which is a `double`.
+    DoubleConstant(f64),
+    /// A string literal such as `"hi\n"`, holding the bytes of the string without a terminating
+    /// null byte.
+    StringLiteral(Vec<u8>),
     IntKeyword,
+    UnsignedKeyword,
+    SignedKeyword,
+    LongKeyword,
+    ShortKeyword,
+    CharKeyword,
+    BoolKeyword,
+    DoubleKeyword,
     VoidKeyword,
     ReturnKeyword,
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    DoKeyword,
+    ForKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    StaticKeyword,
+    ExternKeyword,
+    StructKeyword,
+    EnumKeyword,
+    ConstKeyword,
     OpenParen,
     CloseParen,
     OpenBrace,
     CloseBrace,
+    OpenBracket,
+    CloseBracket,
     Semicolon,
+    Comma,
+    Ellipsis,
+    Period,
+    Arrow,
     Tilde,
     Hyphen,
     DoubleHyphen,
     Plus,
+    DoublePlus,
     Asterisk,
     ForwardSlash,
     Percent,
     ExclamationMark,
     DoubleAmpersand,
     DoublePipe,
+    Equal,
     DoubleEqual,
     ExclamationEqual,
     LessThan,
     GreaterThan,
     LessThanEqual,
     GreaterThanEqual,
+    Ampersand,
+    Pipe,
+    Caret,
+    DoubleLessThan,
+    DoubleGreaterThan,
+    PlusEqual,
+    HyphenEqual,
+    AsteriskEqual,
+    ForwardSlashEqual,
+    PercentEqual,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    DoubleLessThanEqual,
+    DoubleGreaterThanEqual,
 }
 
 impl Token {
@@ -44,10 +105,11 @@ impl Token {
     /// # Examples
     ///
     /// ```
+    /// # use cmm::common::symbol::Symbol;
     /// # use cmm::compiler::lexer::tokens::Token;
     /// # use cmm::compiler::lexer::tokens::TokenType;
     ///
-    /// let identifier_token = Token::Identifier(String::from("variable"));
+    /// let identifier_token = Token::Identifier(Symbol::from("variable"));
     /// assert_eq!(identifier_token.kind(), TokenType::Identifier);
     ///
     /// let int_keyword_token = Token::IntKeyword;
@@ -57,30 +119,78 @@ impl Token {
         match self {
             Token::Identifier(_) => TokenType::Identifier,
             Token::Constant(_) => TokenType::Constant,
+            Token::LongConstant(_) => TokenType::LongConstant,
+            Token::UnsignedConstant(_) => TokenType::UnsignedConstant,
+            Token::UnsignedLongConstant(_) => TokenType::UnsignedLongConstant,
+            Token::CharConstant(_) => TokenType::CharConstant,
+            Token::DoubleConstant(_) => TokenType::DoubleConstant,
+            Token::StringLiteral(_) => TokenType::StringLiteral,
             Token::IntKeyword => TokenType::IntKeyword,
+            Token::UnsignedKeyword => TokenType::UnsignedKeyword,
+            Token::SignedKeyword => TokenType::SignedKeyword,
+            Token::LongKeyword => TokenType::LongKeyword,
+            Token::ShortKeyword => TokenType::ShortKeyword,
+            Token::CharKeyword => TokenType::CharKeyword,
+            Token::BoolKeyword => TokenType::BoolKeyword,
+            Token::DoubleKeyword => TokenType::DoubleKeyword,
             Token::VoidKeyword => TokenType::VoidKeyword,
             Token::ReturnKeyword => TokenType::ReturnKeyword,
+            Token::IfKeyword => TokenType::IfKeyword,
+            Token::ElseKeyword => TokenType::ElseKeyword,
+            Token::WhileKeyword => TokenType::WhileKeyword,
+            Token::DoKeyword => TokenType::DoKeyword,
+            Token::ForKeyword => TokenType::ForKeyword,
+            Token::BreakKeyword => TokenType::BreakKeyword,
+            Token::ContinueKeyword => TokenType::ContinueKeyword,
+            Token::StaticKeyword => TokenType::StaticKeyword,
+            Token::ExternKeyword => TokenType::ExternKeyword,
+            Token::StructKeyword => TokenType::StructKeyword,
+            Token::EnumKeyword => TokenType::EnumKeyword,
+            Token::ConstKeyword => TokenType::ConstKeyword,
             Token::OpenParen => TokenType::OpenParen,
             Token::CloseParen => TokenType::CloseParen,
             Token::OpenBrace => TokenType::OpenBrace,
             Token::CloseBrace => TokenType::CloseBrace,
+            Token::OpenBracket => TokenType::OpenBracket,
+            Token::CloseBracket => TokenType::CloseBracket,
             Token::Semicolon => TokenType::Semicolon,
+            Token::Comma => TokenType::Comma,
+            Token::Ellipsis => TokenType::Ellipsis,
+            Token::Period => TokenType::Period,
+            Token::Arrow => TokenType::Arrow,
             Token::Tilde => TokenType::Tilde,
             Token::Hyphen => TokenType::Hyphen,
             Token::DoubleHyphen => TokenType::DoubleHyphen,
             Token::Plus => TokenType::Plus,
+            Token::DoublePlus => TokenType::DoublePlus,
             Token::Asterisk => TokenType::Asterisk,
             Token::ForwardSlash => TokenType::ForwardSlash,
             Token::Percent => TokenType::Percent,
             Token::ExclamationMark => TokenType::ExclamationMark,
             Token::DoubleAmpersand => TokenType::DoubleAmpersand,
             Token::DoublePipe => TokenType::DoublePipe,
+            Token::Equal => TokenType::Equal,
             Token::DoubleEqual => TokenType::DoubleEqual,
             Token::ExclamationEqual => TokenType::ExclamationEqual,
             Token::LessThan => TokenType::LessThan,
             Token::GreaterThan => TokenType::GreaterThan,
             Token::LessThanEqual => TokenType::LessThanEqual,
             Token::GreaterThanEqual => TokenType::GreaterThanEqual,
+            Token::Ampersand => TokenType::Ampersand,
+            Token::Pipe => TokenType::Pipe,
+            Token::Caret => TokenType::Caret,
+            Token::DoubleLessThan => TokenType::DoubleLessThan,
+            Token::DoubleGreaterThan => TokenType::DoubleGreaterThan,
+            Token::PlusEqual => TokenType::PlusEqual,
+            Token::HyphenEqual => TokenType::HyphenEqual,
+            Token::AsteriskEqual => TokenType::AsteriskEqual,
+            Token::ForwardSlashEqual => TokenType::ForwardSlashEqual,
+            Token::PercentEqual => TokenType::PercentEqual,
+            Token::AmpersandEqual => TokenType::AmpersandEqual,
+            Token::PipeEqual => TokenType::PipeEqual,
+            Token::CaretEqual => TokenType::CaretEqual,
+            Token::DoubleLessThanEqual => TokenType::DoubleLessThanEqual,
+            Token::DoubleGreaterThanEqual => TokenType::DoubleGreaterThanEqual,
         }
     }
 
@@ -103,22 +213,27 @@ impl Token {
     /// assert_eq!(token.is_binary_operator(), false);
     /// ```
     pub fn is_binary_operator(&self) -> bool {
-        match self {
-            Token::Plus => true,
-            Token::Hyphen => true,
-            Token::Asterisk => true,
-            Token::ForwardSlash => true,
-            Token::Percent => true,
-            Token::DoubleAmpersand => true,
-            Token::DoublePipe => true,
-            Token::DoubleEqual => true,
-            Token::ExclamationEqual => true,
-            Token::LessThan => true,
-            Token::GreaterThan => true,
-            Token::LessThanEqual => true,
-            Token::GreaterThanEqual => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            Token::Plus
+                | Token::Hyphen
+                | Token::Asterisk
+                | Token::ForwardSlash
+                | Token::Percent
+                | Token::DoubleAmpersand
+                | Token::DoublePipe
+                | Token::DoubleEqual
+                | Token::ExclamationEqual
+                | Token::LessThan
+                | Token::GreaterThan
+                | Token::LessThanEqual
+                | Token::GreaterThanEqual
+                | Token::Ampersand
+                | Token::Pipe
+                | Token::Caret
+                | Token::DoubleLessThan
+                | Token::DoubleGreaterThan
+        )
     }
 
     /// Gets the precedence of a binary operator.
@@ -149,12 +264,17 @@ impl Token {
             Token::Percent => 50,
             Token::Plus => 45,
             Token::Hyphen => 45,
+            Token::DoubleLessThan => 40,
+            Token::DoubleGreaterThan => 40,
             Token::LessThan => 35,
             Token::GreaterThan => 35,
             Token::LessThanEqual => 35,
             Token::GreaterThanEqual => 35,
             Token::DoubleEqual => 30,
             Token::ExclamationEqual => 30,
+            Token::Ampersand => 25,
+            Token::Caret => 20,
+            Token::Pipe => 15,
             Token::DoubleAmpersand => 10,
             Token::DoublePipe => 5,
             _ => return Err(format!("Token {:?} is not a binary operator", self)),
@@ -168,30 +288,82 @@ impl fmt::Display for Token {
         match self {
             Token::Identifier(identifier) => write!(f, "Identifier: {}", identifier),
             Token::Constant(constant) => write!(f, "Constant: {}", constant),
+            Token::LongConstant(constant) => write!(f, "LongConstant: {}", constant),
+            Token::UnsignedConstant(constant) => write!(f, "UnsignedConstant: {}", constant),
+            Token::UnsignedLongConstant(constant) => {
+                write!(f, "UnsignedLongConstant: {}", constant)
+            }
+            Token::CharConstant(constant) => write!(f, "CharConstant: {}", constant),
+            Token::DoubleConstant(constant) => write!(f, "DoubleConstant: {:?}", constant),
+            Token::StringLiteral(value) => {
+                write!(f, "StringLiteral: {}", print_string_literal(value))
+            }
             Token::IntKeyword => write!(f, "IntKeyword"),
+            Token::UnsignedKeyword => write!(f, "UnsignedKeyword"),
+            Token::SignedKeyword => write!(f, "SignedKeyword"),
+            Token::LongKeyword => write!(f, "LongKeyword"),
+            Token::ShortKeyword => write!(f, "ShortKeyword"),
+            Token::CharKeyword => write!(f, "CharKeyword"),
+            Token::BoolKeyword => write!(f, "BoolKeyword"),
+            Token::DoubleKeyword => write!(f, "DoubleKeyword"),
             Token::VoidKeyword => write!(f, "VoidKeyword"),
             Token::ReturnKeyword => write!(f, "ReturnKeyword"),
+            Token::IfKeyword => write!(f, "IfKeyword"),
+            Token::ElseKeyword => write!(f, "ElseKeyword"),
+            Token::WhileKeyword => write!(f, "WhileKeyword"),
+            Token::DoKeyword => write!(f, "DoKeyword"),
+            Token::ForKeyword => write!(f, "ForKeyword"),
+            Token::BreakKeyword => write!(f, "BreakKeyword"),
+            Token::ContinueKeyword => write!(f, "ContinueKeyword"),
+            Token::StaticKeyword => write!(f, "StaticKeyword"),
+            Token::ExternKeyword => write!(f, "ExternKeyword"),
+            Token::StructKeyword => write!(f, "StructKeyword"),
+            Token::EnumKeyword => write!(f, "EnumKeyword"),
+            Token::ConstKeyword => write!(f, "ConstKeyword"),
             Token::OpenParen => write!(f, "OpenParen"),
             Token::CloseParen => write!(f, "CloseParen"),
             Token::OpenBrace => write!(f, "OpenBrace"),
             Token::CloseBrace => write!(f, "CloseBrace"),
+            Token::OpenBracket => write!(f, "OpenBracket"),
+            Token::CloseBracket => write!(f, "CloseBracket"),
             Token::Semicolon => write!(f, "Semicolon"),
+            Token::Comma => write!(f, "Comma"),
+            Token::Ellipsis => write!(f, "Ellipsis"),
+            Token::Period => write!(f, "Period"),
+            Token::Arrow => write!(f, "Arrow"),
             Token::Tilde => write!(f, "Tilde"),
             Token::Hyphen => write!(f, "Hyphen"),
             Token::DoubleHyphen => write!(f, "DoubleHyphen"),
             Token::Plus => write!(f, "Plus"),
+            Token::DoublePlus => write!(f, "DoublePlus"),
             Token::Asterisk => write!(f, "Asterisk"),
             Token::ForwardSlash => write!(f, "ForwardSlash"),
             Token::Percent => write!(f, "Percent"),
             Token::ExclamationMark => write!(f, "ExclamationMark"),
             Token::DoubleAmpersand => write!(f, "DoubleAmpersand"),
             Token::DoublePipe => write!(f, "DoublePipe"),
+            Token::Equal => write!(f, "Equal"),
             Token::DoubleEqual => write!(f, "DoubleEqual"),
             Token::ExclamationEqual => write!(f, "ExclamationEqual"),
             Token::LessThan => write!(f, "LessThan"),
             Token::GreaterThan => write!(f, "GreaterThan"),
             Token::LessThanEqual => write!(f, "LessThanEqual"),
             Token::GreaterThanEqual => write!(f, "GreaterThanEqual"),
+            Token::Ampersand => write!(f, "Ampersand"),
+            Token::Pipe => write!(f, "Pipe"),
+            Token::Caret => write!(f, "Caret"),
+            Token::DoubleLessThan => write!(f, "DoubleLessThan"),
+            Token::DoubleGreaterThan => write!(f, "DoubleGreaterThan"),
+            Token::PlusEqual => write!(f, "PlusEqual"),
+            Token::HyphenEqual => write!(f, "HyphenEqual"),
+            Token::AsteriskEqual => write!(f, "AsteriskEqual"),
+            Token::ForwardSlashEqual => write!(f, "ForwardSlashEqual"),
+            Token::PercentEqual => write!(f, "PercentEqual"),
+            Token::AmpersandEqual => write!(f, "AmpersandEqual"),
+            Token::PipeEqual => write!(f, "PipeEqual"),
+            Token::CaretEqual => write!(f, "CaretEqual"),
+            Token::DoubleLessThanEqual => write!(f, "DoubleLessThanEqual"),
+            Token::DoubleGreaterThanEqual => write!(f, "DoubleGreaterThanEqual"),
         }
     }
 }
@@ -201,30 +373,78 @@ impl fmt::Display for Token {
 pub enum TokenType {
     Identifier,
     Constant,
+    LongConstant,
+    UnsignedConstant,
+    UnsignedLongConstant,
+    CharConstant,
+    DoubleConstant,
+    StringLiteral,
     IntKeyword,
+    UnsignedKeyword,
+    SignedKeyword,
+    LongKeyword,
+    ShortKeyword,
+    CharKeyword,
+    BoolKeyword,
+    DoubleKeyword,
     VoidKeyword,
     ReturnKeyword,
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    DoKeyword,
+    ForKeyword,
+    BreakKeyword,
+    ContinueKeyword,
+    StaticKeyword,
+    ExternKeyword,
+    StructKeyword,
+    EnumKeyword,
+    ConstKeyword,
     OpenParen,
     CloseParen,
     OpenBrace,
     CloseBrace,
+    OpenBracket,
+    CloseBracket,
     Semicolon,
+    Comma,
+    Ellipsis,
+    Period,
+    Arrow,
     Tilde,
     Hyphen,
     DoubleHyphen,
     Plus,
+    DoublePlus,
     Asterisk,
     ForwardSlash,
     Percent,
     ExclamationMark,
     DoubleAmpersand,
     DoublePipe,
+    Equal,
     DoubleEqual,
     ExclamationEqual,
     LessThan,
     GreaterThan,
     LessThanEqual,
     GreaterThanEqual,
+    Ampersand,
+    Pipe,
+    Caret,
+    DoubleLessThan,
+    DoubleGreaterThan,
+    PlusEqual,
+    HyphenEqual,
+    AsteriskEqual,
+    ForwardSlashEqual,
+    PercentEqual,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    DoubleLessThanEqual,
+    DoubleGreaterThanEqual,
 }
 
 impl fmt::Display for TokenType {
@@ -232,30 +452,78 @@ impl fmt::Display for TokenType {
         match self {
             TokenType::Identifier => write!(f, "Identifier"),
             TokenType::Constant => write!(f, "Constant"),
+            TokenType::LongConstant => write!(f, "LongConstant"),
+            TokenType::UnsignedConstant => write!(f, "UnsignedConstant"),
+            TokenType::UnsignedLongConstant => write!(f, "UnsignedLongConstant"),
+            TokenType::CharConstant => write!(f, "CharConstant"),
+            TokenType::DoubleConstant => write!(f, "DoubleConstant"),
+            TokenType::StringLiteral => write!(f, "StringLiteral"),
             TokenType::IntKeyword => write!(f, "IntKeyword"),
+            TokenType::UnsignedKeyword => write!(f, "UnsignedKeyword"),
+            TokenType::SignedKeyword => write!(f, "SignedKeyword"),
+            TokenType::LongKeyword => write!(f, "LongKeyword"),
+            TokenType::ShortKeyword => write!(f, "ShortKeyword"),
+            TokenType::CharKeyword => write!(f, "CharKeyword"),
+            TokenType::BoolKeyword => write!(f, "BoolKeyword"),
+            TokenType::DoubleKeyword => write!(f, "DoubleKeyword"),
             TokenType::VoidKeyword => write!(f, "VoidKeyword"),
             TokenType::ReturnKeyword => write!(f, "ReturnKeyword"),
+            TokenType::IfKeyword => write!(f, "IfKeyword"),
+            TokenType::ElseKeyword => write!(f, "ElseKeyword"),
+            TokenType::WhileKeyword => write!(f, "WhileKeyword"),
+            TokenType::DoKeyword => write!(f, "DoKeyword"),
+            TokenType::ForKeyword => write!(f, "ForKeyword"),
+            TokenType::BreakKeyword => write!(f, "BreakKeyword"),
+            TokenType::ContinueKeyword => write!(f, "ContinueKeyword"),
+            TokenType::StaticKeyword => write!(f, "StaticKeyword"),
+            TokenType::ExternKeyword => write!(f, "ExternKeyword"),
+            TokenType::StructKeyword => write!(f, "StructKeyword"),
+            TokenType::EnumKeyword => write!(f, "EnumKeyword"),
+            TokenType::ConstKeyword => write!(f, "ConstKeyword"),
             TokenType::OpenParen => write!(f, "OpenParen"),
             TokenType::CloseParen => write!(f, "CloseParen"),
             TokenType::OpenBrace => write!(f, "OpenBrace"),
             TokenType::CloseBrace => write!(f, "CloseBrace"),
+            TokenType::OpenBracket => write!(f, "OpenBracket"),
+            TokenType::CloseBracket => write!(f, "CloseBracket"),
             TokenType::Semicolon => write!(f, "Semicolon"),
+            TokenType::Comma => write!(f, "Comma"),
+            TokenType::Ellipsis => write!(f, "Ellipsis"),
+            TokenType::Period => write!(f, "Period"),
+            TokenType::Arrow => write!(f, "Arrow"),
             TokenType::Tilde => write!(f, "Tilde"),
             TokenType::Hyphen => write!(f, "Hyphen"),
             TokenType::DoubleHyphen => write!(f, "DoubleHyphen"),
             TokenType::Plus => write!(f, "Plus"),
+            TokenType::DoublePlus => write!(f, "DoublePlus"),
             TokenType::Asterisk => write!(f, "Asterisk"),
             TokenType::ForwardSlash => write!(f, "ForwardSlash"),
             TokenType::Percent => write!(f, "Percent"),
             TokenType::ExclamationMark => write!(f, "ExclamationMark"),
             TokenType::DoubleAmpersand => write!(f, "DoubleAmpersand"),
             TokenType::DoublePipe => write!(f, "DoublePipe"),
+            TokenType::Equal => write!(f, "Equal"),
             TokenType::DoubleEqual => write!(f, "DoubleEqual"),
             TokenType::ExclamationEqual => write!(f, "ExclamationEqual"),
             TokenType::LessThan => write!(f, "LessThan"),
             TokenType::GreaterThan => write!(f, "GreaterThan"),
             TokenType::LessThanEqual => write!(f, "LessThanEqual"),
             TokenType::GreaterThanEqual => write!(f, "GreaterThanEqual"),
+            TokenType::Ampersand => write!(f, "Ampersand"),
+            TokenType::Pipe => write!(f, "Pipe"),
+            TokenType::Caret => write!(f, "Caret"),
+            TokenType::DoubleLessThan => write!(f, "DoubleLessThan"),
+            TokenType::DoubleGreaterThan => write!(f, "DoubleGreaterThan"),
+            TokenType::PlusEqual => write!(f, "PlusEqual"),
+            TokenType::HyphenEqual => write!(f, "HyphenEqual"),
+            TokenType::AsteriskEqual => write!(f, "AsteriskEqual"),
+            TokenType::ForwardSlashEqual => write!(f, "ForwardSlashEqual"),
+            TokenType::PercentEqual => write!(f, "PercentEqual"),
+            TokenType::AmpersandEqual => write!(f, "AmpersandEqual"),
+            TokenType::PipeEqual => write!(f, "PipeEqual"),
+            TokenType::CaretEqual => write!(f, "CaretEqual"),
+            TokenType::DoubleLessThanEqual => write!(f, "DoubleLessThanEqual"),
+            TokenType::DoubleGreaterThanEqual => write!(f, "DoubleGreaterThanEqual"),
         }
     }
 }
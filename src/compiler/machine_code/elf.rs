@@ -0,0 +1,162 @@
+use crate::common::symbol::Symbol;
+use crate::common::target::Target;
+use crate::compiler::code_gen::assembly_ast::{AssemblyAst, AssemblyFunction};
+use crate::compiler::machine_code::encode_program;
+use crate::compiler::machine_code::errors::EncodingError;
+
+/// The virtual address the executable is loaded at, the traditional base of static x86-64 binaries.
+const BASE_ADDRESS: u64 = 0x40_0000;
+/// The size of the ELF64 file header.
+const FILE_HEADER_SIZE: u16 = 64;
+/// The size of an ELF64 program header.
+const PROGRAM_HEADER_SIZE: u16 = 56;
+/// The alignment of the machine code of the program, which the alignment of its constants is relative to.
+const CODE_ALIGNMENT: u64 = 16;
+
+/// The program entry point, which calls `main` with the argument count and the argument vector the
+/// kernel placed on the stack, and exits with its return value.
+///
+/// `movl (%rsp), %edi; leaq 8(%rsp), %rsi; call main; movl %eax, %edi; movl $231, %eax; syscall`,
+/// where 231 is `exit_group`. `main` directly follows the entry point, so the call skips the
+/// remaining 9 bytes.
+const ENTRY_POINT: [u8; 22] = [
+    0x8b, 0x3c, 0x24, 0x48, 0x8d, 0x74, 0x24, 0x08, 0xe8, 0x09, 0x00, 0x00, 0x00, 0x89, 0xc7, 0xb8,
+    0xe7, 0x00, 0x00, 0x00, 0x0f, 0x05,
+];
+
+/// Builds a static x86-64 Linux executable from an assembly AST, without an assembler or linker.
+///
+/// The executable consists of a single loadable segment holding an entry point, the machine code
+/// of the program, its read-only constants, and its static variables. The segment is only writable
+/// if the program has static variables. It depends on no C library, so it runs on any x86-64 Linux system.
+///
+/// # Arguments
+///
+/// * `assembly_ast`: A reference to the `AssemblyAst` of the program.
+/// * `target`: The platform to build the executable for. Only x86-64 Linux is supported.
+///
+/// # Returns
+///
+/// The contents of the executable file on success, or the `EncodingError` describing why the
+/// program cannot be encoded.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::target::Target;
+/// # use cmm::compiler::code_gen::convert_ast;
+/// # use cmm::compiler::ir_gen::TackyEmitter;
+/// # use cmm::compiler::machine_code::elf::write_static_executable;
+/// # use cmm::compiler::validate_source;
+/// let validated_ast = validate_source("int main(void) { return 2; }").unwrap();
+/// let assembly_ast = convert_ast(TackyEmitter::new().convert_ast(validated_ast).unwrap()).unwrap();
+/// let executable = write_static_executable(&assembly_ast, &Target::X86_64_LINUX).unwrap();
+/// assert_eq!(&executable[..4], b"\x7fELF");
+/// assert!(write_static_executable(&assembly_ast, &Target::X86_64_MACOS).is_err());
+/// ```
+pub fn write_static_executable(
+    assembly_ast: &AssemblyAst,
+    target: &Target,
+) -> Result<Vec<u8>, EncodingError> {
+    if *target != Target::X86_64_LINUX {
+        return Err(EncodingError::UnsupportedTarget { target: *target });
+    }
+    let AssemblyAst::Program {
+        functions,
+        static_variables,
+        ..
+    } = assembly_ast;
+    if !functions
+        .iter()
+        .any(|AssemblyFunction::Function { identifier, .. }| identifier == "main")
+    {
+        return Err(EncodingError::UndefinedFunction {
+            identifier: Symbol::from("main"),
+        });
+    }
+    // `encode_program` places `main` at the start of the code.
+    let machine_code = encode_program(assembly_ast)?;
+
+    // The entry point is preceded by padding, so that `main` directly following it is aligned.
+    let headers_size = u64::from(FILE_HEADER_SIZE + PROGRAM_HEADER_SIZE);
+    let code_offset = (headers_size + ENTRY_POINT.len() as u64).next_multiple_of(CODE_ALIGNMENT);
+    let entry_offset = code_offset - ENTRY_POINT.len() as u64;
+    let file_size = code_offset + machine_code.len() as u64;
+    let mut executable = Vec::with_capacity(file_size as usize);
+    write_file_header(&mut executable, BASE_ADDRESS + entry_offset);
+    write_program_header(&mut executable, file_size, !static_variables.is_empty());
+    executable.resize(entry_offset as usize, 0);
+    executable.extend_from_slice(&ENTRY_POINT);
+    executable.extend_from_slice(&machine_code);
+    Ok(executable)
+}
+
+/// Writes the ELF64 file header of an executable with a single program header and no sections.
+fn write_file_header(executable: &mut Vec<u8>, entry_address: u64) {
+    // The magic number, 64-bit, little-endian, ELF version 1, System V ABI, and padding.
+    executable.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    // An executable file for x86-64, ELF version 1.
+    executable.extend_from_slice(&2u16.to_le_bytes());
+    executable.extend_from_slice(&0x3eu16.to_le_bytes());
+    executable.extend_from_slice(&1u32.to_le_bytes());
+    executable.extend_from_slice(&entry_address.to_le_bytes());
+    // The program headers follow the file header, there is no section header table.
+    executable.extend_from_slice(&u64::from(FILE_HEADER_SIZE).to_le_bytes());
+    executable.extend_from_slice(&0u64.to_le_bytes());
+    executable.extend_from_slice(&0u32.to_le_bytes());
+    executable.extend_from_slice(&FILE_HEADER_SIZE.to_le_bytes());
+    executable.extend_from_slice(&PROGRAM_HEADER_SIZE.to_le_bytes());
+    executable.extend_from_slice(&1u16.to_le_bytes());
+    // Section header size, count, and the index of the section name table.
+    executable.extend_from_slice(&[0; 6]);
+}
+
+/// Writes a program header loading the whole file as a readable and executable segment, which is
+/// also writable if `writable` is set.
+fn write_program_header(executable: &mut Vec<u8>, file_size: u64, writable: bool) {
+    // A loadable segment that is readable, executable, and possibly writable.
+    let flags = if writable { 0b111u32 } else { 0b101u32 };
+    executable.extend_from_slice(&1u32.to_le_bytes());
+    executable.extend_from_slice(&flags.to_le_bytes());
+    // The file offset, the virtual and physical addresses, and the sizes in the file and in memory.
+    executable.extend_from_slice(&0u64.to_le_bytes());
+    executable.extend_from_slice(&BASE_ADDRESS.to_le_bytes());
+    executable.extend_from_slice(&BASE_ADDRESS.to_le_bytes());
+    executable.extend_from_slice(&file_size.to_le_bytes());
+    executable.extend_from_slice(&file_size.to_le_bytes());
+    // The page alignment.
+    executable.extend_from_slice(&0x1000u64.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::code_gen::assembly_ast::{AssemblyFunction, AssemblyInstruction};
+
+    #[test]
+    fn test_executable_layout() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![AssemblyInstruction::Ret],
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let executable = write_static_executable(&assembly_ast, &Target::X86_64_LINUX).unwrap();
+        let headers_size = usize::from(FILE_HEADER_SIZE + PROGRAM_HEADER_SIZE);
+        // The entry point follows the headers, and `main` starts at an aligned address after it.
+        let code_offset = 144;
+        let entry_offset = code_offset - ENTRY_POINT.len();
+        assert!(entry_offset >= headers_size);
+        assert_eq!(executable.len(), code_offset + 9);
+        assert_eq!(
+            executable[24..32],
+            (BASE_ADDRESS + entry_offset as u64).to_le_bytes()
+        );
+        assert_eq!(executable[entry_offset..code_offset], ENTRY_POINT);
+        // The loadable segment covers the whole file.
+        assert_eq!(executable[96..104], (executable.len() as u64).to_le_bytes());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::common::symbol::Symbol;
+use crate::common::target::Target;
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors that can occur while encoding an assembly AST into machine code.
+#[derive(Debug, PartialEq)]
+pub enum EncodingError {
+    /// Raised when an instruction still refers to a pseudo register, which has no machine encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the pseudo register.
+    PseudoOperand { name: Symbol },
+    /// Raised when an instruction has a combination of operands x86-64 cannot encode, such as two
+    /// memory operands.
+    ///
+    /// # Arguments
+    ///
+    /// * `instruction`: The debug representation of the instruction.
+    InvalidOperands { instruction: String },
+    /// Raised when a jump targets a label that is not defined in its function.
+    ///
+    /// # Arguments
+    ///
+    /// * `label`: The name of the missing label.
+    UndefinedLabel { label: Symbol },
+    /// Raised when an instruction addresses a constant that is not defined in the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the missing constant.
+    UndefinedConstant { name: Symbol },
+    /// Raised when a call targets a function that is not defined in the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the missing function.
+    UndefinedFunction { identifier: Symbol },
+    /// Raised when an executable is requested for a platform the internal toolchain cannot build for.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: The requested target.
+    UnsupportedTarget { target: Target },
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodingError::PseudoOperand { name } => write!(
+                f,
+                "Encoding error: Pseudo register '{}' was not replaced with a stack location",
+                name
+            ),
+            EncodingError::InvalidOperands { instruction } => write!(
+                f,
+                "Encoding error: The operands of '{}' cannot be encoded",
+                instruction
+            ),
+            EncodingError::UndefinedLabel { label } => {
+                write!(f, "Encoding error: Jump to undefined label '{}'", label)
+            }
+            EncodingError::UndefinedConstant { name } => {
+                write!(
+                    f,
+                    "Encoding error: Reference to undefined constant '{}'",
+                    name
+                )
+            }
+            EncodingError::UndefinedFunction { identifier } => {
+                write!(
+                    f,
+                    "Encoding error: Call to undefined function '{}'",
+                    identifier
+                )
+            }
+            EncodingError::UnsupportedTarget { target } => write!(
+                f,
+                "Encoding error: The internal toolchain only builds executables for x86_64-unknown-linux-gnu, not '{}'",
+                target
+            ),
+        }
+    }
+}
+
+impl Error for EncodingError {}
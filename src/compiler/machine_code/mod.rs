@@ -0,0 +1,1853 @@
+pub mod elf;
+pub mod errors;
+
+use crate::common::symbol::Symbol;
+use crate::compiler::code_gen::assembly_ast::{
+    AssemblyAst, AssemblyBinaryOperator, AssemblyConditionCode, AssemblyFunction,
+    AssemblyInstruction, AssemblyOperand, AssemblyRegister, AssemblyStaticConstant,
+    AssemblyStaticInit, AssemblyStaticVariable, AssemblyType, AssemblyUnaryOperator,
+};
+use errors::EncodingError;
+use rustc_hash::FxHashMap;
+
+/// The typical length of an encoded instruction in bytes, used to pre-size the output.
+const AVERAGE_INSTRUCTION_LENGTH: usize = 6;
+
+/// Encodes a function of an assembly AST into x86-64 machine code.
+///
+/// The machine code is the same program the assembler builds from the emitted assembly code,
+/// including the stack frame setup of `code_emission`. Jumps are resolved within the function, so
+/// the code can be placed at any address.
+///
+/// # Arguments
+///
+/// * `function`: A reference to the `AssemblyFunction` to encode. Its pseudo registers must be replaced with stack locations.
+///
+/// # Returns
+///
+/// The machine code of the function on success, or the `EncodingError` of the first instruction
+/// that cannot be encoded.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::code_gen::assembly_ast::{AssemblyFunction, AssemblyInstruction, AssemblyOperand, AssemblyRegister, AssemblyType};
+/// # use cmm::compiler::machine_code::encode_function;
+/// let function = AssemblyFunction::Function {
+///     identifier: Symbol::from("main"),
+///     global: true,
+///     instructions: vec![
+///         AssemblyInstruction::Mov {
+///             assembly_type: AssemblyType::Longword,
+///             source: AssemblyOperand::Imm(2),
+///             destination: AssemblyOperand::Register(AssemblyRegister::AX),
+///         },
+///         AssemblyInstruction::Ret,
+///     ],
+/// };
+/// assert_eq!(
+///     encode_function(&function).unwrap(),
+///     [0x55, 0x48, 0x89, 0xe5, 0xc7, 0xc0, 0x02, 0x00, 0x00, 0x00, 0x48, 0x89, 0xec, 0x5d, 0xc3]
+/// );
+/// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "encode_function", skip_all)
+)]
+pub fn encode_function(function: &AssemblyFunction) -> Result<Vec<u8>, EncodingError> {
+    encode_functions_with_data(&[function], &[], &[])
+}
+
+/// Encodes an assembly AST into x86-64 machine code followed by its read-only constants.
+///
+/// The code of `main` comes first, so that a program starts at the beginning of its code, and the
+/// other functions follow in program order. Calls are resolved within the program. The constants
+/// directly follow the code of the functions and are addressed relative to the instruction pointer,
+/// so the code can still be placed at any address. They are aligned relative to the start of the
+/// code, which is aligned if the code is placed at an address aligned to 16 bytes.
+///
+/// # Arguments
+///
+/// * `assembly_ast`: A reference to the `AssemblyAst` to encode. Its pseudo registers must be replaced with stack locations.
+///
+/// # Returns
+///
+/// The machine code of the program on success, or the `EncodingError` of the first instruction
+/// that cannot be encoded.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::code_gen::convert_ast;
+/// # use cmm::compiler::ir_gen::TackyEmitter;
+/// # use cmm::compiler::machine_code::encode_program;
+/// # use cmm::compiler::validate_source;
+/// let validated_ast = validate_source("int main(void) { return \"hi\" && 1; }").unwrap();
+/// let assembly_ast = convert_ast(TackyEmitter::new().convert_ast(validated_ast).unwrap()).unwrap();
+/// assert!(encode_program(&assembly_ast).unwrap().ends_with(b"hi\0"));
+/// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "encode_program", skip_all)
+)]
+pub fn encode_program(assembly_ast: &AssemblyAst) -> Result<Vec<u8>, EncodingError> {
+    let AssemblyAst::Program {
+        functions,
+        static_constants,
+        static_variables,
+    } = assembly_ast;
+    let is_main = |function: &&AssemblyFunction| {
+        let AssemblyFunction::Function { identifier, .. } = function;
+        identifier == "main"
+    };
+    let ordered_functions: Vec<&AssemblyFunction> = functions
+        .iter()
+        .filter(is_main)
+        .chain(functions.iter().filter(|function| !is_main(function)))
+        .collect();
+    encode_functions_with_data(&ordered_functions, static_constants, static_variables)
+}
+
+/// Encodes functions one after another, and lays out the constants and the static variables they
+/// refer to after their code.
+fn encode_functions_with_data(
+    functions: &[&AssemblyFunction],
+    static_constants: &[AssemblyStaticConstant],
+    static_variables: &[AssemblyStaticVariable],
+) -> Result<Vec<u8>, EncodingError> {
+    let instruction_count = functions
+        .iter()
+        .map(|AssemblyFunction::Function { instructions, .. }| instructions.len() + 2)
+        .sum();
+    let mut encoder = Encoder::with_capacity(instruction_count);
+    for AssemblyFunction::Function {
+        identifier,
+        global: _,
+        instructions,
+    } in functions
+    {
+        encoder
+            .functions
+            .insert(identifier.clone(), encoder.code.len());
+        // pushq %rbp; movq %rsp, %rbp
+        encoder.code.extend_from_slice(&[0x55, 0x48, 0x89, 0xe5]);
+        for instruction in instructions {
+            encoder.encode_instruction(instruction)?;
+        }
+    }
+    encoder.finish(static_constants, static_variables)
+}
+
+/// Represents an operand that is encoded in the r/m field of a ModRM byte.
+#[derive(Debug, Clone, Copy)]
+enum RegisterOrMemory<'a> {
+    /// A register with its 4-bit register number.
+    Register(u8),
+    /// A memory location at the given offset from the address in the register with the given
+    /// number, such as a stack location relative to %rbp. The register is never %rsp or %r12,
+    /// which would need a SIB byte.
+    Memory(u8, i32),
+    /// A memory location at the address in the base register plus the value of the index register
+    /// times the scale, given by their numbers. It is encoded with a SIB byte.
+    Indexed(u8, u8, i32),
+    /// A constant or a static variable, addressed relative to the end of the instruction.
+    Constant(&'a Symbol),
+}
+
+/// Collects the machine code of functions together with its labels, unresolved jumps, calls, and
+/// unresolved references to constants.
+struct Encoder {
+    code: Vec<u8>,
+    labels: FxHashMap<Symbol, usize>,
+    /// The positions of the 32-bit jump displacements, and the labels they jump to.
+    jumps: Vec<(usize, Symbol)>,
+    /// The positions of the encoded functions.
+    functions: FxHashMap<Symbol, usize>,
+    /// The positions of the 32-bit call displacements, and the functions they call.
+    calls: Vec<(usize, Symbol)>,
+    /// The positions of the 32-bit displacements addressing constants and static variables, the
+    /// ends of their instructions, and the names they address.
+    constant_references: Vec<(usize, usize, Symbol)>,
+}
+
+impl Encoder {
+    fn with_capacity(instruction_count: usize) -> Self {
+        Encoder {
+            code: Vec::with_capacity(instruction_count * AVERAGE_INSTRUCTION_LENGTH),
+            labels: FxHashMap::default(),
+            jumps: Vec::new(),
+            functions: FxHashMap::default(),
+            calls: Vec::new(),
+            constant_references: Vec::new(),
+        }
+    }
+
+    /// Patches the displacements of the jumps and calls, once every label and function is known, and
+    /// appends the constants and the static variables to patch the displacements of their
+    /// addresses. Strings are null-terminated, and `double`s and variables are padded with zeros to
+    /// their alignment. The variables hold their initial values, including the zeros.
+    fn finish(
+        mut self,
+        static_constants: &[AssemblyStaticConstant],
+        static_variables: &[AssemblyStaticVariable],
+    ) -> Result<Vec<u8>, EncodingError> {
+        for (position, label) in std::mem::take(&mut self.jumps) {
+            let target = *self
+                .labels
+                .get(&label)
+                .ok_or(EncodingError::UndefinedLabel { label })?;
+            self.patch_displacement(position, position + 4, target);
+        }
+        for (position, identifier) in std::mem::take(&mut self.calls) {
+            let target = *self
+                .functions
+                .get(&identifier)
+                .ok_or(EncodingError::UndefinedFunction { identifier })?;
+            self.patch_displacement(position, position + 4, target);
+        }
+        let mut constants = FxHashMap::default();
+        for static_constant in static_constants {
+            match static_constant {
+                AssemblyStaticConstant::String { identifier, value } => {
+                    constants.insert(identifier, self.code.len());
+                    self.code.extend_from_slice(value);
+                    self.code.push(0);
+                }
+                AssemblyStaticConstant::Double {
+                    identifier,
+                    value,
+                    alignment,
+                } => {
+                    let alignment = usize::try_from(*alignment).unwrap_or(1).max(1);
+                    self.code
+                        .resize(self.code.len().next_multiple_of(alignment), 0);
+                    constants.insert(identifier, self.code.len());
+                    self.code.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+        for AssemblyStaticVariable::Variable {
+            identifier,
+            alignment,
+            initial_values,
+            ..
+        } in static_variables
+        {
+            let alignment = usize::try_from(*alignment).unwrap_or(1).max(1);
+            self.code
+                .resize(self.code.len().next_multiple_of(alignment), 0);
+            constants.insert(identifier, self.code.len());
+            for initial_value in initial_values {
+                match initial_value {
+                    AssemblyStaticInit::Integer {
+                        assembly_type,
+                        value,
+                    } => {
+                        let size = assembly_type.size() as usize;
+                        self.code.extend_from_slice(&value.to_le_bytes()[..size]);
+                    }
+                    AssemblyStaticInit::Double(value) => {
+                        self.code.extend_from_slice(&value.to_le_bytes())
+                    }
+                    AssemblyStaticInit::Zero(size) => self.code.resize(self.code.len() + size, 0),
+                }
+            }
+        }
+        for (position, instruction_end, name) in std::mem::take(&mut self.constant_references) {
+            let target = *constants
+                .get(&name)
+                .ok_or(EncodingError::UndefinedConstant { name })?;
+            self.patch_displacement(position, instruction_end, target);
+        }
+        Ok(self.code)
+    }
+
+    /// Writes the 32-bit displacement at a position, which is relative to the end of its
+    /// instruction. Only the displacement of a constant or a static variable may be followed by an
+    /// immediate.
+    fn patch_displacement(&mut self, position: usize, instruction_end: usize, target: usize) {
+        let displacement = target as i64 - instruction_end as i64;
+        let displacement =
+            i32::try_from(displacement).expect("A function never exceeds 2 GiB of code");
+        self.code[position..position + 4].copy_from_slice(&displacement.to_le_bytes());
+    }
+
+    fn encode_instruction(
+        &mut self,
+        instruction: &AssemblyInstruction,
+    ) -> Result<(), EncodingError> {
+        let invalid_operands = || EncodingError::InvalidOperands {
+            instruction: format!("{:?}", instruction),
+        };
+        let first_constant_reference = self.constant_references.len();
+        // Word instructions are encoded like their 4-byte forms after the operand-size prefix,
+        // which precedes the REX prefix.
+        if matches!(
+            instruction,
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Word,
+                ..
+            } | AssemblyInstruction::Unary {
+                assembly_type: AssemblyType::Word,
+                ..
+            } | AssemblyInstruction::Binary {
+                assembly_type: AssemblyType::Word,
+                ..
+            } | AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Word,
+                ..
+            } | AssemblyInstruction::Idiv {
+                assembly_type: AssemblyType::Word,
+                ..
+            } | AssemblyInstruction::Div {
+                assembly_type: AssemblyType::Word,
+                ..
+            }
+        ) {
+            self.code.push(0x66);
+        }
+        match instruction {
+            AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Double,
+                source,
+                destination,
+            } => match sse_register_number(destination) {
+                // movsd source, %xmm
+                Some(destination) => {
+                    let source = sse_register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                    self.encode_sse(0xf2, 0x10, destination, source, false);
+                }
+                // movsd %xmm, destination
+                None => {
+                    let source = sse_register_number(source).ok_or_else(invalid_operands)?;
+                    let destination =
+                        register_or_memory(destination)?.ok_or_else(invalid_operands)?;
+                    self.encode_sse(0xf2, 0x11, source, destination, false);
+                }
+            },
+            AssemblyInstruction::Mov {
+                assembly_type,
+                source,
+                destination,
+            } => {
+                let quadword = *assembly_type == AssemblyType::Quadword;
+                if *assembly_type == AssemblyType::Byte
+                    && needs_empty_rex(&[register_number(source), register_number(destination)])
+                {
+                    self.code.push(0x40);
+                }
+                let destination = register_or_memory(destination)?.ok_or_else(invalid_operands)?;
+                match (source, destination) {
+                    (AssemblyOperand::Imm(value), _) if i32::try_from(*value).is_ok() => {
+                        self.encode_modrm(
+                            &[sized_opcode(*assembly_type, 0xc7)],
+                            0,
+                            destination,
+                            quadword,
+                        );
+                        self.encode_immediate(*assembly_type, *value, invalid_operands)?;
+                    }
+                    // movabsq $value, %register
+                    (AssemblyOperand::Imm(value), RegisterOrMemory::Register(register))
+                        if quadword =>
+                    {
+                        self.code
+                            .extend_from_slice(&[0x48 | register >> 3, 0xb8 | (register & 0b111)]);
+                        self.code.extend_from_slice(&value.to_le_bytes());
+                    }
+                    (AssemblyOperand::Imm(_), _) => return Err(invalid_operands()),
+                    (source, RegisterOrMemory::Register(register)) => {
+                        let source = register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                        self.encode_modrm(
+                            &[sized_opcode(*assembly_type, 0x8b)],
+                            register,
+                            source,
+                            quadword,
+                        );
+                    }
+                    (
+                        source,
+                        RegisterOrMemory::Memory(..)
+                        | RegisterOrMemory::Indexed(..)
+                        | RegisterOrMemory::Constant(_),
+                    ) => {
+                        let source = register_number(source).ok_or_else(invalid_operands)?;
+                        self.encode_modrm(
+                            &[sized_opcode(*assembly_type, 0x89)],
+                            source,
+                            destination,
+                            quadword,
+                        );
+                    }
+                }
+            }
+            AssemblyInstruction::Movsx {
+                source_type,
+                destination_type,
+                source,
+                destination,
+            } => {
+                let opcode: &[u8] = match (source_type, destination_type) {
+                    (AssemblyType::Byte, AssemblyType::Longword | AssemblyType::Quadword) => {
+                        &[0x0f, 0xbe]
+                    }
+                    (AssemblyType::Word, AssemblyType::Longword | AssemblyType::Quadword) => {
+                        &[0x0f, 0xbf]
+                    }
+                    (AssemblyType::Longword, AssemblyType::Quadword) => &[0x63],
+                    _ => return Err(invalid_operands()),
+                };
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                let source = register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                self.encode_modrm(
+                    opcode,
+                    destination,
+                    source,
+                    *destination_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::MovZeroExtend {
+                source_type: AssemblyType::Byte,
+                destination_type:
+                    destination_type @ (AssemblyType::Longword | AssemblyType::Quadword),
+                source,
+                destination,
+            } => {
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                let source = register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                self.encode_modrm(
+                    &[0x0f, 0xb6],
+                    destination,
+                    source,
+                    *destination_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::MovZeroExtend { .. } => return Err(invalid_operands()),
+            AssemblyInstruction::Lea {
+                source: AssemblyOperand::Data(name),
+                destination,
+            } => {
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                self.encode_modrm(&[0x8d], destination, RegisterOrMemory::Constant(name), true);
+            }
+            AssemblyInstruction::Lea {
+                source,
+                destination,
+            } => {
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                let source = match register_or_memory(source)? {
+                    Some(
+                        source @ (RegisterOrMemory::Memory(..) | RegisterOrMemory::Indexed(..)),
+                    ) => source,
+                    _ => return Err(invalid_operands()),
+                };
+                self.encode_modrm(&[0x8d], destination, source, true);
+            }
+            AssemblyInstruction::Unary {
+                op,
+                assembly_type,
+                operand,
+            } => {
+                let operand = register_or_memory(operand)?.ok_or_else(invalid_operands)?;
+                let extension = match op {
+                    AssemblyUnaryOperator::Neg => 3,
+                    AssemblyUnaryOperator::Not => 2,
+                };
+                self.encode_modrm(
+                    &[sized_opcode(*assembly_type, 0xf7)],
+                    extension,
+                    operand,
+                    *assembly_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::Binary {
+                op,
+                assembly_type: AssemblyType::Double,
+                source,
+                destination,
+            } => {
+                // The mandatory prefix and the opcode following 0x0f.
+                let (prefix, opcode) = match op {
+                    AssemblyBinaryOperator::Add => (0xf2, 0x58),
+                    AssemblyBinaryOperator::Mult => (0xf2, 0x59),
+                    AssemblyBinaryOperator::Sub => (0xf2, 0x5c),
+                    AssemblyBinaryOperator::DivDouble => (0xf2, 0x5e),
+                    AssemblyBinaryOperator::And => (0x66, 0x54),
+                    AssemblyBinaryOperator::Or => (0x66, 0x56),
+                    AssemblyBinaryOperator::Xor => (0x66, 0x57),
+                    AssemblyBinaryOperator::Sal
+                    | AssemblyBinaryOperator::Sar
+                    | AssemblyBinaryOperator::Shr => return Err(invalid_operands()),
+                };
+                let destination = sse_register_number(destination).ok_or_else(invalid_operands)?;
+                let source = sse_register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                self.encode_sse(prefix, opcode, destination, source, false);
+            }
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::DivDouble,
+                ..
+            } => return Err(invalid_operands()),
+            // There is no two-operand form of `imul` for bytes.
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type: AssemblyType::Byte,
+                ..
+            } => return Err(invalid_operands()),
+            AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type,
+                source,
+                destination,
+            } => {
+                let quadword = *assembly_type == AssemblyType::Quadword;
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                if let AssemblyOperand::Imm(value) = source {
+                    self.encode_modrm(
+                        &[0x69],
+                        destination,
+                        RegisterOrMemory::Register(destination),
+                        quadword,
+                    );
+                    self.encode_immediate(*assembly_type, *value, invalid_operands)?;
+                } else {
+                    let source = register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                    self.encode_modrm(&[0x0f, 0xaf], destination, source, quadword);
+                }
+            }
+            AssemblyInstruction::Binary {
+                op:
+                    op @ (AssemblyBinaryOperator::Sal
+                    | AssemblyBinaryOperator::Sar
+                    | AssemblyBinaryOperator::Shr),
+                assembly_type,
+                source,
+                destination,
+            } => {
+                let quadword = *assembly_type == AssemblyType::Quadword;
+                let extension = match op {
+                    AssemblyBinaryOperator::Sal => 4,
+                    AssemblyBinaryOperator::Shr => 5,
+                    _ => 7,
+                };
+                let destination = register_or_memory(destination)?.ok_or_else(invalid_operands)?;
+                match source {
+                    AssemblyOperand::Imm(count) => {
+                        self.encode_modrm(
+                            &[sized_opcode(*assembly_type, 0xc1)],
+                            extension,
+                            destination,
+                            quadword,
+                        );
+                        self.code.push(*count as u8);
+                    }
+                    AssemblyOperand::Register(AssemblyRegister::CX) => {
+                        self.encode_modrm(
+                            &[sized_opcode(*assembly_type, 0xd3)],
+                            extension,
+                            destination,
+                            quadword,
+                        );
+                    }
+                    _ => return Err(invalid_operands()),
+                }
+            }
+            AssemblyInstruction::Binary {
+                op,
+                assembly_type,
+                source,
+                destination,
+            } => {
+                // The opcode extension of the immediate form, and the opcodes of the forms writing
+                // to the r/m operand and to the register operand.
+                let (extension, to_memory, to_register) = match op {
+                    AssemblyBinaryOperator::Add => (0, 0x01, 0x03),
+                    AssemblyBinaryOperator::Sub => (5, 0x29, 0x2b),
+                    AssemblyBinaryOperator::And => (4, 0x21, 0x23),
+                    AssemblyBinaryOperator::Or => (1, 0x09, 0x0b),
+                    AssemblyBinaryOperator::Xor => (6, 0x31, 0x33),
+                    AssemblyBinaryOperator::Mult
+                    | AssemblyBinaryOperator::Sal
+                    | AssemblyBinaryOperator::Sar
+                    | AssemblyBinaryOperator::Shr
+                    | AssemblyBinaryOperator::DivDouble => {
+                        unreachable!("Multiplication, shifts, and divisions are encoded above")
+                    }
+                };
+                let destination = register_or_memory(destination)?.ok_or_else(invalid_operands)?;
+                self.encode_arithmetic(
+                    *assembly_type,
+                    source,
+                    destination,
+                    (extension, to_memory, to_register),
+                    invalid_operands,
+                )?;
+            }
+            // `comisd left, %xmm` compares the register with `left`.
+            AssemblyInstruction::Cmp {
+                assembly_type: AssemblyType::Double,
+                left,
+                right,
+            } => {
+                let right = sse_register_number(right).ok_or_else(invalid_operands)?;
+                let left = sse_register_or_memory(left)?.ok_or_else(invalid_operands)?;
+                self.encode_sse(0x66, 0x2f, right, left, false);
+            }
+            AssemblyInstruction::Cmp {
+                assembly_type,
+                left,
+                right,
+            } => {
+                // `cmpl left, right` computes `right - left`, so `right` is the first operand.
+                let right = register_or_memory(right)?.ok_or_else(invalid_operands)?;
+                self.encode_arithmetic(
+                    *assembly_type,
+                    left,
+                    right,
+                    (7, 0x39, 0x3b),
+                    invalid_operands,
+                )?;
+            }
+            AssemblyInstruction::Idiv {
+                assembly_type,
+                operand,
+            } => {
+                let operand = register_or_memory(operand)?.ok_or_else(invalid_operands)?;
+                self.encode_modrm(
+                    &[sized_opcode(*assembly_type, 0xf7)],
+                    7,
+                    operand,
+                    *assembly_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::Div {
+                assembly_type,
+                operand,
+            } => {
+                let operand = register_or_memory(operand)?.ok_or_else(invalid_operands)?;
+                self.encode_modrm(
+                    &[sized_opcode(*assembly_type, 0xf7)],
+                    6,
+                    operand,
+                    *assembly_type == AssemblyType::Quadword,
+                );
+            }
+            // cbtw
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Byte,
+            } => self.code.extend_from_slice(&[0x66, 0x98]),
+            // cwtd
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Word,
+            } => self.code.extend_from_slice(&[0x66, 0x99]),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Longword,
+            } => self.code.push(0x99),
+            // cqo
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Quadword,
+            } => self.code.extend_from_slice(&[0x48, 0x99]),
+            AssemblyInstruction::Cdq {
+                assembly_type: AssemblyType::Double,
+            } => return Err(invalid_operands()),
+            AssemblyInstruction::Cvtsi2sd {
+                assembly_type: assembly_type @ (AssemblyType::Longword | AssemblyType::Quadword),
+                source,
+                destination,
+            } => {
+                let destination = sse_register_number(destination).ok_or_else(invalid_operands)?;
+                let source = register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                self.encode_sse(
+                    0xf2,
+                    0x2a,
+                    destination,
+                    source,
+                    *assembly_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::Cvttsd2si {
+                assembly_type: assembly_type @ (AssemblyType::Longword | AssemblyType::Quadword),
+                source,
+                destination,
+            } => {
+                let destination = register_number(destination).ok_or_else(invalid_operands)?;
+                let source = sse_register_or_memory(source)?.ok_or_else(invalid_operands)?;
+                self.encode_sse(
+                    0xf2,
+                    0x2c,
+                    destination,
+                    source,
+                    *assembly_type == AssemblyType::Quadword,
+                );
+            }
+            AssemblyInstruction::Cvtsi2sd { .. } | AssemblyInstruction::Cvttsd2si { .. } => {
+                return Err(invalid_operands());
+            }
+            AssemblyInstruction::Jmp { label } => {
+                self.code.push(0xe9);
+                self.encode_jump_target(label);
+            }
+            AssemblyInstruction::JmpCC { condition, label } => {
+                self.code
+                    .extend_from_slice(&[0x0f, 0x80 | condition_code(condition)]);
+                self.encode_jump_target(label);
+            }
+            AssemblyInstruction::SetCC { condition, operand } => {
+                let operand = register_or_memory(operand)?.ok_or_else(invalid_operands)?;
+                self.encode_modrm(&[0x0f, 0x90 | condition_code(condition)], 0, operand, false);
+            }
+            AssemblyInstruction::Label(label) => {
+                self.labels.insert(label.clone(), self.code.len());
+            }
+            AssemblyInstruction::AllocateStack { stack_offset } => {
+                // subq $stack_offset, %rsp
+                self.encode_modrm(&[0x81], 5, RegisterOrMemory::Register(4), true);
+                self.code.extend_from_slice(&stack_offset.to_le_bytes());
+            }
+            AssemblyInstruction::DeallocateStack { stack_offset } => {
+                // addq $stack_offset, %rsp
+                self.encode_modrm(&[0x81], 0, RegisterOrMemory::Register(4), true);
+                self.code.extend_from_slice(&stack_offset.to_le_bytes());
+            }
+            AssemblyInstruction::Push(AssemblyOperand::Imm(value)) => {
+                // pushq $value, sign extended to 64 bits
+                let value = i32::try_from(*value).map_err(|_| invalid_operands())?;
+                self.code.push(0x68);
+                self.code.extend_from_slice(&value.to_le_bytes());
+            }
+            AssemblyInstruction::Push(operand) => match register_number(operand) {
+                // pushq %register
+                Some(register) => {
+                    if register >= 8 {
+                        self.code.push(0x41);
+                    }
+                    self.code.push(0x50 | (register & 0b111));
+                }
+                // pushq memory, which is 64 bits without a REX prefix
+                None => {
+                    let operand = sse_register_or_memory(operand)?
+                        .filter(|operand| !matches!(operand, RegisterOrMemory::Register(_)))
+                        .ok_or_else(invalid_operands)?;
+                    self.encode_modrm(&[0xff], 6, operand, false);
+                }
+            },
+            AssemblyInstruction::Call(identifier) => {
+                self.code.push(0xe8);
+                self.calls.push((self.code.len(), identifier.clone()));
+                self.code.extend_from_slice(&[0; 4]);
+            }
+            AssemblyInstruction::Ret => {
+                // movq %rbp, %rsp; popq %rbp; ret
+                self.code.extend_from_slice(&[0x48, 0x89, 0xec, 0x5d, 0xc3]);
+            }
+        }
+        // An immediate may follow the displacement of a constant or a static variable, which is
+        // relative to the end of the whole instruction.
+        let instruction_end = self.code.len();
+        for (_, end, _) in &mut self.constant_references[first_constant_reference..] {
+            *end = instruction_end;
+        }
+        Ok(())
+    }
+
+    /// Encodes an instruction of the `add`, `sub`, `and`, `or`, `xor`, and `cmp` family, which share their forms.
+    ///
+    /// # Arguments
+    ///
+    /// * `assembly_type`: The size of the operands.
+    /// * `source`: The operand in the AT&T source position.
+    /// * `destination`: The operand in the AT&T destination position.
+    /// * `opcodes`: The opcode extension of the immediate form, and the opcodes writing to the r/m operand and to the register operand.
+    /// * `invalid_operands`: Creates the error for a source that cannot be encoded.
+    fn encode_arithmetic(
+        &mut self,
+        assembly_type: AssemblyType,
+        source: &AssemblyOperand,
+        destination: RegisterOrMemory,
+        (extension, to_memory, to_register): (u8, u8, u8),
+        invalid_operands: impl Fn() -> EncodingError,
+    ) -> Result<(), EncodingError> {
+        let quadword = assembly_type == AssemblyType::Quadword;
+        match (source, destination) {
+            (AssemblyOperand::Imm(value), _) => {
+                self.encode_modrm(
+                    &[sized_opcode(assembly_type, 0x81)],
+                    extension,
+                    destination,
+                    quadword,
+                );
+                self.encode_immediate(assembly_type, *value, invalid_operands)?;
+            }
+            (source, RegisterOrMemory::Register(register)) => {
+                let source = register_or_memory(source)?.ok_or_else(&invalid_operands)?;
+                self.encode_modrm(
+                    &[sized_opcode(assembly_type, to_register)],
+                    register,
+                    source,
+                    quadword,
+                );
+            }
+            (
+                source,
+                RegisterOrMemory::Memory(..)
+                | RegisterOrMemory::Indexed(..)
+                | RegisterOrMemory::Constant(_),
+            ) => {
+                let source = register_number(source).ok_or_else(&invalid_operands)?;
+                self.encode_modrm(
+                    &[sized_opcode(assembly_type, to_memory)],
+                    source,
+                    destination,
+                    quadword,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes a 32-bit immediate, which quadword instructions sign extend to 64 bits, or the 8-bit
+    /// or 16-bit immediate of a byte or word instruction.
+    ///
+    /// # Arguments
+    ///
+    /// * `assembly_type`: The size of the operands of the instruction.
+    /// * `value`: The value of the immediate. Byte and word immediates may be given as signed or
+    ///   unsigned values.
+    /// * `invalid_operands`: Creates the error for a value that does not fit into the immediate.
+    fn encode_immediate(
+        &mut self,
+        assembly_type: AssemblyType,
+        value: i64,
+        invalid_operands: impl Fn() -> EncodingError,
+    ) -> Result<(), EncodingError> {
+        if assembly_type == AssemblyType::Byte {
+            let value = u8::try_from(value)
+                .or_else(|_| i8::try_from(value).map(|value| value as u8))
+                .map_err(|_| invalid_operands())?;
+            self.code.push(value);
+            return Ok(());
+        }
+        if assembly_type == AssemblyType::Word {
+            let value = u16::try_from(value)
+                .or_else(|_| i16::try_from(value).map(|value| value as u16))
+                .map_err(|_| invalid_operands())?;
+            self.code.extend_from_slice(&value.to_le_bytes());
+            return Ok(());
+        }
+        let value = i32::try_from(value).map_err(|_| invalid_operands())?;
+        self.code.extend_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    /// Encodes the optional REX prefix, the opcode, and the ModRM byte with its displacement.
+    ///
+    /// # Arguments
+    ///
+    /// * `opcode`: The bytes of the opcode.
+    /// * `register`: The register number or opcode extension in the reg field.
+    /// * `operand`: The operand in the r/m field.
+    /// * `quadword`: Whether the instruction operates on 64-bit operands.
+    fn encode_modrm(
+        &mut self,
+        opcode: &[u8],
+        register: u8,
+        operand: RegisterOrMemory,
+        quadword: bool,
+    ) {
+        let (operand_register, index_register) = match operand {
+            RegisterOrMemory::Register(number) | RegisterOrMemory::Memory(number, _) => (number, 0),
+            RegisterOrMemory::Indexed(base, index, _) => (base, index),
+            RegisterOrMemory::Constant(_) => (RBP, 0),
+        };
+        let rex = 0x40
+            | (quadword as u8) << 3
+            | (register >> 3) << 2
+            | (index_register >> 3) << 1
+            | operand_register >> 3;
+        if rex != 0x40 {
+            self.code.push(rex);
+        }
+        self.code.extend_from_slice(opcode);
+        let reg_field = (register & 0b111) << 3;
+        match operand {
+            RegisterOrMemory::Register(number) => {
+                self.code.push(0b11_000_000 | reg_field | (number & 0b111));
+            }
+            // A displacement byte is always encoded, as the r/m field of %rbp without one selects
+            // the instruction pointer.
+            RegisterOrMemory::Memory(base, offset) => match i8::try_from(offset) {
+                Ok(offset) => {
+                    self.code.push(0b01_000_000 | reg_field | (base & 0b111));
+                    self.code.push(offset as u8);
+                }
+                Err(_) => {
+                    self.code.push(0b10_000_000 | reg_field | (base & 0b111));
+                    self.code.extend_from_slice(&offset.to_le_bytes());
+                }
+            },
+            // The r/m field of %rsp selects a SIB byte, followed by a zero displacement byte for
+            // the same reason as above.
+            RegisterOrMemory::Indexed(base, index, scale) => {
+                self.code.push(0b01_000_000 | reg_field | 0b100);
+                self.code.push(
+                    (scale.trailing_zeros() as u8) << 6 | (index & 0b111) << 3 | (base & 0b111),
+                );
+                self.code.push(0);
+            }
+            // The r/m field of %rbp without a displacement byte selects the instruction pointer.
+            RegisterOrMemory::Constant(name) => {
+                self.code.push(reg_field | RBP);
+                let position = self.code.len();
+                self.constant_references
+                    .push((position, position + 4, name.clone()));
+                self.code.extend_from_slice(&[0; 4]);
+            }
+        }
+    }
+
+    /// Encodes an SSE instruction, whose mandatory prefix precedes the REX prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix`: The mandatory prefix, 0xf2 for scalar `double` instructions and 0x66 for packed ones.
+    /// * `opcode`: The opcode following the 0x0f escape byte.
+    /// * `register`: The register number in the reg field.
+    /// * `operand`: The operand in the r/m field.
+    /// * `quadword`: Whether the integer operand of a conversion is 64 bits.
+    fn encode_sse(
+        &mut self,
+        prefix: u8,
+        opcode: u8,
+        register: u8,
+        operand: RegisterOrMemory,
+        quadword: bool,
+    ) {
+        self.code.push(prefix);
+        self.encode_modrm(&[0x0f, opcode], register, operand, quadword);
+    }
+
+    /// Reserves a 32-bit displacement to a label, which is resolved by `finish`.
+    fn encode_jump_target(&mut self, label: &Symbol) {
+        self.jumps.push((self.code.len(), label.clone()));
+        self.code.extend_from_slice(&[0; 4]);
+    }
+}
+
+/// The register number of %rbp, which also selects %rbp-relative addressing in the r/m field.
+const RBP: u8 = 5;
+
+/// Returns the opcode of an instruction for the given operand size.
+///
+/// The byte forms of the instructions the encoder uses precede their 2-byte, 4-byte, and 8-byte
+/// forms, e.g. `movb` is 0x88 and `movl` 0x89. The byte registers only differ from %ah, %ch,
+/// %dh, and %bh by a REX prefix for %spl, %bpl, %sil, and %dil, the code generator only moves
+/// bytes into and out of %sil and %dil, see `needs_empty_rex`.
+///
+/// # Arguments
+///
+/// * `assembly_type`: The size of the operands.
+/// * `opcode`: The opcode of the 2-byte, 4-byte, and 8-byte forms.
+fn sized_opcode(assembly_type: AssemblyType, opcode: u8) -> u8 {
+    match assembly_type {
+        AssemblyType::Byte => opcode - 1,
+        AssemblyType::Word
+        | AssemblyType::Longword
+        | AssemblyType::Quadword
+        | AssemblyType::Double => opcode,
+    }
+}
+
+/// Returns whether a byte instruction on the given registers needs an empty REX prefix, which
+/// selects %sil and %dil instead of %dh and %bh when no other REX bit is set.
+fn needs_empty_rex(registers: &[Option<u8>]) -> bool {
+    let registers = registers.iter().flatten();
+    registers.clone().any(|number| (4..8).contains(number))
+        && registers.clone().all(|number| *number < 8)
+}
+
+/// Returns the 4-bit register number of a general-purpose register operand.
+fn register_number(operand: &AssemblyOperand) -> Option<u8> {
+    match operand {
+        AssemblyOperand::Register(AssemblyRegister::AX) => Some(0),
+        AssemblyOperand::Register(AssemblyRegister::CX) => Some(1),
+        AssemblyOperand::Register(AssemblyRegister::DX) => Some(2),
+        AssemblyOperand::Register(AssemblyRegister::SI) => Some(6),
+        AssemblyOperand::Register(AssemblyRegister::DI) => Some(7),
+        AssemblyOperand::Register(AssemblyRegister::R8) => Some(8),
+        AssemblyOperand::Register(AssemblyRegister::R9) => Some(9),
+        AssemblyOperand::Register(AssemblyRegister::R10) => Some(10),
+        AssemblyOperand::Register(AssemblyRegister::R11) => Some(11),
+        _ => None,
+    }
+}
+
+/// Returns the 4-bit register number of an SSE register operand.
+fn sse_register_number(operand: &AssemblyOperand) -> Option<u8> {
+    match operand {
+        AssemblyOperand::Register(AssemblyRegister::XMM0) => Some(0),
+        AssemblyOperand::Register(AssemblyRegister::XMM1) => Some(1),
+        AssemblyOperand::Register(AssemblyRegister::XMM2) => Some(2),
+        AssemblyOperand::Register(AssemblyRegister::XMM3) => Some(3),
+        AssemblyOperand::Register(AssemblyRegister::XMM4) => Some(4),
+        AssemblyOperand::Register(AssemblyRegister::XMM5) => Some(5),
+        AssemblyOperand::Register(AssemblyRegister::XMM6) => Some(6),
+        AssemblyOperand::Register(AssemblyRegister::XMM7) => Some(7),
+        AssemblyOperand::Register(AssemblyRegister::XMM14) => Some(14),
+        AssemblyOperand::Register(AssemblyRegister::XMM15) => Some(15),
+        _ => None,
+    }
+}
+
+/// Returns the r/m form of an SSE register or a memory operand of an SSE instruction, or `None`
+/// for other operands.
+fn sse_register_or_memory(
+    operand: &AssemblyOperand,
+) -> Result<Option<RegisterOrMemory<'_>>, EncodingError> {
+    match operand {
+        AssemblyOperand::Register(_) => {
+            Ok(sse_register_number(operand).map(RegisterOrMemory::Register))
+        }
+        operand => register_or_memory(operand),
+    }
+}
+
+/// Returns the r/m form of a general-purpose register or memory operand, or `None` for an immediate
+/// value.
+fn register_or_memory(
+    operand: &AssemblyOperand,
+) -> Result<Option<RegisterOrMemory<'_>>, EncodingError> {
+    match operand {
+        AssemblyOperand::Imm(_) => Ok(None),
+        AssemblyOperand::Data(name) => Ok(Some(RegisterOrMemory::Constant(name))),
+        AssemblyOperand::Register(_) => {
+            Ok(register_number(operand).map(RegisterOrMemory::Register))
+        }
+        AssemblyOperand::Stack(offset) => Ok(Some(RegisterOrMemory::Memory(RBP, *offset))),
+        AssemblyOperand::Memory(register, offset) => {
+            let base = register_number(&AssemblyOperand::Register(register.clone()));
+            Ok(base.map(|base| RegisterOrMemory::Memory(base, *offset)))
+        }
+        AssemblyOperand::Indexed(base, index, scale) => {
+            let base = register_number(&AssemblyOperand::Register(base.clone()));
+            let index = register_number(&AssemblyOperand::Register(index.clone()));
+            Ok(base
+                .zip(index)
+                .filter(|_| matches!(scale, 1 | 2 | 4 | 8))
+                .map(|(base, index)| RegisterOrMemory::Indexed(base, index, *scale)))
+        }
+        AssemblyOperand::Pseudo(name) => Err(EncodingError::PseudoOperand { name: name.clone() }),
+    }
+}
+
+/// Returns the 4-bit condition code shared by the `jcc` and `setcc` opcodes.
+fn condition_code(condition: &AssemblyConditionCode) -> u8 {
+    match condition {
+        AssemblyConditionCode::B => 0x2,
+        AssemblyConditionCode::AE => 0x3,
+        AssemblyConditionCode::E => 0x4,
+        AssemblyConditionCode::NE => 0x5,
+        AssemblyConditionCode::BE => 0x6,
+        AssemblyConditionCode::A => 0x7,
+        AssemblyConditionCode::P => 0xa,
+        AssemblyConditionCode::NP => 0xb,
+        AssemblyConditionCode::L => 0xc,
+        AssemblyConditionCode::GE => 0xd,
+        AssemblyConditionCode::LE => 0xe,
+        AssemblyConditionCode::G => 0xf,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(instructions: Vec<AssemblyInstruction>) -> Result<Vec<u8>, EncodingError> {
+        let code = encode_function(&AssemblyFunction::Function {
+            identifier: "main".into(),
+            global: true,
+            instructions,
+        })?;
+        // Strip the frame setup shared by every function.
+        Ok(code[4..].to_vec())
+    }
+
+    #[test]
+    fn test_encode_stack_operands() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(7),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-200),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sub,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+            ])
+            .unwrap(),
+            [
+                0xc7, 0x45, 0xfc, 0x07, 0x00, 0x00, 0x00, // movl $7, -4(%rbp)
+                0x44, 0x8b, 0x95, 0x38, 0xff, 0xff, 0xff, // movl -200(%rbp), %r10d
+                0x44, 0x29, 0x55, 0xf8, // subl %r10d, -8(%rbp)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_multiplication_and_division() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Mult,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Mult,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Cdq {
+                    assembly_type: AssemblyType::Longword,
+                },
+                AssemblyInstruction::Idiv {
+                    assembly_type: AssemblyType::Longword,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+            ])
+            .unwrap(),
+            [
+                0x44, 0x0f, 0xaf, 0x5d, 0xfc, // imull -4(%rbp), %r11d
+                0x45, 0x69, 0xdb, 0x03, 0x00, 0x00, 0x00, // imull $3, %r11d
+                0x99, // cdq
+                0x41, 0xf7, 0xfa, // idivl %r10d
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_bitwise_operations_and_shifts() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::And,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(5),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Xor,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::R10),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Or,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sal,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::CX),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sar,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::CX),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+            ])
+            .unwrap(),
+            [
+                0x81, 0x65, 0xfc, 0x05, 0x00, 0x00, 0x00, // andl $5, -4(%rbp)
+                0x44, 0x31, 0x55, 0xf8, // xorl %r10d, -8(%rbp)
+                0x44, 0x0b, 0x5d, 0xfc, // orl -4(%rbp), %r11d
+                0xc1, 0x65, 0xfc, 0x03, // sall $3, -4(%rbp)
+                0x8b, 0x4d, 0xfc, // movl -4(%rbp), %ecx
+                0xd3, 0x7d, 0xf8, // sarl %cl, -8(%rbp)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_unsigned_operations() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Div {
+                    assembly_type: AssemblyType::Longword,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R10),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Shr,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(2),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Shr,
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Register(AssemblyRegister::CX),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::A,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::B,
+                    operand: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::AE,
+                    operand: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::BE,
+                    operand: AssemblyOperand::Stack(-4),
+                },
+            ])
+            .unwrap(),
+            [
+                0x41, 0xf7, 0xf2, // divl %r10d
+                0xc1, 0x6d, 0xfc, 0x02, // shrl $2, -4(%rbp)
+                0xd3, 0x6d, 0xf8, // shrl %cl, -8(%rbp)
+                0x41, 0x0f, 0x97, 0xc3, // seta %r11b
+                0x0f, 0x92, 0x45, 0xfc, // setb -4(%rbp)
+                0x0f, 0x93, 0x45, 0xfc, // setae -4(%rbp)
+                0x0f, 0x96, 0x45, 0xfc, // setbe -4(%rbp)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_comparisons_and_jumps() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Longword,
+                    left: AssemblyOperand::Imm(0),
+                    right: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::JmpCC {
+                    condition: AssemblyConditionCode::E,
+                    label: "end".into(),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::GE,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::SetCC {
+                    condition: AssemblyConditionCode::NP,
+                    operand: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Label("end".into()),
+                AssemblyInstruction::Jmp {
+                    label: "end".into(),
+                },
+            ])
+            .unwrap(),
+            [
+                0x81, 0x7d, 0xfc, 0x00, 0x00, 0x00, 0x00, // cmpl $0, -4(%rbp)
+                0x0f, 0x84, 0x08, 0x00, 0x00, 0x00, // je end
+                0x41, 0x0f, 0x9d, 0xc3, // setge %r11b
+                0x41, 0x0f, 0x9b, 0xc3, // setnp %r11b
+                0xe9, 0xfb, 0xff, 0xff, 0xff, // end: jmp end
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_quadword_operations() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(4294967296),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(-1),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Longword,
+                    destination_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Add,
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r10.clone(),
+                    destination: AssemblyOperand::Stack(-16),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Mult,
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: register_r11,
+                },
+                AssemblyInstruction::Cdq {
+                    assembly_type: AssemblyType::Quadword,
+                },
+                AssemblyInstruction::Idiv {
+                    assembly_type: AssemblyType::Quadword,
+                    operand: register_r10,
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Sal,
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Imm(62),
+                    destination: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Unary {
+                    op: AssemblyUnaryOperator::Neg,
+                    assembly_type: AssemblyType::Quadword,
+                    operand: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Quadword,
+                    left: AssemblyOperand::Imm(0),
+                    right: AssemblyOperand::Stack(-8),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+            ])
+            .unwrap(),
+            [
+                0x49, 0xba, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+                0x00, // movabsq $4294967296, %r10
+                0x48, 0xc7, 0x45, 0xf8, 0xff, 0xff, 0xff, 0xff, // movq $-1, -8(%rbp)
+                0x4c, 0x63, 0x5d, 0xfc, // movslq -4(%rbp), %r11
+                0x4c, 0x01, 0x55, 0xf0, // addq %r10, -16(%rbp)
+                0x4d, 0x69, 0xdb, 0x03, 0x00, 0x00, 0x00, // imulq $3, %r11
+                0x48, 0x99, // cqo
+                0x49, 0xf7, 0xfa, // idivq %r10
+                0x48, 0xc1, 0x65, 0xf8, 0x3e, // salq $62, -8(%rbp)
+                0x48, 0xf7, 0x5d, 0xf8, // negq -8(%rbp)
+                0x48, 0x81, 0x7d, 0xf8, 0x00, 0x00, 0x00, 0x00, // cmpq $0, -8(%rbp)
+                0x48, 0x8b, 0x45, 0xf8, // movq -8(%rbp), %rax
+            ]
+        );
+        // Only a move into a register takes an immediate that does not fit into 32 bits.
+        assert!(matches!(
+            encode(vec![AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Add,
+                assembly_type: AssemblyType::Quadword,
+                source: AssemblyOperand::Imm(4294967296),
+                destination: AssemblyOperand::Stack(-8),
+            }]),
+            Err(EncodingError::InvalidOperands { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encode_program_with_constants() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![
+                    AssemblyInstruction::Lea {
+                        source: AssemblyOperand::Data("string.1".into()),
+                        destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                    },
+                    AssemblyInstruction::Lea {
+                        source: AssemblyOperand::Data("string.0".into()),
+                        destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                    },
+                    AssemblyInstruction::Ret,
+                ],
+            }],
+            static_constants: vec![
+                AssemblyStaticConstant::String {
+                    identifier: "string.0".into(),
+                    value: b"a".to_vec(),
+                },
+                AssemblyStaticConstant::String {
+                    identifier: "string.1".into(),
+                    value: Vec::new(),
+                },
+            ],
+            static_variables: Vec::new(),
+        };
+        // The constants follow the code, each with its null terminator.
+        assert_eq!(
+            encode_program(&assembly_ast).unwrap(),
+            [
+                0x55, 0x48, 0x89, 0xe5, 0x4c, 0x8d, 0x1d, 0x0e, 0x00, 0x00, 0x00, 0x48, 0x8d, 0x05,
+                0x05, 0x00, 0x00, 0x00, 0x48, 0x89, 0xec, 0x5d, 0xc3, b'a', 0x00, 0x00
+            ]
+        );
+        assert_eq!(
+            encode(vec![AssemblyInstruction::Lea {
+                source: AssemblyOperand::Data("string.0".into()),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            }]),
+            Err(EncodingError::UndefinedConstant {
+                name: "string.0".into()
+            })
+        );
+        assert!(matches!(
+            encode(vec![AssemblyInstruction::Lea {
+                source: AssemblyOperand::Data("string.0".into()),
+                destination: AssemblyOperand::Stack(-8),
+            }]),
+            Err(EncodingError::InvalidOperands { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encode_static_variable_operands() {
+        let counter = AssemblyOperand::Data("counter".into());
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![
+                    AssemblyInstruction::Binary {
+                        op: AssemblyBinaryOperator::Add,
+                        assembly_type: AssemblyType::Longword,
+                        source: AssemblyOperand::Imm(1),
+                        destination: counter.clone(),
+                    },
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Longword,
+                        source: counter,
+                        destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                    },
+                    AssemblyInstruction::Ret,
+                ],
+            }],
+            static_constants: Vec::new(),
+            static_variables: vec![AssemblyStaticVariable::Variable {
+                identifier: "counter".into(),
+                global: false,
+                alignment: 4,
+                initial_values: vec![AssemblyStaticInit::Integer {
+                    assembly_type: AssemblyType::Longword,
+                    value: 5,
+                }],
+            }],
+        };
+        // The displacement of `addl $1, counter(%rip)` is relative to the end of its immediate.
+        assert_eq!(
+            encode_program(&assembly_ast).unwrap(),
+            [
+                0x55, 0x48, 0x89, 0xe5, 0x81, 0x05, 0x0e, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                0x8b, 0x05, 0x08, 0x00, 0x00, 0x00, 0x48, 0x89, 0xec, 0x5d, 0xc3, 0x00, 0x00, 0x00,
+                0x05, 0x00, 0x00, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_memory_operands() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Stack(-4),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Imm(3),
+                    destination: AssemblyOperand::Memory(AssemblyRegister::AX, 0),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Memory(AssemblyRegister::AX, 0),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Quadword,
+                    source: AssemblyOperand::Memory(AssemblyRegister::R11, 8),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+            ])
+            .unwrap()[..19],
+            [
+                0x4c, 0x8d, 0x5d, 0xfc, 0xc7, 0x40, 0x00, 0x03, 0x00, 0x00, 0x00, 0x44, 0x8b, 0x50,
+                0x00, 0x49, 0x8b, 0x43, 0x08
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_indexed_operands() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Indexed(AssemblyRegister::AX, AssemblyRegister::DX, 4),
+                    destination: AssemblyOperand::Register(AssemblyRegister::R11),
+                },
+                AssemblyInstruction::Lea {
+                    source: AssemblyOperand::Indexed(AssemblyRegister::R8, AssemblyRegister::R9, 1),
+                    destination: AssemblyOperand::Register(AssemblyRegister::AX),
+                },
+            ])
+            .unwrap()[..10],
+            [0x4c, 0x8d, 0x5c, 0x90, 0x00, 0x4b, 0x8d, 0x44, 0x08, 0x00]
+        );
+        assert!(
+            encode(vec![AssemblyInstruction::Lea {
+                source: AssemblyOperand::Indexed(AssemblyRegister::AX, AssemblyRegister::DX, 3),
+                destination: AssemblyOperand::Register(AssemblyRegister::R11),
+            }])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_encode_word_operations() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Word,
+                    source: AssemblyOperand::Imm(-25536),
+                    destination: AssemblyOperand::Stack(-2),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Word,
+                    source: register_r10.clone(),
+                    destination: AssemblyOperand::Stack(-4),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Word,
+                    source: AssemblyOperand::Stack(-2),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Word,
+                    destination_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-2),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Word,
+                    destination_type: AssemblyType::Quadword,
+                    source: register_r10,
+                    destination: register_r11,
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Word,
+                    left: AssemblyOperand::Imm(10),
+                    right: AssemblyOperand::Stack(-2),
+                },
+                AssemblyInstruction::Cdq {
+                    assembly_type: AssemblyType::Word,
+                },
+            ])
+            .unwrap(),
+            [
+                0x66, 0xc7, 0x45, 0xfe, 0x40, 0x9c, // movw $-25536, -2(%rbp)
+                0x66, 0x44, 0x89, 0x55, 0xfc, // movw %r10w, -4(%rbp)
+                0x66, 0x44, 0x8b, 0x5d, 0xfe, // movw -2(%rbp), %r11w
+                0x44, 0x0f, 0xbf, 0x55, 0xfe, // movswl -2(%rbp), %r10d
+                0x4d, 0x0f, 0xbf, 0xda, // movswq %r10w, %r11
+                0x66, 0x81, 0x7d, 0xfe, 0x0a, 0x00, // cmpw $10, -2(%rbp)
+                0x66, 0x99, // cwtd
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_byte_operations() {
+        let register_r10 = AssemblyOperand::Register(AssemblyRegister::R10);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Imm(97),
+                    destination: AssemblyOperand::Stack(-1),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Imm(-56),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: register_r10.clone(),
+                    destination: AssemblyOperand::Stack(-2),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-1),
+                    destination: register_r10.clone(),
+                },
+                AssemblyInstruction::Movsx {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Quadword,
+                    source: register_r10.clone(),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::MovZeroExtend {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-2),
+                    destination: register_r11,
+                },
+                AssemblyInstruction::MovZeroExtend {
+                    source_type: AssemblyType::Byte,
+                    destination_type: AssemblyType::Quadword,
+                    source: register_r10.clone(),
+                    destination: register_r10,
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Byte,
+                    left: AssemblyOperand::Imm(10),
+                    right: AssemblyOperand::Stack(-1),
+                },
+                AssemblyInstruction::Unary {
+                    op: AssemblyUnaryOperator::Neg,
+                    assembly_type: AssemblyType::Byte,
+                    operand: AssemblyOperand::Stack(-1),
+                },
+                AssemblyInstruction::Cdq {
+                    assembly_type: AssemblyType::Byte,
+                },
+            ])
+            .unwrap(),
+            [
+                0xc6, 0x45, 0xff, 0x61, // movb $97, -1(%rbp)
+                0x41, 0xc6, 0xc2, 0xc8, // movb $-56, %r10b
+                0x44, 0x88, 0x55, 0xfe, // movb %r10b, -2(%rbp)
+                0x44, 0x0f, 0xbe, 0x55, 0xff, // movsbl -1(%rbp), %r10d
+                0x4d, 0x0f, 0xbe, 0xda, // movsbq %r10b, %r11
+                0x44, 0x0f, 0xb6, 0x5d, 0xfe, // movzbl -2(%rbp), %r11d
+                0x4d, 0x0f, 0xb6, 0xd2, // movzbq %r10b, %r10
+                0x80, 0x7d, 0xff, 0x0a, // cmpb $10, -1(%rbp)
+                0xf6, 0x5d, 0xff, // negb -1(%rbp)
+                0x66, 0x98, // cbtw
+            ]
+        );
+        // Bytes are never multiplied, as arithmetic happens on promoted operands.
+        assert!(matches!(
+            encode(vec![AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type: AssemblyType::Byte,
+                source: AssemblyOperand::Imm(3),
+                destination: AssemblyOperand::Register(AssemblyRegister::R11),
+            }]),
+            Err(EncodingError::InvalidOperands { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reject_unencodable_instructions() {
+        assert_eq!(
+            encode(vec![AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Pseudo("x".into()),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            }]),
+            Err(EncodingError::PseudoOperand { name: "x".into() })
+        );
+        assert!(matches!(
+            encode(vec![AssemblyInstruction::Mov {
+                assembly_type: AssemblyType::Longword,
+                source: AssemblyOperand::Stack(-4),
+                destination: AssemblyOperand::Stack(-8),
+            }]),
+            Err(EncodingError::InvalidOperands { .. })
+        ));
+        assert_eq!(
+            encode(vec![AssemblyInstruction::Jmp {
+                label: "missing".into()
+            }]),
+            Err(EncodingError::UndefinedLabel {
+                label: "missing".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_encode_double_operations() {
+        let register_xmm0 = AssemblyOperand::Register(AssemblyRegister::XMM0);
+        let register_xmm14 = AssemblyOperand::Register(AssemblyRegister::XMM14);
+        let register_xmm15 = AssemblyOperand::Register(AssemblyRegister::XMM15);
+        let register_r11 = AssemblyOperand::Register(AssemblyRegister::R11);
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Double,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: register_xmm14.clone(),
+                },
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Double,
+                    source: register_xmm15.clone(),
+                    destination: AssemblyOperand::Stack(-16),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Add,
+                    assembly_type: AssemblyType::Double,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: register_xmm15.clone(),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::DivDouble,
+                    assembly_type: AssemblyType::Double,
+                    source: register_xmm14.clone(),
+                    destination: register_xmm15.clone(),
+                },
+                AssemblyInstruction::Binary {
+                    op: AssemblyBinaryOperator::Xor,
+                    assembly_type: AssemblyType::Double,
+                    source: register_xmm0.clone(),
+                    destination: register_xmm0.clone(),
+                },
+                AssemblyInstruction::Cmp {
+                    assembly_type: AssemblyType::Double,
+                    left: AssemblyOperand::Stack(-8),
+                    right: register_xmm0,
+                },
+                AssemblyInstruction::Cvtsi2sd {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-4),
+                    destination: register_xmm15.clone(),
+                },
+                AssemblyInstruction::Cvtsi2sd {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_r11.clone(),
+                    destination: register_xmm15,
+                },
+                AssemblyInstruction::Cvttsd2si {
+                    assembly_type: AssemblyType::Longword,
+                    source: AssemblyOperand::Stack(-8),
+                    destination: register_r11.clone(),
+                },
+                AssemblyInstruction::Cvttsd2si {
+                    assembly_type: AssemblyType::Quadword,
+                    source: register_xmm14,
+                    destination: register_r11,
+                },
+            ])
+            .unwrap(),
+            [
+                0xf2, 0x44, 0x0f, 0x10, 0x75, 0xf8, // movsd -8(%rbp), %xmm14
+                0xf2, 0x44, 0x0f, 0x11, 0x7d, 0xf0, // movsd %xmm15, -16(%rbp)
+                0xf2, 0x44, 0x0f, 0x58, 0x7d, 0xf8, // addsd -8(%rbp), %xmm15
+                0xf2, 0x45, 0x0f, 0x5e, 0xfe, // divsd %xmm14, %xmm15
+                0x66, 0x0f, 0x57, 0xc0, // xorpd %xmm0, %xmm0
+                0x66, 0x0f, 0x2f, 0x45, 0xf8, // comisd -8(%rbp), %xmm0
+                0xf2, 0x44, 0x0f, 0x2a, 0x7d, 0xfc, // cvtsi2sdl -4(%rbp), %xmm15
+                0xf2, 0x4d, 0x0f, 0x2a, 0xfb, // cvtsi2sdq %r11, %xmm15
+                0xf2, 0x44, 0x0f, 0x2c, 0x5d, 0xf8, // cvttsd2sil -8(%rbp), %r11d
+                0xf2, 0x4d, 0x0f, 0x2c, 0xde, // cvttsd2siq %xmm14, %r11
+            ]
+        );
+        // SSE instructions only write to SSE registers.
+        assert!(matches!(
+            encode(vec![AssemblyInstruction::Binary {
+                op: AssemblyBinaryOperator::Mult,
+                assembly_type: AssemblyType::Double,
+                source: AssemblyOperand::Register(AssemblyRegister::XMM0),
+                destination: AssemblyOperand::Register(AssemblyRegister::AX),
+            }]),
+            Err(EncodingError::InvalidOperands { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encode_program_with_double_constants() {
+        let assembly_ast = AssemblyAst::Program {
+            functions: vec![AssemblyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                instructions: vec![
+                    AssemblyInstruction::Mov {
+                        assembly_type: AssemblyType::Double,
+                        source: AssemblyOperand::Data("double.0".into()),
+                        destination: AssemblyOperand::Register(AssemblyRegister::XMM14),
+                    },
+                    AssemblyInstruction::Ret,
+                ],
+            }],
+            static_constants: vec![
+                AssemblyStaticConstant::String {
+                    identifier: "string.0".into(),
+                    value: b"a".to_vec(),
+                },
+                AssemblyStaticConstant::Double {
+                    identifier: "double.0".into(),
+                    value: 1.5,
+                    alignment: 16,
+                },
+            ],
+            static_variables: Vec::new(),
+        };
+        // The `double` is padded to an offset of 32 bytes from the start of the code.
+        let code = encode_program(&assembly_ast).unwrap();
+        assert_eq!(
+            code[4..13],
+            [0xf2, 0x44, 0x0f, 0x10, 0x35, 0x13, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(code[18..32], [b'a', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(code[32..], 1.5f64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_encode_calls() {
+        assert_eq!(
+            encode(vec![
+                AssemblyInstruction::Push(AssemblyOperand::Imm(5)),
+                AssemblyInstruction::Push(AssemblyOperand::Register(AssemblyRegister::R10)),
+                AssemblyInstruction::Push(AssemblyOperand::Stack(-8)),
+                AssemblyInstruction::Mov {
+                    assembly_type: AssemblyType::Byte,
+                    source: AssemblyOperand::Imm(97),
+                    destination: AssemblyOperand::Register(AssemblyRegister::DI),
+                },
+                AssemblyInstruction::Call("main".into()),
+                AssemblyInstruction::DeallocateStack { stack_offset: 16 },
+            ])
+            .unwrap(),
+            [
+                0x68, 0x05, 0x00, 0x00, 0x00, // pushq $5
+                0x41, 0x52, // pushq %r10
+                0xff, 0x75, 0xf8, // pushq -8(%rbp)
+                0x40, 0xc6, 0xc7, 0x61, // movb $97, %dil
+                0xe8, 0xe9, 0xff, 0xff, 0xff, // call main, 23 bytes back
+                0x48, 0x81, 0xc4, 0x10, 0x00, 0x00, 0x00, // addq $16, %rsp
+            ]
+        );
+        assert_eq!(
+            encode(vec![AssemblyInstruction::Call("f".into())]),
+            Err(EncodingError::UndefinedFunction {
+                identifier: "f".into()
+            })
+        );
+    }
+}
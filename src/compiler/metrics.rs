@@ -0,0 +1,201 @@
+use crate::common::symbol::Symbol;
+use crate::common::target::Target;
+#[cfg(feature = "x86_64")]
+use crate::compiler::code_gen::{
+    self,
+    assembly_ast::{AssemblyAst, AssemblyFunction, AssemblyInstruction},
+};
+use crate::compiler::errors::CompilerError;
+use crate::compiler::ir_gen::{
+    TackyEmitter,
+    tacky_ast::{TackyAst, TackyFunction},
+};
+use crate::compiler::{has_backend, validate_source};
+use std::fmt;
+
+/// Represents code quality metrics of a single compiled function.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionMetrics {
+    /// The name of the function.
+    pub identifier: Symbol,
+    /// The number of TACKY instructions emitted for the function.
+    pub tacky_instructions_before_optimization: usize,
+    /// The number of TACKY instructions left after optimization.
+    pub tacky_instructions_after_optimization: usize,
+    /// The number of assembly instructions, excluding labels, or `None` if no backend ran.
+    pub assembly_instructions: Option<usize>,
+    /// The bytes of stack allocated for the function, or `None` if no backend ran.
+    pub stack_frame_size: Option<u32>,
+}
+
+/// Represents the metrics of every function of a program.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MetricsReport {
+    pub functions: Vec<FunctionMetrics>,
+}
+
+impl fmt::Display for MetricsReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format_optional = |value: Option<String>| value.unwrap_or_else(|| String::from("-"));
+        let identifier_width = self
+            .functions
+            .iter()
+            .map(|function| function.identifier.len())
+            .chain(std::iter::once("function".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "{:<identifier_width$}  {:>12}  {:>11}  {:>8}  {:>11}",
+            "function", "tacky before", "tacky after", "assembly", "stack frame"
+        )?;
+        for function in self.functions.iter() {
+            writeln!(
+                f,
+                "{:<identifier_width$}  {:>12}  {:>11}  {:>8}  {:>11}",
+                function.identifier,
+                function.tacky_instructions_before_optimization,
+                function.tacky_instructions_after_optimization,
+                format_optional(
+                    function
+                        .assembly_instructions
+                        .map(|count| count.to_string())
+                ),
+                format_optional(function.stack_frame_size.map(|size| format!("{} B", size))),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compiles C-- source code and collects code quality metrics for every function.
+///
+/// The assembly metrics are only collected when an enabled backend generates code for the target.
+/// The compiler does not optimize TACKY yet, so the instruction counts before and after
+/// optimization are equal.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The preprocessed source code to compile.
+/// * `target`: The platform to generate code for.
+///
+/// # Returns
+///
+/// A `MetricsReport` on success, or the `CompilerError` of the stage that failed.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::target::Target;
+/// # use cmm::compiler::metrics::collect_metrics;
+/// let report = collect_metrics("int main(void) { return -2; }", &Target::X86_64_LINUX).unwrap();
+/// assert_eq!(report.functions[0].identifier, "main");
+/// assert_eq!(report.functions[0].tacky_instructions_before_optimization, 2);
+/// ```
+pub fn collect_metrics(
+    cmm_source_code: &str,
+    target: &Target,
+) -> Result<MetricsReport, CompilerError> {
+    let tacky_ast = TackyEmitter::new().convert_ast(validate_source(cmm_source_code)?)?;
+    let TackyAst::Program { functions, .. } = &tacky_ast;
+    let mut functions: Vec<FunctionMetrics> = functions
+        .iter()
+        .map(
+            |TackyFunction::Function {
+                 identifier,
+                 instructions,
+                 ..
+             }| FunctionMetrics {
+                identifier: identifier.clone(),
+                tacky_instructions_before_optimization: instructions.len(),
+                tacky_instructions_after_optimization: instructions.len(),
+                assembly_instructions: None,
+                stack_frame_size: None,
+            },
+        )
+        .collect();
+
+    if has_backend(target) {
+        add_assembly_metrics(tacky_ast, &mut functions)?;
+    }
+
+    Ok(MetricsReport { functions })
+}
+
+/// Generates assembly for a program and fills in the assembly metrics of its functions.
+#[cfg(feature = "x86_64")]
+fn add_assembly_metrics(
+    tacky_ast: TackyAst,
+    functions: &mut [FunctionMetrics],
+) -> Result<(), CompilerError> {
+    let AssemblyAst::Program {
+        functions: assembly_functions,
+        ..
+    } = code_gen::convert_ast(tacky_ast)?;
+    for (function_metrics, AssemblyFunction::Function { instructions, .. }) in
+        functions.iter_mut().zip(&assembly_functions)
+    {
+        function_metrics.assembly_instructions = Some(
+            instructions
+                .iter()
+                .filter(|instruction| !matches!(instruction, AssemblyInstruction::Label(_)))
+                .count(),
+        );
+        // The stack frame is allocated first, later allocations pad the arguments of calls.
+        function_metrics.stack_frame_size = Some(
+            instructions
+                .iter()
+                .find_map(|instruction| match instruction {
+                    AssemblyInstruction::AllocateStack { stack_offset } => {
+                        Some(stack_offset.unsigned_abs())
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "x86_64"))]
+fn add_assembly_metrics(
+    _tacky_ast: TackyAst,
+    _functions: &mut [FunctionMetrics],
+) -> Result<(), CompilerError> {
+    unreachable!("No target has a backend when the crate is built without one")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "x86_64")]
+    fn test_collect_metrics_counts_instructions() {
+        let report =
+            collect_metrics("int main(void) { return 1 && ~2; }", &Target::X86_64_LINUX).unwrap();
+        let main = &report.functions[0];
+        assert_eq!(main.tacky_instructions_before_optimization, 9);
+        assert_eq!(main.tacky_instructions_after_optimization, 9);
+        assert_eq!(main.assembly_instructions, Some(13));
+        assert_eq!(main.stack_frame_size, Some(16));
+    }
+
+    #[test]
+    fn test_report_display_without_backend() {
+        let report = MetricsReport {
+            functions: vec![FunctionMetrics {
+                identifier: Symbol::from("main"),
+                tacky_instructions_before_optimization: 4,
+                tacky_instructions_after_optimization: 3,
+                assembly_instructions: None,
+                stack_frame_size: None,
+            }],
+        };
+        assert_eq!(
+            report.to_string(),
+            "function  tacky before  tacky after  assembly  stack frame\n\
+             main                 4            3         -            -\n"
+        );
+    }
+}
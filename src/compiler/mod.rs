@@ -1,27 +1,125 @@
+#[cfg(feature = "x86_64")]
 pub mod code_emission;
+#[cfg(feature = "x86_64")]
 pub mod code_gen;
+pub mod error_codes;
+pub mod errors;
+pub mod interpreter;
 pub mod ir_gen;
 pub mod lexer;
+#[cfg(feature = "x86_64")]
+pub mod machine_code;
+pub mod metrics;
+pub mod optimizer;
 pub mod parser;
+pub mod pipeline;
+#[cfg(feature = "qbe")]
+pub mod qbe_emission;
+pub mod semantic;
+pub mod session;
+#[cfg(feature = "x86_64")]
+pub mod source_map;
 
+use crate::common::diagnostics::{Span, WarningOptions};
+use crate::common::target::{Architecture, Target};
+use crate::compiler::lexer::errors::LexerError;
 use crate::compiler::lexer::tokens::Token;
+use errors::CompilerError;
+use ir_gen::tacky_ast::TackyAst;
+use optimizer::PassManager;
 use parser::Parser;
+use parser::cmm_ast::CmmAst;
+use parser::errors::ParserError;
+use semantic::{ValidatedAst, validate};
+use session::CompilerSession;
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
 
 /// Represents the different stages a C-- compilation can proceed to.
 ///
-/// This enum allows for early termination of the compilation process after a specific stage.
-#[derive(Debug)]
+/// This enum allows for early termination of the compilation process after a specific stage. The
+/// stages are listed in the order they run, and parse from their lowercase names, e.g. `validate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum Stage {
     /// Stop after the lexing stage.
     Lex,
     /// Stop after the parsing stage.
     Parse,
-    /// Stop after the TACKY IR stage.
+    /// Stop after the semantic analysis, which resolves the variables and reports the warnings of
+    /// the program.
+    Validate,
+    /// Stop after the TACKY IR stage, before the passes of the `PassManager` run.
     Tacky,
+    /// Stop after the passes of the `PassManager` transformed the TACKY IR.
+    Optimize,
     /// Stop after the code generation stage.
     Codegen,
 }
 
+impl Stage {
+    /// Every stage, in the order they run.
+    pub const ALL: [Stage; 6] = [
+        Stage::Lex,
+        Stage::Parse,
+        Stage::Validate,
+        Stage::Tacky,
+        Stage::Optimize,
+        Stage::Codegen,
+    ];
+
+    /// Returns the lowercase name of the stage, e.g. `validate`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Lex => "lex",
+            Stage::Parse => "parse",
+            Stage::Validate => "validate",
+            Stage::Tacky => "tacky",
+            Stage::Optimize => "optimize",
+            Stage::Codegen => "codegen",
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Stage {
+    type Err = String;
+
+    /// Parses the lowercase name of a stage, e.g. `codegen`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Stage::ALL
+            .into_iter()
+            .find(|stage| stage.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Stage::ALL.iter().map(Stage::name).collect();
+                format!(
+                    "Unknown stage '{}', expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Represents the language standards the compiler accepts source code of.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Standard {
+    /// Standard C, which rejects the GNU extensions.
+    #[default]
+    #[cfg_attr(feature = "cli", value(aliases = ["c99", "c11", "c17"]))]
+    C,
+    /// C with the GNU extensions, such as binary constants like `0b1010`.
+    #[cfg_attr(feature = "cli", value(aliases = ["gnu99", "gnu11", "gnu17"]))]
+    Gnu,
+}
+
 /// Represents the possible outcomes of a compiler stage.
 ///
 /// Each variant encapsulates the successful result of a specific phase in the compilation process,
@@ -31,60 +129,342 @@ pub enum CompilerResult {
     /// The result of the lexer, a vector of tokens.
     Lexer(Vec<Token>),
     /// The result of the parser, an Abstract Syntax Tree (AST).
-    Parser(parser::cmm_ast::CmmAst),
+    Parser(CmmAst),
+    /// The result of the semantic analysis, the AST of a program without errors.
+    Validator(CmmAst),
     /// The result of the Tacky intermediate representation generation.
-    Tacky(ir_gen::tacky_ast::TackyAst),
+    Tacky(TackyAst),
+    /// The result of the passes of the `PassManager`, the transformed TACKY IR.
+    Optimizer(TackyAst),
     /// The result of the code generator, an assembly AST.
+    #[cfg(feature = "x86_64")]
     Codegen(code_gen::assembly_ast::AssemblyAst),
     /// The final emitted code as a string.
     Final(String),
 }
 
+/// Options that control how a single C-- compilation is executed.
+#[derive(Debug, Default, Clone)]
+pub struct CompileOptions {
+    /// An optional `Stage` to specify the maximum compilation stage to reach.
+    pub process_until: Option<Stage>,
+    /// The platform the emitted assembly is built for.
+    pub target: Target,
+    /// Suppresses status messages, so that only the requested output is written to stdout.
+    pub quiet: bool,
+    /// The `Backend` generating code from the TACKY IR.
+    pub backend: Backend,
+    /// The warnings the compilation reports, and whether they fail it, like the `-W` flags.
+    pub warnings: WarningOptions,
+    /// The language `Standard` of the source code, which decides whether GNU extensions are
+    /// accepted.
+    pub standard: Standard,
+}
+
+/// Represents the code generators that can turn TACKY IR into the final output.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Backend {
+    /// The built-in code generator, which emits assembly for the target architecture.
+    #[default]
+    Native,
+    /// Emits QBE intermediate language, which the `qbe` compiler turns into assembly for the target.
+    /// QBE IL has no assembly AST, so `Stage::Codegen` returns the final QBE IL as well.
+    #[cfg(feature = "qbe")]
+    Qbe,
+}
+
+impl Backend {
+    /// Checks whether the backend generates code for the given target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target`: The platform to generate code for.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the backend is enabled and supports the target architecture.
+    pub fn supports(&self, target: &Target) -> bool {
+        match self {
+            Backend::Native => has_backend(target),
+            // QBE supports every architecture and operating system known to the compiler.
+            #[cfg(feature = "qbe")]
+            Backend::Qbe => true,
+        }
+    }
+}
+
+/// Checks whether an enabled backend generates code for the given target.
+///
+/// # Arguments
+///
+/// * `target`: The platform to generate code for.
+///
+/// # Returns
+///
+/// `true` if the crate was built with a backend for the target architecture.
+pub fn has_backend(target: &Target) -> bool {
+    match target.architecture {
+        Architecture::X86_64 => cfg!(feature = "x86_64"),
+        Architecture::Aarch64 => false,
+    }
+}
+
+/// Lexes and parses C-- source code without collecting the tokens up front.
+///
+/// The parser pulls each token from the lexer as it needs it, so a syntax error is reported as
+/// soon as it is found. A lexer error takes precedence over the parser error it causes, since the
+/// parser only sees the tokens before it.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The source code to parse.
+///
+/// # Returns
+///
+/// The `CmmAst` of the program on success, or the `CompilerError` of the lexer or the parser.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::errors::CompilerError;
+/// # use cmm::compiler::parse_source;
+/// assert!(parse_source("int main(void) { return 2; }").is_ok());
+/// let error = parse_source("int main(void) { return @; }").unwrap_err();
+/// assert!(matches!(error, CompilerError::Lexer(_)));
+/// assert_eq!(error.span().unwrap().start_column, 25);
+/// ```
+pub fn parse_source(cmm_source_code: &str) -> Result<CmmAst, CompilerError> {
+    parse_token_stream(lexer::Lexer::new(cmm_source_code).with_spans())
+}
+
+/// Parses C-- source code and validates it, see `parse_source` and `validate`. Warnings are not
+/// reported.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The source code to validate.
+///
+/// # Returns
+///
+/// The `ValidatedAst` of the program on success, or the `CompilerError` of the lexer, the parser,
+/// or the semantic analysis.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::errors::CompilerError;
+/// # use cmm::compiler::validate_source;
+/// assert!(validate_source("int main(void) { return 2; }").is_ok());
+/// let error = validate_source("int main(void) { continue; }").unwrap_err();
+/// assert!(matches!(error, CompilerError::Semantic(_)));
+/// ```
+pub fn validate_source(cmm_source_code: &str) -> Result<ValidatedAst, CompilerError> {
+    Ok(validate(parse_source(cmm_source_code)?)?)
+}
+
+/// Parses the tokens of a lazy token stream, such as a `Lexer` created with `with_spans`, see
+/// `parse_source`.
+///
+/// A parser error is located at the token the parser looked at last when it failed, which is the
+/// offending token, or at the end of the last token when the input ends too early.
+///
+/// # Arguments
+///
+/// * `tokens`: The stream of tokens and their locations, which ends after the first `LexerError`.
+///
+/// # Returns
+///
+/// The `CmmAst` of the program on success, or the `CompilerError` of the lexer or the parser.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::errors::CompilerError;
+/// # use cmm::compiler::lexer::Lexer;
+/// # use cmm::compiler::parse_token_stream;
+/// let tokens = Lexer::new("int main(void) {\n    return 2\n}").with_spans();
+/// let Err(CompilerError::Parser(error)) = parse_token_stream(tokens) else {
+///     panic!("Expected a parser error");
+/// };
+/// let span = error.span().unwrap();
+/// assert_eq!((span.start_line, span.start_column), (3, 1));
+/// ```
+pub fn parse_token_stream(
+    tokens: impl Iterator<Item = Result<(Token, Span), LexerError>>,
+) -> Result<CmmAst, CompilerError> {
+    let mut lexer_error = None;
+    // The location of the last token the parser pulled, which may be a token it only peeked at.
+    let last_span = Cell::new(None);
+    let cmm_ast = Parser::new(tokens.map_while(|token| match token {
+        Ok((token, span)) => {
+            last_span.set(Some(span));
+            Some((token, span))
+        }
+        Err(error) => {
+            lexer_error = Some(error);
+            None
+        }
+    }))
+    .parse_ast();
+    let last_span = last_span.get();
+    if let Some(error) = lexer_error {
+        return Err(error.into());
+    }
+    cmm_ast.map_err(|error| {
+        let span = match (&error, last_span) {
+            (ParserError::UnexpectedEndOfInput, Some(span)) => Some(Span {
+                start_line: span.end_line,
+                start_column: span.end_column,
+                ..span
+            }),
+            (_, span) => span,
+        };
+        match span {
+            Some(span) => ParserError::Located {
+                error: Box::new(error),
+                span,
+            }
+            .into(),
+            None => error.into(),
+        }
+    })
+}
+
 /// Compiles a preprocessed C-- source code to assembly code.
 ///
 /// This function orchestrates the entire compilation pipeline, from lexing to assembly emission.
-/// It can be configured to stop at a specific stage using the `process_until` argument.
+/// It can be configured to stop at a specific stage using the `process_until` option.
 ///
 /// # Arguments
 ///
 /// * `cmm_source_code`: The source code to compile.
-/// * `process_until`: An optional `Stage` to specify the maximum compilation stage to reach.
+/// * `options`: The `CompileOptions` controlling the final stage and the emission target.
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on successful compilation, or an `anyhow::Error` if any stage of the compilation fails.
+/// Returns the `CompilerResult` of the last executed stage on success, or a `CompilerError` describing
+/// the stage that failed.
 pub fn run_cmm_compiler(
     cmm_source_code: &str,
-    process_until: &Option<Stage>,
-) -> anyhow::Result<CompilerResult> {
-    println!("Compiling with a custom C compiler...");
-    let tokens = lexer::tokenize(cmm_source_code);
-
-    if let Some(Stage::Lex) = process_until {
-        return Ok(CompilerResult::Lexer(tokens));
-    }
+    options: &CompileOptions,
+) -> Result<CompilerResult, CompilerError> {
+    run_cmm_compiler_with_passes(cmm_source_code, options, &mut PassManager::new())
+}
 
-    let mut parser = Parser::new(tokens);
-    let cmm_ast = parser.parse_ast()?;
+/// Compiles a preprocessed C-- source code to assembly code, transforming the TACKY IR with custom passes.
+///
+/// Behaves like `run_cmm_compiler`, but runs the passes of the `PassManager` over the TACKY IR
+/// before code generation. Stopping at `Stage::Tacky` returns the IR before the passes run, and
+/// stopping at `Stage::Optimize` returns it after they ran.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The source code to compile.
+/// * `options`: The `CompileOptions` controlling the final stage and the emission target.
+/// * `pass_manager`: The `PassManager` holding the passes to run.
+///
+/// # Returns
+///
+/// Returns the `CompilerResult` of the last executed stage on success, or a `CompilerError` describing
+/// the stage that failed.
+pub fn run_cmm_compiler_with_passes(
+    cmm_source_code: &str,
+    options: &CompileOptions,
+    pass_manager: &mut PassManager,
+) -> Result<CompilerResult, CompilerError> {
+    CompilerSession::new(options.clone()).compile_with_passes(cmm_source_code, pass_manager)
+}
 
-    if let Some(Stage::Parse) = process_until {
-        return Ok(CompilerResult::Parser(cmm_ast));
+/// Compiles a preprocessed C-- source code all the way to assembly code, without printing anything.
+///
+/// Unlike the compiler driver, this function neither touches the filesystem nor spawns processes,
+/// so it also works in sandboxed hosts such as WebAssembly.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The source code to compile.
+/// * `target`: The platform the emitted assembly is built for.
+///
+/// # Returns
+///
+/// The assembly code on success, or the `CompilerError` of the stage that failed.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::target::Target;
+/// # use cmm::compiler::compile_to_assembly_string;
+/// # #[cfg(feature = "x86_64")] {
+/// let assembly_code =
+///     compile_to_assembly_string("int main(void) { return 2; }", &Target::X86_64_LINUX).unwrap();
+/// assert!(assembly_code.contains("movl $2, %eax"));
+/// # }
+/// ```
+pub fn compile_to_assembly_string(
+    cmm_source_code: &str,
+    target: &Target,
+) -> Result<String, CompilerError> {
+    let options = CompileOptions {
+        process_until: None,
+        target: *target,
+        quiet: true,
+        backend: Backend::Native,
+        warnings: WarningOptions::default(),
+        standard: Standard::C,
+    };
+    match run_cmm_compiler(cmm_source_code, &options)? {
+        CompilerResult::Final(assembly_code) => Ok(assembly_code),
+        _ => unreachable!("A compilation without a stopping stage returns the final code"),
     }
+}
 
-    let mut tacky_emitter = ir_gen::TackyEmitter::new();
-    let tacky_ast = tacky_emitter.convert_ast(cmm_ast)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if let Some(Stage::Tacky) = process_until {
-        return Ok(CompilerResult::Tacky(tacky_ast));
+    #[test]
+    fn test_stage_names_round_trip() {
+        for stage in Stage::ALL {
+            assert_eq!(stage.to_string().parse::<Stage>(), Ok(stage));
+        }
+        assert_eq!(
+            "emit".parse::<Stage>(),
+            Err(String::from(
+                "Unknown stage 'emit', expected one of lex, parse, validate, tacky, optimize, codegen"
+            ))
+        );
     }
 
-    let assembly_ast = code_gen::convert_ast(tacky_ast)?;
-
-    if let Some(Stage::Codegen) = process_until {
-        return Ok(CompilerResult::Codegen(assembly_ast));
+    #[test]
+    fn test_parser_errors_are_located() {
+        let location = |source: &str| {
+            let span = parse_source(source).unwrap_err().span().unwrap();
+            (span.start_line, span.start_column)
+        };
+        assert_eq!(location("int main(void) {\n    return ~;\n}"), (2, 13));
+        assert_eq!(location("int main(void) { return 0; }\n}"), (2, 1));
+        assert_eq!(location("int x = ;\nint main(void) { return 0; }"), (1, 9));
+        assert_eq!(location("int f(void);\n  x = 1;\n"), (2, 3));
+        assert_eq!(location("int main(void) {\n  return 0;"), (2, 12));
+        assert_eq!(parse_source("").unwrap_err().span(), None);
     }
 
-    let assembly_code = code_emission::emit_assembly(&assembly_ast);
-
-    Ok(CompilerResult::Final(assembly_code))
+    #[test]
+    fn test_semantic_errors_are_located() {
+        let location = |source: &str| {
+            let error = CompilerError::from(validate(parse_source(source).unwrap()).unwrap_err());
+            let span = error.span().unwrap();
+            (span.start_line, span.start_column)
+        };
+        assert_eq!(location("int main(void) {\n    return 1 + x;\n}"), (2, 16));
+        assert_eq!(location("int main(void) {\n    break;\n}"), (2, 5));
+        assert_eq!(location("int main(void) {\n  int a; int a;\n}"), (2, 10));
+        assert_eq!(
+            location("int main(void) {\n  return 1 + (2.0 % 3);\n}"),
+            (2, 15)
+        );
+        assert_eq!(location("void f(void) {\n  return 1;\n}"), (2, 3));
+        assert_eq!(location("int f(int a,\n int a);"), (1, 1));
+        assert_eq!(location("struct s { int m; int m; };"), (1, 1));
+    }
 }
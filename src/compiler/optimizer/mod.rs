@@ -0,0 +1,261 @@
+use crate::compiler::ir_gen::tacky_ast::{TackyAst, TackyFunction};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::fmt;
+
+/// Represents a transformation of TACKY IR.
+///
+/// Implement this trait to add custom IR transformations to the compiler without forking it, and
+/// register them in a `PassManager` passed to `run_cmm_compiler_with_passes`.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyFunction, TackyInstruction};
+/// # use cmm::compiler::optimizer::TackyPass;
+/// /// Removes every label, which is only valid for functions without jumps.
+/// struct RemoveLabels;
+///
+/// impl TackyPass for RemoveLabels {
+///     fn name(&self) -> &str {
+///         "remove-labels"
+///     }
+///
+///     fn run(&self, function: &mut TackyFunction) -> bool {
+///         let TackyFunction::Function { instructions, .. } = function;
+///         let original_length = instructions.len();
+///         instructions.retain(|instruction| !matches!(instruction, TackyInstruction::Label(_)));
+///         instructions.len() != original_length
+///     }
+/// }
+/// ```
+pub trait TackyPass: Send + Sync {
+    /// Returns the name of the pass, used in traces and to identify the pass in a `PassManager`.
+    fn name(&self) -> &str;
+
+    /// Transforms a function in place.
+    ///
+    /// With the `parallel` feature, the functions of a program are transformed in parallel, so the
+    /// pass is shared between threads and only sees the function it transforms. A pass that keeps
+    /// state between functions holds it behind a `Mutex` or an atomic.
+    ///
+    /// Before version 0.2.0, this method took `&mut self` and the trait had no `Send + Sync`
+    /// bounds. The signature does not depend on the features, so a pass compiles with and
+    /// without `parallel`.
+    ///
+    /// # Arguments
+    ///
+    /// * `function`: The `TackyFunction` to transform.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the pass changed the function.
+    fn run(&self, function: &mut TackyFunction) -> bool;
+}
+
+/// Runs a sequence of registered `TackyPass`es over the TACKY IR of a program.
+///
+/// The passes run once each, in the order they were registered. The functions are independent of
+/// each other, so with the `parallel` feature each function runs through the passes on its own
+/// thread of the rayon pool. Without it, the functions run through the passes one after another.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn TackyPass>>,
+}
+
+impl PassManager {
+    /// Creates a `PassManager` without any passes.
+    pub fn new() -> Self {
+        PassManager::default()
+    }
+
+    /// Registers a pass to run after the passes registered before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `pass`: The `TackyPass` to register.
+    ///
+    /// # Returns
+    ///
+    /// The `PassManager`, so that registrations can be chained.
+    pub fn register<P: TackyPass + 'static>(&mut self, pass: P) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Returns the names of the registered passes, in the order they run.
+    pub fn pass_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    /// Returns `true` if no pass has been registered.
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Runs every registered pass over every function of a program.
+    ///
+    /// # Arguments
+    ///
+    /// * `tacky_ast`: The TACKY IR to transform in place.
+    ///
+    /// # Returns
+    ///
+    /// `true` if any pass changed the program.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "optimize", skip_all))]
+    pub fn run(&mut self, tacky_ast: &mut TackyAst) -> bool {
+        let TackyAst::Program { functions, .. } = tacky_ast;
+        #[cfg(feature = "tracing")]
+        let optimize_span = tracing::Span::current();
+        let optimize = |function: &mut TackyFunction| {
+            let mut changed = false;
+            for pass in self.passes.iter() {
+                #[cfg(feature = "tracing")]
+                let _span = tracing::info_span!(parent: &optimize_span, "pass", name = pass.name())
+                    .entered();
+                changed |= pass.run(function);
+            }
+            changed
+        };
+        #[cfg(feature = "parallel")]
+        let changed = functions
+            .par_iter_mut()
+            .map(optimize)
+            .reduce(|| false, |left, right| left || right);
+        #[cfg(not(feature = "parallel"))]
+        let changed = functions
+            .iter_mut()
+            .map(optimize)
+            .fold(false, |changed, function_changed| {
+                changed | function_changed
+            });
+        changed
+    }
+}
+
+impl fmt::Debug for PassManager {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.pass_names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::symbol::Symbol;
+    use crate::compiler::ir_gen::tacky_ast::{TackyConstant, TackyInstruction, TackyValue};
+    use std::collections::BTreeMap;
+
+    /// Replaces the returned constant with a fixed value.
+    struct ReturnConstant(i32);
+
+    impl TackyPass for ReturnConstant {
+        fn name(&self) -> &str {
+            "return-constant"
+        }
+
+        fn run(&self, function: &mut TackyFunction) -> bool {
+            let TackyFunction::Function { instructions, .. } = function;
+            let mut changed = false;
+            for instruction in instructions.iter_mut() {
+                if let TackyInstruction::Return { value } = instruction
+                    && *value != Some(TackyValue::Constant(TackyConstant::Int(self.0)))
+                {
+                    *value = Some(TackyValue::Constant(TackyConstant::Int(self.0)));
+                    changed = true;
+                }
+            }
+            changed
+        }
+    }
+
+    fn return_program(value: i32) -> TackyAst {
+        TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: Symbol::from("main"),
+                global: true,
+                parameters: vec![],
+                instructions: vec![TackyInstruction::Return {
+                    value: Some(TackyValue::Constant(TackyConstant::Int(value))),
+                }],
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_passes_run_in_registration_order() {
+        let mut pass_manager = PassManager::new();
+        pass_manager
+            .register(ReturnConstant(3))
+            .register(ReturnConstant(4));
+        assert_eq!(
+            pass_manager.pass_names().collect::<Vec<_>>(),
+            vec!["return-constant", "return-constant"]
+        );
+
+        let mut tacky_ast = return_program(1);
+        assert!(pass_manager.run(&mut tacky_ast));
+        assert_eq!(tacky_ast, return_program(4));
+    }
+
+    /// Appends the index of the pass to the name of every function.
+    struct RenameFunction(usize);
+
+    impl TackyPass for RenameFunction {
+        fn name(&self) -> &str {
+            "rename-function"
+        }
+
+        fn run(&self, function: &mut TackyFunction) -> bool {
+            let TackyFunction::Function { identifier, .. } = function;
+            *identifier = Symbol::from(format!("{}.{}", identifier, self.0));
+            true
+        }
+    }
+
+    #[test]
+    fn test_functions_keep_their_order() {
+        let function_names = (0..64).map(|index| format!("f{}", index));
+        let mut tacky_ast = TackyAst::Program {
+            functions: function_names
+                .clone()
+                .map(|name| TackyFunction::Function {
+                    identifier: Symbol::from(name),
+                    global: true,
+                    parameters: vec![],
+                    instructions: vec![],
+                    variable_types: BTreeMap::new(),
+                })
+                .collect(),
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        let mut pass_manager = PassManager::new();
+        pass_manager
+            .register(RenameFunction(1))
+            .register(RenameFunction(2));
+        assert!(pass_manager.run(&mut tacky_ast));
+
+        // Every function runs through the passes in order, and stays at its position.
+        let TackyAst::Program { functions, .. } = tacky_ast;
+        assert_eq!(
+            functions
+                .iter()
+                .map(|TackyFunction::Function { identifier, .. }| identifier.to_string())
+                .collect::<Vec<_>>(),
+            function_names
+                .map(|name| format!("{}.1.2", name))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_empty_pass_manager_keeps_program() {
+        let mut tacky_ast = return_program(1);
+        assert!(!PassManager::new().run(&mut tacky_ast));
+        assert_eq!(tacky_ast, return_program(1));
+    }
+}
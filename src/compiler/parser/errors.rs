@@ -1,3 +1,5 @@
+use crate::common::diagnostics::Span;
+use crate::compiler::error_codes::{self, ErrorCode};
 use crate::compiler::lexer::tokens::{Token, TokenType};
 use std::error::Error;
 use std::fmt;
@@ -26,8 +28,59 @@ pub enum ParserError {
         actual: TokenType,
     },
 
-    /// Raised when the parser encounters trailing tokens after the program has been parsed.
-    UnexpectedTrailingTokens { found: Vec<Token> },
+    /// Raised when the input continues after a parsed statement, e.g. `1 2` in the REPL.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The first token after the statement.
+    UnexpectedTrailingToken { found: Token },
+
+    /// Raised when the type specifiers of a declaration do not form a valid type, e.g. `int int`.
+    ///
+    /// # Arguments
+    ///
+    /// * `specifiers`: The type specifiers of the declaration, in source order.
+    InvalidTypeSpecifiers { specifiers: Vec<TokenType> },
+
+    /// Raised when the size of an array declaration is not a positive integer constant, e.g.
+    /// `int a[0]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The token given as the size.
+    InvalidArraySize { found: Token },
+
+    /// Wraps an error with the location of the token it was raised at. The parser itself only
+    /// sees tokens, so the locations are added by `parse_token_stream`, which parses tokens
+    /// together with their locations.
+    ///
+    /// # Arguments
+    ///
+    /// * `error`: The error raised by the parser.
+    /// * `span`: The location of the offending token, or of the end of the input.
+    Located { error: Box<ParserError>, span: Span },
+}
+
+impl ParserError {
+    /// Returns the stable `ErrorCode` of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ParserError::UnexpectedEndOfInput => error_codes::UNEXPECTED_END_OF_INPUT,
+            ParserError::UnexpectedToken { .. } => error_codes::EXPECTED_TOKEN,
+            ParserError::UnexpectedTrailingToken { .. } => error_codes::TRAILING_TOKENS,
+            ParserError::InvalidTypeSpecifiers { .. } => error_codes::INVALID_TYPE_SPECIFIERS,
+            ParserError::InvalidArraySize { .. } => error_codes::INVALID_ARRAY_SIZE,
+            ParserError::Located { error, .. } => error.code(),
+        }
+    }
+
+    /// Returns the location of the error, or `None` if it was raised without one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ParserError::Located { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ParserError {
@@ -50,13 +103,20 @@ impl fmt::Display for ParserError {
                     )
                 }
             },
-            ParserError::UnexpectedTrailingTokens { found } => {
-                write!(
-                    f,
-                    "Parser error: Unexpected trailing tokens found {:?}",
-                    found
-                )
+            ParserError::UnexpectedTrailingToken { found } => {
+                write!(f, "Parser error: Unexpected trailing token {:?}", found)
+            }
+            ParserError::InvalidTypeSpecifiers { specifiers } => {
+                write!(f, "Parser error: Invalid type specifiers {:?}", specifiers)
+            }
+            ParserError::InvalidArraySize { found } => {
+                write!(f, "Parser error: Invalid array size {:?}", found)
             }
+            ParserError::Located { error, span } => write!(
+                f,
+                "{} at line {}, column {}",
+                error, span.start_line, span.start_column
+            ),
         }
     }
 }
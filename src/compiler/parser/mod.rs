@@ -1,42 +1,108 @@
-pub mod cmm_ast;
 pub mod errors;
+pub use cmm_types::{cmm_ast, printer};
 
+use crate::common::diagnostics::Span;
+use crate::common::symbol::Symbol;
 use crate::compiler::lexer::tokens::{Token, TokenType};
 use cmm_ast::{
-    CmmAst, CmmBinaryOperator, CmmExpression, CmmFunction, CmmStatement, CmmUnaryOperator,
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmEnumDeclaration, CmmEnumerator,
+    CmmExpression, CmmForInit, CmmFunction, CmmInitializer, CmmMember, CmmParameter, CmmStatement,
+    CmmStorageClass, CmmStructDeclaration, CmmType, CmmUnaryOperator, ExprId, ExpressionArena,
 };
 use errors::{ParserError, TokenTypeOption};
+use std::iter::Peekable;
+
+/// The precedence of the assignment operator, lower than the precedence of every binary operator.
+const ASSIGNMENT_PRECEDENCE: u32 = 1;
+
+/// Represents an item of the token stream of a `Parser`, either a bare `Token` or a `Token`
+/// paired with its location, as produced by a `Lexer` created with `with_spans`.
+pub trait ParserToken {
+    /// Returns the token of the item.
+    fn token(&self) -> &Token;
+
+    /// Returns the location of the token, or `None` if the stream has no locations.
+    fn span(&self) -> Option<Span>;
+
+    /// Consumes the item and returns its token.
+    fn into_token(self) -> Token;
+}
+
+impl ParserToken for Token {
+    fn token(&self) -> &Token {
+        self
+    }
+
+    fn span(&self) -> Option<Span> {
+        None
+    }
+
+    fn into_token(self) -> Token {
+        self
+    }
+}
+
+impl ParserToken for (Token, Span) {
+    fn token(&self) -> &Token {
+        &self.0
+    }
+
+    fn span(&self) -> Option<Span> {
+        Some(self.1)
+    }
+
+    fn into_token(self) -> Token {
+        self.0
+    }
+}
+
+/// Represents a declaration at the top level of a program that starts with a type, see
+/// `Parser::parse_top_level_declaration`.
+enum TopLevelDeclaration {
+    /// A file-scope variable declaration.
+    Variable(CmmDeclaration),
+    /// A function declaration or definition.
+    Function(CmmFunction),
+}
 
 /// Represents a parser for a given sequence of tokens.
 ///
 /// It is responsible for consuming tokens and constructing an Abstract Syntax Tree (AST).
-pub struct Parser {
-    /// The sequence of tokens to be parsed.
-    pub tokens: Vec<Token>,
-    /// The current position within the `tokens` vector.
-    pub position: usize,
+/// The tokens are pulled from an iterator one at a time, so they can be lexed on demand instead
+/// of being collected up front. When the tokens come with their locations, the parsed nodes hold
+/// the spans from their first to their last token, and no token is kept after it is consumed.
+pub struct Parser<I: Iterator<Item: ParserToken> = std::vec::IntoIter<Token>> {
+    /// The remaining tokens, with a lookahead buffer of one token.
+    tokens: Peekable<I>,
+    /// The arena the parsed expressions are stored in.
+    expressions: ExpressionArena,
+    /// The location of the last consumed token.
+    last_span: Option<Span>,
 }
 
-impl Parser {
+impl<I: Iterator<Item: ParserToken>> Parser<I> {
     /// Creates a new `Parser` instance.
     ///
     /// # Arguments
     ///
-    /// * `tokens`: A vector of `Token`s to be parsed.
+    /// * `tokens`: The `Token`s to be parsed, e.g. a vector or a lazy `Lexer` stream, optionally
+    ///   paired with their `Span`s.
     ///
     /// # Returns
     ///
     /// A new `Parser` instance initialized with the provided tokens.
-    pub fn new(tokens: Vec<Token>) -> Self {
+    pub fn new<T: IntoIterator<IntoIter = I>>(tokens: T) -> Self {
         Self {
-            tokens,
-            position: 0,
+            tokens: tokens.into_iter().peekable(),
+            expressions: ExpressionArena::new(),
+            last_span: None,
         }
     }
 
     /// Parses the entire sequence of tokens into an Abstract Syntax Tree (AST).
     ///
-    /// This is the main entry point for the parsing process.
+    /// This is the main entry point for the parsing process. A program consists of one or more
+    /// structure, enumeration, variable, and function declarations and function definitions.
     ///
     /// # Returns
     ///
@@ -45,11 +111,12 @@ impl Parser {
     /// # Examples
     ///
     /// ```
+    /// # use cmm::common::symbol::Symbol;
     /// # use cmm::compiler::lexer::tokens::Token;
-    /// # use cmm::compiler::parser::cmm_ast::{CmmAst, CmmFunction, CmmStatement, CmmExpression, CmmUnaryOperator};
+    /// # use cmm::compiler::parser::cmm_ast::{CmmAst, CmmBlockItem, CmmFunction, CmmType, CmmStatement, CmmExpression, CmmUnaryOperator, ExpressionArena};
     /// # use cmm::compiler::parser::Parser;
     /// # use cmm::compiler::parser::errors::ParserError;
-    /// let identifier = "main".to_string();
+    /// let identifier = Symbol::from("main");
     /// let tokens = vec![
     ///     Token::IntKeyword,
     ///     Token::Identifier(identifier.clone()),
@@ -67,560 +134,2720 @@ impl Parser {
     /// ];
     /// let mut parser = Parser::new(tokens);
     /// let ast = parser.parse_ast()?;
-    /// assert_eq!(ast, CmmAst::Program { function: CmmFunction::Function { identifier, body: CmmStatement::Return { expression: CmmExpression::Unary { operator: CmmUnaryOperator::Negate, expression: Box::new(CmmExpression::IntegerConstant { value: 1 }) } } } });
+    /// let mut expressions = ExpressionArena::new();
+    /// let constant = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
+    /// let expression = expressions.alloc(CmmExpression::Unary { operator: CmmUnaryOperator::Negate, expression: constant });
+    /// assert_eq!(ast, CmmAst::Program { structs: vec![], enums: vec![], variables: vec![], functions: vec![CmmFunction::Function { identifier, storage_class: None, return_type: Some(CmmType::Int), parameters: vec![], variadic: false, body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return { expression: Some(expression), span: None })]), span: None }], expressions });
     /// # Ok::<(), ParserError>(())
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "parse", skip_all))]
     pub fn parse_ast(&mut self) -> Result<CmmAst, ParserError> {
-        let function = self.parse_function()?;
-        if self.position < self.tokens.len() {
-            return Err(ParserError::UnexpectedTrailingTokens {
-                found: self.tokens[self.position..].to_vec(),
-            });
+        let mut structs = Vec::new();
+        let mut enums = Vec::new();
+        let mut variables = Vec::new();
+        let mut functions = Vec::new();
+        loop {
+            if self.next_token_if_eq(&Token::StructKeyword) {
+                structs.push(self.parse_struct_declaration()?);
+            } else if self.next_token_if_eq(&Token::EnumKeyword) {
+                enums.push(self.parse_enum_declaration()?);
+            } else if starts_declaration(self.peek_token()?) {
+                match self.parse_top_level_declaration()? {
+                    TopLevelDeclaration::Variable(declaration) => variables.push(declaration),
+                    TopLevelDeclaration::Function(function) => functions.push(function),
+                }
+            } else {
+                // Only the offending token is consumed, so the error is located at it.
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::Many(vec![
+                        TokenType::IntKeyword,
+                        TokenType::VoidKeyword,
+                        TokenType::StructKeyword,
+                        TokenType::EnumKeyword,
+                        TokenType::StaticKeyword,
+                        TokenType::ExternKeyword,
+                    ]),
+                    actual: self.consume_token()?.kind(),
+                });
+            }
+            if self.tokens.peek().is_none() {
+                break;
+            }
         }
-        Ok(CmmAst::Program { function })
+        Ok(CmmAst::Program {
+            structs,
+            enums,
+            variables,
+            functions,
+            expressions: std::mem::take(&mut self.expressions),
+        })
     }
 
-    /// Parses a function definition from the token stream.
+    /// Parses a single statement, or a bare expression, which is treated as a return statement.
     ///
-    /// A function definition is expected to start with `int`, followed by an identifier,
-    /// parentheses, and a body containing a statement.
+    /// The semicolon after a bare expression is optional. This is the entry point for evaluating
+    /// snippets of C-- code outside of a function, e.g. in the REPL.
+    ///
+    /// The expressions of the statement stay in the parser, see `Parser::into_expressions`.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the `CmmFunction` if successful, or a `ParserError`.
-    fn parse_function(&mut self) -> Result<CmmFunction, ParserError> {
-        let _int = self.expect_token(TokenType::IntKeyword)?;
-        let identifier = self.parse_identifier()?;
-        let _open_paren = self.expect_token(TokenType::OpenParen)?;
-        let _void = self.expect_token(TokenType::VoidKeyword)?;
-        let _close_paren = self.expect_token(TokenType::CloseParen)?;
-        let _open_brace = self.expect_token(TokenType::OpenBrace)?;
-        let statement = self.parse_statement()?;
-        let _close_brace = self.expect_token(TokenType::CloseBrace)?;
-        Ok(CmmFunction::Function {
-            identifier,
-            body: statement,
-        })
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::lexer::tokens::Token;
+    /// # use cmm::compiler::parser::Parser;
+    /// # use cmm::compiler::parser::cmm_ast::{CmmStatement, CmmExpression};
+    /// let mut parser = Parser::new(vec![Token::Constant(2)]);
+    /// let Ok(CmmStatement::Return { expression: Some(expression), .. }) = parser.parse_statement_or_expression() else {
+    ///     panic!("Expected a return statement");
+    /// };
+    /// assert_eq!(
+    ///     parser.into_expressions()[expression],
+    ///     CmmExpression::IntegerConstant { value: 2 }
+    /// );
+    /// ```
+    pub fn parse_statement_or_expression(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        let statement = match self.peek_token()? {
+            Token::ReturnKeyword
+            | Token::IfKeyword
+            | Token::WhileKeyword
+            | Token::DoKeyword
+            | Token::ForKeyword
+            | Token::BreakKeyword
+            | Token::ContinueKeyword
+            | Token::Semicolon => self.parse_statement()?,
+            _ => {
+                let expression = self.parse_expression(0)?;
+                self.next_token_if_eq(&Token::Semicolon);
+                CmmStatement::Return {
+                    expression: Some(expression),
+                    span: self.span_from(start),
+                }
+            }
+        };
+        self.expect_end_of_input()?;
+        Ok(statement)
     }
 
-    /// Parses a single statement from the token stream.
+    /// Consumes the parser and returns the arena of the expressions parsed so far.
+    pub fn into_expressions(self) -> ExpressionArena {
+        self.expressions
+    }
+
+    /// Parses a function or a file-scope variable declaration from the token stream.
+    ///
+    /// A declaration starts with its type, `void` or any type without an array suffix such as
+    /// `double` or `long *`, optionally preceded by a `static` or `extern` storage-class specifier,
+    /// followed by an identifier. An identifier followed by a parameter list in parentheses
+    /// declares a function. A definition continues with a body of declarations and statements in
+    /// braces, while a prototype ends with a semicolon. Otherwise, the identifier declares a
+    /// variable, with optional array sizes and initializer, terminated by a semicolon.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
-    fn parse_statement(&mut self) -> Result<CmmStatement, ParserError> {
-        let _return = self.expect_token(TokenType::ReturnKeyword)?;
-        let expression = self.parse_expression(0)?;
-        let _semicolon = self.expect_token(TokenType::Semicolon)?;
-        Ok(CmmStatement::Return { expression })
+    /// A `Result` containing the `TopLevelDeclaration` if successful, or a `ParserError`.
+    fn parse_top_level_declaration(&mut self) -> Result<TopLevelDeclaration, ParserError> {
+        let start = self.next_span();
+        let storage_class = self.parse_storage_class();
+        let return_type = if self.next_token_if_eq(&Token::VoidKeyword) {
+            None
+        } else {
+            Some(self.parse_type()?)
+        };
+        let identifier = self.parse_identifier()?;
+        if let Some(variable_type) = &return_type
+            && self.peek_token()? != &Token::OpenParen
+        {
+            let declaration = self.parse_declarator_suffixes(
+                start,
+                storage_class,
+                identifier,
+                variable_type.clone(),
+            )?;
+            self.expect_token(TokenType::Semicolon)?;
+            return Ok(TopLevelDeclaration::Variable(declaration));
+        }
+        self.expect_token(TokenType::OpenParen)?;
+        let (parameters, variadic) = self.parse_parameter_list()?;
+        self.expect_token(TokenType::CloseParen)?;
+        let span = self.span_from(start);
+        let body = if self.next_token_if_eq(&Token::Semicolon) {
+            None
+        } else {
+            self.expect_token(TokenType::OpenBrace)?;
+            Some(self.parse_block_items()?)
+        };
+        Ok(TopLevelDeclaration::Function(CmmFunction::Function {
+            identifier,
+            storage_class,
+            return_type,
+            parameters,
+            variadic,
+            body,
+            span,
+        }))
     }
 
-    /// Parses an identifier string from the token stream.
+    /// Parses an optional `static` or `extern` storage-class specifier from the token stream.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the identifier string if successful, or a `ParserError`.
-    fn parse_identifier(&mut self) -> Result<String, ParserError> {
-        let token = self.consume_token()?;
-        match token {
-            Token::Identifier(identifier) => Ok(identifier.clone()),
-            _ => {
-                return Err(ParserError::UnexpectedToken {
-                    expected: TokenTypeOption::One(TokenType::Identifier),
-                    actual: token.kind(),
-                });
-            }
+    /// The parsed `CmmStorageClass`, or `None` if the next token is not a storage-class specifier.
+    fn parse_storage_class(&mut self) -> Option<CmmStorageClass> {
+        if self.next_token_if_eq(&Token::StaticKeyword) {
+            Some(CmmStorageClass::Static)
+        } else if self.next_token_if_eq(&Token::ExternKeyword) {
+            Some(CmmStorageClass::Extern)
+        } else {
+            None
         }
     }
 
-    /// Parses an expression from the token stream.
+    /// Parses the block items of a block up to and including its closing brace from the token
+    /// stream.
     ///
-    /// Uses the precedence climbing algorithm to parse expressions.
+    /// An item starting with a type specifier is a variable declaration terminated by a
+    /// semicolon, any other item is a statement.
     ///
-    /// Supported expressions:
-    /// - Binary operations on two factors
-    /// - Single factor
+    /// # Returns
     ///
-    /// # Arguments
+    /// A `Result` containing the parsed `CmmBlockItem`s in source order if successful, or a
+    /// `ParserError`.
+    fn parse_block_items(&mut self) -> Result<Vec<CmmBlockItem>, ParserError> {
+        let mut items = Vec::new();
+        while !self.next_token_if_eq(&Token::CloseBrace) {
+            let token = self.peek_token()?;
+            let item = if is_type_specifier(token)
+                || matches!(token, Token::StaticKeyword | Token::ExternKeyword)
+            {
+                let declaration = self.parse_declaration()?;
+                self.expect_token(TokenType::Semicolon)?;
+                CmmBlockItem::Declaration(declaration)
+            } else {
+                CmmBlockItem::Statement(self.parse_statement()?)
+            };
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Parses the parameter list of a function declaration without its parentheses from the token
+    /// stream.
     ///
-    /// * `min_precedence` - The minimum precedence of the binary operator to be parsed.
+    /// The list is either `void`, for a function without parameters, or comma-separated parameters
+    /// that each consist of a type and a name, e.g. `int a, unsigned long *b, char c[4]`. A variadic
+    /// function ends the list with an ellipsis, e.g. `int count, ...`, and like in C23, the ellipsis
+    /// may also be the only entry of the list.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmExpression` if successful, or a `ParserError`.
-    fn parse_expression(&mut self, min_precedence: u32) -> Result<CmmExpression, ParserError> {
-        let mut left = self.parse_factor()?;
-        let mut next_token = self.peek_token()?.clone();
+    /// A `Result` containing the parsed `CmmParameter`s and whether the function is variadic if
+    /// successful, or a `ParserError`.
+    fn parse_parameter_list(&mut self) -> Result<(Vec<CmmParameter>, bool), ParserError> {
+        if self.next_token_if_eq(&Token::VoidKeyword) {
+            return Ok((Vec::new(), false));
+        }
+        let mut parameters = Vec::new();
         loop {
-            if !next_token.is_binary_operator() {
-                break;
+            if self.next_token_if_eq(&Token::Ellipsis) {
+                return Ok((parameters, true));
             }
-
-            // Non-binary operators will get -1 precedence, leading to a break in the next condition check
-            let next_token_precedence = next_token
-                .get_binary_operator_precedence()
-                .map(|x| x as i32)
-                .unwrap_or(-1);
-
-            if next_token_precedence < min_precedence as i32 {
-                break;
+            let parameter_type = self.parse_type()?;
+            let identifier = self.parse_identifier()?;
+            let parameter_type = self.parse_array_suffixes(parameter_type)?;
+            parameters.push(CmmParameter::Parameter {
+                identifier,
+                parameter_type,
+            });
+            if !self.next_token_if_eq(&Token::Comma) {
+                return Ok((parameters, false));
             }
-
-            let operator = self.parse_binary_operator()?;
-            let right = self.parse_expression((next_token_precedence + 1) as u32)?;
-            left = CmmExpression::Binary {
-                operator,
-                left: Box::new(left),
-                right: Box::new(right),
-            };
-            next_token = self.peek_token()?.clone();
         }
-        Ok(left)
     }
 
-    /// Parses a factor from the token stream.
+    /// Parses a single statement from the token stream.
     ///
-    /// Supported factor:
-    /// - Integer constants
-    /// - Unary operations on a factor
-    /// - Parenthesized expressions
+    /// Supported statements:
+    /// - Return statements
+    /// - If statements with an optional else branch
+    /// - While, do-while, and for loops
+    /// - Break and continue statements
+    /// - Null statements and expression statements
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmExpression` if successful, or a `ParserError`.
-    fn parse_factor(&mut self) -> Result<CmmExpression, ParserError> {
-        let token = self.peek_token()?;
-        match token {
-            Token::Constant(_) => self.parse_constant_integer_factor(),
-            Token::Hyphen | Token::Tilde | Token::ExclamationMark => self.parse_unary_factor(),
-            Token::OpenParen => self.parse_parenthesized_expression(),
-            _ => Err(ParserError::UnexpectedToken {
-                expected: TokenTypeOption::Many(vec![
-                    TokenType::Constant,
-                    TokenType::Hyphen,
-                    TokenType::Tilde,
-                    TokenType::OpenParen,
-                ]),
-                actual: token.kind(),
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        match self.peek_token()? {
+            Token::ReturnKeyword => self.parse_return_statement(),
+            Token::IfKeyword => self.parse_if_statement(),
+            Token::WhileKeyword => self.parse_while_statement(),
+            Token::DoKeyword => self.parse_do_while_statement(),
+            Token::ForKeyword => self.parse_for_statement(),
+            Token::BreakKeyword => self.parse_keyword_statement(TokenType::BreakKeyword, |span| {
+                CmmStatement::Break { span }
             }),
+            Token::ContinueKeyword => self
+                .parse_keyword_statement(TokenType::ContinueKeyword, |span| {
+                    CmmStatement::Continue { span }
+                }),
+            Token::Semicolon => {
+                let start = self.next_span();
+                self.expect_token(TokenType::Semicolon)?;
+                Ok(CmmStatement::Null {
+                    span: self.span_from(start),
+                })
+            }
+            _ => self.parse_expression_statement(),
         }
     }
 
-    /// Parses a constant integer expression from the token stream.
+    /// Parses an expression statement, an expression followed by a semicolon, from the token
+    /// stream.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmExpression` if successful, or a `ParserError`.
-    fn parse_constant_integer_factor(&mut self) -> Result<CmmExpression, ParserError> {
-        let token = self.consume_token()?;
-        match token {
-            Token::Constant(value) => Ok(CmmExpression::IntegerConstant { value: *value }),
-            _ => Err(ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(TokenType::Constant),
-                actual: token.kind(),
-            }),
-        }
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_expression_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        let expression = self.parse_expression(0)?;
+        self.expect_token(TokenType::Semicolon)?;
+        Ok(CmmStatement::Expression {
+            expression,
+            span: self.span_from(start),
+        })
     }
 
-    /// Parses a unary expression from the token stream.
+    /// Parses a return statement from the token stream. The returned expression is left out in a
+    /// `void` function, as in `return;`.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed unary `CmmExpression` if successful, or a `ParserError`.
-    fn parse_unary_factor(&mut self) -> Result<CmmExpression, ParserError> {
-        let operator = self.parse_unary_operator()?;
-        let inner_factor = self.parse_factor()?;
-        Ok(CmmExpression::Unary {
-            operator,
-            expression: Box::new(inner_factor),
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_return_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(TokenType::ReturnKeyword)?;
+        let expression = if self.next_token_if_eq(&Token::Semicolon) {
+            None
+        } else {
+            let expression = self.parse_expression(0)?;
+            self.expect_token(TokenType::Semicolon)?;
+            Some(expression)
+        };
+        Ok(CmmStatement::Return {
+            expression,
+            span: self.span_from(start),
         })
     }
 
-    /// Parses a unary operator from the token stream.
+    /// Parses an if statement from the token stream.
+    ///
+    /// An `else` directly following the then branch belongs to this `if`, so in nested if
+    /// statements it is attached to the innermost `if` that can take it.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmUnaryOperator` if successful, or a `ParserError`.
-    fn parse_unary_operator(&mut self) -> Result<CmmUnaryOperator, ParserError> {
-        let token = self.consume_token()?;
-        match token {
-            Token::Hyphen => Ok(CmmUnaryOperator::Negate),
-            Token::Tilde => Ok(CmmUnaryOperator::Complement),
-            Token::ExclamationMark => Ok(CmmUnaryOperator::Not),
-            _ => Err(ParserError::UnexpectedToken {
-                expected: TokenTypeOption::Many(vec![
-                    TokenType::Hyphen,
-                    TokenType::Tilde,
-                    TokenType::ExclamationMark,
-                ]),
-                actual: token.kind(),
-            }),
-        }
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_if_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(TokenType::IfKeyword)?;
+        let condition = self.parse_parenthesized_expression()?;
+        let then_branch = Box::new(self.parse_statement()?);
+        let else_branch = if self.next_token_if_eq(&Token::ElseKeyword) {
+            Some(Box::new(self.parse_statement()?))
+        } else {
+            None
+        };
+        Ok(CmmStatement::If {
+            condition,
+            then_branch,
+            else_branch,
+            span: self.span_from(start),
+        })
     }
 
-    /// Parses a binary operator from the token stream.
+    /// Parses a while loop from the token stream.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmBinaryOperator` if successful, or a `ParserError`.
-    fn parse_binary_operator(&mut self) -> Result<CmmBinaryOperator, ParserError> {
-        let token = self.consume_token()?;
-        match token {
-            Token::Plus => Ok(CmmBinaryOperator::Add),
-            Token::Hyphen => Ok(CmmBinaryOperator::Subtract),
-            Token::Asterisk => Ok(CmmBinaryOperator::Multiply),
-            Token::ForwardSlash => Ok(CmmBinaryOperator::Divide),
-            Token::Percent => Ok(CmmBinaryOperator::Remainder),
-            Token::DoubleAmpersand => Ok(CmmBinaryOperator::And),
-            Token::DoublePipe => Ok(CmmBinaryOperator::Or),
-            Token::DoubleEqual => Ok(CmmBinaryOperator::Equal),
-            Token::ExclamationEqual => Ok(CmmBinaryOperator::NotEqual),
-            Token::LessThan => Ok(CmmBinaryOperator::LessThan),
-            Token::GreaterThan => Ok(CmmBinaryOperator::GreaterThan),
-            Token::LessThanEqual => Ok(CmmBinaryOperator::LessThanEqual),
-            Token::GreaterThanEqual => Ok(CmmBinaryOperator::GreaterThanEqual),
-            _ => Err(ParserError::UnexpectedToken {
-                expected: TokenTypeOption::Many(vec![
-                    TokenType::Plus,
-                    TokenType::Hyphen,
-                    TokenType::Asterisk,
-                    TokenType::ForwardSlash,
-                    TokenType::Percent,
-                    TokenType::DoubleAmpersand,
-                    TokenType::DoublePipe,
-                    TokenType::DoubleEqual,
-                    TokenType::ExclamationEqual,
-                    TokenType::LessThan,
-                    TokenType::GreaterThan,
-                    TokenType::LessThanEqual,
-                    TokenType::GreaterThanEqual,
-                ]),
-                actual: token.kind(),
-            }),
-        }
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_while_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(TokenType::WhileKeyword)?;
+        let condition = self.parse_parenthesized_expression()?;
+        let body = Box::new(self.parse_statement()?);
+        Ok(CmmStatement::While {
+            condition,
+            body,
+            span: self.span_from(start),
+        })
     }
 
-    /// Parses a parenthesized expression from the token stream.
+    /// Parses a do-while loop from the token stream.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the parsed `CmmExpression` if successful, or a `ParserError`.
-    fn parse_parenthesized_expression(&mut self) -> Result<CmmExpression, ParserError> {
-        let _open_paren = self.expect_token(TokenType::OpenParen)?;
-        let expression = self.parse_expression(0)?;
-        let _close_paren = self.expect_token(TokenType::CloseParen)?;
-        Ok(expression)
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_do_while_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(TokenType::DoKeyword)?;
+        let body = Box::new(self.parse_statement()?);
+        self.expect_token(TokenType::WhileKeyword)?;
+        let condition = self.parse_parenthesized_expression()?;
+        self.expect_token(TokenType::Semicolon)?;
+        Ok(CmmStatement::DoWhile {
+            body,
+            condition,
+            span: self.span_from(start),
+        })
     }
 
-    /// Consumes the next token from the stream and checks if it matches the expected token.
+    /// Parses a statement consisting of a single keyword and a semicolon, such as `break;`.
     ///
     /// # Arguments
     ///
-    /// * `expected`: The `Token` that is expected.
+    /// * `keyword`: The `TokenType` of the keyword.
+    /// * `statement`: Creates the `CmmStatement` the keyword stands for from its span.
     ///
     /// # Returns
     ///
-    /// A `Result` containing `()` if the token matches, or a `ParserError` if it does not match or if the end of input is reached unexpectedly.
-    fn expect_token(&mut self, expected_type: TokenType) -> Result<(), ParserError> {
-        let actual = self.consume_token()?;
-        let actual_type = actual.kind();
-        if actual_type != expected_type {
-            return Err(ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(expected_type),
-                actual: actual_type,
-            });
-        }
-        Ok(())
+    /// A `Result` containing the created statement if successful, or a `ParserError`.
+    fn parse_keyword_statement(
+        &mut self,
+        keyword: TokenType,
+        statement: impl FnOnce(Option<Span>) -> CmmStatement,
+    ) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(keyword)?;
+        self.expect_token(TokenType::Semicolon)?;
+        Ok(statement(self.span_from(start)))
     }
 
-    /// Consumes and returns the next token from the stream.
+    /// Parses a for loop from the token stream.
+    ///
+    /// Each of the three clauses may be empty, and the initial clause may declare a variable.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the next `Token` if available, or a `ParserError` if the end of input is reached.
-    fn consume_token(&mut self) -> Result<&Token, ParserError> {
-        if self.position >= self.tokens.len() {
-            return Err(ParserError::UnexpectedEndOfInput);
-        }
-        let token = &self.tokens[self.position];
-        self.position += 1;
-        Ok(token)
+    /// A `Result` containing the parsed `CmmStatement` if successful, or a `ParserError`.
+    fn parse_for_statement(&mut self) -> Result<CmmStatement, ParserError> {
+        let start = self.next_span();
+        self.expect_token(TokenType::ForKeyword)?;
+        self.expect_token(TokenType::OpenParen)?;
+        let init = if is_type_specifier(self.peek_token()?) {
+            CmmForInit::Declaration(self.parse_declaration()?)
+        } else {
+            CmmForInit::Expression(self.parse_optional_expression(&Token::Semicolon)?)
+        };
+        self.expect_token(TokenType::Semicolon)?;
+        let condition = self.parse_optional_expression(&Token::Semicolon)?;
+        self.expect_token(TokenType::Semicolon)?;
+        let post = self.parse_optional_expression(&Token::CloseParen)?;
+        self.expect_token(TokenType::CloseParen)?;
+        let body = Box::new(self.parse_statement()?);
+        Ok(CmmStatement::For {
+            init,
+            condition,
+            post,
+            body,
+            span: self.span_from(start),
+        })
     }
 
-    /// Peeks at the next token from the stream without consuming it.
+    /// Parses a variable declaration without its terminating semicolon from the token stream. The
+    /// declaration may start with a `static` or `extern` storage-class specifier.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the next `Token` if available, or a `ParserError` if the end of input is reached.
-    fn peek_token(&mut self) -> Result<&Token, ParserError> {
-        if self.position >= self.tokens.len() {
-            return Err(ParserError::UnexpectedEndOfInput);
-        }
-        let token = &self.tokens[self.position];
-        Ok(token)
+    /// A `Result` containing the parsed `CmmDeclaration` if successful, or a `ParserError`.
+    fn parse_declaration(&mut self) -> Result<CmmDeclaration, ParserError> {
+        let start = self.next_span();
+        let storage_class = self.parse_storage_class();
+        let variable_type = self.parse_type()?;
+        let identifier = self.parse_identifier()?;
+        self.parse_declarator_suffixes(start, storage_class, identifier, variable_type)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Parses the rest of a variable declaration after its identifier from the token stream, which
+    /// are the array sizes and the initializer, without the terminating semicolon.
+    ///
+    /// # Arguments
+    ///
+    /// * `start`: The location of the first token of the declaration.
+    /// * `storage_class`: The storage-class specifier given before the type, if any.
+    /// * `identifier`: The name of the variable.
+    /// * `variable_type`: The type given before the identifier.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmDeclaration` if successful, or a `ParserError`.
+    fn parse_declarator_suffixes(
+        &mut self,
+        start: Option<Span>,
+        storage_class: Option<CmmStorageClass>,
+        identifier: Symbol,
+        variable_type: CmmType,
+    ) -> Result<CmmDeclaration, ParserError> {
+        let variable_type = self.parse_array_suffixes(variable_type)?;
+        let initializer = if self.next_token_if_eq(&Token::Equal) {
+            Some(self.parse_initializer()?)
+        } else {
+            None
+        };
+        Ok(CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            span: self.span_from(start),
+        })
+    }
 
-    #[test]
-    fn test_consume_single_token_success() {
-        let tokens = vec![Token::IntKeyword];
-        let mut parser = Parser::new(tokens);
-        let token = parser.consume_token().unwrap();
-        assert_eq!(token.clone(), Token::IntKeyword);
+    /// Parses the initializer of a declaration from the token stream, which is either an
+    /// expression or a braced list of initializers such as `{{1, 2}, {3}}`.
+    ///
+    /// The list holds at least one initializer and may end with a comma.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmInitializer` if successful, or a `ParserError`.
+    fn parse_initializer(&mut self) -> Result<CmmInitializer, ParserError> {
+        if !self.next_token_if_eq(&Token::OpenBrace) {
+            return Ok(CmmInitializer::Single(self.parse_expression(0)?));
+        }
+        let mut initializers = vec![self.parse_initializer()?];
+        while self.next_token_if_eq(&Token::Comma) {
+            if self.peek_token()? == &Token::CloseBrace {
+                break;
+            }
+            initializers.push(self.parse_initializer()?);
+        }
+        self.expect_token(TokenType::CloseBrace)?;
+        Ok(CmmInitializer::Compound(initializers))
     }
 
-    #[test]
-    fn test_consume_single_token_failure_no_tokens() {
-        let tokens = vec![];
-        let mut parser = Parser::new(tokens);
-        let result = parser.consume_token();
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), ParserError::UnexpectedEndOfInput);
+    /// Parses a structure declaration after its `struct` keyword from the token stream, e.g.
+    /// `point { int x; int y; };`. A structure has at least one member.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmStructDeclaration` if successful, or a `ParserError`.
+    fn parse_struct_declaration(&mut self) -> Result<CmmStructDeclaration, ParserError> {
+        // The declaration starts with the consumed `struct` keyword.
+        let start = self.last_span;
+        let tag = self.parse_identifier()?;
+        self.expect_token(TokenType::OpenBrace)?;
+        let mut members = Vec::new();
+        loop {
+            let member_type = self.parse_type()?;
+            let identifier = self.parse_identifier()?;
+            let member_type = self.parse_array_suffixes(member_type)?;
+            self.expect_token(TokenType::Semicolon)?;
+            members.push(CmmMember::Member {
+                identifier,
+                member_type,
+            });
+            if self.next_token_if_eq(&Token::CloseBrace) {
+                break;
+            }
+        }
+        self.expect_token(TokenType::Semicolon)?;
+        Ok(CmmStructDeclaration::Struct {
+            tag,
+            members,
+            span: self.span_from(start),
+        })
     }
 
-    #[test]
-    fn test_expect_token_success() {
-        let tokens = vec![Token::IntKeyword];
-        let mut parser = Parser::new(tokens);
-        let result = parser.expect_token(TokenType::IntKeyword);
+    /// Parses an enumeration declaration after its `enum` keyword from the token stream, e.g.
+    /// `color { RED, GREEN = 5, BLUE };`. The tag is optional, and the enumerator list holds at
+    /// least one enumerator and may end with a comma.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmEnumDeclaration` if successful, or a `ParserError`.
+    fn parse_enum_declaration(&mut self) -> Result<CmmEnumDeclaration, ParserError> {
+        // The declaration starts with the consumed `enum` keyword.
+        let start = self.last_span;
+        let tag = match self.peek_token()? {
+            Token::Identifier(_) => Some(self.parse_identifier()?),
+            _ => None,
+        };
+        self.expect_token(TokenType::OpenBrace)?;
+        let mut enumerators = Vec::new();
+        loop {
+            let identifier = self.parse_identifier()?;
+            let value = if self.next_token_if_eq(&Token::Equal) {
+                Some(self.parse_expression(0)?)
+            } else {
+                None
+            };
+            enumerators.push(CmmEnumerator::Enumerator { identifier, value });
+            if !self.next_token_if_eq(&Token::Comma) || self.peek_token()? == &Token::CloseBrace {
+                break;
+            }
+        }
+        self.expect_token(TokenType::CloseBrace)?;
+        self.expect_token(TokenType::Semicolon)?;
+        Ok(CmmEnumDeclaration::Enum {
+            tag,
+            enumerators,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses a type from the token stream, consisting of type specifiers followed by an asterisk
+    /// for every level of pointers, e.g. `unsigned char **`. A `const` after an asterisk
+    /// qualifies the pointer itself, as in `int *const`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmType` if successful, or a `ParserError`.
+    fn parse_type(&mut self) -> Result<CmmType, ParserError> {
+        let mut parsed_type = self.parse_type_specifiers()?;
+        while self.next_token_if_eq(&Token::Asterisk) {
+            parsed_type = CmmType::Pointer(Box::new(parsed_type));
+            while self.next_token_if_eq(&Token::ConstKeyword) {
+                parsed_type = parsed_type.qualified();
+            }
+        }
+        Ok(parsed_type)
+    }
+
+    /// Parses the array sizes following the identifier of a declarator from the token stream, e.g.
+    /// `[2][3]`.
+    ///
+    /// The sizes apply from the right, so `int a[2][3]` declares an array of 2 arrays of 3 `int`s.
+    ///
+    /// # Arguments
+    ///
+    /// * `element_type`: The type given before the identifier, e.g. `int` for `int a[2]`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the declared `CmmType`, which is `element_type` itself without sizes,
+    /// or a `ParserError` if a size is not a positive integer constant.
+    fn parse_array_suffixes(&mut self, element_type: CmmType) -> Result<CmmType, ParserError> {
+        let mut sizes = Vec::new();
+        while self.next_token_if_eq(&Token::OpenBracket) {
+            let size = match self.consume_token()? {
+                Token::Constant(size) | Token::LongConstant(size) if size > 0 => size as usize,
+                Token::UnsignedConstant(size) if size > 0 => size as usize,
+                Token::UnsignedLongConstant(size) if size > 0 => size as usize,
+                found => return Err(ParserError::InvalidArraySize { found }),
+            };
+            self.expect_token(TokenType::CloseBracket)?;
+            sizes.push(size);
+        }
+        Ok(sizes
+            .into_iter()
+            .rev()
+            .fold(element_type, |element_type, size| {
+                CmmType::Array(Box::new(element_type), size)
+            }))
+    }
+
+    /// Parses the type specifiers of a declaration from the token stream.
+    ///
+    /// The specifiers may appear in any order, so `int`, `unsigned`, `unsigned int`, and
+    /// `int unsigned` are all valid, as are `long`, `long int`, and `int long`, `unsigned long`,
+    /// `unsigned long int`, and `long int unsigned`, `short`, `short int`, and `int short`, and
+    /// `char`, `unsigned char`, and `char unsigned`. `_Bool` and `double` are only valid on their
+    /// own, and so are a structure type such as `struct point` and an enumeration type such as
+    /// `enum color`, which is `int`. A `signed` specifier may be added to the signed integer types,
+    /// so `signed` and `signed int` are `int` and `signed char` is `char`. A `const` qualifier may
+    /// appear anywhere among the specifiers and qualifies the type they denote.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `CmmType` the specifiers denote if successful, or a `ParserError`
+    /// if there are no specifiers, a specifier is repeated, or the combination has no type, such as
+    /// `unsigned short`, `signed unsigned`, or `long char`.
+    fn parse_type_specifiers(&mut self) -> Result<CmmType, ParserError> {
+        let mut is_const = false;
+        let mut tagged_type = None;
+        let mut specifiers = Vec::new();
+        while is_type_specifier(self.peek_token()?) {
+            match self.consume_token()? {
+                Token::ConstKeyword => is_const = true,
+                Token::StructKeyword if tagged_type.is_none() && specifiers.is_empty() => {
+                    tagged_type = Some(CmmType::Struct(self.parse_identifier()?));
+                }
+                Token::EnumKeyword if tagged_type.is_none() && specifiers.is_empty() => {
+                    self.parse_identifier()?;
+                    tagged_type = Some(CmmType::Int);
+                }
+                token => specifiers.push(token.kind()),
+            }
+        }
+        let (signed, mut other_specifiers): (Vec<_>, Vec<_>) = specifiers
+            .iter()
+            .cloned()
+            .partition(|specifier| *specifier == TokenType::SignedKeyword);
+        let is_signed = match signed.len() {
+            0 => false,
+            1 => true,
+            _ => return Err(ParserError::InvalidTypeSpecifiers { specifiers }),
+        };
+        // The specifiers are matched in the order of `unsigned long int`, whatever their order.
+        other_specifiers.sort_by_key(|specifier| match specifier {
+            TokenType::UnsignedKeyword => 0,
+            TokenType::IntKeyword => 2,
+            _ => 1,
+        });
+        let specified_type = match (tagged_type, other_specifiers.as_slice()) {
+            (Some(tagged_type), []) if !is_signed => tagged_type,
+            (Some(_), _) => return Err(ParserError::InvalidTypeSpecifiers { specifiers }),
+            (None, []) if is_signed => CmmType::Int,
+            (None, []) => {
+                let actual = self.consume_token()?.kind();
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::Many(vec![
+                        TokenType::IntKeyword,
+                        TokenType::UnsignedKeyword,
+                        TokenType::SignedKeyword,
+                        TokenType::LongKeyword,
+                        TokenType::ShortKeyword,
+                        TokenType::CharKeyword,
+                        TokenType::BoolKeyword,
+                        TokenType::DoubleKeyword,
+                        TokenType::StructKeyword,
+                        TokenType::EnumKeyword,
+                    ]),
+                    actual,
+                });
+            }
+            (None, [TokenType::IntKeyword]) => CmmType::Int,
+            (None, [TokenType::UnsignedKeyword])
+            | (None, [TokenType::UnsignedKeyword, TokenType::IntKeyword]) => CmmType::UnsignedInt,
+            (None, [TokenType::LongKeyword])
+            | (None, [TokenType::LongKeyword, TokenType::IntKeyword]) => CmmType::Long,
+            (None, [TokenType::UnsignedKeyword, TokenType::LongKeyword])
+            | (
+                None,
+                [
+                    TokenType::UnsignedKeyword,
+                    TokenType::LongKeyword,
+                    TokenType::IntKeyword,
+                ],
+            ) => CmmType::UnsignedLong,
+            (None, [TokenType::ShortKeyword])
+            | (None, [TokenType::ShortKeyword, TokenType::IntKeyword]) => CmmType::Short,
+            (None, [TokenType::CharKeyword]) => CmmType::Char,
+            (None, [TokenType::UnsignedKeyword, TokenType::CharKeyword]) => CmmType::UnsignedChar,
+            (None, [TokenType::BoolKeyword]) => CmmType::Bool,
+            (None, [TokenType::DoubleKeyword]) => CmmType::Double,
+            (None, _) => return Err(ParserError::InvalidTypeSpecifiers { specifiers }),
+        };
+        if is_signed
+            && !matches!(
+                specified_type,
+                CmmType::Int | CmmType::Long | CmmType::Short | CmmType::Char
+            )
+        {
+            return Err(ParserError::InvalidTypeSpecifiers { specifiers });
+        }
+        Ok(if is_const {
+            specified_type.qualified()
+        } else {
+            specified_type
+        })
+    }
+
+    /// Parses an expression, unless the next token is the token that ends an empty clause.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminator`: The token following the clause, which is not consumed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed expression, `None` for an empty clause, or
+    /// a `ParserError`.
+    fn parse_optional_expression(
+        &mut self,
+        terminator: &Token,
+    ) -> Result<Option<ExprId>, ParserError> {
+        if self.peek_token()? == terminator {
+            return Ok(None);
+        }
+        self.parse_expression(0).map(Some)
+    }
+
+    /// Parses an identifier string from the token stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the identifier string if successful, or a `ParserError`.
+    fn parse_identifier(&mut self) -> Result<Symbol, ParserError> {
+        let token = self.consume_token()?;
+        match token {
+            Token::Identifier(identifier) => Ok(identifier),
+            _ => Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Identifier),
+                actual: token.kind(),
+            }),
+        }
+    }
+
+    /// Parses an expression from the token stream.
+    ///
+    /// Uses the precedence climbing algorithm to parse expressions.
+    ///
+    /// Supported expressions:
+    /// - Binary operations on two factors
+    /// - Right-associative assignments, binding weaker than every binary operator
+    /// - Single factor
+    ///
+    /// # Arguments
+    ///
+    /// * `min_precedence` - The minimum precedence of the binary operator to be parsed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed expression if successful, or a `ParserError`.
+    fn parse_expression(&mut self, min_precedence: u32) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let mut left = self.parse_factor()?;
+        // The end of input terminates the expression, the caller reports any missing tokens.
+        while let Some(next_token) = self.tokens.peek().map(ParserToken::token) {
+            let compound_operator = compound_assignment_operator(next_token);
+            let next_token_precedence = match next_token {
+                Token::Equal => ASSIGNMENT_PRECEDENCE as i32,
+                _ if compound_operator.is_some() => ASSIGNMENT_PRECEDENCE as i32,
+                // Non-binary operators will get -1 precedence, leading to a break in the next condition check
+                _ if next_token.is_binary_operator() => next_token
+                    .get_binary_operator_precedence()
+                    .map(|x| x as i32)
+                    .unwrap_or(-1),
+                _ => break,
+            };
+
+            if next_token_precedence < min_precedence as i32 {
+                break;
+            }
+
+            if self.next_token_if_eq(&Token::Equal) {
+                // The right operand may be another assignment, which makes assignments right-associative.
+                let value = self.parse_expression(ASSIGNMENT_PRECEDENCE)?;
+                left = self.alloc_expression(
+                    CmmExpression::Assignment {
+                        target: left,
+                        value,
+                    },
+                    start,
+                );
+                continue;
+            }
+            if let Some(operator) = compound_operator {
+                self.consume_token()?;
+                let value = self.parse_expression(ASSIGNMENT_PRECEDENCE)?;
+                left = self.alloc_expression(
+                    CmmExpression::CompoundAssignment {
+                        operator,
+                        target: left,
+                        value,
+                    },
+                    start,
+                );
+                continue;
+            }
+
+            let operator = self.parse_binary_operator()?;
+            let right = self.parse_expression((next_token_precedence + 1) as u32)?;
+            left = self.alloc_expression(
+                CmmExpression::Binary {
+                    operator,
+                    left,
+                    right,
+                },
+                start,
+            );
+        }
+        Ok(left)
+    }
+
+    /// Parses a factor from the token stream.
+    ///
+    /// Supported factor:
+    /// - Integer constants
+    /// - String literals
+    /// - Variables
+    /// - Function calls
+    /// - Prefix unary operations on a factor
+    /// - Address-of and dereference expressions of a factor
+    /// - Casts of a factor
+    /// - Parenthesized expressions
+    /// - Postfix increments, decrements, and subscripts such as `a[i]` of the above, except prefix
+    ///   unary operations, address-of and dereference expressions, and casts
+    ///
+    /// Postfix operators bind tighter than prefix operators and casts, so `-x++` negates `x++`,
+    /// `(long) x++` converts `x++`, and `*a[1]` dereferences `a[1]`. A parenthesis followed by a type specifier starts a cast.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed factor if successful, or a `ParserError`.
+    fn parse_factor(&mut self) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let token = self.peek_token()?;
+        let mut factor = match token {
+            Token::Constant(_)
+            | Token::LongConstant(_)
+            | Token::UnsignedConstant(_)
+            | Token::UnsignedLongConstant(_)
+            | Token::CharConstant(_)
+            | Token::DoubleConstant(_) => self.parse_constant_factor()?,
+            Token::StringLiteral(_) => self.parse_string_literal_factor()?,
+            Token::Identifier(_) => {
+                let identifier = self.parse_identifier()?;
+                let expression = if self.next_token_if_eq(&Token::OpenParen) {
+                    let arguments = self.parse_argument_list()?;
+                    CmmExpression::FunctionCall {
+                        identifier,
+                        arguments,
+                    }
+                } else {
+                    CmmExpression::Variable { identifier }
+                };
+                self.alloc_expression(expression, start)
+            }
+            Token::Plus
+            | Token::Hyphen
+            | Token::Tilde
+            | Token::ExclamationMark
+            | Token::DoublePlus
+            | Token::DoubleHyphen => return self.parse_unary_factor(),
+            Token::Ampersand | Token::Asterisk => return self.parse_pointer_factor(),
+            Token::OpenParen => {
+                self.consume_token()?;
+                if is_type_specifier(self.peek_token()?) {
+                    return self.parse_cast_factor(start);
+                }
+                let expression = self.parse_expression(0)?;
+                self.expect_token(TokenType::CloseParen)?;
+                expression
+            }
+            _ => {
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::Many(vec![
+                        TokenType::Constant,
+                        TokenType::LongConstant,
+                        TokenType::UnsignedConstant,
+                        TokenType::UnsignedLongConstant,
+                        TokenType::CharConstant,
+                        TokenType::DoubleConstant,
+                        TokenType::StringLiteral,
+                        TokenType::Identifier,
+                        TokenType::Plus,
+                        TokenType::Hyphen,
+                        TokenType::Tilde,
+                        TokenType::ExclamationMark,
+                        TokenType::DoublePlus,
+                        TokenType::DoubleHyphen,
+                        TokenType::Ampersand,
+                        TokenType::Asterisk,
+                        TokenType::OpenParen,
+                    ]),
+                    actual: token.kind(),
+                });
+            }
+        };
+        loop {
+            let expression = match self.tokens.peek().map(ParserToken::token) {
+                Some(Token::DoublePlus) => CmmExpression::Unary {
+                    operator: CmmUnaryOperator::PostfixIncrement,
+                    expression: factor,
+                },
+                Some(Token::DoubleHyphen) => CmmExpression::Unary {
+                    operator: CmmUnaryOperator::PostfixDecrement,
+                    expression: factor,
+                },
+                Some(Token::OpenBracket) => {
+                    self.consume_token()?;
+                    let index = self.parse_expression(0)?;
+                    self.expect_token(TokenType::CloseBracket)?;
+                    factor = self.alloc_expression(
+                        CmmExpression::Subscript {
+                            array: factor,
+                            index,
+                        },
+                        start,
+                    );
+                    continue;
+                }
+                Some(Token::Period | Token::Arrow) => {
+                    let operator = self.consume_token()?;
+                    let member = self.parse_identifier()?;
+                    let expression = match operator {
+                        Token::Arrow => CmmExpression::PointerMember {
+                            pointer: factor,
+                            member,
+                        },
+                        _ => CmmExpression::Member {
+                            structure: factor,
+                            member,
+                        },
+                    };
+                    factor = self.alloc_expression(expression, start);
+                    continue;
+                }
+                _ => break,
+            };
+            self.consume_token()?;
+            factor = self.alloc_expression(expression, start);
+        }
+        Ok(factor)
+    }
+
+    /// Parses the arguments of a function call after its opening parenthesis from the token stream,
+    /// including the closing parenthesis.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId`s of the comma-separated arguments if successful, or a
+    /// `ParserError`.
+    fn parse_argument_list(&mut self) -> Result<Vec<ExprId>, ParserError> {
+        let mut arguments = Vec::new();
+        if self.next_token_if_eq(&Token::CloseParen) {
+            return Ok(arguments);
+        }
+        loop {
+            arguments.push(self.parse_expression(0)?);
+            if !self.next_token_if_eq(&Token::Comma) {
+                self.expect_token(TokenType::CloseParen)?;
+                return Ok(arguments);
+            }
+        }
+    }
+
+    /// Parses a constant expression from the token stream.
+    ///
+    /// Like in C, an integer constant without a suffix is an `int` if it fits into one and a `long`
+    /// otherwise, unless the lexer already made it an `unsigned int`, and a character constant is an `int` holding the code of its character.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed constant if successful, or a `ParserError`.
+    fn parse_constant_factor(&mut self) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let token = self.consume_token()?;
+        let expression = match token {
+            Token::Constant(value) => match i32::try_from(value) {
+                Ok(value) => CmmExpression::IntegerConstant { value },
+                Err(_) => CmmExpression::LongConstant { value },
+            },
+            Token::LongConstant(value) => CmmExpression::LongConstant { value },
+            Token::UnsignedConstant(value) => CmmExpression::UnsignedIntegerConstant { value },
+            Token::UnsignedLongConstant(value) => CmmExpression::UnsignedLongConstant { value },
+            Token::CharConstant(value) => CmmExpression::IntegerConstant {
+                value: i32::from(value),
+            },
+            Token::DoubleConstant(value) => CmmExpression::DoubleConstant { value },
+            _ => {
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::Many(vec![
+                        TokenType::Constant,
+                        TokenType::LongConstant,
+                        TokenType::UnsignedConstant,
+                        TokenType::UnsignedLongConstant,
+                        TokenType::CharConstant,
+                        TokenType::DoubleConstant,
+                    ]),
+                    actual: token.kind(),
+                });
+            }
+        };
+        Ok(self.alloc_expression(expression, start))
+    }
+
+    /// Parses a string literal expression from the token stream.
+    ///
+    /// Like in C, adjacent string literals are joined into a single string, so `"a" "b"` is parsed
+    /// like `"ab"`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed string literal if successful, or a `ParserError`.
+    fn parse_string_literal_factor(&mut self) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let mut value = match self.consume_token()? {
+            Token::StringLiteral(value) => value,
+            token => {
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::One(TokenType::StringLiteral),
+                    actual: token.kind(),
+                });
+            }
+        };
+        while let Some(Token::StringLiteral(next_value)) =
+            self.tokens.peek().map(ParserToken::token)
+        {
+            value.extend_from_slice(next_value);
+            self.consume_token()?;
+        }
+        Ok(self.alloc_expression(CmmExpression::StringLiteral { value }, start))
+    }
+
+    /// Parses a unary expression from the token stream.
+    ///
+    /// Constants are lexed without their sign, so the negation of 2147483648 is parsed into a
+    /// single constant holding the smallest `int`, as 2147483648 itself would be a `long`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed unary expression if successful, or a `ParserError`.
+    fn parse_unary_factor(&mut self) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let operator = self.parse_unary_operator()?;
+        if operator == CmmUnaryOperator::Negate
+            && self.tokens.peek().map(ParserToken::token)
+                == Some(&Token::Constant(-i64::from(i32::MIN)))
+        {
+            self.consume_token()?;
+            return Ok(
+                self.alloc_expression(CmmExpression::IntegerConstant { value: i32::MIN }, start)
+            );
+        }
+        let inner_factor = self.parse_factor()?;
+        Ok(self.alloc_expression(
+            CmmExpression::Unary {
+                operator,
+                expression: inner_factor,
+            },
+            start,
+        ))
+    }
+
+    /// Parses an address-of or a dereference expression from the token stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed expression if successful, or a `ParserError`.
+    fn parse_pointer_factor(&mut self) -> Result<ExprId, ParserError> {
+        let start = self.next_span();
+        let token = self.consume_token()?;
+        let expression = self.parse_factor()?;
+        let expression = match token {
+            Token::Ampersand => CmmExpression::AddressOf { expression },
+            Token::Asterisk => CmmExpression::Dereference { expression },
+            _ => {
+                return Err(ParserError::UnexpectedToken {
+                    expected: TokenTypeOption::Many(vec![
+                        TokenType::Ampersand,
+                        TokenType::Asterisk,
+                    ]),
+                    actual: token.kind(),
+                });
+            }
+        };
+        Ok(self.alloc_expression(expression, start))
+    }
+
+    /// Parses the rest of a cast expression after its opening parenthesis from the token stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `start`: The location of the opening parenthesis.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the parsed cast if successful, or a `ParserError`.
+    fn parse_cast_factor(&mut self, start: Option<Span>) -> Result<ExprId, ParserError> {
+        let target_type = self.parse_type()?;
+        self.expect_token(TokenType::CloseParen)?;
+        let expression = self.parse_factor()?;
+        Ok(self.alloc_expression(
+            CmmExpression::Cast {
+                target_type,
+                expression,
+            },
+            start,
+        ))
+    }
+
+    /// Parses a unary operator from the token stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmUnaryOperator` if successful, or a `ParserError`.
+    fn parse_unary_operator(&mut self) -> Result<CmmUnaryOperator, ParserError> {
+        let token = self.consume_token()?;
+        match token {
+            Token::Plus => Ok(CmmUnaryOperator::Plus),
+            Token::Hyphen => Ok(CmmUnaryOperator::Negate),
+            Token::Tilde => Ok(CmmUnaryOperator::Complement),
+            Token::ExclamationMark => Ok(CmmUnaryOperator::Not),
+            Token::DoublePlus => Ok(CmmUnaryOperator::PrefixIncrement),
+            Token::DoubleHyphen => Ok(CmmUnaryOperator::PrefixDecrement),
+            _ => Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::Many(vec![
+                    TokenType::Plus,
+                    TokenType::Hyphen,
+                    TokenType::Tilde,
+                    TokenType::ExclamationMark,
+                    TokenType::DoublePlus,
+                    TokenType::DoubleHyphen,
+                ]),
+                actual: token.kind(),
+            }),
+        }
+    }
+
+    /// Parses a binary operator from the token stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `CmmBinaryOperator` if successful, or a `ParserError`.
+    fn parse_binary_operator(&mut self) -> Result<CmmBinaryOperator, ParserError> {
+        let token = self.consume_token()?;
+        match token {
+            Token::Plus => Ok(CmmBinaryOperator::Add),
+            Token::Hyphen => Ok(CmmBinaryOperator::Subtract),
+            Token::Asterisk => Ok(CmmBinaryOperator::Multiply),
+            Token::ForwardSlash => Ok(CmmBinaryOperator::Divide),
+            Token::Percent => Ok(CmmBinaryOperator::Remainder),
+            Token::Ampersand => Ok(CmmBinaryOperator::BitwiseAnd),
+            Token::Pipe => Ok(CmmBinaryOperator::BitwiseOr),
+            Token::Caret => Ok(CmmBinaryOperator::BitwiseXor),
+            Token::DoubleLessThan => Ok(CmmBinaryOperator::LeftShift),
+            Token::DoubleGreaterThan => Ok(CmmBinaryOperator::RightShift),
+            Token::DoubleAmpersand => Ok(CmmBinaryOperator::And),
+            Token::DoublePipe => Ok(CmmBinaryOperator::Or),
+            Token::DoubleEqual => Ok(CmmBinaryOperator::Equal),
+            Token::ExclamationEqual => Ok(CmmBinaryOperator::NotEqual),
+            Token::LessThan => Ok(CmmBinaryOperator::LessThan),
+            Token::GreaterThan => Ok(CmmBinaryOperator::GreaterThan),
+            Token::LessThanEqual => Ok(CmmBinaryOperator::LessThanEqual),
+            Token::GreaterThanEqual => Ok(CmmBinaryOperator::GreaterThanEqual),
+            _ => Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::Many(vec![
+                    TokenType::Plus,
+                    TokenType::Hyphen,
+                    TokenType::Asterisk,
+                    TokenType::ForwardSlash,
+                    TokenType::Percent,
+                    TokenType::Ampersand,
+                    TokenType::Pipe,
+                    TokenType::Caret,
+                    TokenType::DoubleLessThan,
+                    TokenType::DoubleGreaterThan,
+                    TokenType::DoubleAmpersand,
+                    TokenType::DoublePipe,
+                    TokenType::DoubleEqual,
+                    TokenType::ExclamationEqual,
+                    TokenType::LessThan,
+                    TokenType::GreaterThan,
+                    TokenType::LessThanEqual,
+                    TokenType::GreaterThanEqual,
+                ]),
+                actual: token.kind(),
+            }),
+        }
+    }
+
+    /// Parses a parenthesized expression from the token stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ExprId` of the inner expression if successful, or a `ParserError`.
+    fn parse_parenthesized_expression(&mut self) -> Result<ExprId, ParserError> {
+        self.expect_token(TokenType::OpenParen)?;
+        let expression = self.parse_expression(0)?;
+        self.expect_token(TokenType::CloseParen)?;
+        Ok(expression)
+    }
+
+    /// Consumes the next token from the stream and checks if it matches the expected token.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected`: The `Token` that is expected.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `()` if the token matches, or a `ParserError` if it does not match or if the end of input is reached unexpectedly.
+    fn expect_token(&mut self, expected_type: TokenType) -> Result<(), ParserError> {
+        let actual = self.consume_token()?;
+        let actual_type = actual.kind();
+        if actual_type != expected_type {
+            return Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(expected_type),
+                actual: actual_type,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that every token of the stream has been consumed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `()` at the end of input, or a `ParserError` with the first remaining
+    /// token, which is consumed so that the error is located at it.
+    fn expect_end_of_input(&mut self) -> Result<(), ParserError> {
+        if self.tokens.peek().is_some() {
+            let found = self.consume_token()?;
+            return Err(ParserError::UnexpectedTrailingToken { found });
+        }
+        Ok(())
+    }
+
+    /// Consumes and returns the next token from the stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the next `Token` if available, or a `ParserError` if the end of input is reached.
+    fn consume_token(&mut self) -> Result<Token, ParserError> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or(ParserError::UnexpectedEndOfInput)?;
+        self.last_span = token.span();
+        Ok(token.into_token())
+    }
+
+    /// Consumes the next token if it equals the given token.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected`: The `Token` to consume.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the token was consumed.
+    fn next_token_if_eq(&mut self, expected: &Token) -> bool {
+        match self.tokens.next_if(|token| token.token() == expected) {
+            Some(token) => {
+                self.last_span = token.span();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stores an expression parsed from the tokens consumed since the given location.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The parsed expression.
+    /// * `start`: The location of the first token of the expression.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId` of the stored expression.
+    fn alloc_expression(&mut self, expression: CmmExpression, start: Option<Span>) -> ExprId {
+        let span = self.span_from(start);
+        self.expressions.alloc_at(expression, span)
+    }
+
+    /// Returns the location of the next token, which starts the node parsed next, or `None` at
+    /// the end of input or without locations.
+    fn next_span(&mut self) -> Option<Span> {
+        self.tokens.peek().and_then(ParserToken::span)
+    }
+
+    /// Returns the location of a node from its first token to the last consumed token.
+    ///
+    /// # Arguments
+    ///
+    /// * `start`: The location of the first token of the node, see `next_span`.
+    fn span_from(&self, start: Option<Span>) -> Option<Span> {
+        start.zip(self.last_span).map(|(start, end)| start.to(&end))
+    }
+
+    /// Peeks at the next token from the stream without consuming it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the next `Token` if available, or a `ParserError` if the end of input is reached.
+    fn peek_token(&mut self) -> Result<&Token, ParserError> {
+        self.tokens
+            .peek()
+            .map(ParserToken::token)
+            .ok_or(ParserError::UnexpectedEndOfInput)
+    }
+}
+
+/// Returns the binary operator a compound assignment token applies, e.g. `Add` for `+=`.
+///
+/// # Arguments
+///
+/// * `token`: The token to inspect.
+///
+/// # Returns
+///
+/// The `CmmBinaryOperator` of the compound assignment, or `None` if the token is not one.
+fn compound_assignment_operator(token: &Token) -> Option<CmmBinaryOperator> {
+    match token {
+        Token::PlusEqual => Some(CmmBinaryOperator::Add),
+        Token::HyphenEqual => Some(CmmBinaryOperator::Subtract),
+        Token::AsteriskEqual => Some(CmmBinaryOperator::Multiply),
+        Token::ForwardSlashEqual => Some(CmmBinaryOperator::Divide),
+        Token::PercentEqual => Some(CmmBinaryOperator::Remainder),
+        Token::AmpersandEqual => Some(CmmBinaryOperator::BitwiseAnd),
+        Token::PipeEqual => Some(CmmBinaryOperator::BitwiseOr),
+        Token::CaretEqual => Some(CmmBinaryOperator::BitwiseXor),
+        Token::DoubleLessThanEqual => Some(CmmBinaryOperator::LeftShift),
+        Token::DoubleGreaterThanEqual => Some(CmmBinaryOperator::RightShift),
+        _ => None,
+    }
+}
+
+/// Returns whether a token starts a function, variable, structure, or enumeration declaration at
+/// the top level of a program.
+///
+/// # Arguments
+///
+/// * `token`: The token to inspect.
+///
+/// # Returns
+///
+/// `true` if the token is a type specifier, `const`, `void`, or a storage-class specifier.
+fn starts_declaration(token: &Token) -> bool {
+    is_type_specifier(token)
+        || matches!(
+            token,
+            Token::VoidKeyword | Token::StaticKeyword | Token::ExternKeyword
+        )
+}
+
+/// Returns whether a token is a type specifier or the `const` qualifier, which start a declaration
+/// or a cast.
+///
+/// # Arguments
+///
+/// * `token`: The token to inspect.
+///
+/// # Returns
+///
+/// `true` if the token is `int`, `unsigned`, `long`, `short`, `char`, `_Bool`, `double`,
+/// `struct`, `enum`, or `const`.
+fn is_type_specifier(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::IntKeyword
+            | Token::UnsignedKeyword
+            | Token::SignedKeyword
+            | Token::LongKeyword
+            | Token::ShortKeyword
+            | Token::CharKeyword
+            | Token::BoolKeyword
+            | Token::DoubleKeyword
+            | Token::StructKeyword
+            | Token::EnumKeyword
+            | Token::ConstKeyword
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::error_codes;
+    use crate::compiler::lexer::{tokenize, tokenize_with_spans};
+
+    impl<I: Iterator<Item: ParserToken>> Parser<I> {
+        /// Parses a top-level declaration that must declare a function.
+        fn parse_function(&mut self) -> Result<CmmFunction, ParserError> {
+            match self.parse_top_level_declaration()? {
+                TopLevelDeclaration::Function(function) => Ok(function),
+                TopLevelDeclaration::Variable(_) => panic!("Expected a function declaration"),
+            }
+        }
+    }
+
+    /// Returns the text of a single line of source code that a span covers.
+    fn span_text(source_code: &str, span: Option<Span>) -> &str {
+        let span = span.expect("The node was parsed with a span");
+        &source_code[span.start_column - 1..span.end_column - 1]
+    }
+
+    #[test]
+    fn test_binary_operator_precedence_matches_tokens() {
+        let tokens = [
+            Token::Plus,
+            Token::Hyphen,
+            Token::Asterisk,
+            Token::ForwardSlash,
+            Token::Percent,
+            Token::Ampersand,
+            Token::Pipe,
+            Token::Caret,
+            Token::DoubleLessThan,
+            Token::DoubleGreaterThan,
+            Token::DoubleAmpersand,
+            Token::DoublePipe,
+            Token::DoubleEqual,
+            Token::ExclamationEqual,
+            Token::LessThan,
+            Token::GreaterThan,
+            Token::LessThanEqual,
+            Token::GreaterThanEqual,
+        ];
+        for token in tokens {
+            let token_precedence = token.get_binary_operator_precedence().unwrap();
+            let operator = Parser::new(vec![token]).parse_binary_operator().unwrap();
+            assert_eq!(operator.precedence(), token_precedence, "{:?}", operator);
+        }
+    }
+
+    #[test]
+    fn test_consume_single_token_success() {
+        let tokens = vec![Token::IntKeyword];
+        let mut parser = Parser::new(tokens);
+        let token = parser.consume_token().unwrap();
+        assert_eq!(token.clone(), Token::IntKeyword);
+    }
+
+    #[test]
+    fn test_consume_single_token_failure_no_tokens() {
+        let tokens: Vec<Token> = vec![];
+        let mut parser = Parser::new(tokens);
+        let result = parser.consume_token();
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), ParserError::UnexpectedEndOfInput);
+    }
+
+    #[test]
+    fn test_expect_token_success() {
+        let tokens = vec![Token::IntKeyword];
+        let mut parser = Parser::new(tokens);
+        let result = parser.expect_token(TokenType::IntKeyword);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), ());
     }
 
     #[test]
-    fn test_expect_token_failure_no_tokens() {
-        let tokens = vec![];
+    fn test_expect_token_failure_no_tokens() {
+        let tokens: Vec<Token> = vec![];
+        let mut parser = Parser::new(tokens);
+        let result = parser.expect_token(TokenType::IntKeyword);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), ParserError::UnexpectedEndOfInput);
+    }
+
+    #[test]
+    fn test_expect_token_failure_unexpected_sequence() {
+        let tokens = vec![Token::IntKeyword];
+        let mut parser = Parser::new(tokens);
+        let result = parser.expect_token(TokenType::ReturnKeyword);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::ReturnKeyword),
+                actual: TokenType::IntKeyword
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_valid_constant_integer_expression() {
+        let tokens = vec![Token::Constant(1), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_expression(0);
+        assert!(
+            result.is_ok(),
+            "Should parse valid constant integer expression, got {:?}",
+            result
+        );
+        assert_eq!(
+            parser.expressions[result.unwrap()],
+            CmmExpression::IntegerConstant { value: 1 }
+        );
+    }
+
+    #[test]
+    fn test_parse_double_constant() {
+        let tokens = tokenize("1.5 * 2e1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary { left, right, .. } = parser.expressions[expression] else {
+            panic!("Expected a multiplication");
+        };
+        assert_eq!(
+            parser.expressions[left],
+            CmmExpression::DoubleConstant { value: 1.5 }
+        );
+        assert_eq!(
+            parser.expressions[right],
+            CmmExpression::DoubleConstant { value: 20.0 }
+        );
+    }
+
+    #[test]
+    fn test_parse_char_constant_is_int() {
+        let tokens = tokenize("'a' + '\\n';").unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary { left, right, .. } = parser.expressions[expression] else {
+            panic!("Expected an addition");
+        };
+        assert_eq!(
+            parser.expressions[left],
+            CmmExpression::IntegerConstant { value: 97 }
+        );
+        assert_eq!(
+            parser.expressions[right],
+            CmmExpression::IntegerConstant { value: 10 }
+        );
+    }
+
+    #[test]
+    fn test_parse_adjacent_string_literals() {
+        let tokens = tokenize("\"con\" \"cat\\n\" \"\";").unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::StringLiteral {
+                value: b"concat\n".to_vec()
+            }
+        );
+        assert!(matches!(parser.tokens.peek(), Some(Token::Semicolon)));
+    }
+
+    #[test]
+    fn test_parse_valid_unary_expression_negate() {
+        let tokens = vec![Token::Hyphen, Token::Constant(1), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_expression(0);
+        assert!(
+            result.is_ok(),
+            "Should parse valid unary expression negate, got {:?}",
+            result
+        );
+        let mut expected = ExpressionArena::new();
+        let constant = expected.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let unary = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: constant,
+        });
+        assert_eq!(result.unwrap(), unary);
+        assert_eq!(parser.expressions, expected);
+    }
+
+    #[test]
+    fn test_parse_negated_smallest_int_constant() {
+        let tokens = vec![Token::Hyphen, Token::Constant(2147483648), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::IntegerConstant { value: i32::MIN }
+        );
+    }
+
+    #[test]
+    fn test_parse_unsigned_constants() {
+        let tokens = vec![Token::UnsignedConstant(4294967295), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::UnsignedIntegerConstant { value: 4294967295 }
+        );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, expression),
+            "4294967295U"
+        );
+
+        let tokens = vec![Token::UnsignedLongConstant(u64::MAX), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::UnsignedLongConstant { value: u64::MAX }
+        );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, expression),
+            "18446744073709551615UL"
+        );
+    }
+
+    #[test]
+    fn test_parse_long_constants() {
+        let tokens = vec![Token::Constant(2147483648), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::LongConstant { value: 2147483648 }
+        );
+
+        let tokens = vec![Token::LongConstant(1), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::LongConstant { value: 1 }
+        );
+
+        // Only a direct negation makes the constant fit into an int.
+        let tokens = vec![
+            Token::Hyphen,
+            Token::OpenParen,
+            Token::Constant(2147483648),
+            Token::CloseParen,
+            Token::Semicolon,
+        ];
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Unary {
+            expression: operand,
+            ..
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a negation");
+        };
+        assert_eq!(
+            parser.expressions[operand],
+            CmmExpression::LongConstant { value: 2147483648 }
+        );
+    }
+
+    #[test]
+    fn test_parse_valid_unary_expression_complement() {
+        let tokens = vec![Token::Tilde, Token::Constant(1), Token::Semicolon];
         let mut parser = Parser::new(tokens);
-        let result = parser.expect_token(TokenType::IntKeyword);
-        assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), ParserError::UnexpectedEndOfInput);
+        let result = parser.parse_expression(0);
+        assert!(
+            result.is_ok(),
+            "Should parse valid unary expression complement, got {:?}",
+            result
+        );
+        let mut expected = ExpressionArena::new();
+        let constant = expected.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let unary = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Complement,
+            expression: constant,
+        });
+        assert_eq!(result.unwrap(), unary);
+        assert_eq!(parser.expressions, expected);
     }
 
     #[test]
-    fn test_expect_token_failure_unexpected_sequence() {
-        let tokens = vec![Token::IntKeyword];
+    fn test_parse_valid_unary_expression_not() {
+        let tokens = vec![
+            Token::ExclamationMark,
+            Token::Constant(0),
+            Token::Plus,
+            Token::Constant(1),
+            Token::Semicolon,
+        ];
         let mut parser = Parser::new(tokens);
-        let result = parser.expect_token(TokenType::ReturnKeyword);
-        assert!(result.is_err());
+        let result = parser.parse_expression(0).unwrap();
         assert_eq!(
-            result.unwrap_err(),
-            ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(TokenType::ReturnKeyword),
-                actual: TokenType::IntKeyword
-            }
+            printer::print_expression(&parser.expressions, result),
+            "!0 + 1"
         );
+
+        let tokens = vec![
+            Token::ExclamationMark,
+            Token::ExclamationMark,
+            Token::Identifier(Symbol::from("x")),
+            Token::Semicolon,
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_expression(0).unwrap();
+        let mut expected = ExpressionArena::new();
+        let variable = expected.alloc(CmmExpression::Variable {
+            identifier: Symbol::from("x"),
+        });
+        let inner = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Not,
+            expression: variable,
+        });
+        let outer = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Not,
+            expression: inner,
+        });
+        assert_eq!(result, outer);
+        assert_eq!(parser.expressions, expected);
     }
 
     #[test]
-    fn test_parse_valid_constant_integer_expression() {
-        let tokens = vec![Token::Constant(1), Token::Semicolon];
+    fn test_parse_valid_unary_expression_plus() {
+        let tokens = vec![
+            Token::Constant(1),
+            Token::Plus,
+            Token::Plus,
+            Token::Hyphen,
+            Token::Constant(2),
+            Token::Semicolon,
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_expression(0).unwrap();
+        let mut expected = ExpressionArena::new();
+        let left = expected.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let constant = expected.alloc(CmmExpression::IntegerConstant { value: 2 });
+        let negation = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: constant,
+        });
+        let plus = expected.alloc(CmmExpression::Unary {
+            operator: CmmUnaryOperator::Plus,
+            expression: negation,
+        });
+        let sum = expected.alloc(CmmExpression::Binary {
+            operator: CmmBinaryOperator::Add,
+            left,
+            right: plus,
+        });
+        assert_eq!(result, sum);
+        assert_eq!(parser.expressions, expected);
+        assert_eq!(
+            printer::print_expression(&parser.expressions, result),
+            "1 + +-2"
+        );
+    }
+
+    #[test]
+    fn test_parse_ampersand_precedence() {
+        let tokens = vec![
+            Token::OpenParen,
+            Token::Constant(10),
+            Token::DoubleAmpersand,
+            Token::Constant(0),
+            Token::CloseParen,
+            Token::Plus,
+            Token::OpenParen,
+            Token::Constant(0),
+            Token::DoubleAmpersand,
+            Token::Constant(4),
+            Token::CloseParen,
+            Token::Plus,
+            Token::OpenParen,
+            Token::Constant(0),
+            Token::DoubleAmpersand,
+            Token::Constant(0),
+            Token::CloseParen,
+            Token::Semicolon,
+        ];
         let mut parser = Parser::new(tokens);
         let result = parser.parse_expression(0);
         assert!(
             result.is_ok(),
-            "Should parse valid constant integer expression, got {:?}",
+            "Should be able to parse expression, got error: {:?}",
             result
         );
-        assert_eq!(result.unwrap(), CmmExpression::IntegerConstant { value: 1 });
+        let mut expected = ExpressionArena::new();
+        let binary = |expected: &mut ExpressionArena, operator, left, right| {
+            expected.alloc(CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            })
+        };
+        let ten = expected.alloc(CmmExpression::IntegerConstant { value: 10 });
+        let zero = expected.alloc(CmmExpression::IntegerConstant { value: 0 });
+        let first_and = binary(&mut expected, CmmBinaryOperator::And, ten, zero);
+        let zero = expected.alloc(CmmExpression::IntegerConstant { value: 0 });
+        let four = expected.alloc(CmmExpression::IntegerConstant { value: 4 });
+        let second_and = binary(&mut expected, CmmBinaryOperator::And, zero, four);
+        let first_sum = binary(&mut expected, CmmBinaryOperator::Add, first_and, second_and);
+        let zero = expected.alloc(CmmExpression::IntegerConstant { value: 0 });
+        let other_zero = expected.alloc(CmmExpression::IntegerConstant { value: 0 });
+        let third_and = binary(&mut expected, CmmBinaryOperator::And, zero, other_zero);
+        let sum = binary(&mut expected, CmmBinaryOperator::Add, first_sum, third_and);
+        assert_eq!(result.unwrap(), sum);
+        assert_eq!(parser.expressions, expected);
     }
 
     #[test]
-    fn test_parse_valid_unary_expression_negate() {
-        let tokens = vec![Token::Hyphen, Token::Constant(1), Token::Semicolon];
+    fn test_parse_valid_parenthesized_expression() {
+        let tokens = vec![
+            Token::OpenParen,
+            Token::Constant(1),
+            Token::CloseParen,
+            Token::Semicolon,
+        ];
         let mut parser = Parser::new(tokens);
         let result = parser.parse_expression(0);
         assert!(
             result.is_ok(),
-            "Should parse valid unary expression negate, got {:?}",
+            "Should parse valid parenthesized expression, got {:?}",
+            result
+        );
+        assert_eq!(
+            parser.expressions[result.unwrap()],
+            CmmExpression::IntegerConstant { value: 1 }
+        );
+    }
+
+    #[test]
+    fn test_parse_valid_operator_precedence() {
+        let tokens = vec![
+            Token::Constant(1),
+            Token::Asterisk,
+            Token::Constant(2),
+            Token::Hyphen,
+            Token::Constant(3),
+            Token::Asterisk,
+            Token::OpenParen,
+            Token::Constant(4),
+            Token::Plus,
+            Token::Constant(5),
+            Token::CloseParen,
+            Token::Semicolon,
+        ];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_expression(0);
+        assert!(
+            result.is_ok(),
+            "Should parse expression with correct operator precedence, got {:?}",
             result
         );
+        let mut expected = ExpressionArena::new();
+        let binary = |expected: &mut ExpressionArena, operator, left, right| {
+            expected.alloc(CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            })
+        };
+        let one = expected.alloc(CmmExpression::IntegerConstant { value: 1 });
+        let two = expected.alloc(CmmExpression::IntegerConstant { value: 2 });
+        let product = binary(&mut expected, CmmBinaryOperator::Multiply, one, two);
+        let three = expected.alloc(CmmExpression::IntegerConstant { value: 3 });
+        let four = expected.alloc(CmmExpression::IntegerConstant { value: 4 });
+        let five = expected.alloc(CmmExpression::IntegerConstant { value: 5 });
+        let sum = binary(&mut expected, CmmBinaryOperator::Add, four, five);
+        let other_product = binary(&mut expected, CmmBinaryOperator::Multiply, three, sum);
+        let difference = binary(
+            &mut expected,
+            CmmBinaryOperator::Subtract,
+            product,
+            other_product,
+        );
+        assert_eq!(result.unwrap(), difference);
+        assert_eq!(parser.expressions, expected);
+    }
+
+    #[test]
+    fn test_parse_identifier_success() {
+        let identifier = Symbol::from("main");
+        let tokens = vec![Token::Identifier(identifier.clone())];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_identifier();
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), identifier);
+    }
+
+    #[test]
+    fn test_parse_identifier_failure_unexpected_sequence() {
+        let tokens = vec![Token::IntKeyword];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_identifier();
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Identifier),
+                actual: TokenType::IntKeyword
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_statement_success() {
+        let tokens = vec![Token::ReturnKeyword, Token::Constant(1), Token::Semicolon];
+        let mut parser = Parser::new(tokens);
+        let result = parser.parse_statement();
+        assert!(result.is_ok());
+        let Ok(CmmStatement::Return {
+            expression: Some(expression),
+            ..
+        }) = result
+        else {
+            panic!("Expected a return statement");
+        };
+        assert_eq!(
+            parser.expressions[expression],
+            CmmExpression::IntegerConstant { value: 1 }
+        );
+    }
+
+    #[test]
+    fn test_parse_if_statement_attaches_else_to_innermost_if() {
+        let tokens = tokenize("if (1) if (2) return 3; else return 4;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse_statement().unwrap();
+        // The constants are the only expressions, so they are stored in source order.
+        let constant = |index: usize| parser.expressions.iter().nth(index).unwrap().0;
+        let return_statement = |index: usize| {
+            Box::new(CmmStatement::Return {
+                expression: Some(constant(index)),
+                span: None,
+            })
+        };
+        assert_eq!(
+            statement,
+            CmmStatement::If {
+                condition: constant(0),
+                then_branch: Box::new(CmmStatement::If {
+                    condition: constant(1),
+                    then_branch: return_statement(2),
+                    else_branch: Some(return_statement(3)),
+                    span: None,
+                }),
+                else_branch: None,
+                span: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_while_statement() {
+        let tokens = tokenize("while (1) if (0) return 2;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse_statement().unwrap();
+        let constant = |index: usize| parser.expressions.iter().nth(index).unwrap().0;
+        assert_eq!(
+            statement,
+            CmmStatement::While {
+                condition: constant(0),
+                body: Box::new(CmmStatement::If {
+                    condition: constant(1),
+                    then_branch: Box::new(CmmStatement::Return {
+                        expression: Some(constant(2)),
+                        span: None,
+                    }),
+                    else_branch: None,
+                    span: None,
+                }),
+                span: None,
+            }
+        );
+
+        let tokens = tokenize("while (1);").unwrap();
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse_statement().unwrap();
+        let condition = parser.expressions.iter().next().unwrap().0;
+        assert_eq!(
+            statement,
+            CmmStatement::While {
+                condition,
+                body: Box::new(CmmStatement::Null { span: None }),
+                span: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_do_while_statement() {
+        let tokens = tokenize("do return 1; while (0);").unwrap();
+        let mut parser = Parser::new(tokens);
+        let statement = parser.parse_statement().unwrap();
+        let constant = |index: usize| parser.expressions.iter().nth(index).unwrap().0;
+        assert_eq!(
+            statement,
+            CmmStatement::DoWhile {
+                body: Box::new(CmmStatement::Return {
+                    expression: Some(constant(0)),
+                    span: None,
+                }),
+                condition: constant(1),
+                span: None,
+            }
+        );
+
+        let tokens = tokenize("do return 1; while (0)").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedEndOfInput)
+        );
+    }
+
+    #[test]
+    fn test_parse_for_statement() {
+        let tokens = tokenize("for (int i = 0; i < 2; i = i + 1) return i;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let CmmStatement::For {
+            init:
+                CmmForInit::Declaration(CmmDeclaration::Declaration {
+                    identifier,
+                    variable_type: CmmType::Int,
+                    initializer: Some(CmmInitializer::Single(initializer)),
+                    ..
+                }),
+            condition: Some(condition),
+            post: Some(post),
+            body,
+            ..
+        } = parser.parse_statement().unwrap()
+        else {
+            panic!("Expected a for loop with all clauses");
+        };
+        assert_eq!(identifier, "i");
+        let print = |expression| printer::print_expression(&parser.expressions, expression);
+        assert_eq!(print(initializer), "0");
+        assert_eq!(print(condition), "i < 2");
+        assert_eq!(print(post), "i = i + 1");
+        assert!(matches!(*body, CmmStatement::Return { .. }));
+
+        let tokens = tokenize("for (;;) return 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert!(matches!(
+            parser.parse_statement(),
+            Ok(CmmStatement::For {
+                init: CmmForInit::Expression(None),
+                condition: None,
+                post: None,
+                ..
+            })
+        ));
+
+        let tokens = tokenize("for (int i; i) return 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Semicolon),
+                actual: TokenType::CloseParen
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_const_types() {
+        let constant = |cmm_type| CmmType::Const(Box::new(cmm_type));
+        let pointer = |cmm_type| CmmType::Pointer(Box::new(cmm_type));
+        for (source, expected) in [
+            ("const int u", constant(CmmType::Int)),
+            ("unsigned const u", constant(CmmType::UnsignedInt)),
+            ("long const int u", constant(CmmType::Long)),
+            ("const char *u", pointer(constant(CmmType::Char))),
+            ("char *const u", constant(pointer(CmmType::Char))),
+            (
+                "const int *const *u",
+                pointer(constant(pointer(constant(CmmType::Int)))),
+            ),
+            ("const enum e u", constant(CmmType::Int)),
+            (
+                "struct s const u",
+                constant(CmmType::Struct(Symbol::from("s"))),
+            ),
+            (
+                "const int u[2]",
+                CmmType::Array(Box::new(constant(CmmType::Int)), 2),
+            ),
+        ] {
+            let tokens = tokenize(&format!("for ({source};;) return 1;")).unwrap();
+            let mut parser = Parser::new(tokens);
+            let Ok(CmmStatement::For {
+                init: CmmForInit::Declaration(CmmDeclaration::Declaration { variable_type, .. }),
+                ..
+            }) = parser.parse_statement()
+            else {
+                panic!("Expected a for loop declaring a variable");
+            };
+            assert_eq!(variable_type, expected, "{source}");
+        }
+
+        let tokens = tokenize("for (struct s int u;;) return 1;").unwrap();
+        assert_eq!(
+            Parser::new(tokens).parse_statement(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::IntKeyword]
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_type_specifiers() {
+        for (source, expected) in [
+            ("int", CmmType::Int),
+            ("unsigned", CmmType::UnsignedInt),
+            ("unsigned int", CmmType::UnsignedInt),
+            ("int unsigned", CmmType::UnsignedInt),
+            ("long", CmmType::Long),
+            ("long int", CmmType::Long),
+            ("int long", CmmType::Long),
+            ("unsigned long", CmmType::UnsignedLong),
+            ("long unsigned", CmmType::UnsignedLong),
+            ("unsigned long int", CmmType::UnsignedLong),
+            ("int long unsigned", CmmType::UnsignedLong),
+            ("short", CmmType::Short),
+            ("short int", CmmType::Short),
+            ("int short", CmmType::Short),
+            ("char", CmmType::Char),
+            ("unsigned char", CmmType::UnsignedChar),
+            ("char unsigned", CmmType::UnsignedChar),
+            ("signed", CmmType::Int),
+            ("signed int", CmmType::Int),
+            ("int signed", CmmType::Int),
+            ("signed long", CmmType::Long),
+            ("long signed int", CmmType::Long),
+            ("signed short", CmmType::Short),
+            ("signed char", CmmType::Char),
+            ("char signed", CmmType::Char),
+            ("double", CmmType::Double),
+            ("_Bool", CmmType::Bool),
+        ] {
+            let tokens = tokenize(&format!("for ({source} u = 1;;) return u;")).unwrap();
+            let mut parser = Parser::new(tokens);
+            let Ok(CmmStatement::For {
+                init: CmmForInit::Declaration(CmmDeclaration::Declaration { variable_type, .. }),
+                ..
+            }) = parser.parse_statement()
+            else {
+                panic!("Expected a for loop declaring a variable");
+            };
+            assert_eq!(variable_type, expected, "{source}");
+        }
+
+        let tokens = tokenize("for (unsigned int unsigned u;;) return u;").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![
+                    TokenType::UnsignedKeyword,
+                    TokenType::IntKeyword,
+                    TokenType::UnsignedKeyword
+                ]
+            })
+        );
+        let tokens = tokenize("for (unsigned short u;;) return u;").unwrap();
+        assert_eq!(
+            Parser::new(tokens).parse_statement(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::UnsignedKeyword, TokenType::ShortKeyword]
+            })
+        );
+        let tokens = tokenize("for (char int c;;) return c;").unwrap();
+        assert_eq!(
+            Parser::new(tokens).parse_statement(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::CharKeyword, TokenType::IntKeyword]
+            })
+        );
+        let tokens = tokenize("for (long double d;;) return d;").unwrap();
+        assert_eq!(
+            Parser::new(tokens).parse_statement(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::LongKeyword, TokenType::DoubleKeyword]
+            })
+        );
+        for (source, specifiers) in [
+            (
+                "signed unsigned",
+                vec![TokenType::SignedKeyword, TokenType::UnsignedKeyword],
+            ),
+            (
+                "signed signed",
+                vec![TokenType::SignedKeyword, TokenType::SignedKeyword],
+            ),
+            (
+                "signed double",
+                vec![TokenType::SignedKeyword, TokenType::DoubleKeyword],
+            ),
+            ("enum color signed", vec![TokenType::SignedKeyword]),
+        ] {
+            let tokens = tokenize(&format!("for ({source} s;;) return s;")).unwrap();
+            assert_eq!(
+                Parser::new(tokens).parse_statement(),
+                Err(ParserError::InvalidTypeSpecifiers { specifiers }),
+                "{source}"
+            );
+        }
+        assert_eq!(
+            ParserError::InvalidTypeSpecifiers { specifiers: vec![] }.code(),
+            error_codes::INVALID_TYPE_SPECIFIERS
+        );
+    }
+
+    #[test]
+    fn test_parse_break_and_continue() {
+        let tokens = tokenize("while (1) if (1) break; else continue;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let Ok(CmmStatement::While { body, .. }) = parser.parse_statement() else {
+            panic!("Expected a while loop");
+        };
+        let CmmStatement::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } = *body
+        else {
+            panic!("Expected an if statement with an else branch");
+        };
+        assert_eq!(*then_branch, CmmStatement::Break { span: None });
+        assert_eq!(*else_branch, CmmStatement::Continue { span: None });
+
+        let tokens = tokenize("break 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Semicolon),
+                actual: TokenType::Constant
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_null_and_expression_statements() {
+        let tokens = tokenize("for (;;) if (x) ; else x = f(x) + 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let Ok(CmmStatement::For { body, .. }) = parser.parse_statement() else {
+            panic!("Expected a for loop");
+        };
+        let CmmStatement::If {
+            then_branch,
+            else_branch: Some(else_branch),
+            ..
+        } = *body
+        else {
+            panic!("Expected an if statement with an else branch");
+        };
+        assert_eq!(*then_branch, CmmStatement::Null { span: None });
+        let CmmStatement::Expression { expression, .. } = *else_branch else {
+            panic!("Expected an expression statement");
+        };
+        assert!(matches!(
+            parser.expressions[expression],
+            CmmExpression::Assignment { .. }
+        ));
+
+        let tokens = tokenize("x = 1").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedEndOfInput)
+        );
+    }
+
+    #[test]
+    fn test_parse_assignments_are_right_associative() {
+        let source_code = "a = b = 1 + 2 * 3";
+        let tokens = tokenize_with_spans(source_code).unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Assignment { target, value } = parser.expressions[expression] else {
+            panic!("Expected an assignment");
+        };
+        assert_eq!(
+            parser.expressions[target],
+            CmmExpression::Variable {
+                identifier: Symbol::from("a")
+            }
+        );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, value),
+            "b = 1 + 2 * 3"
+        );
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(expression)),
+            source_code
+        );
+
+        let tokens = tokenize("1 + a = 2").unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert_eq!(
+            printer::print_expression(&parser.expressions, expression),
+            "(1 + a) = 2"
+        );
+    }
+
+    #[test]
+    fn test_parse_prefix_increment_and_decrement() {
+        let source_code = "- --a * ++b";
+        let tokens = tokenize_with_spans(source_code).unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left,
+            right,
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a multiplication");
+        };
         assert_eq!(
-            result.unwrap(),
+            printer::print_expression(&parser.expressions, left),
+            "- --a"
+        );
+        assert!(matches!(
+            parser.expressions[right],
             CmmExpression::Unary {
-                operator: CmmUnaryOperator::Negate,
-                expression: Box::new(CmmExpression::IntegerConstant { value: 1 })
+                operator: CmmUnaryOperator::PrefixIncrement,
+                ..
             }
+        ));
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(right)),
+            "++b"
         );
     }
 
     #[test]
-    fn test_parse_valid_unary_expression_complement() {
-        let tokens = vec![Token::Tilde, Token::Constant(1), Token::Semicolon];
+    fn test_parse_postfix_increment_and_decrement() {
+        let source_code = "-a++ * (b)--";
+        let tokens = tokenize_with_spans(source_code).unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_expression(0);
-        assert!(
-            result.is_ok(),
-            "Should parse valid unary expression complement, got {:?}",
-            result
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left,
+            right,
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a multiplication");
+        };
+        // The postfix operator binds tighter than the prefix negation.
+        let CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: negated,
+        } = parser.expressions[left]
+        else {
+            panic!("Expected a negation");
+        };
+        assert!(matches!(
+            parser.expressions[negated],
+            CmmExpression::Unary {
+                operator: CmmUnaryOperator::PostfixIncrement,
+                ..
+            }
+        ));
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(right)),
+            "(b)--"
         );
         assert_eq!(
-            result.unwrap(),
+            printer::print_expression(&parser.expressions, expression),
+            "-a++ * b--"
+        );
+
+        let tokens = tokenize("--a--").unwrap();
+        let mut parser = Parser::new(tokens);
+        let expression = parser.parse_expression(0).unwrap();
+        assert!(matches!(
+            parser.expressions[expression],
             CmmExpression::Unary {
-                operator: CmmUnaryOperator::Complement,
-                expression: Box::new(CmmExpression::IntegerConstant { value: 1 })
+                operator: CmmUnaryOperator::PrefixDecrement,
+                ..
             }
-        );
+        ));
     }
 
     #[test]
-    fn test_parse_ampersand_precedence() {
-        let tokens = vec![
-            Token::OpenParen,
-            Token::Constant(10),
-            Token::DoubleAmpersand,
-            Token::Constant(0),
-            Token::CloseParen,
-            Token::Plus,
-            Token::OpenParen,
-            Token::Constant(0),
-            Token::DoubleAmpersand,
-            Token::Constant(4),
-            Token::CloseParen,
-            Token::Plus,
-            Token::OpenParen,
-            Token::Constant(0),
-            Token::DoubleAmpersand,
-            Token::Constant(0),
-            Token::CloseParen,
-            Token::Semicolon,
-        ];
+    fn test_parse_casts() {
+        let tokens = tokenize("(unsigned short) a").unwrap();
+        assert_eq!(
+            Parser::new(tokens).parse_expression(0),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::UnsignedKeyword, TokenType::ShortKeyword]
+            })
+        );
+
+        let source_code = "-(long) a++ * (char) (b + 1)";
+        let tokens = tokenize_with_spans(source_code).unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_expression(0);
-        assert!(
-            result.is_ok(),
-            "Should be able to parse expression, got error: {:?}",
-            result
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left,
+            right,
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a multiplication");
+        };
+        // The cast binds weaker than the postfix increment and tighter than the negation.
+        let CmmExpression::Unary {
+            operator: CmmUnaryOperator::Negate,
+            expression: negated,
+        } = parser.expressions[left]
+        else {
+            panic!("Expected a negation");
+        };
+        let CmmExpression::Cast {
+            target_type: CmmType::Long,
+            expression: converted,
+        } = parser.expressions[negated]
+        else {
+            panic!("Expected a cast to long");
+        };
+        assert!(matches!(
+            parser.expressions[converted],
+            CmmExpression::Unary {
+                operator: CmmUnaryOperator::PostfixIncrement,
+                ..
+            }
+        ));
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(negated)),
+            "(long) a++"
         );
         assert_eq!(
-            result.unwrap(),
-            CmmExpression::Binary {
-                operator: CmmBinaryOperator::Add,
-                left: Box::new(CmmExpression::Binary {
-                    operator: CmmBinaryOperator::Add,
-                    left: Box::new(CmmExpression::Binary {
-                        operator: CmmBinaryOperator::And,
-                        left: Box::new(CmmExpression::IntegerConstant { value: 10 }),
-                        right: Box::new(CmmExpression::IntegerConstant { value: 0 }),
-                    }),
-                    right: Box::new(CmmExpression::Binary {
-                        operator: CmmBinaryOperator::And,
-                        left: Box::new(CmmExpression::IntegerConstant { value: 0 }),
-                        right: Box::new(CmmExpression::IntegerConstant { value: 4 }),
-                    }),
-                }),
-                right: Box::new(CmmExpression::Binary {
-                    operator: CmmBinaryOperator::And,
-                    left: Box::new(CmmExpression::IntegerConstant { value: 0 }),
-                    right: Box::new(CmmExpression::IntegerConstant { value: 0 }),
-                }),
-            }
+            span_text(source_code, parser.expressions.span(right)),
+            "(char) (b + 1)"
+        );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, expression),
+            "-(long) a++ * (char) (b + 1)"
         );
     }
 
     #[test]
-    fn test_parse_valid_parenthesized_expression() {
-        let tokens = vec![
-            Token::OpenParen,
-            Token::Constant(1),
-            Token::CloseParen,
-            Token::Semicolon,
-        ];
+    fn test_parse_pointers() {
+        let source_code = "*&a * **(int **) b";
+        let tokens = tokenize_with_spans(source_code).unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_expression(0);
-        assert!(
-            result.is_ok(),
-            "Should parse valid parenthesized expression, got {:?}",
-            result
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary {
+            operator: CmmBinaryOperator::Multiply,
+            left,
+            right,
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a multiplication");
+        };
+        let CmmExpression::Dereference {
+            expression: address,
+        } = parser.expressions[left]
+        else {
+            panic!("Expected a dereference");
+        };
+        assert!(matches!(
+            parser.expressions[address],
+            CmmExpression::AddressOf { .. }
+        ));
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(right)),
+            "**(int **) b"
+        );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, expression),
+            "*&a * **(int **) b"
+        );
+
+        let tokens = tokenize("for (char **p = 0; ; ) return 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let CmmStatement::For {
+            init: CmmForInit::Declaration(CmmDeclaration::Declaration { variable_type, .. }),
+            ..
+        } = parser.parse_statement().unwrap()
+        else {
+            panic!("Expected a for loop with a declaration");
+        };
+        assert_eq!(
+            variable_type,
+            CmmType::Pointer(Box::new(CmmType::Pointer(Box::new(CmmType::Char))))
         );
-        assert_eq!(result.unwrap(), CmmExpression::IntegerConstant { value: 1 });
     }
 
     #[test]
-    fn test_parse_valid_operator_precedence() {
-        let tokens = vec![
-            Token::Constant(1),
-            Token::Asterisk,
-            Token::Constant(2),
-            Token::Hyphen,
-            Token::Constant(3),
-            Token::Asterisk,
-            Token::OpenParen,
-            Token::Constant(4),
-            Token::Plus,
-            Token::Constant(5),
-            Token::CloseParen,
-            Token::Semicolon,
-        ];
+    fn test_parse_arrays() {
+        let source_code = "*a[i + 1][2]++ - (p + 1)[0]";
+        let tokens = tokenize_with_spans(source_code).unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_expression(0);
-        assert!(
-            result.is_ok(),
-            "Should parse expression with correct operator precedence, got {:?}",
-            result
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::Binary {
+            operator: CmmBinaryOperator::Subtract,
+            left,
+            ..
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a subtraction");
+        };
+        // Subscripts and postfix operators bind tighter than the dereference.
+        let CmmExpression::Dereference {
+            expression: increment,
+        } = parser.expressions[left]
+        else {
+            panic!("Expected a dereference");
+        };
+        let CmmExpression::Unary {
+            operator: CmmUnaryOperator::PostfixIncrement,
+            expression: element,
+        } = parser.expressions[increment]
+        else {
+            panic!("Expected a postfix increment");
+        };
+        let CmmExpression::Subscript { array: row, .. } = parser.expressions[element] else {
+            panic!("Expected a subscript");
+        };
+        assert!(matches!(
+            parser.expressions[row],
+            CmmExpression::Subscript { .. }
+        ));
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(element)),
+            "a[i + 1][2]"
         );
         assert_eq!(
-            result.unwrap(),
-            CmmExpression::Binary {
-                operator: CmmBinaryOperator::Subtract,
-                left: Box::new(CmmExpression::Binary {
-                    operator: CmmBinaryOperator::Multiply,
-                    left: Box::new(CmmExpression::IntegerConstant { value: 1 }),
-                    right: Box::new(CmmExpression::IntegerConstant { value: 2 }),
-                }),
-                right: Box::new(CmmExpression::Binary {
-                    operator: CmmBinaryOperator::Multiply,
-                    left: Box::new(CmmExpression::IntegerConstant { value: 3 }),
-                    right: Box::new(CmmExpression::Binary {
-                        operator: CmmBinaryOperator::Add,
-                        left: Box::new(CmmExpression::IntegerConstant { value: 4 }),
-                        right: Box::new(CmmExpression::IntegerConstant { value: 5 }),
-                    }),
-                }),
-            }
+            printer::print_expression(&parser.expressions, expression),
+            "*a[i + 1][2]++ - (p + 1)[0]"
         );
+
+        let tokens = tokenize("for (char *a[2][3]; ; ) return 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let CmmStatement::For {
+            init: CmmForInit::Declaration(CmmDeclaration::Declaration { variable_type, .. }),
+            ..
+        } = parser.parse_statement().unwrap()
+        else {
+            panic!("Expected a for loop with a declaration");
+        };
+        let row_type = CmmType::Array(Box::new(CmmType::Pointer(Box::new(CmmType::Char))), 3);
+        assert_eq!(variable_type, CmmType::Array(Box::new(row_type), 2));
+
+        let tokens = tokenize("for (int a[2][2] = {{1, 2,}, {i}}; ; ) return 1;").unwrap();
+        let mut parser = Parser::new(tokens);
+        let CmmStatement::For {
+            init: CmmForInit::Declaration(declaration),
+            ..
+        } = parser.parse_statement().unwrap()
+        else {
+            panic!("Expected a for loop with a declaration");
+        };
+        let CmmDeclaration::Declaration {
+            initializer: Some(CmmInitializer::Compound(rows)),
+            ..
+        } = &declaration
+        else {
+            panic!("Expected a compound initializer");
+        };
+        // The trailing comma does not add an element.
+        assert!(matches!(
+            &rows[..],
+            [CmmInitializer::Compound(first), CmmInitializer::Compound(second)]
+                if first.len() == 2 && second.len() == 1
+        ));
+        for source_code in ["int a[2] = {}", "int a[2] = {1,,}", "int a[2] = {1 2}"] {
+            let mut parser = Parser::new(tokenize(source_code).unwrap());
+            assert!(parser.parse_declaration().is_err(), "{source_code}");
+        }
+
+        for (source_code, found) in [
+            ("int a[0]", Token::Constant(0)),
+            ("int a[n]", Token::Identifier(Symbol::from("n"))),
+            ("int a[1.5]", Token::DoubleConstant(1.5)),
+        ] {
+            let mut parser = Parser::new(tokenize(source_code).unwrap());
+            assert_eq!(
+                parser.parse_declaration(),
+                Err(ParserError::InvalidArraySize { found }),
+                "{source_code}"
+            );
+        }
     }
 
     #[test]
-    fn test_parse_identifier_success() {
-        let identifier = "main".to_string();
-        let tokens = vec![Token::Identifier(identifier.clone())];
-        let mut parser = Parser::new(tokens);
-        let result = parser.parse_identifier();
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), identifier);
+    fn test_parse_ast_locates_declarations_and_statements() {
+        let source_code = "int main(void) { int a = 1; while (a) a = a - 1; return a; }";
+        let mut parser = Parser::new(tokenize_with_spans(source_code).unwrap());
+        let CmmAst::Program { functions, .. } = parser.parse_ast().unwrap();
+        let [
+            CmmFunction::Function {
+                body: Some(body),
+                span,
+                ..
+            },
+        ] = functions.as_slice()
+        else {
+            panic!("Expected a function definition");
+        };
+        assert_eq!(span_text(source_code, *span), "int main(void)");
+        let [
+            CmmBlockItem::Declaration(declaration),
+            CmmBlockItem::Statement(while_statement),
+            CmmBlockItem::Statement(return_statement),
+        ] = body.as_slice()
+        else {
+            panic!("Expected a declaration and two statements");
+        };
+        let CmmDeclaration::Declaration { span, .. } = declaration;
+        assert_eq!(span_text(source_code, *span), "int a = 1");
+        assert_eq!(
+            span_text(source_code, while_statement.span()),
+            "while (a) a = a - 1;"
+        );
+        assert_eq!(span_text(source_code, return_statement.span()), "return a;");
     }
 
     #[test]
-    fn test_parse_identifier_failure_unexpected_sequence() {
-        let tokens = vec![Token::IntKeyword];
+    fn test_parse_compound_assignments() {
+        let source_code = "a += b <<= c = 1 | 2 ^ 3 & 4 >> 1";
+        let tokens = tokenize_with_spans(source_code).unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_identifier();
-        assert!(result.is_err());
+        let expression = parser.parse_expression(0).unwrap();
+        let CmmExpression::CompoundAssignment {
+            operator: CmmBinaryOperator::Add,
+            target,
+            value,
+        } = parser.expressions[expression]
+        else {
+            panic!("Expected a compound assignment");
+        };
         assert_eq!(
-            result.unwrap_err(),
-            ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(TokenType::Identifier),
-                actual: TokenType::IntKeyword
+            parser.expressions[target],
+            CmmExpression::Variable {
+                identifier: Symbol::from("a")
             }
         );
+        assert_eq!(
+            printer::print_expression(&parser.expressions, value),
+            "b <<= c = 1 | 2 ^ 3 & 4 >> 1"
+        );
+        assert_eq!(
+            span_text(source_code, parser.expressions.span(expression)),
+            source_code
+        );
+
+        let compound_operators = [
+            ("-=", CmmBinaryOperator::Subtract),
+            ("*=", CmmBinaryOperator::Multiply),
+            ("/=", CmmBinaryOperator::Divide),
+            ("%=", CmmBinaryOperator::Remainder),
+            ("&=", CmmBinaryOperator::BitwiseAnd),
+            ("|=", CmmBinaryOperator::BitwiseOr),
+            ("^=", CmmBinaryOperator::BitwiseXor),
+            (">>=", CmmBinaryOperator::RightShift),
+        ];
+        for (symbol, expected_operator) in compound_operators {
+            let tokens = tokenize(&format!("a {} 2", symbol)).unwrap();
+            let mut parser = Parser::new(tokens);
+            let expression = parser.parse_expression(0).unwrap();
+            assert!(
+                matches!(
+                    &parser.expressions[expression],
+                    CmmExpression::CompoundAssignment { operator, .. } if *operator == expected_operator
+                ),
+                "{}",
+                symbol
+            );
+        }
     }
 
     #[test]
-    fn test_parse_statement_success() {
-        let tokens = vec![Token::ReturnKeyword, Token::Constant(1), Token::Semicolon];
+    fn test_parse_if_statement_failure_missing_parentheses() {
+        let tokens = tokenize("if 1 return 2;").unwrap();
         let mut parser = Parser::new(tokens);
-        let result = parser.parse_statement();
-        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap(),
-            CmmStatement::Return {
-                expression: CmmExpression::IntegerConstant { value: 1 }
-            }
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::OpenParen),
+                actual: TokenType::Constant
+            })
         );
+
+        let tokens = tokenize("if (1) else return 2;").unwrap();
+        let mut parser = Parser::new(tokens);
+        assert!(matches!(
+            parser.parse_statement(),
+            Err(ParserError::UnexpectedToken {
+                actual: TokenType::ElseKeyword,
+                ..
+            })
+        ));
     }
 
     #[test]
@@ -634,8 +2861,21 @@ mod tests {
             ParserError::UnexpectedToken {
                 expected: TokenTypeOption::Many(vec![
                     TokenType::Constant,
+                    TokenType::LongConstant,
+                    TokenType::UnsignedConstant,
+                    TokenType::UnsignedLongConstant,
+                    TokenType::CharConstant,
+                    TokenType::DoubleConstant,
+                    TokenType::StringLiteral,
+                    TokenType::Identifier,
+                    TokenType::Plus,
                     TokenType::Hyphen,
                     TokenType::Tilde,
+                    TokenType::ExclamationMark,
+                    TokenType::DoublePlus,
+                    TokenType::DoubleHyphen,
+                    TokenType::Ampersand,
+                    TokenType::Asterisk,
                     TokenType::OpenParen
                 ]),
                 actual: TokenType::VoidKeyword
@@ -645,7 +2885,7 @@ mod tests {
 
     #[test]
     fn test_parse_function_success() {
-        let identifier = "main".to_string();
+        let identifier = Symbol::from("main");
         let tokens = vec![
             Token::IntKeyword,
             Token::Identifier(identifier.clone()),
@@ -661,20 +2901,29 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let result = parser.parse_function();
         assert!(result.is_ok());
+        let mut expected = ExpressionArena::new();
+        let expression = expected.alloc(CmmExpression::IntegerConstant { value: 1 });
         assert_eq!(
             result.unwrap(),
             CmmFunction::Function {
-                identifier: identifier,
-                body: CmmStatement::Return {
-                    expression: CmmExpression::IntegerConstant { value: 1 }
-                }
+                identifier,
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(expression),
+                    span: None
+                })]),
+                span: None
             }
         );
+        assert_eq!(parser.expressions, expected);
     }
 
     #[test]
     fn test_parse_function_failure_unexpected_sequence() {
-        let identifier = "main".to_string();
+        let identifier = Symbol::from("main");
         let tokens = vec![
             Token::IntKeyword,
             Token::Identifier(identifier.clone()),
@@ -685,23 +2934,157 @@ mod tests {
             Token::ReturnKeyword,
             Token::Constant(1),
             Token::Semicolon,
+            Token::ReturnKeyword,
+            Token::Constant(2),
             Token::Semicolon,
         ];
         let mut parser = Parser::new(tokens);
         let result = parser.parse_function();
         assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), ParserError::UnexpectedEndOfInput);
+    }
+
+    #[test]
+    fn test_parse_function_block_items() {
+        let tokens = tokenize(
+            "int main(void) { int x = 1; long y; for (int i = 0; i < 3; x = x * 2) continue; return x; }",
+        )
+        .unwrap();
+        let mut parser = Parser::new(tokens);
+        let CmmFunction::Function {
+            body: Some(body), ..
+        } = parser.parse_function().unwrap()
+        else {
+            panic!("Expected a function definition");
+        };
+        assert!(matches!(
+            body.as_slice(),
+            [
+                CmmBlockItem::Declaration(CmmDeclaration::Declaration {
+                    initializer: Some(_),
+                    ..
+                }),
+                CmmBlockItem::Declaration(CmmDeclaration::Declaration {
+                    variable_type: CmmType::Long,
+                    initializer: None,
+                    ..
+                }),
+                CmmBlockItem::Statement(CmmStatement::For { .. }),
+                CmmBlockItem::Statement(CmmStatement::Return { .. }),
+            ]
+        ));
+
+        let empty_body = tokenize("int main(void) {}").unwrap();
+        assert!(matches!(
+            Parser::new(empty_body).parse_function(),
+            Ok(CmmFunction::Function { body: Some(body), .. }) if body.is_empty()
+        ));
+        let missing_semicolon = tokenize("int main(void) { int x return x; }").unwrap();
         assert_eq!(
-            result.unwrap_err(),
-            ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(TokenType::CloseBrace),
-                actual: TokenType::Semicolon
-            }
+            Parser::new(missing_semicolon).parse_function(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Semicolon),
+                actual: TokenType::ReturnKeyword
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_function_signatures() {
+        let void_function = tokenize("void reset(void) { return; }").unwrap();
+        assert_eq!(
+            Parser::new(void_function).parse_function(),
+            Ok(CmmFunction::Function {
+                identifier: Symbol::from("reset"),
+                storage_class: None,
+                return_type: None,
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: None,
+                    span: None
+                })]),
+                span: None,
+            })
+        );
+        for (source, expected) in [
+            ("char f(void);", CmmType::Char),
+            ("long f(void);", CmmType::Long),
+            ("unsigned int f(void);", CmmType::UnsignedInt),
+            ("double f(void);", CmmType::Double),
+            (
+                "double *f(void);",
+                CmmType::Pointer(Box::new(CmmType::Double)),
+            ),
+            (
+                "const char **f(void);",
+                CmmType::Pointer(Box::new(CmmType::Pointer(Box::new(
+                    CmmType::Char.qualified(),
+                )))),
+            ),
+        ] {
+            let Ok(CmmFunction::Function { return_type, .. }) =
+                Parser::new(tokenize(source).unwrap()).parse_function()
+            else {
+                panic!("Expected {source} to parse");
+            };
+            assert_eq!(return_type, Some(expected), "{source}");
+        }
+        let without_return_type = tokenize("f(void);").unwrap();
+        assert!(matches!(
+            Parser::new(without_return_type).parse_function(),
+            Err(ParserError::UnexpectedToken {
+                actual: TokenType::Identifier,
+                ..
+            })
+        ));
+        let with_parameters = tokenize("int f(int a, unsigned short b) { return 0; }").unwrap();
+        assert_eq!(
+            Parser::new(with_parameters).parse_function(),
+            Err(ParserError::InvalidTypeSpecifiers {
+                specifiers: vec![TokenType::UnsignedKeyword, TokenType::ShortKeyword]
+            })
+        );
+        let with_parameters = tokenize("int f(int a, char b) { return 0; }").unwrap();
+        let Ok(CmmFunction::Function { parameters, .. }) =
+            Parser::new(with_parameters).parse_function()
+        else {
+            panic!("Expected a function");
+        };
+        assert_eq!(
+            parameters,
+            vec![
+                CmmParameter::Parameter {
+                    identifier: Symbol::from("a"),
+                    parameter_type: CmmType::Int,
+                },
+                CmmParameter::Parameter {
+                    identifier: Symbol::from("b"),
+                    parameter_type: CmmType::Char,
+                },
+            ]
+        );
+        let without_parameter_name = tokenize("int f(int) { return 0; }").unwrap();
+        assert_eq!(
+            Parser::new(without_parameter_name).parse_function(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Identifier),
+                actual: TokenType::CloseParen
+            })
         );
+        let trailing_comma = tokenize("int f(int a,) { return 0; }").unwrap();
+        assert!(matches!(
+            Parser::new(trailing_comma).parse_function(),
+            Err(ParserError::UnexpectedToken {
+                actual: TokenType::CloseParen,
+                ..
+            })
+        ));
     }
 
     #[test]
     fn test_parse_ast_success() {
-        let identifier = "main".to_string();
+        let identifier = Symbol::from("main");
         let tokens = vec![
             Token::IntKeyword,
             Token::Identifier(identifier.clone()),
@@ -717,22 +3100,370 @@ mod tests {
         let mut parser = Parser::new(tokens);
         let result = parser.parse_ast();
         assert!(result.is_ok());
+        let mut expressions = ExpressionArena::new();
+        let expression = expressions.alloc(CmmExpression::IntegerConstant { value: 1 });
         assert_eq!(
             result.unwrap(),
             CmmAst::Program {
-                function: CmmFunction::Function {
+                structs: vec![],
+                enums: vec![],
+                variables: vec![],
+                functions: vec![CmmFunction::Function {
                     identifier,
-                    body: CmmStatement::Return {
-                        expression: CmmExpression::IntegerConstant { value: 1 }
-                    }
-                }
+                    storage_class: None,
+                    return_type: Some(CmmType::Int),
+                    parameters: vec![],
+                    variadic: false,
+                    body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                        expression: Some(expression),
+                        span: None
+                    })]),
+                    span: None
+                }],
+                expressions,
             }
         );
     }
 
+    #[test]
+    fn test_parse_ast_with_prototypes() {
+        let tokens =
+            tokenize("int f(int a); int main(void) { return f(1); } int f(int a);").unwrap();
+        let Ok(CmmAst::Program { functions, .. }) = Parser::new(tokens).parse_ast() else {
+            panic!("Expected a program");
+        };
+        let declarations: Vec<(&str, bool)> = functions
+            .iter()
+            .map(
+                |CmmFunction::Function {
+                     identifier, body, ..
+                 }| (identifier.as_str(), body.is_some()),
+            )
+            .collect();
+        assert_eq!(declarations, [("f", false), ("main", true), ("f", false)]);
+
+        let missing_semicolon = tokenize("int f(void) int main(void) { return 0; }").unwrap();
+        assert_eq!(
+            Parser::new(missing_semicolon).parse_ast(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::OpenBrace),
+                actual: TokenType::IntKeyword
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ast_with_file_scope_variables() {
+        let tokens = tokenize(
+            "int g = 3; long a[2][3] = {{1}, {2, 3}}; int main(void) { return g; } double d;",
+        )
+        .unwrap();
+        let Ok(CmmAst::Program {
+            variables,
+            functions,
+            ..
+        }) = Parser::new(tokens).parse_ast()
+        else {
+            panic!("Expected a program");
+        };
+        assert_eq!(functions.len(), 1);
+        let declared: Vec<(&str, &CmmType, bool)> = variables
+            .iter()
+            .map(
+                |CmmDeclaration::Declaration {
+                     identifier,
+                     variable_type,
+                     initializer,
+                     ..
+                 }| (identifier.as_str(), variable_type, initializer.is_some()),
+            )
+            .collect();
+        let array = CmmType::Array(Box::new(CmmType::Array(Box::new(CmmType::Long), 3)), 2);
+        assert_eq!(
+            declared,
+            [
+                ("g", &CmmType::Int, true),
+                ("a", &array, true),
+                ("d", &CmmType::Double, false)
+            ]
+        );
+
+        let missing_semicolon = tokenize("int g = 3 int main(void) { return g; }").unwrap();
+        assert_eq!(
+            Parser::new(missing_semicolon).parse_ast(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::Semicolon),
+                actual: TokenType::IntKeyword
+            })
+        );
+        let void_variable = tokenize("void g;").unwrap();
+        assert_eq!(
+            Parser::new(void_variable).parse_ast(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::OpenParen),
+                actual: TokenType::Semicolon
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ast_with_storage_classes() {
+        let tokens = tokenize("static int f(void); extern int g(void); int h(void);").unwrap();
+        let Ok(CmmAst::Program { functions, .. }) = Parser::new(tokens).parse_ast() else {
+            panic!("Expected a program");
+        };
+        let storage_classes: Vec<Option<CmmStorageClass>> = functions
+            .iter()
+            .map(|CmmFunction::Function { storage_class, .. }| *storage_class)
+            .collect();
+        assert_eq!(
+            storage_classes,
+            [
+                Some(CmmStorageClass::Static),
+                Some(CmmStorageClass::Extern),
+                None
+            ]
+        );
+
+        // Variables take storage-class specifiers at file scope and in function bodies.
+        let tokens = tokenize(
+            "static int a; extern long b; int main(void) { static int c = 1; extern long b; }",
+        )
+        .unwrap();
+        let Ok(CmmAst::Program {
+            variables,
+            functions,
+            ..
+        }) = Parser::new(tokens).parse_ast()
+        else {
+            panic!("Expected a program");
+        };
+        let CmmFunction::Function {
+            body: Some(body), ..
+        } = &functions[0]
+        else {
+            panic!("Expected a function definition");
+        };
+        let local_declarations = body.iter().map(|item| match item {
+            CmmBlockItem::Declaration(declaration) => declaration,
+            CmmBlockItem::Statement(_) => panic!("Expected a declaration"),
+        });
+        let storage_classes: Vec<(&str, Option<CmmStorageClass>)> = variables
+            .iter()
+            .chain(local_declarations)
+            .map(
+                |CmmDeclaration::Declaration {
+                     identifier,
+                     storage_class,
+                     ..
+                 }| (identifier.as_str(), *storage_class),
+            )
+            .collect();
+        assert_eq!(
+            storage_classes,
+            [
+                ("a", Some(CmmStorageClass::Static)),
+                ("b", Some(CmmStorageClass::Extern)),
+                ("c", Some(CmmStorageClass::Static)),
+                ("b", Some(CmmStorageClass::Extern))
+            ]
+        );
+
+        // A declaration has at most one storage-class specifier, which precedes the type.
+        let two_storage_classes = tokenize("static extern int f(void);").unwrap();
+        assert_eq!(
+            Parser::new(two_storage_classes).parse_ast(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::Many(vec![
+                    TokenType::IntKeyword,
+                    TokenType::UnsignedKeyword,
+                    TokenType::SignedKeyword,
+                    TokenType::LongKeyword,
+                    TokenType::ShortKeyword,
+                    TokenType::CharKeyword,
+                    TokenType::BoolKeyword,
+                    TokenType::DoubleKeyword,
+                    TokenType::StructKeyword,
+                    TokenType::EnumKeyword,
+                ]),
+                actual: TokenType::ExternKeyword
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ast_with_variadic_functions() {
+        let tokens = tokenize("int printf(...); int f(char c, ...); int g(int a);").unwrap();
+        let Ok(CmmAst::Program { functions, .. }) = Parser::new(tokens).parse_ast() else {
+            panic!("Expected a program");
+        };
+        let declarations: Vec<(&str, usize, bool)> = functions
+            .iter()
+            .map(
+                |CmmFunction::Function {
+                     identifier,
+                     parameters,
+                     variadic,
+                     ..
+                 }| (identifier.as_str(), parameters.len(), *variadic),
+            )
+            .collect();
+        assert_eq!(
+            declarations,
+            [("printf", 0, true), ("f", 1, true), ("g", 1, false)]
+        );
+
+        // The ellipsis must end the parameter list.
+        let misplaced_ellipsis = tokenize("int f(..., int a);").unwrap();
+        assert_eq!(
+            Parser::new(misplaced_ellipsis).parse_ast(),
+            Err(ParserError::UnexpectedToken {
+                expected: TokenTypeOption::One(TokenType::CloseParen),
+                actual: TokenType::Comma
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ast_with_structs() {
+        let source_code = "struct node { int value; struct node *next; char name[4]; };\n\
+                           int f(struct node *n) { return n->next->value + (*n).name[1]; }";
+        let tokens = tokenize(source_code).unwrap();
+        let Ok(CmmAst::Program {
+            structs,
+            functions,
+            expressions,
+            ..
+        }) = Parser::new(tokens).parse_ast()
+        else {
+            panic!("Expected a program");
+        };
+        let node = Symbol::from("node");
+        let pointer = CmmType::Pointer(Box::new(CmmType::Struct(node.clone())));
+        assert_eq!(
+            structs,
+            [CmmStructDeclaration::Struct {
+                tag: node,
+                members: vec![
+                    CmmMember::Member {
+                        identifier: Symbol::from("value"),
+                        member_type: CmmType::Int,
+                    },
+                    CmmMember::Member {
+                        identifier: Symbol::from("next"),
+                        member_type: pointer.clone(),
+                    },
+                    CmmMember::Member {
+                        identifier: Symbol::from("name"),
+                        member_type: CmmType::Array(Box::new(CmmType::Char), 4),
+                    },
+                ],
+                span: None,
+            }]
+        );
+        let CmmFunction::Function {
+            parameters,
+            body: Some(body),
+            ..
+        } = &functions[0]
+        else {
+            panic!("Expected a function definition");
+        };
+        let [
+            CmmBlockItem::Statement(CmmStatement::Return {
+                expression: Some(expression),
+                ..
+            }),
+        ] = body.as_slice()
+        else {
+            panic!("Expected a function returning an expression");
+        };
+        assert!(matches!(
+            &parameters[..],
+            [CmmParameter::Parameter { parameter_type, .. }] if parameter_type == &pointer
+        ));
+        // Member accesses bind like subscripts, tighter than the dereference and the addition.
+        assert_eq!(
+            printer::print_expression(&expressions, *expression),
+            "n->next->value + (*n).name[1]"
+        );
+
+        for source_code in [
+            "struct s {};",
+            "struct s { int x; }",
+            "struct { int x; };",
+            "struct s { int x };",
+        ] {
+            let tokens = tokenize(source_code).unwrap();
+            assert!(Parser::new(tokens).parse_ast().is_err(), "{source_code}");
+        }
+    }
+
+    #[test]
+    fn test_parse_ast_with_enums() {
+        let source_code = "enum color { RED, GREEN = 1 + 2, }; enum { A };\n\
+                           int f(enum color c) { return (enum color) c; }";
+        let tokens = tokenize(source_code).unwrap();
+        let Ok(CmmAst::Program {
+            enums,
+            functions,
+            expressions,
+            ..
+        }) = Parser::new(tokens).parse_ast()
+        else {
+            panic!("Expected a program");
+        };
+        let [
+            CmmEnumDeclaration::Enum {
+                tag: Some(tag),
+                enumerators,
+                ..
+            },
+            CmmEnumDeclaration::Enum { tag: None, .. },
+        ] = &enums[..]
+        else {
+            panic!("Expected a tagged and an anonymous enumeration");
+        };
+        assert_eq!(tag, &Symbol::from("color"));
+        let [
+            CmmEnumerator::Enumerator {
+                identifier: red,
+                value: None,
+            },
+            CmmEnumerator::Enumerator {
+                identifier: green,
+                value: Some(value),
+            },
+        ] = &enumerators[..]
+        else {
+            panic!("Expected two enumerators");
+        };
+        assert_eq!((red.as_str(), green.as_str()), ("RED", "GREEN"));
+        assert_eq!(printer::print_expression(&expressions, *value), "1 + 2");
+        // An enumeration type is `int`.
+        let CmmFunction::Function { parameters, .. } = &functions[0];
+        assert!(matches!(
+            &parameters[..],
+            [CmmParameter::Parameter {
+                parameter_type: CmmType::Int,
+                ..
+            }]
+        ));
+
+        for source_code in [
+            "enum e {};",
+            "enum e { A }",
+            "enum e { A,, B };",
+            "enum e { 1 };",
+        ] {
+            let tokens = tokenize(source_code).unwrap();
+            assert!(Parser::new(tokens).parse_ast().is_err(), "{source_code}");
+        }
+    }
+
     #[test]
     fn test_parse_ast_failure_no_tokens() {
-        let tokens = vec![];
+        let tokens: Vec<Token> = vec![];
         let mut parser = Parser::new(tokens);
         let result = parser.parse_ast();
         assert!(result.is_err());
@@ -743,7 +3474,7 @@ mod tests {
     fn test_parse_ast_failure_too_short_sequence() {
         let tokens = vec![
             Token::IntKeyword,
-            Token::Identifier("main".to_string()),
+            Token::Identifier(Symbol::from("main")),
             Token::OpenParen,
             Token::VoidKeyword,
             Token::CloseParen,
@@ -758,7 +3489,7 @@ mod tests {
     fn test_parse_ast_failure_unexpected_sequence() {
         let tokens = vec![
             Token::IntKeyword,
-            Token::Identifier("main".to_string()),
+            Token::Identifier(Symbol::from("main")),
             Token::ReturnKeyword,
             Token::VoidKeyword,
             Token::CloseParen,
@@ -775,17 +3506,17 @@ mod tests {
         assert_eq!(
             result.unwrap_err(),
             ParserError::UnexpectedToken {
-                expected: TokenTypeOption::One(TokenType::OpenParen),
+                expected: TokenTypeOption::One(TokenType::Semicolon),
                 actual: TokenType::ReturnKeyword
             }
         );
     }
 
     #[test]
-    fn test_parse_ast_failure_unexpected_trailing_tokens() {
+    fn test_parse_ast_failure_unexpected_top_level_token() {
         let tokens = vec![
             Token::IntKeyword,
-            Token::Identifier("main".to_string()),
+            Token::Identifier(Symbol::from("main")),
             Token::OpenParen,
             Token::VoidKeyword,
             Token::CloseParen,
@@ -802,9 +3533,45 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
-            ParserError::UnexpectedTrailingTokens {
-                found: vec![Token::Semicolon, Token::Semicolon]
+            ParserError::UnexpectedToken {
+                expected: TokenTypeOption::Many(vec![
+                    TokenType::IntKeyword,
+                    TokenType::VoidKeyword,
+                    TokenType::StructKeyword,
+                    TokenType::EnumKeyword,
+                    TokenType::StaticKeyword,
+                    TokenType::ExternKeyword,
+                ]),
+                actual: TokenType::Semicolon
             }
         );
+        // Only the offending token is consumed.
+        assert_eq!(parser.tokens.next(), Some(Token::Semicolon));
+    }
+
+    #[test]
+    fn test_parse_statement_failure_unexpected_trailing_token() {
+        let tokens = vec![Token::Constant(1), Token::Constant(2), Token::Constant(3)];
+        let mut parser = Parser::new(tokens);
+        assert_eq!(
+            parser.parse_statement_or_expression(),
+            Err(ParserError::UnexpectedTrailingToken {
+                found: Token::Constant(2)
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_ast_stops_pulling_tokens_at_first_error() {
+        let tokens = [
+            Token::IntKeyword,
+            Token::OpenParen,
+            Token::VoidKeyword,
+            Token::CloseParen,
+        ];
+        let mut pulled = 0;
+        let result = Parser::new(tokens.into_iter().inspect(|_| pulled += 1)).parse_ast();
+        assert!(result.is_err());
+        assert_eq!(pulled, 2);
     }
 }
@@ -0,0 +1,354 @@
+#[cfg(feature = "x86_64")]
+use crate::compiler::code_emission::emit_assembly;
+#[cfg(feature = "x86_64")]
+use crate::compiler::code_gen::{self, assembly_ast::AssemblyAst};
+use crate::compiler::errors::CompilerError;
+use crate::compiler::ir_gen::verifier::verify_tacky;
+use crate::compiler::ir_gen::{TackyEmitter, tacky_ast::TackyAst};
+use crate::compiler::lexer::{Lexer, tokens::Token};
+use crate::compiler::optimizer::PassManager;
+use crate::compiler::parser::cmm_ast::CmmAst;
+#[cfg(feature = "qbe")]
+use crate::compiler::qbe_emission::emit_qbe;
+use crate::compiler::semantic::conversions::insert_conversions;
+use crate::compiler::semantic::{
+    ValidatedAst, check_program, find_unused_variables, find_warnings,
+};
+use crate::compiler::session::CompilerSession;
+use crate::compiler::{Standard, parse_token_stream};
+use std::marker::PhantomData;
+
+/// Represents a single step of the compilation pipeline, turning an `I` into an `O`.
+///
+/// Stages are chained with `then` into a pipeline, which is itself a stage, so every prefix of a
+/// pipeline can be run on its own. Every stage of a compilation receives the same
+/// `CompilerSession`, which holds the options, the interner, and the diagnostics shared between
+/// the stages. Closures taking an `I` and the session and returning `Result<O, CompilerError>` are
+/// stages too, which makes it easy to replace a built-in stage or to insert an extra one.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::CompileOptions;
+/// # use cmm::compiler::errors::CompilerError;
+/// # use cmm::compiler::parser::cmm_ast::CmmAst;
+/// # use cmm::compiler::pipeline::{CompilerStage, ParserStage, SemanticStage, TackyStage};
+/// # use cmm::compiler::session::CompilerSession;
+/// let mut parsed_programs = 0;
+/// let mut pipeline = ParserStage
+///     .then(|cmm_ast: CmmAst, _: &mut CompilerSession| -> Result<CmmAst, CompilerError> {
+///         parsed_programs += 1;
+///         Ok(cmm_ast)
+///     })
+///     .then(SemanticStage)
+///     .then(TackyStage);
+/// let mut session = CompilerSession::new(CompileOptions::default());
+/// assert!(pipeline.run("int main(void) { return 2; }", &mut session).is_ok());
+/// drop(pipeline);
+/// assert_eq!(parsed_programs, 1);
+/// ```
+pub trait CompilerStage<I, O> {
+    /// Runs the stage.
+    ///
+    /// # Arguments
+    ///
+    /// * `input`: The output of the previous stage.
+    /// * `session`: The `CompilerSession` of the compilation.
+    ///
+    /// # Returns
+    ///
+    /// The output of the stage on success, or the `CompilerError` describing the failure.
+    fn run(&mut self, input: I, session: &mut CompilerSession) -> Result<O, CompilerError>;
+
+    /// Chains another stage after this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `next`: The stage consuming the output of this stage.
+    ///
+    /// # Returns
+    ///
+    /// A `Chain` running both stages, which stops at the first failing stage.
+    fn then<P, S>(self, next: S) -> Chain<Self, S, O>
+    where
+        Self: Sized,
+        S: CompilerStage<O, P>,
+    {
+        Chain {
+            first: self,
+            second: next,
+            intermediate: PhantomData,
+        }
+    }
+}
+
+impl<I, O, F> CompilerStage<I, O> for F
+where
+    F: FnMut(I, &mut CompilerSession) -> Result<O, CompilerError>,
+{
+    fn run(&mut self, input: I, session: &mut CompilerSession) -> Result<O, CompilerError> {
+        self(input, session)
+    }
+}
+
+/// Represents two stages run one after another, created by `CompilerStage::then`.
+///
+/// `M` is the type passed from the first stage to the second one.
+pub struct Chain<A, B, M> {
+    first: A,
+    second: B,
+    intermediate: PhantomData<fn() -> M>,
+}
+
+impl<I, M, O, A, B> CompilerStage<I, O> for Chain<A, B, M>
+where
+    A: CompilerStage<I, M>,
+    B: CompilerStage<M, O>,
+{
+    fn run(&mut self, input: I, session: &mut CompilerSession) -> Result<O, CompilerError> {
+        let intermediate = self.first.run(input, session)?;
+        self.second.run(intermediate, session)
+    }
+}
+
+/// Splits source code into tokens, interning the identifiers in the session's interner.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LexerStage;
+
+impl CompilerStage<&str, Vec<Token>> for LexerStage {
+    fn run(
+        &mut self,
+        cmm_source_code: &str,
+        session: &mut CompilerSession,
+    ) -> Result<Vec<Token>, CompilerError> {
+        let gnu_extensions = session.options.standard == Standard::Gnu;
+        Ok(Lexer::with_interner(cmm_source_code, &mut session.interner)
+            .with_gnu_extensions(gnu_extensions)
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+/// Lexes and parses source code into a C-- AST, locating parser errors in the source code, see
+/// `parse_token_stream`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParserStage;
+
+impl CompilerStage<&str, CmmAst> for ParserStage {
+    fn run(
+        &mut self,
+        cmm_source_code: &str,
+        session: &mut CompilerSession,
+    ) -> Result<CmmAst, CompilerError> {
+        let gnu_extensions = session.options.standard == Standard::Gnu;
+        parse_token_stream(
+            Lexer::with_interner(cmm_source_code, &mut session.interner)
+                .with_gnu_extensions(gnu_extensions)
+                .with_spans(),
+        )
+    }
+}
+
+/// Checks that every variable of a C-- AST is declared, see `resolve_variables`, and that the
+/// operators have operands of valid types, see `check_types`. Then reports the warnings of the AST
+/// that the session's warning options enable as diagnostics of the session, and makes its
+/// implicit conversions explicit, see `insert_conversions`. The resolved variables and the types
+/// are passed on to the TACKY stage with the AST.
+///
+/// When the session treats warnings as errors, the first warning fails the compilation instead.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SemanticStage;
+
+impl CompilerStage<CmmAst, ValidatedAst> for SemanticStage {
+    fn run(
+        &mut self,
+        cmm_ast: CmmAst,
+        session: &mut CompilerSession,
+    ) -> Result<ValidatedAst, CompilerError> {
+        let annotations = check_program(&cmm_ast)?;
+        let warnings = find_warnings(&cmm_ast, &annotations)
+            .into_iter()
+            .chain(find_unused_variables(&cmm_ast, annotations.variables()));
+        for warning in warnings {
+            session.warn(warning)?;
+        }
+        Ok(insert_conversions(cmm_ast, annotations))
+    }
+}
+
+/// Converts a validated C-- AST into TACKY IR, interning the generated names in the session's
+/// interner.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TackyStage;
+
+impl CompilerStage<ValidatedAst, TackyAst> for TackyStage {
+    fn run(
+        &mut self,
+        validated_ast: ValidatedAst,
+        session: &mut CompilerSession,
+    ) -> Result<TackyAst, CompilerError> {
+        Ok(TackyEmitter::with_interner(&mut session.interner).convert_ast(validated_ast)?)
+    }
+}
+
+/// Runs the registered passes over the TACKY IR.
+impl CompilerStage<TackyAst, TackyAst> for PassManager {
+    fn run(
+        &mut self,
+        mut tacky_ast: TackyAst,
+        _session: &mut CompilerSession,
+    ) -> Result<TackyAst, CompilerError> {
+        PassManager::run(self, &mut tacky_ast);
+        Ok(tacky_ast)
+    }
+}
+
+/// Runs the registered passes over the TACKY IR, leaving the `PassManager` with the caller.
+impl CompilerStage<TackyAst, TackyAst> for &mut PassManager {
+    fn run(
+        &mut self,
+        mut tacky_ast: TackyAst,
+        _session: &mut CompilerSession,
+    ) -> Result<TackyAst, CompilerError> {
+        PassManager::run(self, &mut tacky_ast);
+        Ok(tacky_ast)
+    }
+}
+
+/// Checks the invariants of the TACKY IR, see `verify_tacky`.
+///
+/// Runs after the passes of the `PassManager`, so that it also catches IR broken by custom passes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TackyVerifierStage;
+
+impl CompilerStage<TackyAst, TackyAst> for TackyVerifierStage {
+    fn run(
+        &mut self,
+        tacky_ast: TackyAst,
+        _session: &mut CompilerSession,
+    ) -> Result<TackyAst, CompilerError> {
+        verify_tacky(&tacky_ast)?;
+        Ok(tacky_ast)
+    }
+}
+
+/// Converts TACKY IR into an assembly AST.
+#[cfg(feature = "x86_64")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CodegenStage;
+
+#[cfg(feature = "x86_64")]
+impl CompilerStage<TackyAst, AssemblyAst> for CodegenStage {
+    fn run(
+        &mut self,
+        tacky_ast: TackyAst,
+        _session: &mut CompilerSession,
+    ) -> Result<AssemblyAst, CompilerError> {
+        Ok(code_gen::convert_ast(tacky_ast)?)
+    }
+}
+
+/// Emits the assembly code of an assembly AST for the session's target.
+#[cfg(feature = "x86_64")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EmissionStage;
+
+#[cfg(feature = "x86_64")]
+impl CompilerStage<AssemblyAst, String> for EmissionStage {
+    fn run(
+        &mut self,
+        assembly_ast: AssemblyAst,
+        session: &mut CompilerSession,
+    ) -> Result<String, CompilerError> {
+        Ok(emit_assembly(&assembly_ast, &session.options.target))
+    }
+}
+
+/// Emits the QBE IL of a TACKY IR.
+#[cfg(feature = "qbe")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QbeEmissionStage;
+
+#[cfg(feature = "qbe")]
+impl CompilerStage<TackyAst, String> for QbeEmissionStage {
+    fn run(
+        &mut self,
+        tacky_ast: TackyAst,
+        _session: &mut CompilerSession,
+    ) -> Result<String, CompilerError> {
+        Ok(emit_qbe(&tacky_ast))
+    }
+}
+
+/// Fails the compilation when the session's backend does not support the session's target.
+///
+/// Runs right before the backend stages, so that errors in the source code are reported first.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TargetCheckStage;
+
+impl CompilerStage<TackyAst, TackyAst> for TargetCheckStage {
+    fn run(
+        &mut self,
+        tacky_ast: TackyAst,
+        session: &mut CompilerSession,
+    ) -> Result<TackyAst, CompilerError> {
+        let options = &session.options;
+        if !options.backend.supports(&options.target) {
+            return Err(CompilerError::UnsupportedTarget {
+                target: options.target,
+            });
+        }
+        Ok(tacky_ast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::CompileOptions;
+    use crate::compiler::ir_gen::tacky_ast::{
+        TackyConstant, TackyFunction, TackyInstruction, TackyValue,
+    };
+    use crate::compiler::parse_source;
+
+    #[test]
+    fn test_chain_stops_at_first_error() {
+        let mut later_stage_ran = false;
+        let result = ParserStage
+            .then(
+                |cmm_ast: CmmAst, _: &mut CompilerSession| -> Result<CmmAst, CompilerError> {
+                    later_stage_ran = true;
+                    Ok(cmm_ast)
+                },
+            )
+            .run(
+                "int main(void) { return }",
+                &mut CompilerSession::new(CompileOptions::default()),
+            );
+        assert!(matches!(result, Err(CompilerError::Parser(_))));
+        assert!(!later_stage_ran);
+    }
+
+    #[test]
+    fn test_custom_stage_replaces_built_in_stage() {
+        let constant_parser = |_: &str, _: &mut CompilerSession| -> Result<CmmAst, CompilerError> {
+            parse_source("int main(void) { return 7; }")
+        };
+        let tacky_ast = constant_parser
+            .then(SemanticStage)
+            .then(TackyStage)
+            .run(
+                "not C at all",
+                &mut CompilerSession::new(CompileOptions::default()),
+            )
+            .unwrap();
+        let TackyAst::Program { functions, .. } = tacky_ast;
+        let [TackyFunction::Function { instructions, .. }]: [TackyFunction; 1] =
+            functions.try_into().unwrap();
+        assert_eq!(
+            instructions,
+            vec![TackyInstruction::Return {
+                value: Some(TackyValue::Constant(TackyConstant::Int(7)))
+            }]
+        );
+    }
+}
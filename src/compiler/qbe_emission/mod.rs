@@ -0,0 +1,1059 @@
+use crate::common::symbol::Symbol;
+use crate::common::target::{Architecture, OperatingSystem, Target};
+use crate::compiler::ir_gen::static_accesses::lower_static_accesses;
+use crate::compiler::ir_gen::tacky_ast::{
+    TackyAst, TackyBinaryOperator, TackyConstant, TackyFunction, TackyInstruction,
+    TackyStaticConstant, TackyStaticInit, TackyStaticVariable, TackyType, TackyUnaryOperator,
+    TackyValue,
+};
+use crate::compiler::parser::printer::print_string_literal;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Write};
+
+/// The typical length of an emitted QBE instruction line in bytes, used to pre-size the output.
+const AVERAGE_INSTRUCTION_LENGTH: usize = 24;
+
+/// Emits QBE intermediate language from a TACKY IR.
+///
+/// QBE generates assembly for several architectures from the same IL, so this backend does not
+/// depend on the target. Every C-- `int` and `unsigned int` is a QBE word (`w`), every `long` a QBE
+/// long (`l`), and the signedness selects the operation, e.g. `udiv` instead of `div`. QBE has no
+/// byte temporaries, so a `char` or `unsigned char` is a word holding its sign or zero extended
+/// value. A `double` is a QBE double (`d`). A string literal is a data definition, whose address is
+/// a long. A variable whose address is taken also lives in a stack slot, see `write_function`.
+///
+/// # Arguments
+///
+/// * `tacky_ast`: A reference to the `TackyAst` to be converted into QBE IL.
+///
+/// # Returns
+///
+/// A `String` containing the QBE IL.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::symbol::Symbol;
+/// # use cmm::compiler::ir_gen::tacky_ast::{TackyAst, TackyConstant, TackyFunction, TackyInstruction, TackyValue};
+/// # use std::collections::BTreeMap;
+/// # use cmm::compiler::qbe_emission::emit_qbe;
+/// let tacky_ast = TackyAst::Program {
+///     functions: vec![TackyFunction::Function {
+///         identifier: Symbol::from("main"),
+///         global: true,
+///         parameters: vec![],
+///         instructions: vec![TackyInstruction::Return { value: Some(TackyValue::Constant(TackyConstant::Int(2))) }],
+///         variable_types: BTreeMap::new(),
+///     }],
+///     static_constants: Vec::new(),
+///     static_variables: Vec::new(),
+/// };
+/// assert_eq!(emit_qbe(&tacky_ast), "export function w $main() {\n@start\n\tret 2\n}\n");
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(name = "emit_qbe", skip_all))]
+pub fn emit_qbe(tacky_ast: &TackyAst) -> String {
+    let TackyAst::Program { functions, .. } = tacky_ast;
+    let line_count: usize = functions
+        .iter()
+        .map(|TackyFunction::Function { instructions, .. }| instructions.len() + 3)
+        .sum();
+    let mut program_code = String::with_capacity((line_count + 1) * AVERAGE_INSTRUCTION_LENGTH);
+    write_qbe(&mut program_code, tacky_ast).expect("Writing to a String cannot fail");
+    program_code
+}
+
+/// Writes the QBE IL of a TACKY IR to a writer.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `tacky_ast`: A reference to the `TackyAst` to be converted into QBE IL.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+pub fn write_qbe<W: Write>(output: &mut W, tacky_ast: &TackyAst) -> fmt::Result {
+    // QBE has no memory operands, so the static variables are accessed through their addresses.
+    match &lower_static_accesses(tacky_ast) {
+        TackyAst::Program {
+            functions,
+            static_constants,
+            static_variables,
+        } => {
+            for function in functions {
+                write_function(output, function)?;
+            }
+            for static_constant in static_constants {
+                write_static_constant(output, static_constant)?;
+            }
+            for static_variable in static_variables {
+                write_static_variable(output, static_variable)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes the data definition of a static constant, which is local to the program.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `static_constant`: A reference to the `TackyStaticConstant` to be emitted.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_static_constant<W: Write>(
+    output: &mut W,
+    static_constant: &TackyStaticConstant,
+) -> fmt::Result {
+    match static_constant {
+        TackyStaticConstant::String { identifier, value } => writeln!(
+            output,
+            "data ${} = {{ b {}, b 0 }}",
+            identifier,
+            print_string_literal(value)
+        ),
+    }
+}
+
+/// Writes the data definition of a static variable, which is exported if it is global. A variable
+/// defined in another file has no definition.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `static_variable`: A reference to the `TackyStaticVariable` to be emitted.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_static_variable<W: Write>(
+    output: &mut W,
+    static_variable: &TackyStaticVariable,
+) -> fmt::Result {
+    let TackyStaticVariable::Variable {
+        identifier,
+        global,
+        alignment,
+        initial_values,
+    } = static_variable
+    else {
+        return Ok(());
+    };
+    if *global {
+        write!(output, "export ")?;
+    }
+    write!(output, "data ${} = align {} {{ ", identifier, alignment)?;
+    for (index, initial_value) in initial_values.iter().enumerate() {
+        if index > 0 {
+            write!(output, ", ")?;
+        }
+        match initial_value {
+            TackyStaticInit::Value(TackyConstant::Double(value)) => {
+                write!(output, "d d_{:?}", value)?
+            }
+            TackyStaticInit::Value(constant) => write!(
+                output,
+                "{} {}",
+                match constant.constant_type().size() {
+                    1 => "b",
+                    2 => "h",
+                    4 => "w",
+                    _ => "l",
+                },
+                constant.as_i64()
+            )?,
+            TackyStaticInit::Zero(size) => write!(output, "z {}", size)?,
+        }
+    }
+    writeln!(output, " }}")
+}
+
+/// Returns the name of the QBE target generating assembly for the given platform.
+///
+/// # Arguments
+///
+/// * `target`: The platform the assembly is built for.
+///
+/// # Returns
+///
+/// The name passed to `qbe -t`, e.g. `amd64_sysv`.
+pub fn qbe_target_name(target: &Target) -> &'static str {
+    match (target.architecture, target.operating_system) {
+        (Architecture::X86_64, OperatingSystem::Linux) => "amd64_sysv",
+        (Architecture::X86_64, OperatingSystem::MacOs) => "amd64_apple",
+        (Architecture::Aarch64, OperatingSystem::Linux) => "arm64",
+        (Architecture::Aarch64, OperatingSystem::MacOs) => "arm64_apple",
+    }
+}
+
+/// Writes the QBE IL of a single function definition.
+///
+/// The function returns the QBE class of its returned values, or a word if it returns none. QBE
+/// blocks must end in a jump, so a block is started after every jump that is not followed by a
+/// label, and a function whose last block falls through returns 0 like a C `main` does.
+///
+/// QBE temporaries have no address, so every variable whose address is taken gets a stack slot
+/// allocated at the start of the function. The slot is written after every assignment of the
+/// variable, and the variable is read back from its slot after every store through a pointer and
+/// every call, either of which may have changed it.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `function`: A reference to the `TackyFunction` to be emitted.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_function<W: Write>(output: &mut W, function: &TackyFunction) -> fmt::Result {
+    let TackyFunction::Function {
+        identifier,
+        global,
+        parameters,
+        instructions,
+        variable_types,
+    } = function;
+    if *global {
+        write!(output, "export ")?;
+    }
+    // A `void` function returns a word, as does `main` when it falls through.
+    let return_class = instructions
+        .iter()
+        .find_map(|instruction| match instruction {
+            TackyInstruction::Return { value: Some(value) } => {
+                Some(qbe_class(value.value_type(variable_types)))
+            }
+            _ => None,
+        })
+        .unwrap_or('w');
+    write!(output, "function {} ${}(", return_class, identifier)?;
+    let parameters: Vec<TackyValue> = parameters
+        .iter()
+        .cloned()
+        .map(TackyValue::Variable)
+        .collect();
+    write_typed_values(output, &parameters, variable_types)?;
+    writeln!(output, ") {{")?;
+    writeln!(output, "@start")?;
+    let (arrays, address_taken): (BTreeSet<&Symbol>, BTreeSet<&Symbol>) = instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            TackyInstruction::GetAddress { source, .. } if variable_types.contains_key(source) => {
+                Some(source)
+            }
+            _ => None,
+        })
+        .partition(|variable| matches!(variable_types[*variable], TackyType::Aggregate { .. }));
+    // An array only lives in its slot, so it is never stored to or reloaded from it.
+    for array in &arrays {
+        let TackyType::Aggregate { size, alignment } = variable_types[*array] else {
+            unreachable!("Only arrays are partitioned into the arrays")
+        };
+        writeln!(
+            output,
+            "	%{}.addr =l alloc{} {}",
+            array,
+            alignment.clamp(4, 16),
+            size
+        )?;
+    }
+    for variable in &address_taken {
+        writeln!(output, "	%{}.addr =l alloc8 8", variable)?;
+    }
+    for parameter in &parameters {
+        write_slot_store(output, parameter, &address_taken, variable_types)?;
+    }
+    let mut block_count = 0;
+    let mut block_terminated = false;
+    for instruction in instructions {
+        if block_terminated && !matches!(instruction, TackyInstruction::Label(_)) {
+            writeln!(output, "@block.{}", block_count)?;
+            block_count += 1;
+        }
+        match instruction {
+            TackyInstruction::JumpIfZero { condition, target } => {
+                let condition =
+                    write_jump_condition(output, condition, block_count, variable_types)?;
+                writeln!(
+                    output,
+                    "\tjnz {}, @block.{}, @{}",
+                    condition, block_count, target
+                )?;
+                writeln!(output, "@block.{}", block_count)?;
+                block_count += 1;
+            }
+            TackyInstruction::JumpIfNotZero { condition, target } => {
+                let condition =
+                    write_jump_condition(output, condition, block_count, variable_types)?;
+                writeln!(
+                    output,
+                    "\tjnz {}, @{}, @block.{}",
+                    condition, target, block_count
+                )?;
+                writeln!(output, "@block.{}", block_count)?;
+                block_count += 1;
+            }
+            _ => write_instruction(output, instruction, variable_types)?,
+        }
+        if let Some(destination) = instruction_destination(instruction) {
+            write_slot_store(output, destination, &address_taken, variable_types)?;
+        }
+        if matches!(
+            instruction,
+            TackyInstruction::Store { .. } | TackyInstruction::FunCall { .. }
+        ) {
+            for variable in &address_taken {
+                let variable_type = variable_types[*variable];
+                writeln!(
+                    output,
+                    "\t%{} ={} {} %{}.addr",
+                    variable,
+                    qbe_class(variable_type),
+                    load_operation(variable_type),
+                    variable
+                )?;
+            }
+        }
+        block_terminated = matches!(
+            instruction,
+            TackyInstruction::Return { .. } | TackyInstruction::Jump { .. }
+        );
+    }
+    if !block_terminated {
+        match return_class {
+            'd' => writeln!(output, "\tret d_0.0")?,
+            _ => writeln!(output, "\tret 0")?,
+        }
+    }
+    writeln!(output, "}}")
+}
+
+/// Writes the store of a value to its stack slot, if it is a variable whose address is taken.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `value`: The value that was just assigned.
+/// * `address_taken`: The variables of the function whose address is taken.
+/// * `variable_types`: The types of the variables of the function.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_slot_store<W: Write>(
+    output: &mut W,
+    value: &TackyValue,
+    address_taken: &BTreeSet<&Symbol>,
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> fmt::Result {
+    match value {
+        TackyValue::Variable(identifier) if address_taken.contains(identifier) => writeln!(
+            output,
+            "	{} %{}, %{}.addr",
+            store_operation(variable_types[identifier]),
+            identifier,
+            identifier
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the value an instruction assigns to, if any.
+fn instruction_destination(instruction: &TackyInstruction) -> Option<&TackyValue> {
+    match instruction {
+        TackyInstruction::Unary { destination, .. }
+        | TackyInstruction::Binary { destination, .. }
+        | TackyInstruction::Copy { destination, .. }
+        | TackyInstruction::GetAddress { destination, .. }
+        | TackyInstruction::AddPtr { destination, .. }
+        | TackyInstruction::Load { destination, .. }
+        | TackyInstruction::FunCall { destination, .. }
+        | TackyInstruction::SignExtend { destination, .. }
+        | TackyInstruction::ZeroExtend { destination, .. }
+        | TackyInstruction::Truncate { destination, .. }
+        | TackyInstruction::IntToDouble { destination, .. }
+        | TackyInstruction::UIntToDouble { destination, .. }
+        | TackyInstruction::DoubleToInt { destination, .. }
+        | TackyInstruction::DoubleToUInt { destination, .. } => Some(destination),
+        TackyInstruction::Return { .. }
+        | TackyInstruction::Store { .. }
+        | TackyInstruction::Jump { .. }
+        | TackyInstruction::JumpIfZero { .. }
+        | TackyInstruction::JumpIfNotZero { .. }
+        | TackyInstruction::Label(_) => None,
+    }
+}
+
+/// Writes the comparison of a long or double jump condition with zero, since `jnz` only tests words.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `condition`: The condition of the jump.
+/// * `block_count`: The number of the block following the jump, which names the comparison.
+/// * `variable_types`: The types of the variables of the function.
+///
+/// # Returns
+///
+/// The `JumpCondition` for `jnz` to test, or the error of the writer.
+fn write_jump_condition<'a, W: Write>(
+    output: &mut W,
+    condition: &'a TackyValue,
+    block_count: usize,
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> Result<JumpCondition<'a>, fmt::Error> {
+    let (comparison, zero) = match qbe_class(condition.value_type(variable_types)) {
+        'l' => ("cnel", "0"),
+        'd' => ("cned", "d_0"),
+        _ => return Ok(JumpCondition::Word(condition)),
+    };
+    let condition_word = JumpCondition::NonZero(block_count);
+    writeln!(
+        output,
+        "\t{} =w {} {}, {}",
+        condition_word,
+        comparison,
+        QbeValue(condition),
+        zero
+    )?;
+    Ok(condition_word)
+}
+
+/// Writes the QBE IL of a single instruction that does not branch conditionally.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `instruction`: A reference to the `TackyInstruction` to be emitted.
+/// * `variable_types`: The types of the variables of the function.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_instruction<W: Write>(
+    output: &mut W,
+    instruction: &TackyInstruction,
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> fmt::Result {
+    match instruction {
+        TackyInstruction::Return { value: Some(value) } => {
+            writeln!(output, "\tret {}", QbeValue(value))
+        }
+        // Every QBE function returns a word, so a `void` function returns 0.
+        TackyInstruction::Return { value: None } => writeln!(output, "\tret 0"),
+        TackyInstruction::Unary {
+            operator,
+            source,
+            destination,
+        } => {
+            let (operation, operand) = match (operator, source.value_type(variable_types)) {
+                (TackyUnaryOperator::Complement, _) => ("xor", ", -1"),
+                (TackyUnaryOperator::Negate, _) => ("neg", ""),
+                (
+                    TackyUnaryOperator::Not,
+                    TackyType::Long | TackyType::UnsignedLong | TackyType::Pointer,
+                ) => ("ceql", ", 0"),
+                (TackyUnaryOperator::Not, TackyType::Double) => ("ceqd", ", d_0"),
+                (TackyUnaryOperator::Not, _) => ("ceqw", ", 0"),
+            };
+            writeln!(
+                output,
+                "\t{} ={} {} {}{}",
+                QbeValue(destination),
+                qbe_class(destination.value_type(variable_types)),
+                operation,
+                QbeValue(source),
+                operand
+            )
+        }
+        TackyInstruction::Binary {
+            operator,
+            source1,
+            source2,
+            destination,
+        } => writeln!(
+            output,
+            "\t{} ={} {} {}, {}",
+            QbeValue(destination),
+            qbe_class(destination.value_type(variable_types)),
+            binary_operation(operator, source1.value_type(variable_types)),
+            QbeValue(source1),
+            QbeValue(source2)
+        ),
+        // A value converted to `short` or a character type extends its lowest bytes again, e.g.
+        // an `unsigned char` holding 255 becomes a `char` holding -1. A word operation reads the
+        // lower 32 bits of a long, so the other conversions are copies.
+        TackyInstruction::Copy {
+            source,
+            destination,
+        }
+        | TackyInstruction::Truncate {
+            source,
+            destination,
+        } => {
+            let destination_type = destination.value_type(variable_types);
+            let operation = match destination_type {
+                TackyType::Short | TackyType::Char | TackyType::UnsignedChar
+                    if destination_type != source.value_type(variable_types) =>
+                {
+                    extension_operation(destination_type)
+                }
+                _ => "copy",
+            };
+            writeln!(
+                output,
+                "\t{} ={} {} {}",
+                QbeValue(destination),
+                qbe_class(destination_type),
+                operation,
+                QbeValue(source)
+            )
+        }
+        TackyInstruction::SignExtend {
+            source,
+            destination,
+        }
+        | TackyInstruction::ZeroExtend {
+            source,
+            destination,
+        } => writeln!(
+            output,
+            "\t{} ={} {} {}",
+            QbeValue(destination),
+            qbe_class(destination.value_type(variable_types)),
+            extension_operation(source.value_type(variable_types)),
+            QbeValue(source)
+        ),
+        TackyInstruction::IntToDouble {
+            source,
+            destination,
+        }
+        | TackyInstruction::UIntToDouble {
+            source,
+            destination,
+        }
+        | TackyInstruction::DoubleToInt {
+            source,
+            destination,
+        }
+        | TackyInstruction::DoubleToUInt {
+            source,
+            destination,
+        } => {
+            let source_type = source.value_type(variable_types);
+            let destination_type = destination.value_type(variable_types);
+            let operation = match (source_type, destination_type.is_signed()) {
+                (TackyType::Double, true) => "dtosi",
+                (TackyType::Double, false) => "dtoui",
+                (TackyType::Long, _) => "sltof",
+                (TackyType::UnsignedLong, _) => "ultof",
+                (TackyType::UnsignedInt, _) => "uwtof",
+                _ => "swtof",
+            };
+            writeln!(
+                output,
+                "\t{} ={} {} {}",
+                QbeValue(destination),
+                qbe_class(destination_type),
+                operation,
+                QbeValue(source)
+            )
+        }
+        TackyInstruction::GetAddress {
+            source,
+            destination,
+        } if variable_types.contains_key(source) => {
+            writeln!(
+                output,
+                "\t{} =l copy %{}.addr",
+                QbeValue(destination),
+                source
+            )
+        }
+        TackyInstruction::GetAddress {
+            source,
+            destination,
+        } => writeln!(output, "\t{} =l copy ${}", QbeValue(destination), source),
+        // The index is a long, scaled into a byte offset before it is added to the address.
+        TackyInstruction::AddPtr {
+            pointer,
+            index,
+            scale,
+            destination,
+        } => {
+            writeln!(
+                output,
+                "\t{}.offset =l mul {}, {}",
+                QbeValue(destination),
+                QbeValue(index),
+                scale
+            )?;
+            writeln!(
+                output,
+                "\t{} =l add {}, {}.offset",
+                QbeValue(destination),
+                QbeValue(pointer),
+                QbeValue(destination)
+            )
+        }
+        TackyInstruction::Load {
+            source_pointer,
+            destination,
+        } => {
+            let destination_type = destination.value_type(variable_types);
+            writeln!(
+                output,
+                "\t{} ={} {} {}",
+                QbeValue(destination),
+                qbe_class(destination_type),
+                load_operation(destination_type),
+                QbeValue(source_pointer)
+            )
+        }
+        TackyInstruction::Store {
+            source,
+            destination_pointer,
+        } => writeln!(
+            output,
+            "\t{} {}, {}",
+            store_operation(source.value_type(variable_types)),
+            QbeValue(source),
+            QbeValue(destination_pointer)
+        ),
+        TackyInstruction::FunCall {
+            identifier,
+            arguments,
+            named_arguments,
+            destination,
+        } => {
+            write!(
+                output,
+                "\t{} ={} call ${}(",
+                QbeValue(destination),
+                qbe_class(destination.value_type(variable_types)),
+                identifier
+            )?;
+            match named_arguments {
+                // QBE marks the start of the further arguments of a variadic call with `...`.
+                Some(count) => {
+                    let (named, further) = arguments.split_at(*count);
+                    write_typed_values(output, named, variable_types)?;
+                    write!(output, "{}...", if named.is_empty() { "" } else { ", " })?;
+                    if !further.is_empty() {
+                        write!(output, ", ")?;
+                        write_typed_values(output, further, variable_types)?;
+                    }
+                }
+                None => write_typed_values(output, arguments, variable_types)?,
+            }
+            writeln!(output, ")")
+        }
+        TackyInstruction::Jump { target } => writeln!(output, "\tjmp @{}", target),
+        TackyInstruction::Label(label) => writeln!(output, "@{}", label),
+        TackyInstruction::JumpIfZero { .. } | TackyInstruction::JumpIfNotZero { .. } => {
+            unreachable!("Conditional jumps start a new block and are emitted by the function")
+        }
+    }
+}
+
+/// Writes the parameters of a function or the arguments of a call, each preceded by its QBE class
+/// and separated by commas, e.g. `w %a.0, l 2`.
+///
+/// # Arguments
+///
+/// * `output`: The writer to write the QBE IL to.
+/// * `values`: The parameters or the arguments, in order.
+/// * `variable_types`: The types of the variables of the function.
+///
+/// # Returns
+///
+/// A `fmt::Result` with the error of the writer, if any.
+fn write_typed_values<W: Write>(
+    output: &mut W,
+    values: &[TackyValue],
+    variable_types: &BTreeMap<Symbol, TackyType>,
+) -> fmt::Result {
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            write!(output, ", ")?;
+        }
+        write!(
+            output,
+            "{} {}",
+            qbe_class(value.value_type(variable_types)),
+            QbeValue(value)
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the QBE class of the values of a type, `w` for words, `l` for longs and addresses, and
+/// `d` for doubles.
+fn qbe_class(value_type: TackyType) -> char {
+    match value_type {
+        TackyType::Int
+        | TackyType::UnsignedInt
+        | TackyType::Short
+        | TackyType::Char
+        | TackyType::UnsignedChar => 'w',
+        TackyType::Long
+        | TackyType::UnsignedLong
+        | TackyType::Pointer
+        | TackyType::Aggregate { .. } => 'l',
+        TackyType::Double => 'd',
+    }
+}
+
+/// Returns the QBE operation extending the lower bits holding a value of a type to the whole
+/// temporary, e.g. `extsb` for a `char`, which sign extends its lowest byte.
+fn extension_operation(value_type: TackyType) -> &'static str {
+    match value_type {
+        TackyType::Short => "extsh",
+        TackyType::Char => "extsb",
+        TackyType::UnsignedChar => "extub",
+        TackyType::Int => "extsw",
+        TackyType::UnsignedInt => "extuw",
+        TackyType::Long
+        | TackyType::UnsignedLong
+        | TackyType::Double
+        | TackyType::Pointer
+        | TackyType::Aggregate { .. } => "copy",
+    }
+}
+
+/// Returns the QBE operation loading a value of a type from memory, e.g. `loadsb` for a `char`,
+/// which sign extends the loaded byte.
+fn load_operation(value_type: TackyType) -> &'static str {
+    match value_type {
+        TackyType::Short => "loadsh",
+        TackyType::Char => "loadsb",
+        TackyType::UnsignedChar => "loadub",
+        TackyType::Int | TackyType::UnsignedInt => "loadw",
+        TackyType::Long
+        | TackyType::UnsignedLong
+        | TackyType::Pointer
+        | TackyType::Aggregate { .. } => "loadl",
+        TackyType::Double => "loadd",
+    }
+}
+
+/// Returns the QBE operation storing a value of a type to memory, e.g. `storeb` for a `char`.
+fn store_operation(value_type: TackyType) -> &'static str {
+    match value_type {
+        TackyType::Short => "storeh",
+        TackyType::Char | TackyType::UnsignedChar => "storeb",
+        TackyType::Int | TackyType::UnsignedInt => "storew",
+        TackyType::Long
+        | TackyType::UnsignedLong
+        | TackyType::Pointer
+        | TackyType::Aggregate { .. } => "storel",
+        TackyType::Double => "stored",
+    }
+}
+
+/// Returns the QBE operation of a binary operator on operands of the given type. Comparisons name
+/// the class of their operands, the other operations take it from their result.
+fn binary_operation(operator: &TackyBinaryOperator, operand_type: TackyType) -> &'static str {
+    if operand_type == TackyType::Double {
+        match operator {
+            TackyBinaryOperator::Equal => return "ceqd",
+            TackyBinaryOperator::NotEqual => return "cned",
+            TackyBinaryOperator::LessThan => return "cltd",
+            TackyBinaryOperator::GreaterThan => return "cgtd",
+            TackyBinaryOperator::LessThanEqual => return "cled",
+            TackyBinaryOperator::GreaterThanEqual => return "cged",
+            _ => {}
+        }
+    }
+    if matches!(operand_type, TackyType::Long | TackyType::UnsignedLong) {
+        match (operator, operand_type.is_signed()) {
+            (TackyBinaryOperator::Equal, _) => return "ceql",
+            (TackyBinaryOperator::NotEqual, _) => return "cnel",
+            (TackyBinaryOperator::LessThan, true) => return "csltl",
+            (TackyBinaryOperator::LessThan, false) => return "cultl",
+            (TackyBinaryOperator::GreaterThan, true) => return "csgtl",
+            (TackyBinaryOperator::GreaterThan, false) => return "cugtl",
+            (TackyBinaryOperator::LessThanEqual, true) => return "cslel",
+            (TackyBinaryOperator::LessThanEqual, false) => return "culel",
+            (TackyBinaryOperator::GreaterThanEqual, true) => return "csgel",
+            (TackyBinaryOperator::GreaterThanEqual, false) => return "cugel",
+            _ => {}
+        }
+    }
+    match (operator, operand_type.is_signed()) {
+        (TackyBinaryOperator::Add, _) => "add",
+        (TackyBinaryOperator::Subtract, _) => "sub",
+        (TackyBinaryOperator::Multiply, _) => "mul",
+        (TackyBinaryOperator::Divide, true) => "div",
+        (TackyBinaryOperator::Divide, false) => "udiv",
+        (TackyBinaryOperator::Remainder, true) => "rem",
+        (TackyBinaryOperator::Remainder, false) => "urem",
+        (TackyBinaryOperator::BitwiseAnd, _) => "and",
+        (TackyBinaryOperator::BitwiseOr, _) => "or",
+        (TackyBinaryOperator::BitwiseXor, _) => "xor",
+        (TackyBinaryOperator::LeftShift, _) => "shl",
+        (TackyBinaryOperator::RightShift, true) => "sar",
+        (TackyBinaryOperator::RightShift, false) => "shr",
+        (TackyBinaryOperator::Equal, _) => "ceqw",
+        (TackyBinaryOperator::NotEqual, _) => "cnew",
+        (TackyBinaryOperator::LessThan, true) => "csltw",
+        (TackyBinaryOperator::LessThan, false) => "cultw",
+        (TackyBinaryOperator::GreaterThan, true) => "csgtw",
+        (TackyBinaryOperator::GreaterThan, false) => "cugtw",
+        (TackyBinaryOperator::LessThanEqual, true) => "cslew",
+        (TackyBinaryOperator::LessThanEqual, false) => "culew",
+        (TackyBinaryOperator::GreaterThanEqual, true) => "csgew",
+        (TackyBinaryOperator::GreaterThanEqual, false) => "cugew",
+    }
+}
+
+/// Formats a value as a QBE constant or temporary when displayed.
+struct QbeValue<'a>(&'a TackyValue);
+
+impl fmt::Display for QbeValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            TackyValue::Constant(TackyConstant::Double(value)) => write!(f, "d_{:?}", value),
+            TackyValue::Constant(constant) => write!(f, "{}", constant.as_i64()),
+            TackyValue::Variable(identifier) => write!(f, "%{}", identifier),
+        }
+    }
+}
+
+/// Represents the word a conditional jump tests.
+enum JumpCondition<'a> {
+    /// A word condition, tested as is.
+    Word(&'a TackyValue),
+    /// A `long` condition compared with zero into a temporary, named after the following block.
+    NonZero(usize),
+}
+
+impl fmt::Display for JumpCondition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JumpCondition::Word(condition) => QbeValue(condition).fmt(f),
+            JumpCondition::NonZero(block_count) => write!(f, "%nonzero.{}", block_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::ir_gen::TackyEmitter;
+    use crate::compiler::validate_source;
+
+    fn compile_to_qbe(source_code: &str) -> String {
+        let validated_ast = validate_source(source_code).unwrap();
+        emit_qbe(&TackyEmitter::new().convert_ast(validated_ast).unwrap())
+    }
+
+    #[test]
+    fn test_emit_arithmetic() {
+        assert_eq!(
+            compile_to_qbe("int main(void) { return -(2 * ~3) % 4; }"),
+            "export function w $main() {\n\
+             @start\n\
+             \t%tmp.0 =w xor 3, -1\n\
+             \t%tmp.1 =w mul 2, %tmp.0\n\
+             \t%tmp.2 =w neg %tmp.1\n\
+             \t%tmp.3 =w rem %tmp.2, 4\n\
+             \tret %tmp.3\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_conditional_jumps_start_blocks() {
+        let qbe_code = compile_to_qbe("int main(void) { return 1 && 2; }");
+        assert_eq!(
+            qbe_code,
+            "export function w $main() {\n\
+             @start\n\
+             \tjnz 1, @block.0, @and_false.0\n\
+             @block.0\n\
+             \tjnz 2, @block.1, @and_false.0\n\
+             @block.1\n\
+             \t%tmp.0 =w copy 1\n\
+             \tjmp @and_end.1\n\
+             @and_false.0\n\
+             \t%tmp.0 =w copy 0\n\
+             @and_end.1\n\
+             \tret %tmp.0\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_fallthrough_returns_zero() {
+        let tacky_ast = TackyAst::Program {
+            functions: vec![TackyFunction::Function {
+                identifier: "main".into(),
+                global: true,
+                parameters: vec![],
+                instructions: vec![
+                    TackyInstruction::Return {
+                        value: Some(TackyValue::Constant(TackyConstant::Int(1))),
+                    },
+                    TackyInstruction::Copy {
+                        source: TackyValue::Constant(TackyConstant::Int(2)),
+                        destination: TackyValue::Variable("x".into()),
+                    },
+                ],
+                variable_types: BTreeMap::new(),
+            }],
+            static_constants: Vec::new(),
+            static_variables: Vec::new(),
+        };
+        assert_eq!(
+            emit_qbe(&tacky_ast),
+            "export function w $main() {\n@start\n\tret 1\n@block.0\n\t%x =w copy 2\n\tret 0\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_emit_char_conversions() {
+        assert_eq!(
+            compile_to_qbe("int main(void) { for (unsigned char u = 300; ; u += 'a') return -u; }"),
+            "export function w $main() {\n\
+             @start\n\
+             \t%u.0 =w copy 44\n\
+             @for_start.0\n\
+             \t%tmp.1 =w extub %u.0\n\
+             \t%tmp.2 =w neg %tmp.1\n\
+             \tret %tmp.2\n\
+             @for_continue.1\n\
+             \t%tmp.3 =w extub %u.0\n\
+             \t%tmp.4 =w add %tmp.3, 97\n\
+             \t%u.0 =w extub %tmp.4\n\
+             \tjmp @for_start.0\n\
+             @for_end.2\n\
+             \tret 0\n\
+             }\n"
+        );
+        // Truncating into a `char` sign extends the low byte to keep the word canonical.
+        let qbe_code = compile_to_qbe("int main(void) { for (char c = 0; ; c -= 56) return c; }");
+        assert!(qbe_code.contains("\t%c.0 =w extsb %tmp.3\n"));
+    }
+
+    #[test]
+    fn test_emit_string_literals() {
+        let qbe_code = compile_to_qbe("int main(void) { if (\"a\\\"b\" && !\"\") return 1; }");
+        // Addresses are longs, which `jnz` cannot test directly.
+        assert!(qbe_code.contains("\t%tmp.1 =l copy $string.0\n\t%nonzero.0 =w cnel %tmp.1, 0\n"));
+        assert!(qbe_code.contains("\t%tmp.4 =w ceql %tmp.3, 0\n"));
+        assert!(qbe_code.ends_with(
+            "}\ndata $string.0 = { b \"a\\\"b\", b 0 }\ndata $string.2 = { b \"\", b 0 }\n"
+        ));
+    }
+
+    #[test]
+    fn test_emit_doubles() {
+        let qbe_code = compile_to_qbe(
+            "int main(void) { for (double d = 2.5; !d || d / 2 > 1; d = -d) for (unsigned int u = d; u; ) return d + u; }",
+        );
+        assert!(qbe_code.contains("\t%d.0 =d copy d_2.5\n"));
+        assert!(qbe_code.contains("\t%tmp.1 =w ceqd %d.0, d_0\n"));
+        assert!(qbe_code.contains("\t%tmp.2 =d div %d.0, d_2.0\n\t%tmp.3 =w cgtd %tmp.2, d_1.0\n"));
+        assert!(qbe_code.contains("\t%tmp.6 =w dtoui %d.0\n"));
+        assert!(qbe_code.contains("\t%tmp.7 =d uwtof %u.5\n"));
+        assert!(qbe_code.contains("\t%tmp.9 =w dtosi %tmp.8\n"));
+        assert!(qbe_code.contains("\t%tmp.10 =d neg %d.0\n"));
+    }
+
+    #[test]
+    fn test_emit_unsigned_longs() {
+        let qbe_code = compile_to_qbe(
+            "int main(void) { for (unsigned long u = 1e19; u >= 2UL; u >>= 1) return u / 3 + (double) u; }",
+        );
+        assert!(qbe_code.contains("\t%u.0 =l copy -8446744073709551616\n"));
+        assert!(qbe_code.contains("\t%tmp.1 =w cugel %u.0, 2\n"));
+        assert!(qbe_code.contains("\t%tmp.2 =l udiv %u.0, 3\n\t%tmp.3 =d ultof %tmp.2\n"));
+        assert!(qbe_code.contains("\t%u.0 =l shr %u.0, 1\n"));
+        let qbe_code =
+            compile_to_qbe("int main(void) { double d = 1e19; unsigned long u = d; return !u; }");
+        assert!(qbe_code.contains("\t%tmp.2 =l dtoui %d.0\n"));
+        assert!(qbe_code.contains("\t%tmp.3 =w ceql %u.1, 0\n"));
+    }
+
+    #[test]
+    fn test_emit_function_calls() {
+        let qbe_code = compile_to_qbe(
+            "int f(int argc, double d) { if (argc == 1) return f(2, argc); else return 0; }",
+        );
+        assert!(qbe_code.starts_with("export function w $f(w %argc.0, d %d.1) {\n"));
+        assert!(qbe_code.contains("\t%tmp.4 =w call $f(w 2, d %tmp.3)\n"));
+    }
+
+    #[test]
+    fn test_emit_return_types() {
+        let qbe_code = compile_to_qbe(
+            "double half(double x) { return x / 2; } long *p(long *l) { return l; } int main(void) { return half(3.0); }",
+        );
+        assert!(qbe_code.starts_with("export function d $half(d %x.0) {\n"));
+        assert!(qbe_code.contains("export function l $p(l %l.2) {\n"));
+        assert!(qbe_code.contains("\t%tmp.3 =d call $half(d d_3.0)\n"));
+    }
+
+    #[test]
+    fn test_emit_static_functions() {
+        let qbe_code = compile_to_qbe(
+            "static int f(void) { return 1; } extern int main(void) { return f(); }",
+        );
+        assert!(qbe_code.starts_with("function w $f() {\n"));
+        assert!(qbe_code.contains("export function w $main() {\n"));
+    }
+
+    #[test]
+    fn test_emit_static_variables() {
+        let qbe_code = compile_to_qbe(
+            "long a[3] = {1, 2}; double d = 0.5; int main(void) { return a[1] + (int) d; }",
+        );
+        assert!(qbe_code.contains("\t%tmp.0 =l copy $a\n"));
+        assert!(qbe_code.ends_with(
+            "export data $a = align 16 { l 1, l 2, z 8 }\n\
+             export data $d = align 8 { d d_0.5 }\n"
+        ));
+    }
+
+    #[test]
+    fn test_emit_variadic_function_calls() {
+        let qbe_code =
+            compile_to_qbe("int f(long a, ...); int main(void) { return f(1, 2L, (char)3); }");
+        assert!(qbe_code.contains("\t%tmp.0 =w call $f(l 1, ..., l 2, w 3)\n"));
+    }
+
+    #[test]
+    fn test_emit_pointers() {
+        let qbe_code = compile_to_qbe(
+            "int set(char *p) { return *p = 1; } int f(char c) { for (char *p = &c; set(p); ) return *p + c; }",
+        );
+        assert!(qbe_code.contains("\tstoreb 1, %p.0\n"));
+        // The parameter is stored to its slot on entry and read back after the call.
+        assert!(qbe_code.contains(
+            "@start\n\t%c.1.addr =l alloc8 8\n\tstoreb %c.1, %c.1.addr\n\t%tmp.3 =l copy %c.1.addr\n"
+        ));
+        assert!(qbe_code.contains("\t%tmp.4 =w call $set(l %p.2)\n\t%c.1 =w loadsb %c.1.addr\n"));
+        assert!(qbe_code.contains("\t%tmp.5 =w loadsb %p.2\n"));
+    }
+
+    #[test]
+    fn test_emit_arrays() {
+        let qbe_code = compile_to_qbe("int f(int i) { for (long a[3]; ; ) return a[i] = 2; }");
+        // The array only lives in its slot, and the index is scaled by the size of a `long`.
+        assert!(qbe_code.contains("@start\n\t%a.1.addr =l alloc16 24\n@for_start.0\n"));
+        assert!(qbe_code.contains(
+            "\t%tmp.4.offset =l mul %tmp.3, 8\n\t%tmp.4 =l add %tmp.2, %tmp.4.offset\n\tstorel 2, %tmp.4\n"
+        ));
+        assert!(!qbe_code.contains("loadl %a.1.addr"));
+    }
+
+    #[test]
+    fn test_qbe_target_names() {
+        assert_eq!(qbe_target_name(&Target::X86_64_LINUX), "amd64_sysv");
+        assert_eq!(qbe_target_name(&Target::X86_64_MACOS), "amd64_apple");
+    }
+}
@@ -0,0 +1,595 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::parser::cmm_ast::{
+    CmmBinaryOperator, CmmExpression, CmmType, CmmUnaryOperator, ExprId, ExpressionArena,
+};
+
+/// Represents the value of a constant expression in its C type.
+///
+/// The values of `char`, `short`, and `_Bool` expressions are promoted to `int`, like the operands
+/// of every operator are, and a pointer is an `unsigned long` address.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArithmeticConstant {
+    /// The value of an `int` expression.
+    Int(i32),
+    /// The value of an `unsigned int` expression.
+    UnsignedInt(u32),
+    /// The value of a `long` expression.
+    Long(i64),
+    /// The value of an `unsigned long` or pointer expression.
+    UnsignedLong(u64),
+    /// The value of a `double` expression.
+    Double(f64),
+}
+
+impl ArithmeticConstant {
+    /// Returns the value converted to a `double`, rounded to the nearest `double` if it has no
+    /// exact representation.
+    pub fn to_double(self) -> f64 {
+        match self {
+            ArithmeticConstant::Int(value) => f64::from(value),
+            ArithmeticConstant::UnsignedInt(value) => f64::from(value),
+            ArithmeticConstant::Long(value) => value as f64,
+            ArithmeticConstant::UnsignedLong(value) => value as f64,
+            ArithmeticConstant::Double(value) => value,
+        }
+    }
+
+    /// Returns the value of an integer constant if it fits an `int`, or `None` if the value is out
+    /// of range or a `double`.
+    pub fn to_int(self) -> Option<i32> {
+        match self {
+            ArithmeticConstant::Int(value) => Some(value),
+            ArithmeticConstant::UnsignedInt(value) => i32::try_from(value).ok(),
+            ArithmeticConstant::Long(value) => i32::try_from(value).ok(),
+            ArithmeticConstant::UnsignedLong(value) => i32::try_from(value).ok(),
+            ArithmeticConstant::Double(_) => None,
+        }
+    }
+
+    /// Returns `true` if the value is zero. Both zeros of a `double` are zero.
+    pub fn is_zero(self) -> bool {
+        match self {
+            ArithmeticConstant::Double(value) => value == 0.0,
+            _ => self.as_i64() == 0,
+        }
+    }
+
+    /// Returns the value of an integer constant as a `long`, or the bits of an `unsigned long`,
+    /// which is how the conversions between the integer types see it. A `double` is truncated.
+    fn as_i64(self) -> i64 {
+        match self {
+            ArithmeticConstant::Int(value) => i64::from(value),
+            ArithmeticConstant::UnsignedInt(value) => i64::from(value),
+            ArithmeticConstant::Long(value) => value,
+            ArithmeticConstant::UnsignedLong(value) => value as i64,
+            ArithmeticConstant::Double(value) => value as i64,
+        }
+    }
+
+    /// Returns the rank of the type of the value in the usual arithmetic conversions, where the
+    /// operand of the lower rank is converted to the type of the other operand.
+    fn rank(self) -> u8 {
+        match self {
+            ArithmeticConstant::Int(_) => 0,
+            ArithmeticConstant::UnsignedInt(_) => 1,
+            ArithmeticConstant::Long(_) => 2,
+            ArithmeticConstant::UnsignedLong(_) => 3,
+            ArithmeticConstant::Double(_) => 4,
+        }
+    }
+
+    /// Converts the value to a type like a C cast does.
+    ///
+    /// Converting to a smaller integer type keeps the low bits of the value, and the result is
+    /// promoted to `int`. A `double` converted to an integer type is truncated towards zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_type`: The type to convert to.
+    ///
+    /// # Returns
+    ///
+    /// The converted value, or `None` if the type is not a scalar type or a `double` is converted
+    /// to a pointer.
+    pub fn convert_to(self, target_type: &CmmType) -> Option<ArithmeticConstant> {
+        let value = match (self, target_type.unqualified()) {
+            (_, CmmType::Double) => return Some(ArithmeticConstant::Double(self.to_double())),
+            (_, CmmType::Bool) => return Some(ArithmeticConstant::Int(i32::from(!self.is_zero()))),
+            (ArithmeticConstant::Double(_), CmmType::Pointer(_)) => return None,
+            (ArithmeticConstant::Double(value), CmmType::UnsignedLong) => {
+                return Some(ArithmeticConstant::UnsignedLong(value as u64));
+            }
+            (value, _) => value.as_i64(),
+        };
+        match target_type.unqualified() {
+            CmmType::Int => Some(ArithmeticConstant::Int(value as i32)),
+            CmmType::UnsignedInt => Some(ArithmeticConstant::UnsignedInt(value as u32)),
+            CmmType::Long => Some(ArithmeticConstant::Long(value)),
+            CmmType::UnsignedLong | CmmType::Pointer(_) => {
+                Some(ArithmeticConstant::UnsignedLong(value as u64))
+            }
+            CmmType::Short => Some(ArithmeticConstant::Int(i32::from(value as i16))),
+            CmmType::Char => Some(ArithmeticConstant::Int(i32::from(value as i8))),
+            CmmType::UnsignedChar => Some(ArithmeticConstant::Int(i32::from(value as u8))),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to the type of another value, which has the same or a higher rank.
+    fn convert_to_type_of(self, other: ArithmeticConstant) -> ArithmeticConstant {
+        match other {
+            ArithmeticConstant::Int(_) => ArithmeticConstant::Int(self.as_i64() as i32),
+            ArithmeticConstant::UnsignedInt(_) => {
+                ArithmeticConstant::UnsignedInt(self.as_i64() as u32)
+            }
+            ArithmeticConstant::Long(_) => ArithmeticConstant::Long(self.as_i64()),
+            ArithmeticConstant::UnsignedLong(_) => {
+                ArithmeticConstant::UnsignedLong(self.as_i64() as u64)
+            }
+            ArithmeticConstant::Double(_) => ArithmeticConstant::Double(self.to_double()),
+        }
+    }
+}
+
+/// Evaluates an integer constant expression while the program is compiled.
+///
+/// A constant expression consists of integer constants, enumerators, casts to integer types, and
+/// the unary and binary operators without side effects. Every operation is evaluated in the type
+/// the program computes it in, so an `int` wraps around at 32 bits, and the unsigned types compare,
+/// divide, and shift as unsigned values.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the expression and its operands.
+/// * `expression`: The `ExprId` of the expression.
+/// * `enumerator_value`: Looks up the value of an enumerator by the `ExprId` and the name of a
+///   variable expression, or returns `None` if the expression refers to a variable.
+///
+/// # Returns
+///
+/// The value of the expression in its type, or `None` if it is not a constant expression, divides
+/// by zero, or shifts by more than the width of its type.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::semantic::constant_expression::{ArithmeticConstant, evaluate_constant};
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::parser::cmm_ast::{CmmAst, CmmBlockItem, CmmFunction, CmmStatement};
+/// let CmmAst::Program { functions, expressions, .. } =
+///     parse_source("int main(void) { return (1 << 4) - ~2u; }").unwrap();
+/// let CmmFunction::Function { body: Some(body), .. } = &functions[0] else {
+///     panic!("Expected a function definition");
+/// };
+/// let [CmmBlockItem::Statement(CmmStatement::Return { expression: Some(expression), .. })] = body.as_slice() else {
+///     panic!("Expected a return statement");
+/// };
+/// assert_eq!(
+///     evaluate_constant(&expressions, *expression, &|_, _| None),
+///     Some(ArithmeticConstant::UnsignedInt(19))
+/// );
+/// ```
+pub fn evaluate_constant(
+    expressions: &ExpressionArena,
+    expression: ExprId,
+    enumerator_value: &dyn Fn(ExprId, &Symbol) -> Option<i32>,
+) -> Option<ArithmeticConstant> {
+    match fold_constant(expressions, expression, enumerator_value, false)? {
+        ArithmeticConstant::Double(_) => None,
+        value => Some(value),
+    }
+}
+
+/// Evaluates an arithmetic constant expression while the program is compiled, such as the
+/// initializer of a file-scope variable.
+///
+/// Besides the integer constant expressions of `evaluate_constant`, an arithmetic constant
+/// expression may contain `double` constants, casts between the arithmetic types, casts of integer
+/// constants to pointers, and the unary `+`, `-`, and `!`, the arithmetic operators except `%`,
+/// the comparisons, and the logical operators on `double` operands. The integer operand of a
+/// `double` operation is converted from its own type.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the expression and its operands.
+/// * `expression`: The `ExprId` of the expression.
+/// * `enumerator_value`: Looks up the value of an enumerator, see `evaluate_constant`.
+///
+/// # Returns
+///
+/// The value of the expression in its type, or `None` if it is not an arithmetic constant
+/// expression.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::semantic::constant_expression::{
+/// #     ArithmeticConstant, evaluate_arithmetic_constant,
+/// # };
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::parser::cmm_ast::{CmmAst, CmmDeclaration, CmmInitializer};
+/// let CmmAst::Program { variables, expressions, .. } =
+///     parse_source("double d = 1 + 2.5 * 2;").unwrap();
+/// let CmmDeclaration::Declaration { initializer: Some(CmmInitializer::Single(value)), .. } =
+///     &variables[0]
+/// else {
+///     panic!("Expected an initialized declaration");
+/// };
+/// assert_eq!(
+///     evaluate_arithmetic_constant(&expressions, *value, &|_, _| None),
+///     Some(ArithmeticConstant::Double(6.0))
+/// );
+/// ```
+pub fn evaluate_arithmetic_constant(
+    expressions: &ExpressionArena,
+    expression: ExprId,
+    enumerator_value: &dyn Fn(ExprId, &Symbol) -> Option<i32>,
+) -> Option<ArithmeticConstant> {
+    fold_constant(expressions, expression, enumerator_value, true)
+}
+
+/// Evaluates a constant expression in the types of its operations, see `evaluate_constant`.
+///
+/// # Arguments
+///
+/// * `expressions`: The arena holding the expression and its operands.
+/// * `expression`: The `ExprId` of the expression.
+/// * `enumerator_value`: Looks up the value of an enumerator, see `evaluate_constant`.
+/// * `allow_doubles`: Whether `double` constants and casts to `double` are constant, which they
+///   are in arithmetic constant expressions but not in integer ones.
+fn fold_constant(
+    expressions: &ExpressionArena,
+    expression: ExprId,
+    enumerator_value: &dyn Fn(ExprId, &Symbol) -> Option<i32>,
+    allow_doubles: bool,
+) -> Option<ArithmeticConstant> {
+    let evaluate =
+        |operand: &ExprId| fold_constant(expressions, *operand, enumerator_value, allow_doubles);
+    match &expressions[expression] {
+        CmmExpression::IntegerConstant { value } => Some(ArithmeticConstant::Int(*value)),
+        CmmExpression::LongConstant { value } => Some(ArithmeticConstant::Long(*value)),
+        CmmExpression::UnsignedIntegerConstant { value } => {
+            Some(ArithmeticConstant::UnsignedInt(*value))
+        }
+        CmmExpression::UnsignedLongConstant { value } => {
+            Some(ArithmeticConstant::UnsignedLong(*value))
+        }
+        CmmExpression::DoubleConstant { value } if allow_doubles => {
+            Some(ArithmeticConstant::Double(*value))
+        }
+        CmmExpression::Variable { identifier } => {
+            enumerator_value(expression, identifier).map(ArithmeticConstant::Int)
+        }
+        CmmExpression::Cast {
+            target_type,
+            expression,
+        } => {
+            // An integer constant expression only casts to the integer types.
+            if !allow_doubles
+                && matches!(
+                    target_type.unqualified(),
+                    CmmType::Double | CmmType::Pointer(_)
+                )
+            {
+                return None;
+            }
+            evaluate(expression)?.convert_to(target_type)
+        }
+        CmmExpression::Unary {
+            operator,
+            expression,
+        } => evaluate_unary(operator, evaluate(expression)?),
+        CmmExpression::Binary {
+            operator,
+            left,
+            right,
+        } => {
+            let left = evaluate(left)?;
+            // The logical operators only evaluate their right operand if it decides the result.
+            match operator {
+                CmmBinaryOperator::And if left.is_zero() => {
+                    return Some(ArithmeticConstant::Int(0));
+                }
+                CmmBinaryOperator::Or if !left.is_zero() => {
+                    return Some(ArithmeticConstant::Int(1));
+                }
+                _ => {}
+            }
+            evaluate_binary(operator, left, evaluate(right)?)
+        }
+        _ => None,
+    }
+}
+
+/// Applies a unary operator to a constant in its type, or returns `None` if the operator has side
+/// effects or does not apply to a `double`.
+fn evaluate_unary(
+    operator: &CmmUnaryOperator,
+    value: ArithmeticConstant,
+) -> Option<ArithmeticConstant> {
+    match (operator, value) {
+        (CmmUnaryOperator::Plus, _) => Some(value),
+        (CmmUnaryOperator::Not, _) => Some(ArithmeticConstant::Int(i32::from(value.is_zero()))),
+        (CmmUnaryOperator::Negate, ArithmeticConstant::Double(value)) => {
+            Some(ArithmeticConstant::Double(-value))
+        }
+        (CmmUnaryOperator::Negate, _) => {
+            Some(ArithmeticConstant::Long(value.as_i64().wrapping_neg()).convert_to_type_of(value))
+        }
+        (CmmUnaryOperator::Complement, ArithmeticConstant::Double(_)) => None,
+        (CmmUnaryOperator::Complement, _) => {
+            Some(ArithmeticConstant::Long(!value.as_i64()).convert_to_type_of(value))
+        }
+        _ => None,
+    }
+}
+
+/// Applies a binary operator to two constants, or returns `None` if it divides by zero, overflows
+/// a division, shifts by more than the width of the left operand, or does not apply to a `double`.
+///
+/// The operands are converted to their common type first, except for the shifts, whose result has
+/// the type of the left operand. The logical operators are only applied here once their left
+/// operand did not decide the result.
+fn evaluate_binary(
+    operator: &CmmBinaryOperator,
+    left: ArithmeticConstant,
+    right: ArithmeticConstant,
+) -> Option<ArithmeticConstant> {
+    let compare = |result: bool| Some(ArithmeticConstant::Int(i32::from(result)));
+    match operator {
+        CmmBinaryOperator::And | CmmBinaryOperator::Or => return compare(!right.is_zero()),
+        CmmBinaryOperator::LeftShift | CmmBinaryOperator::RightShift => {
+            return evaluate_shift(operator, left, right);
+        }
+        _ => {}
+    }
+    let (left, right) = if left.rank() < right.rank() {
+        (left.convert_to_type_of(right), right)
+    } else {
+        (left, right.convert_to_type_of(left))
+    };
+    match (left, right) {
+        (ArithmeticConstant::Double(left), ArithmeticConstant::Double(right)) => match operator {
+            CmmBinaryOperator::Add => Some(ArithmeticConstant::Double(left + right)),
+            CmmBinaryOperator::Subtract => Some(ArithmeticConstant::Double(left - right)),
+            CmmBinaryOperator::Multiply => Some(ArithmeticConstant::Double(left * right)),
+            CmmBinaryOperator::Divide => Some(ArithmeticConstant::Double(left / right)),
+            CmmBinaryOperator::Equal => compare(left == right),
+            CmmBinaryOperator::NotEqual => compare(left != right),
+            CmmBinaryOperator::GreaterThan => compare(left > right),
+            CmmBinaryOperator::LessThan => compare(left < right),
+            CmmBinaryOperator::GreaterThanEqual => compare(left >= right),
+            CmmBinaryOperator::LessThanEqual => compare(left <= right),
+            _ => None,
+        },
+        (ArithmeticConstant::UnsignedLong(left), ArithmeticConstant::UnsignedLong(right)) => {
+            let result = match operator {
+                CmmBinaryOperator::Add => left.wrapping_add(right),
+                CmmBinaryOperator::Subtract => left.wrapping_sub(right),
+                CmmBinaryOperator::Multiply => left.wrapping_mul(right),
+                CmmBinaryOperator::Divide => left.checked_div(right)?,
+                CmmBinaryOperator::Remainder => left.checked_rem(right)?,
+                CmmBinaryOperator::BitwiseAnd => left & right,
+                CmmBinaryOperator::BitwiseOr => left | right,
+                CmmBinaryOperator::BitwiseXor => left ^ right,
+                CmmBinaryOperator::Equal => return compare(left == right),
+                CmmBinaryOperator::NotEqual => return compare(left != right),
+                CmmBinaryOperator::GreaterThan => return compare(left > right),
+                CmmBinaryOperator::LessThan => return compare(left < right),
+                CmmBinaryOperator::GreaterThanEqual => return compare(left >= right),
+                CmmBinaryOperator::LessThanEqual => return compare(left <= right),
+                _ => return None,
+            };
+            Some(ArithmeticConstant::UnsignedLong(result))
+        }
+        // The values of `int`, `unsigned int`, and `long` are exact in a `long`, so the result is
+        // computed there and wraps around once it is converted back to their type.
+        (left_value, right_value) => {
+            let (left, right) = (left_value.as_i64(), right_value.as_i64());
+            let result = match operator {
+                CmmBinaryOperator::Add => left.wrapping_add(right),
+                CmmBinaryOperator::Subtract => left.wrapping_sub(right),
+                CmmBinaryOperator::Multiply => left.wrapping_mul(right),
+                CmmBinaryOperator::Divide | CmmBinaryOperator::Remainder => {
+                    // Only the smallest value of a signed type divided by -1 overflows.
+                    let overflows = right == -1
+                        && match left_value {
+                            ArithmeticConstant::Int(_) => left == i64::from(i32::MIN),
+                            ArithmeticConstant::Long(_) => left == i64::MIN,
+                            _ => false,
+                        };
+                    if right == 0 || overflows {
+                        return None;
+                    }
+                    match operator {
+                        CmmBinaryOperator::Divide => left / right,
+                        _ => left % right,
+                    }
+                }
+                CmmBinaryOperator::BitwiseAnd => left & right,
+                CmmBinaryOperator::BitwiseOr => left | right,
+                CmmBinaryOperator::BitwiseXor => left ^ right,
+                CmmBinaryOperator::Equal => return compare(left == right),
+                CmmBinaryOperator::NotEqual => return compare(left != right),
+                CmmBinaryOperator::GreaterThan => return compare(left > right),
+                CmmBinaryOperator::LessThan => return compare(left < right),
+                CmmBinaryOperator::GreaterThanEqual => return compare(left >= right),
+                CmmBinaryOperator::LessThanEqual => return compare(left <= right),
+                _ => return None,
+            };
+            Some(ArithmeticConstant::Long(result).convert_to_type_of(left_value))
+        }
+    }
+}
+
+/// Shifts an integer constant in its own type, or returns `None` if the count is negative or not
+/// less than the width of the type. An unsigned value is shifted right logically, a signed one
+/// arithmetically.
+fn evaluate_shift(
+    operator: &CmmBinaryOperator,
+    value: ArithmeticConstant,
+    count: ArithmeticConstant,
+) -> Option<ArithmeticConstant> {
+    let width = match value {
+        ArithmeticConstant::Int(_) | ArithmeticConstant::UnsignedInt(_) => 32,
+        ArithmeticConstant::Long(_) | ArithmeticConstant::UnsignedLong(_) => 64,
+        ArithmeticConstant::Double(_) => return None,
+    };
+    let count = match count {
+        ArithmeticConstant::Double(_) => return None,
+        ArithmeticConstant::UnsignedLong(count) => u32::try_from(count).ok()?,
+        count => u32::try_from(count.as_i64()).ok()?,
+    };
+    if count >= width {
+        return None;
+    }
+    let result = match (operator, value) {
+        (CmmBinaryOperator::LeftShift, _) => value.as_i64() << count,
+        (_, ArithmeticConstant::UnsignedLong(value)) => (value >> count) as i64,
+        // The other values are exact in a `long`, so an arithmetic shift of an `unsigned int`
+        // shifts in zeros.
+        (_, value) => value.as_i64() >> count,
+    };
+    Some(ArithmeticConstant::Long(result).convert_to_type_of(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::lexer::tokenize;
+    use crate::compiler::parser::Parser;
+    use crate::compiler::parser::cmm_ast::CmmStatement;
+
+    fn evaluate(source_code: &str) -> Option<ArithmeticConstant> {
+        let mut parser = Parser::new(tokenize(source_code).unwrap());
+        let CmmStatement::Return {
+            expression: Some(expression),
+            ..
+        } = parser.parse_statement_or_expression().unwrap()
+        else {
+            panic!("Expected an expression");
+        };
+        // Only the enumerator `A` is declared, with the value 5.
+        let enumerator_value = |_, identifier: &Symbol| (identifier == "A").then_some(5);
+        evaluate_constant(&parser.into_expressions(), expression, &enumerator_value)
+    }
+
+    #[test]
+    fn test_evaluate_constant_expressions() {
+        for (source_code, value) in [
+            ("-3 * (2 + 4) % 5", ArithmeticConstant::Int(-3)),
+            ("A << 2 | 1", ArithmeticConstant::Int(21)),
+            ("~0 == -1 && !(A > 4)", ArithmeticConstant::Int(0)),
+            ("0 && 1 / 0", ArithmeticConstant::Int(0)),
+            ("A || x", ArithmeticConstant::Int(1)),
+            (
+                "(char) 300 + (long) 2147483648",
+                ArithmeticConstant::Long(2147483692),
+            ),
+            (
+                "(unsigned int) -1",
+                ArithmeticConstant::UnsignedInt(4294967295),
+            ),
+            ("(_Bool) 256 + (_Bool) 0", ArithmeticConstant::Int(1)),
+        ] {
+            assert_eq!(evaluate(source_code), Some(value), "{source_code}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_constant_expressions_in_their_types() {
+        for (source_code, value) in [
+            ("2147483647 + 1", ArithmeticConstant::Int(i32::MIN)),
+            ("2147483647 + 1 == 0", ArithmeticConstant::Int(0)),
+            (
+                "4294967295u / 2",
+                ArithmeticConstant::UnsignedInt(2147483647),
+            ),
+            ("4294967295u + 1", ArithmeticConstant::UnsignedInt(0)),
+            ("-1 < 0u", ArithmeticConstant::Int(0)),
+            ("-1 < 0l", ArithmeticConstant::Int(1)),
+            ("4294967295u >> 31", ArithmeticConstant::UnsignedInt(1)),
+            ("-8 >> 1", ArithmeticConstant::Int(-4)),
+            ("-7 % 4u", ArithmeticConstant::UnsignedInt(1)),
+            ("1 << 31", ArithmeticConstant::Int(i32::MIN)),
+            ("1l << 40", ArithmeticConstant::Long(1 << 40)),
+            ("-2u", ArithmeticConstant::UnsignedInt(4294967294)),
+            ("~0u", ArithmeticConstant::UnsignedInt(u32::MAX)),
+            ("(short) 65535", ArithmeticConstant::Int(-1)),
+            ("18446744073709551615ul > 0", ArithmeticConstant::Int(1)),
+            ("18446744073709551615ul > -1", ArithmeticConstant::Int(0)),
+            (
+                "18446744073709551615ul / 2",
+                ArithmeticConstant::UnsignedLong(9223372036854775807),
+            ),
+            (
+                "18446744073709551615ul >> 63",
+                ArithmeticConstant::UnsignedLong(1),
+            ),
+            (
+                "(int) (18446744073709551615ul % 10)",
+                ArithmeticConstant::Int(5),
+            ),
+        ] {
+            assert_eq!(evaluate(source_code), Some(value), "{source_code}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_non_constant_expressions() {
+        for source_code in [
+            "x + 1",
+            "1 / 0",
+            "1 << 64",
+            "1 >> -1",
+            "1.5",
+            "f()",
+            "(double) 1",
+            "(int *) 0",
+            "1 << 40l",
+            "(-2147483647 - 1) / -1",
+        ] {
+            assert_eq!(evaluate(source_code), None, "{source_code}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_arithmetic_constant_expressions() {
+        let evaluate = |source_code: &str| {
+            let mut parser = Parser::new(tokenize(source_code).unwrap());
+            let CmmStatement::Return {
+                expression: Some(expression),
+                ..
+            } = parser.parse_statement_or_expression().unwrap()
+            else {
+                panic!("Expected an expression");
+            };
+            evaluate_arithmetic_constant(&parser.into_expressions(), expression, &|_, _| None)
+        };
+        for (source_code, value) in [
+            ("-1.5 * 2", Some(ArithmeticConstant::Double(-3.0))),
+            ("(int) 2.9 + 1", Some(ArithmeticConstant::Int(3))),
+            ("(double) 1 / 4", Some(ArithmeticConstant::Double(0.25))),
+            ("(char) 1.9 < 1.5", Some(ArithmeticConstant::Int(1))),
+            ("!0.0 && 1", Some(ArithmeticConstant::Int(1))),
+            ("(int *) 0", Some(ArithmeticConstant::UnsignedLong(0))),
+            ("7 % 4", Some(ArithmeticConstant::Int(3))),
+            (
+                "18446744073709551615ul + 0.0",
+                Some(ArithmeticConstant::Double(18446744073709551615.0)),
+            ),
+            (
+                "4294967295u * 1.0",
+                Some(ArithmeticConstant::Double(4294967295.0)),
+            ),
+            (
+                "(unsigned long) 1e19",
+                Some(ArithmeticConstant::UnsignedLong(10000000000000000000)),
+            ),
+            ("2.5 % 2", None),
+            ("x + 1.5", None),
+        ] {
+            assert_eq!(evaluate(source_code), value, "{source_code}");
+        }
+    }
+}
@@ -0,0 +1,665 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::parser::cmm_ast::{
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmEnumDeclaration, CmmEnumerator,
+    CmmExpression, CmmForInit, CmmFunction, CmmInitializer, CmmMember, CmmStatement,
+    CmmStructDeclaration, CmmType, CmmUnaryOperator, ExprId, ExpressionArena,
+};
+use crate::compiler::semantic::ValidatedAst;
+use crate::compiler::semantic::type_checker::TypeAnnotations;
+use rustc_hash::FxHashMap;
+
+/// Makes the implicit conversions of a C-- program explicit, by wrapping the operands they apply
+/// to in `CmmExpression::Cast` nodes.
+///
+/// The pass follows the conversions of C between arithmetic types:
+///
+/// * The operands of the arithmetic, bitwise, and comparison operators are converted to their
+///   common type, see `CmmType::common_type`, and the operands of the shift operators and of the
+///   unary `+`, `-`, and `~` are promoted, see `CmmType::promoted`.
+/// * The value of an assignment, an initializer, a returned expression, and the arguments of a
+///   call are converted to the type of the target, the variable, the function result, and the
+///   parameters.
+///
+/// The types of the expressions come from the type checker, see `check_types`, which also checks
+/// the conversions. Conversions involving pointers are left to the TACKY generator, since they
+/// keep the address, or turn the null pointer constant into a pointer. So are compound
+/// assignments, whose operation happens in the common type before the result is converted back
+/// to the type of the target.
+///
+/// The expressions are copied into a new arena, operands before the expressions that use them, so
+/// the `ExprId`s of the returned AST differ from the ones of the parser. The annotations are moved
+/// to the new `ExprId`s, and the inserted casts are annotated with their types.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: The `CmmAst` to convert.
+/// * `types`: The `TypeAnnotations` of the `CmmAst`.
+///
+/// # Returns
+///
+/// The `ValidatedAst` of the `CmmAst` with explicit conversions and its annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::check_program;
+/// # use cmm::compiler::semantic::conversions::insert_conversions;
+/// let cmm_ast = parse_source("int main(void) { char c = 1; return c + 2L; }").unwrap();
+/// let types = check_program(&cmm_ast).unwrap();
+/// assert_eq!(
+///     insert_conversions(cmm_ast, types).cmm_ast.to_string(),
+///     "int main(void) {\n    char c = (char) 1;\n    return (int) ((long) c + 2L);\n}\n"
+/// );
+/// ```
+pub fn insert_conversions(cmm_ast: CmmAst, types: TypeAnnotations) -> ValidatedAst {
+    let CmmAst::Program {
+        structs,
+        enums,
+        variables,
+        functions,
+        expressions,
+    } = cmm_ast;
+    let mut inserter = ConversionInserter {
+        source: &expressions,
+        types: &types,
+        expressions: ExpressionArena::new(),
+        renumbered: FxHashMap::default(),
+        conversions: Vec::new(),
+        structs: structs
+            .iter()
+            .map(|CmmStructDeclaration::Struct { tag, members, .. }| (tag.clone(), members.clone()))
+            .collect(),
+        return_type: None,
+    };
+    let enums = enums
+        .into_iter()
+        .map(|declaration| inserter.convert_enum(declaration))
+        .collect();
+    let variables = variables
+        .into_iter()
+        .map(|declaration| inserter.convert_declaration(declaration))
+        .collect();
+    let functions = functions
+        .into_iter()
+        .map(|function| inserter.convert_function(function))
+        .collect();
+    let ConversionInserter {
+        expressions: converted_expressions,
+        renumbered,
+        conversions,
+        ..
+    } = inserter;
+    let mut annotations = types;
+    annotations.renumber(&renumbered);
+    for (id, conversion_type) in conversions {
+        annotations.annotate(id, conversion_type);
+    }
+    ValidatedAst {
+        cmm_ast: CmmAst::Program {
+            structs,
+            enums,
+            variables,
+            functions,
+            expressions: converted_expressions,
+        },
+        annotations,
+    }
+}
+
+/// Copies the expressions of a program into a new arena, inserting the implicit conversions.
+struct ConversionInserter<'a> {
+    /// The arena of the parsed program.
+    source: &'a ExpressionArena,
+    /// The types of the expressions of the parsed program.
+    types: &'a TypeAnnotations,
+    /// The arena of the converted program.
+    expressions: ExpressionArena,
+    /// The `ExprId`s of the copies in the converted program, by the `ExprId`s of the parsed
+    /// program.
+    renumbered: FxHashMap<ExprId, ExprId>,
+    /// The inserted casts of the converted program, and their types.
+    conversions: Vec<(ExprId, CmmType)>,
+    /// The members of the structure types of the program, by tag.
+    structs: FxHashMap<Symbol, Vec<CmmMember>>,
+    /// The return type of the function being converted.
+    return_type: Option<CmmType>,
+}
+
+impl ConversionInserter<'_> {
+    fn convert_enum(&mut self, declaration: CmmEnumDeclaration) -> CmmEnumDeclaration {
+        let CmmEnumDeclaration::Enum {
+            tag,
+            enumerators,
+            span,
+        } = declaration;
+        let enumerators = enumerators
+            .into_iter()
+            .map(
+                |CmmEnumerator::Enumerator { identifier, value }| CmmEnumerator::Enumerator {
+                    identifier,
+                    value: value.map(|value| self.convert_expression(value).0),
+                },
+            )
+            .collect();
+        CmmEnumDeclaration::Enum {
+            tag,
+            enumerators,
+            span,
+        }
+    }
+
+    fn convert_function(&mut self, function: CmmFunction) -> CmmFunction {
+        let CmmFunction::Function {
+            identifier,
+            storage_class,
+            return_type,
+            parameters,
+            variadic,
+            body,
+            span,
+        } = function;
+        let body = body.map(|items| {
+            self.return_type = return_type.clone();
+            items
+                .into_iter()
+                .map(|item| match item {
+                    CmmBlockItem::Declaration(declaration) => {
+                        CmmBlockItem::Declaration(self.convert_declaration(declaration))
+                    }
+                    CmmBlockItem::Statement(statement) => {
+                        CmmBlockItem::Statement(self.convert_statement(statement))
+                    }
+                })
+                .collect()
+        });
+        CmmFunction::Function {
+            identifier,
+            storage_class,
+            return_type,
+            parameters,
+            variadic,
+            body,
+            span,
+        }
+    }
+
+    /// Converts the initializer of a declaration to the type of the variable.
+    fn convert_declaration(&mut self, declaration: CmmDeclaration) -> CmmDeclaration {
+        let CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            span,
+        } = declaration;
+        let initializer =
+            initializer.map(|initializer| self.convert_initializer(initializer, &variable_type));
+        CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            span,
+        }
+    }
+
+    /// Converts the scalars of an initializer to the types of the objects they initialize. The
+    /// elements of an array are initialized in order, and so are the members of a structure.
+    fn convert_initializer(
+        &mut self,
+        initializer: CmmInitializer,
+        target_type: &CmmType,
+    ) -> CmmInitializer {
+        match (initializer, target_type.unqualified()) {
+            (CmmInitializer::Single(expression), target_type) => {
+                CmmInitializer::Single(self.convert_expression_to(expression, target_type))
+            }
+            (CmmInitializer::Compound(initializers), CmmType::Array(element_type, _)) => {
+                CmmInitializer::Compound(
+                    initializers
+                        .into_iter()
+                        .map(|initializer| self.convert_initializer(initializer, element_type))
+                        .collect(),
+                )
+            }
+            (CmmInitializer::Compound(initializers), CmmType::Struct(tag)) => {
+                let member_types: Vec<CmmType> = self
+                    .structs
+                    .get(tag)
+                    .into_iter()
+                    .flatten()
+                    .map(|CmmMember::Member { member_type, .. }| member_type.clone())
+                    .collect();
+                CmmInitializer::Compound(
+                    initializers
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, initializer)| match member_types.get(index) {
+                            Some(member_type) => self.convert_initializer(initializer, member_type),
+                            None => self.copy_initializer(initializer),
+                        })
+                        .collect(),
+                )
+            }
+            (initializer, _) => self.copy_initializer(initializer),
+        }
+    }
+
+    /// Copies an initializer whose shape does not match its type, which the type checker
+    /// rejects.
+    fn copy_initializer(&mut self, initializer: CmmInitializer) -> CmmInitializer {
+        match initializer {
+            CmmInitializer::Single(expression) => {
+                CmmInitializer::Single(self.convert_expression(expression).0)
+            }
+            CmmInitializer::Compound(initializers) => CmmInitializer::Compound(
+                initializers
+                    .into_iter()
+                    .map(|initializer| self.copy_initializer(initializer))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn convert_statement(&mut self, statement: CmmStatement) -> CmmStatement {
+        match statement {
+            CmmStatement::Return { expression, span } => {
+                let return_type = self.return_type.clone();
+                let expression = expression.map(|expression| match &return_type {
+                    Some(return_type) => self.convert_expression_to(expression, return_type),
+                    None => self.convert_expression(expression).0,
+                });
+                CmmStatement::Return { expression, span }
+            }
+            CmmStatement::Expression { expression, span } => CmmStatement::Expression {
+                expression: self.convert_expression(expression).0,
+                span,
+            },
+            CmmStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => CmmStatement::If {
+                condition: self.convert_expression(condition).0,
+                then_branch: Box::new(self.convert_statement(*then_branch)),
+                else_branch: else_branch
+                    .map(|else_branch| Box::new(self.convert_statement(*else_branch))),
+                span,
+            },
+            CmmStatement::While {
+                condition,
+                body,
+                span,
+            } => CmmStatement::While {
+                condition: self.convert_expression(condition).0,
+                body: Box::new(self.convert_statement(*body)),
+                span,
+            },
+            CmmStatement::DoWhile {
+                body,
+                condition,
+                span,
+            } => CmmStatement::DoWhile {
+                body: Box::new(self.convert_statement(*body)),
+                condition: self.convert_expression(condition).0,
+                span,
+            },
+            CmmStatement::For {
+                init,
+                condition,
+                post,
+                body,
+                span,
+            } => {
+                let init = match init {
+                    CmmForInit::Declaration(declaration) => {
+                        CmmForInit::Declaration(self.convert_declaration(declaration))
+                    }
+                    CmmForInit::Expression(expression) => CmmForInit::Expression(
+                        expression.map(|expression| self.convert_expression(expression).0),
+                    ),
+                };
+                CmmStatement::For {
+                    init,
+                    condition: condition.map(|condition| self.convert_expression(condition).0),
+                    post: post.map(|post| self.convert_expression(post).0),
+                    body: Box::new(self.convert_statement(*body)),
+                    span,
+                }
+            }
+            CmmStatement::Null { .. }
+            | CmmStatement::Break { .. }
+            | CmmStatement::Continue { .. } => statement,
+        }
+    }
+
+    /// Copies an expression and converts its value to a type, see `convert_value`.
+    fn convert_expression_to(&mut self, expression: ExprId, target_type: &CmmType) -> ExprId {
+        let (expression, expression_type) = self.convert_expression(expression);
+        self.convert_value(expression, expression_type.as_ref(), target_type)
+    }
+
+    /// Wraps an expression of the converted program in a cast to a type, unless both types are
+    /// not arithmetic or the expression already has the type.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The expression in the arena of the converted program.
+    /// * `expression_type`: The type of the expression, or `None` if it is unknown.
+    /// * `target_type`: The type to convert the value to.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId` of the cast, or of the expression itself if no conversion is needed.
+    fn convert_value(
+        &mut self,
+        expression: ExprId,
+        expression_type: Option<&CmmType>,
+        target_type: &CmmType,
+    ) -> ExprId {
+        match expression_type {
+            Some(expression_type)
+                if expression_type.is_arithmetic()
+                    && target_type.is_arithmetic()
+                    && expression_type.unqualified() != target_type.unqualified() =>
+            {
+                let conversion_type = target_type.unqualified().clone();
+                let conversion = self.expressions.alloc_at(
+                    CmmExpression::Cast {
+                        target_type: conversion_type.clone(),
+                        expression,
+                    },
+                    self.expressions.span(expression),
+                );
+                self.conversions.push((conversion, conversion_type));
+                conversion
+            }
+            _ => expression,
+        }
+    }
+
+    /// Copies an expression of the parsed program into the new arena, converting its operands.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The `ExprId` of the expression in the arena of the parsed program.
+    ///
+    /// # Returns
+    ///
+    /// The `ExprId` of the copy, and the type of the expression, or `None` if it is unknown or
+    /// `void`. The type of an array is not decayed to a pointer.
+    fn convert_expression(&mut self, id: ExprId) -> (ExprId, Option<CmmType>) {
+        let expression = match self.source[id].clone() {
+            CmmExpression::FunctionCall {
+                identifier,
+                arguments,
+            } => {
+                let parameter_types = self
+                    .types
+                    .function_type(&identifier)
+                    .map(|function_type| function_type.parameter_types.clone())
+                    .unwrap_or_default();
+                let arguments = arguments
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, argument)| match parameter_types.get(index) {
+                        Some(parameter_type) => {
+                            self.convert_expression_to(argument, parameter_type)
+                        }
+                        None => self.convert_expression(argument).0,
+                    })
+                    .collect();
+                CmmExpression::FunctionCall {
+                    identifier,
+                    arguments,
+                }
+            }
+            CmmExpression::Cast {
+                target_type,
+                expression,
+            } => CmmExpression::Cast {
+                target_type,
+                expression: self.convert_expression(expression).0,
+            },
+            CmmExpression::AddressOf { expression } => CmmExpression::AddressOf {
+                expression: self.convert_expression(expression).0,
+            },
+            CmmExpression::Dereference { expression } => CmmExpression::Dereference {
+                expression: self.convert_expression(expression).0,
+            },
+            CmmExpression::Subscript { array, index } => CmmExpression::Subscript {
+                array: self.convert_expression(array).0,
+                index: self.convert_expression(index).0,
+            },
+            CmmExpression::Member { structure, member } => CmmExpression::Member {
+                structure: self.convert_expression(structure).0,
+                member,
+            },
+            CmmExpression::PointerMember { pointer, member } => CmmExpression::PointerMember {
+                pointer: self.convert_expression(pointer).0,
+                member,
+            },
+            CmmExpression::Unary {
+                operator,
+                expression,
+            } => self.convert_unary(operator, expression),
+            CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            } => self.convert_binary(operator, left, right),
+            CmmExpression::Assignment { target, value } => {
+                let (target, target_type) = self.convert_expression(target);
+                let value = match &target_type {
+                    Some(target_type) => self.convert_expression_to(value, target_type),
+                    None => self.convert_expression(value).0,
+                };
+                CmmExpression::Assignment { target, value }
+            }
+            CmmExpression::CompoundAssignment {
+                operator,
+                target,
+                value,
+            } => CmmExpression::CompoundAssignment {
+                operator,
+                target: self.convert_expression(target).0,
+                value: self.convert_expression(value).0,
+            },
+            expression => expression,
+        };
+        let copy = self.expressions.alloc_at(expression, self.source.span(id));
+        self.renumbered.insert(id, copy);
+        (copy, self.types.expression_type(id).cloned())
+    }
+
+    /// Converts the operand of a unary operator. The operand of `+`, `-`, and `~` is promoted.
+    fn convert_unary(&mut self, operator: CmmUnaryOperator, expression: ExprId) -> CmmExpression {
+        let (expression, expression_type) = self.convert_expression(expression);
+        let expression = match (&operator, expression_type) {
+            (
+                CmmUnaryOperator::Plus | CmmUnaryOperator::Negate | CmmUnaryOperator::Complement,
+                Some(operand_type),
+            ) if operand_type.is_arithmetic() => {
+                self.convert_value(expression, Some(&operand_type), &operand_type.promoted())
+            }
+            _ => expression,
+        };
+        CmmExpression::Unary {
+            operator,
+            expression,
+        }
+    }
+
+    /// Converts the operands of a binary operator.
+    ///
+    /// Arithmetic operands are converted to their common type, except for the operands of the
+    /// shifts, which are promoted separately, and the operands of `&&` and `||`, which are only
+    /// compared with zero.
+    fn convert_binary(
+        &mut self,
+        operator: CmmBinaryOperator,
+        left: ExprId,
+        right: ExprId,
+    ) -> CmmExpression {
+        let (left, left_type) = self.convert_expression(left);
+        let (right, right_type) = self.convert_expression(right);
+        let (left, right) = match (left_type, right_type) {
+            _ if matches!(operator, CmmBinaryOperator::And | CmmBinaryOperator::Or) => {
+                (left, right)
+            }
+            (Some(left_type), Some(right_type))
+                if left_type.is_arithmetic() && right_type.is_arithmetic() =>
+            {
+                if matches!(
+                    operator,
+                    CmmBinaryOperator::LeftShift | CmmBinaryOperator::RightShift
+                ) {
+                    (
+                        self.convert_value(left, Some(&left_type), &left_type.promoted()),
+                        self.convert_value(right, Some(&right_type), &right_type.promoted()),
+                    )
+                } else {
+                    let common_type = left_type.common_type(&right_type);
+                    (
+                        self.convert_value(left, Some(&left_type), &common_type),
+                        self.convert_value(right, Some(&right_type), &common_type),
+                    )
+                }
+            }
+            _ => (left, right),
+        };
+        CmmExpression::Binary {
+            operator,
+            left,
+            right,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parse_source;
+    use crate::compiler::parser::printer::print_expression;
+    use crate::compiler::semantic::check_program;
+
+    fn convert(source_code: &str) -> String {
+        let cmm_ast = parse_source(source_code).unwrap();
+        let types = check_program(&cmm_ast).unwrap();
+        insert_conversions(cmm_ast, types).cmm_ast.to_string()
+    }
+
+    #[test]
+    fn test_annotations_follow_the_converted_expressions() {
+        let cmm_ast = parse_source("int main(void) { char c = 1; return c + 2L; }").unwrap();
+        let types = check_program(&cmm_ast).unwrap();
+        let ValidatedAst {
+            cmm_ast,
+            annotations,
+        } = insert_conversions(cmm_ast, types);
+        let CmmAst::Program { expressions, .. } = &cmm_ast;
+        let typed_expressions: Vec<String> = expressions
+            .iter()
+            .map(|(id, _)| {
+                format!(
+                    "{}: {}",
+                    print_expression(expressions, id),
+                    annotations.expression_type(id).unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            typed_expressions,
+            vec![
+                "1: int",
+                "(char) 1: char",
+                "c: char",
+                "2L: long",
+                "(long) c: long",
+                "(long) c + 2L: long",
+                "(int) ((long) c + 2L): int",
+            ]
+        );
+        let variable_names: Vec<&str> = expressions
+            .iter()
+            .filter_map(|(id, _)| annotations.variables().unique_name(id))
+            .map(Symbol::as_str)
+            .collect();
+        assert_eq!(variable_names, vec!["c.0"]);
+    }
+
+    #[test]
+    fn test_insert_usual_arithmetic_conversions() {
+        assert_eq!(
+            convert(
+                "int main(void) { unsigned u = 1; short s = 2; double d = s; \
+                 return u < -1 && s * 3L > d; }"
+            ),
+            "int main(void) {\n\
+             \x20   unsigned int u = (unsigned int) 1;\n\
+             \x20   short s = (short) 2;\n\
+             \x20   double d = (double) s;\n\
+             \x20   return u < (unsigned int) -1 && (double) ((long) s * 3L) > d;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_promotions() {
+        assert_eq!(
+            convert("int main(void) { char c = 1; long l = 2; return -c + (~c << l); }"),
+            "int main(void) {\n\
+             \x20   char c = (char) 1;\n\
+             \x20   long l = (long) 2;\n\
+             \x20   return -(int) c + (~(int) c << l);\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_conversions_of_calls_and_objects() {
+        assert_eq!(
+            convert(
+                "struct pair { char first; long second; };\n\
+                 int f(long n, double d);\n\
+                 int main(void) {\n\
+                 struct pair p;\n\
+                 int a[2] = {3L, 4};\n\
+                 int *q = a;\n\
+                 p.first = q[1];\n\
+                 p.second += 1;\n\
+                 return f(p.first, *q);\n\
+                 }"
+            ),
+            "struct pair {\n\
+             \x20   char first;\n\
+             \x20   long second;\n\
+             };\n\
+             \n\
+             int f(long n, double d);\n\
+             \n\
+             int main(void) {\n\
+             \x20   struct pair p;\n\
+             \x20   int a[2] = {(int) 3L, 4};\n\
+             \x20   int *q = a;\n\
+             \x20   p.first = (char) q[1];\n\
+             \x20   p.second += 1;\n\
+             \x20   return f((long) p.first, (double) *q);\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn test_pointers_are_not_converted() {
+        assert_eq!(
+            convert("int main(void) { long *p = 0; p = p + 1; return p == 0; }"),
+            "int main(void) {\n\
+             \x20   long *p = 0;\n\
+             \x20   p = p + 1;\n\
+             \x20   return p == 0;\n\
+             }\n"
+        );
+    }
+}
@@ -0,0 +1,732 @@
+use crate::common::diagnostics::Span;
+use crate::common::symbol::Symbol;
+use crate::compiler::error_codes::{self, ErrorCode};
+use crate::compiler::ir_gen::type_table::MAX_OBJECT_SIZE;
+use crate::compiler::parser::cmm_ast::CmmType;
+use crate::compiler::semantic::symbol_table::FunctionType;
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors that the semantic analysis finds in a parsed C-- program.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SemanticError {
+    /// Raised when a variable is used outside the scope of every declaration of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    UndeclaredVariable { identifier: Symbol },
+    /// Raised when a variable is declared twice in the same scope, such as a local variable of a
+    /// function named like one of its parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    DuplicateVariable { identifier: Symbol },
+    /// Raised when two parameters of a function have the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the parameters.
+    DuplicateParameter { identifier: Symbol },
+    /// Raised when a function is defined more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    FunctionRedefinition { identifier: Symbol },
+    /// Raised when a file-scope variable is initialized by more than one of its declarations.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    VariableRedefinition { identifier: Symbol },
+    /// Raised when declarations of a file-scope variable have different types.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    /// * `previous`: The type of the previous declaration.
+    /// * `current`: The type of the conflicting declaration.
+    ConflictingVariableType {
+        identifier: Symbol,
+        previous: CmmType,
+        current: CmmType,
+    },
+    /// Raised when a name is declared in the file scope both as a variable and as a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable and the function.
+    ConflictingKind { identifier: Symbol },
+    /// Raised when a called function is not declared before the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    UndeclaredFunction { identifier: Symbol },
+    /// Raised when the name of a visible variable is called like a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    VariableCalledAsFunction { identifier: Symbol },
+    /// Raised when a `break` statement is not inside a loop.
+    BreakOutsideLoop,
+    /// Raised when a `continue` statement is not inside a loop.
+    ContinueOutsideLoop,
+    /// Raised when an operand of an operator has a type the operator does not accept, such as a
+    /// `double` operand of `%`, or a dereferenced integer.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The printed source code of the operation.
+    /// * `operand_type`: The type of the invalid operand.
+    InvalidOperandType {
+        expression: String,
+        operand_type: CmmType,
+    },
+    /// Raised when the target of an assignment, the operand of an increment or a decrement, or the
+    /// operand of `&` is not an lvalue, so there is no object to modify or to take the address of,
+    /// such as in `1 = x` or `(a + b)++`.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand`: The printed source code of the operand.
+    /// * `expression`: The printed source code of the expression needing the lvalue.
+    InvalidLvalue { operand: String, expression: String },
+    /// Raised when a whole array is assigned, incremented, or decremented, such as in `a = b`,
+    /// since only its elements can be modified.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand`: The printed source code of the array.
+    /// * `expression`: The printed source code of the assignment, increment, or decrement.
+    ArrayAssignment { operand: String, expression: String },
+    /// Raised when an object of a `const` qualified type is assigned, incremented, or
+    /// decremented, such as a `const` variable, an object a pointer to `const` points to, or a
+    /// member of a `const` structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The printed source code of the assignment, increment, or decrement.
+    /// * `target_type`: The type of the object.
+    AssignmentToConst {
+        expression: String,
+        target_type: CmmType,
+    },
+    /// Raised when a pointer is assigned, passed, returned, compared, or subtracted where a pointer
+    /// to another type is expected.
+    IncompatiblePointerTypes,
+    /// Raised when a value other than a pointer or the null pointer constant is converted to a
+    /// pointer.
+    NonPointerUsedAsPointer,
+    /// Raised when a pointer, such as a string literal, is converted to a number or compared with
+    /// one, rather than tested as a condition.
+    PointerUsedAsInteger,
+    /// Raised when a whole structure is used as a value, such as an operand, an assigned value, or
+    /// a parameter, rather than accessing its members.
+    StructUsedAsValue,
+    /// Raised when the value of a call to a `void` function is used, rather than discarded by an
+    /// expression statement.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    VoidValueUsed { identifier: Symbol },
+    /// Raised when a function is called with a different number of arguments than it has
+    /// parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `expected`: The number of parameters.
+    /// * `actual`: The number of arguments.
+    WrongArgumentCount {
+        identifier: Symbol,
+        expected: usize,
+        actual: usize,
+    },
+    /// Raised when a `void` function returns a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    ReturnValueInVoidFunction { identifier: Symbol },
+    /// Raised when a function returning a value has a `return` statement without one.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    MissingReturnValue { identifier: Symbol },
+    /// Raised when an array is initialized with an expression, a structure or an array of
+    /// structures is initialized, or a variable of another type with a braced list.
+    InvalidInitializer,
+    /// Raised when the initializer of a variable with static storage duration is not a constant
+    /// expression, which the compiler must evaluate to place the value in the data of the program.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    NonConstantInitializer { identifier: Symbol },
+    /// Raised when an `extern` declaration inside a function has an initializer, which would
+    /// define a variable of another file.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    ExternInitializer { identifier: Symbol },
+    /// Raised when a braced list has more initializers than the array has elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected`: The number of elements of the array.
+    /// * `actual`: The number of initializers.
+    TooManyInitializers { expected: usize, actual: usize },
+    /// Raised when a structure type is used before its declaration where its size or members must
+    /// be known, or a structure contains itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag of the structure.
+    UndeclaredStruct { tag: Symbol },
+    /// Raised when an array or structure type is larger than the largest object, see
+    /// `type_table::MAX_OBJECT_SIZE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `found`: The type that is too large.
+    TypeTooLarge { found: CmmType },
+    /// Raised when a structure type is declared more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag of the structure.
+    StructRedefinition { tag: Symbol },
+    /// Raised when two members of a structure have the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag of the structure.
+    /// * `member`: The name of the members.
+    DuplicateMember { tag: Symbol, member: Symbol },
+    /// Raised when a structure has no member of the accessed name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag of the structure.
+    /// * `member`: The name of the accessed member.
+    UnknownMember { tag: Symbol, member: Symbol },
+    /// Raised when the operand of `.` is not a structure, or the operand of `->` is not a pointer
+    /// to a structure.
+    MemberOfNonStruct,
+    /// Raised when an enumeration type is declared more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag`: The tag of the enumeration.
+    EnumRedefinition { tag: Symbol },
+    /// Raised when an enumerator is declared more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the enumerator.
+    EnumeratorRedefinition { identifier: Symbol },
+    /// Raised when the value of an enumerator is no integer constant expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the enumerator.
+    InvalidEnumerator { identifier: Symbol },
+    /// Raised when the value of an enumerator does not fit an `int`, either as given or as one more
+    /// than the previous enumerator.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the enumerator.
+    EnumeratorOutOfRange { identifier: Symbol },
+    /// Raised when a function is declared `static` after a declaration gave it external linkage.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    ConflictingLinkage { identifier: Symbol },
+    /// Raised when a file-scope variable is declared `static` after a declaration gave it external
+    /// linkage, or without a storage-class specifier after a `static` declaration.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    /// * `is_static`: Whether the conflicting declaration is the `static` one.
+    ConflictingVariableLinkage { identifier: Symbol, is_static: bool },
+    /// Raised when declarations of a function have different return types.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `previous`: The return type of the previous declaration, or `None` for `void`.
+    /// * `current`: The return type of the conflicting declaration, or `None` for `void`.
+    ConflictingReturnType {
+        identifier: Symbol,
+        previous: Option<CmmType>,
+        current: Option<CmmType>,
+    },
+    /// Raised when declarations of a function have different numbers of parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `previous`: The number of parameters of the previous declaration.
+    /// * `current`: The number of parameters of the conflicting declaration.
+    ConflictingParameterCount {
+        identifier: Symbol,
+        previous: usize,
+        current: usize,
+    },
+    /// Raised when one declaration of a function is variadic and another one is not.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `variadic`: Whether the conflicting declaration is variadic.
+    ConflictingVariadic { identifier: Symbol, variadic: bool },
+    /// Raised when declarations of a function have different types for a parameter. Parameters
+    /// declared as arrays are compared as pointers, and `const` qualifiers of the parameters
+    /// themselves are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `position`: The 1-based position of the parameter.
+    /// * `previous`: The parameter type of the previous declaration.
+    /// * `current`: The parameter type of the conflicting declaration.
+    ConflictingParameterType {
+        identifier: Symbol,
+        position: usize,
+        previous: CmmType,
+        current: CmmType,
+    },
+    /// Raised when `main` is declared with a signature that C does not allow. It must return `int`,
+    /// must not be `static`, and takes either no parameters or an `int` and a `char **`.
+    ///
+    /// # Arguments
+    ///
+    /// * `is_static`: Whether the declaration is `static`.
+    /// * `function_type`: The type of the declaration.
+    InvalidMainSignature {
+        is_static: bool,
+        function_type: FunctionType,
+    },
+    /// Wraps an error with the location of the innermost declaration, statement, or expression it
+    /// was raised for.
+    ///
+    /// # Arguments
+    ///
+    /// * `error`: The error raised by the semantic analysis.
+    /// * `span`: The location of the offending code.
+    Located {
+        error: Box<SemanticError>,
+        span: Span,
+    },
+}
+
+impl SemanticError {
+    /// Returns the stable `ErrorCode` of the error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SemanticError::UndeclaredVariable { .. } => error_codes::UNDECLARED_VARIABLE,
+            SemanticError::DuplicateVariable { .. }
+            | SemanticError::DuplicateParameter { .. }
+            | SemanticError::FunctionRedefinition { .. }
+            | SemanticError::VariableRedefinition { .. } => error_codes::DUPLICATE_DECLARATION,
+            SemanticError::ConflictingVariableType { .. }
+            | SemanticError::ConflictingKind { .. } => error_codes::CONFLICTING_DECLARATIONS,
+            SemanticError::UndeclaredFunction { .. }
+            | SemanticError::VariableCalledAsFunction { .. } => error_codes::INVALID_FUNCTION_CALL,
+            SemanticError::BreakOutsideLoop | SemanticError::ContinueOutsideLoop => {
+                error_codes::JUMP_OUTSIDE_LOOP
+            }
+            SemanticError::InvalidOperandType { .. } => error_codes::INVALID_OPERAND_TYPE,
+            SemanticError::InvalidLvalue { .. } | SemanticError::ArrayAssignment { .. } => {
+                error_codes::INVALID_ASSIGNMENT_TARGET
+            }
+            SemanticError::AssignmentToConst { .. } => error_codes::ASSIGNMENT_TO_CONST,
+            SemanticError::PointerUsedAsInteger
+            | SemanticError::StructUsedAsValue
+            | SemanticError::VoidValueUsed { .. } => error_codes::INVALID_OPERAND_TYPE,
+            SemanticError::IncompatiblePointerTypes | SemanticError::NonPointerUsedAsPointer => {
+                error_codes::INCOMPATIBLE_POINTER_TYPES
+            }
+            SemanticError::WrongArgumentCount { .. } => error_codes::INVALID_FUNCTION_CALL,
+            SemanticError::ReturnValueInVoidFunction { .. }
+            | SemanticError::MissingReturnValue { .. } => error_codes::INVALID_RETURN,
+            SemanticError::InvalidInitializer
+            | SemanticError::NonConstantInitializer { .. }
+            | SemanticError::ExternInitializer { .. }
+            | SemanticError::TooManyInitializers { .. } => error_codes::INVALID_INITIALIZER,
+            SemanticError::UndeclaredStruct { .. } => error_codes::INCOMPLETE_TYPE,
+            SemanticError::TypeTooLarge { .. } => error_codes::TYPE_TOO_LARGE,
+            SemanticError::StructRedefinition { .. }
+            | SemanticError::DuplicateMember { .. }
+            | SemanticError::EnumRedefinition { .. }
+            | SemanticError::EnumeratorRedefinition { .. } => error_codes::DUPLICATE_DECLARATION,
+            SemanticError::UnknownMember { .. } | SemanticError::MemberOfNonStruct => {
+                error_codes::UNKNOWN_MEMBER
+            }
+            SemanticError::InvalidEnumerator { .. }
+            | SemanticError::EnumeratorOutOfRange { .. } => error_codes::INVALID_ENUMERATOR,
+            SemanticError::ConflictingLinkage { .. }
+            | SemanticError::ConflictingVariableLinkage { .. } => error_codes::CONFLICTING_LINKAGE,
+            SemanticError::ConflictingReturnType { .. }
+            | SemanticError::ConflictingParameterCount { .. }
+            | SemanticError::ConflictingVariadic { .. }
+            | SemanticError::ConflictingParameterType { .. } => {
+                error_codes::CONFLICTING_FUNCTION_TYPES
+            }
+            SemanticError::InvalidMainSignature { .. } => error_codes::INVALID_MAIN_SIGNATURE,
+            SemanticError::Located { error, .. } => error.code(),
+        }
+    }
+
+    /// Returns the location of the error, or `None` if it was raised without one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            SemanticError::Located { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its location, e.g. to match on the kind of a located error.
+    pub fn unlocated(self) -> SemanticError {
+        match self {
+            SemanticError::Located { error, .. } => *error,
+            error => error,
+        }
+    }
+
+    /// Locates the error at the code it was raised for, unless it is already located at a nested
+    /// part of that code.
+    ///
+    /// # Arguments
+    ///
+    /// * `span`: The location of the code, or `None` if it was not parsed from source code.
+    ///
+    /// # Returns
+    ///
+    /// The located error, or the error itself if it has a location or `span` is `None`.
+    pub(super) fn located(self, span: Option<Span>) -> SemanticError {
+        match (self, span) {
+            (error @ SemanticError::Located { .. }, _) | (error, None) => error,
+            (error, Some(span)) => SemanticError::Located {
+                error: Box::new(error),
+                span,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SemanticError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SemanticError::UndeclaredVariable { identifier } => write!(
+                f,
+                "Semantic error: Use of undeclared variable '{}'",
+                identifier
+            ),
+            SemanticError::DuplicateVariable { identifier } => write!(
+                f,
+                "Semantic error: Variable '{}' is already declared in this scope",
+                identifier
+            ),
+            SemanticError::DuplicateParameter { identifier } => {
+                write!(f, "Semantic error: Duplicate parameter '{}'", identifier)
+            }
+            SemanticError::FunctionRedefinition { identifier } => write!(
+                f,
+                "Semantic error: Redefinition of function '{}'",
+                identifier
+            ),
+            SemanticError::VariableRedefinition { identifier } => write!(
+                f,
+                "Semantic error: Redefinition of variable '{}'",
+                identifier
+            ),
+            SemanticError::ConflictingVariableType {
+                identifier,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Semantic error: Variable '{}' is declared as '{}', but a previous declaration has '{}'",
+                identifier, current, previous
+            ),
+            SemanticError::ConflictingKind { identifier } => write!(
+                f,
+                "Semantic error: '{}' is declared both as a variable and as a function",
+                identifier
+            ),
+            SemanticError::UndeclaredFunction { identifier } => write!(
+                f,
+                "Semantic error: Call of undeclared function '{}'",
+                identifier
+            ),
+            SemanticError::VariableCalledAsFunction { identifier } => write!(
+                f,
+                "Semantic error: The variable '{}' is not a function and cannot be called",
+                identifier
+            ),
+            SemanticError::BreakOutsideLoop => {
+                write!(f, "Semantic error: 'break' statement not in a loop")
+            }
+            SemanticError::ContinueOutsideLoop => {
+                write!(f, "Semantic error: 'continue' statement not in a loop")
+            }
+            SemanticError::InvalidOperandType {
+                expression,
+                operand_type,
+            } => write!(
+                f,
+                "Semantic error: Invalid operand of type '{}' in '{}'",
+                operand_type, expression
+            ),
+            SemanticError::InvalidLvalue {
+                operand,
+                expression,
+            } => write!(
+                f,
+                "Semantic error: '{}' in '{}' is not an lvalue",
+                operand, expression
+            ),
+            SemanticError::ArrayAssignment {
+                operand,
+                expression,
+            } => write!(
+                f,
+                "Semantic error: The array '{}' in '{}' cannot be modified, only its elements can",
+                operand, expression
+            ),
+            SemanticError::AssignmentToConst {
+                expression,
+                target_type,
+            } => write!(
+                f,
+                "Semantic error: An object of type '{}' cannot be assigned, incremented, or decremented in '{}'",
+                target_type, expression
+            ),
+            SemanticError::IncompatiblePointerTypes => write!(
+                f,
+                "Semantic error: Pointers to different types cannot be assigned or compared without a cast"
+            ),
+            SemanticError::NonPointerUsedAsPointer => write!(
+                f,
+                "Semantic error: Only a pointer or the null pointer constant 0 can be converted to a pointer"
+            ),
+            SemanticError::PointerUsedAsInteger => write!(
+                f,
+                "Semantic error: A pointer, such as a string literal, cannot be used as an integer"
+            ),
+            SemanticError::StructUsedAsValue => write!(
+                f,
+                "Semantic error: A structure cannot be used as a value, only its members can"
+            ),
+            SemanticError::VoidValueUsed { identifier } => write!(
+                f,
+                "Semantic error: The function '{}' returns void, so its call has no value",
+                identifier
+            ),
+            SemanticError::WrongArgumentCount {
+                identifier,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Semantic error: Function '{}' takes {} argument{}, but {} {} given",
+                identifier,
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                actual,
+                if *actual == 1 { "was" } else { "were" }
+            ),
+            SemanticError::ReturnValueInVoidFunction { identifier } => write!(
+                f,
+                "Semantic error: The void function '{}' cannot return a value",
+                identifier
+            ),
+            SemanticError::MissingReturnValue { identifier } => write!(
+                f,
+                "Semantic error: The function '{}' must return a value",
+                identifier
+            ),
+            SemanticError::InvalidInitializer => write!(
+                f,
+                "Semantic error: An array must be initialized with a braced list, a structure cannot be initialized, and other variables are initialized with an expression"
+            ),
+            SemanticError::NonConstantInitializer { identifier } => write!(
+                f,
+                "Semantic error: The initializer of the static variable '{}' is not a constant",
+                identifier
+            ),
+            SemanticError::ExternInitializer { identifier } => write!(
+                f,
+                "Semantic error: The extern declaration of '{}' inside a function cannot have an initializer",
+                identifier
+            ),
+            SemanticError::TooManyInitializers { expected, actual } => write!(
+                f,
+                "Semantic error: An array of {} elements is initialized with {} elements",
+                expected, actual
+            ),
+            SemanticError::UndeclaredStruct { tag } => {
+                write!(f, "Semantic error: Use of incomplete type 'struct {}'", tag)
+            }
+            SemanticError::TypeTooLarge { found } => write!(
+                f,
+                "Semantic error: The type '{}' is larger than the largest object of {} bytes",
+                found, MAX_OBJECT_SIZE
+            ),
+            SemanticError::StructRedefinition { tag } => {
+                write!(f, "Semantic error: Redefinition of 'struct {}'", tag)
+            }
+            SemanticError::DuplicateMember { tag, member } => write!(
+                f,
+                "Semantic error: Duplicate member '{}' in 'struct {}'",
+                member, tag
+            ),
+            SemanticError::UnknownMember { tag, member } => write!(
+                f,
+                "Semantic error: 'struct {}' has no member named '{}'",
+                tag, member
+            ),
+            SemanticError::MemberOfNonStruct => write!(
+                f,
+                "Semantic error: Only a structure has members for '.', and only a pointer to a structure for '->'"
+            ),
+            SemanticError::EnumRedefinition { tag } => {
+                write!(f, "Semantic error: Redefinition of 'enum {}'", tag)
+            }
+            SemanticError::EnumeratorRedefinition { identifier } => write!(
+                f,
+                "Semantic error: Redefinition of enumerator '{}'",
+                identifier
+            ),
+            SemanticError::InvalidEnumerator { identifier } => write!(
+                f,
+                "Semantic error: The value of enumerator '{}' is not an integer constant expression",
+                identifier
+            ),
+            SemanticError::EnumeratorOutOfRange { identifier } => write!(
+                f,
+                "Semantic error: The value of enumerator '{}' does not fit an int",
+                identifier
+            ),
+            SemanticError::ConflictingLinkage { identifier } => write!(
+                f,
+                "Semantic error: Static declaration of function '{}' follows a non-static declaration",
+                identifier
+            ),
+            SemanticError::ConflictingVariableLinkage {
+                identifier,
+                is_static: true,
+            } => write!(
+                f,
+                "Semantic error: Static declaration of variable '{}' follows a non-static declaration",
+                identifier
+            ),
+            SemanticError::ConflictingVariableLinkage {
+                identifier,
+                is_static: false,
+            } => write!(
+                f,
+                "Semantic error: Non-static declaration of variable '{}' follows a static declaration",
+                identifier
+            ),
+            SemanticError::ConflictingReturnType {
+                identifier,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Semantic error: Function '{}' is declared to return '{}', but a previous declaration returns '{}'",
+                identifier,
+                ReturnType(current),
+                ReturnType(previous)
+            ),
+            SemanticError::ConflictingParameterCount {
+                identifier,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Semantic error: Function '{}' is declared with {} parameter(s), but a previous declaration has {}",
+                identifier, current, previous
+            ),
+            SemanticError::ConflictingVariadic {
+                identifier,
+                variadic,
+            } => write!(
+                f,
+                "Semantic error: Function '{}' is declared {} '...', but a previous declaration is {}",
+                identifier,
+                if *variadic { "with" } else { "without" },
+                if *variadic {
+                    "not variadic"
+                } else {
+                    "variadic"
+                }
+            ),
+            SemanticError::ConflictingParameterType {
+                identifier,
+                position,
+                previous,
+                current,
+            } => write!(
+                f,
+                "Semantic error: Parameter {} of function '{}' is declared as '{}', but a previous declaration has '{}'",
+                position, identifier, current, previous
+            ),
+            SemanticError::InvalidMainSignature {
+                is_static,
+                function_type,
+            } => {
+                write!(
+                    f,
+                    "Semantic error: 'main' must be declared as 'int main(void)' or 'int main(int argc, char **argv)', not '{}{} main(",
+                    if *is_static { "static " } else { "" },
+                    ReturnType(&function_type.return_type)
+                )?;
+                let parameter_types = &function_type.parameter_types;
+                if parameter_types.is_empty() && !function_type.variadic {
+                    write!(f, "void")?;
+                }
+                for (index, parameter_type) in parameter_types.iter().enumerate() {
+                    let separator = if index == 0 { "" } else { ", " };
+                    write!(f, "{}{}", separator, parameter_type)?;
+                }
+                if function_type.variadic {
+                    let separator = if parameter_types.is_empty() { "" } else { ", " };
+                    write!(f, "{}...", separator)?;
+                }
+                write!(f, ")'")
+            }
+            SemanticError::Located { error, span } => write!(
+                f,
+                "{} at line {}, column {}",
+                error, span.start_line, span.start_column
+            ),
+        }
+    }
+}
+
+/// Displays the return type of a function, which is `void` if it returns nothing.
+struct ReturnType<'a>(&'a Option<CmmType>);
+
+impl fmt::Display for ReturnType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(return_type) => write!(f, "{}", return_type),
+            None => write!(f, "void"),
+        }
+    }
+}
+
+impl Error for SemanticError {}
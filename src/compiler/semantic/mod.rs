@@ -0,0 +1,488 @@
+pub mod constant_expression;
+pub mod conversions;
+pub mod errors;
+pub mod resolver;
+pub mod symbol_table;
+pub mod type_checker;
+pub mod warnings;
+
+use crate::common::diagnostics::Span;
+use crate::compiler::parser::cmm_ast::{
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmExpression, CmmFunction,
+    CmmStatement, CmmStorageClass, CmmType, ExprId, ExpressionArena,
+};
+use crate::compiler::parser::printer::print_expression;
+use conversions::insert_conversions;
+use errors::SemanticError;
+use resolver::{VariableResolution, resolve_variables};
+use rustc_hash::FxHashSet;
+use type_checker::{TypeAnnotations, check_types};
+use warnings::SemanticWarning;
+
+/// Represents a C-- program the semantic analysis accepted, together with what the analysis found
+/// out about it, which the TACKY generator builds on.
+#[derive(Debug, PartialEq)]
+pub struct ValidatedAst {
+    /// The checked program.
+    pub cmm_ast: CmmAst,
+    /// The resolved variables, the declared functions, and the types of the expressions of the
+    /// program, by the `ExprId`s of its arena.
+    pub annotations: TypeAnnotations,
+}
+
+/// Resolves the variables and functions of a C-- program and checks its types, see
+/// `resolve_variables` and `check_types`. The symbol table the resolver builds is passed on in the
+/// annotations, so the type checker and the TACKY generator read the same declarations.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to check.
+///
+/// # Returns
+///
+/// A `Result` containing the `TypeAnnotations` of the program, or the first `SemanticError`.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::check_program;
+/// # use cmm::compiler::semantic::errors::SemanticError;
+/// let cmm_ast = parse_source("int main(void) { break; }").unwrap();
+/// let error = check_program(&cmm_ast).unwrap_err();
+/// assert_eq!(error.span().map(|span| (span.start_line, span.start_column)), Some((1, 18)));
+/// assert_eq!(error.unlocated(), SemanticError::BreakOutsideLoop);
+/// ```
+pub fn check_program(cmm_ast: &CmmAst) -> Result<TypeAnnotations, SemanticError> {
+    check_types(cmm_ast, resolve_variables(cmm_ast)?)
+}
+
+/// Checks a C-- program, see `check_program`, and makes its implicit conversions explicit, see
+/// `insert_conversions`. Warnings are not reported.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: The `CmmAst` to validate.
+///
+/// # Returns
+///
+/// A `Result` containing the `ValidatedAst` of the program, or the first `SemanticError`.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::validate;
+/// let cmm_ast = parse_source("int main(void) { long l = 1; return l; }").unwrap();
+/// assert_eq!(
+///     validate(cmm_ast).unwrap().cmm_ast.to_string(),
+///     "int main(void) {\n    long l = (long) 1;\n    return (int) l;\n}\n"
+/// );
+/// ```
+pub fn validate(cmm_ast: CmmAst) -> Result<ValidatedAst, SemanticError> {
+    let annotations = check_program(&cmm_ast)?;
+    Ok(insert_conversions(cmm_ast, annotations))
+}
+
+/// Finds the suspicious constructs of a C-- program that deserve a warning.
+///
+/// Currently detects integer divisions and remainders, including their compound assignments,
+/// whose divisor is the constant zero, and code that can never run, see `find_unreachable_code`.
+/// The checks only look at the literal divisor, so `1 / (1 - 1)` is not reported. A division of
+/// type `double` is not reported either, since dividing a `double` by zero gives an infinity or
+/// NaN instead of failing.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to check.
+/// * `types`: The `TypeAnnotations` of the program, see `check_program`.
+///
+/// # Returns
+///
+/// The warnings of the program, the divisions by zero first, in the order the parser built the
+/// offending expressions.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::Span;
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::{check_program, find_warnings};
+/// # use cmm::compiler::semantic::warnings::SemanticWarning;
+/// let cmm_ast = parse_source("int main(void) { return 2 % 0 + (int) (1.0 / 0); }").unwrap();
+/// let types = check_program(&cmm_ast).unwrap();
+/// assert_eq!(
+///     find_warnings(&cmm_ast, &types),
+///     vec![SemanticWarning::DivisionByZero {
+///         expression: "2 % 0".to_string(),
+///         span: Some(Span { start_line: 1, start_column: 25, end_line: 1, end_column: 30 }),
+///     }]
+/// );
+/// assert_eq!(
+///     find_warnings(&cmm_ast, &types)[0].to_string(),
+///     "Semantic warning: Division by zero in '2 % 0' fails at runtime at line 1, column 25"
+/// );
+/// ```
+pub fn find_warnings(cmm_ast: &CmmAst, types: &TypeAnnotations) -> Vec<SemanticWarning> {
+    let CmmAst::Program { expressions, .. } = cmm_ast;
+    let is_double = |id: &ExprId| types.expression_type(*id) == Some(&CmmType::Double);
+    expressions
+        .iter()
+        .filter(|(id, expression)| match expression {
+            CmmExpression::Binary {
+                operator: CmmBinaryOperator::Divide | CmmBinaryOperator::Remainder,
+                right,
+                ..
+            } => is_zero_constant(&expressions[*right]) && !is_double(id),
+            // The operation has the type of the target, since the divisor is an integer.
+            CmmExpression::CompoundAssignment {
+                operator: CmmBinaryOperator::Divide | CmmBinaryOperator::Remainder,
+                target,
+                value,
+            } => is_zero_constant(&expressions[*value]) && !is_double(target),
+            _ => false,
+        })
+        .map(|(id, _)| SemanticWarning::DivisionByZero {
+            expression: print_expression(expressions, id),
+            span: expressions.span(id),
+        })
+        .chain(find_unreachable_code(cmm_ast))
+        .collect()
+}
+
+/// Finds the function bodies with code after an unconditional jump, a `return`, `break`, or
+/// `continue` statement, which can never run.
+///
+/// Null statements and declarations without an initializer do not count as code. Only the
+/// statements directly in a function body are checked, so a `return` inside the branch of an `if`
+/// makes nothing unreachable.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to check.
+///
+/// # Returns
+///
+/// A warning for every function body with unreachable code, naming the first jump it follows and
+/// located at the first unreachable statement or declaration.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::Span;
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::find_unreachable_code;
+/// # use cmm::compiler::semantic::warnings::SemanticWarning;
+/// let cmm_ast = parse_source("int main(void) { return 1; return 2; }").unwrap();
+/// assert_eq!(
+///     find_unreachable_code(&cmm_ast),
+///     vec![SemanticWarning::UnreachableCode {
+///         jump: "return 1".to_string(),
+///         function: "main".into(),
+///         span: Some(Span { start_line: 1, start_column: 28, end_line: 1, end_column: 37 }),
+///     }]
+/// );
+/// ```
+pub fn find_unreachable_code(cmm_ast: &CmmAst) -> Vec<SemanticWarning> {
+    let CmmAst::Program {
+        functions,
+        expressions,
+        ..
+    } = cmm_ast;
+    let mut warnings = Vec::new();
+    for CmmFunction::Function {
+        identifier, body, ..
+    } in functions
+    {
+        let Some(body) = body else {
+            continue;
+        };
+        let first_jump = body
+            .iter()
+            .enumerate()
+            .find_map(|(position, item)| match item {
+                CmmBlockItem::Statement(statement) => {
+                    print_jump(expressions, statement).map(|jump| (position, jump))
+                }
+                CmmBlockItem::Declaration(_) => None,
+            });
+        if let Some((position, jump)) = first_jump
+            && let Some(unreachable) = body[position + 1..].iter().find(|item| is_code(item))
+        {
+            warnings.push(SemanticWarning::UnreachableCode {
+                jump,
+                function: identifier.clone(),
+                span: block_item_span(unreachable),
+            });
+        }
+    }
+    warnings
+}
+
+/// Returns the printed source code of a `return`, `break`, or `continue` statement, or `None` if
+/// the statement is not an unconditional jump.
+fn print_jump(expressions: &ExpressionArena, statement: &CmmStatement) -> Option<String> {
+    match statement {
+        CmmStatement::Return {
+            expression: Some(expression),
+            ..
+        } => Some(format!(
+            "return {}",
+            print_expression(expressions, *expression)
+        )),
+        CmmStatement::Return {
+            expression: None, ..
+        } => Some("return".to_string()),
+        CmmStatement::Break { .. } => Some("break".to_string()),
+        CmmStatement::Continue { .. } => Some("continue".to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the location of a block item, or `None` if it was not parsed from source code.
+fn block_item_span(item: &CmmBlockItem) -> Option<Span> {
+    match item {
+        CmmBlockItem::Statement(statement) => statement.span(),
+        CmmBlockItem::Declaration(CmmDeclaration::Declaration { span, .. }) => *span,
+    }
+}
+
+/// Returns `true` if the block item does something when it runs.
+fn is_code(item: &CmmBlockItem) -> bool {
+    match item {
+        CmmBlockItem::Statement(statement) => !matches!(statement, CmmStatement::Null { .. }),
+        CmmBlockItem::Declaration(CmmDeclaration::Declaration { initializer, .. }) => {
+            initializer.is_some()
+        }
+    }
+}
+
+/// Finds the local variables of a C-- program whose values are never read, which `-Wunused`
+/// reports.
+///
+/// A variable is read when it appears anywhere but as the target of a plain assignment, so
+/// incrementing a variable, taking its address, or assigning to one of its members reads it.
+/// Parameters and `extern` local variables, which are defined elsewhere, are not reported.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to check.
+/// * `variables`: The `VariableResolution` of the program, see `resolve_variables`.
+///
+/// # Returns
+///
+/// A warning for every unused variable, in declaration order.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::diagnostics::Span;
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::find_unused_variables;
+/// # use cmm::compiler::semantic::resolver::resolve_variables;
+/// # use cmm::compiler::semantic::warnings::SemanticWarning;
+/// let cmm_ast = parse_source("int main(void) { int a = 1; int b; b = 2; return a; }").unwrap();
+/// let variables = resolve_variables(&cmm_ast).unwrap();
+/// assert_eq!(
+///     find_unused_variables(&cmm_ast, &variables),
+///     vec![SemanticWarning::UnusedVariable {
+///         identifier: "b".into(),
+///         function: "main".into(),
+///         span: Some(Span { start_line: 1, start_column: 29, end_line: 1, end_column: 34 }),
+///     }]
+/// );
+/// ```
+pub fn find_unused_variables(
+    cmm_ast: &CmmAst,
+    variables: &VariableResolution,
+) -> Vec<SemanticWarning> {
+    let CmmAst::Program { expressions, .. } = cmm_ast;
+    let assigned: FxHashSet<_> = expressions
+        .iter()
+        .filter_map(|(_, expression)| match expression {
+            CmmExpression::Assignment { target, .. } => Some(*target),
+            _ => None,
+        })
+        .collect();
+    let read: FxHashSet<_> = expressions
+        .iter()
+        .filter(|(id, _)| !assigned.contains(id))
+        .filter_map(|(id, _)| variables.unique_name(id))
+        .collect();
+    variables
+        .locals()
+        .iter()
+        .filter(|local| local.storage_class != Some(CmmStorageClass::Extern))
+        .filter(|local| !read.contains(&local.unique_name))
+        .map(|local| SemanticWarning::UnusedVariable {
+            identifier: local.identifier.clone(),
+            function: local.function.clone(),
+            span: local.span,
+        })
+        .collect()
+}
+
+/// Returns `true` if the expression is a constant zero of any integer type.
+fn is_zero_constant(expression: &CmmExpression) -> bool {
+    matches!(
+        expression,
+        CmmExpression::IntegerConstant { value: 0 }
+            | CmmExpression::LongConstant { value: 0 }
+            | CmmExpression::UnsignedIntegerConstant { value: 0 }
+            | CmmExpression::UnsignedLongConstant { value: 0 }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parse_source;
+
+    /// Returns the warnings of a valid program, see `find_warnings`.
+    fn warnings(source_code: &str) -> Vec<SemanticWarning> {
+        let cmm_ast = parse_source(source_code).unwrap();
+        let types = check_program(&cmm_ast).unwrap();
+        find_warnings(&cmm_ast, &types)
+    }
+
+    /// Returns the span of the columns of the first line of source code.
+    fn first_line(start_column: usize, end_column: usize) -> Option<Span> {
+        Some(Span {
+            start_line: 1,
+            start_column,
+            end_line: 1,
+            end_column,
+        })
+    }
+
+    #[test]
+    fn test_find_division_by_zero() {
+        assert_eq!(
+            warnings("int main(void) { return (1 / 0) + 2 / (0 + 0) % 0; }"),
+            vec![
+                SemanticWarning::DivisionByZero {
+                    expression: "1 / 0".to_string(),
+                    span: first_line(26, 31),
+                },
+                SemanticWarning::DivisionByZero {
+                    expression: "2 / (0 + 0) % 0".to_string(),
+                    span: first_line(35, 50),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_compound_division_by_zero() {
+        assert_eq!(
+            warnings("int main(void) { for (int x = 1; ; x %= 0L) return x /= 0; }"),
+            vec![
+                SemanticWarning::DivisionByZero {
+                    expression: "x %= 0L".to_string(),
+                    span: first_line(36, 43),
+                },
+                SemanticWarning::DivisionByZero {
+                    expression: "x /= 0".to_string(),
+                    span: first_line(52, 58),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_unused_variables() {
+        let cmm_ast = parse_source(
+            "struct s { int m; };
+             int f(int unused_parameter) {
+                 int a = 1; int b; int c = 3; int d; struct s e; int g;
+                 for (int i = 0; ; ) b = c;
+                 b = a;
+                 d++;
+                 e.m = 2;
+                 return *&g;
+             }",
+        )
+        .unwrap();
+        let variables = resolver::resolve_variables(&cmm_ast).unwrap();
+        assert_eq!(
+            find_unused_variables(&cmm_ast, &variables),
+            [("b", 3, 29, 34), ("i", 4, 23, 32)]
+                .map(|(identifier, line, start_column, end_column)| {
+                    SemanticWarning::UnusedVariable {
+                        identifier: identifier.into(),
+                        function: "f".into(),
+                        span: Some(Span {
+                            start_line: line,
+                            start_column,
+                            end_line: line,
+                            end_column,
+                        }),
+                    }
+                })
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn test_find_unreachable_code() {
+        let cmm_ast = parse_source(
+            "int f(int a) { if (a) return 1; a = 2; return a;; int b; }
+             int g(int a) { return a + 1; break; a = 3; }
+             void h(void) { return; int c = 1; }
+             int i(void);",
+        )
+        .unwrap();
+        assert_eq!(
+            find_unreachable_code(&cmm_ast),
+            vec![
+                SemanticWarning::UnreachableCode {
+                    jump: "return a + 1".to_string(),
+                    function: "g".into(),
+                    span: Some(Span {
+                        start_line: 2,
+                        start_column: 43,
+                        end_line: 2,
+                        end_column: 49,
+                    }),
+                },
+                SemanticWarning::UnreachableCode {
+                    jump: "return".to_string(),
+                    function: "h".into(),
+                    span: Some(Span {
+                        start_line: 3,
+                        start_column: 37,
+                        end_line: 3,
+                        end_column: 46,
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nonzero_divisors_are_not_reported() {
+        assert!(warnings("int main(void) { return 0 / 1 + 2 % 3; }").is_empty());
+    }
+
+    #[test]
+    fn test_double_divisions_by_zero_are_not_reported() {
+        assert!(
+            warnings(
+                "int main(void) {
+                     double d = 1.0 / 0;
+                     d /= 0;
+                     d = d / 0L + (0.5 / 0U);
+                     return 0;
+                 }"
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            warnings("int main(void) { double d = 1.0; int i = 1; i /= 0; return i; }"),
+            vec![SemanticWarning::DivisionByZero {
+                expression: "i /= 0".to_string(),
+                span: first_line(45, 51),
+            }]
+        );
+    }
+}
@@ -0,0 +1,1176 @@
+use crate::common::diagnostics::Span;
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::GENERATED_NAME_SEPARATOR;
+use crate::compiler::parser::cmm_ast::{
+    CmmAst, CmmBlockItem, CmmDeclaration, CmmEnumDeclaration, CmmEnumerator, CmmExpression,
+    CmmForInit, CmmFunction, CmmInitializer, CmmParameter, CmmStatement, CmmStorageClass, CmmType,
+    ExprId, ExpressionArena,
+};
+use crate::compiler::semantic::errors::SemanticError;
+use crate::compiler::semantic::symbol_table::{Declaration, FunctionType, Linkage, SymbolTable};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Represents the declarations the variables and functions of a program refer to.
+///
+/// Every declaration of a local variable gets a unique name, `<name>.<counter>` like the names of
+/// the TACKY generator, so variables of different scopes that share a name in the source code get
+/// different names. A variable with linkage, a file-scope variable or an `extern` local variable,
+/// keeps its name, like a function.
+#[derive(Debug, Default, PartialEq)]
+pub struct VariableResolution {
+    /// The symbol table the resolver declared the names in. Once the resolution is done, only the
+    /// file scope is open, which declares the file-scope variables and the functions with their
+    /// types and linkage.
+    symbols: SymbolTable,
+    /// The unique names of the variables the `CmmExpression::Variable` expressions refer to.
+    names: FxHashMap<ExprId, Symbol>,
+    /// The declared types of the variables, by unique name. A parameter declared as an array is a
+    /// pointer to its first element.
+    variable_types: FxHashMap<Symbol, CmmType>,
+    /// The local variables declared in the function bodies, in declaration order.
+    locals: Vec<LocalVariable>,
+    /// The unique names of the parameters of the function definitions, by function name.
+    parameters: FxHashMap<Symbol, Vec<Symbol>>,
+}
+
+/// Represents a local variable declared in the body of a function, but not a parameter.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LocalVariable {
+    /// The name of the variable in the program.
+    pub identifier: Symbol,
+    /// The unique name of the variable, which is the name itself for an `extern` variable.
+    pub unique_name: Symbol,
+    /// The storage-class specifier of the declaration, if any.
+    pub storage_class: Option<CmmStorageClass>,
+    /// The name of the function declaring the variable.
+    pub function: Symbol,
+    /// The location of the declaration, or `None` if it was not parsed from source code.
+    pub span: Option<Span>,
+}
+
+impl VariableResolution {
+    /// Returns the symbol table of the program, whose file scope declares the file-scope variables
+    /// and the functions with their types and linkage.
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    /// Returns the type of a function, or `None` if the program does not declare it.
+    pub fn function_type(&self, identifier: &Symbol) -> Option<&FunctionType> {
+        self.symbols
+            .lookup_in_file_scope(identifier)?
+            .function_type()
+    }
+
+    /// Returns the unique name of the variable a `CmmExpression::Variable` refers to, or `None` if
+    /// the expression is not a variable or refers to an enumerator.
+    pub fn unique_name(&self, id: ExprId) -> Option<&Symbol> {
+        self.names.get(&id)
+    }
+
+    /// Returns the declared type of a variable, or `None` if no variable has the unique name.
+    pub fn variable_type(&self, unique_name: &Symbol) -> Option<&CmmType> {
+        self.variable_types.get(unique_name)
+    }
+
+    /// Returns the local variables declared in the function bodies, in declaration order.
+    pub fn locals(&self) -> &[LocalVariable] {
+        &self.locals
+    }
+
+    /// Returns the unique names of the parameters of a function definition, in order, or `None` if
+    /// the program does not define the function.
+    pub fn parameters(&self, function: &Symbol) -> Option<&[Symbol]> {
+        self.parameters.get(function).map(Vec::as_slice)
+    }
+
+    /// Moves the names of the variable expressions to the `ExprId`s of another arena, see
+    /// `insert_conversions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `renumbered`: The new `ExprId` of every expression, by its old `ExprId`.
+    pub(super) fn renumber(&mut self, renumbered: &FxHashMap<ExprId, ExprId>) {
+        self.names = std::mem::take(&mut self.names)
+            .into_iter()
+            .map(|(id, unique_name)| (renumbered[&id], unique_name))
+            .collect();
+    }
+}
+
+/// Resolves every variable and called function of a C-- program to the declaration it refers to.
+///
+/// The functions are declared in the file scope of the symbol table in program order, so a
+/// function is called after its first declaration. The declarations of a function must agree with
+/// each other, see `check_redeclaration`, a function is defined at most once, and `main` has one
+/// of the signatures C allows, see `check_main_signature`.
+///
+/// The file-scope variables are declared before the functions, so every function sees all of
+/// them. Their declarations must agree on the type and on being `static`, see
+/// `Linkage::of_declaration`, and at most one of them has an initializer. A name of the file scope
+/// is either a variable or a function. An `extern` local variable refers to the file-scope
+/// variable of its name, or to a variable of another file, and cannot be initialized. A variable
+/// refers to its innermost visible declaration. The parameters and the declarations of a function
+/// body share a single scope, and the declaration in the initial clause of a for loop
+/// is only visible inside the loop. A variable is visible from its declarator on, so the
+/// initializer of a declaration already refers to the new variable. An identifier that is not a
+/// visible variable may still name an enumerator, which is an `int` constant.
+///
+/// The values of enumerators are constant expressions, which the type checker evaluates, so they
+/// are not resolved.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to resolve.
+///
+/// # Returns
+///
+/// A `Result` containing the `VariableResolution` of the program, or a `SemanticError` if a
+/// variable or a called function is not declared, a variable is called like a function, a scope or
+/// a parameter list declares a name twice, or the declarations of a function or a file-scope
+/// variable conflict.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::semantic::errors::SemanticError;
+/// # use cmm::compiler::semantic::resolver::resolve_variables;
+/// let cmm_ast = parse_source("int main(void) { int a = 1; return a + b; }").unwrap();
+/// assert_eq!(
+///     resolve_variables(&cmm_ast).map_err(SemanticError::unlocated),
+///     Err(SemanticError::UndeclaredVariable { identifier: "b".into() })
+/// );
+/// ```
+pub fn resolve_variables(cmm_ast: &CmmAst) -> Result<VariableResolution, SemanticError> {
+    let CmmAst::Program {
+        enums,
+        variables,
+        functions,
+        expressions,
+        ..
+    } = cmm_ast;
+    let mut resolver = VariableResolver {
+        expressions,
+        defined_variables: FxHashSet::default(),
+        defined_functions: FxHashSet::default(),
+        enumerators: enums
+            .iter()
+            .flat_map(|CmmEnumDeclaration::Enum { enumerators, .. }| enumerators)
+            .map(|CmmEnumerator::Enumerator { identifier, .. }| identifier.clone())
+            .collect(),
+        counter: 0,
+        function: None,
+        resolution: VariableResolution::default(),
+    };
+    for declaration in variables {
+        let CmmDeclaration::Declaration { span, .. } = declaration;
+        resolver
+            .resolve_file_scope_declaration(declaration)
+            .map_err(|error| error.located(*span))?;
+    }
+    for function in functions {
+        // Errors in the body are located at the offending code, the others at the declarator.
+        let CmmFunction::Function { span, .. } = function;
+        resolver
+            .resolve_function(function)
+            .map_err(|error| error.located(*span))?;
+    }
+    Ok(resolver.resolution)
+}
+
+/// Walks the functions of a program, keeping track of the visible declarations.
+struct VariableResolver<'a> {
+    /// The arena of the program.
+    expressions: &'a ExpressionArena,
+    /// The names of the file-scope variables initialized so far.
+    defined_variables: FxHashSet<&'a Symbol>,
+    /// The names of the functions defined so far.
+    defined_functions: FxHashSet<&'a Symbol>,
+    /// The names of the enumerators of the program.
+    enumerators: FxHashSet<Symbol>,
+    /// A counter for the unique names.
+    counter: usize,
+    /// The name of the function whose body is being resolved.
+    function: Option<Symbol>,
+    /// The resolution built so far, whose symbol table holds the declarations visible at the
+    /// current point of the program.
+    resolution: VariableResolution,
+}
+
+impl<'a> VariableResolver<'a> {
+    fn resolve_function(&mut self, function: &'a CmmFunction) -> Result<(), SemanticError> {
+        let CmmFunction::Function {
+            identifier,
+            storage_class,
+            return_type,
+            parameters,
+            variadic,
+            body,
+            ..
+        } = function;
+        let mut parameter_names = FxHashSet::default();
+        for CmmParameter::Parameter { identifier, .. } in parameters {
+            if !parameter_names.insert(identifier) {
+                return Err(SemanticError::DuplicateParameter {
+                    identifier: identifier.clone(),
+                });
+            }
+        }
+        let function_type = FunctionType {
+            return_type: return_type.clone(),
+            parameter_types: parameters
+                .iter()
+                .map(|CmmParameter::Parameter { parameter_type, .. }| parameter_type.decayed())
+                .collect(),
+            variadic: *variadic,
+        };
+        self.declare_function(identifier, *storage_class, function_type, body.is_some())?;
+        let Some(body) = body else {
+            return Ok(());
+        };
+        self.function = Some(identifier.clone());
+        self.resolution.symbols.push_scope();
+        let parameter_names = parameters
+            .iter()
+            .map(
+                |CmmParameter::Parameter {
+                     identifier,
+                     parameter_type,
+                 }| self.declare_variable(identifier, parameter_type.decayed()),
+            )
+            .collect::<Result<_, _>>()?;
+        self.resolution
+            .parameters
+            .insert(identifier.clone(), parameter_names);
+        for item in body {
+            match item {
+                CmmBlockItem::Declaration(declaration) => self.resolve_declaration(declaration)?,
+                CmmBlockItem::Statement(statement) => self.resolve_statement(statement)?,
+            }
+        }
+        self.resolution.symbols.pop_scope();
+        Ok(())
+    }
+
+    /// Declares a file-scope variable in the file scope, after checking it against the previous
+    /// declarations of its name, and resolves its initializer.
+    ///
+    /// # Arguments
+    ///
+    /// * `declaration`: The declaration of the variable.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the name is declared as a function, as a variable
+    /// of another type, or as a variable of another linkage, the variable is initialized a second
+    /// time, or the initializer refers to an undeclared name.
+    fn resolve_file_scope_declaration(
+        &mut self,
+        declaration: &'a CmmDeclaration,
+    ) -> Result<(), SemanticError> {
+        let CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            ..
+        } = declaration;
+        let symbols = &mut self.resolution.symbols;
+        let previous = symbols.lookup_in_file_scope(identifier);
+        check_variable_redeclaration(identifier, variable_type, previous)?;
+        let previous_linkage = previous.map(|previous| previous.linkage);
+        match (storage_class, previous_linkage) {
+            (Some(CmmStorageClass::Static), Some(Linkage::External)) => {
+                return Err(SemanticError::ConflictingVariableLinkage {
+                    identifier: identifier.clone(),
+                    is_static: true,
+                });
+            }
+            (None, Some(Linkage::Internal)) => {
+                return Err(SemanticError::ConflictingVariableLinkage {
+                    identifier: identifier.clone(),
+                    is_static: false,
+                });
+            }
+            _ => {}
+        }
+        if initializer.is_some() && !self.defined_variables.insert(identifier) {
+            return Err(SemanticError::VariableRedefinition {
+                identifier: identifier.clone(),
+            });
+        }
+        symbols.declare(
+            identifier.clone(),
+            Declaration::linked_variable(
+                identifier.clone(),
+                variable_type.clone(),
+                Linkage::of_declaration(*storage_class, previous_linkage),
+                *storage_class,
+            ),
+        );
+        self.resolution
+            .variable_types
+            .insert(identifier.clone(), variable_type.clone());
+        match initializer {
+            Some(initializer) => self.resolve_initializer(initializer),
+            None => Ok(()),
+        }
+    }
+
+    fn resolve_declaration(&mut self, declaration: &CmmDeclaration) -> Result<(), SemanticError> {
+        let CmmDeclaration::Declaration {
+            identifier,
+            storage_class,
+            variable_type,
+            initializer,
+            span,
+        } = declaration;
+        let unique_name = match storage_class {
+            Some(CmmStorageClass::Extern) => {
+                self.declare_extern_variable(identifier, variable_type, initializer.is_some())
+            }
+            _ => self.declare_variable(identifier, variable_type.clone()),
+        }
+        .map_err(|error| error.located(*span))?;
+        self.resolution.locals.push(LocalVariable {
+            identifier: identifier.clone(),
+            unique_name,
+            storage_class: *storage_class,
+            function: self
+                .function
+                .clone()
+                .expect("Declarations are inside function bodies"),
+            span: *span,
+        });
+        match initializer {
+            Some(initializer) => self.resolve_initializer(initializer),
+            None => Ok(()),
+        }
+    }
+
+    fn resolve_initializer(&mut self, initializer: &CmmInitializer) -> Result<(), SemanticError> {
+        match initializer {
+            CmmInitializer::Single(expression) => self.resolve_expression(*expression),
+            CmmInitializer::Compound(initializers) => initializers
+                .iter()
+                .try_for_each(|initializer| self.resolve_initializer(initializer)),
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &CmmStatement) -> Result<(), SemanticError> {
+        match statement {
+            CmmStatement::Return { expression, .. } => {
+                self.resolve_optional_expression(*expression)
+            }
+            CmmStatement::Expression { expression, .. } => self.resolve_expression(*expression),
+            CmmStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.resolve_expression(*condition)?;
+                self.resolve_statement(then_branch)?;
+                match else_branch {
+                    Some(else_branch) => self.resolve_statement(else_branch),
+                    None => Ok(()),
+                }
+            }
+            CmmStatement::While {
+                condition, body, ..
+            }
+            | CmmStatement::DoWhile {
+                body, condition, ..
+            } => {
+                self.resolve_expression(*condition)?;
+                self.resolve_statement(body)
+            }
+            CmmStatement::For {
+                init,
+                condition,
+                post,
+                body,
+                ..
+            } => {
+                self.resolution.symbols.push_scope();
+                match init {
+                    CmmForInit::Declaration(declaration) => {
+                        self.resolve_declaration(declaration)?
+                    }
+                    CmmForInit::Expression(expression) => {
+                        self.resolve_optional_expression(*expression)?
+                    }
+                }
+                self.resolve_optional_expression(*condition)?;
+                self.resolve_optional_expression(*post)?;
+                self.resolve_statement(body)?;
+                self.resolution.symbols.pop_scope();
+                Ok(())
+            }
+            CmmStatement::Null { .. }
+            | CmmStatement::Break { .. }
+            | CmmStatement::Continue { .. } => Ok(()),
+        }
+    }
+
+    fn resolve_optional_expression(&mut self, id: Option<ExprId>) -> Result<(), SemanticError> {
+        match id {
+            Some(id) => self.resolve_expression(id),
+            None => Ok(()),
+        }
+    }
+
+    fn resolve_expression(&mut self, id: ExprId) -> Result<(), SemanticError> {
+        match &self.expressions[id] {
+            CmmExpression::IntegerConstant { .. }
+            | CmmExpression::LongConstant { .. }
+            | CmmExpression::UnsignedIntegerConstant { .. }
+            | CmmExpression::UnsignedLongConstant { .. }
+            | CmmExpression::DoubleConstant { .. }
+            | CmmExpression::StringLiteral { .. } => Ok(()),
+            CmmExpression::Variable { identifier } => {
+                match self.resolution.symbols.lookup(identifier) {
+                    Some(declaration) if declaration.variable_type().is_some() => {
+                        self.resolution
+                            .names
+                            .insert(id, declaration.unique_name.clone());
+                        Ok(())
+                    }
+                    _ if self.enumerators.contains(identifier) => Ok(()),
+                    _ => Err(SemanticError::UndeclaredVariable {
+                        identifier: identifier.clone(),
+                    }
+                    .located(self.expressions.span(id))),
+                }
+            }
+            CmmExpression::FunctionCall {
+                identifier,
+                arguments,
+            } => {
+                // A variable hides a function of the same name.
+                match self.resolution.symbols.lookup(identifier) {
+                    Some(declaration) if declaration.variable_type().is_some() => {
+                        return Err(SemanticError::VariableCalledAsFunction {
+                            identifier: identifier.clone(),
+                        }
+                        .located(self.expressions.span(id)));
+                    }
+                    Some(_) => {}
+                    None => {
+                        return Err(SemanticError::UndeclaredFunction {
+                            identifier: identifier.clone(),
+                        }
+                        .located(self.expressions.span(id)));
+                    }
+                }
+                arguments
+                    .iter()
+                    .try_for_each(|argument| self.resolve_expression(*argument))
+            }
+            CmmExpression::Cast { expression, .. }
+            | CmmExpression::AddressOf { expression }
+            | CmmExpression::Dereference { expression }
+            | CmmExpression::Unary { expression, .. }
+            | CmmExpression::Member {
+                structure: expression,
+                ..
+            }
+            | CmmExpression::PointerMember {
+                pointer: expression,
+                ..
+            } => self.resolve_expression(*expression),
+            CmmExpression::Subscript {
+                array: left,
+                index: right,
+            }
+            | CmmExpression::Binary { left, right, .. }
+            | CmmExpression::Assignment {
+                target: left,
+                value: right,
+            }
+            | CmmExpression::CompoundAssignment {
+                target: left,
+                value: right,
+                ..
+            } => {
+                self.resolve_expression(*left)?;
+                self.resolve_expression(*right)
+            }
+        }
+    }
+
+    /// Declares a function in the file scope, after checking it against its previous declaration.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `storage_class`: The storage-class specifier of the declaration.
+    /// * `function_type`: The type of the declaration.
+    /// * `is_definition`: Whether the declaration has a body.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the declaration conflicts with a previous one or
+    /// with a file-scope variable, defines the function a second time, or gives `main` an invalid
+    /// signature.
+    fn declare_function(
+        &mut self,
+        identifier: &'a Symbol,
+        storage_class: Option<CmmStorageClass>,
+        function_type: FunctionType,
+        is_definition: bool,
+    ) -> Result<(), SemanticError> {
+        if identifier == "main" {
+            check_main_signature(storage_class, &function_type)?;
+        }
+        let symbols = &mut self.resolution.symbols;
+        let previous = symbols.lookup_in_file_scope(identifier);
+        if previous.is_some_and(|previous| previous.variable_type().is_some()) {
+            return Err(SemanticError::ConflictingKind {
+                identifier: identifier.clone(),
+            });
+        }
+        if let Some(previous) = previous {
+            check_redeclaration(identifier, previous, storage_class, &function_type)?;
+        }
+        if is_definition && !self.defined_functions.insert(identifier) {
+            return Err(SemanticError::FunctionRedefinition {
+                identifier: identifier.clone(),
+            });
+        }
+        let linkage = Linkage::of_declaration(
+            storage_class,
+            previous.map(|declaration| declaration.linkage),
+        );
+        symbols.declare(
+            identifier.clone(),
+            Declaration::function(identifier.clone(), function_type, linkage, storage_class),
+        );
+        Ok(())
+    }
+
+    /// Declares an `extern` local variable in the innermost scope, which keeps its name. It takes
+    /// the linkage of the visible declaration of its name, unless that declaration has no linkage,
+    /// and has external linkage otherwise, like in C. It refers to the file-scope variable of its
+    /// name, or to a variable of another file if the file scope does not declare one.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable in the program.
+    /// * `variable_type`: The declared type of the variable.
+    /// * `has_initializer`: Whether the declaration has an initializer.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the unique name of the variable, or a `SemanticError` if the
+    /// declaration has an initializer, the innermost scope already declares a variable without
+    /// linkage of the name, or the declaration conflicts with the file-scope declaration, such as
+    /// a `static` one hidden by a local variable.
+    fn declare_extern_variable(
+        &mut self,
+        identifier: &Symbol,
+        variable_type: &CmmType,
+        has_initializer: bool,
+    ) -> Result<Symbol, SemanticError> {
+        if has_initializer {
+            return Err(SemanticError::ExternInitializer {
+                identifier: identifier.clone(),
+            });
+        }
+        let symbols = &mut self.resolution.symbols;
+        if symbols
+            .lookup_in_current_scope(identifier)
+            .is_some_and(|current| current.linkage == Linkage::None)
+        {
+            return Err(SemanticError::DuplicateVariable {
+                identifier: identifier.clone(),
+            });
+        }
+        let visible_linkage = symbols
+            .lookup(identifier)
+            .map(|visible| visible.linkage)
+            .filter(|linkage| *linkage != Linkage::None);
+        let linkage = Linkage::of_declaration(Some(CmmStorageClass::Extern), visible_linkage);
+        let previous = symbols.lookup_in_file_scope(identifier);
+        check_variable_redeclaration(identifier, variable_type, previous)?;
+        if previous.is_some_and(|previous| previous.linkage != linkage) {
+            return Err(SemanticError::ConflictingVariableLinkage {
+                identifier: identifier.clone(),
+                is_static: false,
+            });
+        }
+        symbols.declare(
+            identifier.clone(),
+            Declaration::linked_variable(
+                identifier.clone(),
+                variable_type.clone(),
+                linkage,
+                Some(CmmStorageClass::Extern),
+            ),
+        );
+        self.resolution
+            .variable_types
+            .insert(identifier.clone(), variable_type.clone());
+        Ok(identifier.clone())
+    }
+
+    /// Declares a variable in the innermost scope and generates its unique name.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable in the program.
+    /// * `variable_type`: The declared type of the variable.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the unique name of the variable, or a `SemanticError` if the innermost
+    /// scope already declares the variable.
+    fn declare_variable(
+        &mut self,
+        identifier: &Symbol,
+        variable_type: CmmType,
+    ) -> Result<Symbol, SemanticError> {
+        if self
+            .resolution
+            .symbols
+            .lookup_in_current_scope(identifier)
+            .is_some()
+        {
+            return Err(SemanticError::DuplicateVariable {
+                identifier: identifier.clone(),
+            });
+        }
+        let unique_name = Symbol::from(format!(
+            "{}{}{}",
+            identifier, GENERATED_NAME_SEPARATOR, self.counter
+        ));
+        self.counter += 1;
+        self.resolution
+            .variable_types
+            .insert(unique_name.clone(), variable_type.clone());
+        self.resolution.symbols.declare(
+            identifier.clone(),
+            Declaration::variable(unique_name.clone(), variable_type),
+        );
+        Ok(unique_name)
+    }
+}
+
+/// Checks that a declaration of a variable with linkage agrees with the previous declaration of
+/// its name in the file scope, which must declare a variable of the same type.
+///
+/// # Arguments
+///
+/// * `identifier`: The name of the variable.
+/// * `variable_type`: The type of the new declaration.
+/// * `previous`: The previous declaration of the name in the file scope, if any.
+///
+/// # Returns
+///
+/// An empty `Result`, or a `SemanticError` if the name is declared as a function or as a variable
+/// of another type.
+fn check_variable_redeclaration(
+    identifier: &Symbol,
+    variable_type: &CmmType,
+    previous: Option<&Declaration>,
+) -> Result<(), SemanticError> {
+    let Some(previous) = previous else {
+        return Ok(());
+    };
+    match previous.variable_type() {
+        None => Err(SemanticError::ConflictingKind {
+            identifier: identifier.clone(),
+        }),
+        Some(previous_type) if previous_type != variable_type => {
+            Err(SemanticError::ConflictingVariableType {
+                identifier: identifier.clone(),
+                previous: previous_type.clone(),
+                current: variable_type.clone(),
+            })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks that a declaration of a function agrees with its previous declaration.
+///
+/// Both declarations must have the same return type, the same number of parameters of the same
+/// types, and both or neither must be variadic. The `const` qualifiers of the parameters
+/// themselves do not matter. A `static` declaration cannot follow one with external linkage.
+///
+/// # Arguments
+///
+/// * `identifier`: The name of the function.
+/// * `previous`: The previous declaration of the function, in the file scope.
+/// * `storage_class`: The storage-class specifier of the new declaration.
+/// * `function_type`: The type of the new declaration.
+///
+/// # Returns
+///
+/// An empty `Result`, or a `SemanticError` describing the first difference.
+fn check_redeclaration(
+    identifier: &Symbol,
+    previous: &Declaration,
+    storage_class: Option<CmmStorageClass>,
+    function_type: &FunctionType,
+) -> Result<(), SemanticError> {
+    if storage_class == Some(CmmStorageClass::Static) && previous.linkage == Linkage::External {
+        return Err(SemanticError::ConflictingLinkage {
+            identifier: identifier.clone(),
+        });
+    }
+    let Some(previous_type) = previous.function_type() else {
+        return Ok(());
+    };
+    if previous_type.return_type != function_type.return_type {
+        return Err(SemanticError::ConflictingReturnType {
+            identifier: identifier.clone(),
+            previous: previous_type.return_type.clone(),
+            current: function_type.return_type.clone(),
+        });
+    }
+    if previous_type.parameter_types.len() != function_type.parameter_types.len() {
+        return Err(SemanticError::ConflictingParameterCount {
+            identifier: identifier.clone(),
+            previous: previous_type.parameter_types.len(),
+            current: function_type.parameter_types.len(),
+        });
+    }
+    if previous_type.variadic != function_type.variadic {
+        return Err(SemanticError::ConflictingVariadic {
+            identifier: identifier.clone(),
+            variadic: function_type.variadic,
+        });
+    }
+    let parameters = previous_type
+        .parameter_types
+        .iter()
+        .zip(&function_type.parameter_types);
+    for (index, (previous, current)) in parameters.enumerate() {
+        if previous.unqualified() != current.unqualified() {
+            return Err(SemanticError::ConflictingParameterType {
+                identifier: identifier.clone(),
+                position: index + 1,
+                previous: previous.clone(),
+                current: current.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a declaration of `main` has a signature C allows: `int main(void)` or
+/// `int main(int argc, char **argv)`, without `static`. The `const` qualifiers of the parameters
+/// themselves do not matter.
+///
+/// # Arguments
+///
+/// * `storage_class`: The storage-class specifier of the declaration.
+/// * `function_type`: The type of the declaration.
+///
+/// # Returns
+///
+/// An empty `Result`, or a `SemanticError` if the signature is not allowed.
+fn check_main_signature(
+    storage_class: Option<CmmStorageClass>,
+    function_type: &FunctionType,
+) -> Result<(), SemanticError> {
+    let char_pointer_pointer =
+        CmmType::Pointer(Box::new(CmmType::Pointer(Box::new(CmmType::Char))));
+    let has_valid_parameters = match function_type.parameter_types.as_slice() {
+        [] => true,
+        [argc, argv] => {
+            argc.unqualified() == &CmmType::Int && argv.unqualified() == &char_pointer_pointer
+        }
+        _ => false,
+    };
+    let is_static = storage_class == Some(CmmStorageClass::Static);
+    if function_type.return_type != Some(CmmType::Int)
+        || is_static
+        || function_type.variadic
+        || !has_valid_parameters
+    {
+        return Err(SemanticError::InvalidMainSignature {
+            is_static,
+            function_type: function_type.clone(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parse_source;
+
+    fn resolve(source_code: &str) -> Result<VariableResolution, SemanticError> {
+        resolve_variables(&parse_source(source_code).unwrap()).map_err(SemanticError::unlocated)
+    }
+
+    /// Returns the unique names of the variable expressions of a program, in arena order.
+    fn unique_names(cmm_ast: &CmmAst) -> Vec<String> {
+        let CmmAst::Program { expressions, .. } = cmm_ast;
+        let resolution = resolve_variables(cmm_ast).unwrap();
+        expressions
+            .iter()
+            .filter_map(|(id, _)| resolution.unique_name(id))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_shadowed_variables() {
+        let cmm_ast = parse_source(
+            "int f(int a) { return a; }
+             int main(void) {
+                 int a = 1;
+                 for (int a = a; a < 3; a = a + 1) a;
+                 return a;
+             }",
+        )
+        .unwrap();
+        assert_eq!(
+            unique_names(&cmm_ast),
+            vec!["a.0", "a.2", "a.2", "a.2", "a.2", "a.2", "a.1"]
+        );
+        let resolution = resolve_variables(&cmm_ast).unwrap();
+        assert_eq!(
+            resolution.parameters(&"f".into()),
+            Some(&[Symbol::from("a.0")][..])
+        );
+        assert_eq!(resolution.parameters(&"main".into()), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_enumerators_are_not_variables() {
+        let cmm_ast = parse_source(
+            "enum color { RED, GREEN };
+             int main(void) { int GREEN = 2; return RED + GREEN; }",
+        )
+        .unwrap();
+        assert_eq!(unique_names(&cmm_ast), vec!["GREEN.0"]);
+    }
+
+    #[test]
+    fn test_resolve_file_scope_variables() {
+        let cmm_ast = parse_source(
+            "int g;
+             int main(void) { int h = g; return h + g + i; }
+             int g = 2;
+             long i;",
+        )
+        .unwrap();
+        assert_eq!(unique_names(&cmm_ast), vec!["g", "h.0", "g", "i"]);
+        let resolution = resolve_variables(&cmm_ast).unwrap();
+        assert_eq!(resolution.variable_type(&"i".into()), Some(&CmmType::Long));
+        assert_eq!(
+            resolution
+                .symbols()
+                .lookup_in_file_scope("g")
+                .unwrap()
+                .linkage,
+            Linkage::External
+        );
+        assert_eq!(resolution.locals().len(), 1);
+    }
+
+    #[test]
+    fn test_reject_conflicting_file_scope_variables() {
+        for (source, error) in [
+            (
+                "int g = 1;\nint g = 2;",
+                SemanticError::VariableRedefinition {
+                    identifier: "g".into(),
+                },
+            ),
+            (
+                "int g;\nlong g;",
+                SemanticError::ConflictingVariableType {
+                    identifier: "g".into(),
+                    previous: CmmType::Int,
+                    current: CmmType::Long,
+                },
+            ),
+            (
+                "int g;\nint g(void);",
+                SemanticError::ConflictingKind {
+                    identifier: "g".into(),
+                },
+            ),
+            (
+                "int g = h;",
+                SemanticError::UndeclaredVariable {
+                    identifier: "h".into(),
+                },
+            ),
+        ] {
+            assert_eq!(resolve(source).map(|_| ()), Err(error), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_resolve_storage_classes_of_variables() {
+        let cmm_ast = parse_source(
+            "static int s;
+             extern int e;
+             extern int s;
+             int main(void) { static int count; extern int e; extern int s; return count + e + s; }",
+        )
+        .unwrap();
+        // The `extern` locals refer to the file-scope variables, and the `static` one is renamed.
+        assert_eq!(unique_names(&cmm_ast), vec!["count.0", "e", "s"]);
+        let resolution = resolve_variables(&cmm_ast).unwrap();
+        let linkage = |identifier| {
+            resolution
+                .symbols()
+                .lookup_in_file_scope(identifier)
+                .unwrap()
+                .linkage
+        };
+        assert_eq!(linkage("s"), Linkage::Internal);
+        assert_eq!(linkage("e"), Linkage::External);
+        let storage_classes: Vec<_> = resolution
+            .locals()
+            .iter()
+            .map(|local| local.storage_class)
+            .collect();
+        assert_eq!(
+            storage_classes,
+            [
+                Some(CmmStorageClass::Static),
+                Some(CmmStorageClass::Extern),
+                Some(CmmStorageClass::Extern)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reject_conflicting_storage_classes_of_variables() {
+        for (source, error) in [
+            (
+                "int g;\nstatic int g;",
+                SemanticError::ConflictingVariableLinkage {
+                    identifier: "g".into(),
+                    is_static: true,
+                },
+            ),
+            (
+                "static int g;\nint g;",
+                SemanticError::ConflictingVariableLinkage {
+                    identifier: "g".into(),
+                    is_static: false,
+                },
+            ),
+            (
+                "int main(void) { extern int g = 1; return g; }",
+                SemanticError::ExternInitializer {
+                    identifier: "g".into(),
+                },
+            ),
+            (
+                "int main(void) { int g; extern int g; return g; }",
+                SemanticError::DuplicateVariable {
+                    identifier: "g".into(),
+                },
+            ),
+            (
+                "long g;\nint main(void) { extern int g; return g; }",
+                SemanticError::ConflictingVariableType {
+                    identifier: "g".into(),
+                    previous: CmmType::Long,
+                    current: CmmType::Int,
+                },
+            ),
+        ] {
+            assert_eq!(resolve(source).map(|_| ()), Err(error), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_reject_undeclared_and_duplicate_names() {
+        for (source, error) in [
+            (
+                "int main(void) { for (int i = 0; i < 2; i = i + 1); return i; }",
+                SemanticError::UndeclaredVariable {
+                    identifier: "i".into(),
+                },
+            ),
+            (
+                "int main(void) { int x = 1; int x = 2; return x; }",
+                SemanticError::DuplicateVariable {
+                    identifier: "x".into(),
+                },
+            ),
+            (
+                "int f(int a) { int a = 2; return a; }",
+                SemanticError::DuplicateVariable {
+                    identifier: "a".into(),
+                },
+            ),
+            (
+                "int f(int a, long a);",
+                SemanticError::DuplicateParameter {
+                    identifier: "a".into(),
+                },
+            ),
+            (
+                "int f(int a) { int f = a; return f(a); }",
+                SemanticError::VariableCalledAsFunction {
+                    identifier: "f".into(),
+                },
+            ),
+        ] {
+            let cmm_ast = parse_source(source).unwrap();
+            assert_eq!(
+                resolve_variables(&cmm_ast).map_err(SemanticError::unlocated),
+                Err(error),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_undeclared_and_redefined_functions() {
+        for (source, error) in [
+            (
+                "int main(void) { return f(); }\nint f(void) { return 1; }",
+                SemanticError::UndeclaredFunction {
+                    identifier: "f".into(),
+                },
+            ),
+            (
+                "int f(void) { return 1; }\nint f(void) { return 2; }",
+                SemanticError::FunctionRedefinition {
+                    identifier: "f".into(),
+                },
+            ),
+        ] {
+            assert_eq!(resolve(source).map(|_| ()), Err(error), "{}", source);
+        }
+        let resolution = resolve("int f(void);\nint f(void) { return f(); }").unwrap();
+        assert_eq!(
+            resolution.symbols().lookup("f").unwrap().linkage,
+            Linkage::External
+        );
+    }
+
+    #[test]
+    fn test_reject_conflicting_declarations() {
+        for (source, error) in [
+            (
+                "int f(void);\nstatic int f(void);",
+                SemanticError::ConflictingLinkage {
+                    identifier: "f".into(),
+                },
+            ),
+            (
+                "int f(int a);\nvoid f(int a);",
+                SemanticError::ConflictingReturnType {
+                    identifier: "f".into(),
+                    previous: Some(CmmType::Int),
+                    current: None,
+                },
+            ),
+            (
+                "int f(int a);\nint f(int a, int b) { return a + b; }",
+                SemanticError::ConflictingParameterCount {
+                    identifier: "f".into(),
+                    previous: 1,
+                    current: 2,
+                },
+            ),
+            (
+                "int f(int a, ...);\nint f(int a);",
+                SemanticError::ConflictingVariadic {
+                    identifier: "f".into(),
+                    variadic: false,
+                },
+            ),
+            (
+                "int f(int a, int *b);\nint f(int a, long *b);",
+                SemanticError::ConflictingParameterType {
+                    identifier: "f".into(),
+                    position: 2,
+                    previous: CmmType::Pointer(Box::new(CmmType::Int)),
+                    current: CmmType::Pointer(Box::new(CmmType::Long)),
+                },
+            ),
+        ] {
+            assert_eq!(resolve(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(
+            resolve(
+                "static int f(int a[3], const int b);\n\
+                 extern int f(int *a, int b);\n\
+                 int f(int *a, int b) { return *a + b; }"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_main_signature() {
+        let main_type =
+            |return_type: Option<CmmType>, parameter_types: Vec<CmmType>| FunctionType {
+                return_type,
+                parameter_types,
+                variadic: false,
+            };
+        let char_pointer = CmmType::Pointer(Box::new(CmmType::Char));
+        for (source, error) in [
+            (
+                "void main(void) { return; }",
+                SemanticError::InvalidMainSignature {
+                    is_static: false,
+                    function_type: main_type(None, vec![]),
+                },
+            ),
+            (
+                "static int main(void) { return 0; }",
+                SemanticError::InvalidMainSignature {
+                    is_static: true,
+                    function_type: main_type(Some(CmmType::Int), vec![]),
+                },
+            ),
+            (
+                "int main(int argc, int argv) { return 0; }",
+                SemanticError::InvalidMainSignature {
+                    is_static: false,
+                    function_type: main_type(Some(CmmType::Int), vec![CmmType::Int, CmmType::Int]),
+                },
+            ),
+            (
+                "int main(int a, char **b, char **c) { return 0; }",
+                SemanticError::InvalidMainSignature {
+                    is_static: false,
+                    function_type: main_type(
+                        Some(CmmType::Int),
+                        vec![
+                            CmmType::Int,
+                            CmmType::Pointer(Box::new(char_pointer.clone())),
+                            CmmType::Pointer(Box::new(char_pointer.clone())),
+                        ],
+                    ),
+                },
+            ),
+        ] {
+            assert_eq!(resolve(source).map(|_| ()), Err(error), "{}", source);
+        }
+        for source in [
+            "int main(void) { return 0; }",
+            "int main(int argc, char **argv) { return argc; }",
+            "extern int main(const int argc, char **const argv);",
+        ] {
+            assert!(resolve(source).is_ok(), "{}", source);
+        }
+        assert_eq!(
+            resolve("int main(int a, char **b, char **c) { return 0; }")
+                .unwrap_err()
+                .to_string(),
+            "Semantic error: 'main' must be declared as 'int main(void)' or \
+             'int main(int argc, char **argv)', not 'int main(int, char **, char **)'"
+        );
+    }
+}
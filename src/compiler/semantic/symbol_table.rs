@@ -0,0 +1,328 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::parser::cmm_ast::{CmmStorageClass, CmmType};
+use rustc_hash::FxHashMap;
+
+/// Represents the linkage of a declared name, which determines whether declarations in other
+/// scopes or files refer to the same entity.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Linkage {
+    /// The name only refers to its own declaration, like a local variable.
+    None,
+    /// The name refers to the same entity throughout its file, like a `static` function or
+    /// file-scope variable.
+    Internal,
+    /// The name refers to the same entity throughout the program, like a function or a file-scope
+    /// variable without a storage-class specifier, or an `extern` local variable.
+    External,
+}
+
+impl Linkage {
+    /// Returns the linkage a declaration of a function, a file-scope variable, or an `extern` local
+    /// variable gives its name.
+    ///
+    /// A `static` declaration gives internal linkage, and other declarations keep the linkage of a
+    /// previous declaration, or have external linkage. Declaring a name `static` after it got
+    /// external linkage is an error the caller reports, and so is declaring a variable without a
+    /// storage-class specifier after it got internal linkage.
+    ///
+    /// # Arguments
+    ///
+    /// * `storage_class`: The storage-class specifier of the declaration.
+    /// * `previous`: The linkage of the previous declaration of the name, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use cmm::compiler::parser::cmm_ast::CmmStorageClass;
+    /// # use cmm::compiler::semantic::symbol_table::Linkage;
+    /// assert_eq!(Linkage::of_declaration(None, None), Linkage::External);
+    /// assert_eq!(
+    ///     Linkage::of_declaration(Some(CmmStorageClass::Extern), Some(Linkage::Internal)),
+    ///     Linkage::Internal
+    /// );
+    /// ```
+    pub fn of_declaration(
+        storage_class: Option<CmmStorageClass>,
+        previous: Option<Linkage>,
+    ) -> Self {
+        match (storage_class, previous) {
+            (Some(CmmStorageClass::Static), _) => Linkage::Internal,
+            (_, Some(previous)) => previous,
+            (_, None) => Linkage::External,
+        }
+    }
+}
+
+/// Represents the type of a declared function.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FunctionType {
+    /// The type of the returned value, or `None` for a `void` function.
+    pub return_type: Option<CmmType>,
+    /// The types of the parameters, in order. A parameter declared as an array is a pointer to its
+    /// first element.
+    pub parameter_types: Vec<CmmType>,
+    /// Whether the function takes further arguments after its parameters.
+    pub variadic: bool,
+}
+
+/// Represents the type of a declared name.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SymbolType {
+    /// The type of a variable.
+    Object(CmmType),
+    /// The type of a function.
+    Function(FunctionType),
+}
+
+/// Represents what a declaration tells about a name.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Declaration {
+    /// The name the later stages refer to the declared entity by. Local variables are renamed to
+    /// `<name>.<counter>`, and variables with linkage and functions keep their names.
+    pub unique_name: Symbol,
+    /// The type of the declared entity.
+    pub symbol_type: SymbolType,
+    /// The linkage of the name.
+    pub linkage: Linkage,
+    /// The storage-class specifier of the declaration, if any.
+    pub storage_class: Option<CmmStorageClass>,
+}
+
+impl Declaration {
+    /// Creates the declaration of a local variable, which has no linkage.
+    ///
+    /// # Arguments
+    ///
+    /// * `unique_name`: The unique name of the variable.
+    /// * `variable_type`: The declared type of the variable.
+    pub fn variable(unique_name: Symbol, variable_type: CmmType) -> Self {
+        Declaration {
+            unique_name,
+            symbol_type: SymbolType::Object(variable_type),
+            linkage: Linkage::None,
+            storage_class: None,
+        }
+    }
+
+    /// Creates the declaration of a variable with linkage, a file-scope variable or an `extern`
+    /// local variable, which keeps its name like a function.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    /// * `variable_type`: The declared type of the variable.
+    /// * `linkage`: The linkage of the variable, see `Linkage::of_declaration`.
+    /// * `storage_class`: The storage-class specifier of the declaration.
+    pub fn linked_variable(
+        identifier: Symbol,
+        variable_type: CmmType,
+        linkage: Linkage,
+        storage_class: Option<CmmStorageClass>,
+    ) -> Self {
+        Declaration {
+            unique_name: identifier,
+            symbol_type: SymbolType::Object(variable_type),
+            linkage,
+            storage_class,
+        }
+    }
+
+    /// Creates the declaration of a function, which keeps its name.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the function.
+    /// * `function_type`: The type of the function.
+    /// * `linkage`: The linkage of the function, see `Linkage::of_declaration`.
+    /// * `storage_class`: The storage-class specifier of the declaration.
+    pub fn function(
+        identifier: Symbol,
+        function_type: FunctionType,
+        linkage: Linkage,
+        storage_class: Option<CmmStorageClass>,
+    ) -> Self {
+        Declaration {
+            unique_name: identifier,
+            symbol_type: SymbolType::Function(function_type),
+            linkage,
+            storage_class,
+        }
+    }
+
+    /// Returns the type of the declared variable, or `None` if a function is declared.
+    pub fn variable_type(&self) -> Option<&CmmType> {
+        match &self.symbol_type {
+            SymbolType::Object(variable_type) => Some(variable_type),
+            SymbolType::Function(_) => None,
+        }
+    }
+
+    /// Returns the type of the declared function, or `None` if a variable is declared.
+    pub fn function_type(&self) -> Option<&FunctionType> {
+        match &self.symbol_type {
+            SymbolType::Function(function_type) => Some(function_type),
+            SymbolType::Object(_) => None,
+        }
+    }
+}
+
+/// Represents the names visible at a point of a program, in nested scopes.
+///
+/// The outermost scope is the file scope, which holds the file-scope variables and the functions.
+/// Every block opens a scope inside the current one, and a declaration in an inner scope hides the
+/// declarations of the same name in the outer scopes until the inner scope is closed.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parser::cmm_ast::CmmType;
+/// # use cmm::compiler::semantic::symbol_table::{Declaration, SymbolTable};
+/// let mut symbols = SymbolTable::new();
+/// symbols.push_scope();
+/// symbols.declare("x".into(), Declaration::variable("x.0".into(), CmmType::Int));
+/// symbols.push_scope();
+/// symbols.declare("x".into(), Declaration::variable("x.1".into(), CmmType::Long));
+/// assert_eq!(symbols.lookup("x").unwrap().unique_name.as_str(), "x.1");
+/// symbols.pop_scope();
+/// assert_eq!(symbols.lookup("x").unwrap().variable_type(), Some(&CmmType::Int));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct SymbolTable {
+    /// The declarations of every open scope, by name, the file scope first and the innermost
+    /// scope last.
+    scopes: Vec<FxHashMap<Symbol, Declaration>>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolTable {
+    /// Creates a symbol table holding an empty file scope.
+    pub fn new() -> Self {
+        SymbolTable {
+            scopes: vec![FxHashMap::default()],
+        }
+    }
+
+    /// Opens a scope inside the current one.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(FxHashMap::default());
+    }
+
+    /// Closes the innermost scope, forgetting its declarations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if only the file scope is open, which cannot be closed.
+    pub fn pop_scope(&mut self) {
+        assert!(self.scopes.len() > 1, "The file scope cannot be closed");
+        self.scopes.pop();
+    }
+
+    /// Returns `true` if only the file scope is open.
+    pub fn is_file_scope(&self) -> bool {
+        self.scopes.len() == 1
+    }
+
+    /// Declares a name in the innermost scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name as written in the program.
+    /// * `declaration`: What the declaration tells about the name.
+    ///
+    /// # Returns
+    ///
+    /// The previous declaration of the name in the innermost scope, which the new one replaces, or
+    /// `None` if the scope did not declare the name yet.
+    pub fn declare(&mut self, identifier: Symbol, declaration: Declaration) -> Option<Declaration> {
+        self.scopes
+            .last_mut()
+            .expect("The file scope is always open")
+            .insert(identifier, declaration)
+    }
+
+    /// Looks up the innermost visible declaration of a name.
+    pub fn lookup(&self, identifier: &str) -> Option<&Declaration> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(identifier))
+    }
+
+    /// Looks up the declaration of a name in the innermost scope, ignoring the outer scopes.
+    pub fn lookup_in_current_scope(&self, identifier: &str) -> Option<&Declaration> {
+        self.scopes.last()?.get(identifier)
+    }
+
+    /// Looks up the declaration of a name in the file scope, even if an inner scope hides it.
+    pub fn lookup_in_file_scope(&self, identifier: &str) -> Option<&Declaration> {
+        self.scopes.first()?.get(identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function_type() -> FunctionType {
+        FunctionType {
+            return_type: Some(CmmType::Int),
+            parameter_types: vec![],
+            variadic: false,
+        }
+    }
+
+    #[test]
+    fn test_inner_scopes_shadow_outer_scopes() {
+        let mut symbols = SymbolTable::new();
+        symbols.declare(
+            "f".into(),
+            Declaration::function("f".into(), function_type(), Linkage::External, None),
+        );
+        symbols.push_scope();
+        assert!(!symbols.is_file_scope());
+        symbols.declare(
+            "f".into(),
+            Declaration::variable("f.0".into(), CmmType::Char),
+        );
+        assert_eq!(
+            symbols.lookup("f").unwrap().variable_type(),
+            Some(&CmmType::Char)
+        );
+        assert_eq!(
+            symbols.lookup_in_file_scope("f").unwrap().function_type(),
+            Some(&function_type())
+        );
+        symbols.push_scope();
+        assert_eq!(symbols.lookup_in_current_scope("f"), None);
+        symbols.pop_scope();
+        symbols.pop_scope();
+        assert!(symbols.is_file_scope());
+        assert_eq!(symbols.lookup("f").unwrap().linkage, Linkage::External);
+    }
+
+    #[test]
+    fn test_redeclaration_returns_previous_declaration() {
+        let mut symbols = SymbolTable::new();
+        let first = Declaration::function(
+            "f".into(),
+            function_type(),
+            Linkage::Internal,
+            Some(CmmStorageClass::Static),
+        );
+        assert_eq!(symbols.declare("f".into(), first.clone()), None);
+        let second = Declaration::function("f".into(), function_type(), Linkage::Internal, None);
+        assert_eq!(symbols.declare("f".into(), second.clone()), Some(first));
+        assert_eq!(symbols.lookup("f"), Some(&second));
+    }
+
+    #[test]
+    #[should_panic(expected = "The file scope cannot be closed")]
+    fn test_file_scope_cannot_be_closed() {
+        SymbolTable::new().pop_scope();
+    }
+}
@@ -0,0 +1,1837 @@
+use crate::common::symbol::Symbol;
+use crate::compiler::ir_gen::type_table::TypeTable;
+use crate::compiler::parser::cmm_ast::{
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmDeclaration, CmmEnumDeclaration, CmmEnumerator,
+    CmmExpression, CmmForInit, CmmFunction, CmmInitializer, CmmMember, CmmParameter, CmmStatement,
+    CmmStorageClass, CmmStructDeclaration, CmmType, CmmUnaryOperator, ExprId, ExpressionArena,
+};
+use crate::compiler::parser::printer::print_expression;
+use crate::compiler::semantic::constant_expression::{
+    ArithmeticConstant, evaluate_arithmetic_constant, evaluate_constant,
+};
+use crate::compiler::semantic::errors::SemanticError;
+use crate::compiler::semantic::resolver::VariableResolution;
+use crate::compiler::semantic::symbol_table::{FunctionType, SymbolTable};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// Represents the types the type checker found for a program.
+///
+/// The expressions are identified by their `ExprId`s in the arena of the checked AST, so the
+/// annotations only apply to that AST.
+#[derive(Debug, Default, PartialEq)]
+pub struct TypeAnnotations {
+    /// The types of the expressions. Calls of `void` functions have none.
+    expression_types: FxHashMap<ExprId, CmmType>,
+    /// The variables the expressions refer to, their declared types, and the symbol table of the
+    /// program.
+    variables: VariableResolution,
+    /// The values of the enumerators, by name.
+    enumerators: FxHashMap<Symbol, i32>,
+}
+
+impl TypeAnnotations {
+    /// Returns the type of an expression, or `None` if it has no value. The type of an array is not
+    /// decayed to a pointer.
+    pub fn expression_type(&self, id: ExprId) -> Option<&CmmType> {
+        self.expression_types.get(&id)
+    }
+
+    /// Returns the type of a function, or `None` if the program does not declare it.
+    pub fn function_type(&self, identifier: &Symbol) -> Option<&FunctionType> {
+        self.variables.function_type(identifier)
+    }
+
+    /// Returns the symbol table of the program, whose file scope declares the functions with
+    /// their types and linkage, see `VariableResolution::symbols`.
+    pub fn symbols(&self) -> &SymbolTable {
+        self.variables.symbols()
+    }
+
+    /// Returns the resolved variables of the program, and their declared types.
+    pub fn variables(&self) -> &VariableResolution {
+        &self.variables
+    }
+
+    /// Returns the value of an enumerator, or `None` if the program does not declare it.
+    pub fn enumerator_value(&self, identifier: &Symbol) -> Option<i32> {
+        self.enumerators.get(identifier).copied()
+    }
+
+    /// Moves the annotations of the expressions to the `ExprId`s of another arena, see
+    /// `insert_conversions`. Expressions missing from `renumbered` lose their annotations.
+    ///
+    /// # Arguments
+    ///
+    /// * `renumbered`: The new `ExprId` of every expression, by its old `ExprId`.
+    pub(super) fn renumber(&mut self, renumbered: &FxHashMap<ExprId, ExprId>) {
+        self.expression_types = std::mem::take(&mut self.expression_types)
+            .into_iter()
+            .filter_map(|(id, expression_type)| Some((*renumbered.get(&id)?, expression_type)))
+            .collect();
+        self.variables.renumber(renumbered);
+    }
+
+    /// Records the type of an expression, such as a conversion inserted after the type check.
+    pub(super) fn annotate(&mut self, id: ExprId, expression_type: CmmType) {
+        self.expression_types.insert(id, expression_type);
+    }
+}
+
+/// Annotates every expression of a C-- program with its type, and checks the operand types of the
+/// operators.
+///
+/// The types follow the conversions of C, see `CmmType::common_type` and `CmmType::promoted`:
+/// arithmetic operators evaluate to the common type of their operands, the shifts and the unary
+/// `+`, `-`, and `~` to their promoted left operand, comparisons and logical operators to `int`,
+/// and assignments to the type of their target. A pointer plus or minus an integer is a pointer,
+/// and the difference of two pointers is a `long`.
+///
+/// The checks reject operands that the operators do not accept, such as a `double` operand of `%`,
+/// a pointer operand of `*`, or a dereferenced integer, and `break` and `continue` statements
+/// outside of loops. The
+/// values of assignments, initializers, arguments, and `return` statements must convert to the
+/// types of their targets: a pointer only converts to a pointer to the same type, gaining a
+/// `const` qualifier at most, and only the null pointer constant, an integer constant expression
+/// of zero, converts to a pointer. Whole structures are no values, so only their members and
+/// addresses are used, and the structures whose size or members are needed must be declared. The
+/// values of the enumerators are evaluated in declaration order, and the initializers of the
+/// file-scope and `static` local variables must be constant expressions, see
+/// `evaluate_arithmetic_constant`.
+///
+/// # Arguments
+///
+/// * `cmm_ast`: A reference to the `CmmAst` to check.
+/// * `variables`: The resolved variables of the program, see `resolve_variables`. Its symbol table
+///   declares the functions the calls and `return` statements are checked against.
+///
+/// # Returns
+///
+/// A `Result` containing the `TypeAnnotations` of the program, or a `SemanticError` for the first
+/// error found.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::parse_source;
+/// # use cmm::compiler::parser::cmm_ast::CmmType;
+/// # use cmm::compiler::semantic::errors::SemanticError;
+/// # use cmm::compiler::semantic::resolver::resolve_variables;
+/// # use cmm::compiler::semantic::type_checker::check_types;
+/// let cmm_ast = parse_source("int main(void) { double d = 2.5; return d % 2; }").unwrap();
+/// let variables = resolve_variables(&cmm_ast).unwrap();
+/// assert_eq!(
+///     check_types(&cmm_ast, variables).map_err(SemanticError::unlocated),
+///     Err(SemanticError::InvalidOperandType {
+///         expression: "d % 2".to_string(),
+///         operand_type: CmmType::Double,
+///     })
+/// );
+/// ```
+pub fn check_types(
+    cmm_ast: &CmmAst,
+    variables: VariableResolution,
+) -> Result<TypeAnnotations, SemanticError> {
+    let CmmAst::Program {
+        structs,
+        enums,
+        variables: declarations,
+        functions,
+        expressions,
+    } = cmm_ast;
+    let mut checker = TypeChecker {
+        expressions,
+        structs: FxHashMap::default(),
+        types: TypeTable::default(),
+        current_function: None,
+        discarded_expression: None,
+        loop_depth: 0,
+        annotations: TypeAnnotations {
+            variables,
+            ..TypeAnnotations::default()
+        },
+    };
+    for CmmStructDeclaration::Struct { tag, members, span } in structs {
+        checker
+            .declare_struct(tag, members)
+            .map_err(|error| error.located(*span))?;
+    }
+    let mut enum_tags = FxHashSet::default();
+    for CmmEnumDeclaration::Enum {
+        tag,
+        enumerators,
+        span,
+    } in enums
+    {
+        if let Some(tag) = tag
+            && !enum_tags.insert(tag)
+        {
+            return Err(SemanticError::EnumRedefinition { tag: tag.clone() }.located(*span));
+        }
+        checker
+            .declare_enumerators(enumerators)
+            .map_err(|error| error.located(*span))?;
+    }
+    for declaration in declarations {
+        checker.check_static_declaration(declaration)?;
+    }
+    for function in functions {
+        // Errors in the body are located at the offending code, the others at the declarator.
+        let CmmFunction::Function { span, .. } = function;
+        checker
+            .check_function(function)
+            .map_err(|error| error.located(*span))?;
+    }
+    Ok(checker.annotations)
+}
+
+/// Walks the functions of a program, annotating their expressions.
+struct TypeChecker<'a> {
+    /// The arena of the program.
+    expressions: &'a ExpressionArena,
+    /// The members of the structure types declared so far, by tag.
+    structs: FxHashMap<&'a Symbol, &'a [CmmMember]>,
+    /// The layouts of the structure types declared so far, which give the sizes of types.
+    types: TypeTable,
+    /// The name of the function being checked, whose return type `return` statements follow.
+    current_function: Option<&'a Symbol>,
+    /// The expression of the expression statement being checked. Its value is discarded, so it may
+    /// be a call to a `void` function.
+    discarded_expression: Option<ExprId>,
+    /// The number of loops enclosing the statement being checked.
+    loop_depth: usize,
+    /// The annotations built so far.
+    annotations: TypeAnnotations,
+}
+
+impl<'a> TypeChecker<'a> {
+    /// Declares a structure type, whose members must have distinct names and complete types, and
+    /// which must not be larger than the largest object. A structure is complete once it is
+    /// declared, so it cannot contain itself.
+    fn declare_struct(
+        &mut self,
+        tag: &'a Symbol,
+        members: &'a [CmmMember],
+    ) -> Result<(), SemanticError> {
+        if self.structs.contains_key(tag) {
+            return Err(SemanticError::StructRedefinition { tag: tag.clone() });
+        }
+        let mut member_names = FxHashSet::default();
+        for CmmMember::Member {
+            identifier,
+            member_type,
+        } in members
+        {
+            if !member_names.insert(identifier) {
+                return Err(SemanticError::DuplicateMember {
+                    tag: tag.clone(),
+                    member: identifier.clone(),
+                });
+            }
+            self.check_complete(member_type)?;
+        }
+        self.types
+            .declare_struct(tag, members)
+            .ok_or_else(|| SemanticError::TypeTooLarge {
+                found: CmmType::Struct(tag.clone()),
+            })?;
+        self.structs.insert(tag, members);
+        Ok(())
+    }
+
+    /// Evaluates the values of the enumerators of an enumeration, which are `int` constants.
+    ///
+    /// An enumerator without a value is one more than the previous enumerator, and the first
+    /// enumerator is zero. A value can refer to the enumerators declared before it, as in
+    /// `enum { A = 1, B = A * 2 };`.
+    fn declare_enumerators(&mut self, enumerators: &[CmmEnumerator]) -> Result<(), SemanticError> {
+        let mut next_value = Some(0i32);
+        for CmmEnumerator::Enumerator { identifier, value } in enumerators {
+            if self.annotations.enumerators.contains_key(identifier) {
+                return Err(SemanticError::EnumeratorRedefinition {
+                    identifier: identifier.clone(),
+                });
+            }
+            let value = match value {
+                Some(value) => {
+                    self.check_expression(*value)?;
+                    self.constant_value(*value)
+                        .ok_or_else(|| SemanticError::InvalidEnumerator {
+                            identifier: identifier.clone(),
+                        })?
+                        .to_int()
+                }
+                None => next_value,
+            };
+            let value = value.ok_or_else(|| SemanticError::EnumeratorOutOfRange {
+                identifier: identifier.clone(),
+            })?;
+            self.annotations
+                .enumerators
+                .insert(identifier.clone(), value);
+            next_value = value.checked_add(1);
+        }
+        Ok(())
+    }
+
+    fn check_function(&mut self, function: &'a CmmFunction) -> Result<(), SemanticError> {
+        let CmmFunction::Function {
+            identifier,
+            return_type,
+            parameters,
+            body,
+            ..
+        } = function;
+        // Structures are passed by pointer, since they are not values. Nor are they returned, since
+        // they do not fit a return register.
+        if return_type
+            .iter()
+            .chain(
+                parameters
+                    .iter()
+                    .map(|CmmParameter::Parameter { parameter_type, .. }| parameter_type),
+            )
+            .any(is_struct)
+        {
+            return Err(SemanticError::StructUsedAsValue);
+        }
+        self.current_function = Some(identifier);
+        for item in body.iter().flatten() {
+            match item {
+                CmmBlockItem::Declaration(
+                    declaration @ CmmDeclaration::Declaration {
+                        storage_class: Some(CmmStorageClass::Static),
+                        ..
+                    },
+                ) => self.check_static_declaration(declaration)?,
+                CmmBlockItem::Declaration(declaration) => self.check_declaration(declaration)?,
+                CmmBlockItem::Statement(statement) => self.check_statement(statement)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks a declaration of a variable, see `check_initializer`, and locates its errors at the
+    /// declaration unless they are located at an expression of the initializer.
+    fn check_declaration(&mut self, declaration: &CmmDeclaration) -> Result<(), SemanticError> {
+        let CmmDeclaration::Declaration {
+            variable_type,
+            initializer,
+            span,
+            ..
+        } = declaration;
+        self.check_initializer(variable_type, initializer.as_ref())
+            .map_err(|error| error.located(*span))
+    }
+
+    /// Checks a declaration of a variable with static storage duration, a file-scope variable or a
+    /// `static` local variable, like `check_declaration`. The variable is initialized before the
+    /// program starts, so every expression of its initializer must be an arithmetic constant
+    /// expression, see `evaluate_arithmetic_constant`.
+    fn check_static_declaration(
+        &mut self,
+        declaration: &CmmDeclaration,
+    ) -> Result<(), SemanticError> {
+        self.check_declaration(declaration)?;
+        let CmmDeclaration::Declaration {
+            identifier,
+            initializer,
+            span,
+            ..
+        } = declaration;
+        match initializer {
+            Some(initializer) => self
+                .check_constant_initializer(identifier, initializer)
+                .map_err(|error| error.located(*span)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that every expression of an initializer is an arithmetic constant expression.
+    fn check_constant_initializer(
+        &self,
+        identifier: &Symbol,
+        initializer: &CmmInitializer,
+    ) -> Result<(), SemanticError> {
+        match initializer {
+            CmmInitializer::Single(expression) => {
+                match evaluate_arithmetic_constant(
+                    self.expressions,
+                    *expression,
+                    &|variable, identifier| self.enumerator_value(variable, identifier),
+                ) {
+                    Some(_) => Ok(()),
+                    None => Err(SemanticError::NonConstantInitializer {
+                        identifier: identifier.clone(),
+                    }
+                    .located(self.expressions.span(*expression))),
+                }
+            }
+            CmmInitializer::Compound(initializers) => {
+                initializers.iter().try_for_each(|initializer| {
+                    self.check_constant_initializer(identifier, initializer)
+                })
+            }
+        }
+    }
+
+    /// Checks the type of a declared variable, which must be complete, and its initializer.
+    ///
+    /// A variable is initialized with an expression converting to its type, and an array of
+    /// scalars with a braced list, see `check_array_initializer`. Structures and arrays of
+    /// structures cannot be initialized.
+    fn check_initializer(
+        &mut self,
+        variable_type: &CmmType,
+        initializer: Option<&CmmInitializer>,
+    ) -> Result<(), SemanticError> {
+        self.check_complete(variable_type)?;
+        match (initializer, variable_type) {
+            (None, _) => Ok(()),
+            (Some(CmmInitializer::Single(initializer)), _)
+                if !matches!(
+                    variable_type.unqualified(),
+                    CmmType::Array(_, _) | CmmType::Struct(_)
+                ) =>
+            {
+                let value_type = self.check_expression(*initializer)?;
+                self.check_assignment(*initializer, value_type, variable_type)
+            }
+            (Some(initializer @ CmmInitializer::Compound(_)), CmmType::Array(_, _))
+                if !is_struct(variable_type.scalar_type()) =>
+            {
+                self.check_array_initializer(initializer, variable_type)
+            }
+            (Some(_), _) => Err(SemanticError::InvalidInitializer),
+        }
+    }
+
+    /// Checks the initializer of an array of scalars, or of one of its elements. An array is
+    /// initialized with a braced list of at most one initializer per element, and a scalar with an
+    /// expression converting to its type.
+    fn check_array_initializer(
+        &mut self,
+        initializer: &CmmInitializer,
+        target_type: &CmmType,
+    ) -> Result<(), SemanticError> {
+        match (initializer, target_type) {
+            (CmmInitializer::Compound(initializers), CmmType::Array(element_type, size)) => {
+                if initializers.len() > *size {
+                    return Err(SemanticError::TooManyInitializers {
+                        expected: *size,
+                        actual: initializers.len(),
+                    });
+                }
+                initializers.iter().try_for_each(|initializer| {
+                    self.check_array_initializer(initializer, element_type)
+                })
+            }
+            (CmmInitializer::Single(expression), scalar_type)
+                if !matches!(scalar_type, CmmType::Array(_, _)) =>
+            {
+                let value_type = self.check_expression(*expression)?;
+                self.check_assignment(*expression, value_type, scalar_type)
+            }
+            _ => Err(SemanticError::InvalidInitializer),
+        }
+    }
+
+    fn check_statement(&mut self, statement: &CmmStatement) -> Result<(), SemanticError> {
+        match statement {
+            CmmStatement::Return { expression, span } => self
+                .check_return(*expression)
+                .map_err(|error| error.located(*span)),
+            CmmStatement::Expression { expression, .. } => {
+                self.discarded_expression = Some(*expression);
+                self.check_expression(*expression).map(|_| ())
+            }
+            CmmStatement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.check_expression(*condition)?;
+                self.check_statement(then_branch)?;
+                match else_branch {
+                    Some(else_branch) => self.check_statement(else_branch),
+                    None => Ok(()),
+                }
+            }
+            CmmStatement::While {
+                condition, body, ..
+            }
+            | CmmStatement::DoWhile {
+                body, condition, ..
+            } => {
+                self.check_expression(*condition)?;
+                self.check_loop_body(body)
+            }
+            CmmStatement::For {
+                init,
+                condition,
+                post,
+                body,
+                ..
+            } => {
+                match init {
+                    CmmForInit::Declaration(declaration) => self.check_declaration(declaration)?,
+                    CmmForInit::Expression(expression) => {
+                        self.check_optional_expression(*expression)?
+                    }
+                }
+                self.check_optional_expression(*condition)?;
+                self.check_optional_expression(*post)?;
+                self.check_loop_body(body)
+            }
+            CmmStatement::Break { span } if self.loop_depth == 0 => {
+                Err(SemanticError::BreakOutsideLoop.located(*span))
+            }
+            CmmStatement::Continue { span } if self.loop_depth == 0 => {
+                Err(SemanticError::ContinueOutsideLoop.located(*span))
+            }
+            CmmStatement::Null { .. }
+            | CmmStatement::Break { .. }
+            | CmmStatement::Continue { .. } => Ok(()),
+        }
+    }
+
+    /// Checks a `return` statement, which returns a value converting to the return type of the
+    /// function, or no value from a `void` function.
+    fn check_return(&mut self, expression: Option<ExprId>) -> Result<(), SemanticError> {
+        let identifier = self
+            .current_function
+            .expect("Statements are checked inside a function");
+        let return_type = self
+            .annotations
+            .function_type(identifier)
+            .and_then(|function_type| function_type.return_type.clone());
+        match (expression, return_type) {
+            (Some(expression), Some(return_type)) => {
+                let value_type = self.check_expression(expression)?;
+                self.check_assignment(expression, value_type, &return_type)
+            }
+            (None, None) => Ok(()),
+            (Some(_), None) => Err(SemanticError::ReturnValueInVoidFunction {
+                identifier: identifier.clone(),
+            }),
+            (None, Some(_)) => Err(SemanticError::MissingReturnValue {
+                identifier: identifier.clone(),
+            }),
+        }
+    }
+
+    /// Checks the body of a loop, in which `break` and `continue` statements are allowed.
+    fn check_loop_body(&mut self, body: &CmmStatement) -> Result<(), SemanticError> {
+        self.loop_depth += 1;
+        let result = self.check_statement(body);
+        self.loop_depth -= 1;
+        result
+    }
+
+    fn check_optional_expression(&mut self, id: Option<ExprId>) -> Result<(), SemanticError> {
+        match id {
+            Some(id) => self.check_expression(id).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// Annotates an expression whose value is used and its operands with their types. A whole
+    /// structure is no value, see `check_object`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The `ExprId` of the expression.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the type of the expression, or `None` if it has no value, or a
+    /// `SemanticError` if an operand has an invalid type.
+    fn check_expression(&mut self, id: ExprId) -> Result<Option<CmmType>, SemanticError> {
+        let expression_type = self.check_object(id)?;
+        if expression_type.as_ref().is_some_and(is_struct) {
+            return Err(SemanticError::StructUsedAsValue.located(self.expressions.span(id)));
+        }
+        Ok(expression_type)
+    }
+
+    /// Annotates an expression and its operands with their types, like `check_expression`. The
+    /// expression may designate a whole structure, as the operands of `.` and `&` do.
+    ///
+    /// An error is located at the innermost expression whose check raised it.
+    fn check_object(&mut self, id: ExprId) -> Result<Option<CmmType>, SemanticError> {
+        self.annotate_object(id)
+            .map_err(|error| error.located(self.expressions.span(id)))
+    }
+
+    /// Annotates an expression and its operands with their types, see `check_object`, without
+    /// locating the errors of the expression itself.
+    fn annotate_object(&mut self, id: ExprId) -> Result<Option<CmmType>, SemanticError> {
+        let expression_type = match &self.expressions[id] {
+            CmmExpression::IntegerConstant { .. } => Some(CmmType::Int),
+            CmmExpression::LongConstant { .. } => Some(CmmType::Long),
+            CmmExpression::UnsignedIntegerConstant { .. } => Some(CmmType::UnsignedInt),
+            CmmExpression::UnsignedLongConstant { .. } => Some(CmmType::UnsignedLong),
+            CmmExpression::DoubleConstant { .. } => Some(CmmType::Double),
+            CmmExpression::StringLiteral { value } => {
+                Some(CmmType::Array(Box::new(CmmType::Char), value.len() + 1))
+            }
+            CmmExpression::Variable { identifier } => {
+                let variables = &self.annotations.variables;
+                match variables.unique_name(id) {
+                    Some(unique_name) => variables.variable_type(unique_name).cloned(),
+                    None => self
+                        .annotations
+                        .enumerators
+                        .contains_key(identifier)
+                        .then_some(CmmType::Int),
+                }
+            }
+            CmmExpression::FunctionCall {
+                identifier,
+                arguments,
+            } => {
+                let FunctionType {
+                    return_type,
+                    parameter_types,
+                    variadic,
+                } = self
+                    .annotations
+                    .function_type(identifier)
+                    .cloned()
+                    .expect("The resolver rejects calls of undeclared functions");
+                // A call to a `void` function has no value, so it can only be discarded.
+                if return_type.is_none() && self.discarded_expression != Some(id) {
+                    return Err(SemanticError::VoidValueUsed {
+                        identifier: identifier.clone(),
+                    });
+                }
+                if arguments.len() < parameter_types.len()
+                    || (!variadic && arguments.len() > parameter_types.len())
+                {
+                    return Err(SemanticError::WrongArgumentCount {
+                        identifier: identifier.clone(),
+                        expected: parameter_types.len(),
+                        actual: arguments.len(),
+                    });
+                }
+                for (index, argument) in arguments.iter().enumerate() {
+                    let argument_type = self.check_expression(*argument)?;
+                    // The further arguments of a variadic function are only promoted.
+                    if let Some(parameter_type) = parameter_types.get(index) {
+                        self.check_assignment(*argument, argument_type, parameter_type)?;
+                    }
+                }
+                return_type
+            }
+            CmmExpression::Cast {
+                target_type,
+                expression,
+            } => {
+                let source_type = self.check_expression(*expression)?;
+                self.check_cast(*expression, source_type, target_type)?;
+                Some(target_type.clone())
+            }
+            CmmExpression::AddressOf { expression } => {
+                let object_type = self.check_object(*expression)?;
+                self.check_lvalue(id, *expression)?;
+                object_type.map(|object_type| CmmType::Pointer(Box::new(object_type)))
+            }
+            CmmExpression::Dereference { expression } => {
+                match self.check_expression(*expression)? {
+                    Some(operand_type) => match referenced_type(&operand_type) {
+                        Some(referenced_type) => Some(referenced_type),
+                        None => return Err(self.invalid_operand(id, operand_type)),
+                    },
+                    None => None,
+                }
+            }
+            CmmExpression::Subscript { array, index } => {
+                let array_type = self.check_expression(*array)?;
+                let index_type = self.check_expression(*index)?;
+                match (array_type.as_ref(), index_type.as_ref()) {
+                    (Some(array_type), Some(index_type)) => {
+                        match (referenced_type(array_type), referenced_type(index_type)) {
+                            (Some(element_type), None) if is_integer(index_type) => {
+                                self.check_complete(&element_type)?;
+                                Some(element_type)
+                            }
+                            (None, Some(element_type)) if is_integer(array_type) => {
+                                self.check_complete(&element_type)?;
+                                Some(element_type)
+                            }
+                            (Some(_), _) => {
+                                return Err(self.invalid_operand(id, index_type.clone()));
+                            }
+                            (None, _) => return Err(self.invalid_operand(id, array_type.clone())),
+                        }
+                    }
+                    (array_type, index_type) => array_type
+                        .and_then(referenced_type)
+                        .or_else(|| index_type.and_then(referenced_type)),
+                }
+            }
+            CmmExpression::Member { structure, member } => {
+                let structure_type = self.check_object(*structure)?;
+                Some(self.member_type(structure_type.as_ref(), member)?)
+            }
+            CmmExpression::PointerMember { pointer, member } => {
+                let pointer_type = self.check_expression(*pointer)?;
+                let structure_type = pointer_type.as_ref().and_then(referenced_type);
+                Some(self.member_type(structure_type.as_ref(), member)?)
+            }
+            CmmExpression::Unary {
+                operator,
+                expression,
+            } => {
+                let operand_type = self.check_expression(*expression)?;
+                if matches!(
+                    operator,
+                    CmmUnaryOperator::PrefixIncrement
+                        | CmmUnaryOperator::PrefixDecrement
+                        | CmmUnaryOperator::PostfixIncrement
+                        | CmmUnaryOperator::PostfixDecrement
+                ) {
+                    self.check_modifiable_lvalue(id, *expression, operand_type.as_ref())?;
+                }
+                self.check_unary(id, operator, operand_type)?
+            }
+            CmmExpression::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                let left_type = self.check_expression(*left)?;
+                let right_type = self.check_expression(*right)?;
+                self.check_binary(id, operator, (*left, left_type), (*right, right_type))?
+            }
+            CmmExpression::Assignment { target, value } => {
+                let target_type = self.check_expression(*target)?;
+                self.check_modifiable_lvalue(id, *target, target_type.as_ref())?;
+                let value_type = self.check_expression(*value)?;
+                if let Some(target_type) = &target_type {
+                    self.check_assignment(*value, value_type, target_type)?;
+                }
+                target_type
+            }
+            CmmExpression::CompoundAssignment {
+                operator,
+                target,
+                value,
+            } => {
+                let target_type = self.check_expression(*target)?;
+                self.check_modifiable_lvalue(id, *target, target_type.as_ref())?;
+                let value_type = self.check_expression(*value)?;
+                // The result of the operation is converted back to the type of the target.
+                let result_type = self.check_binary(
+                    id,
+                    operator,
+                    (*target, target_type.clone()),
+                    (*value, value_type),
+                )?;
+                if let Some(target_type) = &target_type {
+                    self.check_assignment(id, result_type, target_type)?;
+                }
+                target_type
+            }
+        };
+        if let Some(expression_type) = &expression_type {
+            self.annotations
+                .expression_types
+                .insert(id, expression_type.clone());
+        }
+        Ok(expression_type)
+    }
+
+    /// Checks the operand of a unary operator. The operand of `+` and `-` must be arithmetic, the
+    /// operand of `~` an integer, and the other operands scalars, which are numbers or pointers.
+    fn check_unary(
+        &self,
+        id: ExprId,
+        operator: &CmmUnaryOperator,
+        operand_type: Option<CmmType>,
+    ) -> Result<Option<CmmType>, SemanticError> {
+        let Some(operand_type) = operand_type.map(|operand_type| operand_type.decayed()) else {
+            return Ok(None);
+        };
+        let is_valid = match operator {
+            CmmUnaryOperator::Plus | CmmUnaryOperator::Negate => operand_type.is_arithmetic(),
+            CmmUnaryOperator::Complement => is_integer(&operand_type),
+            CmmUnaryOperator::Not
+            | CmmUnaryOperator::PrefixIncrement
+            | CmmUnaryOperator::PrefixDecrement
+            | CmmUnaryOperator::PostfixIncrement
+            | CmmUnaryOperator::PostfixDecrement => is_scalar(&operand_type),
+        };
+        if !is_valid {
+            return Err(self.invalid_operand(id, operand_type));
+        }
+        // Incrementing a pointer moves it by the size of the object it points to.
+        if let Some(referenced_type) = referenced_type(&operand_type) {
+            self.check_complete(&referenced_type)?;
+        }
+        Ok(Some(match operator {
+            CmmUnaryOperator::Plus | CmmUnaryOperator::Negate | CmmUnaryOperator::Complement => {
+                operand_type.promoted()
+            }
+            CmmUnaryOperator::Not => CmmType::Int,
+            _ => operand_type,
+        }))
+    }
+
+    /// Checks the operands of a binary operator, or of the operation of a compound assignment.
+    ///
+    /// The operands of `*` and `/` must be arithmetic, and the operands of `%`, the bitwise
+    /// operators, and the shifts integers. `+` also adds an integer to a pointer, and `-` also
+    /// subtracts an integer or a pointer to the same type from a pointer, where the pointers must
+    /// point to complete types. Comparisons and logical operators accept any scalars, but pointers
+    /// are only compared with pointers to the same type, or tested for equality with the null
+    /// pointer constant.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The `ExprId` of the operation.
+    /// * `operator`: The binary operator.
+    /// * `left`: The `ExprId` of the left operand and its type, or `None` if it has no value.
+    /// * `right`: The `ExprId` of the right operand and its type, or `None` if it has no value.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the type of the operation, or a `SemanticError` if the operands
+    /// cannot be operated on.
+    fn check_binary(
+        &self,
+        id: ExprId,
+        operator: &CmmBinaryOperator,
+        (left, left_type): (ExprId, Option<CmmType>),
+        (right, right_type): (ExprId, Option<CmmType>),
+    ) -> Result<Option<CmmType>, SemanticError> {
+        let (Some(left_type), Some(right_type)) = (left_type, right_type) else {
+            return Ok(None);
+        };
+        let (left_type, right_type) = (left_type.decayed(), right_type.decayed());
+        let invalid_type = match operator {
+            CmmBinaryOperator::Multiply | CmmBinaryOperator::Divide => [&left_type, &right_type]
+                .into_iter()
+                .find(|operand_type| !operand_type.is_arithmetic()),
+            CmmBinaryOperator::Remainder
+            | CmmBinaryOperator::BitwiseAnd
+            | CmmBinaryOperator::BitwiseOr
+            | CmmBinaryOperator::BitwiseXor
+            | CmmBinaryOperator::LeftShift
+            | CmmBinaryOperator::RightShift => [&left_type, &right_type]
+                .into_iter()
+                .find(|operand_type| !is_integer(operand_type)),
+            CmmBinaryOperator::Add => match (is_pointer(&left_type), is_pointer(&right_type)) {
+                (true, _) => (!is_integer(&right_type)).then_some(&right_type),
+                (false, true) => (!is_integer(&left_type)).then_some(&left_type),
+                (false, false) => [&left_type, &right_type]
+                    .into_iter()
+                    .find(|operand_type| !operand_type.is_arithmetic()),
+            },
+            CmmBinaryOperator::Subtract => {
+                match (is_pointer(&left_type), is_pointer(&right_type)) {
+                    (true, true) => None,
+                    (true, false) => (!is_integer(&right_type)).then_some(&right_type),
+                    (false, _) => [&left_type, &right_type]
+                        .into_iter()
+                        .find(|operand_type| !operand_type.is_arithmetic()),
+                }
+            }
+            CmmBinaryOperator::And
+            | CmmBinaryOperator::Or
+            | CmmBinaryOperator::Equal
+            | CmmBinaryOperator::NotEqual
+            | CmmBinaryOperator::GreaterThan
+            | CmmBinaryOperator::LessThan
+            | CmmBinaryOperator::GreaterThanEqual
+            | CmmBinaryOperator::LessThanEqual => [&left_type, &right_type]
+                .into_iter()
+                .find(|operand_type| !is_scalar(operand_type)),
+        };
+        if let Some(invalid_type) = invalid_type {
+            return Err(self.invalid_operand(id, invalid_type.clone()));
+        }
+        let referenced_types = (left_type.referenced_type(), right_type.referenced_type());
+        match operator {
+            CmmBinaryOperator::Equal
+            | CmmBinaryOperator::NotEqual
+            | CmmBinaryOperator::GreaterThan
+            | CmmBinaryOperator::LessThan
+            | CmmBinaryOperator::GreaterThanEqual
+            | CmmBinaryOperator::LessThanEqual => {
+                let is_equality = matches!(
+                    operator,
+                    CmmBinaryOperator::Equal | CmmBinaryOperator::NotEqual
+                );
+                match referenced_types {
+                    (Some(left_referenced), Some(right_referenced))
+                        if left_referenced.unqualified() != right_referenced.unqualified() =>
+                    {
+                        return Err(SemanticError::IncompatiblePointerTypes);
+                    }
+                    (Some(_), Some(_)) | (None, None) => {}
+                    (Some(_), None) if is_equality && self.is_null_pointer_constant(right) => {}
+                    (None, Some(_)) if is_equality && self.is_null_pointer_constant(left) => {}
+                    _ => return Err(SemanticError::PointerUsedAsInteger),
+                }
+            }
+            CmmBinaryOperator::Add | CmmBinaryOperator::Subtract => match referenced_types {
+                (Some(left_referenced), Some(right_referenced)) => {
+                    if left_referenced.unqualified() != right_referenced.unqualified() {
+                        return Err(SemanticError::IncompatiblePointerTypes);
+                    }
+                    self.check_complete(left_referenced)?;
+                }
+                (Some(referenced_type), None) | (None, Some(referenced_type)) => {
+                    self.check_complete(referenced_type)?;
+                }
+                (None, None) => {}
+            },
+            _ => {}
+        }
+        Ok(Some(match operator {
+            CmmBinaryOperator::And
+            | CmmBinaryOperator::Or
+            | CmmBinaryOperator::Equal
+            | CmmBinaryOperator::NotEqual
+            | CmmBinaryOperator::GreaterThan
+            | CmmBinaryOperator::LessThan
+            | CmmBinaryOperator::GreaterThanEqual
+            | CmmBinaryOperator::LessThanEqual => CmmType::Int,
+            CmmBinaryOperator::LeftShift | CmmBinaryOperator::RightShift => left_type.promoted(),
+            CmmBinaryOperator::Subtract if is_pointer(&left_type) && is_pointer(&right_type) => {
+                CmmType::Long
+            }
+            _ if is_pointer(&left_type) => left_type,
+            _ if is_pointer(&right_type) => right_type,
+            _ => left_type.common_type(&right_type),
+        }))
+    }
+
+    /// Returns the type of a member of a structure. The members of a `const` qualified structure
+    /// are `const` qualified as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `structure_type`: The type of the structure, or `None` if the operand has no value.
+    /// * `member`: The name of the member.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the type of the member, or a `SemanticError` if the type is no
+    /// declared structure with a member of the name.
+    fn member_type(
+        &self,
+        structure_type: Option<&CmmType>,
+        member: &Symbol,
+    ) -> Result<CmmType, SemanticError> {
+        let Some(structure_type) = structure_type else {
+            return Err(SemanticError::MemberOfNonStruct);
+        };
+        let CmmType::Struct(tag) = structure_type.unqualified() else {
+            return Err(SemanticError::MemberOfNonStruct);
+        };
+        let members = self
+            .structs
+            .get(tag)
+            .ok_or_else(|| SemanticError::UndeclaredStruct { tag: tag.clone() })?;
+        let member_type = members
+            .iter()
+            .find_map(
+                |CmmMember::Member {
+                     identifier,
+                     member_type,
+                 }| (identifier == member).then(|| member_type.clone()),
+            )
+            .ok_or_else(|| SemanticError::UnknownMember {
+                tag: tag.clone(),
+                member: member.clone(),
+            })?;
+        Ok(if structure_type.is_const() {
+            member_type.qualified()
+        } else {
+            member_type
+        })
+    }
+
+    /// Checks that a value converts implicitly to the type of the object it is assigned to, which
+    /// assignments, initializers, arguments, and `return` statements need.
+    ///
+    /// Arithmetic values convert to each other, and every scalar converts to `_Bool`. A pointer
+    /// only converts to a pointer to the same type, which may add a `const` qualifier to the type
+    /// it points to, but not drop one, and to no arithmetic type. The null pointer constant
+    /// converts to every pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The `ExprId` of the assigned value.
+    /// * `value_type`: The type of the value, or `None` if it has no value.
+    /// * `target_type`: The type of the object the value is assigned to.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the value does not convert to the type.
+    fn check_assignment(
+        &self,
+        value: ExprId,
+        value_type: Option<CmmType>,
+        target_type: &CmmType,
+    ) -> Result<(), SemanticError> {
+        let Some(value_type) = value_type.map(|value_type| value_type.decayed()) else {
+            return Ok(());
+        };
+        match (target_type.unqualified(), value_type.referenced_type()) {
+            (CmmType::Pointer(referenced_type), Some(value_referenced_type)) => {
+                if value_referenced_type == referenced_type.as_ref()
+                    || (referenced_type.is_const()
+                        && value_referenced_type.unqualified() == referenced_type.unqualified())
+                {
+                    Ok(())
+                } else {
+                    Err(SemanticError::IncompatiblePointerTypes)
+                }
+            }
+            (CmmType::Pointer(_), None) if self.is_null_pointer_constant(value) => Ok(()),
+            (CmmType::Pointer(_), None) => Err(SemanticError::NonPointerUsedAsPointer),
+            (CmmType::Bool, _) | (_, None) => Ok(()),
+            (_, Some(_)) => Err(SemanticError::PointerUsedAsInteger),
+        }
+    }
+
+    /// Checks the operand of a cast. Unlike an assignment, a cast converts a pointer to a pointer
+    /// to another type, but still only the null pointer constant to a pointer, and no pointer to an
+    /// arithmetic type other than `_Bool`. A structure is no value, so nothing is cast to one.
+    ///
+    /// # Arguments
+    ///
+    /// * `operand`: The `ExprId` of the operand.
+    /// * `operand_type`: The type of the operand, or `None` if it has no value.
+    /// * `target_type`: The type the operand is cast to.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the operand cannot be cast to the type.
+    fn check_cast(
+        &self,
+        operand: ExprId,
+        operand_type: Option<CmmType>,
+        target_type: &CmmType,
+    ) -> Result<(), SemanticError> {
+        if is_struct(target_type) {
+            return Err(SemanticError::StructUsedAsValue);
+        }
+        let Some(operand_type) = operand_type.map(|operand_type| operand_type.decayed()) else {
+            return Ok(());
+        };
+        match (target_type.unqualified(), is_pointer(&operand_type)) {
+            (CmmType::Pointer(_), false) if !self.is_null_pointer_constant(operand) => {
+                Err(SemanticError::NonPointerUsedAsPointer)
+            }
+            (CmmType::Pointer(_), _) | (CmmType::Bool, _) | (_, false) => Ok(()),
+            (_, true) => Err(SemanticError::PointerUsedAsInteger),
+        }
+    }
+
+    /// Checks that a type is complete, so that the size of its objects is known. A structure is
+    /// complete once it is declared, and an array once its elements are, unless it is larger than
+    /// the largest object.
+    fn check_complete(&self, cmm_type: &CmmType) -> Result<(), SemanticError> {
+        match cmm_type.unqualified() {
+            CmmType::Array(element_type, _) => self.check_complete(element_type)?,
+            CmmType::Struct(tag) if !self.structs.contains_key(tag) => {
+                return Err(SemanticError::UndeclaredStruct { tag: tag.clone() });
+            }
+            _ => {}
+        }
+        match self.types.checked_size(cmm_type) {
+            Some(_) => Ok(()),
+            None => Err(SemanticError::TypeTooLarge {
+                found: cmm_type.clone(),
+            }),
+        }
+    }
+
+    /// Returns whether an expression is the null pointer constant, an integer constant expression
+    /// of zero, see `constant_value`.
+    fn is_null_pointer_constant(&self, id: ExprId) -> bool {
+        self.annotations.expression_type(id).is_some_and(is_integer)
+            && self
+                .constant_value(id)
+                .is_some_and(ArithmeticConstant::is_zero)
+    }
+
+    /// Evaluates an integer constant expression, see `evaluate_constant`. An identifier refers to
+    /// the enumerators declared so far, unless it names a variable.
+    fn constant_value(&self, id: ExprId) -> Option<ArithmeticConstant> {
+        evaluate_constant(self.expressions, id, &|variable, identifier| {
+            self.enumerator_value(variable, identifier)
+        })
+    }
+
+    /// Returns the value of the enumerator a variable expression refers to, or `None` if it refers
+    /// to a variable.
+    fn enumerator_value(&self, variable: ExprId, identifier: &Symbol) -> Option<i32> {
+        match self.annotations.variables.unique_name(variable) {
+            Some(_) => None,
+            None => self.annotations.enumerator_value(identifier),
+        }
+    }
+
+    /// Checks that an operand designates an object, which assignments, increments, decrements, and
+    /// `&` need. Variables, dereferenced pointers, subscripts, and the members of such objects are
+    /// lvalues, while the values of other expressions and enumerators are not stored anywhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The `ExprId` of the assignment, increment, decrement, or `&`.
+    /// * `operand`: The `ExprId` of the operand that must be an lvalue.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the operand is not an lvalue.
+    fn check_lvalue(&self, id: ExprId, operand: ExprId) -> Result<(), SemanticError> {
+        if self.is_lvalue(operand) {
+            return Ok(());
+        }
+        Err(SemanticError::InvalidLvalue {
+            operand: print_expression(self.expressions, operand),
+            expression: print_expression(self.expressions, id),
+        })
+    }
+
+    /// Checks that an operand designates an object that can be modified, which assignments,
+    /// increments, and decrements need. The operand must be an lvalue, see `check_lvalue`, and
+    /// must neither be an array, whose elements can only be modified one by one, nor an object of a
+    /// `const` qualified type.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The `ExprId` of the assignment, increment, or decrement.
+    /// * `operand`: The `ExprId` of the modified operand.
+    /// * `operand_type`: The type of the operand, or `None` if it is unknown.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or a `SemanticError` if the operand cannot be modified.
+    fn check_modifiable_lvalue(
+        &self,
+        id: ExprId,
+        operand: ExprId,
+        operand_type: Option<&CmmType>,
+    ) -> Result<(), SemanticError> {
+        self.check_lvalue(id, operand)?;
+        match operand_type {
+            Some(CmmType::Array(_, _)) => Err(SemanticError::ArrayAssignment {
+                operand: print_expression(self.expressions, operand),
+                expression: print_expression(self.expressions, id),
+            }),
+            Some(target_type) if target_type.is_const() => Err(SemanticError::AssignmentToConst {
+                expression: print_expression(self.expressions, id),
+                target_type: target_type.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn is_lvalue(&self, id: ExprId) -> bool {
+        match &self.expressions[id] {
+            CmmExpression::Variable { .. } => self.annotations.variables.unique_name(id).is_some(),
+            CmmExpression::Dereference { .. }
+            | CmmExpression::Subscript { .. }
+            | CmmExpression::PointerMember { .. } => true,
+            CmmExpression::Member { structure, .. } => self.is_lvalue(*structure),
+            _ => false,
+        }
+    }
+
+    /// Builds the error for an operand of an invalid type in an expression.
+    fn invalid_operand(&self, id: ExprId, operand_type: CmmType) -> SemanticError {
+        SemanticError::InvalidOperandType {
+            expression: print_expression(self.expressions, id),
+            operand_type: operand_type.unqualified().clone(),
+        }
+    }
+}
+
+/// Returns the type of the objects a pointer or an array refers to, or `None` for other types.
+fn referenced_type(pointer_type: &CmmType) -> Option<CmmType> {
+    pointer_type.decayed().referenced_type().cloned()
+}
+
+/// Returns `true` if the type is a pointer, possibly `const` qualified.
+fn is_pointer(operand_type: &CmmType) -> bool {
+    operand_type.referenced_type().is_some()
+}
+
+/// Returns `true` if the type is an integer type, which are the arithmetic types but `double`.
+fn is_integer(operand_type: &CmmType) -> bool {
+    operand_type.is_arithmetic() && operand_type.unqualified() != &CmmType::Double
+}
+
+/// Returns `true` if the type is a structure, possibly `const` qualified.
+fn is_struct(cmm_type: &CmmType) -> bool {
+    matches!(cmm_type.unqualified(), CmmType::Struct(_))
+}
+
+/// Returns `true` if values of the type are numbers or pointers, which can be compared with zero.
+fn is_scalar(operand_type: &CmmType) -> bool {
+    operand_type.is_arithmetic() || is_pointer(operand_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::parse_source;
+    use crate::compiler::semantic::resolver::resolve_variables;
+    use crate::compiler::semantic::symbol_table::Linkage;
+
+    fn check(source_code: &str) -> Result<TypeAnnotations, SemanticError> {
+        let cmm_ast = parse_source(source_code).unwrap();
+        let variables = resolve_variables(&cmm_ast).unwrap();
+        check_types(&cmm_ast, variables).map_err(SemanticError::unlocated)
+    }
+
+    /// Returns the printed expressions of a program with their types, in arena order.
+    fn typed_expressions(source_code: &str) -> Vec<String> {
+        let cmm_ast = parse_source(source_code).unwrap();
+        let variables = resolve_variables(&cmm_ast).unwrap();
+        let annotations = check_types(&cmm_ast, variables).unwrap();
+        let CmmAst::Program { expressions, .. } = &cmm_ast;
+        expressions
+            .iter()
+            .map(|(id, _)| match annotations.expression_type(id) {
+                Some(expression_type) => {
+                    format!("{}: {}", print_expression(expressions, id), expression_type)
+                }
+                None => format!("{}: void", print_expression(expressions, id)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_annotate_expression_types() {
+        assert_eq!(
+            typed_expressions(
+                "void f(void);
+                 int main(void) {
+                     char c = 'a';
+                     int a[2] = {1, 2};
+                     f();
+                     return (c << 1L) + a[1] * 2.0 > (a + 1) - a;
+                 }"
+            ),
+            vec![
+                "97: int",
+                "1: int",
+                "2: int",
+                "f(): void",
+                "c: char",
+                "1L: long",
+                "c << 1L: int",
+                "a: int [2]",
+                "1: int",
+                "a[1]: int",
+                "2.0: double",
+                "a[1] * 2.0: double",
+                "(c << 1L) + a[1] * 2.0: double",
+                "a: int [2]",
+                "1: int",
+                "a + 1: int *",
+                "a: int [2]",
+                "a + 1 - a: long",
+                "(c << 1L) + a[1] * 2.0 > a + 1 - a: int",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_function_and_variable_types() {
+        let annotations =
+            check("static int f(int values[3], long n, ...);\nint f(int values[3], long n, ...) { return n; }")
+                .unwrap();
+        assert_eq!(
+            annotations.symbols().lookup("f").unwrap().linkage,
+            Linkage::Internal
+        );
+        assert_eq!(
+            annotations.function_type(&"f".into()),
+            Some(&FunctionType {
+                return_type: Some(CmmType::Int),
+                parameter_types: vec![CmmType::Pointer(Box::new(CmmType::Int)), CmmType::Long],
+                variadic: true,
+            })
+        );
+        assert_eq!(
+            annotations.variables().variable_type(&"values.0".into()),
+            Some(&CmmType::Pointer(Box::new(CmmType::Int)))
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_operand_types() {
+        for (source, expression, operand_type) in [
+            (
+                "int main(void) { double d = 1.0; return ~d; }",
+                "~d",
+                CmmType::Double,
+            ),
+            (
+                "int main(void) { int x = 1; int *p = &x; return p * 2; }",
+                "p * 2",
+                CmmType::Pointer(Box::new(CmmType::Int)),
+            ),
+            (
+                "int main(void) { int x = 1; return *x; }",
+                "*x",
+                CmmType::Int,
+            ),
+            (
+                "int main(void) { double d = 1.0; d <<= 2; return 0; }",
+                "d <<= 2",
+                CmmType::Double,
+            ),
+            (
+                "int main(void) { int a[2] = {1, 2}; return a[1.0]; }",
+                "a[1.0]",
+                CmmType::Double,
+            ),
+            (
+                "int main(void) { return -\"a\"; }",
+                "-\"a\"",
+                CmmType::Pointer(Box::new(CmmType::Char)),
+            ),
+            (
+                "int f(int x) { return +&x == 0; }",
+                "+&x",
+                CmmType::Pointer(Box::new(CmmType::Int)),
+            ),
+            (
+                "int f(int *p) { return p % 2; }",
+                "p % 2",
+                CmmType::Pointer(Box::new(CmmType::Int)),
+            ),
+        ] {
+            assert_eq!(
+                check(source),
+                Err(SemanticError::InvalidOperandType {
+                    expression: expression.to_string(),
+                    operand_type,
+                }),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_lvalues() {
+        for (source, operand, expression) in [
+            (
+                "int main(void) { int x = 1; 1 = x; return x; }",
+                "1",
+                "1 = x",
+            ),
+            (
+                "int main(void) { int a = 1; int b = 2; (a + b)++; return a; }",
+                "a + b",
+                "(a + b)++",
+            ),
+            (
+                "enum color { RED };\nint main(void) { return --RED; }",
+                "RED",
+                "--RED",
+            ),
+            (
+                "int f(void);\nint main(void) { f() += 2; return 0; }",
+                "f()",
+                "f() += 2",
+            ),
+            (
+                "int main(void) { int x = 1; int *p = &(x = 2); return *p; }",
+                "x = 2",
+                "&(x = 2)",
+            ),
+            ("int f(int x) { return +x = 1; }", "+x", "+x = 1"),
+            ("enum { A };\nint main(void) { return *&A; }", "A", "&A"),
+        ] {
+            assert_eq!(
+                check(source),
+                Err(SemanticError::InvalidLvalue {
+                    operand: operand.to_string(),
+                    expression: expression.to_string(),
+                }),
+                "{}",
+                source
+            );
+        }
+        assert!(
+            check(
+                "struct s { int m; };\n\
+                 int main(void) { struct s v; struct s *p = &v; int a[2]; \
+                 v.m = 1; p->m++; a[0] = *&v.m; *(a + 1) -= 2; return 0; }"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_array_assignments() {
+        for (source, operand, expression) in [
+            (
+                "int main(void) { int a[2]; int b[2]; a = b; return 0; }",
+                "a",
+                "a = b",
+            ),
+            (
+                "int main(void) { int a[2][2]; a[1]++; return 0; }",
+                "a[1]",
+                "a[1]++",
+            ),
+            (
+                "int main(void) { char s[3]; s += 1; return 0; }",
+                "s",
+                "s += 1",
+            ),
+        ] {
+            assert_eq!(
+                check(source).map(|_| ()),
+                Err(SemanticError::ArrayAssignment {
+                    operand: operand.to_string(),
+                    expression: expression.to_string(),
+                }),
+                "{}",
+                source
+            );
+        }
+        assert!(
+            check("int main(void) { int a[2][2]; int *p = a[1]; a[1][0]++; return *p; }").is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_assignments_to_const() {
+        let constant = |cmm_type| CmmType::Const(Box::new(cmm_type));
+        for (source, expression, target_type) in [
+            (
+                "int main(void) { const int x = 1; return x = 2; }",
+                "x = 2",
+                constant(CmmType::Int),
+            ),
+            (
+                "int f(const long x) { return x++; }",
+                "x++",
+                constant(CmmType::Long),
+            ),
+            (
+                "int f(int x) { const int *p = &x; return *p += 1; }",
+                "*p += 1",
+                constant(CmmType::Int),
+            ),
+            (
+                "int f(int x) { int *const p = &x; return !(p = 0); }",
+                "p = 0",
+                constant(CmmType::Pointer(Box::new(CmmType::Int))),
+            ),
+            (
+                "int main(void) { const char a[2] = {1, 2}; return --a[0]; }",
+                "--a[0]",
+                constant(CmmType::Char),
+            ),
+            (
+                "struct s { int x; };\nint main(void) { const struct s v; return v.x = 1; }",
+                "v.x = 1",
+                constant(CmmType::Int),
+            ),
+        ] {
+            assert_eq!(
+                check(source).map(|_| ()),
+                Err(SemanticError::AssignmentToConst {
+                    expression: expression.to_string(),
+                    target_type,
+                }),
+                "{}",
+                source
+            );
+        }
+        assert!(
+            check(
+                "int f(int x) { const int *p = &x; int *const q = &x; p = q; *q = 2; return *p; }"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_jumps_outside_loops() {
+        for (source, error) in [
+            (
+                "int main(void) { if (1) break; return 0; }",
+                SemanticError::BreakOutsideLoop,
+            ),
+            (
+                "int main(void) { for (;;) ; continue; }",
+                SemanticError::ContinueOutsideLoop,
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(
+            check(
+                "int f(void);\nint f(void) { while (1) do continue; while (0); for (;;) break; return f(); }"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_calls_and_returns() {
+        for (source, error) in [
+            (
+                "int f(int x) { return f(); }",
+                SemanticError::WrongArgumentCount {
+                    identifier: "f".into(),
+                    expected: 1,
+                    actual: 0,
+                },
+            ),
+            (
+                "int f(int a, ...);\nint main(void) { return f(); }",
+                SemanticError::WrongArgumentCount {
+                    identifier: "f".into(),
+                    expected: 1,
+                    actual: 0,
+                },
+            ),
+            (
+                "void f(void) { return 1; }",
+                SemanticError::ReturnValueInVoidFunction {
+                    identifier: "f".into(),
+                },
+            ),
+            (
+                "int main(void) { return; }",
+                SemanticError::MissingReturnValue {
+                    identifier: "main".into(),
+                },
+            ),
+            (
+                "void f(void);\nint main(void) { return f(); }",
+                SemanticError::VoidValueUsed {
+                    identifier: "f".into(),
+                },
+            ),
+            (
+                "void f(void);\nint main(void) { int x = 1; x + f(); return x; }",
+                SemanticError::VoidValueUsed {
+                    identifier: "f".into(),
+                },
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(check("void f(void);\nvoid g(void) { f(); return; }").is_ok());
+        assert_eq!(
+            check("int f(int x) { return f(); }")
+                .unwrap_err()
+                .to_string(),
+            "Semantic error: Function 'f' takes 1 argument, but 0 were given"
+        );
+        assert_eq!(
+            check("int f(void) { return f(1); }")
+                .unwrap_err()
+                .to_string(),
+            "Semantic error: Function 'f' takes 0 arguments, but 1 was given"
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_conversions() {
+        for (source, error) in [
+            (
+                "int f(long x) { int *p = &x; return 1; }",
+                SemanticError::IncompatiblePointerTypes,
+            ),
+            (
+                "int f(int x) { return &x < (long *) 0; }",
+                SemanticError::IncompatiblePointerTypes,
+            ),
+            (
+                "int f(int x, long y) { return &x - &y; }",
+                SemanticError::IncompatiblePointerTypes,
+            ),
+            (
+                "int main(void) { const int x = 1; int *p = &x; return *p; }",
+                SemanticError::IncompatiblePointerTypes,
+            ),
+            (
+                "int f(int x) { int *p = x; return 1; }",
+                SemanticError::NonPointerUsedAsPointer,
+            ),
+            (
+                "int f(int x) { return *(int *) x; }",
+                SemanticError::NonPointerUsedAsPointer,
+            ),
+            (
+                "int main(void) { return \"a\"; }",
+                SemanticError::PointerUsedAsInteger,
+            ),
+            (
+                "int main(void) { return 1 == \"a\"; }",
+                SemanticError::PointerUsedAsInteger,
+            ),
+            (
+                "int main(void) { long l = \"a\"; return 1; }",
+                SemanticError::PointerUsedAsInteger,
+            ),
+            (
+                "int main(void) { int i = 0; i += \"a\"; return 1; }",
+                SemanticError::PointerUsedAsInteger,
+            ),
+            (
+                "int main(void) { return (long) \"a\"; }",
+                SemanticError::PointerUsedAsInteger,
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        for source in [
+            "int main(void) { long *p = 0; return p != 0 && p == p && !p; }",
+            "int main(void) { char *p = (char *) (1 - 1); return (_Bool) p + (p == 0L); }",
+            "int main(void) { if (\"a\" || 0) return 1; return 0; }",
+            "int f(int x) { const int *p = &x; return p == &x; }",
+        ] {
+            assert!(check(source).is_ok(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_initializers() {
+        for (source, error) in [
+            (
+                "int main(void) { int a[2] = {1, 2, 3}; return 1; }",
+                SemanticError::TooManyInitializers {
+                    expected: 2,
+                    actual: 3,
+                },
+            ),
+            (
+                "int main(void) { int a[2][1] = {{1}, {2, 3}}; return 1; }",
+                SemanticError::TooManyInitializers {
+                    expected: 1,
+                    actual: 2,
+                },
+            ),
+            (
+                "int main(void) { int a[2] = 1; return 1; }",
+                SemanticError::InvalidInitializer,
+            ),
+            (
+                "int main(void) { int a[2][2] = {1, 2}; return 1; }",
+                SemanticError::InvalidInitializer,
+            ),
+            (
+                "int main(void) { int x = {1}; return 1; }",
+                SemanticError::InvalidInitializer,
+            ),
+            (
+                "int main(void) { int *a[1] = {1}; return 1; }",
+                SemanticError::NonPointerUsedAsPointer,
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(
+            check("int f(int i) { char a[2][2] = {{i}, {2}}; int *p[1] = {0}; return 1; }").is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_structs() {
+        let tag = || Symbol::from("s");
+        for (source, error) in [
+            (
+                "int f(struct s *p) { return p->x; }",
+                SemanticError::UndeclaredStruct { tag: tag() },
+            ),
+            (
+                "int main(void) { struct s v; return 1; }",
+                SemanticError::UndeclaredStruct { tag: tag() },
+            ),
+            (
+                "struct s { int x; };\nstruct s { long y; };",
+                SemanticError::StructRedefinition { tag: tag() },
+            ),
+            (
+                "struct s { int x; long x; };",
+                SemanticError::DuplicateMember {
+                    tag: tag(),
+                    member: "x".into(),
+                },
+            ),
+            (
+                "struct s { int x; };\nint f(struct s *p) { return p->y; }",
+                SemanticError::UnknownMember {
+                    tag: tag(),
+                    member: "y".into(),
+                },
+            ),
+            (
+                "struct s { int x; };\nint f(struct s *p) { return p.x; }",
+                SemanticError::MemberOfNonStruct,
+            ),
+            (
+                "struct s { int x; };\nint f(int x) { return (x + 1).x; }",
+                SemanticError::MemberOfNonStruct,
+            ),
+            (
+                "struct s { int x; };\nint f(struct s *p) { return p->x->x; }",
+                SemanticError::MemberOfNonStruct,
+            ),
+            (
+                "struct s { int x; };\nint f(struct s v);",
+                SemanticError::StructUsedAsValue,
+            ),
+            (
+                "struct s { int x; };\nconst struct s f(void);",
+                SemanticError::StructUsedAsValue,
+            ),
+            (
+                "struct s { int x; };\nint f(struct s *p, struct s *q) { *p = *q; return 1; }",
+                SemanticError::StructUsedAsValue,
+            ),
+            (
+                "struct s { int x; };\nint f(struct s *p) { return *p == 0; }",
+                SemanticError::StructUsedAsValue,
+            ),
+            (
+                "struct s { int x; };\nint main(void) { struct s v = {1}; return 1; }",
+                SemanticError::InvalidInitializer,
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(
+            check(
+                "struct s { int x; struct s *next; };\n\
+                 int f(struct s *p) { return (*p).next->next == &p->next[1]; }"
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_reject_types_too_large() {
+        let too_large = || SemanticError::TypeTooLarge {
+            found: CmmType::Array(Box::new(CmmType::Long), 2305843009213693952),
+        };
+        for (source, error) in [
+            ("long a[2305843009213693952];", too_large()),
+            (
+                "int main(void) { static long a[2305843009213693952]; return 0; }",
+                too_large(),
+            ),
+            (
+                "int main(void) { long a[2305843009213693952]; return 0; }",
+                too_large(),
+            ),
+            (
+                "struct s { char a[4611686018427387904]; char b[4611686018427387904]; };",
+                SemanticError::TypeTooLarge {
+                    found: CmmType::Struct("s".into()),
+                },
+            ),
+            (
+                "struct s { long x; char bytes[9223372036854775800]; };",
+                SemanticError::TypeTooLarge {
+                    found: CmmType::Struct("s".into()),
+                },
+            ),
+            (
+                "char c[4][4611686018427387904];",
+                SemanticError::TypeTooLarge {
+                    found: CmmType::Array(
+                        Box::new(CmmType::Array(Box::new(CmmType::Char), 4611686018427387904)),
+                        4,
+                    ),
+                },
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        assert!(check("char c[9223372036854775807];").is_ok());
+    }
+
+    #[test]
+    fn test_reject_invalid_enums() {
+        for (source, error) in [
+            (
+                "enum e { A };\nenum e { B };",
+                SemanticError::EnumRedefinition { tag: "e".into() },
+            ),
+            (
+                "enum { A };\nenum { B, A };",
+                SemanticError::EnumeratorRedefinition {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = B, B };",
+                SemanticError::InvalidEnumerator {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = 2147483647, B };",
+                SemanticError::EnumeratorOutOfRange {
+                    identifier: "B".into(),
+                },
+            ),
+            (
+                "enum { A = 2147483648 };",
+                SemanticError::EnumeratorOutOfRange {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = 18446744073709551615ul };",
+                SemanticError::EnumeratorOutOfRange {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = (unsigned int) -1 };",
+                SemanticError::EnumeratorOutOfRange {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = 1.5 };",
+                SemanticError::InvalidEnumerator {
+                    identifier: "A".into(),
+                },
+            ),
+            (
+                "enum { A = 1 / 0 };",
+                SemanticError::InvalidEnumerator {
+                    identifier: "A".into(),
+                },
+            ),
+        ] {
+            assert_eq!(check(source).map(|_| ()), Err(error), "{}", source);
+        }
+        let annotations = check("enum { A = 2, B, C = B * 3 };").unwrap();
+        assert_eq!(annotations.enumerator_value(&"C".into()), Some(9));
+        // The values are computed in the types of their operations, and converted to `int` once
+        // they fit it.
+        let annotations = check(
+            "enum { BIG = 18446744073709551615ul > 0, WRAPPED = 2147483647 + 1, LOW = 4294967295u / 2 };",
+        )
+        .unwrap();
+        assert_eq!(annotations.enumerator_value(&"BIG".into()), Some(1));
+        assert_eq!(
+            annotations.enumerator_value(&"WRAPPED".into()),
+            Some(i32::MIN)
+        );
+        assert_eq!(annotations.enumerator_value(&"LOW".into()), Some(i32::MAX));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::common::diagnostics::{Span, Warning};
+use crate::common::symbol::Symbol;
+use crate::compiler::error_codes::{self, ErrorCode};
+use std::error::Error;
+use std::fmt;
+
+/// Represents suspicious constructs that are valid C--, but likely do not do what was intended.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SemanticWarning {
+    /// Raised when the divisor of a division or a remainder is the constant zero, which makes the
+    /// program fail at runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression`: The printed source code of the division.
+    /// * `span`: The location of the division.
+    DivisionByZero {
+        expression: String,
+        span: Option<Span>,
+    },
+    /// Raised when a local variable is declared, but its value is never read. Assigning to the
+    /// variable does not count as reading it.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier`: The name of the variable.
+    /// * `function`: The name of the function declaring the variable.
+    /// * `span`: The location of the declaration of the variable.
+    UnusedVariable {
+        identifier: Symbol,
+        function: Symbol,
+        span: Option<Span>,
+    },
+    /// Raised when a function body has code after a `return`, `break`, or `continue` statement,
+    /// which jumps away unconditionally, so the code can never run.
+    ///
+    /// # Arguments
+    ///
+    /// * `jump`: The printed source code of the jump statement, without the semicolon.
+    /// * `function`: The name of the function.
+    /// * `span`: The location of the first unreachable statement or declaration.
+    UnreachableCode {
+        jump: String,
+        function: Symbol,
+        span: Option<Span>,
+    },
+}
+
+impl SemanticWarning {
+    /// Returns the kind of the warning, which the `-W` flags enable and disable.
+    pub fn warning(&self) -> Warning {
+        match self {
+            SemanticWarning::DivisionByZero { .. } => Warning::DivisionByZero,
+            SemanticWarning::UnusedVariable { .. } => Warning::UnusedVariable,
+            SemanticWarning::UnreachableCode { .. } => Warning::UnreachableCode,
+        }
+    }
+
+    /// Returns the stable `ErrorCode` of the warning.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SemanticWarning::DivisionByZero { .. } => error_codes::DIVISION_BY_ZERO,
+            SemanticWarning::UnusedVariable { .. } => error_codes::UNUSED_VARIABLE,
+            SemanticWarning::UnreachableCode { .. } => error_codes::UNREACHABLE_CODE,
+        }
+    }
+
+    /// Returns the location of the warning, or `None` if the offending code was not parsed from
+    /// source code.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            SemanticWarning::DivisionByZero { span, .. }
+            | SemanticWarning::UnusedVariable { span, .. }
+            | SemanticWarning::UnreachableCode { span, .. } => *span,
+        }
+    }
+
+    /// Returns the description of the warning without its location, which `span` returns.
+    pub fn message(&self) -> String {
+        match self {
+            SemanticWarning::DivisionByZero { expression, .. } => format!(
+                "Semantic warning: Division by zero in '{}' fails at runtime",
+                expression
+            ),
+            SemanticWarning::UnusedVariable {
+                identifier,
+                function,
+                ..
+            } => format!(
+                "Semantic warning: Unused variable '{}' in function '{}'",
+                identifier, function
+            ),
+            SemanticWarning::UnreachableCode { jump, function, .. } => format!(
+                "Semantic warning: Code after '{}' in function '{}' is unreachable",
+                jump, function
+            ),
+        }
+    }
+}
+
+impl fmt::Display for SemanticWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message())?;
+        match self.span() {
+            Some(span) => write!(
+                f,
+                " at line {}, column {}",
+                span.start_line, span.start_column
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for SemanticWarning {}
@@ -0,0 +1,529 @@
+use crate::common::diagnostics::{Diagnostic, Diagnostics};
+use crate::common::symbol::Interner;
+#[cfg(feature = "x86_64")]
+use crate::compiler::code_gen::assembly_ast::AssemblyAst;
+use crate::compiler::errors::CompilerError;
+use crate::compiler::ir_gen::tacky_ast::TackyAst;
+use crate::compiler::lexer::{Lexer, tokens::Token};
+use crate::compiler::optimizer::PassManager;
+use crate::compiler::parser::cmm_ast::CmmAst;
+#[cfg(feature = "qbe")]
+use crate::compiler::pipeline::QbeEmissionStage;
+#[cfg(feature = "x86_64")]
+use crate::compiler::pipeline::{CodegenStage, EmissionStage};
+use crate::compiler::pipeline::{
+    CompilerStage, LexerStage, ParserStage, SemanticStage, TackyStage, TackyVerifierStage,
+    TargetCheckStage,
+};
+use crate::compiler::semantic::warnings::SemanticWarning;
+use crate::compiler::{
+    Backend, CompileOptions, CompilerResult, Stage, Standard, parse_token_stream,
+};
+
+/// A callback observing an intermediate artifact of a compilation.
+type Hook<'a, T> = Box<dyn FnMut(&T) + 'a>;
+
+/// Represents the state shared by the stages of a compilation.
+///
+/// The session owns the options, the interner of the identifiers and generated names, and the
+/// diagnostics reported so far, and it is passed to every `CompilerStage`. A session can compile
+/// several sources, e.g. in a language server, reusing its interner and collecting the diagnostics
+/// of every compilation.
+///
+/// Observer hooks let embedders inspect or log the intermediate artifacts of a compilation without
+/// reimplementing the pipeline. Each hook is called once per compilation that reaches its stage,
+/// in the order the hooks were registered.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::session::CompilerSession;
+/// # use cmm::compiler::{CompileOptions, Stage};
+/// let mut token_counts = Vec::new();
+/// let mut session = CompilerSession::new(CompileOptions {
+///     process_until: Some(Stage::Tacky),
+///     quiet: true,
+///     ..Default::default()
+/// });
+/// session.on_tokens(|tokens| token_counts.push(tokens.len()));
+/// session.compile("int main(void) { return 2; }").unwrap();
+/// assert!(session.compile("int main(void) { return }").is_err());
+/// assert_eq!(session.diagnostics().len(), 1);
+/// drop(session);
+/// assert_eq!(token_counts, [10, 8]);
+/// ```
+pub struct CompilerSession<'a> {
+    /// The `CompileOptions` of the compilations run in the session, including the target.
+    pub options: CompileOptions,
+    /// The interner shared by the lexer and the TACKY generation.
+    pub interner: Interner,
+    /// The diagnostics reported by the compilations so far.
+    diagnostics: Diagnostics,
+    /// The observer hooks, see `on_tokens`.
+    hooks: Hooks<'a>,
+}
+
+/// Represents the observer hooks registered on a `CompilerSession`.
+#[derive(Default)]
+struct Hooks<'a> {
+    on_tokens: Vec<Hook<'a, [Token]>>,
+    on_ast: Vec<Hook<'a, CmmAst>>,
+    on_tacky: Vec<Hook<'a, TackyAst>>,
+    #[cfg(feature = "x86_64")]
+    on_assembly: Vec<Hook<'a, AssemblyAst>>,
+}
+
+impl<'a> CompilerSession<'a> {
+    /// Creates a session without any hooks or diagnostics.
+    ///
+    /// # Arguments
+    ///
+    /// * `options`: The `CompileOptions` of the compilations run in the session.
+    pub fn new(options: CompileOptions) -> Self {
+        CompilerSession {
+            options,
+            interner: Interner::new(),
+            diagnostics: Diagnostics::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Returns the diagnostics reported by the compilations so far, in the order they were reported.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        self.diagnostics.as_slice()
+    }
+
+    /// Removes and returns the diagnostics reported so far.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.diagnostics.take()
+    }
+
+    /// Reports a diagnostic, e.g. a warning found by a stage that does not fail the compilation.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostic`: The `Diagnostic` to add to the session.
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.report(diagnostic);
+    }
+
+    /// Reports a warning found by a stage, unless the warning options of the session disable it.
+    ///
+    /// # Arguments
+    ///
+    /// * `warning`: The `SemanticWarning` to report.
+    ///
+    /// # Returns
+    ///
+    /// An empty `Result`, or the warning as a `CompilerError` when the session treats warnings as
+    /// errors, which fails the compilation.
+    pub fn warn(&mut self, warning: SemanticWarning) -> Result<(), CompilerError> {
+        let options = &self.options.warnings;
+        if !options.is_enabled(warning.warning()) {
+            return Ok(());
+        }
+        if options.as_errors {
+            return Err(warning.into());
+        }
+        self.report(Diagnostic::from(&warning));
+        Ok(())
+    }
+
+    /// Registers a hook called with the tokens of the source code.
+    ///
+    /// The parser normally pulls the tokens from the lexer one at a time, so registering this hook
+    /// makes the session collect all tokens before parsing.
+    pub fn on_tokens(&mut self, hook: impl FnMut(&[Token]) + 'a) -> &mut Self {
+        self.hooks.on_tokens.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a hook called with the C-- AST produced by the parser.
+    pub fn on_ast(&mut self, hook: impl FnMut(&CmmAst) + 'a) -> &mut Self {
+        self.hooks.on_ast.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a hook called with the TACKY IR passed to the backend, after the custom passes
+    /// ran. When the compilation stops at `Stage::Tacky`, the hook sees the IR before the passes.
+    pub fn on_tacky(&mut self, hook: impl FnMut(&TackyAst) + 'a) -> &mut Self {
+        self.hooks.on_tacky.push(Box::new(hook));
+        self
+    }
+
+    /// Registers a hook called with the assembly AST produced by the native code generator.
+    #[cfg(feature = "x86_64")]
+    pub fn on_assembly(&mut self, hook: impl FnMut(&AssemblyAst) + 'a) -> &mut Self {
+        self.hooks.on_assembly.push(Box::new(hook));
+        self
+    }
+
+    /// Compiles a preprocessed C-- source code, see `run_cmm_compiler`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmm_source_code`: The source code to compile.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `CompilerResult` of the last executed stage on success, or a `CompilerError`
+    /// describing the stage that failed, which is also reported as a diagnostic.
+    pub fn compile(&mut self, cmm_source_code: &str) -> Result<CompilerResult, CompilerError> {
+        self.compile_with_passes(cmm_source_code, &mut PassManager::new())
+    }
+
+    /// Compiles a preprocessed C-- source code, transforming the TACKY IR with custom passes, see
+    /// `run_cmm_compiler_with_passes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cmm_source_code`: The source code to compile.
+    /// * `pass_manager`: The `PassManager` holding the passes to run.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `CompilerResult` of the last executed stage on success, or a `CompilerError`
+    /// describing the stage that failed, which is also reported as a diagnostic.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "compile", skip_all, fields(target = %self.options.target))
+    )]
+    pub fn compile_with_passes(
+        &mut self,
+        cmm_source_code: &str,
+        pass_manager: &mut PassManager,
+    ) -> Result<CompilerResult, CompilerError> {
+        if !self.options.quiet {
+            println!("Compiling with a custom C compiler...");
+        }
+        // The stages observing the artifacts borrow the hooks while the session is passed to
+        // every stage, so the hooks are moved out of the session during the compilation.
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = run_stages(cmm_source_code, pass_manager, &mut hooks, self);
+        self.hooks = hooks;
+        if let Err(error) = &result {
+            self.report(Diagnostic::from(error));
+        }
+        result
+    }
+}
+
+/// Runs the compiler stages selected by the session's options.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The source code to compile.
+/// * `pass_manager`: The `PassManager` holding the passes to run.
+/// * `hooks`: The hooks observing the artifacts of the stages.
+/// * `session`: The `CompilerSession` passed to the stages.
+///
+/// # Returns
+///
+/// Returns the `CompilerResult` of the last executed stage on success, or a `CompilerError`
+/// describing the stage that failed.
+fn run_stages(
+    cmm_source_code: &str,
+    pass_manager: &mut PassManager,
+    hooks: &mut Hooks,
+    session: &mut CompilerSession,
+) -> Result<CompilerResult, CompilerError> {
+    let process_until = session.options.process_until;
+    if let Some(Stage::Lex) = process_until {
+        let tokens = LexerStage.run(cmm_source_code, session)?;
+        notify(&mut hooks.on_tokens, &tokens[..]);
+        return Ok(CompilerResult::Lexer(tokens));
+    }
+
+    let on_tokens = &mut hooks.on_tokens;
+    let parser =
+        |cmm_source_code: &str, session: &mut CompilerSession| -> Result<CmmAst, CompilerError> {
+            if on_tokens.is_empty() {
+                return ParserStage.run(cmm_source_code, session);
+            }
+            let gnu_extensions = session.options.standard == Standard::Gnu;
+            let (tokens, spans): (Vec<_>, Vec<_>) =
+                Lexer::with_interner(cmm_source_code, &mut session.interner)
+                    .with_gnu_extensions(gnu_extensions)
+                    .with_spans()
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .unzip();
+            notify(on_tokens, &tokens[..]);
+            parse_token_stream(tokens.into_iter().zip(spans).map(Ok))
+        };
+    let mut front_end = parser.then(observer(&mut hooks.on_ast));
+    if let Some(Stage::Parse) = process_until {
+        return front_end
+            .run(cmm_source_code, session)
+            .map(CompilerResult::Parser);
+    }
+
+    let mut validated = front_end.then(SemanticStage);
+    if let Some(Stage::Validate) = process_until {
+        return validated
+            .run(cmm_source_code, session)
+            .map(|validated_ast| CompilerResult::Validator(validated_ast.cmm_ast));
+    }
+
+    let tacky = validated.then(TackyStage);
+    if let Some(Stage::Tacky) = process_until {
+        return tacky
+            .then(observer(&mut hooks.on_tacky))
+            .run(cmm_source_code, session)
+            .map(CompilerResult::Tacky);
+    }
+
+    let mut optimized = tacky
+        .then(pass_manager)
+        .then(TackyVerifierStage)
+        .then(observer(&mut hooks.on_tacky));
+    if let Some(Stage::Optimize) = process_until {
+        return optimized
+            .run(cmm_source_code, session)
+            .map(CompilerResult::Optimizer);
+    }
+
+    let middle_end = optimized.then(TargetCheckStage);
+    match session.options.backend {
+        #[cfg(feature = "x86_64")]
+        Backend::Native => {
+            let mut codegen = middle_end
+                .then(CodegenStage)
+                .then(observer(&mut hooks.on_assembly));
+            if let Some(Stage::Codegen) = process_until {
+                return codegen
+                    .run(cmm_source_code, session)
+                    .map(CompilerResult::Codegen);
+            }
+            codegen
+                .then(EmissionStage)
+                .run(cmm_source_code, session)
+                .map(CompilerResult::Final)
+        }
+        #[cfg(not(feature = "x86_64"))]
+        Backend::Native => {
+            // Without a backend the target check fails for every target, after reporting errors
+            // in the source code.
+            let mut middle_end = middle_end;
+            middle_end.run(cmm_source_code, session)?;
+            unreachable!("No target has a backend when the crate is built without one")
+        }
+        #[cfg(feature = "qbe")]
+        Backend::Qbe => middle_end
+            .then(QbeEmissionStage)
+            .run(cmm_source_code, session)
+            .map(CompilerResult::Final),
+    }
+}
+
+/// Calls every hook with an artifact.
+fn notify<T: ?Sized>(hooks: &mut [Hook<'_, T>], artifact: &T) {
+    for hook in hooks {
+        hook(artifact);
+    }
+}
+
+/// Creates a stage passing its input through unchanged after showing it to the hooks.
+fn observer<'h, 'a: 'h, T>(
+    hooks: &'h mut [Hook<'a, T>],
+) -> impl FnMut(T, &mut CompilerSession) -> Result<T, CompilerError> + 'h {
+    move |artifact, _session: &mut CompilerSession| {
+        notify(hooks, &artifact);
+        Ok(artifact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::diagnostics::Severity;
+    use crate::compiler::ir_gen::errors::TackyVerificationError;
+    use crate::compiler::ir_gen::tacky_ast::{TackyFunction, TackyInstruction};
+    use crate::compiler::optimizer::TackyPass;
+    use std::cell::RefCell;
+
+    fn quiet_session<'a>(process_until: Option<Stage>) -> CompilerSession<'a> {
+        CompilerSession::new(CompileOptions {
+            process_until,
+            quiet: true,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_hooks_observe_every_stage() {
+        let observed = RefCell::new(Vec::new());
+        // Without a backend, the compilation of a whole program fails after TACKY.
+        let mut session = quiet_session(if cfg!(feature = "x86_64") {
+            None
+        } else {
+            Some(Stage::Optimize)
+        });
+        session
+            .on_tokens(|_| observed.borrow_mut().push("tokens"))
+            .on_ast(|_| observed.borrow_mut().push("ast"))
+            .on_tacky(|_| observed.borrow_mut().push("tacky"));
+        #[cfg(feature = "x86_64")]
+        session.on_assembly(|_| observed.borrow_mut().push("assembly"));
+        session.compile("int main(void) { return 2; }").unwrap();
+        drop(session);
+
+        let mut expected = vec!["tokens", "ast", "tacky"];
+        if cfg!(feature = "x86_64") {
+            expected.push("assembly");
+        }
+        assert_eq!(observed.into_inner(), expected);
+    }
+
+    #[test]
+    fn test_hooks_stop_with_compilation() {
+        let mut tacky_instructions = None;
+        let mut session = quiet_session(Some(Stage::Tacky));
+        session.on_tacky(|tacky_ast| {
+            let TackyAst::Program { functions, .. } = tacky_ast;
+            let [TackyFunction::Function { instructions, .. }] = functions.as_slice() else {
+                panic!("Expected a single function");
+            };
+            tacky_instructions = Some(instructions.len());
+        });
+        let result = session.compile("int main(void) { return -2; }");
+        drop(session);
+        assert!(matches!(result, Ok(CompilerResult::Tacky(_))));
+        assert_eq!(tacky_instructions, Some(2));
+    }
+
+    #[test]
+    fn test_hooks_skip_failed_stages() {
+        let mut ast_seen = false;
+        let mut session = quiet_session(None);
+        session.on_ast(|_| ast_seen = true);
+        assert!(session.compile("int main(void) { return }").is_err());
+        drop(session);
+        assert!(!ast_seen);
+    }
+
+    #[test]
+    fn test_stop_after_validate_and_optimize() {
+        let mut session = quiet_session(Some(Stage::Validate));
+        let result = session.compile("int main(void) { return 2 / 0; }");
+        assert!(matches!(result, Ok(CompilerResult::Validator(_))));
+        assert_eq!(session.take_diagnostics().len(), 1);
+
+        let mut tacky_asts = Vec::new();
+        for stage in [Stage::Tacky, Stage::Optimize] {
+            session.options.process_until = Some(stage);
+            tacky_asts.push(session.compile("int main(void) { return -2; }").unwrap());
+        }
+        let [
+            CompilerResult::Tacky(unoptimized_ast),
+            CompilerResult::Optimizer(optimized_ast),
+        ] = &tacky_asts[..]
+        else {
+            panic!("Expected the TACKY IR before and after the passes");
+        };
+        // Without registered passes, the optimizer leaves the IR unchanged.
+        assert_eq!(unoptimized_ast, optimized_ast);
+    }
+
+    /// Renames every label to the name of the function, which the verifier must reject.
+    struct LabelsNamedLikeFunction;
+
+    impl TackyPass for LabelsNamedLikeFunction {
+        fn name(&self) -> &str {
+            "labels-named-like-function"
+        }
+
+        fn run(&self, function: &mut TackyFunction) -> bool {
+            let TackyFunction::Function {
+                identifier,
+                instructions,
+                ..
+            } = function;
+            for instruction in instructions.iter_mut() {
+                if let TackyInstruction::Label(label) = instruction {
+                    *label = identifier.clone();
+                }
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn test_verifier_rejects_ir_of_custom_passes() {
+        let mut pass_manager = PassManager::new();
+        pass_manager.register(LabelsNamedLikeFunction);
+        let result = quiet_session(None)
+            .compile_with_passes("int main(void) { return 1 && 2; }", &mut pass_manager);
+        assert!(matches!(
+            result,
+            Err(CompilerError::TackyVerification(
+                TackyVerificationError::UnmangledName { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_stages_share_session_interner() {
+        let mut session = quiet_session(Some(Stage::Tacky));
+        session.compile("int main(void) { return -2; }").unwrap();
+        // The lexer interned `main`, the TACKY generation `tmp.0`.
+        assert_eq!(session.interner.len(), 2);
+        session.compile("int main(void) { return -3; }").unwrap();
+        assert_eq!(session.interner.len(), 2);
+    }
+
+    #[test]
+    fn test_warnings_are_reported_as_diagnostics() {
+        let mut session = quiet_session(Some(Stage::Tacky));
+        session.compile("int main(void) { return 1 % 0; }").unwrap();
+        let diagnostics = session.take_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E0009"));
+
+        session.options.warnings.as_errors = true;
+        let error = session
+            .compile("int main(void) { return 1 % 0; }")
+            .unwrap_err();
+        assert!(matches!(error, CompilerError::Warning(_)));
+        assert_eq!(session.diagnostics(), [Diagnostic::from(&error)]);
+    }
+
+    #[test]
+    fn test_errors_are_reported_as_diagnostics() {
+        let mut session = quiet_session(Some(Stage::Tacky));
+        let error = session.compile("int main(void) { return }").unwrap_err();
+        session.compile("int main(void) { return 2; }").unwrap();
+        assert_eq!(session.diagnostics(), [Diagnostic::from(&error)]);
+        assert_eq!(session.take_diagnostics().len(), 1);
+        assert!(session.diagnostics().is_empty());
+    }
+
+    #[cfg(not(feature = "x86_64"))]
+    #[test]
+    fn test_compilation_without_backend_stops_after_tacky() {
+        let mut session = quiet_session(None);
+        let error = session.compile("int main(void) { return 2; }").unwrap_err();
+        assert!(matches!(error, CompilerError::UnsupportedTarget { .. }));
+
+        // Errors in the source code are still reported before the missing backend.
+        let error = session.compile("int main(void) { return x; }").unwrap_err();
+        assert!(matches!(error, CompilerError::Semantic(_)));
+    }
+
+    #[test]
+    fn test_binary_constants_need_gnu_standard() {
+        let source = "int main(void) { return 0b101; }";
+        let mut session = quiet_session(Some(Stage::Parse));
+        let error = session.compile(source).unwrap_err();
+        assert_eq!(Diagnostic::from(&error).code.as_deref(), Some("E0025"));
+
+        session.options.standard = Standard::Gnu;
+        assert!(session.compile(source).is_ok());
+        let tokens_seen = RefCell::new(false);
+        session.on_tokens(|_| *tokens_seen.borrow_mut() = true);
+        assert!(session.compile(source).is_ok());
+        drop(session);
+        assert!(tokens_seen.into_inner());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::common::diagnostics::Span;
+use crate::common::target::Target;
+use crate::compiler::errors::CompilerError;
+use crate::compiler::ir_gen::TackyEmitter;
+use crate::compiler::parser::cmm_ast::CmmAst;
+use crate::compiler::semantic::{ValidatedAst, check_program};
+use crate::compiler::{code_emission, code_gen, parse_source};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Represents the source location of a single line of emitted assembly code.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SourceMapping {
+    /// The 1-based line of the assembly code.
+    pub assembly_line: usize,
+    /// The location of the expression the line was generated from.
+    pub span: Span,
+}
+
+/// Represents a mapping from emitted assembly lines to the C-- source code they were generated from.
+///
+/// The spans refer to the preprocessed source code, which is included in the map, so that tools can
+/// show it even though the preprocessor may have moved lines around. Lines that were not generated
+/// from an expression, such as the function prologue or the implicit return of `main`, have no
+/// mapping.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SourceMap {
+    /// The assembly file the map describes.
+    pub file: Option<PathBuf>,
+    /// The C source file the assembly was compiled from.
+    pub source: Option<PathBuf>,
+    /// The preprocessed source code the spans refer to.
+    pub source_content: String,
+    /// The mapped assembly lines, in increasing line order.
+    pub mappings: Vec<SourceMapping>,
+}
+
+/// Compiles C-- source code and maps every emitted assembly line to its originating source span.
+///
+/// The assembly code is generated the same way as by `run_cmm_compiler`, so the line numbers match
+/// the assembly code emitted by a compilation without custom passes.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The preprocessed source code to compile.
+/// * `target`: The platform the assembly code is emitted for.
+///
+/// # Returns
+///
+/// A `SourceMap` without file names on success, or the `CompilerError` of the stage that failed.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::common::target::Target;
+/// # use cmm::compiler::source_map::build_source_map;
+/// let source_map = build_source_map("int main(void) {\n    return 2;\n}", &Target::X86_64_LINUX).unwrap();
+/// let first_mapping = &source_map.mappings[0];
+/// assert_eq!((first_mapping.span.start_line, first_mapping.span.start_column), (2, 12));
+/// ```
+pub fn build_source_map(
+    cmm_source_code: &str,
+    target: &Target,
+) -> Result<SourceMap, CompilerError> {
+    let cmm_ast = parse_source(cmm_source_code)?;
+    let CmmAst::Program { expressions, .. } = &cmm_ast;
+    let expression_spans: Vec<Option<Span>> = expressions
+        .iter()
+        .map(|(id, _)| expressions.span(id))
+        .collect();
+    // The conversions are not inserted, so that the origins are the expressions of the parser.
+    let validated_ast = ValidatedAst {
+        annotations: check_program(&cmm_ast)?,
+        cmm_ast,
+    };
+    let mut tacky_emitter = TackyEmitter::with_instruction_origins();
+    let tacky_ast = tacky_emitter.convert_ast(validated_ast)?;
+    let tacky_origins = tacky_emitter
+        .instruction_origins()
+        .expect("The emitter records origins")
+        .to_vec();
+    let (assembly_ast, assembly_origins) = code_gen::convert_ast_with_origins(tacky_ast)?;
+    let instruction_lines = code_emission::instruction_lines(&assembly_ast, target);
+
+    let mut mappings = Vec::with_capacity(instruction_lines.len());
+    for (lines, origin) in instruction_lines.into_iter().zip(assembly_origins) {
+        let Some(&Some(expression)) = origin.and_then(|tacky_index| tacky_origins.get(tacky_index))
+        else {
+            continue;
+        };
+        let Some(span) = expression_spans[expression.index()] else {
+            continue;
+        };
+        mappings.extend(lines.map(|assembly_line| SourceMapping {
+            assembly_line,
+            span,
+        }));
+    }
+    Ok(SourceMap {
+        file: None,
+        source: None,
+        source_content: cmm_source_code.to_string(),
+        mappings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::code_emission::emit_assembly;
+    use crate::compiler::validate_source;
+
+    #[test]
+    fn test_mappings_point_to_expressions() {
+        let source_code = "int main(void) {\n    return 1 + (2 && 3);\n}";
+        let source_map = build_source_map(source_code, &Target::X86_64_LINUX).unwrap();
+        let validated_ast = validate_source(source_code).unwrap();
+        let tacky_ast = TackyEmitter::new().convert_ast(validated_ast).unwrap();
+        let assembly_code = emit_assembly(
+            &code_gen::convert_ast(tacky_ast).unwrap(),
+            &Target::X86_64_LINUX,
+        );
+        let assembly_lines: Vec<&str> = assembly_code.lines().collect();
+        let span_text = |span: &Span| {
+            let line = source_code.lines().nth(span.start_line - 1).unwrap();
+            &line[span.start_column - 1..span.end_column - 1]
+        };
+
+        let mapped_text = |instruction: &str| {
+            let mapping = source_map
+                .mappings
+                .iter()
+                .find(|mapping| assembly_lines[mapping.assembly_line - 1].starts_with(instruction))
+                .unwrap();
+            span_text(&mapping.span)
+        };
+        assert_eq!(mapped_text("\tje "), "2 && 3");
+        assert_eq!(mapped_text("\taddl "), "1 + (2 && 3)");
+        assert_eq!(mapped_text("\tret"), "1 + (2 && 3)");
+        // The symbol declaration and the stack frame setup have no source expression.
+        assert!(
+            source_map
+                .mappings
+                .iter()
+                .all(|mapping| mapping.assembly_line > 4)
+        );
+        assert!(
+            source_map
+                .mappings
+                .windows(2)
+                .all(|pair| pair[0].assembly_line < pair[1].assembly_line)
+        );
+    }
+}
@@ -1,5 +1,19 @@
+use crate::cache::{CacheKey, CompilationCache};
+use crate::common::target::Target;
+use crate::common::validation;
+use crate::compiler::Stage;
+#[cfg(feature = "x86_64")]
+use crate::compiler::machine_code::elf::write_static_executable;
+#[cfg(feature = "x86_64")]
+use crate::compiler::run_cmm_compiler;
+use crate::compiler::session::CompilerSession;
+#[cfg(feature = "qbe")]
+use crate::compiler::{Backend, qbe_emission};
+use crate::compiler::{CompileOptions, CompilerResult};
 use anyhow::Context;
-use std::path::Path;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Runs the GCC preprocessor on a C source file.
@@ -11,6 +25,7 @@ use std::process::Command;
 ///
 /// * `source_file_path`: The path to the input C source file. Must have a `.c` extension.
 /// * `preprocessed_file_path`: The path to the output preprocessed C source file. Must have an `.i` extension.
+/// * `options`: The `CompileOptions` providing the target and verbosity. Cross-compilation flags are added when the target differs from the host.
 ///
 /// # Returns
 ///
@@ -19,10 +34,13 @@ use std::process::Command;
 pub fn run_gcc_preprocessor(
     source_file_path: &Path,
     preprocessed_file_path: &Path,
+    options: &CompileOptions,
 ) -> anyhow::Result<()> {
-    println!("Invoking GCC Preprocessor...");
+    if !options.quiet {
+        println!("Invoking GCC Preprocessor...");
+    }
 
-    let status = Command::new("gcc")
+    let status = gcc_command(options)
         .arg("-E")
         .arg("-P")
         .arg(source_file_path)
@@ -32,36 +50,459 @@ pub fn run_gcc_preprocessor(
         .context("Failed to execute GCC preprocessing. Is it installed and in your PATH?")?;
 
     if status.success() {
+        if !options.quiet {
+            println!(
+                "Preprocessed file created at: {}",
+                preprocessed_file_path.display()
+            );
+        }
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "GCC Preprocessor failed with exit code: {:?}",
+            status.code()
+        ))
+    }
+}
+
+/// Prepares a C source file for the compiler without running a preprocessor.
+///
+/// The internal toolchain has no preprocessor, so this function only copies the source file, and
+/// rejects it if it contains a preprocessor directive.
+///
+/// # Arguments
+///
+/// * `source_file_path`: The path to the input C source file. Must have a `.c` extension.
+/// * `preprocessed_file_path`: The path to the output source file. Must have an `.i` extension.
+/// * `options`: The `CompileOptions` providing the verbosity.
+///
+/// # Returns
+///
+/// Returns `Ok(())` on success, or an `anyhow::Error` if:
+/// - The source file cannot be read or the output file cannot be written.
+/// - The source file contains a line starting with `#`.
+pub fn run_internal_preprocessor(
+    source_file_path: &Path,
+    preprocessed_file_path: &Path,
+    options: &CompileOptions,
+) -> anyhow::Result<()> {
+    let source_code = std::fs::read_to_string(source_file_path)
+        .with_context(|| format!("Failed to read '{}'", source_file_path.display()))?;
+    if let Some(line) = source_code
+        .lines()
+        .position(|line| line.trim_start().starts_with('#'))
+    {
+        return Err(anyhow::anyhow!(
+            "Line {} contains a preprocessor directive, which the internal toolchain does not support. Use --toolchain gcc to preprocess the source file",
+            line + 1
+        ));
+    }
+    std::fs::write(preprocessed_file_path, source_code)?;
+    if !options.quiet {
         println!(
-            "Preprocessed file created at: {}",
+            "Copied the source file without preprocessing to: {}",
             preprocessed_file_path.display()
         );
+    }
+    Ok(())
+}
+
+/// Compiles a preprocessed C-- source file into an assembly file.
+///
+/// This function validates the input and output paths, reads the preprocessed source code, runs the
+/// C-- compiler on it, and writes the emitted assembly code to the output path. If the compilation is
+/// stopped before code emission, nothing is written and the intermediate result is returned instead.
+///
+/// # Arguments
+///
+/// * `input_path`: The path to the preprocessed source file. Must have an `.i` extension.
+/// * `output_path`: An optional path for the assembly file. Must have an `.s` extension, or be `-` to write the assembly to stdout. Defaults to the input path with an `.s` extension.
+/// * `options`: The `CompileOptions` used for the compilation.
+///
+/// # Returns
+///
+/// Returns `Ok((PathBuf, CompilerResult))` containing the validated output path and the result of the last executed stage.
+/// Returns an `anyhow::Error` if path validation, file I/O, or the compilation itself fails.
+pub fn compile_file(
+    input_path: &Path,
+    output_path: Option<&Path>,
+    options: &CompileOptions,
+) -> anyhow::Result<(PathBuf, CompilerResult)> {
+    let mut session = CompilerSession::new(options.clone());
+    compile_file_with_cache(input_path, output_path, &mut session, None)
+}
+
+/// Compiles a preprocessed C-- source file into an assembly file, reusing cached assembly code.
+///
+/// Behaves like `compile_file`, but compiles in the given session, which collects the diagnostics of
+/// the compilation. When a cache is given and the compilation runs until code emission, the assembly
+/// code of an unchanged source file compiled with the same options is read from the cache instead of
+/// being recompiled, and new assembly code is added to the cache. The front end still runs on a
+/// cached source file, so that its warnings are reported, and fail it under `-Werror`, as usual.
+///
+/// # Arguments
+///
+/// * `input_path`: The path to the preprocessed source file. Must have an `.i` extension.
+/// * `output_path`: An optional path for the assembly file, see `compile_file`.
+/// * `session`: The `CompilerSession` holding the `CompileOptions` used for the compilation.
+/// * `cache`: An optional `CompilationCache` to look up and store the assembly code in.
+///
+/// # Returns
+///
+/// Returns `Ok((PathBuf, CompilerResult))` containing the validated output path and the result of the last executed stage.
+/// Returns an `anyhow::Error` if path validation, file I/O, or the compilation itself fails.
+pub fn compile_file_with_cache(
+    input_path: &Path,
+    output_path: Option<&Path>,
+    session: &mut CompilerSession,
+    cache: Option<&CompilationCache>,
+) -> anyhow::Result<(PathBuf, CompilerResult)> {
+    let options = &session.options.clone();
+    let (compiler_input_path, compiler_output_path) =
+        validation::validate_compiler_paths(input_path, output_path)?;
+    let cmm_source_code = std::fs::read_to_string(&compiler_input_path)?;
+    let cache = cache.filter(|_| options.process_until.is_none());
+    let cache_key = CacheKey::new(&cmm_source_code, options);
+    let compilation_result = match cache.and_then(|cache| cache.get(&cache_key)) {
+        Some(assembly_code) => {
+            if !options.quiet {
+                println!("Reusing cached assembly code...");
+            }
+            session.options = CompileOptions {
+                process_until: Some(Stage::Validate),
+                quiet: true,
+                ..options.clone()
+            };
+            let front_end_result = session.compile(&cmm_source_code);
+            session.options = options.clone();
+            front_end_result?;
+            CompilerResult::Final(assembly_code)
+        }
+        None => {
+            let compilation_result = session.compile(&cmm_source_code)?;
+            #[cfg(feature = "qbe")]
+            let compilation_result = match compilation_result {
+                CompilerResult::Final(qbe_code) if options.backend == Backend::Qbe => {
+                    CompilerResult::Final(run_qbe_compiler(&qbe_code, options)?)
+                }
+                compilation_result => compilation_result,
+            };
+            if let (Some(cache), CompilerResult::Final(assembly_code)) =
+                (cache, &compilation_result)
+            {
+                cache
+                    .insert(&cache_key, assembly_code)
+                    .context("Failed to write the compilation cache")?;
+            }
+            compilation_result
+        }
+    };
+
+    if let CompilerResult::Final(assembly_code) = &compilation_result {
+        if compiler_output_path == Path::new(validation::STDOUT_PATH) {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(assembly_code.as_bytes())?;
+            stdout.flush()?;
+        } else {
+            std::fs::write(&compiler_output_path, assembly_code)?;
+            if !options.quiet {
+                println!(
+                    "Assembly code created at: {}",
+                    compiler_output_path.display()
+                );
+            }
+        }
+    }
+
+    Ok((compiler_output_path, compilation_result))
+}
+
+/// Runs the QBE compiler to turn QBE IL into assembly code.
+///
+/// This function invokes `qbe -t <target>`, passing the IL on stdin and reading the assembly from stdout.
+///
+/// # Arguments
+///
+/// * `qbe_code`: The QBE IL emitted by the QBE backend.
+/// * `options`: The `CompileOptions` providing the target and verbosity.
+///
+/// # Returns
+///
+/// Returns the assembly code on success, or an `anyhow::Error` if QBE fails or is not found.
+#[cfg(feature = "qbe")]
+pub fn run_qbe_compiler(qbe_code: &str, options: &CompileOptions) -> anyhow::Result<String> {
+    if !options.quiet {
+        println!("Invoking QBE...");
+    }
+
+    let mut child = Command::new("qbe")
+        .arg("-t")
+        .arg(qbe_emission::qbe_target_name(&options.target))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to execute QBE. Is it installed and in your PATH?")?;
+    child
+        .stdin
+        .take()
+        .expect("The stdin of QBE is piped")
+        .write_all(qbe_code.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout)?)
+    } else {
+        Err(anyhow::anyhow!(
+            "QBE failed with exit code: {:?}",
+            output.status.code()
+        ))
+    }
+}
+
+/// Represents an error the assembler reported for a line of the emitted assembly code.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AssemblerError {
+    /// The 1-based line of the assembly code.
+    pub line: usize,
+    /// The instruction on the line, as emitted but without surrounding whitespace.
+    pub instruction: String,
+    /// The message of the assembler.
+    pub message: String,
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}: `{}`: {}",
+            self.line, self.instruction, self.message
+        )
+    }
+}
+
+/// Extracts the errors reported for the lines of an assembly code read from stdin.
+///
+/// Both the GNU assembler format (`{standard input}:5: Error: ...`) and the Clang format
+/// (`<stdin>:5:2: error: ...`) are recognized, other lines of the output are ignored.
+///
+/// # Arguments
+///
+/// * `assembler_output`: The stderr output of the assembler.
+/// * `assembly_code`: The assembly code passed to the assembler.
+///
+/// # Returns
+///
+/// The `AssemblerError`s in the order the assembler reported them.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler_driver::parse_assembler_errors;
+/// let errors = parse_assembler_errors(
+///     "{standard input}: Assembler messages:\n{standard input}:2: Error: no such instruction: `movx $2,%eax'\n",
+///     "_main:\n\tmovx $2, %eax\n",
+/// );
+/// assert_eq!(errors[0].to_string(), "line 2: `movx $2, %eax`: Error: no such instruction: `movx $2,%eax'");
+/// ```
+pub fn parse_assembler_errors(assembler_output: &str, assembly_code: &str) -> Vec<AssemblerError> {
+    let assembly_lines: Vec<&str> = assembly_code.lines().collect();
+    assembler_output
+        .lines()
+        .filter_map(|output_line| {
+            let location = output_line
+                .strip_prefix("{standard input}:")
+                .or_else(|| output_line.strip_prefix("<stdin>:"))?;
+            let (line, message) = location.split_once(':')?;
+            let line: usize = line.parse().ok()?;
+            // Clang also reports the column, which is not needed to find the instruction.
+            let message = match message.split_once(':') {
+                Some((column, message)) if column.parse::<usize>().is_ok() => message,
+                _ => message,
+            };
+            Some(AssemblerError {
+                line,
+                instruction: assembly_lines
+                    .get(line.checked_sub(1)?)
+                    .map_or("", |instruction| instruction.trim())
+                    .to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Checks that the system assembler accepts an assembly code, without keeping the object file.
+///
+/// This function invokes `gcc -c -x assembler`, passing the assembly code on stdin, and maps the
+/// reported errors back to the rejected instructions, see `parse_assembler_errors`.
+///
+/// # Arguments
+///
+/// * `assembly_code`: The emitted assembly code to check.
+/// * `options`: The `CompileOptions` providing the target and verbosity. Cross-compilation flags are added when the target differs from the host.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the assembler accepts the assembly code, or an `anyhow::Error` listing the
+/// rejected instructions if it does not, or if GCC cannot be executed.
+pub fn run_assembler_check(assembly_code: &str, options: &CompileOptions) -> anyhow::Result<()> {
+    if !options.quiet {
+        println!("Checking the assembly code with the GCC Assembler...");
+    }
+
+    let temp_dir = tempfile::tempdir()?;
+    let mut child = gcc_command(options)
+        .args(["-c", "-x", "assembler", "-", "-o"])
+        .arg(temp_dir.path().join("check.o"))
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to execute GCC Assembler. Is it installed and in your PATH?")?;
+    child
+        .stdin
+        .take()
+        .expect("The stdin of GCC is piped")
+        .write_all(assembly_code.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if output.status.success() {
+        return Ok(());
+    }
+    let assembler_output = String::from_utf8_lossy(&output.stderr);
+    let errors = parse_assembler_errors(&assembler_output, assembly_code);
+    if errors.is_empty() {
+        return Err(anyhow::anyhow!(
+            "GCC Assembler rejected the assembly code:\n{}",
+            assembler_output.trim_end()
+        ));
+    }
+    let error_lines: Vec<String> = errors.iter().map(|error| format!("  {}", error)).collect();
+    Err(anyhow::anyhow!(
+        "GCC Assembler rejected the assembly code:\n{}",
+        error_lines.join("\n")
+    ))
+}
+
+/// Runs the GCC assembler to create an object file from an assembly file.
+///
+/// This function invokes `gcc -c` to assemble the file without linking it.
+///
+/// # Arguments
+///
+/// * `assembly_file_path`: A reference to the `Path` of the assembly file to assemble.
+/// * `object_file_path`: A reference to the `Path` where the object file should be created.
+/// * `options`: The `CompileOptions` providing the target and verbosity. Cross-compilation flags are added when the target differs from the host.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the assembling is successful.
+/// Returns an `anyhow::Result` with an error if the GCC assembler fails to execute or fails during assembling.
+pub fn run_gcc_assembler(
+    assembly_file_path: &Path,
+    object_file_path: &Path,
+    options: &CompileOptions,
+) -> anyhow::Result<()> {
+    if !options.quiet {
+        println!("Invoking GCC Assembler...");
+    }
+
+    let status = gcc_command(options)
+        .arg("-c")
+        .arg(assembly_file_path)
+        .arg("-o")
+        .arg(object_file_path)
+        .status()
+        .context("Failed to execute GCC Assembler. Is it installed and in your PATH?")?;
+
+    if status.success() {
+        if !options.quiet {
+            println!("Object file created at: {}", object_file_path.display());
+        }
         Ok(())
     } else {
         Err(anyhow::anyhow!(
-            "GCC Preprocessor failed with exit code: {:?}",
+            "GCC Assembler failed with exit code: {:?}",
             status.code()
         ))
     }
 }
 
+/// Builds a static executable from a preprocessed C-- source file without an assembler or linker.
+///
+/// This function compiles the source code with the native backend, encodes the machine code itself
+/// and writes a static executable, see `write_static_executable`. Only x86-64 Linux executables
+/// can be built this way.
+///
+/// # Arguments
+///
+/// * `cmm_source_code`: The preprocessed source code to compile.
+/// * `executable_path`: A reference to the `Path` where the executable should be created.
+/// * `options`: The `CompileOptions` used for the compilation.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the executable is created.
+/// Returns an `anyhow::Result` with an error if the compilation or the encoding fails, or the
+/// executable cannot be written.
+#[cfg(feature = "x86_64")]
+pub fn run_internal_linker(
+    cmm_source_code: &str,
+    executable_path: &Path,
+    options: &CompileOptions,
+) -> anyhow::Result<()> {
+    if !options.quiet {
+        println!("Invoking internal linker...");
+    }
+
+    let codegen_options = CompileOptions {
+        process_until: Some(Stage::Codegen),
+        ..options.clone()
+    };
+    let CompilerResult::Codegen(assembly_ast) =
+        run_cmm_compiler(cmm_source_code, &codegen_options)?
+    else {
+        return Err(anyhow::anyhow!(
+            "The internal toolchain only links code of the native backend"
+        ));
+    };
+    let executable = write_static_executable(&assembly_ast, &options.target)?;
+    std::fs::write(executable_path, executable)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(executable_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    if !options.quiet {
+        println!("Executable file created at: {}", executable_path.display());
+    }
+    Ok(())
+}
+
 /// Run the GCC linker to create an executable from an assembly file.
 ///
 /// This function invokes `gcc -o` to perform linking, and forming the final executable.
 ///
 /// # Arguments
 ///
-/// * `assembly_file_path`: A reference to the `Path` of the assembly file to link.
+/// * `assembly_file_path`: A reference to the `Path` of the assembly or object file to link.
 /// * `executable_path`: A reference to the `Path` where the executable should be created.
+/// * `options`: The `CompileOptions` providing the target and verbosity. Cross-compilation flags are added when the target differs from the host.
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` if the linking process is successful.
 /// Returns an `anyhow::Result` with an error if the GCC linker fails to execute or fails during the linking process.
-pub fn run_gcc_linker(assembly_file_path: &Path, executable_path: &Path) -> anyhow::Result<()> {
-    println!("Invoking GCC Linker...");
+pub fn run_gcc_linker(
+    assembly_file_path: &Path,
+    executable_path: &Path,
+    options: &CompileOptions,
+) -> anyhow::Result<()> {
+    if !options.quiet {
+        println!("Invoking GCC Linker...");
+    }
 
-    let status = Command::new("gcc")
+    let status = gcc_command(options)
         .arg(assembly_file_path)
         .arg("-o")
         .arg(executable_path)
@@ -69,7 +510,9 @@ pub fn run_gcc_linker(assembly_file_path: &Path, executable_path: &Path) -> anyh
         .context("Failed to execute GCC Linker. Is it installed and in your PATH?")?;
 
     if status.success() {
-        println!("Executable file created at: {}", executable_path.display());
+        if !options.quiet {
+            println!("Executable file created at: {}", executable_path.display());
+        }
         Ok(())
     } else {
         Err(anyhow::anyhow!(
@@ -78,3 +521,20 @@ pub fn run_gcc_linker(assembly_file_path: &Path, executable_path: &Path) -> anyh
         ))
     }
 }
+
+/// Creates the command running the gcc driver that builds for the target of the options, see
+/// `Target::compiler_driver`.
+///
+/// # Arguments
+///
+/// * `options`: The compile options holding the target.
+///
+/// # Returns
+///
+/// A `Command` running the driver with the flags selecting the target.
+fn gcc_command(options: &CompileOptions) -> Command {
+    let (driver, flags) = options.target.compiler_driver(&Target::host());
+    let mut command = Command::new(driver);
+    command.args(flags);
+    command
+}
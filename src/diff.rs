@@ -0,0 +1,334 @@
+use std::fmt;
+
+/// The string used to indent nested nodes in a printed diff.
+const INDENT: &str = "    ";
+
+/// Represents a node of a generic tree built from the pretty-printed `Debug` output of a value.
+///
+/// Every compiler stage output derives `Debug`, so building the tree from it keeps the diff in sync
+/// with the AST and IR definitions without any per-type code.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TreeNode {
+    /// The text of the node without its opening delimiter, e.g. `function: Function`.
+    pub label: String,
+    /// The nested nodes, in order.
+    pub children: Vec<TreeNode>,
+}
+
+/// Represents the kind of a line in a printed tree diff.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DiffKind {
+    /// An unchanged ancestor of a change, printed for context.
+    Context,
+    /// A node that only exists in the old tree.
+    Removed,
+    /// A node that only exists in the new tree.
+    Added,
+}
+
+/// Represents a single line of a printed tree diff.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    /// The nesting depth of the node.
+    pub depth: usize,
+    pub label: String,
+}
+
+/// Represents a structural diff between two trees.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TreeDiff {
+    pub lines: Vec<DiffLine>,
+}
+
+impl TreeDiff {
+    /// Returns `true` if the compared trees are identical.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+impl fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.lines.iter() {
+            let marker = match line.kind {
+                DiffKind::Context => ' ',
+                DiffKind::Removed => '-',
+                DiffKind::Added => '+',
+            };
+            writeln!(f, "{} {}{}", marker, INDENT.repeat(line.depth), line.label)?;
+        }
+        Ok(())
+    }
+}
+
+/// Represents an edit step produced by aligning two lists of sibling nodes.
+enum Edit {
+    Keep,
+    Remove(usize),
+    Add(usize),
+}
+
+/// Builds a tree from the pretty-printed `Debug` output of a value.
+///
+/// # Arguments
+///
+/// * `value`: The value to convert.
+///
+/// # Returns
+///
+/// The root `TreeNode` of the value.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::ir_gen::tacky_ast::TackyConstant;
+/// # use cmm::diff::build_tree;
+/// let tree = build_tree(&TackyConstant::Int(2));
+/// assert_eq!(tree.label, "Int");
+/// assert_eq!(tree.children[0].label, "2");
+/// ```
+pub fn build_tree<T: fmt::Debug + ?Sized>(value: &T) -> TreeNode {
+    let debug_output = format!("{:#?}", value);
+    let mut stack = vec![TreeNode {
+        label: String::new(),
+        children: vec![],
+    }];
+    for line in debug_output.lines() {
+        let text = line.trim();
+        let text = text.strip_suffix(',').unwrap_or(text);
+        if matches!(text, "}" | "]" | ")") {
+            if let Some(node) = stack.pop() {
+                push_child(&mut stack, node);
+            }
+        } else if let Some((label, delimiters)) = [("{", "{}"), ("[", "[]"), ("(", "()")]
+            .iter()
+            .find_map(|(opener, delimiters)| Some((text.strip_suffix(opener)?, delimiters)))
+        {
+            // Anonymous lists and tuples are labeled with their delimiters.
+            let label = match label.trim_end() {
+                "" => delimiters,
+                label => label,
+            };
+            stack.push(TreeNode {
+                label: label.to_string(),
+                children: vec![],
+            });
+        } else {
+            push_child(
+                &mut stack,
+                TreeNode {
+                    label: text.to_string(),
+                    children: vec![],
+                },
+            );
+        }
+    }
+    // Unbalanced output cannot come from a derived `Debug`, but collapse it rather than lose nodes.
+    while stack.len() > 1 {
+        let node = stack.pop().unwrap();
+        push_child(&mut stack, node);
+    }
+    let mut root = stack.pop().unwrap();
+    match root.children.len() {
+        1 => root.children.pop().unwrap(),
+        _ => root,
+    }
+}
+
+/// Attaches a node to the innermost open node of the stack.
+fn push_child(stack: &mut [TreeNode], node: TreeNode) {
+    if let Some(parent) = stack.last_mut() {
+        parent.children.push(node);
+    }
+}
+
+/// Computes a minimal structural diff between the `Debug` representations of two values.
+///
+/// Unchanged subtrees are omitted; only the changed nodes and their ancestors are part of the diff.
+///
+/// # Arguments
+///
+/// * `old`: The value to compare against.
+/// * `new`: The changed value.
+///
+/// # Returns
+///
+/// A `TreeDiff`, which is empty if both values have the same structure.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::compiler::ir_gen::tacky_ast::TackyConstant;
+/// # use cmm::diff::structural_diff;
+/// let diff = structural_diff(&TackyConstant::Int(2), &TackyConstant::Int(3));
+/// assert_eq!(diff.to_string(), "  Int\n-     2\n+     3\n");
+/// assert!(structural_diff(&TackyConstant::Int(2), &TackyConstant::Int(2)).is_empty());
+/// ```
+pub fn structural_diff<T: fmt::Debug + ?Sized>(old: &T, new: &T) -> TreeDiff {
+    let old_tree = build_tree(old);
+    let new_tree = build_tree(new);
+    let mut lines = vec![];
+    diff_siblings(
+        std::slice::from_ref(&old_tree),
+        std::slice::from_ref(&new_tree),
+        0,
+        &mut lines,
+    );
+    TreeDiff { lines }
+}
+
+/// Appends the diff of two lists of sibling nodes to `lines`.
+///
+/// The siblings are aligned with a longest common subsequence. A removed and an added node with the
+/// same label are treated as a modification of the same node and diffed recursively.
+fn diff_siblings(old: &[TreeNode], new: &[TreeNode], depth: usize, lines: &mut Vec<DiffLine>) {
+    let edits = align_siblings(old, new);
+    let mut removed: Vec<usize> = vec![];
+    let mut added: Vec<usize> = vec![];
+    for edit in edits.into_iter().chain(std::iter::once(Edit::Keep)) {
+        match edit {
+            Edit::Remove(index) => removed.push(index),
+            Edit::Add(index) => added.push(index),
+            Edit::Keep => {
+                diff_changed_run(old, new, &removed, &added, depth, lines);
+                removed.clear();
+                added.clear();
+            }
+        }
+    }
+}
+
+/// Appends the diff of a run of removed and added siblings between two unchanged siblings.
+fn diff_changed_run(
+    old: &[TreeNode],
+    new: &[TreeNode],
+    removed: &[usize],
+    added: &[usize],
+    depth: usize,
+    lines: &mut Vec<DiffLine>,
+) {
+    let paired = removed.len().min(added.len());
+    for (&old_index, &new_index) in removed.iter().zip(added.iter()) {
+        let (old_node, new_node) = (&old[old_index], &new[new_index]);
+        if old_node.label == new_node.label && !old_node.children.is_empty() {
+            lines.push(DiffLine {
+                kind: DiffKind::Context,
+                depth,
+                label: old_node.label.clone(),
+            });
+            diff_siblings(&old_node.children, &new_node.children, depth + 1, lines);
+        } else {
+            push_subtree(old_node, DiffKind::Removed, depth, lines);
+            push_subtree(new_node, DiffKind::Added, depth, lines);
+        }
+    }
+    for &old_index in &removed[paired..] {
+        push_subtree(&old[old_index], DiffKind::Removed, depth, lines);
+    }
+    for &new_index in &added[paired..] {
+        push_subtree(&new[new_index], DiffKind::Added, depth, lines);
+    }
+}
+
+/// Aligns two lists of sibling nodes using a longest common subsequence of equal subtrees.
+fn align_siblings(old: &[TreeNode], new: &[TreeNode]) -> Vec<Edit> {
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            edits.push(Edit::Remove(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Add(j));
+            j += 1;
+        }
+    }
+    edits.extend((i..old.len()).map(Edit::Remove));
+    edits.extend((j..new.len()).map(Edit::Add));
+    edits
+}
+
+/// Appends a node and all of its descendants to `lines` with the given kind.
+fn push_subtree(node: &TreeNode, kind: DiffKind, depth: usize, lines: &mut Vec<DiffLine>) {
+    lines.push(DiffLine {
+        kind,
+        depth,
+        label: node.label.clone(),
+    });
+    for child in node.children.iter() {
+        push_subtree(child, kind, depth + 1, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::symbol::Symbol;
+    use crate::compiler::ir_gen::tacky_ast::{TackyConstant, TackyInstruction, TackyValue};
+
+    #[test]
+    fn test_build_tree_nested_values() {
+        let instructions = vec![TackyInstruction::Return {
+            value: Some(TackyValue::Variable(Symbol::from("tmp.0"))),
+        }];
+        let tree = build_tree(&instructions);
+        assert_eq!(
+            tree,
+            TreeNode {
+                label: String::from("[]"),
+                children: vec![TreeNode {
+                    label: String::from("Return"),
+                    children: vec![TreeNode {
+                        label: String::from("value: Some"),
+                        children: vec![TreeNode {
+                            label: String::from("Variable"),
+                            children: vec![TreeNode {
+                                label: String::from("\"tmp.0\""),
+                                children: vec![],
+                            }],
+                        }],
+                    }],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_structural_diff_inserted_sibling() {
+        let old = vec![
+            TackyInstruction::Label(Symbol::from("start")),
+            TackyInstruction::Return {
+                value: Some(TackyValue::Constant(TackyConstant::Int(1))),
+            },
+        ];
+        let new = vec![
+            TackyInstruction::Label(Symbol::from("start")),
+            TackyInstruction::Jump {
+                target: Symbol::from("start"),
+            },
+            TackyInstruction::Return {
+                value: Some(TackyValue::Constant(TackyConstant::Int(1))),
+            },
+        ];
+        assert_eq!(
+            structural_diff(&old, &new).to_string(),
+            "  []\n+     Jump\n+         target: \"start\"\n"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::common::target::Target;
+use crate::compiler::compile_to_assembly_string;
+use std::ffi::{CStr, CString, c_char};
+
+/// Represents the outcome of a call to `cmm_compile`.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CmmStatus {
+    /// The source compiled, the output string holds the assembly code.
+    Ok = 0,
+    /// A pointer was null, a string was not valid UTF-8, or the target triple was unknown. The
+    /// output string is left untouched.
+    InvalidArgument = 1,
+    /// The source failed to compile, the output string holds the error message.
+    CompileError = 2,
+}
+
+/// Represents the options of a compilation requested over the C API.
+#[repr(C)]
+#[derive(Debug)]
+pub struct CmmCompileOptions {
+    /// A NUL-terminated target triple such as `x86_64-unknown-linux-gnu`, or null for the default
+    /// target.
+    pub target: *const c_char,
+}
+
+/// Compiles preprocessed C-- source code to assembly code.
+///
+/// On `CmmStatus::Ok` the assembly code, and on `CmmStatus::CompileError` the error message, is
+/// written to `out_assembly` as a newly allocated NUL-terminated string, which the caller must
+/// release with `cmm_string_free`.
+///
+/// # Arguments
+///
+/// * `source`: The NUL-terminated source code to compile.
+/// * `options`: The `CmmCompileOptions` of the compilation, or null for the default options.
+/// * `out_assembly`: The location to store the output string in.
+///
+/// # Returns
+///
+/// The `CmmStatus` describing the outcome of the compilation.
+///
+/// # Safety
+///
+/// `source` must point to a NUL-terminated string, `options` must be null or point to valid
+/// `CmmCompileOptions` whose `target` is null or NUL-terminated, and `out_assembly` must be valid
+/// for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cmm_compile(
+    source: *const c_char,
+    options: *const CmmCompileOptions,
+    out_assembly: *mut *mut c_char,
+) -> CmmStatus {
+    if source.is_null() || out_assembly.is_null() {
+        return CmmStatus::InvalidArgument;
+    }
+    // SAFETY: The caller guarantees that the non-null pointers are valid.
+    let (source, options) = unsafe { (CStr::from_ptr(source), options.as_ref()) };
+    let Ok(source) = source.to_str() else {
+        return CmmStatus::InvalidArgument;
+    };
+    let target = match options.map(|options| options.target) {
+        Some(target) if !target.is_null() => {
+            // SAFETY: The caller guarantees that a non-null target is NUL-terminated.
+            let target = unsafe { CStr::from_ptr(target) };
+            match target.to_str().ok().map(str::parse::<Target>) {
+                Some(Ok(target)) => target,
+                _ => return CmmStatus::InvalidArgument,
+            }
+        }
+        _ => Target::default(),
+    };
+
+    let (status, output) = match compile_to_assembly_string(source, &target) {
+        Ok(assembly_code) => (CmmStatus::Ok, assembly_code),
+        Err(error) => (CmmStatus::CompileError, error.to_string()),
+    };
+    // Neither the assembly code nor the error messages contain NUL bytes, the source is a C string.
+    let output = CString::new(output).expect("Compiler output should not contain NUL bytes");
+    // SAFETY: The caller guarantees that `out_assembly` is valid for writes.
+    unsafe { out_assembly.write(output.into_raw()) };
+    status
+}
+
+/// Releases a string returned by `cmm_compile`.
+///
+/// # Arguments
+///
+/// * `string`: The string to release, or null.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by `cmm_compile` that has not been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cmm_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: The caller guarantees that the string was allocated by `cmm_compile`.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    /// Runs `cmm_compile` and copies its output into an owned string.
+    fn compile_with_c_api(
+        source: &CStr,
+        options: Option<&CmmCompileOptions>,
+    ) -> (CmmStatus, String) {
+        let mut output = ptr::null_mut();
+        let options = options.map_or(ptr::null(), |options| options as *const _);
+        let status = unsafe { cmm_compile(source.as_ptr(), options, &mut output) };
+        let output_string = unsafe { CStr::from_ptr(output) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { cmm_string_free(output) };
+        (status, output_string)
+    }
+
+    #[test]
+    fn test_compile_returns_assembly() {
+        let target = c"x86_64-unknown-linux-gnu";
+        let options = CmmCompileOptions {
+            target: target.as_ptr(),
+        };
+        let (status, assembly_code) =
+            compile_with_c_api(c"int main(void) { return 2; }", Some(&options));
+        assert_eq!(status, CmmStatus::Ok);
+        assert!(assembly_code.contains(".globl main\nmain:\n"));
+        assert!(assembly_code.contains("movl $2, %eax"));
+    }
+
+    #[test]
+    fn test_compile_returns_error_message() {
+        let (status, message) = compile_with_c_api(c"int main(void) { return }", None);
+        assert_eq!(status, CmmStatus::CompileError);
+        assert!(!message.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_arguments_leave_output_untouched() {
+        let mut output = ptr::null_mut();
+        let status = unsafe { cmm_compile(ptr::null(), ptr::null(), &mut output) };
+        assert_eq!(status, CmmStatus::InvalidArgument);
+        assert!(output.is_null());
+
+        let target = c"sparc-sun-solaris";
+        let options = CmmCompileOptions {
+            target: target.as_ptr(),
+        };
+        let status = unsafe {
+            cmm_compile(
+                c"int main(void) { return 2; }".as_ptr(),
+                &options,
+                &mut output,
+            )
+        };
+        assert_eq!(status, CmmStatus::InvalidArgument);
+        assert!(output.is_null());
+        unsafe { cmm_string_free(output) };
+    }
+}
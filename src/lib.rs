@@ -1,5 +1,24 @@
+#[cfg(not(target_family = "wasm"))]
+pub mod cache;
 pub mod common {
+    #[cfg(not(target_family = "wasm"))]
+    pub mod config;
+    pub mod diagnostics;
+    pub mod target;
     pub mod validation;
+    pub use cmm_types::symbol;
 }
 pub mod compiler;
+#[cfg(not(target_family = "wasm"))]
 pub mod compiler_driver;
+pub mod diff;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "repl")]
+pub mod repl;
+#[cfg(feature = "cli")]
+pub mod test_suite;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -0,0 +1,300 @@
+use crate::common::diagnostics::{Diagnostic, Severity};
+use crate::compiler::session::CompilerSession;
+use crate::compiler::{CompileOptions, Stage};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+
+/// The name reported as the source of every published diagnostic.
+const DIAGNOSTIC_SOURCE: &str = "cmm";
+
+/// JSON-RPC error code for requests the server does not implement.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Represents a minimal language server reporting C-- diagnostics.
+///
+/// The server keeps no document state: every `didOpen` and `didChange` notification carries the
+/// full document text, which is compiled up to TACKY emission to collect the diagnostics.
+#[derive(Debug, Default)]
+pub struct LanguageServer {
+    /// Whether the client has sent the `shutdown` request.
+    shutdown_requested: bool,
+    /// Whether the client has sent the `exit` notification.
+    exit_requested: bool,
+}
+
+impl LanguageServer {
+    /// Creates a new `LanguageServer` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` once the client has asked the server to exit.
+    pub fn should_exit(&self) -> bool {
+        self.exit_requested
+    }
+
+    /// Returns the process exit code mandated by the protocol, which depends on a prior `shutdown`.
+    pub fn exit_code(&self) -> i32 {
+        if self.shutdown_requested { 0 } else { 1 }
+    }
+
+    /// Handles a single JSON-RPC message from the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`: The decoded JSON-RPC request or notification.
+    ///
+    /// # Returns
+    ///
+    /// The responses and notifications to send back to the client, in order.
+    pub fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+        match (method, id) {
+            ("initialize", Some(id)) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": {
+                    "capabilities": { "textDocumentSync": 1 },
+                    "serverInfo": { "name": "cmm-lsp", "version": env!("CARGO_PKG_VERSION") },
+                },
+            })],
+            ("shutdown", Some(id)) => {
+                self.shutdown_requested = true;
+                vec![json!({ "jsonrpc": "2.0", "id": id, "result": null })]
+            }
+            ("exit", None) => {
+                self.exit_requested = true;
+                vec![]
+            }
+            ("textDocument/didOpen", None) => {
+                let document = &message["params"]["textDocument"];
+                publish_diagnostics(&document["uri"], document["text"].as_str())
+            }
+            ("textDocument/didChange", None) => {
+                let params = &message["params"];
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                publish_diagnostics(&params["textDocument"]["uri"], text)
+            }
+            (_, Some(id)) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("Unsupported method '{}'", method) },
+            })],
+            (_, None) => vec![],
+        }
+    }
+}
+
+/// Compiles a document and builds the `textDocument/publishDiagnostics` notification for it.
+fn publish_diagnostics(uri: &Value, text: Option<&str>) -> Vec<Value> {
+    let Some(text) = text else {
+        return vec![];
+    };
+    let diagnostics: Vec<Value> = diagnose(text).iter().map(to_lsp_diagnostic).collect();
+    vec![json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })]
+}
+
+/// Collects the lexer, parser, and semantic diagnostics and the warnings of a C-- source file.
+///
+/// # Arguments
+///
+/// * `source_code`: The source code to check.
+///
+/// # Returns
+///
+/// The diagnostics reported for the source code, empty if it compiles without warnings.
+pub fn diagnose(source_code: &str) -> Vec<Diagnostic> {
+    let options = CompileOptions {
+        process_until: Some(Stage::Tacky),
+        quiet: true,
+        ..Default::default()
+    };
+    let mut session = CompilerSession::new(options);
+    // A failed compilation reports its error as a diagnostic of the session.
+    let _ = session.compile(source_code);
+    session.take_diagnostics()
+}
+
+/// Converts a diagnostic into the LSP representation, with 0-based positions.
+///
+/// Diagnostics without a span are reported at the start of the document.
+fn to_lsp_diagnostic(diagnostic: &Diagnostic) -> Value {
+    let (start, end) = match diagnostic.span {
+        Some(span) => (
+            json!({ "line": span.start_line - 1, "character": span.start_column - 1 }),
+            json!({ "line": span.end_line - 1, "character": span.end_column - 1 }),
+        ),
+        None => (
+            json!({ "line": 0, "character": 0 }),
+            json!({ "line": 0, "character": 0 }),
+        ),
+    };
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+    json!({
+        "range": { "start": start, "end": end },
+        "severity": severity,
+        "code": diagnostic.code,
+        "source": DIAGNOSTIC_SOURCE,
+        "message": diagnostic.message,
+    })
+}
+
+/// Reads a single `Content-Length` framed JSON-RPC message.
+///
+/// # Arguments
+///
+/// * `reader`: The stream to read from.
+///
+/// # Returns
+///
+/// The decoded message, `None` when the stream has ended, or an error on malformed input.
+pub fn read_message(reader: &mut impl BufRead) -> std::io::Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse().ok();
+        }
+    }
+    let content_length = content_length.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Missing Content-Length header",
+        )
+    })?;
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+/// Writes a single JSON-RPC message with a `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+/// Serves the language server protocol until the client sends the `exit` notification.
+///
+/// # Arguments
+///
+/// * `reader`: The stream to read client messages from.
+/// * `writer`: The stream to write server messages to.
+///
+/// # Returns
+///
+/// The process exit code on success, or an error if the streams fail.
+pub fn run_server(mut reader: impl BufRead, mut writer: impl Write) -> std::io::Result<i32> {
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(&mut reader)? {
+        for outgoing in server.handle_message(&message) {
+            write_message(&mut writer, &outgoing)?;
+        }
+        if server.should_exit() {
+            return Ok(server.exit_code());
+        }
+    }
+    Ok(server.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(message: &Value) -> String {
+        let content = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+    }
+
+    #[test]
+    fn test_did_open_publishes_diagnostics() {
+        let mut server = LanguageServer::new();
+        let outgoing = server.handle_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///main.c", "text": "int main(void) { return }" } },
+        }));
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0]["params"]["uri"], "file:///main.c");
+        let diagnostics = outgoing[0]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 0);
+    }
+
+    #[test]
+    fn test_diagnose_reports_warnings() {
+        let diagnostics = diagnose("int main(void) { return 2 / 0; }");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(to_lsp_diagnostic(&diagnostics[0])["severity"], 2);
+    }
+
+    #[test]
+    fn test_diagnose_locates_semantic_errors() {
+        let diagnostics = diagnose("int main(void) {\n  return x;\n}");
+        assert_eq!(diagnostics.len(), 1);
+        let range = &to_lsp_diagnostic(&diagnostics[0])["range"];
+        assert_eq!(range["start"], json!({ "line": 1, "character": 9 }));
+        assert_eq!(range["end"], json!({ "line": 1, "character": 10 }));
+    }
+
+    #[test]
+    fn test_did_change_clears_diagnostics() {
+        let mut server = LanguageServer::new();
+        let outgoing = server.handle_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": "file:///main.c", "version": 2 },
+                "contentChanges": [{ "text": "int main(void) { return 2; }" }],
+            },
+        }));
+        assert_eq!(outgoing[0]["params"]["diagnostics"], json!([]));
+    }
+
+    #[test]
+    fn test_run_server_session() {
+        let input = [
+            frame(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} })),
+            frame(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} })),
+            frame(&json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" })),
+            frame(&json!({ "jsonrpc": "2.0", "method": "exit" })),
+        ]
+        .concat();
+        let mut output = Vec::new();
+        let exit_code = run_server(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(exit_code, 0);
+
+        let mut reader = output.as_slice();
+        let initialize = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(initialize["result"]["capabilities"]["textDocumentSync"], 1);
+        let shutdown = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(shutdown["id"], 2);
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::compiler::errors::CompilerError;
+use crate::compiler::interpreter::errors::InterpreterError;
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors that can occur while evaluating REPL input.
+#[derive(Debug, PartialEq)]
+pub enum ReplError {
+    /// Raised when the input cannot be compiled into TACKY IR.
+    Compiler(CompilerError),
+    /// Raised when the TACKY IR cannot be evaluated, e.g. on division by zero.
+    Interpreter(InterpreterError),
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplError::Compiler(error) => write!(f, "{}", error),
+            ReplError::Interpreter(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for ReplError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplError::Compiler(error) => Some(error),
+            ReplError::Interpreter(error) => Some(error),
+        }
+    }
+}
+
+impl From<CompilerError> for ReplError {
+    fn from(error: CompilerError) -> Self {
+        ReplError::Compiler(error)
+    }
+}
+
+impl From<InterpreterError> for ReplError {
+    fn from(error: InterpreterError) -> Self {
+        ReplError::Interpreter(error)
+    }
+}
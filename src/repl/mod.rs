@@ -0,0 +1,245 @@
+pub mod errors;
+
+use crate::common::symbol::Symbol;
+use crate::compiler::errors::CompilerError;
+use crate::compiler::interpreter::interpret_function;
+use crate::compiler::ir_gen::TackyEmitter;
+use crate::compiler::lexer::tokenize;
+use crate::compiler::parser::Parser;
+use crate::compiler::parser::cmm_ast::{
+    CmmAst, CmmBlockItem, CmmFunction, CmmStatement, CmmType, ExprId, ExpressionArena,
+};
+use crate::compiler::semantic::{check_program, validate};
+use errors::ReplError;
+use std::fmt;
+use std::io::{BufRead, Write};
+
+/// The prompt printed before reading each line of input.
+const PROMPT: &str = "cmm> ";
+
+/// The name of the function the input is wrapped into, which no C-- identifier can collide with
+/// because it is reserved for the implementation.
+const ENTRY_FUNCTION: &str = "__cmm_repl";
+
+/// The value of an evaluated expression in its C type.
+///
+/// The integer types narrower than `int` are shown as the `int` they promote to, which prints the
+/// same number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplValue {
+    /// A value of type `int`, or of a narrower integer type.
+    Int(i32),
+    /// A value of type `unsigned int`.
+    UnsignedInt(u32),
+    /// A value of type `long`.
+    Long(i64),
+    /// A value of type `unsigned long`.
+    UnsignedLong(u64),
+    /// A value of type `double`.
+    Double(f64),
+    /// An address, such as the value of a string literal.
+    Pointer(u64),
+}
+
+impl ReplValue {
+    /// Converts a value returned by the interpreter to the type it was computed in.
+    fn new(value: i64, value_type: &CmmType) -> Self {
+        match value_type.unqualified() {
+            CmmType::UnsignedInt => ReplValue::UnsignedInt(value as u32),
+            CmmType::Long => ReplValue::Long(value),
+            CmmType::UnsignedLong => ReplValue::UnsignedLong(value as u64),
+            CmmType::Double => ReplValue::Double(f64::from_bits(value as u64)),
+            CmmType::Pointer(_) | CmmType::Array(..) => ReplValue::Pointer(value as u64),
+            _ => ReplValue::Int(value as i32),
+        }
+    }
+}
+
+impl fmt::Display for ReplValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplValue::Int(value) => write!(f, "{}", value),
+            ReplValue::UnsignedInt(value) => write!(f, "{}", value),
+            ReplValue::Long(value) => write!(f, "{}", value),
+            ReplValue::UnsignedLong(value) => write!(f, "{}", value),
+            // The debug format keeps the fraction of whole numbers, so `3.0` is not shown as `3`.
+            ReplValue::Double(value) => write!(f, "{:?}", value),
+            ReplValue::Pointer(value) => write!(f, "{:#x}", value),
+        }
+    }
+}
+
+/// Evaluates a single C-- expression or statement with the TACKY interpreter.
+///
+/// The input is wrapped into a function without parameters. A bare expression, or the expression
+/// of a `return` statement, is type-checked first and the function returns it in its own type, so
+/// that `1.5 * 2` evaluates to the `double` 3.0 and `(unsigned)-1` to 4294967295. Any other
+/// statement evaluates to the `int` it returns.
+///
+/// # Arguments
+///
+/// * `input`: The expression or statement to evaluate.
+///
+/// # Returns
+///
+/// A `Result` containing the value of the input on success, or a `ReplError` on failure.
+///
+/// # Examples
+///
+/// ```
+/// # use cmm::repl::{ReplValue, evaluate};
+/// assert_eq!(evaluate("1 + 2 * 3"), Ok(ReplValue::Int(7)));
+/// assert_eq!(evaluate("return -7 % 2;"), Ok(ReplValue::Int(-1)));
+/// assert_eq!(evaluate("1.5 * 2"), Ok(ReplValue::Double(3.0)));
+/// assert!(evaluate("1 / 0").is_err());
+/// ```
+pub fn evaluate(input: &str) -> Result<ReplValue, ReplError> {
+    let tokens = tokenize(input).map_err(CompilerError::from)?;
+    let mut parser = Parser::new(tokens);
+    let body = parser
+        .parse_statement_or_expression()
+        .map_err(CompilerError::from)?;
+    let expressions = parser.into_expressions();
+    let value_type = match &body {
+        CmmStatement::Return {
+            expression: Some(expression),
+            ..
+        } => expression_type(*expression, &expressions)?,
+        _ => CmmType::Int,
+    };
+    let cmm_ast = wrap_into_function(body, value_type.clone(), expressions);
+    let validated_ast = validate(cmm_ast).map_err(CompilerError::from)?;
+    let tacky_ast = TackyEmitter::new()
+        .convert_ast(validated_ast)
+        .map_err(CompilerError::from)?;
+    let value = interpret_function(&tacky_ast, ENTRY_FUNCTION)?;
+    Ok(ReplValue::new(value, &value_type))
+}
+
+/// Type-checks an expression on its own and returns its type, with arrays decayed to pointers.
+///
+/// An expression without a value, such as a call of a `void` function, is given the type `int`, so
+/// that the type checker reports it when it is returned.
+fn expression_type(
+    expression: ExprId,
+    expressions: &ExpressionArena,
+) -> Result<CmmType, ReplError> {
+    let cmm_ast = wrap_into_function(
+        CmmStatement::Expression {
+            expression,
+            span: None,
+        },
+        CmmType::Int,
+        expressions.clone(),
+    );
+    let annotations = check_program(&cmm_ast).map_err(CompilerError::from)?;
+    Ok(annotations
+        .expression_type(expression)
+        .map_or(CmmType::Int, |value_type| {
+            value_type.decayed().unqualified().clone()
+        }))
+}
+
+/// Builds a program whose only function, `ENTRY_FUNCTION`, runs a statement.
+fn wrap_into_function(
+    body: CmmStatement,
+    return_type: CmmType,
+    expressions: ExpressionArena,
+) -> CmmAst {
+    CmmAst::Program {
+        structs: vec![],
+        enums: vec![],
+        variables: vec![],
+        functions: vec![CmmFunction::Function {
+            identifier: Symbol::from(ENTRY_FUNCTION),
+            storage_class: None,
+            return_type: Some(return_type),
+            parameters: vec![],
+            variadic: false,
+            body: Some(vec![CmmBlockItem::Statement(body)]),
+            span: None,
+        }],
+        expressions,
+    }
+}
+
+/// Runs an interactive read-eval-print loop until the input ends or `exit` is entered.
+///
+/// Values are written to `output`, errors are reported on the same stream so that a session reads
+/// like a transcript.
+///
+/// # Arguments
+///
+/// * `input`: The stream to read lines from.
+/// * `output`: The stream to write prompts and results to.
+///
+/// # Returns
+///
+/// A `Result` that is `Err` only if reading or writing the streams fails.
+pub fn run_repl(input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+    write!(output, "{}", PROMPT)?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        match line.trim() {
+            "" => {}
+            "exit" | "quit" => return Ok(()),
+            source => match evaluate(source) {
+                Ok(value) => writeln!(output, "{}", value)?,
+                Err(error) => writeln!(output, "{}", error)?,
+            },
+        }
+        write!(output, "{}", PROMPT)?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::interpreter::errors::InterpreterError;
+
+    #[test]
+    fn test_evaluate_errors() {
+        assert!(matches!(
+            evaluate("1 +"),
+            Err(ReplError::Compiler(CompilerError::Parser(_)))
+        ));
+        assert_eq!(
+            evaluate("1 / (2 - 2)"),
+            Err(ReplError::Interpreter(InterpreterError::DivisionByZero))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_in_the_type_of_the_expression() {
+        assert_eq!(evaluate("1.5 * 2"), Ok(ReplValue::Double(3.0)));
+        assert_eq!(
+            evaluate("(unsigned)-1"),
+            Ok(ReplValue::UnsignedInt(u32::MAX))
+        );
+        assert_eq!(evaluate("return 1l << 40;"), Ok(ReplValue::Long(1 << 40)));
+        assert_eq!(
+            evaluate("18446744073709551615ul"),
+            Ok(ReplValue::UnsignedLong(u64::MAX))
+        );
+        assert_eq!(evaluate("(char)300"), Ok(ReplValue::Int(44)));
+        assert_eq!(evaluate("(short)-2"), Ok(ReplValue::Int(-2)));
+        assert!(matches!(evaluate("\"abc\""), Ok(ReplValue::Pointer(_))));
+        // Statements other than `return` evaluate to the `int` they return.
+        assert_eq!(evaluate("if (1) return 2.5;"), Ok(ReplValue::Int(2)));
+    }
+
+    #[test]
+    fn test_run_repl_transcript() {
+        let input = "1 + 2\n\n!0 && 3 > 2;\n1.0 / 4\n@\nexit\n4\n";
+        let mut output = Vec::new();
+        run_repl(input.as_bytes(), &mut output).unwrap();
+        let transcript = String::from_utf8(output).unwrap();
+        assert_eq!(
+            transcript,
+            "cmm> 3\ncmm> cmm> 1\ncmm> 0.25\ncmm> Lexer error: Invalid token '@' at line 1, column 1\ncmm> "
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::compiler::interpreter::interpret;
+use crate::compiler::{CompileOptions, CompilerResult, Stage, run_cmm_compiler};
+use crate::compiler_driver::{compile_file, run_gcc_linker, run_gcc_preprocessor};
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the file in the suite root that maps valid test programs to their expected results.
+const EXPECTED_RESULTS_FILE: &str = "expected_results.json";
+
+/// Directories holding multi-file programs, which need to be linked against a client and are not run.
+const LIBRARIES_DIRECTORY: &str = "libraries";
+
+/// Directories holding tests for optional language features.
+const EXTRA_CREDIT_DIRECTORY: &str = "extra_credit";
+
+/// Represents what is expected from compiling a test program.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expectation {
+    /// The program compiles and its executable exits with the given code.
+    ExitCode(i32),
+    /// The program compiles, the expected exit code is computed with the TACKY interpreter.
+    Interpreted,
+    /// The program must be rejected by the compiler.
+    CompileError,
+}
+
+/// Represents a single test program of the conformance suite.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TestCase {
+    /// The path of the program, relative to the suite root.
+    pub path: PathBuf,
+    pub expectation: Expectation,
+}
+
+/// Represents the outcome of running a single test case.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TestOutcome {
+    Pass,
+    /// The test failed, with a description of the failure.
+    Fail(String),
+}
+
+/// Represents the results of all test cases of a chapter.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChapterReport {
+    pub chapter: u32,
+    pub results: Vec<(TestCase, TestOutcome)>,
+}
+
+impl ChapterReport {
+    /// Returns the number of passed test cases.
+    pub fn passed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, outcome)| *outcome == TestOutcome::Pass)
+            .count()
+    }
+
+    /// Returns `true` if every test case of the chapter passed.
+    pub fn all_passed(&self) -> bool {
+        self.passed() == self.results.len()
+    }
+}
+
+impl fmt::Display for ChapterReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (test_case, outcome) in self.results.iter() {
+            if let TestOutcome::Fail(reason) = outcome {
+                writeln!(f, "FAIL {}: {}", test_case.path.display(), reason)?;
+            }
+        }
+        writeln!(
+            f,
+            "Chapter {}: {}/{} passed",
+            self.chapter,
+            self.passed(),
+            self.results.len()
+        )
+    }
+}
+
+/// Represents the options controlling which test programs of the suite are run.
+#[derive(Debug, Default, Clone)]
+pub struct TestSuiteOptions {
+    /// Also runs the tests of optional language features.
+    pub extra_credit: bool,
+}
+
+/// Finds the chapters available in a test suite directory.
+///
+/// Chapters are directories named `chapter_<N>`, as in the test suite of "Writing a C Compiler".
+///
+/// # Arguments
+///
+/// * `root`: The root directory of the test suite.
+///
+/// # Returns
+///
+/// The sorted chapter numbers, or an error if the directory cannot be read.
+pub fn discover_chapters(root: &Path) -> anyhow::Result<Vec<u32>> {
+    let mut chapters: Vec<u32> = std::fs::read_dir(root)
+        .with_context(|| format!("Failed to read test suite '{}'", root.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("chapter_")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    chapters.sort_unstable();
+    Ok(chapters)
+}
+
+/// Finds the test programs of a chapter.
+///
+/// Programs below `valid/` must compile, programs below any `invalid_*/` directory must be rejected.
+/// Expected exit codes are read from `expected_results.json` in the suite root when it exists.
+///
+/// # Arguments
+///
+/// * `root`: The root directory of the test suite.
+/// * `chapter`: The chapter to discover the test programs of.
+/// * `options`: The `TestSuiteOptions` selecting optional tests.
+///
+/// # Returns
+///
+/// The test cases sorted by path, or an error if the suite cannot be read.
+pub fn discover_test_cases(
+    root: &Path,
+    chapter: u32,
+    options: &TestSuiteOptions,
+) -> anyhow::Result<Vec<TestCase>> {
+    let expected_exit_codes = read_expected_exit_codes(root)?;
+    let chapter_directory = PathBuf::from(format!("chapter_{}", chapter));
+    let mut test_cases = vec![];
+    for entry in std::fs::read_dir(root.join(&chapter_directory))
+        .with_context(|| format!("Chapter {} not found in '{}'", chapter, root.display()))?
+    {
+        let entry = entry?;
+        let group = entry.file_name().to_string_lossy().into_owned();
+        let is_valid = group == "valid";
+        if !entry.path().is_dir() || !(is_valid || group.starts_with("invalid")) {
+            continue;
+        }
+        for path in find_c_files(&entry.path(), options)? {
+            let relative_path = path.strip_prefix(root)?.to_path_buf();
+            let expectation = if !is_valid {
+                Expectation::CompileError
+            } else {
+                match expected_exit_codes.get(&relative_path) {
+                    Some(exit_code) => Expectation::ExitCode(*exit_code),
+                    None => Expectation::Interpreted,
+                }
+            };
+            test_cases.push(TestCase {
+                path: relative_path,
+                expectation,
+            });
+        }
+    }
+    test_cases.sort_by(|left, right| left.path.cmp(&right.path));
+    Ok(test_cases)
+}
+
+/// Recursively finds the `.c` files of a directory, skipping directories that cannot be run alone.
+fn find_c_files(directory: &Path, options: &TestSuiteOptions) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default();
+        if path.is_dir() {
+            let skipped = name == LIBRARIES_DIRECTORY
+                || (name == EXTRA_CREDIT_DIRECTORY && !options.extra_credit);
+            if !skipped {
+                files.extend(find_c_files(&path, options)?);
+            }
+        } else if path.extension().is_some_and(|extension| extension == "c") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Reads the expected exit codes of the valid programs from `expected_results.json`.
+///
+/// The file maps program paths relative to the suite root to objects with a `return_code` field.
+fn read_expected_exit_codes(root: &Path) -> anyhow::Result<HashMap<PathBuf, i32>> {
+    let path = root.join(EXPECTED_RESULTS_FILE);
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+    let expected_results: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .with_context(|| format!("Failed to parse '{}'", path.display()))?;
+    let expected_exit_codes = expected_results
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(program, result)| {
+            let exit_code = result["return_code"].as_i64()?;
+            Some((PathBuf::from(program), exit_code as i32))
+        })
+        .collect();
+    Ok(expected_exit_codes)
+}
+
+/// Runs every test program of a chapter and collects the outcomes.
+///
+/// # Arguments
+///
+/// * `root`: The root directory of the test suite.
+/// * `chapter`: The chapter to run.
+/// * `options`: The `TestSuiteOptions` selecting optional tests.
+///
+/// # Returns
+///
+/// A `ChapterReport` with the outcome of every test case, or an error if the suite cannot be read.
+pub fn run_chapter(
+    root: &Path,
+    chapter: u32,
+    options: &TestSuiteOptions,
+) -> anyhow::Result<ChapterReport> {
+    let results = discover_test_cases(root, chapter, options)?
+        .into_iter()
+        .map(|test_case| {
+            let outcome = run_test_case(root, &test_case);
+            (test_case, outcome)
+        })
+        .collect();
+    Ok(ChapterReport { chapter, results })
+}
+
+/// Compiles a test program, runs it when it is expected to compile, and checks the expectation.
+fn run_test_case(root: &Path, test_case: &TestCase) -> TestOutcome {
+    let compilation = compile_and_run(&root.join(&test_case.path));
+    match (&test_case.expectation, compilation) {
+        (Expectation::CompileError, Err(_)) => TestOutcome::Pass,
+        (Expectation::CompileError, Ok(_)) => {
+            TestOutcome::Fail(String::from("expected a compile error, but it compiled"))
+        }
+        (_, Err(error)) => TestOutcome::Fail(format!("{:#}", error)),
+        (Expectation::ExitCode(expected), Ok(actual)) => check_exit_code(*expected, actual),
+        (Expectation::Interpreted, Ok(actual)) => {
+            match interpreted_exit_code(&root.join(&test_case.path)) {
+                Ok(expected) => check_exit_code(expected, actual),
+                Err(error) => TestOutcome::Fail(format!("{:#}", error)),
+            }
+        }
+    }
+}
+
+/// Compares the exit code of an executable with the expected one, truncated like a process status.
+fn check_exit_code(expected: i32, actual: i32) -> TestOutcome {
+    if expected & 0xff == actual {
+        TestOutcome::Pass
+    } else {
+        TestOutcome::Fail(format!(
+            "expected exit code {}, got {}",
+            expected & 0xff,
+            actual
+        ))
+    }
+}
+
+/// Preprocesses, compiles, links, and runs a C source file in a temporary directory.
+///
+/// # Returns
+///
+/// The exit code of the executable, or an error if any step before running it fails.
+fn compile_and_run(source_path: &Path) -> anyhow::Result<i32> {
+    let temp_dir = tempfile::tempdir()?;
+    let preprocessed_path = temp_dir.path().join("program.i");
+    let executable_path = temp_dir.path().join("program");
+    let options = CompileOptions {
+        quiet: true,
+        ..Default::default()
+    };
+    run_gcc_preprocessor(source_path, &preprocessed_path, &options)?;
+    let (assembly_path, _) = compile_file(&preprocessed_path, None, &options)?;
+    run_gcc_linker(&assembly_path, &executable_path, &options)?;
+    let status = Command::new(&executable_path).status()?;
+    status
+        .code()
+        .context("The program was terminated by a signal")
+}
+
+/// Computes the expected exit code of a C source file with the TACKY interpreter.
+fn interpreted_exit_code(source_path: &Path) -> anyhow::Result<i32> {
+    let options = CompileOptions {
+        process_until: Some(Stage::Tacky),
+        quiet: true,
+        ..Default::default()
+    };
+    let source_code = std::fs::read_to_string(source_path)?;
+    match run_cmm_compiler(&source_code, &options)? {
+        CompilerResult::Tacky(tacky_ast) => Ok(interpret(&tacky_ast)?),
+        _ => anyhow::bail!("Expected TACKY IR"),
+    }
+}
@@ -0,0 +1,53 @@
+use crate::common::target::Target;
+use crate::compiler;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Compiles preprocessed C-- source code to assembly code for the in-browser playground.
+///
+/// Exported to JavaScript as `compileToAssemblyString`. The playground has no preprocessor, so the
+/// source must not contain preprocessor directives.
+///
+/// # Arguments
+///
+/// * `source`: The source code to compile.
+/// * `target`: A target triple such as `x86_64-apple-darwin`, or `undefined` for x86-64 Linux.
+///
+/// # Returns
+///
+/// The assembly code on success, or the error message that is thrown as a JavaScript exception.
+#[wasm_bindgen(js_name = compileToAssemblyString)]
+pub fn compile_to_assembly_string(source: &str, target: Option<String>) -> Result<String, String> {
+    let target = match target {
+        Some(triple) => triple.parse::<Target>()?,
+        None => Target::X86_64_LINUX,
+    };
+    compiler::compile_to_assembly_string(source, &target).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_to_assembly_string() {
+        let assembly_code =
+            compile_to_assembly_string("int main(void) { return 2; }", None).unwrap();
+        assert!(assembly_code.contains(".globl main\nmain:\n"));
+
+        let assembly_code = compile_to_assembly_string(
+            "int main(void) { return 2; }",
+            Some(String::from("x86_64-apple-darwin")),
+        )
+        .unwrap();
+        assert!(assembly_code.contains(".globl _main\n_main:\n"));
+    }
+
+    #[test]
+    fn test_errors_are_messages() {
+        assert!(compile_to_assembly_string("int main(void) { return }", None).is_err());
+        assert!(
+            compile_to_assembly_string("int main(void) { return 2; }", Some(String::from("z80")))
+                .is_err()
+        );
+    }
+}
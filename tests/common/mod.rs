@@ -0,0 +1,104 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use cmm::common::target::Target;
+use cmm::compiler::code_gen::assembly_ast::AssemblyAst;
+use cmm::compiler::ir_gen::tacky_ast::TackyAst;
+use cmm::compiler::lexer::tokens::Token;
+use cmm::compiler::parser::cmm_ast::CmmAst;
+use cmm::compiler::{CompileOptions, CompilerResult, Stage, run_cmm_compiler};
+use cmm::compiler_driver::run_gcc_linker;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The outputs of every compiler stage for a single source file.
+pub struct StageOutputs {
+    pub tokens: Vec<Token>,
+    pub cmm_ast: CmmAst,
+    pub tacky_ast: TackyAst,
+    pub assembly_ast: AssemblyAst,
+    pub assembly_code: String,
+}
+
+/// Runs the compiler once per stage, stopping after that stage, and collects the outputs.
+pub fn run_all_stages(source_code: &str, target: Target) -> StageOutputs {
+    let run_until = |process_until: Option<Stage>| {
+        let options = CompileOptions {
+            process_until,
+            target,
+            quiet: true,
+            ..Default::default()
+        };
+        run_cmm_compiler(source_code, &options).unwrap()
+    };
+    let CompilerResult::Lexer(tokens) = run_until(Some(Stage::Lex)) else {
+        panic!("Expected lexer result");
+    };
+    let CompilerResult::Parser(cmm_ast) = run_until(Some(Stage::Parse)) else {
+        panic!("Expected parser result");
+    };
+    let CompilerResult::Tacky(tacky_ast) = run_until(Some(Stage::Tacky)) else {
+        panic!("Expected tacky result");
+    };
+    let CompilerResult::Codegen(assembly_ast) = run_until(Some(Stage::Codegen)) else {
+        panic!("Expected code gen result");
+    };
+    let CompilerResult::Final(assembly_code) = run_until(None) else {
+        panic!("Expected final result");
+    };
+    StageOutputs {
+        tokens,
+        cmm_ast,
+        tacky_ast,
+        assembly_ast,
+        assembly_code,
+    }
+}
+
+/// Compiles a C-- program for the host, runs the binary, and returns its exit code.
+pub fn compile_and_run(source_code: &str) -> i32 {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+    run_executable(&compile_executable(source_code, temp_dir.path()))
+}
+
+/// Compiles a C-- program for the host, runs the binary, and returns its exit code and its
+/// standard output.
+pub fn compile_and_run_with_output(source_code: &str) -> (i32, String) {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+    let output = Command::new(compile_executable(source_code, temp_dir.path()))
+        .output()
+        .expect("Failed to run the compiled program");
+    let exit_code = output
+        .status
+        .code()
+        .expect("Program was terminated by a signal");
+    (exit_code, String::from_utf8(output.stdout).unwrap())
+}
+
+/// Compiles a C-- program for the host, links it with gcc into a directory, and returns the path
+/// of the executable.
+fn compile_executable(source_code: &str, directory: &Path) -> PathBuf {
+    let options = CompileOptions {
+        quiet: true,
+        ..Default::default()
+    };
+    let CompilerResult::Final(assembly_code) =
+        run_cmm_compiler(source_code, &options).expect("Failed to compile")
+    else {
+        panic!("Expected final result");
+    };
+
+    let assembly_path = directory.join("program.s");
+    let executable_path = directory.join("program");
+    std::fs::write(&assembly_path, assembly_code).expect("Failed to write assembly file");
+    run_gcc_linker(&assembly_path, &executable_path, &options).expect("Failed to link");
+    executable_path
+}
+
+/// Runs an executable and returns its exit code.
+pub fn run_executable(executable_path: &Path) -> i32 {
+    let status = Command::new(executable_path)
+        .status()
+        .expect("Failed to run the compiled program");
+    status.code().expect("Program was terminated by a signal")
+}
@@ -0,0 +1,581 @@
+use cmm::compiler::code_gen::assembly_ast::{AssemblyAst, AssemblyFunction};
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn test_assembly_to_stdout() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 2; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("\t.globl "));
+    assert!(stdout.contains("\tret\n"));
+    assert!(!source_path.with_extension("s").exists());
+    assert!(!source_path.with_extension("i").exists());
+}
+
+#[test]
+fn test_stdout_requires_stop_after_compiler() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 2; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-o", "-"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_cc_compatible_compile_and_link() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    let object_path = temp_dir.path().join("main.o");
+    let executable_path = temp_dir.path().join("main");
+    std::fs::write(&source_path, "int main(void) { return 3; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["-O2", "-g", "-Wall", "-c"])
+        .arg(&source_path)
+        .arg("-o")
+        .arg(&object_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    assert!(object_path.is_file());
+    assert!(!source_path.with_extension("s").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&object_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let status = Command::new(&executable_path)
+        .status()
+        .expect("Failed to run the executable");
+    assert_eq!(status.code(), Some(3));
+}
+
+#[test]
+fn test_cc_compatible_default_outputs_are_overwritten() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 3; }")
+        .expect("Failed to create mock source file");
+
+    // Like cc, compiling twice without -o overwrites the default object file.
+    for _ in 0..2 {
+        let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+            .arg("-c")
+            .arg(&source_path)
+            .output()
+            .expect("Failed to run the compiler driver");
+        assert!(output.status.success());
+        assert!(source_path.with_extension("o").is_file());
+    }
+
+    // A wrong output path is reported before any stage writes an intermediate file.
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg("-c")
+        .arg(&source_path)
+        .arg("-o")
+        .arg(temp_dir.path().join("main.txt"))
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+    assert!(!source_path.with_extension("i").exists());
+    assert!(!source_path.with_extension("s").exists());
+}
+
+#[test]
+fn test_source_map_is_written_next_to_assembly() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) {\n    return -(1 + 2);\n}")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["-S", "--source-map"])
+        .arg(&source_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let source_map = std::fs::read_to_string(temp_dir.path().join("main.map.json"))
+        .expect("Failed to read the source map");
+    let source_map: serde_json::Value =
+        serde_json::from_str(&source_map).expect("Source map should be valid JSON");
+    assert_eq!(
+        source_map["file"].as_str(),
+        source_path.with_extension("s").to_str()
+    );
+    let mappings = source_map["mappings"].as_array().unwrap();
+    assert!(!mappings.is_empty());
+    assert!(
+        mappings
+            .iter()
+            .all(|mapping| mapping["span"]["start_line"] == 2)
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg("--source-map")
+        .arg(&source_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_qbe_backend_rejects_codegen() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 2; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--codegen", "--backend", "qbe"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("The QBE backend has no codegen output"));
+}
+
+#[test]
+fn test_stop_after_stage() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return -2; }")
+        .expect("Failed to create mock source file");
+
+    for (stage, header) in [
+        ("validate", "Validator output:\n"),
+        ("optimize", "Optimizer output:\n"),
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+            .arg(&source_path)
+            .args(["--stop-after", stage])
+            .output()
+            .expect("Failed to run the compiler driver");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(
+            stdout.contains(header),
+            "Missing {:?} in {}",
+            header,
+            stdout
+        );
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--stop-after", "tacky", "--lex"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_diff_subcommand() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let old_path = temp_dir.path().join("old.c");
+    let new_path = temp_dir.path().join("new.c");
+    std::fs::write(&old_path, "int main(void) { return 1 + 2; }").unwrap();
+    std::fs::write(&new_path, "int main(void) { return 1 * 2; }").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg("diff")
+        .args([&old_path, &new_path])
+        .args(["--stage", "tacky"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("-                         operator: Add\n"));
+    assert!(stdout.contains("+                         operator: Multiply\n"));
+}
+
+#[test]
+fn test_json_diagnostics() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "--diagnostics=json"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert_eq!(output.status.code(), Some(1));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["code"], "E0002");
+    let message = diagnostic["message"].as_str().unwrap();
+    assert!(message.starts_with("Parser error"));
+    // The location is only reported in the structured fields.
+    assert!(!message.contains("line"), "{}", message);
+    assert_eq!(diagnostic["file"], source_path.to_str().unwrap());
+    assert_eq!(diagnostic["span"]["start_line"], 1);
+    assert_eq!(diagnostic["span"]["start_column"], 25);
+}
+
+#[test]
+fn test_division_by_zero_warning() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 1 / 0; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with(
+        "warning[E0009]: Semantic warning: Division by zero in '1 / 0' fails at runtime at line 1, column 25"
+    ));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "-Werror"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error[E0009]: "));
+    assert!(stderr.contains("[-Werror]"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "--diagnostics=json"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(diagnostics[0]["severity"], "warning");
+    assert_eq!(diagnostics[0]["code"], "E0009");
+    assert_eq!(diagnostics[0]["warning"], "division-by-zero");
+    assert_eq!(diagnostics[0]["span"]["start_line"], 1);
+    assert_eq!(diagnostics[0]["span"]["start_column"], 25);
+    assert_eq!(diagnostics[0]["span"]["end_column"], 30);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "-Wno-division-by-zero", "-Werror"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_unused_variable_warning() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { int unused = 1; return 0; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "-Wunused"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with(
+        "warning[E0027]: Semantic warning: Unused variable 'unused' in function 'main' at line 1, column 18 [-Wunused-variable]"
+    ));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "-Wall", "-Werror"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error[E0027]: "));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "-Wall", "-Wno-unused-variable"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_validate_resolves_variables() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { int a = 2; return a; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .arg("--validate")
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Validator output:\n"));
+
+    std::fs::write(&source_path, "int main(void) { int a = 2; return b; }")
+        .expect("Failed to create mock source file");
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .arg("--validate")
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error[E0001]: Semantic error: Use of undeclared variable 'b'"));
+
+    std::fs::write(&source_path, "int main(void) { break; return 0; }")
+        .expect("Failed to create mock source file");
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .arg("--validate")
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("error[E0012]: Semantic error: 'break' statement not in a loop"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--validate", "--tacky"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_explain_error_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["explain", "E0002"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("E0002: Expected token\n\n"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["explain", "E9999"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_metrics_report() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return -2; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["-S", "-o", "-", "--metrics"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("\tret\n")
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("function  tacky before  tacky after  assembly  stack frame\n"));
+    assert!(stderr.contains("\nmain                 2            2"));
+}
+
+#[test]
+fn test_cache_dir_reuses_assembly() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    let cache_dir = temp_dir.path().join("cache");
+    std::fs::write(&source_path, "int main(void) { return 2; }")
+        .expect("Failed to create mock source file");
+    let compile = || {
+        Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+            .arg(&source_path)
+            .args(["-S", "-o", "-", "--cache-dir"])
+            .arg(&cache_dir)
+            .output()
+            .expect("Failed to run the compiler driver")
+    };
+
+    let first_output = compile();
+    assert!(first_output.status.success());
+    let cache_entries: Vec<_> = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(cache_entries.len(), 1);
+    assert_eq!(
+        std::fs::read(&cache_entries[0]).unwrap(),
+        first_output.stdout
+    );
+
+    // A modified cache entry shows that the second compilation did not run the compiler.
+    std::fs::write(&cache_entries[0], "\tret\n").unwrap();
+    let second_output = compile();
+    assert!(second_output.status.success());
+    assert_eq!(String::from_utf8(second_output.stdout).unwrap(), "\tret\n");
+}
+
+#[test]
+fn test_cache_dir_reports_warnings_of_cached_compilations() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    let cache_dir = temp_dir.path().join("cache");
+    std::fs::write(&source_path, "int main(void) { int unused = 1; return 2; }")
+        .expect("Failed to create mock source file");
+    let compile = |warning_flags: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+            .arg(&source_path)
+            .args(["-S", "-o", "-", "--cache-dir"])
+            .arg(&cache_dir)
+            .args(warning_flags)
+            .output()
+            .expect("Failed to run the compiler driver")
+    };
+
+    for output in [compile(&["-Wunused"]), compile(&["-Wunused"])] {
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("[-Wunused-variable]"), "{}", stderr);
+    }
+    // The warning options do not change the assembly, so `-Werror` reuses the cached entry, and
+    // still fails the compilation.
+    assert!(!compile(&["-Wunused", "-Werror"]).status.success());
+    assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+}
+
+#[test]
+fn test_codegen_json_output() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return 2; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--codegen", "--format", "json"])
+        .output()
+        .expect("Failed to run the compiler driver");
+
+    assert!(output.status.success());
+    let assembly_ast: AssemblyAst = serde_json::from_slice(&output.stdout).unwrap();
+    let AssemblyAst::Program { functions, .. } = assembly_ast;
+    let [AssemblyFunction::Function { identifier, .. }]: [AssemblyFunction; 1] =
+        functions.try_into().unwrap();
+    assert_eq!(identifier, "main");
+}
+
+#[test]
+fn test_parse_tree_output() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    std::fs::write(&source_path, "int main(void) { return -(1 + 2); }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--parse", "--format", "tree"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    // The output is not a terminal, so the tree is not colorized.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with(
+        "Parser output:\n\
+         Program\n\
+         └── Function main\n\
+         \x20   └── Return\n\
+         \x20       └── Unary Negate (-)\n\
+         \x20           └── Binary Add (+)\n\
+         \x20               ├── Constant 1\n\
+         \x20               └── Constant 2\n"
+    ));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .arg(&source_path)
+        .args(["--tacky", "--format", "tree"])
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn test_internal_toolchain_builds_executable() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let source_path = temp_dir.path().join("main.c");
+    let executable_path = temp_dir.path().join("main");
+    std::fs::write(&source_path, "int main(void) { return 6 * 7; }")
+        .expect("Failed to create mock source file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["--toolchain", "internal"])
+        .arg(&source_path)
+        .arg("-o")
+        .arg(&executable_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(output.status.success());
+    assert!(!source_path.with_extension("s").exists());
+    let status = Command::new(&executable_path)
+        .status()
+        .expect("Failed to run the executable");
+    assert_eq!(status.code(), Some(42));
+
+    std::fs::write(
+        &source_path,
+        "#define ANSWER 42\nint main(void) { return ANSWER; }",
+    )
+    .expect("Failed to create mock source file");
+    let output = Command::new(env!("CARGO_BIN_EXE_cmmc_driver"))
+        .args(["--toolchain", "internal"])
+        .arg(&source_path)
+        .output()
+        .expect("Failed to run the compiler driver");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Line 1 contains a preprocessor directive"));
+}
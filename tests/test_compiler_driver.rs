@@ -0,0 +1,118 @@
+use cmm::compiler::compile_to_assembly_string;
+use cmm::compiler::{CompileOptions, CompilerResult, Stage};
+use cmm::compiler_driver::{
+    AssemblerError, compile_file, parse_assembler_errors, run_assembler_check,
+};
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn setup_preprocessed_file(dir: &tempfile::TempDir, source_code: &str) -> PathBuf {
+    let input_path = dir.path().join("main.i");
+    std::fs::write(&input_path, source_code).expect("Failed to create mock input file");
+    input_path
+}
+
+#[test]
+fn test_compile_file_writes_assembly() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_path = setup_preprocessed_file(&temp_dir, "int main(void) { return 2; }");
+
+    let result = compile_file(&input_path, None, &CompileOptions::default());
+    assert!(result.is_ok());
+    let (output_path, compilation_result) = result.unwrap();
+    assert_eq!(output_path, input_path.with_extension("s"));
+
+    let assembly_code = std::fs::read_to_string(&output_path).unwrap();
+    match compilation_result {
+        CompilerResult::Final(expected_code) => assert_eq!(assembly_code, expected_code),
+        _ => panic!("Expected final result"),
+    }
+}
+
+#[test]
+fn test_compile_file_stops_at_stage_without_writing() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_path = setup_preprocessed_file(&temp_dir, "int main(void) { return 2; }");
+    let options = CompileOptions {
+        process_until: Some(Stage::Parse),
+        ..Default::default()
+    };
+
+    let (output_path, compilation_result) = compile_file(&input_path, None, &options).unwrap();
+    assert!(matches!(compilation_result, CompilerResult::Parser(_)));
+    assert!(!output_path.exists());
+}
+
+#[test]
+fn test_compile_file_invalid_input_extension() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_path = temp_dir.path().join("main.c");
+    std::fs::write(&input_path, "int main(void) { return 2; }").unwrap();
+
+    let result = compile_file(&input_path, None, &CompileOptions::default());
+    assert!(result.is_err());
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("must have a '.i' extension")
+    );
+}
+
+#[test]
+fn test_compile_file_stdout_output_path() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_path = setup_preprocessed_file(&temp_dir, "int main(void) { return 2; }");
+
+    let options = CompileOptions {
+        quiet: true,
+        ..Default::default()
+    };
+    let result = compile_file(&input_path, Some(Path::new("-")), &options);
+    assert!(result.is_ok());
+    let (output_path, _) = result.unwrap();
+    assert_eq!(output_path, PathBuf::from("-"));
+    assert!(!input_path.with_extension("s").exists());
+}
+
+#[test]
+fn test_parse_clang_assembler_errors() {
+    let errors = parse_assembler_errors(
+        "<stdin>:3:2: error: invalid operand for instruction\n\tmovl %eax, $2\n\t^\n",
+        "_main:\n\tpushq %rbp\n\tmovl %eax, $2\n",
+    );
+    assert_eq!(
+        errors,
+        vec![AssemblerError {
+            line: 3,
+            instruction: "movl %eax, $2".to_string(),
+            message: "error: invalid operand for instruction".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_assembler_check() {
+    let options = CompileOptions {
+        quiet: true,
+        ..Default::default()
+    };
+    let assembly_code =
+        compile_to_assembly_string("int main(void) { return 1 < 2; }", &options.target).unwrap();
+    assert!(run_assembler_check(&assembly_code, &options).is_ok());
+
+    let broken_code = assembly_code.replacen("movl", "movx", 1);
+    let error = run_assembler_check(&broken_code, &options).unwrap_err();
+    let broken_line = broken_code
+        .lines()
+        .position(|line| line.contains("movx"))
+        .unwrap()
+        + 1;
+    assert!(
+        error
+            .to_string()
+            .contains(&format!("line {}: `movx", broken_line)),
+        "Unexpected error: {}",
+        error
+    );
+}
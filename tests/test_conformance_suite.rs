@@ -0,0 +1,89 @@
+use cmm::test_suite::{
+    Expectation, TestOutcome, TestSuiteOptions, discover_chapters, discover_test_cases, run_chapter,
+};
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+fn write_program(root: &Path, relative_path: &str, source_code: &str) {
+    let path = root.join(relative_path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, source_code).unwrap();
+}
+
+fn setup_suite(root: &Path) {
+    write_program(
+        root,
+        "chapter_1/valid/return_2.c",
+        "int main(void) { return 2; }",
+    );
+    write_program(
+        root,
+        "chapter_1/invalid_parse/no_semicolon.c",
+        "int main(void) { return 2 }",
+    );
+    write_program(
+        root,
+        "chapter_2/valid/neg.c",
+        "int main(void) { return -(-4); }",
+    );
+    write_program(
+        root,
+        "chapter_2/valid/extra_credit/bitwise.c",
+        "int main(void) { return 1 | 2; }",
+    );
+    std::fs::write(
+        root.join("expected_results.json"),
+        r#"{"chapter_1/valid/return_2.c": {"return_code": 3}}"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_discover_test_cases() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    setup_suite(temp_dir.path());
+
+    assert_eq!(discover_chapters(temp_dir.path()).unwrap(), vec![1, 2]);
+    let test_cases = discover_test_cases(temp_dir.path(), 1, &TestSuiteOptions::default()).unwrap();
+    let expectations: Vec<(PathBuf, Expectation)> = test_cases
+        .into_iter()
+        .map(|test_case| (test_case.path, test_case.expectation))
+        .collect();
+    assert_eq!(
+        expectations,
+        vec![
+            (
+                PathBuf::from("chapter_1/invalid_parse/no_semicolon.c"),
+                Expectation::CompileError
+            ),
+            (
+                PathBuf::from("chapter_1/valid/return_2.c"),
+                Expectation::ExitCode(3)
+            ),
+        ]
+    );
+
+    let default_cases =
+        discover_test_cases(temp_dir.path(), 2, &TestSuiteOptions::default()).unwrap();
+    assert_eq!(default_cases.len(), 1);
+    let extra_credit_cases =
+        discover_test_cases(temp_dir.path(), 2, &TestSuiteOptions { extra_credit: true }).unwrap();
+    assert_eq!(extra_credit_cases.len(), 2);
+}
+
+#[test]
+fn test_run_chapter_report() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    setup_suite(temp_dir.path());
+
+    let report = run_chapter(temp_dir.path(), 1, &TestSuiteOptions::default()).unwrap();
+    assert_eq!(report.passed(), 1);
+    assert_eq!(
+        report.results[1].1,
+        TestOutcome::Fail(String::from("expected exit code 3, got 2"))
+    );
+    assert!(report.to_string().ends_with("Chapter 1: 1/2 passed\n"));
+
+    let report = run_chapter(temp_dir.path(), 2, &TestSuiteOptions::default()).unwrap();
+    assert!(report.all_passed());
+}
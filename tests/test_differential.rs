@@ -0,0 +1,24 @@
+use cmm::common::target::Target;
+use cmm::compiler::interpreter::interpret;
+use common::{compile_and_run, run_all_stages};
+
+mod common;
+
+/// Computes the exit code of a C-- program with the TACKY interpreter.
+fn interpreted_exit_code(source_code: &str) -> i32 {
+    let tacky_ast = run_all_stages(source_code, Target::default()).tacky_ast;
+    interpret(&tacky_ast).expect("Failed to interpret TACKY IR") & 0xff
+}
+
+#[test]
+fn test_native_binaries_match_interpreter() {
+    insta::glob!("test_programs/*.c", |path| {
+        let source_code = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            compile_and_run(&source_code),
+            interpreted_exit_code(&source_code),
+            "Exit code mismatch for {}",
+            path.display()
+        );
+    });
+}
@@ -1,43 +1,225 @@
-use cmm::compiler::{CompilerResult, Stage, run_cmm_compiler};
+use cmm::common::target::{Architecture, Target};
+use cmm::compiler::errors::CompilerError;
+use cmm::compiler::ir_gen::tacky_ast::{
+    TackyConstant, TackyFunction, TackyInstruction, TackyValue,
+};
+use cmm::compiler::optimizer::{PassManager, TackyPass};
+use cmm::compiler::{
+    CompileOptions, CompilerResult, Stage, run_cmm_compiler, run_cmm_compiler_with_passes,
+};
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+use cmm::compiler_driver::run_internal_linker;
+use common::{
+    StageOutputs, compile_and_run, compile_and_run_with_output, run_all_stages, run_executable,
+};
+use std::process::Command;
+
+mod common;
+
+/// Environment variable that enables the golden tests comparing against the system gcc.
+const GCC_GOLDEN_ENV_VAR: &str = "CMM_GCC_GOLDEN";
+
+/// Snapshots the output of every stage of a test program.
+///
+/// Snapshots are recorded with the macOS naming conventions, regardless of the host platform.
+/// Each snapshot is named after the stage, and insta's glob support suffixes it with the name of the
+/// test program, e.g. `test_e2e__tacky@binary_sum.c.snap`.
+fn assert_stage_snapshots(source_code: &str) {
+    let StageOutputs {
+        tokens,
+        cmm_ast,
+        tacky_ast,
+        assembly_ast: asm_ast,
+        assembly_code,
+    } = run_all_stages(source_code, Target::X86_64_MACOS);
+    insta::assert_debug_snapshot!("lexer", tokens);
+    insta::assert_debug_snapshot!("parser", cmm_ast);
+    insta::assert_debug_snapshot!("tacky", tacky_ast);
+    insta::assert_debug_snapshot!("codegen", asm_ast);
+    let prettied_assembly_code = assembly_code.replace("\t", "    ");
+    insta::assert_snapshot!("assembly_code", prettied_assembly_code);
+}
 
 #[test]
-fn test_integer_constant() {
+fn test_program_snapshots() {
     insta::glob!("test_programs/*.c", |path| {
         let source_code = std::fs::read_to_string(path).unwrap();
-        let lexer_result = run_cmm_compiler(&source_code, &Some(Stage::Lex)).unwrap();
-        let tokens = match lexer_result {
-            CompilerResult::Lexer(tokens) => tokens,
-            _ => panic!("Expected lexer result"),
-        };
-        insta::assert_debug_snapshot!("lexer", tokens);
+        assert_stage_snapshots(&source_code);
+    });
+}
 
-        let parser_result = run_cmm_compiler(&source_code, &Some(Stage::Parse)).unwrap();
-        let cmm_ast = match parser_result {
-            CompilerResult::Parser(cmm_ast) => cmm_ast,
-            _ => panic!("Expected parser result"),
-        };
-        insta::assert_debug_snapshot!("parser", cmm_ast);
+#[test]
+fn test_compiler_error_categories() {
+    let options = CompileOptions::default();
+    let lexer_error = run_cmm_compiler("int main(void) { return @; }", &options).unwrap_err();
+    assert!(matches!(lexer_error, CompilerError::Lexer(_)));
 
-        let ir_gen_result = run_cmm_compiler(&source_code, &Some(Stage::Tacky)).unwrap();
-        let tacky_ast = match ir_gen_result {
-            CompilerResult::Tacky(tacky_ast) => tacky_ast,
-            _ => panic!("Expected tacky result"),
-        };
-        insta::assert_debug_snapshot!("tacky", tacky_ast);
+    let parser_error = run_cmm_compiler("int main(void) { return }", &options).unwrap_err();
+    assert!(matches!(parser_error, CompilerError::Parser(_)));
 
-        let code_gen_result = run_cmm_compiler(&source_code, &Some(Stage::Codegen)).unwrap();
-        let asm_ast = match code_gen_result {
-            CompilerResult::Codegen(asm_ast) => asm_ast,
-            _ => panic!("Expected code gen result"),
-        };
-        insta::assert_debug_snapshot!("codegen", asm_ast);
+    // The syntax error is found before the lexer reaches the invalid character.
+    let early_error = run_cmm_compiler("int main(void) return 2; } @", &options).unwrap_err();
+    assert!(matches!(early_error, CompilerError::Parser(_)));
+}
+
+#[test]
+fn test_target_without_backend_stops_after_tacky() {
+    let target = Target {
+        architecture: Architecture::Aarch64,
+        ..Target::X86_64_LINUX
+    };
+    let source_code = "int main(void) { return 2; }";
+    let options = CompileOptions {
+        target,
+        ..Default::default()
+    };
+    assert_eq!(
+        run_cmm_compiler(source_code, &options).unwrap_err(),
+        CompilerError::UnsupportedTarget { target }
+    );
+
+    let options = CompileOptions {
+        process_until: Some(Stage::Tacky),
+        target,
+        ..Default::default()
+    };
+    assert!(matches!(
+        run_cmm_compiler(source_code, &options),
+        Ok(CompilerResult::Tacky(_))
+    ));
+}
+
+/// Returns a fixed constant from every function, standing in for a pass defined outside the crate.
+struct ReturnFortyTwo;
+
+impl TackyPass for ReturnFortyTwo {
+    fn name(&self) -> &str {
+        "return-forty-two"
+    }
+
+    fn run(&self, function: &mut TackyFunction) -> bool {
+        let TackyFunction::Function { instructions, .. } = function;
+        for instruction in instructions.iter_mut() {
+            if let TackyInstruction::Return { value } = instruction {
+                *value = Some(TackyValue::Constant(TackyConstant::Int(42)));
+            }
+        }
+        true
+    }
+}
+
+#[test]
+#[cfg(feature = "x86_64")]
+fn test_external_tacky_pass_changes_assembly() {
+    let mut pass_manager = PassManager::new();
+    pass_manager.register(ReturnFortyTwo);
+    let options = CompileOptions::default();
+    let result =
+        run_cmm_compiler_with_passes("int main(void) { return 2; }", &options, &mut pass_manager)
+            .unwrap();
+    let assembly_code = match result {
+        CompilerResult::Final(assembly_code) => assembly_code,
+        _ => panic!("Expected final result"),
+    };
+    assert!(assembly_code.contains("movl $42, %eax"));
+}
+
+#[test]
+fn test_linux_target_symbol_conventions() {
+    let options = CompileOptions {
+        process_until: None,
+        target: Target::X86_64_LINUX,
+        ..Default::default()
+    };
+    let result = run_cmm_compiler("int main(void) { return 1 && 2; }", &options).unwrap();
+    let assembly_code = match result {
+        CompilerResult::Final(assembly_code) => assembly_code,
+        _ => panic!("Expected final result"),
+    };
+    assert!(assembly_code.contains(".globl main\nmain:\n"));
+    assert!(assembly_code.contains("je .Land_false.0\n"));
+    assert!(assembly_code.contains(".section .note.GNU-stack"));
+}
+
+#[test]
+fn test_variadic_calls_to_printf() {
+    // The arguments of `printf` span the integer and the vector registers, and the stack.
+    let source_code = r#"
+int printf(...);
+int main(void) {
+    return printf("%d %c %ld %.2f %u %d %d %.1f\n", 42, 'x', -7L, 2.5, (unsigned int)-1, 6, 7, 0.5) - 32;
+}
+"#;
+    assert_eq!(
+        compile_and_run_with_output(source_code),
+        (0, "42 x -7 2.50 4294967295 6 7 0.5\n".to_string())
+    );
+}
+
+#[test]
+fn test_double_comparisons_with_nan() {
+    // Every comparison with NaN is false except for `!=`, and NaN is a true condition.
+    let source_code = std::fs::read_to_string("tests/test_programs/double_nan.c").unwrap();
+    assert_eq!(compile_and_run(&source_code), 62);
+}
+
+#[test]
+fn test_function_return_types() {
+    // A `double` is returned in %xmm0, and a `long` or a pointer in the whole of %rax.
+    let source_code =
+        std::fs::read_to_string("tests/test_programs/function_return_types.c").unwrap();
+    assert_eq!(compile_and_run(&source_code), 42);
+}
 
-        let code_emission_result = run_cmm_compiler(&source_code, &None).unwrap();
-        let assembly_code = match code_emission_result {
-            CompilerResult::Final(assembly_code) => assembly_code,
-            _ => panic!("Expected final result"),
+#[test]
+fn test_exit_codes_match_gcc() {
+    if std::env::var_os(GCC_GOLDEN_ENV_VAR).is_none() {
+        eprintln!(
+            "Skipping gcc golden tests, set {} to enable them",
+            GCC_GOLDEN_ENV_VAR
+        );
+        return;
+    }
+    insta::glob!("test_programs/*.c", |path| {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let source_code = std::fs::read_to_string(path).unwrap();
+
+        let gcc_executable_path = temp_dir.path().join("gcc_program");
+        let status = Command::new("gcc")
+            .arg(path)
+            .arg("-o")
+            .arg(&gcc_executable_path)
+            .status()
+            .expect("Failed to run gcc");
+        assert!(status.success(), "gcc failed to compile {}", path.display());
+
+        assert_eq!(
+            compile_and_run(&source_code),
+            run_executable(&gcc_executable_path),
+            "Exit code mismatch with gcc for {}",
+            path.display()
+        );
+    });
+}
+
+#[test]
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn test_internal_toolchain_matches_gcc_toolchain() {
+    insta::glob!("test_programs/*.c", |path| {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let source_code = std::fs::read_to_string(path).unwrap();
+        let executable_path = temp_dir.path().join("program");
+        let options = CompileOptions {
+            quiet: true,
+            ..Default::default()
         };
-        let prettied_assembly_code = assembly_code.replace("\t", "    ");
-        insta::assert_snapshot!("assembly_code", prettied_assembly_code);
+        run_internal_linker(&source_code, &executable_path, &options).expect("Failed to link");
+
+        assert_eq!(
+            run_executable(&executable_path),
+            compile_and_run(&source_code),
+            "Exit code mismatch with the gcc toolchain for {}",
+            path.display()
+        );
     });
 }
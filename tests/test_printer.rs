@@ -0,0 +1,159 @@
+use cmm::common::symbol::Symbol;
+use cmm::compiler::lexer::tokenize;
+use cmm::compiler::parser::Parser;
+use cmm::compiler::parser::cmm_ast::{
+    CmmAst, CmmBinaryOperator, CmmBlockItem, CmmExpression, CmmFunction, CmmStatement, CmmType,
+    CmmUnaryOperator, ExprId, ExpressionArena,
+};
+use cmm::compiler::parser::printer::print_ast;
+use proptest::prelude::*;
+
+fn unary_operator() -> impl Strategy<Value = CmmUnaryOperator> {
+    prop_oneof![
+        Just(CmmUnaryOperator::Plus),
+        Just(CmmUnaryOperator::Complement),
+        Just(CmmUnaryOperator::Negate),
+        Just(CmmUnaryOperator::Not),
+    ]
+}
+
+fn binary_operator() -> impl Strategy<Value = CmmBinaryOperator> {
+    prop_oneof![
+        Just(CmmBinaryOperator::Add),
+        Just(CmmBinaryOperator::Subtract),
+        Just(CmmBinaryOperator::Multiply),
+        Just(CmmBinaryOperator::Divide),
+        Just(CmmBinaryOperator::Remainder),
+        Just(CmmBinaryOperator::And),
+        Just(CmmBinaryOperator::Or),
+        Just(CmmBinaryOperator::Equal),
+        Just(CmmBinaryOperator::NotEqual),
+        Just(CmmBinaryOperator::GreaterThan),
+        Just(CmmBinaryOperator::LessThan),
+        Just(CmmBinaryOperator::GreaterThanEqual),
+        Just(CmmBinaryOperator::LessThanEqual),
+    ]
+}
+
+fn cast_type() -> impl Strategy<Value = CmmType> {
+    prop_oneof![
+        Just(CmmType::Int),
+        Just(CmmType::UnsignedInt),
+        Just(CmmType::Long),
+        Just(CmmType::Char),
+        Just(CmmType::UnsignedChar),
+        Just(CmmType::Bool),
+        Just(CmmType::Double),
+        Just(CmmType::Pointer(Box::new(CmmType::Int))),
+        Just(CmmType::Pointer(Box::new(CmmType::Pointer(Box::new(
+            CmmType::Char
+        ))))),
+        Just(CmmType::Const(Box::new(CmmType::Long))),
+        Just(CmmType::Const(Box::new(CmmType::Pointer(Box::new(
+            CmmType::Const(Box::new(CmmType::Char))
+        ))))),
+    ]
+}
+
+/// Represents a generated expression tree before it is stored in an `ExpressionArena`.
+#[derive(Debug, Clone)]
+enum ExpressionTree {
+    Constant(i32),
+    Unary(CmmUnaryOperator, Box<ExpressionTree>),
+    Cast(CmmType, Box<ExpressionTree>),
+    AddressOf(Box<ExpressionTree>),
+    Dereference(Box<ExpressionTree>),
+    Subscript(Box<ExpressionTree>, Box<ExpressionTree>),
+    Binary(CmmBinaryOperator, Box<ExpressionTree>, Box<ExpressionTree>),
+}
+
+impl ExpressionTree {
+    /// Stores the tree in an arena, operands first, in the same order the parser allocates them.
+    fn alloc(&self, expressions: &mut ExpressionArena) -> ExprId {
+        let expression = match self {
+            ExpressionTree::Constant(value) => CmmExpression::IntegerConstant { value: *value },
+            ExpressionTree::Unary(operator, operand) => CmmExpression::Unary {
+                operator: operator.clone(),
+                expression: operand.alloc(expressions),
+            },
+            ExpressionTree::Cast(target_type, operand) => CmmExpression::Cast {
+                target_type: target_type.clone(),
+                expression: operand.alloc(expressions),
+            },
+            ExpressionTree::AddressOf(operand) => CmmExpression::AddressOf {
+                expression: operand.alloc(expressions),
+            },
+            ExpressionTree::Dereference(operand) => CmmExpression::Dereference {
+                expression: operand.alloc(expressions),
+            },
+            ExpressionTree::Subscript(array, index) => CmmExpression::Subscript {
+                array: array.alloc(expressions),
+                index: index.alloc(expressions),
+            },
+            ExpressionTree::Binary(operator, left, right) => CmmExpression::Binary {
+                operator: operator.clone(),
+                left: left.alloc(expressions),
+                right: right.alloc(expressions),
+            },
+        };
+        expressions.alloc(expression)
+    }
+}
+
+/// Generates random expressions the parser accepts. Constants are non-negative, as negative
+/// numbers are represented by a negation of a constant.
+fn expression() -> impl Strategy<Value = ExpressionTree> {
+    let constant = (0..=i32::MAX).prop_map(ExpressionTree::Constant);
+    constant.prop_recursive(6, 64, 2, |inner| {
+        prop_oneof![
+            (unary_operator(), inner.clone()).prop_map(|(operator, expression)| {
+                ExpressionTree::Unary(operator, Box::new(expression))
+            }),
+            (cast_type(), inner.clone()).prop_map(|(target_type, expression)| {
+                ExpressionTree::Cast(target_type, Box::new(expression))
+            }),
+            inner
+                .clone()
+                .prop_map(|expression| ExpressionTree::AddressOf(Box::new(expression))),
+            inner
+                .clone()
+                .prop_map(|expression| ExpressionTree::Dereference(Box::new(expression))),
+            (inner.clone(), inner.clone()).prop_map(|(array, index)| {
+                ExpressionTree::Subscript(Box::new(array), Box::new(index))
+            }),
+            (binary_operator(), inner.clone(), inner).prop_map(|(operator, left, right)| {
+                ExpressionTree::Binary(operator, Box::new(left), Box::new(right))
+            }),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_print_parse_round_trip(expression_tree in expression()) {
+        let mut expressions = ExpressionArena::new();
+        let expression = expression_tree.alloc(&mut expressions);
+        let cmm_ast = CmmAst::Program {
+            structs: vec![],
+            enums: vec![],
+            variables: vec![],
+            functions: vec![CmmFunction::Function {
+                identifier: Symbol::from("main"),
+                storage_class: None,
+                return_type: Some(CmmType::Int),
+                parameters: vec![],
+                variadic: false,
+                body: Some(vec![CmmBlockItem::Statement(CmmStatement::Return {
+                    expression: Some(expression),
+                    span: None,
+                })]),
+                span: None,
+            }],
+            expressions,
+        };
+        let source_code = print_ast(&cmm_ast);
+        let tokens = tokenize(&source_code).unwrap();
+        let parsed_ast = Parser::new(tokens).parse_ast().unwrap();
+        prop_assert_eq!(parsed_ast, cmm_ast, "Printed source code: {}", source_code);
+    }
+}
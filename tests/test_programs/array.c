@@ -0,0 +1,28 @@
+int sum(int values[3], int count) {
+    for (int total = 0;; total += values[--count])
+        if (count == 0)
+            return total;
+}
+
+int fill(int *p, int *end, int value) {
+    if (p == end)
+        return value;
+    else
+        return fill(p + 1, end, *p = value + 1);
+}
+
+int corner(int rows[2][3], int row) {
+    return rows[row][2];
+}
+
+int last_char(char text[4]) {
+    return text[3];
+}
+
+int main(void) {
+    for (int a[2][3]; fill(a[0], a[0] + 6, 0) == 6;)
+        for (long b[3]; (b[0] = 40) && (1[b] = 2) && (b[2] = -1);)
+            for (char c[4]; (c[3] = 'd') && (*c = 0) == 0;)
+                for (int *p = &a[1][2]; p - a[0] == 5;)
+                    return sum(a[1], 3) + *(a[0] + 1) + 2[*a] + (int) b[b[2] + 2] + (int) (&b[2] - b) + *--p + (p[-1] == 4) + last_char(c) - 'd' - corner(a, 1);
+}
@@ -0,0 +1,5 @@
+int main(void) {
+    for (int x = 12; x != 0; x >>= 1)
+        if (x & 1)
+            return x ^ 5 | 1 << 4 & ~0;
+}
@@ -0,0 +1,9 @@
+int main(void) {
+    int base = 4;
+    long scaled = base * 3;
+    int limit = scaled + base;
+    for (int i = 0; i < limit; i++)
+        if (i > 10)
+            return i + scaled;
+    return limit;
+}
@@ -0,0 +1,18 @@
+struct flags {
+    _Bool ready;
+    _Bool done;
+};
+
+int count(_Bool a, _Bool b) {
+    return a + b;
+}
+
+int main(void) {
+    for (_Bool b = 256; b; b = 0)
+        for (_Bool c = 0; !c; c--)
+            for (_Bool d = 1; (d += 1) && (d++); d = 0)
+                for (_Bool bits[3] = {-1, 0, 2}; bits[2]; bits[2] = 0)
+                    for (struct flags f; (f.ready = 'x') && !(f.done = 0.0); f.ready = 0)
+                        for (_Bool *p = &bits[1]; (*p = p); p = 0)
+                            return b * 100 + c * 30 + d * 5 + count(bits[0], 7) + (_Bool) 0.5 + f.ready + bits[1] + (bits[2] == 1);
+}
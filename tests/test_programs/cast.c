@@ -0,0 +1,5 @@
+int main(void) {
+    for (long l = 4294967298; l > 0; l = 0)
+        return (int) l + (char) 300 + (long) (unsigned int) -1 / 4294967295 + (int) -2.9
+            + (unsigned char) (char) -1 % 7 + (int) ((double) 7 / 2 * 2);
+}
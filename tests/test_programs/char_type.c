@@ -0,0 +1,8 @@
+int main(void) {
+    for (char c = 'a'; c != 'z' + 1; c += 5)
+        for (unsigned char u = 250; u >= 250; u++)
+            for (char n = 200; ; n = -n)
+                for (char tab = '\t'; tab == 9 && '\\' == 92; tab = 0)
+                    if (n > 0)
+                        return c * 2 + (u - 250) + n + '\n' - tab + ('\'' != 39);
+}
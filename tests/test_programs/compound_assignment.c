@@ -0,0 +1,6 @@
+int main(void) {
+    for (int x = 1; x < 1000; x *= 3)
+        for (int y = x; y > 0; y -= 100)
+            if (x > 100)
+                return (y += 10) % 7 + (x /= 2);
+}
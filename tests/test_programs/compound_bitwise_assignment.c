@@ -0,0 +1,6 @@
+int main(void) {
+    for (int x = 1; x < 64; x <<= 2)
+        for (int y = x; y > 0; y &= y - 1)
+            if (y == 16)
+                return (x |= 3) ^ (y %= 5);
+}
@@ -0,0 +1,18 @@
+struct point {
+    int x;
+    int y;
+};
+
+int first_two(const int *values) {
+    return values[0] + *(values + 1);
+}
+
+int main(void) {
+    for (const int a[3] = {4, 5, 6}; a[2]; )
+        for (int x = 10; x; x = 0)
+            for (int *const p = &x; *p; *p = 0)
+                for (const char *s = "const"; s; s = 0)
+                    for (struct point pt; (pt.x = 2) && (pt.y = 3); pt.y = 0)
+                        for (const struct point *q = &pt; q; q = 0)
+                            return first_two(a) + (*p += 3) + s[1] + q->x * q->y + (const long) 1;
+}
@@ -0,0 +1,5 @@
+int main(void) {
+    do
+        return 3;
+    while (0);
+}
@@ -0,0 +1,5 @@
+int main(void) {
+    do
+        continue;
+    while (0);
+}
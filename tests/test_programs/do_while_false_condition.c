@@ -0,0 +1,6 @@
+int main(void) {
+    do
+        if (1 > 2)
+            return 1;
+    while (1 < 0);
+}
@@ -0,0 +1,8 @@
+int main(void) {
+    for (double d = 1.5e1 / 4; d < 100.; d *= -2.5)
+        for (unsigned int u = 3000000000L; d > 3 && !(u < d); u = 0)
+            for (double half = .5 - d; half; half = 0.0)
+                for (unsigned int sum = d + u; sum > 0 && !!half; sum = 0)
+                    if (-half > 3.2 && u / 1e9 == 3 && u / 1e9 != 2 && 3 / 2 * 1.0 < 1.5)
+                        return half * 4 + (d++ / 0.25 >= 15) + sum % 1000 + 2.9 + (d <= 4.75);
+}
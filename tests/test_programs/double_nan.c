@@ -0,0 +1,16 @@
+int main(void) {
+    double zero = 0.0;
+    double nan = zero / zero;
+    int result = (nan == nan) + (nan < 1.0) + (1.0 <= nan) + (nan > 1.0) + (1.0 >= nan) + !nan;
+    if (nan != nan)
+        result = result + 2;
+    if (nan)
+        result = result + 4;
+    if (nan && 1)
+        result = result + 8;
+    if (zero || nan)
+        result = result + 16;
+    if (zero < 1.0 && zero <= 0.0 && zero == -zero && 1.0 != zero)
+        result = result + 32;
+    return result;
+}
@@ -0,0 +1,21 @@
+enum color { RED, GREEN = 5, BLUE, };
+
+enum {
+    SHIFT = 2,
+    MASK = (1 << SHIFT) - 1,
+    NEGATIVE = -MASK * 4,
+    BIG = 18446744073709551615ul > 0
+};
+
+int paint(enum color c) {
+    if (c == BLUE)
+        return 10;
+    else
+        return c;
+}
+
+int main(void) {
+    for (enum color c = GREEN; c != BLUE; c = BLUE)
+        for (int RED = 7; RED; RED = 0)
+            return paint(BLUE) + paint(c) + RED + MASK + NEGATIVE + BIG + (enum color) SHIFT;
+}
@@ -0,0 +1,16 @@
+int increment(int n) {
+    return n + 1;
+}
+
+int main(void) {
+    int total = 0;
+    for (int i = 0; i < 5; i++)
+        total += i;
+    ;
+    increment(total);
+    total * 2;
+    while (total < 20)
+        total++;
+    total = total + 5;
+    return total;
+}
@@ -0,0 +1,6 @@
+int main(void) {
+    for (int a = 0; a < 10; a = a + 1)
+        for (int b = a; b == a; b = a = a + 2)
+            if (a > 5)
+                return a + b;
+}
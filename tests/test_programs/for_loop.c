@@ -0,0 +1,5 @@
+int main(void) {
+    for (int i = 0; i < 5; i = i + 1)
+        if (i == 3)
+            return i;
+}
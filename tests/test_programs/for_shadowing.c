@@ -0,0 +1,5 @@
+int main(void) {
+    for (int i = 1; i; i = 0)
+        for (int i = 2; i; i = 0)
+            return i;
+}
@@ -0,0 +1,13 @@
+int run(int argc, long depth, double scale, int a, int b, int d, int e, char c, unsigned int f,
+        double g, long h) {
+    if (argc == 1)
+        return run(2, 3, 1.5, 1, 2, 3, 4, 'a', 5, 0.25, 4294967296);
+    else if (depth > 0)
+        return run(argc + 1, depth - 1, scale * 2, a, b, d, e, c + 1, f + argc, g, h);
+    else
+        return argc + a + b + d + e + (c - 'a') + f + (int) (scale + g) + (h == 4294967296);
+}
+
+int main(int argc, char **argv) {
+    return run(argc, 0, 0.0, 0, 0, 0, 0, 'a', 0, 0.0, 0);
+}
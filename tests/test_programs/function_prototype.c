@@ -0,0 +1,19 @@
+int is_even(int n);
+
+int is_odd(int n) {
+    if (n == 0)
+        return 0;
+    else
+        return is_even(n - 1);
+}
+
+int is_even(int n) {
+    if (n == 0)
+        return 1;
+    else
+        return is_odd(n - 1);
+}
+
+int main(void) {
+    return is_even(10) * 10 + is_odd(7) * 5 + is_odd(4);
+}
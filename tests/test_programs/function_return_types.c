@@ -0,0 +1,16 @@
+double half(double x) { return x / 2.0; }
+long big(void) { return 4294967296l; }
+int *pick(int *a, int *b, int which) { if (which) return a; return b; }
+unsigned char wrap(int x) { return x; }
+int main(void) {
+    int a = 3;
+    int b = 7;
+    double h = half(9.0);
+    long l = big();
+    int *p = pick(&a, &b, 0);
+    if (h != 4.5) return 1;
+    if (l / 2 != 2147483648l) return 2;
+    if (*p != 7) return 3;
+    if (wrap(258) != 2) return 4;
+    return 42;
+}
@@ -0,0 +1,7 @@
+int main(void) {
+    if (1)
+        if (0)
+            return 1;
+        else
+            return 2;
+}
@@ -0,0 +1,8 @@
+int main(void) {
+    if (1 > 2)
+        return 3;
+    else if (2 > 1)
+        return 4;
+    else
+        return 5;
+}
@@ -0,0 +1,4 @@
+int main(void) {
+    if (0)
+        return 1;
+}
@@ -0,0 +1,14 @@
+int sum(int *values, int count) {
+    for (int total = 0;; total += values[--count])
+        if (count == 0)
+            return total;
+}
+
+int main(void) {
+    for (int a[2][3] = {{1, 2, 3}, {4}}; a[1][2] == 0;)
+        for (long b[4] = {10, -3,}; b[3] == 0;)
+            for (char c[2][2] = {{'a'}, {'b', 'c'}}; c[0][1] == 0;)
+                for (double d[3] = {1.5, 2}; d[2] == 0;)
+                    for (int *p[2] = {&a[1][0], 0}; p[1] == 0;)
+                        return sum(a[0], 6) + (int) (b[0] + b[1] + b[2]) + (c[1][1] - c[0][0]) + (int) (d[0] * d[1]) + *p[0];
+}
@@ -0,0 +1 @@
+int main(void) { return -(-2147483648 / 65536) / 256; }
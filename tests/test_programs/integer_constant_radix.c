@@ -0,0 +1,4 @@
+int main(void) {
+    for (long mask = 0xFFFFFFFFL; mask == 4294967295; mask = 0)
+        return 0x1F + 0755 - 0X1e0 + 010L + (mask >> 0x1C) + 00;
+}
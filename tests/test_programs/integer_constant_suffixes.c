@@ -0,0 +1,4 @@
+int main(void) {
+    for (unsigned int u = 4294967295u; u == 0xFFFFFFFF; u = 0)
+        return (u + 2U) + (3000000000u > 5) * 10 + (0x80000000 >> 28) + 7l + (-1 < 0u);
+}
@@ -0,0 +1,4 @@
+int main(void) {
+    for (int x = 5; ; )
+        return !0 + !!x * 10 + !x * 100 + !(x - 5) * 20;
+}
@@ -0,0 +1,9 @@
+int main(void) {
+    for (long big = 4294967296L * 3 + 5; big > 2147483648; big = 0)
+        for (unsigned int u = -1; u > 0; u = 0)
+            for (long wide = u; wide == 4294967295 && !(u < wide); wide = 0)
+                for (int narrow = big; narrow == 5; narrow = 0)
+                    for (long int l = -9223372036854775807L - 1; l < 0; l /= 2)
+                        if (l == -1)
+                            return (narrow += 8589934592L) + (big >> 32) + (l << 62 >> 60) + (wide - 4294967296) * -4294967296;
+}
@@ -0,0 +1,8 @@
+int main(void) {
+    for (int i = 0; i < 10; i = i + 1)
+        while (1)
+            if (i == 4)
+                return i;
+            else
+                break;
+}
@@ -0,0 +1,7 @@
+int main(void) {
+    for (int i = 0; ; i = i + 1)
+        if (i < 3)
+            continue;
+        else if (i == 5)
+            return i;
+}
@@ -0,0 +1,24 @@
+int set(int *target, int value) {
+    return *target = value;
+}
+
+int add_to(long *total, long amount) {
+    return *total += amount;
+}
+
+int halve(double *value) {
+    return (int) (*value = *value / 2);
+}
+
+int twice(int **pointer) {
+    return **pointer = **pointer * 2;
+}
+
+int pointers(int x, long y, char c, double d) {
+    for (int *p = &x; set(p, 20) == 20 && add_to(&y, 2) == 2 && twice(&p) == 40 && (*p)++ == 40;)
+        return x + (int) y + *&x + (p == &x) + (p != 0) + (*(char *) &c - 'a') + halve(&d);
+}
+
+int main(void) {
+    return pointers(0, 0, 'b', 9.0);
+}
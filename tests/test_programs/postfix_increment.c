@@ -0,0 +1,6 @@
+int main(void) {
+    for (int i = 0; i++ < 20; )
+        for (int j = i; j-- > 0; )
+            if (j == 4 && i-- % 5 == 0)
+                return i * 10 + j--;
+}
@@ -0,0 +1,5 @@
+int main(void) {
+    for (int i = 0; i < 20; ++i)
+        if (++i % 7 == 0)
+            return - --i + 20;
+}
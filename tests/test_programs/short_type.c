@@ -0,0 +1,16 @@
+int halve(short value) {
+    return value / 2;
+}
+
+int main(void) {
+    short small = 40000;
+    short sum = 0;
+    for (short s = 32760; s > 0; s++)
+        sum = s;
+    short array[3] = {1000, -1000, 300};
+    short *pointer = array;
+    double scaled = small * 0.5;
+    long wide = small + 70000L;
+    return halve(array[2]) + (sum == 32767) + (small == -25536) + (scaled < 0) + (wide == 44464)
+        + (pointer[1] < 0) + (unsigned) small % 7;
+}
@@ -0,0 +1,18 @@
+int counter = 3;
+long totals[3] = {10, 20};
+int zeros[4];
+char letters[2][2] = {{'a'}, {'b', 'c'}};
+double scale = 1.5 * 2;
+unsigned int mask = -1;
+
+int bump(int step) {
+    counter += step;
+    return counter;
+}
+
+int main(void) {
+    bump(4);
+    totals[2] = totals[0] + totals[1];
+    zeros[3] = bump(1);
+    return counter + (int) totals[2] + zeros[0] + zeros[3] + letters[1][1] - letters[0][0] + letters[0][1] + (int) scale + (mask == 4294967295u);
+}
@@ -0,0 +1,5 @@
+static int x = 2147483647 + 1 == 0;
+
+int main(void) {
+    return x + 2;
+}
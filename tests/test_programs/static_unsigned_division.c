@@ -0,0 +1,5 @@
+static unsigned u = 4294967295u / 2;
+
+int main(void) {
+    return (u == 2147483647u) + (int) (u >> 28);
+}
@@ -0,0 +1,5 @@
+static double d = 18446744073709551615ul;
+
+int main(void) {
+    return (d == 18446744073709551616.0) + (d > 0.0);
+}
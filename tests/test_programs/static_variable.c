@@ -0,0 +1,24 @@
+static int hidden = 5;
+extern int shared;
+int shared = 2;
+static long table[3] = {1, 2, 3};
+
+static int next(void) {
+    static int calls;
+    static int base = 10;
+    calls += 1;
+    return base + calls;
+}
+
+int read_shared(void) {
+    extern int shared;
+    extern int hidden;
+    for (int shared = 100; shared > 0;)
+        return hidden + shared;
+}
+
+int main(void) {
+    next();
+    next();
+    return next() + read_shared() + (int) table[2] + hidden + shared;
+}
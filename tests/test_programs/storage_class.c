@@ -0,0 +1,16 @@
+static int square(int n);
+
+extern int sum_squares(int n) {
+    if (n == 0)
+        return 0;
+    else
+        return square(n) + sum_squares(n - 1);
+}
+
+static int square(int n) {
+    return n * n;
+}
+
+int main(void) {
+    return sum_squares(4);
+}
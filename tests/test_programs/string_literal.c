@@ -0,0 +1,5 @@
+int main(void) {
+    for (int i = 0; "loop" && i < 3; i++)
+        if (i == 2 && "tab\there" && !"" == 0 || !"escaped \"quote\"\n" "joined \a\0")
+            return i + 40 + !"\0";
+}
@@ -0,0 +1,31 @@
+struct point {
+    char tag;
+    int x;
+    long y;
+};
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+struct shape {
+    struct point corners[2];
+    int sides[3];
+};
+
+int length(struct node *list) {
+    if (list)
+        return 1 + length(list->next);
+    else
+        return 0;
+}
+
+int main(void) {
+    for (struct point p; (p.tag = 'a') && (p.x = 3) && (p.y = 4); p.y = 0)
+        for (struct node a; (a.value = p.x) && !(a.next = 0); a.next = 0)
+            for (struct node b; (b.value = 5) && (b.next = &a); b.next = 0)
+                for (struct shape s; (s.corners[1].x = 6) && (s.sides[2] = 7); s.sides[2] = 0)
+                    for (struct shape *ps = &s; (ps->corners[1].y = b.next->value * 2); ps = 0)
+                        return p.x + (int) p.y + (p.tag - 'a') + length(&b) + s.corners[1].x + ps->sides[2] + (int) s.corners[1].y + b.value;
+}
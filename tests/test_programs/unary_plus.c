@@ -0,0 +1,4 @@
+int main(void) {
+    for (char c = -3; ; )
+        return + +40 + +c - -(+5);
+}
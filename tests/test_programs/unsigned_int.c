@@ -0,0 +1,6 @@
+int main(void) {
+    for (unsigned int u = -1; u > 5; u /= 7)
+        for (int i = -3; i < 0; i++)
+            if (u < 100000000 && i > u && (u >> 24) + i == 2)
+                return (u % 256) ^ (-i >> 1) ^ (i += u) % 64;
+}
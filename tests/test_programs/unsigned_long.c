@@ -0,0 +1,8 @@
+int main(void) {
+    for (unsigned long big = 18446744073709551615UL; big > 0xFFFFFFFF; big /= 1lu << 62)
+        for (unsigned long int u = -1; u == big; u = 0)
+            for (double d = big; d == 18446744073709551616.0; d = 0)
+                for (unsigned long back = d / 2; back % 8 == 0; back = 1)
+                    if (-1L > 1UL && big >> 63 == 1)
+                        return (back / 1000000000000000000ul) + (big % 100) + (d > 4294967296u) + (unsigned long) (d / 1e19);
+}
@@ -0,0 +1,8 @@
+int positive = 18446744073709551615ul > 0;
+unsigned long half = 18446744073709551615ul / 2;
+long top_bits = (long) (18446744073709551615ul >> 60);
+int ordered = 9223372036854775808ul > 9223372036854775807l;
+
+int main(void) {
+    return positive + (half == 9223372036854775807ul) + (int) top_bits + ordered;
+}
@@ -0,0 +1,18 @@
+void add(int *total, int amount) {
+    if (amount < 0)
+        return;
+    *total += amount;
+}
+
+void reset(int *total) {
+    *total = 0;
+}
+
+int main(void) {
+    int total = 5;
+    reset(&total);
+    add(&total, 20);
+    add(&total, -3);
+    add(&total, 7);
+    return total;
+}
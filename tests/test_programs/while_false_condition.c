@@ -0,0 +1,4 @@
+int main(void) {
+    while (1 - 1)
+        return 1;
+}
@@ -0,0 +1,7 @@
+int main(void) {
+    while (2 > 1)
+        if (0)
+            return 1;
+        else
+            return 5;
+}
@@ -59,21 +59,16 @@ fn test_invalid_output_extension() {
 }
 
 #[test]
-fn test_default_output_already_exists() {
+fn test_default_output_is_overwritten() {
     let temp_dir = tempdir().expect("Failed to create temporary directory");
     let input_path = setup_test_files(&temp_dir, "main", "c");
 
     let expected_output = input_path.with_extension("i");
     File::create(&expected_output).expect("Failed to create existing default output file");
 
+    // Like cc, an existing default output is overwritten.
     let result = validate_preprocessor_paths(&input_path, None);
-    assert!(result.is_err());
-    assert!(
-        result
-            .unwrap_err()
-            .to_string()
-            .contains("Output file already exists")
-    );
+    assert_eq!(result.unwrap(), (input_path, expected_output));
 }
 
 #[test]